@@ -7,7 +7,9 @@ use std::path::{Path, PathBuf};
 
 use anyhow::{Context, bail};
 use cot::db::migrations::{DynMigration, MigrationEngine};
-use cot_codegen::model::{Field, Model, ModelArgs, ModelOpts, ModelType};
+use cot_codegen::model::{
+    Field, FieldChoices, FieldDefault, Model, ModelArgs, ModelOpts, ModelType,
+};
 use cot_codegen::symbol_resolver::SymbolResolver;
 use darling::FromMeta;
 use heck::ToSnakeCase;
@@ -339,10 +341,11 @@ impl MigrationGenerator {
 
     fn process_source_files(&self, source_files: Vec<SourceFile>) -> anyhow::Result<AppState> {
         let mut app_state = AppState::new();
+        let choice_enums = ChoiceEnums::from_source_files(&source_files);
 
         for source_file in source_files {
             let path = source_file.path.clone();
-            self.process_parsed_file(source_file, &mut app_state)
+            self.process_parsed_file(source_file, &choice_enums, &mut app_state)
                 .with_context(|| format!("unable to find models in file: {}", path.display()))?;
         }
 
@@ -367,6 +370,7 @@ impl MigrationGenerator {
             path,
             content: file,
         }: SourceFile,
+        choice_enums: &ChoiceEnums,
         app_state: &mut AppState,
     ) -> anyhow::Result<()> {
         trace!("Processing file: {:?}", &path);
@@ -386,6 +390,7 @@ impl MigrationGenerator {
                             item,
                             &args,
                             &symbol_resolver,
+                            choice_enums,
                         )?;
 
                         match args.model_type {
@@ -479,7 +484,7 @@ impl MigrationGenerator {
                 }
                 (Some(&app_model), Some(&migration_model)) => {
                     if app_model.model.table_name != migration_model.model.table_name
-                        || app_model.model.pk_field != migration_model.model.pk_field
+                        || app_model.model.pk_fields != migration_model.model.pk_fields
                         || app_model.model.fields != migration_model.model.fields
                     {
                         modified_models.push(app_model.clone());
@@ -604,6 +609,17 @@ impl MigrationGenerator {
         model_source.vis = syn::Visibility::Inherited;
         model_source.ident = format_ident!("_{}", model_source.ident);
         model_source.attrs.clear();
+        for field in &mut model_source.fields {
+            let choices = model
+                .model
+                .fields
+                .iter()
+                .find(|model_field| field.ident.as_ref() == Some(&model_field.name))
+                .and_then(|model_field| model_field.choices.as_ref());
+            if let Some(FieldChoices::Values(values)) = choices {
+                Self::freeze_field_choices(field, values);
+            }
+        }
         model_source
             .attrs
             .push(syn::parse_quote! {#[derive(::core::fmt::Debug)]});
@@ -615,6 +631,30 @@ impl MigrationGenerator {
         }
     }
 
+    /// Replaces `#[model(choices)]` with the list of the values allowed in the
+    /// field, so that the model stored in the migration doesn't change when the
+    /// variants of the enum do.
+    fn freeze_field_choices(field: &mut syn::Field, values: &[String]) {
+        for attr in &mut field.attrs {
+            if !attr.path().is_ident("model") {
+                continue;
+            }
+            let Ok(metas) = attr.parse_args_with(
+                syn::punctuated::Punctuated::<Meta, syn::Token![,]>::parse_terminated,
+            ) else {
+                continue;
+            };
+
+            let metas = metas.into_iter().map(|meta| match meta {
+                Meta::Path(path) if path.is_ident("choices") => {
+                    parse_quote! { choices = [#(#values),*] }
+                }
+                meta => meta,
+            });
+            *attr = parse_quote! { #[model(#(#metas),*)] };
+        }
+    }
+
     fn get_migration_list(migrations_dir: &PathBuf) -> anyhow::Result<Vec<String>> {
         let dir = match std::fs::read_dir(migrations_dir) {
             Ok(dir) => dir,
@@ -816,7 +856,7 @@ impl MigrationOperationGenerator {
 
     #[must_use]
     fn make_alter_field_operation(
-        _app_model: &ModelInSource,
+        app_model: &ModelInSource,
         app_field: &Field,
         migration_model: &ModelInSource,
         migration_field: &Field,
@@ -832,6 +872,28 @@ impl MigrationOperationGenerator {
             ),
         );
 
+        let without_choices = |field: &Field| Field {
+            choices: None,
+            ..field.clone()
+        };
+        if without_choices(app_field) == without_choices(migration_field) {
+            let op = DynOperation::AlterFieldChoices {
+                table_name: app_model.model.table_name.clone(),
+                model_ty: app_model.model.resolved_ty.clone(),
+                column_name: app_field.column_name.clone(),
+                old_choices: choice_values(migration_field),
+                new_choices: choice_values(app_field),
+            };
+            print_status_msg(
+                StatusType::Modified,
+                &format!(
+                    "Field '{}' from Model '{}'",
+                    &migration_field.name, migration_model.model.name
+                ),
+            );
+            return Some(op);
+        }
+
         todo!();
 
         #[expect(unreachable_code)]
@@ -917,6 +979,144 @@ impl AppState {
     }
 }
 
+/// The enums deriving `SelectChoice` found in the source, used to find the
+/// values allowed in the fields marked with `#[model(choices)]`.
+#[derive(Debug, Clone, Default)]
+struct ChoiceEnums {
+    /// The identifiers of the variants of the enums, by the name of the enum
+    enums: HashMap<String, Vec<Vec<String>>>,
+}
+
+impl ChoiceEnums {
+    #[must_use]
+    fn from_source_files(source_files: &[SourceFile]) -> Self {
+        let mut enums: HashMap<String, Vec<Vec<String>>> = HashMap::new();
+        for source_file in source_files {
+            for item in &source_file.content.items {
+                if let syn::Item::Enum(item) = item
+                    && Self::derives_select_choice(item)
+                {
+                    enums
+                        .entry(item.ident.to_string())
+                        .or_default()
+                        .push(Self::choice_ids(item));
+                }
+            }
+        }
+
+        Self { enums }
+    }
+
+    fn derives_select_choice(item: &syn::ItemEnum) -> bool {
+        let mut derives = false;
+        for attr in &item.attrs {
+            if attr.path().is_ident("derive") {
+                // errors are ignored here, as they are reported by the compiler
+                let _ = attr.parse_nested_meta(|meta| {
+                    if meta
+                        .path
+                        .segments
+                        .last()
+                        .is_some_and(|segment| segment.ident == "SelectChoice")
+                    {
+                        derives = true;
+                    }
+                    Ok(())
+                });
+            }
+        }
+        derives
+    }
+
+    /// Returns the identifiers of the variants of the enum, the same way the
+    /// `SelectChoice` derive macro does.
+    fn choice_ids(item: &syn::ItemEnum) -> Vec<String> {
+        item.variants
+            .iter()
+            .map(|variant| {
+                let mut id = None;
+                for attr in &variant.attrs {
+                    if attr.path().is_ident("select_choice") {
+                        let _ = attr.parse_nested_meta(|meta| {
+                            let value = meta.value()?;
+                            if meta.path.is_ident("id") {
+                                id = Some(value.parse::<syn::LitStr>()?.value());
+                            } else {
+                                value.parse::<syn::Expr>()?;
+                            }
+                            Ok(())
+                        });
+                    }
+                }
+                id.unwrap_or_else(|| variant.ident.to_string())
+            })
+            .collect()
+    }
+
+    /// Replaces `#[model(choices)]` in the field with the identifiers of the
+    /// variants of the enum stored in it.
+    fn resolve_field_choices(&self, field: &mut Field) -> anyhow::Result<()> {
+        if field.choices != Some(FieldChoices::FromType) {
+            return Ok(());
+        }
+
+        let enum_name = choice_enum_name(&field.ty).with_context(|| {
+            format!(
+                "`#[model(choices)]` can only be used on `Choice<T>` fields, but field `{}` is `{}`",
+                field.name,
+                field.ty.to_token_stream()
+            )
+        })?;
+        let values = match self.enums.get(&enum_name).map(Vec::as_slice) {
+            Some([values]) => values.clone(),
+            Some(_) => bail!(
+                "found multiple enums named `{enum_name}` deriving `SelectChoice`, so the values \
+                allowed in field `{}` are ambiguous",
+                field.name
+            ),
+            None => bail!(
+                "`{enum_name}` stored in field `{}` must be an enum deriving `SelectChoice` \
+                defined in this crate",
+                field.name
+            ),
+        };
+        field.choices = Some(FieldChoices::Values(values));
+
+        Ok(())
+    }
+}
+
+/// Returns the name of the enum stored in a `Choice<T>` or
+/// `Option<Choice<T>>` field.
+fn choice_enum_name(ty: &syn::Type) -> Option<String> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    let inner = args.args.iter().find_map(|arg| match arg {
+        syn::GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    })?;
+
+    if segment.ident == "Choice" {
+        let syn::Type::Path(inner) = inner else {
+            return None;
+        };
+        inner
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string())
+    } else if segment.ident == "Option" {
+        choice_enum_name(inner)
+    } else {
+        None
+    }
+}
+
 /// Helper struct to process already existing migrations.
 #[derive(Debug, Clone)]
 struct MigrationProcessor {
@@ -1022,12 +1222,16 @@ impl ModelInSource {
         item: syn::ItemStruct,
         args: &ModelArgs,
         symbol_resolver: &SymbolResolver,
+        choice_enums: &ChoiceEnums,
     ) -> anyhow::Result<Self> {
         let input: syn::DeriveInput = item.clone().into();
         let opts = ModelOpts::new_from_derive_input(&input)
             .map_err(|e| anyhow::anyhow!("cannot parse model: {e}"))?;
         let mut model = opts.as_model(args, symbol_resolver)?;
         model.table_name = format!("{}__{}", app_name.to_snake_case(), model.table_name);
+        for field in model.fields.iter_mut().chain(&mut model.pk_fields) {
+            choice_enums.resolve_field_choices(field)?;
+        }
 
         Ok(Self {
             model_item: item,
@@ -1148,6 +1352,12 @@ impl GeneratedMigration {
                         because it doesn't create a new model"
                         )
                     }
+                    DynOperation::AlterFieldChoices { .. } => {
+                        unreachable!(
+                            "AlterFieldChoices operation shouldn't be a dependency of CreateModel \
+                        because it doesn't create a new model"
+                        )
+                    }
                 };
                 trace!(
                     "Removing foreign keys from {} to {}",
@@ -1184,6 +1394,10 @@ impl GeneratedMigration {
                 // RemoveModel doesn't create dependencies, it only removes a model
                 unreachable!("RemoveModel operation should never create cycles")
             }
+            DynOperation::AlterFieldChoices { .. } => {
+                // AlterFieldChoices doesn't create dependencies, it only changes a constraint
+                unreachable!("AlterFieldChoices operation should never create cycles")
+            }
         }
     }
 
@@ -1286,6 +1500,10 @@ impl GeneratedMigration {
                     // RemoveModel Doesnt Add Foreign Keys
                     Vec::new()
                 }
+                DynOperation::AlterFieldChoices { .. } => {
+                    // AlterFieldChoices Doesnt Add Foreign Keys
+                    Vec::new()
+                }
             })
             .collect()
     }
@@ -1355,6 +1573,17 @@ impl Repr for Field {
         if self.unique {
             tokens = quote! { #tokens.unique() }
         }
+        if let Some(default_value) = self.default.as_ref().and_then(FieldDefault::constant_value) {
+            tokens = quote! { #tokens.default_value(#default_value) }
+        }
+        if let Some(values) = choice_values(self) {
+            tokens = quote! { #tokens.choices(&[#(#values),*]) }
+        }
+        if let Some(decimal) = self.decimal {
+            let precision = decimal.precision;
+            let scale = decimal.scale;
+            tokens = quote! { #tokens.decimal_precision(#precision, #scale) }
+        }
         tokens
     }
 }
@@ -1444,6 +1673,21 @@ pub enum DynOperation {
         model_ty: syn::Type,
         fields: Vec<Field>,
     },
+    AlterFieldChoices {
+        table_name: String,
+        model_ty: syn::Type,
+        column_name: String,
+        old_choices: Option<Vec<String>>,
+        new_choices: Option<Vec<String>>,
+    },
+}
+
+/// Returns the values allowed in given [`Field`] by `#[model(choices)]`.
+fn choice_values(field: &Field) -> Option<Vec<String>> {
+    match &field.choices {
+        Some(FieldChoices::Values(values)) => Some(values.clone()),
+        Some(FieldChoices::FromType) | None => None,
+    }
 }
 
 /// Returns whether given [`Field`] is a foreign key to given type.
@@ -1511,6 +1755,28 @@ impl Repr for DynOperation {
                         .build()
                 }
             }
+            Self::AlterFieldChoices {
+                table_name,
+                column_name,
+                old_choices,
+                new_choices,
+                ..
+            } => {
+                let old_choices = old_choices
+                    .as_ref()
+                    .map(|values| quote! { .old_choices(&[#(#values),*]) });
+                let new_choices = new_choices
+                    .as_ref()
+                    .map(|values| quote! { .new_choices(&[#(#values),*]) });
+                quote! {
+                    ::cot::db::migrations::Operation::alter_field_choices()
+                        .table_name(::cot::db::Identifier::new(#table_name))
+                        .field_name(::cot::db::Identifier::new(#column_name))
+                        #old_choices
+                        #new_choices
+                        .build()
+                }
+            }
         }
     }
 }
@@ -1553,7 +1819,9 @@ impl Error for ParsingError {}
 
 #[cfg(test)]
 mod tests {
-    use cot_codegen::model::{ForeignKeyOnDeletePolicy, ForeignKeyOnUpdatePolicy, ForeignKeySpec};
+    use cot_codegen::model::{
+        DecimalArgs, ForeignKeyOnDeletePolicy, ForeignKeyOnUpdatePolicy, ForeignKeySpec,
+    };
 
     use super::*;
 
@@ -1575,6 +1843,10 @@ mod tests {
                         auto_value: false,
                         primary_key: false,
                         unique: false,
+                        default: None,
+                        slugify_from: None,
+                        choices: None,
+                        decimal: None,
                         foreign_key: Some(ForeignKeySpec {
                             to_model: parse_quote!(crate::OtherModel),
                             on_delete: Some($on_delete),
@@ -1700,6 +1972,10 @@ mod tests {
                     auto_value: false,
                     primary_key: false,
                     unique: false,
+                    default: None,
+                    slugify_from: None,
+                    choices: None,
+                    decimal: None,
                     foreign_key: Some(ForeignKeySpec {
                         to_model: parse_quote!(Table1),
                         on_delete: Some(ForeignKeyOnDeletePolicy::Cascade),
@@ -1742,6 +2018,10 @@ mod tests {
                     auto_value: false,
                     primary_key: false,
                     unique: false,
+                    default: None,
+                    slugify_from: None,
+                    choices: None,
+                    decimal: None,
                     foreign_key: Some(ForeignKeySpec {
                         to_model: parse_quote!(Table2),
                         on_delete: Some(ForeignKeyOnDeletePolicy::Cascade),
@@ -1759,6 +2039,10 @@ mod tests {
                     auto_value: false,
                     primary_key: false,
                     unique: false,
+                    default: None,
+                    slugify_from: None,
+                    choices: None,
+                    decimal: None,
                     foreign_key: Some(ForeignKeySpec {
                         to_model: parse_quote!(Table1),
                         on_delete: Some(ForeignKeyOnDeletePolicy::Cascade),
@@ -1808,6 +2092,10 @@ mod tests {
                 auto_value: false,
                 primary_key: false,
                 unique: false,
+                default: None,
+                slugify_from: None,
+                choices: None,
+                decimal: None,
                 foreign_key: Some(ForeignKeySpec {
                     to_model: parse_quote!(Table2),
                     on_delete: Some(ForeignKeyOnDeletePolicy::Cascade),
@@ -1865,6 +2153,10 @@ mod tests {
                 auto_value: false,
                 primary_key: false,
                 unique: false,
+                default: None,
+                slugify_from: None,
+                choices: None,
+                decimal: None,
                 foreign_key: Some(ForeignKeySpec {
                     to_model: parse_quote!(crate::Table2),
                     on_delete: Some(ForeignKeyOnDeletePolicy::Cascade),
@@ -1896,6 +2188,10 @@ mod tests {
                     auto_value: false,
                     primary_key: false,
                     unique: false,
+                    default: None,
+                    slugify_from: None,
+                    choices: None,
+                    decimal: None,
                     foreign_key: Some(ForeignKeySpec {
                         to_model: parse_quote!(my_crate::Table2),
                         on_delete: Some(ForeignKeyOnDeletePolicy::Cascade),
@@ -1913,6 +2209,10 @@ mod tests {
                     auto_value: false,
                     primary_key: false,
                     unique: false,
+                    default: None,
+                    slugify_from: None,
+                    choices: None,
+                    decimal: None,
                     foreign_key: Some(ForeignKeySpec {
                         to_model: parse_quote!(crate::Table4),
                         on_delete: Some(ForeignKeyOnDeletePolicy::Cascade),
@@ -1948,15 +2248,19 @@ mod tests {
                 resolved_ty: parse_quote!(TestModel),
                 model_type: ModelType::default(),
                 table_name: "test_model".to_string(),
-                pk_field: Field {
+                pk_fields: vec![Field {
                     name: format_ident!("id"),
                     column_name: "id".to_string(),
                     ty: parse_quote!(i32),
                     auto_value: true,
                     primary_key: true,
                     unique: false,
+                    default: None,
+                    slugify_from: None,
+                    choices: None,
+                    decimal: None,
                     foreign_key: None,
-                },
+                }],
                 fields: vec![Field {
                     name: format_ident!("field1"),
                     column_name: "field1".to_string(),
@@ -1964,8 +2268,13 @@ mod tests {
                     auto_value: false,
                     primary_key: false,
                     unique: false,
+                    default: None,
+                    slugify_from: None,
+                    choices: None,
+                    decimal: None,
                     foreign_key: None,
                 }],
+                timestamps: false,
             },
         }
     }
@@ -1986,15 +2295,19 @@ mod tests {
                 resolved_ty: parse_quote!(TestModel),
                 model_type: ModelType::default(),
                 table_name: "test_model".to_string(),
-                pk_field: Field {
+                pk_fields: vec![Field {
                     name: format_ident!("id"),
                     column_name: "id".to_string(),
                     ty: parse_quote!(i32),
                     auto_value: true,
                     primary_key: true,
                     unique: false,
+                    default: None,
+                    slugify_from: None,
+                    choices: None,
+                    decimal: None,
                     foreign_key: None,
-                },
+                }],
                 fields: vec![
                     Field {
                         name: format_ident!("field1"),
@@ -2003,6 +2316,10 @@ mod tests {
                         auto_value: false,
                         primary_key: false,
                         unique: false,
+                        default: None,
+                        slugify_from: None,
+                        choices: None,
+                        decimal: None,
                         foreign_key: None,
                     },
                     Field {
@@ -2012,9 +2329,14 @@ mod tests {
                         auto_value: false,
                         primary_key: false,
                         unique: false,
+                        default: None,
+                        slugify_from: None,
+                        choices: None,
+                        decimal: None,
                         foreign_key: None,
                     },
                 ],
+                timestamps: false,
             },
         }
     }
@@ -2029,6 +2351,10 @@ mod tests {
             auto_value: false,
             primary_key: false,
             unique: false,
+            default: None,
+            slugify_from: None,
+            choices: None,
+            decimal: None,
             foreign_key: None,
         };
 
@@ -2178,6 +2504,10 @@ mod tests {
                 auto_value: false,
                 primary_key: false,
                 unique: false,
+                default: None,
+                slugify_from: None,
+                choices: None,
+                decimal: None,
                 foreign_key: None,
             }),
         };
@@ -2203,6 +2533,171 @@ mod tests {
         );
     }
 
+    #[test]
+    fn repr_for_field_with_default() {
+        let field = |expr: syn::Expr, nullable: bool| Field {
+            name: format_ident!("test_field"),
+            column_name: "test_field".to_string(),
+            ty: parse_quote!(i64),
+            auto_value: false,
+            primary_key: false,
+            unique: false,
+            default: Some(FieldDefault { expr, nullable }),
+            slugify_from: None,
+            choices: None,
+            decimal: None,
+            foreign_key: None,
+        };
+
+        let tokens_str = field(parse_quote!(5), false).repr().to_string();
+        assert!(
+            tokens_str
+                .contains("default_value (:: cot :: db :: migrations :: DefaultValue :: Int (5))"),
+            "Should call default_value() but got: {tokens_str}"
+        );
+
+        let tokens_str = field(parse_quote!(compute()), true).repr().to_string();
+        assert!(
+            !tokens_str.contains("default_value"),
+            "Should not call default_value() for non-constant defaults but got: {tokens_str}"
+        );
+    }
+
+    #[test]
+    fn repr_for_field_with_decimal_precision() {
+        let field = Field {
+            name: format_ident!("price"),
+            column_name: "price".to_string(),
+            ty: parse_quote!(cot::db::Decimal),
+            auto_value: false,
+            primary_key: false,
+            unique: false,
+            default: None,
+            slugify_from: None,
+            choices: None,
+            decimal: Some(DecimalArgs {
+                precision: 10,
+                scale: 2,
+            }),
+            foreign_key: None,
+        };
+
+        let tokens_str = field.repr().to_string();
+        assert!(
+            tokens_str.contains("decimal_precision (10u32 , 2u32)"),
+            "Should call decimal_precision() but got: {tokens_str}"
+        );
+    }
+
+    fn choices_field(choices: Option<FieldChoices>) -> Field {
+        Field {
+            name: format_ident!("status"),
+            column_name: "status".to_string(),
+            ty: parse_quote!(cot::db::Choice<crate::Status>),
+            auto_value: false,
+            primary_key: false,
+            unique: false,
+            default: None,
+            slugify_from: None,
+            choices,
+            decimal: None,
+            foreign_key: None,
+        }
+    }
+
+    fn explicit_choices(values: &[&str]) -> Option<FieldChoices> {
+        Some(FieldChoices::Values(
+            values.iter().map(ToString::to_string).collect(),
+        ))
+    }
+
+    #[test]
+    fn repr_for_field_with_choices() {
+        let tokens_str = choices_field(explicit_choices(&["draft", "published"]))
+            .repr()
+            .to_string();
+
+        assert!(
+            tokens_str.contains(r#"choices (& ["draft" , "published"])"#),
+            "Should call choices() but got: {tokens_str}"
+        );
+    }
+
+    #[test]
+    fn choice_enums_resolve_field_choices() {
+        let file = SourceFile::parse(
+            PathBuf::from("main.rs"),
+            r#"
+                #[derive(Debug, SelectChoice)]
+                enum Status {
+                    #[select_choice(id = "draft", name = "Draft")]
+                    Draft,
+                    Published,
+                }
+            "#,
+        )
+        .unwrap();
+        let choice_enums = ChoiceEnums::from_source_files(&[file]);
+
+        let mut field = choices_field(Some(FieldChoices::FromType));
+        choice_enums.resolve_field_choices(&mut field).unwrap();
+        assert_eq!(field.choices, explicit_choices(&["draft", "Published"]));
+
+        let mut field = choices_field(Some(FieldChoices::FromType));
+        field.ty = parse_quote!(Choice<Unknown>);
+        assert!(choice_enums.resolve_field_choices(&mut field).is_err());
+
+        let mut field = choices_field(Some(FieldChoices::FromType));
+        field.ty = parse_quote!(String);
+        assert!(choice_enums.resolve_field_choices(&mut field).is_err());
+    }
+
+    #[test]
+    fn freeze_field_choices() {
+        let mut field: syn::Field = parse_quote! {
+            #[model(unique, choices)]
+            status: Choice<Status>
+        };
+
+        MigrationGenerator::freeze_field_choices(
+            &mut field,
+            &["draft".to_string(), "published".to_string()],
+        );
+
+        let expected: syn::Attribute = parse_quote! {
+            #[model(unique, choices = ["draft", "published"])]
+        };
+        assert_eq!(field.attrs, vec![expected]);
+    }
+
+    #[test]
+    fn make_alter_field_operation_with_changed_choices() {
+        let app_model = get_test_model();
+        let migration_model = get_test_model();
+
+        let operation = MigrationOperationGenerator::make_alter_field_operation(
+            &app_model,
+            &choices_field(explicit_choices(&["draft", "published", "archived"])),
+            &migration_model,
+            &choices_field(explicit_choices(&["draft", "published"])),
+        )
+        .unwrap();
+
+        let tokens_str = operation.repr().to_string();
+        assert!(
+            tokens_str.contains("alter_field_choices ()"),
+            "Should call alter_field_choices() but got: {tokens_str}"
+        );
+        assert!(
+            tokens_str.contains(r#"old_choices (& ["draft" , "published"])"#),
+            "Should call old_choices() but got: {tokens_str}"
+        );
+        assert!(
+            tokens_str.contains(r#"new_choices (& ["draft" , "published" , "archived"])"#),
+            "Should call new_choices() but got: {tokens_str}"
+        );
+    }
+
     repr_for_foreign_key_operation_test!(
         repr_for_foreign_key_operation_cascade_cascade,
         ForeignKeyOnDeletePolicy::Cascade,
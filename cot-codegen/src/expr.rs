@@ -4,6 +4,10 @@ use syn::Token;
 use syn::parse::{Lookahead1, Parse, ParseStream};
 use syn::spanned::Spanned;
 
+mod kw {
+    syn::custom_keyword!(like);
+}
+
 #[derive(Debug)]
 enum ItemToken {
     Field(FieldParser),
@@ -181,6 +185,8 @@ enum OpParser {
     Lte(Token![<=]),
     Gt(Token![>]),
     Gte(Token![>=]),
+    In(Token![in]),
+    Like(kw::like),
     And(Token![&&]),
     Or(Token![||]),
 }
@@ -210,6 +216,10 @@ impl OpParser {
             OpParser::Gte(input.parse()?)
         } else if lookahead.peek(Token![>]) {
             OpParser::Gt(input.parse()?)
+        } else if lookahead.peek(Token![in]) {
+            OpParser::In(input.parse()?)
+        } else if lookahead.peek(kw::like) {
+            OpParser::Like(input.parse()?)
         } else if lookahead.peek(Token![&&]) {
             OpParser::And(input.parse()?)
         } else if lookahead.peek(Token![||]) {
@@ -233,6 +243,8 @@ impl OpParser {
             OpParser::Lte(lte) => lte.span(),
             OpParser::Gt(gt) => gt.span(),
             OpParser::Gte(gte) => gte.span(),
+            OpParser::In(in_) => in_.span(),
+            OpParser::Like(like) => like.span(),
             OpParser::And(and) => and.span(),
             OpParser::Or(or) => or.span(),
         }
@@ -247,7 +259,9 @@ impl OpParser {
             | OpParser::Lt(_)
             | OpParser::Lte(_)
             | OpParser::Gt(_)
-            | OpParser::Gte(_) => InfixBindingPriority::right_to_left(3),
+            | OpParser::Gte(_)
+            | OpParser::In(_)
+            | OpParser::Like(_) => InfixBindingPriority::right_to_left(3),
             OpParser::And(_) => InfixBindingPriority::left_to_right(2),
             OpParser::Or(_) => InfixBindingPriority::left_to_right(1),
         }
@@ -284,6 +298,10 @@ impl BindingPriority<u8, u8> {
 
 type InfixBindingPriority = BindingPriority<u8, u8>;
 
+/// Binding priority of prefix operators (`!`); higher than any infix operator,
+/// so that `!$a == $b` is parsed as `(!$a) == $b`, just like in Rust.
+const PREFIX_BINDING_PRIORITY: u8 = 19;
+
 /// A parsed expression.
 ///
 /// This type represents a parsed expression that can be used to generate code.
@@ -325,6 +343,10 @@ pub enum Expr {
         function: Box<Expr>,
         args: Vec<syn::Expr>,
     },
+    Not {
+        expr: Box<Expr>,
+        not_token: Token![!],
+    },
     And(Box<Expr>, Box<Expr>),
     Or(Box<Expr>, Box<Expr>),
     Eq(Box<Expr>, Box<Expr>),
@@ -333,6 +355,8 @@ pub enum Expr {
     Lte(Box<Expr>, Box<Expr>),
     Gt(Box<Expr>, Box<Expr>),
     Gte(Box<Expr>, Box<Expr>),
+    In(Box<Expr>, Box<Expr>),
+    Like(Box<Expr>, Box<Expr>),
     Add(Box<Expr>, Box<Expr>),
     Sub(Box<Expr>, Box<Expr>),
     Mul(Box<Expr>, Box<Expr>),
@@ -356,6 +380,15 @@ impl Expr {
             let content;
             let _ = syn::parenthesized!(content in input);
             Self::parse_impl(&content, 0)?
+        } else if input.peek(syn::token::Bracket) {
+            Expr::Value(syn::Expr::Array(input.parse()?))
+        } else if input.peek(Token![!]) {
+            let not_token = input.parse::<Token![!]>()?;
+            let expr = Self::parse_impl(input, PREFIX_BINDING_PRIORITY)?;
+            Expr::Not {
+                expr: Box::new(expr),
+                not_token,
+            }
         } else {
             let lhs_item = input.parse::<ItemToken>()?;
 
@@ -457,11 +490,37 @@ impl Expr {
             OpParser::Lte(_) => Expr::Lte(Box::new(lhs), Box::new(rhs)),
             OpParser::Gt(_) => Expr::Gt(Box::new(lhs), Box::new(rhs)),
             OpParser::Gte(_) => Expr::Gte(Box::new(lhs), Box::new(rhs)),
+            OpParser::In(_) => Expr::In(Box::new(lhs), Box::new(rhs)),
+            OpParser::Like(_) => Expr::Like(Box::new(lhs), Box::new(rhs)),
             OpParser::And(_) => Expr::And(Box::new(lhs), Box::new(rhs)),
             OpParser::Or(_) => Expr::Or(Box::new(lhs), Box::new(rhs)),
         }
     }
 
+    #[must_use]
+    fn binary_tokens(
+        lhs: &Expr,
+        op: &TokenStream,
+        rhs: &Expr,
+        mode: ExprAsTokensMode,
+    ) -> Option<TokenStream> {
+        let lhs_tokens = lhs.as_tokens_impl(mode)?;
+        let rhs_tokens = rhs.as_tokens_impl(mode)?;
+        Some(quote! {#lhs_tokens #op #rhs_tokens})
+    }
+
+    #[must_use]
+    fn is_operation(&self) -> bool {
+        !matches!(
+            self,
+            Expr::FieldRef { .. }
+                | Expr::Value(_)
+                | Expr::MemberAccess { .. }
+                | Expr::PathAccess { .. }
+                | Expr::FunctionCall { .. }
+        )
+    }
+
     #[must_use]
     pub fn as_tokens(&self) -> Option<TokenStream> {
         self.as_tokens_impl(ExprAsTokensMode::FieldRefAsNone)
@@ -504,66 +563,28 @@ impl Expr {
                 let function_tokens = function.as_tokens_impl(mode)?;
                 Some(quote! {#function_tokens(#(#args),*)})
             }
-            Expr::And(lhs, rhs) => {
-                let lhs_tokens = lhs.as_tokens_impl(mode)?;
-                let rhs_tokens = rhs.as_tokens_impl(mode)?;
-                Some(quote! {#lhs_tokens && #rhs_tokens})
-            }
-            Expr::Or(lhs, rhs) => {
-                let lhs_tokens = lhs.as_tokens_impl(mode)?;
-                let rhs_tokens = rhs.as_tokens_impl(mode)?;
-                Some(quote! {#lhs_tokens || #rhs_tokens})
-            }
-            Expr::Eq(lhs, rhs) => {
-                let lhs_tokens = lhs.as_tokens_impl(mode)?;
-                let rhs_tokens = rhs.as_tokens_impl(mode)?;
-                Some(quote! {#lhs_tokens == #rhs_tokens})
-            }
-            Expr::Ne(lhs, rhs) => {
-                let lhs_tokens = lhs.as_tokens_impl(mode)?;
-                let rhs_tokens = rhs.as_tokens_impl(mode)?;
-                Some(quote! {#lhs_tokens != #rhs_tokens})
-            }
-            Expr::Lt(lhs, rhs) => {
-                let lhs_tokens = lhs.as_tokens_impl(mode)?;
-                let rhs_tokens = rhs.as_tokens_impl(mode)?;
-                Some(quote! {#lhs_tokens < #rhs_tokens})
-            }
-            Expr::Lte(lhs, rhs) => {
-                let lhs_tokens = lhs.as_tokens_impl(mode)?;
-                let rhs_tokens = rhs.as_tokens_impl(mode)?;
-                Some(quote! {#lhs_tokens <= #rhs_tokens})
-            }
-            Expr::Gt(lhs, rhs) => {
-                let lhs_tokens = lhs.as_tokens_impl(mode)?;
-                let rhs_tokens = rhs.as_tokens_impl(mode)?;
-                Some(quote! {#lhs_tokens > #rhs_tokens})
-            }
-            Expr::Gte(lhs, rhs) => {
-                let lhs_tokens = lhs.as_tokens_impl(mode)?;
-                let rhs_tokens = rhs.as_tokens_impl(mode)?;
-                Some(quote! {#lhs_tokens >= #rhs_tokens})
-            }
-            Expr::Add(lhs, rhs) => {
-                let lhs_tokens = lhs.as_tokens_impl(mode)?;
-                let rhs_tokens = rhs.as_tokens_impl(mode)?;
-                Some(quote! {#lhs_tokens + #rhs_tokens})
-            }
-            Expr::Sub(lhs, rhs) => {
-                let lhs_tokens = lhs.as_tokens_impl(mode)?;
-                let rhs_tokens = rhs.as_tokens_impl(mode)?;
-                Some(quote! {#lhs_tokens - #rhs_tokens})
-            }
-            Expr::Mul(lhs, rhs) => {
-                let lhs_tokens = lhs.as_tokens_impl(mode)?;
-                let rhs_tokens = rhs.as_tokens_impl(mode)?;
-                Some(quote! {#lhs_tokens * #rhs_tokens})
-            }
-            Expr::Div(lhs, rhs) => {
-                let lhs_tokens = lhs.as_tokens_impl(mode)?;
-                let rhs_tokens = rhs.as_tokens_impl(mode)?;
-                Some(quote! {#lhs_tokens / #rhs_tokens})
+            Expr::Not { expr, not_token } => {
+                let expr_tokens = expr.as_tokens_impl(mode)?;
+                if expr.is_operation() {
+                    Some(quote! {#not_token (#expr_tokens)})
+                } else {
+                    Some(quote! {#not_token #expr_tokens})
+                }
             }
+            Expr::And(lhs, rhs) => Self::binary_tokens(lhs, &quote!(&&), rhs, mode),
+            Expr::Or(lhs, rhs) => Self::binary_tokens(lhs, &quote!(||), rhs, mode),
+            Expr::Eq(lhs, rhs) => Self::binary_tokens(lhs, &quote!(==), rhs, mode),
+            Expr::Ne(lhs, rhs) => Self::binary_tokens(lhs, &quote!(!=), rhs, mode),
+            Expr::Lt(lhs, rhs) => Self::binary_tokens(lhs, &quote!(<), rhs, mode),
+            Expr::Lte(lhs, rhs) => Self::binary_tokens(lhs, &quote!(<=), rhs, mode),
+            Expr::Gt(lhs, rhs) => Self::binary_tokens(lhs, &quote!(>), rhs, mode),
+            Expr::Gte(lhs, rhs) => Self::binary_tokens(lhs, &quote!(>=), rhs, mode),
+            Expr::In(lhs, rhs) => Self::binary_tokens(lhs, &quote!(in), rhs, mode),
+            Expr::Like(lhs, rhs) => Self::binary_tokens(lhs, &quote!(like), rhs, mode),
+            Expr::Add(lhs, rhs) => Self::binary_tokens(lhs, &quote!(+), rhs, mode),
+            Expr::Sub(lhs, rhs) => Self::binary_tokens(lhs, &quote!(-), rhs, mode),
+            Expr::Mul(lhs, rhs) => Self::binary_tokens(lhs, &quote!(*), rhs, mode),
+            Expr::Div(lhs, rhs) => Self::binary_tokens(lhs, &quote!(/), rhs, mode),
         }
     }
 }
@@ -649,6 +670,88 @@ mod tests {
         assert_eq!(expected, unwrap_syn(Expr::parse(input)));
     }
 
+    #[test]
+    fn field_in() {
+        let input = quote! { $field in [1, 2, 3] };
+        let expected = Expr::In(
+            Box::new(field("field")),
+            Box::new(Expr::Value(parse_quote!([1, 2, 3]))),
+        );
+
+        assert_eq!(expected, unwrap_syn(Expr::parse(input)));
+    }
+
+    #[test]
+    fn field_in_variable_and() {
+        let input = quote! { $field in ids && $name like "%foo%" };
+        let expected = Expr::And(
+            Box::new(Expr::In(
+                Box::new(field("field")),
+                Box::new(Expr::Value(parse_quote!(ids))),
+            )),
+            Box::new(Expr::Like(
+                Box::new(field("name")),
+                Box::new(Expr::Value(parse_quote!("%foo%"))),
+            )),
+        );
+
+        assert_eq!(expected, unwrap_syn(Expr::parse(input)));
+    }
+
+    #[test]
+    fn or_and_precedence() {
+        let input = quote! { $a == 1 || $b == 2 && $c == 3 };
+        let expected = Expr::Or(
+            Box::new(Expr::Eq(
+                Box::new(field("a")),
+                Box::new(Expr::Value(parse_quote!(1))),
+            )),
+            Box::new(Expr::And(
+                Box::new(Expr::Eq(
+                    Box::new(field("b")),
+                    Box::new(Expr::Value(parse_quote!(2))),
+                )),
+                Box::new(Expr::Eq(
+                    Box::new(field("c")),
+                    Box::new(Expr::Value(parse_quote!(3))),
+                )),
+            )),
+        );
+
+        assert_eq!(expected, unwrap_syn(Expr::parse(input)));
+    }
+
+    #[test]
+    fn not_field() {
+        let input = quote! { !$done && $a == 1 };
+        let expected = Expr::And(
+            Box::new(not(field("done"))),
+            Box::new(Expr::Eq(
+                Box::new(field("a")),
+                Box::new(Expr::Value(parse_quote!(1))),
+            )),
+        );
+
+        assert_eq!(expected, unwrap_syn(Expr::parse(input)));
+    }
+
+    #[test]
+    fn not_parenthesis() {
+        let input = quote! { !($a == 1 || $b == 2) };
+        let expected = not(Expr::Or(
+            Box::new(Expr::Eq(
+                Box::new(field("a")),
+                Box::new(Expr::Value(parse_quote!(1))),
+            )),
+            Box::new(Expr::Eq(
+                Box::new(field("b")),
+                Box::new(Expr::Value(parse_quote!(2))),
+            )),
+        ));
+
+        assert_eq!(expected, unwrap_syn(Expr::parse(input)));
+    }
+
     #[test]
     fn parenthesis_literal() {
         let input = quote! { (((($a)))) };
@@ -789,6 +892,22 @@ mod tests {
         assert_eq!(input.to_string(), expr.as_tokens().unwrap().to_string());
     }
 
+    #[test]
+    fn tokens_not() {
+        let input = quote! { !is_active(user) };
+        let expr = unwrap_syn(Expr::parse(input.clone()));
+
+        assert_eq!(input.to_string(), expr.as_tokens().unwrap().to_string());
+    }
+
+    #[test]
+    fn tokens_not_parenthesis() {
+        let input = quote! { !(x == 42) };
+        let expr = unwrap_syn(Expr::parse(input.clone()));
+
+        assert_eq!(input.to_string(), expr.as_tokens().unwrap().to_string());
+    }
+
     #[test]
     fn tokens_ne() {
         let input = quote! { x != 42 };
@@ -829,6 +948,22 @@ mod tests {
         assert_eq!(input.to_string(), expr.as_tokens().unwrap().to_string());
     }
 
+    #[test]
+    fn tokens_in() {
+        let input = quote! { x in [1, 2] };
+        let expr = unwrap_syn(Expr::parse(input.clone()));
+
+        assert_eq!(input.to_string(), expr.as_tokens().unwrap().to_string());
+    }
+
+    #[test]
+    fn tokens_like() {
+        let input = quote! { x like "%foo%" };
+        let expr = unwrap_syn(Expr::parse(input.clone()));
+
+        assert_eq!(input.to_string(), expr.as_tokens().unwrap().to_string());
+    }
+
     #[test]
     fn tokens_and() {
         let input = quote! { x && y };
@@ -893,6 +1028,14 @@ mod tests {
         }
     }
 
+    #[must_use]
+    fn not(expr: Expr) -> Expr {
+        Expr::Not {
+            expr: Box::new(expr),
+            not_token: Token![!](span()),
+        }
+    }
+
     #[must_use]
     fn member_access(parent: Expr, member_name: &str) -> Expr {
         Expr::MemberAccess {
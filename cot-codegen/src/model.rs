@@ -7,12 +7,20 @@ use syn::spanned::Spanned;
 
 use crate::symbol_resolver::SymbolResolver;
 
+/// The name of the field storing the creation time of models with
+/// `#[model(timestamps)]`.
+pub const CREATED_AT_FIELD: &str = "created_at";
+/// The name of the field storing the last modification time of models with
+/// `#[model(timestamps)]`.
+pub const UPDATED_AT_FIELD: &str = "updated_at";
+
 #[expect(clippy::module_name_repetitions)]
 #[derive(Debug, Default, FromMeta)]
 pub struct ModelArgs {
     #[darling(default)]
     pub model_type: ModelType,
     pub table_name: Option<String>,
+    pub timestamps: darling::util::Flag,
 }
 
 #[expect(clippy::module_name_repetitions)]
@@ -98,7 +106,11 @@ impl ModelOpts {
             original_name.to_snake_case()
         };
 
-        let primary_key_field = self.get_primary_key_field(&fields)?;
+        let primary_key_fields = self.get_primary_key_fields(&fields)?;
+        if args.timestamps.is_present() {
+            self.check_timestamp_fields(&fields)?;
+        }
+        Self::check_slug_sources(&fields)?;
 
         let ty = {
             let mut ty = syn::Type::Path(syn::TypePath {
@@ -116,12 +128,55 @@ impl ModelOpts {
             resolved_ty: ty,
             model_type: args.model_type,
             table_name,
-            pk_field: primary_key_field.clone(),
+            pk_fields: primary_key_fields.into_iter().cloned().collect(),
             fields,
+            timestamps: args.timestamps.is_present(),
         })
     }
 
-    fn get_primary_key_field<'a>(&self, fields: &'a [Field]) -> Result<&'a Field, syn::Error> {
+    fn check_timestamp_fields(&self, fields: &[Field]) -> Result<(), syn::Error> {
+        for name in [CREATED_AT_FIELD, UPDATED_AT_FIELD] {
+            if !fields.iter().any(|field| field.name == name) {
+                return Err(syn::Error::new(
+                    self.ident.span(),
+                    format!(
+                        "models with `#[model(timestamps)]` must have the `{name}` field \
+                        (for instance, of type `chrono::DateTime<chrono::Utc>`)"
+                    ),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_slug_sources(fields: &[Field]) -> Result<(), syn::Error> {
+        for field in fields {
+            let Some(source) = &field.slugify_from else {
+                continue;
+            };
+
+            if !fields.iter().any(|other| other.name == *source) {
+                return Err(syn::Error::new(
+                    source.span(),
+                    format!("field `{source}` used in `slugify_from` does not exist"),
+                ));
+            }
+            if *source == field.name {
+                return Err(syn::Error::new(
+                    source.span(),
+                    "a slug cannot be generated from the field itself",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_primary_key_fields<'a>(
+        &self,
+        fields: &'a [Field],
+    ) -> Result<Vec<&'a Field>, syn::Error> {
         let pks: Vec<_> = fields.iter().filter(|field| field.primary_key).collect();
         if pks.is_empty() {
             return Err(syn::Error::new(
@@ -130,15 +185,40 @@ impl ModelOpts {
                 the `#[model(primary_key)]` attribute",
             ));
         }
-        if pks.len() > 1 {
+        if pks.len() > 1
+            && let Some(auto_field) = pks.iter().find(|field| Self::is_auto_field(field))
+        {
             return Err(syn::Error::new(
-                pks[1].name.span(),
-                "composite primary keys are not supported; only one primary key field is allowed",
+                auto_field.name.span(),
+                "`Auto` fields cannot be a part of a composite primary key",
             ));
         }
 
-        Ok(pks[0])
+        Ok(pks)
     }
+
+    /// Returns whether the field is an `Auto` field.
+    ///
+    /// The type is checked by its name as well, since the `cot::db::Auto`
+    /// import is not resolved when expanding the `#[model]` macro.
+    fn is_auto_field(field: &Field) -> bool {
+        field.auto_value
+            || matches!(
+                &field.ty,
+                syn::Type::Path(type_path)
+                    if type_path.path.segments.last().is_some_and(|segment| segment.ident == "Auto")
+            )
+    }
+}
+
+/// The arguments of the `#[model(decimal(precision = ..., scale = ...))]`
+/// attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, FromMeta)]
+pub struct DecimalArgs {
+    /// The total number of digits.
+    pub precision: u32,
+    /// The number of digits after the decimal point.
+    pub scale: u32,
 }
 
 #[derive(Debug, Clone, Copy, Default, FromMeta)]
@@ -216,6 +296,11 @@ pub struct FieldOpts {
     pub unique: darling::util::Flag,
     pub field_name: Option<String>,
     pub foreign_key: Option<ForeignKeyArgs>,
+    #[darling(default, with = darling::util::parse_expr::preserve_str_literal, map = Some)]
+    pub default: Option<syn::Expr>,
+    pub slugify_from: Option<syn::Ident>,
+    pub choices: Option<FieldChoices>,
+    pub decimal: Option<DecimalArgs>,
 }
 
 impl FieldOpts {
@@ -295,6 +380,12 @@ impl FieldOpts {
             .or_else(|| self.find_type("ForeignKey", symbol_resolver))
     }
 
+    fn find_decimal_type(&self, symbol_resolver: &SymbolResolver) -> Option<syn::Type> {
+        self.find_type("cot::db::Decimal", symbol_resolver)
+            .or_else(|| self.find_type("rust_decimal::Decimal", symbol_resolver))
+            .or_else(|| self.find_type("Decimal", symbol_resolver))
+    }
+
     /// Convert the field options into a field.
     ///
     /// # Panics
@@ -352,7 +443,41 @@ impl FieldOpts {
             }
         }
 
+        if let Some(decimal) = self.decimal {
+            if self.find_decimal_type(symbol_resolver).is_none() {
+                return Err(syn::Error::new(
+                    self.ident.span(),
+                    "`#[model(decimal(...))]` can only be used on `Decimal` fields",
+                ));
+            }
+            if decimal.precision == 0 || decimal.scale > decimal.precision {
+                return Err(syn::Error::new(
+                    self.ident.span(),
+                    "the decimal precision must be greater than zero and not smaller than the scale",
+                ));
+            }
+        }
+
         let is_primary_key = self.primary_key.is_present();
+        let default = self.default.clone().map(|expr| FieldDefault {
+            expr,
+            nullable: self.is_option_type(symbol_resolver),
+        });
+        if let Some(default) = &default {
+            if is_primary_key {
+                return Err(syn::Error::new(
+                    default.expr.span(),
+                    "default values are not supported on primary key fields",
+                ));
+            }
+            if !default.nullable && default.constant_value().is_none() {
+                return Err(syn::Error::new(
+                    default.expr.span(),
+                    "non-constant default values can only be used on `Option<T>` fields",
+                ));
+            }
+        }
+
         let mut resolved_ty = self.ty.clone();
         symbol_resolver.resolve(&mut resolved_ty, self_reference);
         Ok(Field {
@@ -363,6 +488,10 @@ impl FieldOpts {
             primary_key: is_primary_key,
             foreign_key,
             unique: self.unique.is_present(),
+            default,
+            slugify_from: self.slugify_from.clone(),
+            choices: self.choices.clone(),
+            decimal: self.decimal,
         })
     }
 }
@@ -377,8 +506,12 @@ pub struct Model {
     #[expect(clippy::struct_field_names)] // `type` is not an allowed identifier in Rust
     pub model_type: ModelType,
     pub table_name: String,
-    pub pk_field: Field,
+    /// The fields making up the primary key of the model; there is more than
+    /// one if the model has a composite primary key.
+    pub pk_fields: Vec<Field>,
     pub fields: Vec<Field>,
+    /// Whether the `created_at` and `updated_at` fields are set automatically.
+    pub timestamps: bool,
 }
 
 impl Model {
@@ -400,6 +533,107 @@ pub struct Field {
     /// determined not to be a foreign key.
     pub foreign_key: Option<ForeignKeySpec>,
     pub unique: bool,
+    /// [`Some`] if the field has a `#[model(default = ...)]` attribute.
+    pub default: Option<FieldDefault>,
+    /// [`Some`] if the field is a slug generated from another field with
+    /// `#[model(slugify_from = "...")]`.
+    pub slugify_from: Option<syn::Ident>,
+    /// [`Some`] if the values of the field are restricted with
+    /// `#[model(choices)]`.
+    pub choices: Option<FieldChoices>,
+    /// [`Some`] if the precision and scale of a decimal field are set with
+    /// `#[model(decimal(precision = ..., scale = ...))]`.
+    pub decimal: Option<DecimalArgs>,
+}
+
+/// The values allowed in a field, specified with `#[model(choices)]`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FieldChoices {
+    /// `#[model(choices)]`: the values are the identifiers of the variants of
+    /// the enum stored in the field, which are looked up by the migration
+    /// generator.
+    FromType,
+    /// `#[model(choices = ["...", ...])]`: the values are listed explicitly,
+    /// as in the models stored in the migrations.
+    Values(Vec<String>),
+}
+
+impl FromMeta for FieldChoices {
+    fn from_word() -> darling::Result<Self> {
+        Ok(Self::FromType)
+    }
+
+    fn from_expr(expr: &syn::Expr) -> darling::Result<Self> {
+        let syn::Expr::Array(array) = expr else {
+            return Err(darling::Error::unexpected_expr_type(expr));
+        };
+
+        let values = array
+            .elems
+            .iter()
+            .map(|elem| match elem {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(value),
+                    ..
+                }) => Ok(value.value()),
+                _ => Err(darling::Error::custom("expected a string literal").with_span(elem)),
+            })
+            .collect::<darling::Result<Vec<_>>>()?;
+        if values.is_empty() {
+            return Err(darling::Error::too_few_items(1).with_span(expr));
+        }
+
+        Ok(Self::Values(values))
+    }
+}
+
+/// The default value of a field, specified with `#[model(default = ...)]`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FieldDefault {
+    pub expr: syn::Expr,
+    /// Whether the field is an `Option<T>`. If so, the default value is
+    /// applied in Rust to the fields set to `None` before saving.
+    pub nullable: bool,
+}
+
+impl FieldDefault {
+    /// Returns the `cot::db::migrations::DefaultValue` for the default value,
+    /// or [`None`] if the default value is not a constant literal and hence
+    /// cannot be emitted as the SQL `DEFAULT` clause.
+    #[must_use]
+    pub fn constant_value(&self) -> Option<TokenStream> {
+        let (negative, lit) = match &self.expr {
+            syn::Expr::Lit(expr) => (false, &expr.lit),
+            syn::Expr::Unary(syn::ExprUnary {
+                op: syn::UnOp::Neg(_),
+                expr,
+                ..
+            }) => match &**expr {
+                syn::Expr::Lit(expr) => (true, &expr.lit),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let sign = negative.then(|| quote! { - });
+
+        match lit {
+            syn::Lit::Bool(value) if !negative => {
+                Some(quote! { ::cot::db::migrations::DefaultValue::Bool(#value) })
+            }
+            syn::Lit::Int(value) => {
+                let value = syn::LitInt::new(value.base10_digits(), value.span());
+                Some(quote! { ::cot::db::migrations::DefaultValue::Int(#sign #value) })
+            }
+            syn::Lit::Float(value) => {
+                let value = syn::LitFloat::new(value.base10_digits(), value.span());
+                Some(quote! { ::cot::db::migrations::DefaultValue::Float(#sign #value) })
+            }
+            syn::Lit::Str(value) if !negative => {
+                Some(quote! { ::cot::db::migrations::DefaultValue::String(#value) })
+            }
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -504,6 +738,88 @@ mod tests {
         let args: ModelArgs = ModelArgs::default();
         assert_eq!(args.model_type, ModelType::Application);
         assert!(args.table_name.is_none());
+        assert!(!args.timestamps.is_present());
+    }
+
+    #[test]
+    fn model_opts_as_model_timestamps() {
+        let input: syn::DeriveInput = parse_quote! {
+            #[model(timestamps)]
+            struct TestModel {
+                #[model(primary_key)]
+                id: i32,
+                created_at: chrono::DateTime<chrono::Utc>,
+                updated_at: chrono::DateTime<chrono::Utc>,
+            }
+        };
+        let opts = ModelOpts::new_from_derive_input(&input).unwrap();
+        let args = ModelArgs::from_meta(&input.attrs.first().unwrap().meta).unwrap();
+        let model = opts.as_model(&args, &SymbolResolver::new(vec![])).unwrap();
+        assert!(model.timestamps);
+    }
+
+    #[test]
+    fn model_opts_as_model_timestamps_missing_field() {
+        let input: syn::DeriveInput = parse_quote! {
+            #[model(timestamps)]
+            struct TestModel {
+                #[model(primary_key)]
+                id: i32,
+                created_at: chrono::DateTime<chrono::Utc>,
+            }
+        };
+        let opts = ModelOpts::new_from_derive_input(&input).unwrap();
+        let args = ModelArgs::from_meta(&input.attrs.first().unwrap().meta).unwrap();
+        let err = opts
+            .as_model(&args, &SymbolResolver::new(vec![]))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "models with `#[model(timestamps)]` must have the `updated_at` field \
+            (for instance, of type `chrono::DateTime<chrono::Utc>`)"
+        );
+    }
+
+    #[test]
+    fn model_opts_as_model_slugify_from() {
+        let input: syn::DeriveInput = parse_quote! {
+            struct TestModel {
+                #[model(primary_key)]
+                id: i32,
+                title: String,
+                #[model(slugify_from = "title")]
+                slug: cot::db::Slug,
+            }
+        };
+        let opts = ModelOpts::new_from_derive_input(&input).unwrap();
+        let model = opts
+            .as_model(&ModelArgs::default(), &SymbolResolver::new(vec![]))
+            .unwrap();
+        assert_eq!(model.fields[1].slugify_from, None);
+        assert_eq!(
+            model.fields[2].slugify_from,
+            Some(syn::Ident::new("title", proc_macro2::Span::call_site()))
+        );
+    }
+
+    #[test]
+    fn model_opts_as_model_slugify_from_missing_field() {
+        let input: syn::DeriveInput = parse_quote! {
+            struct TestModel {
+                #[model(primary_key)]
+                id: i32,
+                #[model(slugify_from = "title")]
+                slug: cot::db::Slug,
+            }
+        };
+        let opts = ModelOpts::new_from_derive_input(&input).unwrap();
+        let err = opts
+            .as_model(&ModelArgs::default(), &SymbolResolver::new(vec![]))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "field `title` used in `slugify_from` does not exist"
+        );
     }
 
     #[test]
@@ -619,7 +935,7 @@ mod tests {
     }
 
     #[test]
-    fn model_opts_as_model_multiple_pks() {
+    fn model_opts_as_model_composite_pk() {
         let input: syn::DeriveInput = parse_quote! {
             #[model]
             struct TestModel {
@@ -632,12 +948,35 @@ mod tests {
         };
         let opts = ModelOpts::new_from_derive_input(&input).unwrap();
         let args = ModelArgs::default();
+        let model = opts.as_model(&args, &SymbolResolver::new(vec![])).unwrap();
+        let pk_names: Vec<_> = model
+            .pk_fields
+            .iter()
+            .map(|field| field.name.to_string())
+            .collect();
+        assert_eq!(pk_names, ["id", "id_2"]);
+    }
+
+    #[test]
+    fn model_opts_as_model_composite_pk_rejects_auto() {
+        let input: syn::DeriveInput = parse_quote! {
+            #[model]
+            struct TestModel {
+                #[model(primary_key)]
+                id: Auto<i64>,
+                #[model(primary_key)]
+                id_2: i64,
+                name: String,
+            }
+        };
+        let opts = ModelOpts::new_from_derive_input(&input).unwrap();
+        let args = ModelArgs::default();
         let err = opts
             .as_model(&args, &SymbolResolver::new(vec![]))
             .unwrap_err();
         assert_eq!(
             err.to_string(),
-            "composite primary keys are not supported; only one primary key field is allowed"
+            "`Auto` fields cannot be a part of a composite primary key"
         );
     }
 
@@ -657,6 +996,44 @@ mod tests {
         assert!(field.unique);
     }
 
+    #[test]
+    fn field_opts_choices() {
+        let input: syn::Field = parse_quote! {
+            #[model(choices)]
+            status: Choice<Status>
+        };
+        let field_opts = FieldOpts::from_field(&input).unwrap();
+        assert_eq!(field_opts.choices, Some(FieldChoices::FromType));
+
+        let input: syn::Field = parse_quote! {
+            #[model(choices = ["draft", "published"])]
+            status: Choice<Status>
+        };
+        let field_opts = FieldOpts::from_field(&input).unwrap();
+        assert_eq!(
+            field_opts.choices,
+            Some(FieldChoices::Values(vec![
+                "draft".to_string(),
+                "published".to_string()
+            ]))
+        );
+    }
+
+    #[test]
+    fn field_opts_choices_invalid() {
+        let input: syn::Field = parse_quote! {
+            #[model(choices = [])]
+            status: Choice<Status>
+        };
+        assert!(FieldOpts::from_field(&input).is_err());
+
+        let input: syn::Field = parse_quote! {
+            #[model(choices = [1, 2])]
+            status: Choice<Status>
+        };
+        assert!(FieldOpts::from_field(&input).is_err());
+    }
+
     #[test]
     fn field_opts_raw_name() {
         let input: syn::Field = parse_quote! {
@@ -758,6 +1135,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn field_opts_decimal() {
+        let input: syn::Field = parse_quote! {
+            #[model(decimal(precision = 10, scale = 2))]
+            price: Decimal
+        };
+        let field_opts = FieldOpts::from_field(&input).unwrap();
+        let field = field_opts
+            .as_field(&SymbolResolver::new(vec![]), Some(&"Bar".to_string()))
+            .unwrap();
+
+        assert_eq!(
+            field.decimal,
+            Some(DecimalArgs {
+                precision: 10,
+                scale: 2
+            })
+        );
+    }
+
+    #[test]
+    fn field_opts_decimal_rejects_non_decimal_field() {
+        let input: syn::Field = parse_quote! {
+            #[model(decimal(precision = 10, scale = 2))]
+            price: f64
+        };
+        let field_opts = FieldOpts::from_field(&input).unwrap();
+        let err = field_opts
+            .as_field(&SymbolResolver::new(vec![]), Some(&"Bar".to_string()))
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "`#[model(decimal(...))]` can only be used on `Decimal` fields"
+        );
+    }
+
+    #[test]
+    fn field_opts_decimal_rejects_scale_greater_than_precision() {
+        let input: syn::Field = parse_quote! {
+            #[model(decimal(precision = 2, scale = 4))]
+            price: Option<Decimal>
+        };
+        let field_opts = FieldOpts::from_field(&input).unwrap();
+        let err = field_opts
+            .as_field(&SymbolResolver::new(vec![]), Some(&"Bar".to_string()))
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "the decimal precision must be greater than zero and not smaller than the scale"
+        );
+    }
+
     #[test]
     fn field_opts_foreign_key_rejects_set_none_on_required_foreign_key() {
         let input: syn::Field = parse_quote! {
@@ -804,6 +1235,120 @@ mod tests {
         );
     }
 
+    #[test]
+    fn field_opts_default_constant() {
+        let input: syn::Field = parse_quote! {
+            #[model(default = -5)]
+            foo: i64
+        };
+        let field_opts = FieldOpts::from_field(&input).unwrap();
+        let field = field_opts
+            .as_field(&SymbolResolver::new(vec![]), Some(&"Bar".to_string()))
+            .unwrap();
+
+        let default = field.default.unwrap();
+        assert!(!default.nullable);
+        assert_eq!(
+            default.constant_value().unwrap().to_string(),
+            quote! { ::cot::db::migrations::DefaultValue::Int(-5) }.to_string()
+        );
+    }
+
+    #[test]
+    fn field_opts_default_string_literal() {
+        let input: syn::Field = parse_quote! {
+            #[model(default = "test")]
+            foo: String
+        };
+        let field_opts = FieldOpts::from_field(&input).unwrap();
+        let field = field_opts
+            .as_field(&SymbolResolver::new(vec![]), Some(&"Bar".to_string()))
+            .unwrap();
+
+        assert_eq!(field.default.unwrap().expr, parse_quote!("test"));
+    }
+
+    #[test]
+    fn field_opts_default_non_constant_option() {
+        let input: syn::Field = parse_quote! {
+            #[model(default = chrono::Utc::now())]
+            foo: Option<chrono::DateTime<chrono::Utc>>
+        };
+        let field_opts = FieldOpts::from_field(&input).unwrap();
+        let field = field_opts
+            .as_field(&SymbolResolver::new(vec![]), Some(&"Bar".to_string()))
+            .unwrap();
+
+        let default = field.default.unwrap();
+        assert!(default.nullable);
+        assert!(default.constant_value().is_none());
+    }
+
+    #[test]
+    fn field_opts_default_rejects_non_constant_required_field() {
+        let input: syn::Field = parse_quote! {
+            #[model(default = chrono::Utc::now())]
+            foo: chrono::DateTime<chrono::Utc>
+        };
+        let field_opts = FieldOpts::from_field(&input).unwrap();
+        let err = field_opts
+            .as_field(&SymbolResolver::new(vec![]), Some(&"Bar".to_string()))
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "non-constant default values can only be used on `Option<T>` fields"
+        );
+    }
+
+    #[test]
+    fn field_opts_default_rejects_primary_key() {
+        let input: syn::Field = parse_quote! {
+            #[model(primary_key, default = 1)]
+            id: i32
+        };
+        let field_opts = FieldOpts::from_field(&input).unwrap();
+        let err = field_opts
+            .as_field(&SymbolResolver::new(vec![]), Some(&"Bar".to_string()))
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "default values are not supported on primary key fields"
+        );
+    }
+
+    #[test]
+    fn field_default_constant_value() {
+        let constant_value = |expr: syn::Expr| {
+            FieldDefault {
+                expr,
+                nullable: false,
+            }
+            .constant_value()
+            .map(|tokens| tokens.to_string())
+        };
+
+        assert_eq!(
+            constant_value(parse_quote!(true)),
+            Some(quote! { ::cot::db::migrations::DefaultValue::Bool(true) }.to_string())
+        );
+        assert_eq!(
+            constant_value(parse_quote!(5u8)),
+            Some(quote! { ::cot::db::migrations::DefaultValue::Int(5) }.to_string())
+        );
+        assert_eq!(
+            constant_value(parse_quote!(-1.5)),
+            Some(quote! { ::cot::db::migrations::DefaultValue::Float(-1.5) }.to_string())
+        );
+        assert_eq!(
+            constant_value(parse_quote!("test")),
+            Some(quote! { ::cot::db::migrations::DefaultValue::String("test") }.to_string())
+        );
+        assert_eq!(constant_value(parse_quote!(-"test")), None);
+        assert_eq!(constant_value(parse_quote!(foo())), None);
+    }
+
     #[test]
     fn find_type_resolved() {
         let input: syn::Type =
@@ -830,6 +1375,10 @@ mod tests {
             unique: darling::util::Flag::default(),
             field_name: None,
             foreign_key: None,
+            default: None,
+            slugify_from: None,
+            choices: None,
+            decimal: None,
         };
 
         assert!(opts.find_type("my_crate::MyContainer", &resolver).is_some());
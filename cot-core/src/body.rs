@@ -34,6 +34,7 @@ use crate::{Error, Result};
 #[derive(Debug)]
 pub struct Body {
     pub(crate) inner: BodyInner,
+    trailers: Option<http::HeaderMap>,
 }
 
 pub(crate) enum BodyInner {
@@ -54,10 +55,50 @@ impl Debug for BodyInner {
     }
 }
 
+impl BodyInner {
+    fn poll_frame(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<std::result::Result<Frame<Bytes>, Error>>> {
+        use http_body::Body as _;
+
+        match *self {
+            BodyInner::Fixed(ref mut data) => {
+                if data.is_empty() {
+                    Poll::Ready(None)
+                } else {
+                    let data = std::mem::take(data);
+                    Poll::Ready(Some(Ok(Frame::data(data))))
+                }
+            }
+            BodyInner::Streaming(ref mut stream) => {
+                let stream = Pin::as_mut(stream.get_mut());
+                match stream.poll_next(cx) {
+                    Poll::Ready(Some(result)) => Poll::Ready(Some(result.map(Frame::data))),
+                    Poll::Ready(None) => Poll::Ready(None),
+                    Poll::Pending => Poll::Pending,
+                }
+            }
+            BodyInner::Axum(ref mut axum_body) => {
+                let axum_body = axum_body.get_mut();
+                Pin::new(axum_body)
+                    .poll_frame(cx)
+                    .map_err(|error| ReadRequestBody(Box::new(error)).into())
+            }
+            BodyInner::Wrapper(ref mut http_body) => Pin::new(http_body)
+                .poll_frame(cx)
+                .map_err(|error| ReadRequestBody(Box::new(error)).into()),
+        }
+    }
+}
+
 impl Body {
     #[must_use]
     const fn new(inner: BodyInner) -> Self {
-        Self { inner }
+        Self {
+            inner,
+            trailers: None,
+        }
     }
 
     /// Create an empty body.
@@ -88,6 +129,31 @@ impl Body {
         Self::new(BodyInner::Fixed(data.into()))
     }
 
+    /// Returns the contents of the body if it has been created with fixed
+    /// data, or [`None`] if the body is streamed.
+    ///
+    /// This allows inspecting the body (e.g. to compute a checksum) without
+    /// consuming it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::Body;
+    ///
+    /// let body = Body::fixed("Hello, world!");
+    /// assert_eq!(body.as_fixed().unwrap(), "Hello, world!");
+    ///
+    /// let body = Body::streaming(futures::stream::once(async { Ok("Hello, world!".into()) }));
+    /// assert!(body.as_fixed().is_none());
+    /// ```
+    #[must_use]
+    pub fn as_fixed(&self) -> Option<&Bytes> {
+        match &self.inner {
+            BodyInner::Fixed(data) => Some(data),
+            BodyInner::Streaming(_) | BodyInner::Axum(_) | BodyInner::Wrapper(_) => None,
+        }
+    }
+
     /// Create a body instance from a stream of data.
     ///
     /// # Examples
@@ -107,6 +173,57 @@ impl Body {
         Self::new(BodyInner::Streaming(SyncWrapper::new(Box::pin(stream))))
     }
 
+    /// Create a body instance from a stream of infallible data.
+    ///
+    /// This is a version of [`Self::streaming`] for streams that can't fail,
+    /// which saves wrapping each item in [`Ok`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::Body;
+    ///
+    /// let stream = futures::stream::iter(["Hello, ", "world!"]);
+    /// let body = Body::from_stream(stream);
+    /// ```
+    #[must_use]
+    pub fn from_stream<S, B>(stream: S) -> Self
+    where
+        S: Stream<Item = B> + Send + 'static,
+        B: Into<Bytes>,
+    {
+        use futures_util::StreamExt;
+
+        Self::streaming(stream.map(|data| Ok(data.into())))
+    }
+
+    /// Adds HTTP trailers to this body.
+    ///
+    /// The trailers are sent as a final frame after all the data of the body
+    /// has been sent. This is used by some protocols, such as gRPC-web, to
+    /// send metadata that is only known after the body has been generated.
+    ///
+    /// Note that HTTP/1.1 only supports trailers for chunked responses, and
+    /// clients have to opt in to receive them by sending the `TE: trailers`
+    /// request header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::Body;
+    /// use cot::http::HeaderMap;
+    ///
+    /// let mut trailers = HeaderMap::new();
+    /// trailers.insert("grpc-status", "0".parse().unwrap());
+    ///
+    /// let body = Body::fixed("Hello, world!").with_trailers(trailers);
+    /// ```
+    #[must_use]
+    pub fn with_trailers(mut self, trailers: http::HeaderMap) -> Self {
+        self.trailers = Some(trailers);
+        self
+    }
+
     /// Convert this [`Body`] instance into a byte array.
     ///
     /// This method reads the entire body into memory and returns it as a byte
@@ -169,6 +286,66 @@ impl Body {
             .map_err(ReadRequestBody)?)
     }
 
+    /// Convert this [`Body`] instance into a string.
+    ///
+    /// This method reads the entire body into memory, just like
+    /// [`Self::into_bytes`], and decodes it as UTF-8. Invalid UTF-8 results in
+    /// an error; see [`Self::into_string_lossy`] for a version that replaces
+    /// the invalid sequences instead.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if reading the body fails, or if the body
+    /// is not valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::Body;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> cot::Result<()> {
+    /// let body = Body::fixed("Hello, world!");
+    /// let string = body.into_string().await?;
+    /// assert_eq!(string, "Hello, world!");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn into_string(self) -> Result<String> {
+        let bytes = self.into_bytes().await?;
+
+        Ok(String::from_utf8(bytes.into()).map_err(InvalidUtf8Body)?)
+    }
+
+    /// Convert this [`Body`] instance into a string, replacing invalid UTF-8.
+    ///
+    /// This is a version of [`Self::into_string`] that replaces any invalid
+    /// UTF-8 sequences with [`U+FFFD REPLACEMENT
+    /// CHARACTER`](char::REPLACEMENT_CHARACTER) instead of returning an error.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if reading the body fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::Body;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> cot::Result<()> {
+    /// let body = Body::fixed(b"Hello, \xF0world!".as_slice());
+    /// let string = body.into_string_lossy().await?;
+    /// assert_eq!(string, "Hello, \u{FFFD}world!");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn into_string_lossy(self) -> Result<String> {
+        let bytes = self.into_bytes().await?;
+
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
     #[must_use]
     #[doc(hidden)]
     pub fn axum(inner: axum::body::Body) -> Self {
@@ -195,36 +372,22 @@ impl http_body::Body for Body {
         self: Pin<&mut Self>,
         cx: &mut Context<'_>,
     ) -> Poll<Option<std::result::Result<Frame<Self::Data>, Self::Error>>> {
-        match self.get_mut().inner {
-            BodyInner::Fixed(ref mut data) => {
-                if data.is_empty() {
-                    Poll::Ready(None)
-                } else {
-                    let data = std::mem::take(data);
-                    Poll::Ready(Some(Ok(Frame::data(data))))
-                }
-            }
-            BodyInner::Streaming(ref mut stream) => {
-                let stream = Pin::as_mut(stream.get_mut());
-                match stream.poll_next(cx) {
-                    Poll::Ready(Some(result)) => Poll::Ready(Some(result.map(Frame::data))),
-                    Poll::Ready(None) => Poll::Ready(None),
-                    Poll::Pending => Poll::Pending,
-                }
-            }
-            BodyInner::Axum(ref mut axum_body) => {
-                let axum_body = axum_body.get_mut();
-                Pin::new(axum_body)
-                    .poll_frame(cx)
-                    .map_err(|error| ReadRequestBody(Box::new(error)).into())
-            }
-            BodyInner::Wrapper(ref mut http_body) => Pin::new(http_body)
-                .poll_frame(cx)
-                .map_err(|error| ReadRequestBody(Box::new(error)).into()),
+        let this = self.get_mut();
+        match this.inner.poll_frame(cx) {
+            Poll::Ready(None) => Poll::Ready(
+                this.trailers
+                    .take()
+                    .map(|trailers| Ok(Frame::trailers(trailers))),
+            ),
+            poll => poll,
         }
     }
 
     fn is_end_stream(&self) -> bool {
+        if self.trailers.is_some() {
+            return false;
+        }
+
         match &self.inner {
             BodyInner::Fixed(data) => data.is_empty(),
             BodyInner::Streaming(_) | BodyInner::Axum(_) => false,
@@ -264,6 +427,11 @@ body_from_impl!(Bytes);
 struct ReadRequestBody(#[source] Box<dyn StdError + Send + Sync>);
 impl_into_cot_error!(ReadRequestBody, BAD_REQUEST);
 
+#[derive(Debug, thiserror::Error)]
+#[error("body is not valid UTF-8: {0}")]
+struct InvalidUtf8Body(#[source] std::string::FromUtf8Error);
+impl_into_cot_error!(InvalidUtf8Body, BAD_REQUEST);
+
 #[cfg(test)]
 mod tests {
     use futures::stream;
@@ -292,6 +460,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn body_as_fixed() {
+        assert_eq!(Body::fixed("Hello").as_fixed(), Some(&Bytes::from("Hello")));
+        assert_eq!(Body::empty().as_fixed(), Some(&Bytes::new()));
+
+        let stream = stream::once(async { Ok(Bytes::from("Hello")) });
+        assert_eq!(Body::streaming(stream).as_fixed(), None);
+    }
+
     #[cot::test]
     async fn body_streaming() {
         let stream = stream::once(async { Ok(Bytes::from("Hello, world!")) });
@@ -341,6 +518,62 @@ mod tests {
         }
     }
 
+    #[cot::test]
+    async fn http_body_poll_frame_from_stream() {
+        let mut body = Body::from_stream(stream::iter([b"Hello, ".to_vec(), b"world!".to_vec()]));
+        let mut cx = Context::from_waker(futures::task::noop_waker_ref());
+
+        for expected in ["Hello, ", "world!"] {
+            match Pin::new(&mut body).poll_frame(&mut cx) {
+                Poll::Ready(Some(Ok(frame))) => {
+                    assert_eq!(frame.into_data().unwrap(), Bytes::from(expected));
+                }
+                _ => panic!("Body::from_stream should return the stream items in poll_frame"),
+            }
+        }
+
+        match Pin::new(&mut body).poll_frame(&mut cx) {
+            Poll::Ready(None) => {} // End of stream
+            _ => panic!("Body::from_stream should return None after the stream is exhausted"),
+        }
+    }
+
+    #[cot::test]
+    async fn http_body_poll_frame_trailers() {
+        let mut trailers = http::HeaderMap::new();
+        trailers.insert("grpc-status", http::HeaderValue::from_static("0"));
+        let mut body =
+            Body::from_stream(stream::iter(["Hello, ", "world!"])).with_trailers(trailers.clone());
+        let mut cx = Context::from_waker(futures::task::noop_waker_ref());
+
+        for expected in ["Hello, ", "world!"] {
+            match Pin::new(&mut body).poll_frame(&mut cx) {
+                Poll::Ready(Some(Ok(frame))) => {
+                    assert_eq!(frame.into_data().unwrap(), Bytes::from(expected));
+                }
+                _ => panic!("Body should return the data frames first"),
+            }
+        }
+
+        match Pin::new(&mut body).poll_frame(&mut cx) {
+            Poll::Ready(Some(Ok(frame))) => {
+                assert_eq!(frame.into_trailers().unwrap(), trailers);
+            }
+            _ => panic!("Body should return the trailers after the data frames"),
+        }
+
+        match Pin::new(&mut body).poll_frame(&mut cx) {
+            Poll::Ready(None) => {} // End of stream
+            _ => panic!("Body should return None after the trailers"),
+        }
+    }
+
+    #[test]
+    fn http_body_is_end_stream_trailers() {
+        let body = Body::empty().with_trailers(http::HeaderMap::new());
+        assert!(!body.is_end_stream());
+    }
+
     #[test]
     fn http_body_is_end_stream() {
         let body = Body::empty();
@@ -359,4 +592,40 @@ mod tests {
         let body = Body::fixed(content);
         assert_eq!(body.size_hint().exact(), Some(content.len() as u64));
     }
+
+    #[cot::test]
+    async fn body_into_string() {
+        let body = Body::fixed("Zażółć gęślą jaźń");
+
+        assert_eq!(body.into_string().await.unwrap(), "Zażółć gęślą jaźń");
+    }
+
+    #[cot::test]
+    async fn body_into_string_streaming() {
+        let body = Body::streaming(stream::iter([
+            Ok(Bytes::from_static(b"Hello, \xC5")),
+            Ok(Bytes::from_static(b"\xBCworld!")),
+        ]));
+
+        assert_eq!(body.into_string().await.unwrap(), "Hello, żworld!");
+    }
+
+    #[cot::test]
+    async fn body_into_string_invalid_utf8() {
+        let body = Body::fixed(b"Hello, \xF0world!".as_slice());
+
+        let error = body.into_string().await.unwrap_err();
+
+        assert!(error.to_string().contains("not valid UTF-8"));
+    }
+
+    #[cot::test]
+    async fn body_into_string_lossy() {
+        let body = Body::fixed(b"Hello, \xF0world!".as_slice());
+
+        assert_eq!(
+            body.into_string_lossy().await.unwrap(),
+            "Hello, \u{FFFD}world!"
+        );
+    }
 }
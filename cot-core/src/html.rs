@@ -131,18 +131,43 @@ enum HtmlNode {
     Tag(HtmlTag),
     /// A text node containing plain text content.
     Text(HtmlText),
+    /// Pre-rendered HTML content that is output as-is.
+    Raw(Html),
 }
 
 impl HtmlNode {
+    fn is_inline(&self) -> bool {
+        match self {
+            HtmlNode::Tag(tag) => tag.is_inline(),
+            HtmlNode::Text(_) | HtmlNode::Raw(_) => true,
+        }
+    }
+
     #[must_use]
     fn render(&self) -> Html {
         match self {
             HtmlNode::Tag(tag) => tag.render(),
             HtmlNode::Text(text) => text.render(),
+            HtmlNode::Raw(html) => html.clone(),
         }
     }
 }
 
+const FAIL_MSG: &str = "Failed to write HTML tag";
+
+/// The indentation used by [`HtmlTag::render_pretty`] for each nesting level.
+const PRETTY_INDENT: &str = "  ";
+
+/// Elements that are kept on a single line by [`HtmlTag::render_pretty`].
+const INLINE_ELEMENTS: &[&str] = &[
+    "a", "abbr", "b", "bdi", "bdo", "br", "button", "cite", "code", "data", "dfn", "em", "i",
+    "img", "input", "kbd", "label", "mark", "q", "s", "samp", "small", "span", "strong", "sub",
+    "sup", "time", "u", "var", "wbr",
+];
+
+/// Elements whose content is not reformatted by [`HtmlTag::render_pretty`].
+const WHITESPACE_SENSITIVE_ELEMENTS: &[&str] = &["pre", "textarea", "script", "style"];
+
 /// A helper struct for rendering HTML tags with support for nested content.
 ///
 /// This struct is used to build HTML tags with attributes, boolean attributes,
@@ -269,6 +294,137 @@ impl HtmlTag {
         self
     }
 
+    /// Adds a `data-*` attribute to the HTML tag.
+    ///
+    /// This is equivalent to calling [`attr`](Self::attr) with the `data-`
+    /// prefix prepended to `name`; the value is escaped.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the attribute already exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::html::HtmlTag;
+    ///
+    /// let mut tag = HtmlTag::new("div");
+    /// tag.data("user-id", "5");
+    /// assert_eq!(tag.render().as_str(), "<div data-user-id=\"5\"/>");
+    /// ```
+    pub fn data<V: Into<String>>(&mut self, name: &str, value: V) -> &mut Self {
+        self.attr(format!("data-{name}"), value)
+    }
+
+    /// Adds an `aria-*` attribute to the HTML tag.
+    ///
+    /// This is equivalent to calling [`attr`](Self::attr) with the `aria-`
+    /// prefix prepended to `name`; the value is escaped.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the attribute already exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::html::HtmlTag;
+    ///
+    /// let mut tag = HtmlTag::new("button");
+    /// tag.aria("label", "Close");
+    /// assert_eq!(tag.render().as_str(), "<button aria-label=\"Close\"/>");
+    /// ```
+    pub fn aria<V: Into<String>>(&mut self, name: &str, value: V) -> &mut Self {
+        self.attr(format!("aria-{name}"), value)
+    }
+
+    /// Adds a class to the `class` attribute of the HTML tag.
+    ///
+    /// Unlike setting the `class` attribute with [`attr`](Self::attr), this
+    /// can be called multiple times: the classes are merged into a single
+    /// space-separated `class` attribute. Adding a class that is already
+    /// present has no effect.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::html::HtmlTag;
+    ///
+    /// let mut tag = HtmlTag::new("div");
+    /// tag.add_class("container").add_class("active");
+    /// assert_eq!(tag.render().as_str(), "<div class=\"container active\"/>");
+    /// ```
+    pub fn add_class(&mut self, class: &str) -> &mut Self {
+        if self.has_class(class) {
+            return self;
+        }
+
+        if let Some((_, value)) = self.class_attribute_mut() {
+            if !value.trim().is_empty() {
+                value.push(' ');
+            }
+            value.push_str(class);
+        } else {
+            self.attributes
+                .push(("class".to_string(), class.to_string()));
+        }
+        self
+    }
+
+    /// Removes a class from the `class` attribute of the HTML tag.
+    ///
+    /// If the last class is removed, the `class` attribute is removed
+    /// altogether. Removing a class that is not present has no effect.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::html::HtmlTag;
+    ///
+    /// let mut tag = HtmlTag::new("div");
+    /// tag.add_class("container").add_class("active");
+    /// tag.remove_class("active");
+    /// assert_eq!(tag.render().as_str(), "<div class=\"container\"/>");
+    /// ```
+    pub fn remove_class(&mut self, class: &str) -> &mut Self {
+        if let Some((_, value)) = self.class_attribute_mut() {
+            *value = value
+                .split_whitespace()
+                .filter(|existing| *existing != class)
+                .collect::<Vec<_>>()
+                .join(" ");
+            if value.is_empty() {
+                self.attributes.retain(|(key, _)| key != "class");
+            }
+        }
+        self
+    }
+
+    /// Returns whether the `class` attribute of the HTML tag contains the
+    /// given class.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::html::HtmlTag;
+    ///
+    /// let mut tag = HtmlTag::new("div");
+    /// tag.add_class("container");
+    /// assert!(tag.has_class("container"));
+    /// assert!(!tag.has_class("active"));
+    /// ```
+    #[must_use]
+    pub fn has_class(&self, class: &str) -> bool {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == "class")
+            .is_some_and(|(_, value)| value.split_whitespace().any(|existing| existing == class))
+    }
+
+    fn class_attribute_mut(&mut self) -> Option<&mut (String, String)> {
+        self.attributes.iter_mut().find(|(key, _)| key == "class")
+    }
+
     fn push_child(&mut self, node: HtmlNode) -> &mut Self {
         self.children.push(node);
         self
@@ -305,6 +461,24 @@ impl HtmlTag {
         self.push_child(HtmlNode::Tag(tag.into()))
     }
 
+    /// Adds pre-rendered HTML as a child to this tag.
+    ///
+    /// Unlike [`push_str`](Self::push_str), the content is not escaped, so
+    /// it should only be used with HTML that comes from a trusted source.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::html::{Html, HtmlTag};
+    ///
+    /// let mut div = HtmlTag::new("div");
+    /// div.push_html(Html::new("<b>bold</b>"));
+    /// assert_eq!(div.render().as_str(), "<div><b>bold</b></div>");
+    /// ```
+    pub fn push_html<T: Into<Html>>(&mut self, html: T) -> &mut Self {
+        self.push_child(HtmlNode::Raw(html.into()))
+    }
+
     /// Renders the HTML tag.
     ///
     /// # Panics
@@ -321,21 +495,8 @@ impl HtmlTag {
     /// ```
     #[must_use]
     pub fn render(&self) -> Html {
-        const FAIL_MSG: &str = "Failed to write HTML tag";
-
         let mut result = String::new();
-        write!(&mut result, "<{}", self.tag).expect(FAIL_MSG);
-
-        for (key, value) in &self.attributes {
-            write!(&mut result, " {key}=\"").expect(FAIL_MSG);
-            askama::filters::Html
-                .write_escaped_str(&mut result, value)
-                .expect(FAIL_MSG);
-            write!(&mut result, "\"").expect(FAIL_MSG);
-        }
-        for key in &self.boolean_attributes {
-            write!(&mut result, " {key}").expect(FAIL_MSG);
-        }
+        self.write_start_tag(&mut result);
 
         if self.children.is_empty() {
             write!(&mut result, "/>").expect(FAIL_MSG);
@@ -351,6 +512,102 @@ impl HtmlTag {
 
         result.into()
     }
+
+    /// Renders the HTML tag with indentation, for debugging and snapshot
+    /// tests.
+    ///
+    /// Block elements are put on their own lines and their children are
+    /// indented by two spaces. Inline elements (such as `<span>` or `<a>`)
+    /// and text are kept on a single line, with the leading and trailing
+    /// whitespace trimmed. Elements whose content is whitespace-sensitive
+    /// (`<pre>`, `<textarea>`, `<script>`, and `<style>`) are rendered
+    /// as-is, just like with [`render`](Self::render).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [`String`] writer fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::html::HtmlTag;
+    ///
+    /// let mut ul = HtmlTag::new("ul");
+    /// let mut li = HtmlTag::new("li");
+    /// li.push_str("Hello, ");
+    /// let mut strong = HtmlTag::new("strong");
+    /// strong.push_str("world!");
+    /// li.push_tag(strong);
+    /// ul.push_tag(li);
+    ///
+    /// assert_eq!(
+    ///     ul.render_pretty().as_str(),
+    ///     "<ul>\n  <li>Hello, <strong>world!</strong></li>\n</ul>"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn render_pretty(&self) -> Html {
+        let mut result = String::new();
+        self.write_pretty(&mut result, 0);
+        result.truncate(result.trim_end().len());
+        result.into()
+    }
+
+    fn write_start_tag(&self, result: &mut String) {
+        write!(result, "<{}", self.tag).expect(FAIL_MSG);
+
+        for (key, value) in &self.attributes {
+            write!(result, " {key}=\"").expect(FAIL_MSG);
+            askama::filters::Html
+                .write_escaped_str(&mut *result, value)
+                .expect(FAIL_MSG);
+            write!(result, "\"").expect(FAIL_MSG);
+        }
+        for key in &self.boolean_attributes {
+            write!(result, " {key}").expect(FAIL_MSG);
+        }
+    }
+
+    fn write_pretty(&self, result: &mut String, depth: usize) {
+        let indent = PRETTY_INDENT.repeat(depth);
+
+        if WHITESPACE_SENSITIVE_ELEMENTS.contains(&self.tag.as_str())
+            || self.children.iter().all(HtmlNode::is_inline)
+        {
+            writeln!(result, "{indent}{}", self.render().as_str()).expect(FAIL_MSG);
+            return;
+        }
+
+        write!(result, "{indent}").expect(FAIL_MSG);
+        self.write_start_tag(result);
+        writeln!(result, ">").expect(FAIL_MSG);
+
+        let child_indent = PRETTY_INDENT.repeat(depth + 1);
+        let mut inline_content = String::new();
+        let flush_inline_content = |result: &mut String, inline_content: &mut String| {
+            let content = inline_content.trim();
+            if !content.is_empty() {
+                writeln!(result, "{child_indent}{content}").expect(FAIL_MSG);
+            }
+            inline_content.clear();
+        };
+        for child in &self.children {
+            match child {
+                HtmlNode::Tag(tag) if !child.is_inline() => {
+                    flush_inline_content(result, &mut inline_content);
+                    tag.write_pretty(result, depth + 1);
+                }
+                _ => inline_content.push_str(child.render().as_str()),
+            }
+        }
+        flush_inline_content(result, &mut inline_content);
+
+        writeln!(result, "{indent}</{}>", self.tag).expect(FAIL_MSG);
+    }
+
+    fn is_inline(&self) -> bool {
+        INLINE_ELEMENTS.contains(&self.tag.as_str())
+    }
 }
 
 impl From<&HtmlTag> for HtmlTag {
@@ -365,6 +622,339 @@ impl From<&mut HtmlTag> for HtmlTag {
     }
 }
 
+/// A builder for HTML tables rendering a list of rows.
+///
+/// Each column is defined by its header label and a closure returning the
+/// content of the column's cell for a given row. The text returned by the
+/// closures passed to [`column`](Self::column) is escaped, while
+/// [`html_column`](Self::html_column) can be used for cells that contain
+/// HTML, such as links.
+///
+/// # Examples
+///
+/// ```
+/// use cot::html::{Html, Table};
+///
+/// struct User {
+///     id: i32,
+///     name: String,
+/// }
+///
+/// let users = [User {
+///     id: 1,
+///     name: "<Alice>".to_owned(),
+/// }];
+///
+/// let mut table = Table::new();
+/// table
+///     .column("Name", |user: &User| user.name.clone())
+///     .html_column("Actions", |user: &User| {
+///         Html::new(format!("<a href=\"/users/{}\">Edit</a>", user.id))
+///     });
+///
+/// assert_eq!(
+///     table.render(&users).as_str(),
+///     "<table><thead><tr><th>Name</th><th>Actions</th></tr></thead>\
+///     <tbody><tr><td>&#60;Alice&#62;</td><td><a href=\"/users/1\">Edit</a></td></tr></tbody>\
+///     </table>"
+/// );
+/// ```
+#[derive(derive_more::Debug)]
+pub struct Table<'a, T> {
+    columns: Vec<TableColumn<'a, T>>,
+}
+
+#[derive(derive_more::Debug)]
+struct TableColumn<'a, T> {
+    header: String,
+    #[debug("..")]
+    cell: Box<dyn Fn(&T) -> HtmlNode + 'a>,
+}
+
+impl<'a, T> Table<'a, T> {
+    /// Creates a new table with no columns.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::html::Table;
+    ///
+    /// let table = Table::<()>::new();
+    /// assert_eq!(
+    ///     table.render(&[]).as_str(),
+    ///     "<table><thead><tr></tr></thead><tbody></tbody></table>"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            columns: Vec::new(),
+        }
+    }
+
+    /// Adds a column containing text. The text is escaped when rendered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::html::Table;
+    ///
+    /// let mut table = Table::new();
+    /// table.column("Number", |number: &i32| number.to_string());
+    /// assert_eq!(
+    ///     table.render(&[1]).as_str(),
+    ///     "<table><thead><tr><th>Number</th></tr></thead>\
+    ///     <tbody><tr><td>1</td></tr></tbody></table>"
+    /// );
+    /// ```
+    pub fn column<H, F, V>(&mut self, header: H, cell: F) -> &mut Self
+    where
+        H: Into<String>,
+        F: Fn(&T) -> V + 'a,
+        V: Into<String>,
+    {
+        self.columns.push(TableColumn {
+            header: header.into(),
+            cell: Box::new(move |row| HtmlNode::Text(HtmlText::new(cell(row)))),
+        });
+        self
+    }
+
+    /// Adds a column containing HTML. Unlike with [`column`](Self::column),
+    /// the content is not escaped, so it should only be used with HTML that
+    /// comes from a trusted source.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::html::{Html, Table};
+    ///
+    /// let mut table = Table::new();
+    /// table.html_column("Number", |number: &i32| Html::new(format!("<b>{number}</b>")));
+    /// assert_eq!(
+    ///     table.render(&[1]).as_str(),
+    ///     "<table><thead><tr><th>Number</th></tr></thead>\
+    ///     <tbody><tr><td><b>1</b></td></tr></tbody></table>"
+    /// );
+    /// ```
+    pub fn html_column<H, F>(&mut self, header: H, cell: F) -> &mut Self
+    where
+        H: Into<String>,
+        F: Fn(&T) -> Html + 'a,
+    {
+        self.columns.push(TableColumn {
+            header: header.into(),
+            cell: Box::new(move |row| HtmlNode::Raw(cell(row))),
+        });
+        self
+    }
+
+    /// Builds the `<table>` tag for the given rows.
+    ///
+    /// This is useful to customize the table further, for instance to add
+    /// attributes to it, before rendering it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::html::Table;
+    ///
+    /// let mut table = Table::new();
+    /// table.column("Number", |number: &i32| number.to_string());
+    ///
+    /// let mut tag = table.to_tag(&[1]);
+    /// tag.add_class("table");
+    /// assert_eq!(
+    ///     tag.render().as_str(),
+    ///     "<table class=\"table\"><thead><tr><th>Number</th></tr></thead>\
+    ///     <tbody><tr><td>1</td></tr></tbody></table>"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn to_tag(&self, rows: &[T]) -> HtmlTag {
+        let mut header_row = table_tag("tr");
+        for column in &self.columns {
+            let mut th = table_tag("th");
+            th.push_str(column.header.as_str());
+            header_row.push_tag(th);
+        }
+
+        let mut tbody = table_tag("tbody");
+        for row in rows {
+            let mut tr = table_tag("tr");
+            for column in &self.columns {
+                let mut td = table_tag("td");
+                td.push_child((column.cell)(row));
+                tr.push_tag(td);
+            }
+            tbody.push_tag(tr);
+        }
+
+        let mut thead = HtmlTag::new("thead");
+        thead.push_tag(header_row);
+        let mut table = HtmlTag::new("table");
+        table.push_tag(thead).push_tag(tbody);
+        table
+    }
+
+    /// Renders the table for the given rows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::html::Table;
+    ///
+    /// let mut table = Table::new();
+    /// table.column("Number", |number: &i32| number.to_string());
+    /// assert_eq!(
+    ///     table.render(&[1, 2]).as_str(),
+    ///     "<table><thead><tr><th>Number</th></tr></thead>\
+    ///     <tbody><tr><td>1</td></tr><tr><td>2</td></tr></tbody></table>"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn render(&self, rows: &[T]) -> Html {
+        self.to_tag(rows).render()
+    }
+}
+
+/// Creates a table element that is never rendered as self-closing (which is
+/// what [`HtmlTag::render`] does for tags without children, and which is not
+/// valid for the table elements).
+fn table_tag(tag: &str) -> HtmlTag {
+    let mut tag = HtmlTag::new(tag);
+    tag.push_str("");
+    tag
+}
+
+impl<T> Default for Table<'_, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The number of pages shown on each side of the current page by
+/// [`pagination`].
+const PAGINATION_WINDOW: u32 = 2;
+
+/// Renders pagination links for a paginated list.
+///
+/// The links are rendered as a `<nav>` element containing a list with a link
+/// to the previous page, links to the individual pages, and a link to the
+/// next page. The first and the last page, as well as the pages close to the
+/// current one, are always shown; the other pages are replaced with an
+/// ellipsis. The current page is marked with `aria-current="page"`, and the
+/// previous and next links have `rel="prev"` and `rel="next"` attributes.
+///
+/// The pages are numbered from 1, and `url_for` is called to get the URL of
+/// each linked page. If there is only one page (or none), nothing is
+/// rendered.
+///
+/// # Examples
+///
+/// ```
+/// use cot::html::pagination;
+///
+/// let html = pagination(2, 3, |page| format!("/items?page={page}"));
+/// assert_eq!(
+///     html.as_str(),
+///     "<nav aria-label=\"Pagination\"><ul class=\"pagination\">\
+///     <li><a href=\"/items?page=1\" rel=\"prev\">Previous</a></li>\
+///     <li><a href=\"/items?page=1\">1</a></li>\
+///     <li><a href=\"/items?page=2\" aria-current=\"page\">2</a></li>\
+///     <li><a href=\"/items?page=3\">3</a></li>\
+///     <li><a href=\"/items?page=3\" rel=\"next\">Next</a></li>\
+///     </ul></nav>"
+/// );
+/// ```
+#[must_use]
+pub fn pagination(current: u32, total_pages: u32, url_for: impl Fn(u32) -> String) -> Html {
+    if total_pages <= 1 {
+        return Html::default();
+    }
+    let current = current.clamp(1, total_pages);
+
+    let mut list = HtmlTag::new("ul");
+    list.add_class("pagination");
+
+    let adjacent_link = |page: Option<u32>, rel: &str, label: &str| {
+        let mut item = HtmlTag::new("li");
+        if let Some(page) = page {
+            let mut link = HtmlTag::new("a");
+            link.attr("href", url_for(page)).attr("rel", rel);
+            link.push_str(label);
+            item.push_tag(link);
+        } else {
+            let mut span = HtmlTag::new("span");
+            span.aria("disabled", "true");
+            span.push_str(label);
+            item.push_tag(span);
+        }
+        item
+    };
+
+    list.push_tag(adjacent_link(
+        (current > 1).then(|| current - 1),
+        "prev",
+        "Previous",
+    ));
+    for page in pagination_pages(current, total_pages) {
+        let mut item = HtmlTag::new("li");
+        if let Some(page) = page {
+            let mut link = HtmlTag::new("a");
+            link.attr("href", url_for(page));
+            if page == current {
+                link.aria("current", "page");
+            }
+            link.push_str(page.to_string());
+            item.push_tag(link);
+        } else {
+            let mut ellipsis = HtmlTag::new("span");
+            ellipsis.aria("hidden", "true");
+            ellipsis.push_str("\u{2026}");
+            item.push_tag(ellipsis);
+        }
+        list.push_tag(item);
+    }
+    list.push_tag(adjacent_link(
+        (current < total_pages).then(|| current + 1),
+        "next",
+        "Next",
+    ));
+
+    let mut nav = HtmlTag::new("nav");
+    nav.aria("label", "Pagination");
+    nav.push_tag(list);
+    nav.render()
+}
+
+/// Returns the page numbers shown by [`pagination`], with `None` standing for
+/// an ellipsis. An ellipsis is only used when it replaces at least two pages.
+fn pagination_pages(current: u32, total_pages: u32) -> Vec<Option<u32>> {
+    let start = current.saturating_sub(PAGINATION_WINDOW).max(1);
+    let end = current.saturating_add(PAGINATION_WINDOW).min(total_pages);
+
+    let mut pages = Vec::new();
+    if start > 1 {
+        pages.push(Some(1));
+        if start > 3 {
+            pages.push(None);
+        } else {
+            pages.extend((2..start).map(Some));
+        }
+    }
+    pages.extend((start..=end).map(Some));
+    if end < total_pages {
+        if total_pages - end > 2 {
+            pages.push(None);
+        } else {
+            pages.extend((end + 1..total_pages).map(Some));
+        }
+        pages.push(Some(total_pages));
+    }
+    pages
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct HtmlText {
     content: String,
@@ -544,6 +1134,322 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_html_tag_push_html_is_not_escaped() {
+        let mut div = HtmlTag::new("div");
+        div.push_str("<i>");
+        div.push_html(Html::new("<b>&amp;</b>"));
+        assert_eq!(div.render().as_str(), "<div>&#60;i&#62;<b>&amp;</b></div>");
+    }
+
+    #[test]
+    fn test_html_tag_data_attribute() {
+        let mut div = HtmlTag::new("div");
+        div.data("user-id", "5");
+        assert_eq!(div.render().as_str(), "<div data-user-id=\"5\"/>");
+    }
+
+    #[test]
+    fn test_html_tag_aria_attribute_escaping() {
+        let mut button = HtmlTag::new("button");
+        button.aria("label", "Close \"dialog\"");
+        assert_eq!(
+            button.render().as_str(),
+            "<button aria-label=\"Close &#34;dialog&#34;\"/>"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Attribute already exists: data-id")]
+    fn test_html_tag_data_attribute_duplicate() {
+        let mut div = HtmlTag::new("div");
+        div.attr("data-id", "1");
+        div.data("id", "2");
+    }
+
+    #[test]
+    fn test_html_tag_add_remove_class() {
+        let mut div = HtmlTag::new("div");
+        div.add_class("container").add_class("active");
+        assert_eq!(div.render().as_str(), "<div class=\"container active\"/>");
+
+        div.remove_class("container");
+        assert_eq!(div.render().as_str(), "<div class=\"active\"/>");
+        assert!(div.has_class("active"));
+        assert!(!div.has_class("container"));
+    }
+
+    #[test]
+    fn test_html_tag_add_class_merges_with_attr() {
+        let mut div = HtmlTag::new("div");
+        div.attr("class", "a b").attr("id", "x");
+        div.add_class("b").add_class("c");
+        assert_eq!(div.render().as_str(), "<div class=\"a b c\" id=\"x\"/>");
+    }
+
+    #[test]
+    fn test_html_tag_remove_last_class() {
+        let mut div = HtmlTag::new("div");
+        div.add_class("only");
+        div.remove_class("only").remove_class("missing");
+        assert_eq!(div.render().as_str(), "<div/>");
+        assert!(!div.has_class("only"));
+    }
+
+    #[test]
+    fn test_html_tag_render_pretty_nested() {
+        let mut html = HtmlTag::new("html");
+        let mut body = HtmlTag::new("body");
+        body.add_class("page");
+
+        let mut header = HtmlTag::new("h1");
+        header.push_str("Title");
+        body.push_tag(header);
+
+        let mut section = HtmlTag::new("section");
+        section.push_str("  Text with ");
+        let mut link = HtmlTag::new("a");
+        link.attr("href", "/").push_str("a link");
+        section.push_tag(link);
+        let mut list = HtmlTag::new("ul");
+        for item in ["one", "two"] {
+            let mut li = HtmlTag::new("li");
+            li.push_str(item);
+            list.push_tag(li);
+        }
+        section.push_tag(list);
+        section.push_str(" trailing text ");
+        section.push_tag(HtmlTag::new("hr"));
+        body.push_tag(section);
+
+        let mut pre = HtmlTag::new("pre");
+        pre.push_str("  keep\n");
+        let mut code = HtmlTag::new("div");
+        code.push_str("  as is");
+        pre.push_tag(code);
+        body.push_tag(pre);
+
+        html.push_tag(body);
+
+        assert_eq!(
+            html.render_pretty().as_str(),
+            "<html>
+  <body class=\"page\">
+    <h1>Title</h1>
+    <section>
+      Text with <a href=\"/\">a link</a>
+      <ul>
+        <li>one</li>
+        <li>two</li>
+      </ul>
+      trailing text
+      <hr/>
+    </section>
+    <pre>  keep
+<div>  as is</div></pre>
+  </body>
+</html>"
+        );
+    }
+
+    #[test]
+    fn test_html_tag_render_pretty_inline_only() {
+        let mut span = HtmlTag::new("span");
+        span.push_str("inline");
+        assert_eq!(span.render_pretty(), span.render());
+
+        let mut p = HtmlTag::new("p");
+        p.push_str("Hello, ");
+        p.push_tag(span);
+        assert_eq!(
+            p.render_pretty().as_str(),
+            "<p>Hello, <span>inline</span></p>"
+        );
+    }
+
+    struct TableRow {
+        name: &'static str,
+        count: u32,
+    }
+
+    #[test]
+    fn test_table_escapes_text_cells() {
+        let rows = [
+            TableRow {
+                name: "<script>",
+                count: 1,
+            },
+            TableRow {
+                name: "a & b",
+                count: 2,
+            },
+        ];
+        let mut table = Table::new();
+        table
+            .column("Name & title", |row: &TableRow| row.name)
+            .column("Count", |row: &TableRow| row.count.to_string());
+
+        assert_eq!(
+            table.render(&rows).as_str(),
+            "<table><thead><tr><th>Name &#38; title</th><th>Count</th></tr></thead><tbody>\
+            <tr><td>&#60;script&#62;</td><td>1</td></tr>\
+            <tr><td>a &#38; b</td><td>2</td></tr>\
+            </tbody></table>"
+        );
+    }
+
+    #[test]
+    fn test_table_html_cells() {
+        let rows = [TableRow {
+            name: "<b>bold</b>",
+            count: 1,
+        }];
+        let mut table = Table::new();
+        table.html_column("Name", |row: &TableRow| Html::new(row.name));
+
+        assert_eq!(
+            table.render(&rows).as_str(),
+            "<table><thead><tr><th>Name</th></tr></thead>\
+            <tbody><tr><td><b>bold</b></td></tr></tbody></table>"
+        );
+    }
+
+    #[test]
+    fn test_table_empty_cells_and_rows() {
+        let mut table = Table::new();
+        table.column("Name", |_row: &TableRow| "");
+
+        assert_eq!(
+            table.render(&[]).as_str(),
+            "<table><thead><tr><th>Name</th></tr></thead><tbody></tbody></table>"
+        );
+        assert_eq!(
+            table
+                .render(&[TableRow {
+                    name: "unused",
+                    count: 0
+                }])
+                .as_str(),
+            "<table><thead><tr><th>Name</th></tr></thead><tbody><tr><td></td></tr></tbody></table>"
+        );
+    }
+
+    #[test]
+    fn test_table_borrowing_closure() {
+        let suffix = String::from("!");
+        let mut table = Table::new();
+        table.column("Name", |row: &TableRow| format!("{}{suffix}", row.name));
+
+        let tag = table.to_tag(&[TableRow {
+            name: "a",
+            count: 0,
+        }]);
+        assert!(tag.render().as_str().contains("<td>a!</td>"));
+    }
+
+    #[test]
+    fn test_pagination_single_page() {
+        assert_eq!(
+            pagination(1, 1, |page| format!("?page={page}")),
+            Html::default()
+        );
+        assert_eq!(
+            pagination(1, 0, |page| format!("?page={page}")),
+            Html::default()
+        );
+    }
+
+    #[test]
+    fn test_pagination_first_page() {
+        let html = pagination(1, 2, |page| format!("?page={page}"));
+        assert_eq!(
+            html.as_str(),
+            "<nav aria-label=\"Pagination\"><ul class=\"pagination\">\
+            <li><span aria-disabled=\"true\">Previous</span></li>\
+            <li><a href=\"?page=1\" aria-current=\"page\">1</a></li>\
+            <li><a href=\"?page=2\">2</a></li>\
+            <li><a href=\"?page=2\" rel=\"next\">Next</a></li>\
+            </ul></nav>"
+        );
+    }
+
+    #[test]
+    fn test_pagination_last_page() {
+        let html = pagination(2, 2, |page| format!("?page={page}"));
+        assert!(
+            html.as_str()
+                .contains("<a href=\"?page=1\" rel=\"prev\">Previous</a>")
+        );
+        assert!(
+            html.as_str()
+                .contains("<a href=\"?page=2\" aria-current=\"page\">2</a>")
+        );
+        assert!(
+            html.as_str()
+                .contains("<span aria-disabled=\"true\">Next</span>")
+        );
+    }
+
+    #[test]
+    fn test_pagination_escapes_urls() {
+        let html = pagination(1, 2, |page| format!("?q=\"a\"&page={page}"));
+        assert!(
+            html.as_str()
+                .contains("<a href=\"?q=&#34;a&#34;&#38;page=2\">2</a>")
+        );
+    }
+
+    #[test]
+    fn test_pagination_current_out_of_range() {
+        let html = pagination(10, 3, |page| format!("?page={page}"));
+        assert!(
+            html.as_str()
+                .contains("<a href=\"?page=3\" aria-current=\"page\">3</a>")
+        );
+    }
+
+    #[test]
+    fn test_pagination_pages() {
+        assert_eq!(
+            pagination_pages(1, 5),
+            vec![Some(1), Some(2), Some(3), Some(4), Some(5)]
+        );
+        assert_eq!(
+            pagination_pages(1, 20),
+            vec![Some(1), Some(2), Some(3), None, Some(20)]
+        );
+        assert_eq!(
+            pagination_pages(10, 20),
+            vec![
+                Some(1),
+                None,
+                Some(8),
+                Some(9),
+                Some(10),
+                Some(11),
+                Some(12),
+                None,
+                Some(20)
+            ]
+        );
+        assert_eq!(
+            pagination_pages(4, 7),
+            vec![
+                Some(1),
+                Some(2),
+                Some(3),
+                Some(4),
+                Some(5),
+                Some(6),
+                Some(7)
+            ]
+        );
+        assert_eq!(
+            pagination_pages(20, 20),
+            vec![Some(1), None, Some(18), Some(19), Some(20)]
+        );
+    }
+
     #[test]
     fn test_html_tag_text_escaping_in_children() {
         let mut div = HtmlTag::new("div");
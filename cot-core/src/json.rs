@@ -3,6 +3,9 @@
 //! This module provides structures and methods for creating and rendering JSON
 //! content.
 
+#[cfg(feature = "json")]
+mod rename;
+
 /// A type that represents JSON content.
 ///
 /// Note that this is just a newtype wrapper around data and does not provide
@@ -20,3 +23,290 @@
 #[cfg(feature = "json")]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Json<D>(pub D);
+
+#[cfg(feature = "json")]
+impl<D> Json<D> {
+    /// Renames the struct fields in the serialized JSON according to the
+    /// given rule.
+    ///
+    /// This is useful for APIs consumed by clients that expect a different
+    /// naming convention than Rust's `snake_case`, such as JavaScript
+    /// clients expecting `camelCase`, without having to annotate every struct
+    /// with `#[serde(rename_all = "...")]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::json::{Json, RenameRule};
+    /// use cot::response::IntoResponse;
+    ///
+    /// #[derive(serde::Serialize)]
+    /// struct User {
+    ///     user_name: String,
+    /// }
+    ///
+    /// let json = Json(User {
+    ///     user_name: "John".to_owned(),
+    /// })
+    /// .rename_fields(RenameRule::CamelCase);
+    /// // the response body is `{"userName":"John"}`
+    /// let response = json.into_response();
+    /// ```
+    #[must_use]
+    pub fn rename_fields(self, rule: RenameRule) -> RenamedJson<D> {
+        RenamedJson::new(self.0, rule)
+    }
+}
+
+/// A naming convention to apply to the struct field names when serializing
+/// JSON with [`RenamedJson`].
+///
+/// The field names are expected to be `snake_case`, as is the convention in
+/// Rust; the rules work the same as the corresponding `#[serde(rename_all =
+/// "...")]` attribute values.
+///
+/// # Examples
+///
+/// ```
+/// use cot::json::RenameRule;
+///
+/// assert_eq!(RenameRule::CamelCase.apply("created_at"), "createdAt");
+/// assert_eq!(RenameRule::PascalCase.apply("created_at"), "CreatedAt");
+/// ```
+#[cfg(feature = "json")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RenameRule {
+    /// Rename the fields to `camelCase`.
+    CamelCase,
+    /// Rename the fields to `PascalCase`.
+    PascalCase,
+    /// Rename the fields to `kebab-case`.
+    KebabCase,
+    /// Rename the fields to `SCREAMING_SNAKE_CASE`.
+    ScreamingSnakeCase,
+}
+
+#[cfg(feature = "json")]
+impl RenameRule {
+    /// Applies the rule to a `snake_case` field name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::json::RenameRule;
+    ///
+    /// assert_eq!(RenameRule::KebabCase.apply("created_at"), "created-at");
+    /// assert_eq!(
+    ///     RenameRule::ScreamingSnakeCase.apply("created_at"),
+    ///     "CREATED_AT"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn apply(self, name: &str) -> String {
+        match self {
+            Self::CamelCase => {
+                let pascal = Self::PascalCase.apply(name);
+                let mut chars = pascal.chars();
+                match chars.next() {
+                    Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+                    None => pascal,
+                }
+            }
+            Self::PascalCase => {
+                let mut pascal = String::with_capacity(name.len());
+                let mut capitalize = true;
+                for ch in name.chars() {
+                    if ch == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        pascal.push(ch.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        pascal.push(ch);
+                    }
+                }
+                pascal
+            }
+            Self::KebabCase => name.replace('_', "-"),
+            Self::ScreamingSnakeCase => name.to_ascii_uppercase(),
+        }
+    }
+}
+
+/// JSON content with the struct field names renamed according to a
+/// [`RenameRule`].
+///
+/// The rule is applied to the fields of all the structs (including the
+/// nested ones and the struct enum variants) when serializing the data, so
+/// the serialized types don't need any `#[serde(rename_all = "...")]`
+/// attributes. The keys of maps, such as `HashMap`, are left intact, as they
+/// are data rather than field names. Fields that have an explicit
+/// `#[serde(rename = "...")]` are renamed as well.
+///
+/// This is usually created with [`Json::rename_fields`].
+///
+/// # Examples
+///
+/// ```
+/// use cot::json::{RenameRule, RenamedJson};
+///
+/// #[derive(serde::Serialize)]
+/// struct User {
+///     user_name: String,
+/// }
+///
+/// let json = RenamedJson::new(
+///     User {
+///         user_name: "John".to_owned(),
+///     },
+///     RenameRule::CamelCase,
+/// );
+/// assert_eq!(json.rule(), RenameRule::CamelCase);
+/// ```
+#[cfg(feature = "json")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct RenamedJson<D> {
+    data: D,
+    rule: RenameRule,
+}
+
+#[cfg(feature = "json")]
+impl<D> RenamedJson<D> {
+    /// Creates a new `RenamedJson` with the given data and rename rule.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::json::{RenameRule, RenamedJson};
+    ///
+    /// let json = RenamedJson::new("content", RenameRule::CamelCase);
+    /// assert_eq!(json.into_inner(), "content");
+    /// ```
+    #[must_use]
+    pub const fn new(data: D, rule: RenameRule) -> Self {
+        Self { data, rule }
+    }
+
+    /// Returns the rename rule applied to the data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::json::{RenameRule, RenamedJson};
+    ///
+    /// let json = RenamedJson::new("content", RenameRule::PascalCase);
+    /// assert_eq!(json.rule(), RenameRule::PascalCase);
+    /// ```
+    #[must_use]
+    pub const fn rule(&self) -> RenameRule {
+        self.rule
+    }
+
+    /// Returns the wrapped data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::json::{RenameRule, RenamedJson};
+    ///
+    /// let json = RenamedJson::new("content", RenameRule::CamelCase);
+    /// assert_eq!(json.into_inner(), "content");
+    /// ```
+    #[must_use]
+    pub fn into_inner(self) -> D {
+        self.data
+    }
+}
+
+#[cfg(feature = "json")]
+impl<D: serde::Serialize> serde::Serialize for RenamedJson<D> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        rename::Renamed::new(&self.data, self.rule).serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde::Serialize;
+
+    use super::*;
+
+    #[test]
+    fn rename_rule_apply() {
+        assert_eq!(RenameRule::CamelCase.apply("user_name"), "userName");
+        assert_eq!(RenameRule::CamelCase.apply("id"), "id");
+        assert_eq!(RenameRule::CamelCase.apply("a_b_c"), "aBC");
+        assert_eq!(RenameRule::PascalCase.apply("user_name"), "UserName");
+        assert_eq!(RenameRule::KebabCase.apply("user_name"), "user-name");
+        assert_eq!(
+            RenameRule::ScreamingSnakeCase.apply("user_name"),
+            "USER_NAME"
+        );
+        assert_eq!(RenameRule::CamelCase.apply(""), "");
+    }
+
+    #[derive(Serialize)]
+    struct Address {
+        street_name: String,
+        zip_code: Option<String>,
+    }
+
+    #[derive(Serialize)]
+    enum Contact {
+        PhoneNumber {
+            country_code: u16,
+            local_number: String,
+        },
+        Email(String),
+    }
+
+    #[derive(Serialize)]
+    struct User {
+        first_name: String,
+        home_address: Address,
+        previous_addresses: Vec<Address>,
+        contact: Contact,
+        extra_data: HashMap<String, u32>,
+    }
+
+    #[test]
+    fn renamed_json_nested() {
+        let user = User {
+            first_name: "John".to_owned(),
+            home_address: Address {
+                street_name: "Main".to_owned(),
+                zip_code: Some("00-001".to_owned()),
+            },
+            previous_addresses: vec![Address {
+                street_name: "Side".to_owned(),
+                zip_code: None,
+            }],
+            contact: Contact::PhoneNumber {
+                country_code: 48,
+                local_number: "123456789".to_owned(),
+            },
+            extra_data: HashMap::from([("snake_key".to_owned(), 1)]),
+        };
+
+        let json = serde_json::to_string(&RenamedJson::new(user, RenameRule::CamelCase)).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"firstName":"John","homeAddress":{"streetName":"Main","zipCode":"00-001"},"previousAddresses":[{"streetName":"Side","zipCode":null}],"contact":{"PhoneNumber":{"countryCode":48,"localNumber":"123456789"}},"extraData":{"snake_key":1}}"#
+        );
+    }
+
+    #[test]
+    fn renamed_json_newtype_variant() {
+        let json = serde_json::to_string(&RenamedJson::new(
+            Contact::Email("john@example.com".to_owned()),
+            RenameRule::PascalCase,
+        ))
+        .unwrap();
+
+        assert_eq!(json, r#"{"Email":"john@example.com"}"#);
+    }
+}
@@ -0,0 +1,365 @@
+//! A serializer adapter renaming the struct fields.
+//!
+//! The adapter wraps any [`Serializer`] and forwards everything to it, except
+//! for the struct field names, which are renamed according to a
+//! [`RenameRule`]. Since serde requires the field names to be `&'static str`,
+//! the renamed names are interned: each distinct field name is allocated
+//! once per rule and kept for the lifetime of the program. The set of field
+//! names is bounded by the types defined in the program, so this doesn't
+//! grow indefinitely.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::{LazyLock, Mutex, PoisonError};
+
+use serde::ser::{
+    Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+    SerializeTupleStruct, SerializeTupleVariant, Serializer,
+};
+
+use crate::json::RenameRule;
+
+/// Returns the field name renamed according to the rule.
+fn rename_field(rule: RenameRule, name: &'static str) -> &'static str {
+    static RENAMED_FIELDS: LazyLock<Mutex<HashMap<(RenameRule, &'static str), &'static str>>> =
+        LazyLock::new(|| Mutex::new(HashMap::new()));
+
+    let mut renamed_fields = RENAMED_FIELDS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    renamed_fields.entry((rule, name)).or_insert_with(|| {
+        let renamed = rule.apply(name);
+        if renamed == name {
+            name
+        } else {
+            Box::leak(renamed.into_boxed_str())
+        }
+    })
+}
+
+/// A value serialized with the struct field names renamed.
+pub(crate) struct Renamed<'a, T: ?Sized> {
+    value: &'a T,
+    rule: RenameRule,
+}
+
+impl<'a, T: ?Sized> Renamed<'a, T> {
+    pub(crate) fn new(value: &'a T, rule: RenameRule) -> Self {
+        Self { value, rule }
+    }
+}
+
+impl<T: Serialize + ?Sized> Serialize for Renamed<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(RenamingSerializer {
+            inner: serializer,
+            rule: self.rule,
+        })
+    }
+}
+
+struct RenamingSerializer<S> {
+    inner: S,
+    rule: RenameRule,
+}
+
+macro_rules! forward_primitive {
+    ($($method:ident($ty:ty)),* $(,)?) => {
+        $(
+            fn $method(self, v: $ty) -> Result<Self::Ok, Self::Error> {
+                self.inner.$method(v)
+            }
+        )*
+    };
+}
+
+impl<S: Serializer> Serializer for RenamingSerializer<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Compound<S::SerializeSeq>;
+    type SerializeTuple = Compound<S::SerializeTuple>;
+    type SerializeTupleStruct = Compound<S::SerializeTupleStruct>;
+    type SerializeTupleVariant = Compound<S::SerializeTupleVariant>;
+    type SerializeMap = Compound<S::SerializeMap>;
+    type SerializeStruct = Compound<S::SerializeStruct>;
+    type SerializeStructVariant = Compound<S::SerializeStructVariant>;
+
+    forward_primitive!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_unit_struct(&'static str),
+    );
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.serialize_none()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        self.inner.serialize_some(&Renamed::new(value, self.rule))
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.inner
+            .serialize_unit_variant(name, variant_index, variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.inner
+            .serialize_newtype_struct(name, &Renamed::new(value, self.rule))
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.inner.serialize_newtype_variant(
+            name,
+            variant_index,
+            variant,
+            &Renamed::new(value, self.rule),
+        )
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        let inner = self.inner.serialize_seq(len)?;
+        Ok(Compound {
+            inner,
+            rule: self.rule,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        let inner = self.inner.serialize_tuple(len)?;
+        Ok(Compound {
+            inner,
+            rule: self.rule,
+        })
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        let inner = self.inner.serialize_tuple_struct(name, len)?;
+        Ok(Compound {
+            inner,
+            rule: self.rule,
+        })
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        let inner = self
+            .inner
+            .serialize_tuple_variant(name, variant_index, variant, len)?;
+        Ok(Compound {
+            inner,
+            rule: self.rule,
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        let inner = self.inner.serialize_map(len)?;
+        Ok(Compound {
+            inner,
+            rule: self.rule,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        let inner = self.inner.serialize_struct(name, len)?;
+        Ok(Compound {
+            inner,
+            rule: self.rule,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        let inner = self
+            .inner
+            .serialize_struct_variant(name, variant_index, variant, len)?;
+        Ok(Compound {
+            inner,
+            rule: self.rule,
+        })
+    }
+
+    fn collect_str<T: Display + ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        self.inner.collect_str(value)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+struct Compound<C> {
+    inner: C,
+    rule: RenameRule,
+}
+
+impl<C: SerializeSeq> SerializeSeq for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.inner
+            .serialize_element(&Renamed::new(value, self.rule))
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: SerializeTuple> SerializeTuple for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.inner
+            .serialize_element(&Renamed::new(value, self.rule))
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: SerializeTupleStruct> SerializeTupleStruct for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.inner.serialize_field(&Renamed::new(value, self.rule))
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: SerializeTupleVariant> SerializeTupleVariant for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.inner.serialize_field(&Renamed::new(value, self.rule))
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: SerializeMap> SerializeMap for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    // the map keys are data, not field names, so they're not renamed
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Self::Error> {
+        self.inner.serialize_key(key)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.inner.serialize_value(&Renamed::new(value, self.rule))
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: SerializeStruct> SerializeStruct for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.inner.serialize_field(
+            rename_field(self.rule, key),
+            &Renamed::new(value, self.rule),
+        )
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Self::Error> {
+        self.inner.skip_field(rename_field(self.rule, key))
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: SerializeStructVariant> SerializeStructVariant for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.inner.serialize_field(
+            rename_field(self.rule, key),
+            &Renamed::new(value, self.rule),
+        )
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Self::Error> {
+        self.inner.skip_field(rename_field(self.rule, key))
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.end()
+    }
+}
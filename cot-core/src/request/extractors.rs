@@ -210,15 +210,17 @@ impl_into_cot_error!(QueryParametersParseError, BAD_REQUEST);
 /// Extractor that gets the request body as JSON and deserializes it into a type
 /// `T` implementing [`DeserializeOwned`].
 ///
-/// The content type of the request must be `application/json`.
+/// The content type of the request must be `application/json` (optionally
+/// with parameters, such as `application/json; charset=utf-8`).
 ///
 /// # Errors
 ///
 /// Throws an error if the content type is not `application/json`.
 /// Throws an error if the request body could not be read.
 /// Throws an error if the request body could not be deserialized - either
-/// because the JSON is invalid or because the deserialization to the target
-/// structure failed.
+/// because the JSON is invalid (resulting in `400 Bad Request`) or because the
+/// deserialization to the target structure failed (resulting in
+/// `422 Unprocessable Entity`).
 ///
 /// # Example
 ///
@@ -264,7 +266,8 @@ impl<D: DeserializeOwned> FromRequest for Json<D> {
             .headers
             .get(http::header::CONTENT_TYPE)
             .map_or("".into(), |value| String::from_utf8_lossy(value.as_bytes()));
-        if content_type != crate::headers::JSON_CONTENT_TYPE {
+        let mime_essence = content_type.split(';').next().unwrap_or_default().trim();
+        if !mime_essence.eq_ignore_ascii_case(crate::headers::JSON_CONTENT_TYPE) {
             return Err(InvalidContentType {
                 expected: crate::headers::JSON_CONTENT_TYPE,
                 actual: content_type.into_owned(),
@@ -287,7 +290,17 @@ impl<D: DeserializeOwned> FromRequest for Json<D> {
 #[error("JSON deserialization error: {0}")]
 struct JsonDeserializeError(serde_path_to_error::Error<serde_json::Error>);
 #[cfg(feature = "json")]
-impl_into_cot_error!(JsonDeserializeError, BAD_REQUEST);
+impl From<JsonDeserializeError> for crate::Error {
+    fn from(err: JsonDeserializeError) -> Self {
+        // syntactically valid JSON that doesn't match the target type is a
+        // semantic error, rather than a malformed request
+        let status_code = match err.0.inner().classify() {
+            serde_json::error::Category::Data => crate::StatusCode::UNPROCESSABLE_ENTITY,
+            _ => crate::StatusCode::BAD_REQUEST,
+        };
+        crate::Error::with_status(err, status_code)
+    }
+}
 
 // extractor impls for existing types
 impl FromRequestHead for RequestHead {
@@ -471,6 +484,67 @@ mod tests {
         assert!(matches!(result, UrlQuery(_)));
     }
 
+    #[cfg(feature = "json")]
+    #[cot::test]
+    async fn json_content_type_with_charset() {
+        let request = http::Request::builder()
+            .method(http::Method::POST)
+            .header(
+                http::header::CONTENT_TYPE,
+                "application/json; charset=utf-8",
+            )
+            .body(Body::fixed(r#"{"hello":"world"}"#))
+            .unwrap();
+
+        let (head, body) = request.into_parts();
+        let Json(data): Json<serde_json::Value> = Json::from_request(&head, body).await.unwrap();
+        assert_eq!(data, serde_json::json!({"hello": "world"}));
+    }
+
+    #[cfg(feature = "json")]
+    #[cot::test]
+    async fn json_invalid_syntax() {
+        let request = http::Request::builder()
+            .method(http::Method::POST)
+            .header(
+                http::header::CONTENT_TYPE,
+                crate::headers::JSON_CONTENT_TYPE,
+            )
+            .body(Body::fixed(r#"{"hello":"#))
+            .unwrap();
+
+        let (head, body) = request.into_parts();
+        let error = Json::<serde_json::Value>::from_request(&head, body)
+            .await
+            .unwrap_err();
+        assert_eq!(error.status_code(), http::StatusCode::BAD_REQUEST);
+    }
+
+    #[cfg(feature = "json")]
+    #[cot::test]
+    async fn json_invalid_data() {
+        #[derive(Debug, Deserialize)]
+        #[expect(dead_code)]
+        struct TestData {
+            hello: i32,
+        }
+
+        let request = http::Request::builder()
+            .method(http::Method::POST)
+            .header(
+                http::header::CONTENT_TYPE,
+                crate::headers::JSON_CONTENT_TYPE,
+            )
+            .body(Body::fixed(r#"{"hello":"world"}"#))
+            .unwrap();
+
+        let (head, body) = request.into_parts();
+        let error = Json::<TestData>::from_request(&head, body)
+            .await
+            .unwrap_err();
+        assert_eq!(error.status_code(), http::StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
     #[cfg(feature = "json")]
     #[cot::test]
     async fn json_invalid_content_type() {
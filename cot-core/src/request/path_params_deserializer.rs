@@ -17,9 +17,17 @@ pub(super) enum PathParamsDeserializerError {
         /// The actual number of path parameters that were provided.
         actual: usize,
     },
+    /// A path parameter required by the target type is missing.
+    #[error("missing path parameter `{name}`")]
+    MissingParam {
+        /// The name of the missing path parameter.
+        name: &'static str,
+    },
     /// A value cannot be parsed into given type.
-    #[error("failed to parse value `{value}` as `{expected_type}`")]
+    #[error("failed to parse value `{value}` of path parameter `{name}` as `{expected_type}`")]
     ParseError {
+        /// The name of the path parameter.
+        name: String,
         /// The value that was provided.
         value: String,
         /// The expected type name.
@@ -77,6 +85,10 @@ impl<'de> PathParamsDeserializer<'de> {
         Ok(value)
     }
 
+    fn param_name(&self) -> &'de str {
+        self.path_params.key_at_index(0).unwrap_or_default()
+    }
+
     fn check_param_num(&self, expected: usize) -> Result<(), PathParamsDeserializerError> {
         if self.path_params.len() == expected {
             Ok(())
@@ -100,6 +112,7 @@ macro_rules! deserialize_value {
             let value = value
                 .parse()
                 .map_err(|_| PathParamsDeserializerError::ParseError {
+                    name: self.param_name().to_string(),
                     value: value.to_string(),
                     expected_type: stringify!($type_name),
                 })?;
@@ -247,6 +260,12 @@ impl<'de> Deserializer<'de> for PathParamsDeserializer<'de> {
     where
         V: Visitor<'de>,
     {
+        if let Some(name) = fields
+            .iter()
+            .find(|field| self.path_params.get(field).is_none())
+        {
+            return Err(PathParamsDeserializerError::MissingParam { name });
+        }
         self.check_param_num(fields.len())?;
 
         visitor.visit_map(MapDeserializer::new(self.path_params))
@@ -385,6 +404,11 @@ impl<'de> ValueDeserializer<'de> {
     fn get_single_value(&self) -> Result<&'de str, PathParamsDeserializerError> {
         Ok(self.value)
     }
+
+    fn param_name(&self) -> &'de str {
+        // when deserializing a key, the value is the name of the parameter
+        self.key.unwrap_or(self.value)
+    }
 }
 
 impl<'de> Deserializer<'de> for ValueDeserializer<'de> {
@@ -810,21 +834,38 @@ mod tests {
         #[derive(Debug, PartialEq, Eq, Deserialize)]
         struct Params {
             a: String,
-            b: String,
         }
 
-        let path_params = create_path_params([("x", "a")]);
+        let path_params = create_path_params([("a", "a"), ("b", "b")]);
 
         let actual = Params::deserialize(PathParamsDeserializer::new(&path_params)).unwrap_err();
         assert_eq!(
             actual,
             PathParamsDeserializerError::InvalidParamNumber {
-                expected: 2,
-                actual: 1,
+                expected: 1,
+                actual: 2,
             }
         );
     }
 
+    #[test]
+    fn deserialize_missing_param_struct_error() {
+        #[derive(Debug, PartialEq, Eq, Deserialize)]
+        struct Params {
+            a: String,
+            b: String,
+        }
+
+        let path_params = create_path_params([("a", "a")]);
+
+        let actual = Params::deserialize(PathParamsDeserializer::new(&path_params)).unwrap_err();
+        assert_eq!(
+            actual,
+            PathParamsDeserializerError::MissingParam { name: "b" }
+        );
+        assert_eq!(actual.to_string(), "missing path parameter `b`");
+    }
+
     #[test]
     fn deserialize_parse_error() {
         let path_params = create_path_params([("x", "a")]);
@@ -833,12 +874,46 @@ mod tests {
         assert_eq!(
             actual,
             PathParamsDeserializerError::ParseError {
+                name: "x".to_string(),
                 value: "a".to_string(),
                 expected_type: "i32",
             }
         );
     }
 
+    #[test]
+    fn deserialize_parse_error_struct() {
+        #[derive(Debug, PartialEq, Eq, Deserialize)]
+        struct Params {
+            user_id: i32,
+            post_id: i32,
+        }
+
+        let path_params = create_path_params([("user_id", "1"), ("post_id", "abc")]);
+
+        let actual = Params::deserialize(PathParamsDeserializer::new(&path_params)).unwrap_err();
+        assert_eq!(
+            actual.to_string(),
+            "failed to parse value `abc` of path parameter `post_id` as `i32`"
+        );
+    }
+
+    #[test]
+    fn deserialize_parse_error_tuple() {
+        let path_params = create_path_params([("user_id", "1"), ("post_id", "abc")]);
+
+        let actual =
+            <(i32, i32)>::deserialize(PathParamsDeserializer::new(&path_params)).unwrap_err();
+        assert_eq!(
+            actual,
+            PathParamsDeserializerError::ParseError {
+                name: "post_id".to_string(),
+                value: "abc".to_string(),
+                expected_type: "i32",
+            }
+        );
+    }
+
     #[test]
     fn deserialize_unsupported_type_error() {
         let path_params = create_path_params([("x", "a")]);
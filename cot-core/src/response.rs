@@ -1,4 +1,7 @@
+use std::time::{Duration, SystemTime};
+
 use crate::Body;
+mod cache_control;
 mod into_response;
 
 /// Derive macro for the [`IntoResponse`] trait.
@@ -50,6 +53,7 @@ mod into_response;
 /// ```
 ///
 /// [`IntoResponse`]: crate::response::IntoResponse
+pub use cache_control::CacheControl;
 pub use cot_macros::IntoResponse;
 pub use into_response::{
     IntoResponse, WithBody, WithContentType, WithExtension, WithHeader, WithStatus,
@@ -90,6 +94,188 @@ pub trait ResponseExt: Sized + private::Sealed {
     /// ```
     #[must_use]
     fn builder() -> http::response::Builder;
+
+    /// Marks the response as cacheable for the given duration.
+    ///
+    /// This sets the `Cache-Control` header to `public, max-age=<seconds>` and
+    /// the `Expires` header to the current time plus the given duration. Any
+    /// existing values of these headers are replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::response::{Response, ResponseExt};
+    ///
+    /// let response =
+    ///     Response::new(cot::Body::fixed("Hello world!")).cache_for(Duration::from_hours(1));
+    ///
+    /// assert_eq!(
+    ///     response.headers().get(http::header::CACHE_CONTROL).unwrap(),
+    ///     "public, max-age=3600"
+    /// );
+    /// assert!(response.headers().contains_key(http::header::EXPIRES));
+    /// ```
+    #[must_use]
+    fn cache_for(self, duration: Duration) -> Self;
+
+    /// Sets the `Cache-Control` header to the given directives.
+    ///
+    /// Any existing value of the header is replaced. If `directive` is empty,
+    /// the header is removed instead of being sent without a value. Other
+    /// caching-related headers, such as `Expires`, are left intact.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::response::{CacheControl, Response, ResponseExt};
+    ///
+    /// let response = Response::new(cot::Body::fixed("Hello world!")).cache_control(
+    ///     CacheControl::new()
+    ///         .private()
+    ///         .max_age(Duration::from_mins(10)),
+    /// );
+    ///
+    /// assert_eq!(
+    ///     response.headers().get(http::header::CACHE_CONTROL).unwrap(),
+    ///     "private, max-age=600"
+    /// );
+    /// ```
+    #[must_use]
+    fn cache_control(self, directive: CacheControl) -> Self;
+
+    /// Marks the response as not cacheable.
+    ///
+    /// This sets the `Cache-Control` header to `no-store, no-cache,
+    /// must-revalidate`, which prevents the response from being stored by the
+    /// browser or any intermediate caches (`no-cache` and `must-revalidate`
+    /// are there for the older caches that don't understand `no-store`). It
+    /// is useful for pages containing sensitive data. Any existing `Expires`
+    /// header is removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::response::{Response, ResponseExt};
+    ///
+    /// let response = Response::new(cot::Body::fixed("Secret data")).no_cache();
+    ///
+    /// assert_eq!(
+    ///     response.headers().get(http::header::CACHE_CONTROL).unwrap(),
+    ///     "no-store, no-cache, must-revalidate"
+    /// );
+    /// ```
+    #[must_use]
+    fn no_cache(self) -> Self;
+
+    /// Creates a response that makes the browser download the body as a file
+    /// with the given name.
+    ///
+    /// This sets the `Content-Type` header to the given content type and the
+    /// `Content-Disposition` header to `attachment; filename="..."`. If the
+    /// filename contains non-ASCII characters (or characters not allowed in a
+    /// quoted string), they are replaced with underscores in the `filename`
+    /// parameter, and the full name is additionally passed in the
+    /// `filename*` parameter, encoded as defined in RFC 5987.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the content type is not a valid header value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::Body;
+    /// use cot::response::{Response, ResponseExt};
+    ///
+    /// let response = Response::attachment(Body::fixed("a,b\r\n"), "report.csv", "text/csv");
+    ///
+    /// assert_eq!(
+    ///     response.headers().get(http::header::CONTENT_TYPE).unwrap(),
+    ///     "text/csv"
+    /// );
+    /// assert_eq!(
+    ///     response
+    ///         .headers()
+    ///         .get(http::header::CONTENT_DISPOSITION)
+    ///         .unwrap(),
+    ///     "attachment; filename=\"report.csv\""
+    /// );
+    /// ```
+    #[must_use]
+    fn attachment(body: Body, filename: &str, content_type: &str) -> Self;
+
+    /// Creates a response that the browser should display in the page (if
+    /// possible), using the given filename if the user decides to save it.
+    ///
+    /// This is the same as [`Self::attachment`], except that the
+    /// `Content-Disposition` header uses the `inline` disposition type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the content type is not a valid header value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::Body;
+    /// use cot::response::{Response, ResponseExt};
+    ///
+    /// let response = Response::inline(Body::fixed("%PDF-1.7"), "żółw.pdf", "application/pdf");
+    ///
+    /// assert_eq!(
+    ///     response
+    ///         .headers()
+    ///         .get(http::header::CONTENT_DISPOSITION)
+    ///         .unwrap(),
+    ///     "inline; filename=\"___w.pdf\"; filename*=UTF-8''%C5%BC%C3%B3%C5%82w.pdf"
+    /// );
+    /// ```
+    #[must_use]
+    fn inline(body: Body, filename: &str, content_type: &str) -> Self;
+
+    /// Creates a response that streams the items of the given stream as a JSON
+    /// array.
+    ///
+    /// The array is written incrementally: the opening bracket is sent first,
+    /// followed by each item serialized as soon as it is produced by the
+    /// stream, and the closing bracket once the stream is exhausted. This
+    /// allows sending large collections (e.g. database query results) without
+    /// holding them in memory. The `Content-Type` header is set to
+    /// `application/json`.
+    ///
+    /// If the stream yields an error, or an item fails to serialize, the
+    /// response body is terminated with that error and no further data is
+    /// sent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::response::{Response, ResponseExt};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> cot::Result<()> {
+    /// let items = futures::stream::iter([Ok(1), Ok(2), Ok(3)]);
+    /// let response = Response::json_stream(items);
+    ///
+    /// assert_eq!(
+    ///     response.headers().get(http::header::CONTENT_TYPE).unwrap(),
+    ///     "application/json"
+    /// );
+    /// let body = response.into_body().into_bytes().await?;
+    /// assert_eq!(body, "[1,2,3]");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "json")]
+    #[must_use]
+    fn json_stream<S, T>(stream: S) -> Self
+    where
+        S: futures_core::Stream<Item = crate::Result<T>> + Send + 'static,
+        T: serde::Serialize;
 }
 
 impl private::Sealed for Response {}
@@ -98,6 +284,138 @@ impl ResponseExt for Response {
     fn builder() -> http::response::Builder {
         http::Response::builder()
     }
+
+    fn cache_for(mut self, duration: Duration) -> Self {
+        let cache_control = CacheControl::new()
+            .public()
+            .max_age(duration)
+            .to_header_value();
+        let expires =
+            http::HeaderValue::from_str(&httpdate::fmt_http_date(SystemTime::now() + duration))
+                .expect("HTTP date is always a valid header value");
+
+        let headers = self.headers_mut();
+        headers.insert(http::header::CACHE_CONTROL, cache_control);
+        headers.insert(http::header::EXPIRES, expires);
+        self
+    }
+
+    fn cache_control(mut self, directive: CacheControl) -> Self {
+        let headers = self.headers_mut();
+        if directive.is_empty() {
+            headers.remove(http::header::CACHE_CONTROL);
+        } else {
+            headers.insert(http::header::CACHE_CONTROL, directive.to_header_value());
+        }
+        self
+    }
+
+    fn no_cache(self) -> Self {
+        let mut response =
+            self.cache_control(CacheControl::new().no_store().no_cache().must_revalidate());
+        response.headers_mut().remove(http::header::EXPIRES);
+        response
+    }
+
+    fn attachment(body: Body, filename: &str, content_type: &str) -> Self {
+        with_content_disposition(body, "attachment", filename, content_type)
+    }
+
+    fn inline(body: Body, filename: &str, content_type: &str) -> Self {
+        with_content_disposition(body, "inline", filename, content_type)
+    }
+
+    #[cfg(feature = "json")]
+    fn json_stream<S, T>(stream: S) -> Self
+    where
+        S: futures_core::Stream<Item = crate::Result<T>> + Send + 'static,
+        T: serde::Serialize,
+    {
+        use futures_util::{StreamExt, future, stream as stream_util};
+
+        let items = stream.enumerate().map(|(index, item)| {
+            let mut buf = Vec::new();
+            if index > 0 {
+                buf.push(b',');
+            }
+            let mut serializer = serde_json::Serializer::new(&mut buf);
+            serde_path_to_error::serialize(&item?, &mut serializer)
+                .map_err(into_response::JsonSerializeError)?;
+            Ok(bytes::Bytes::from(buf))
+        });
+        let body = stream_util::once(future::ready(Ok(bytes::Bytes::from_static(b"["))))
+            .chain(items)
+            .chain(stream_util::once(future::ready(Ok(
+                bytes::Bytes::from_static(b"]"),
+            ))))
+            // stop at the first error so that a truncated array is never
+            // terminated as if it were complete
+            .scan(false, |failed, item| {
+                if *failed {
+                    return future::ready(None);
+                }
+                *failed = item.is_err();
+                future::ready(Some(item))
+            });
+
+        let mut response = Response::new(Body::streaming(body));
+        response.headers_mut().insert(
+            http::header::CONTENT_TYPE,
+            http::HeaderValue::from_static(crate::headers::JSON_CONTENT_TYPE),
+        );
+        response
+    }
+}
+
+fn with_content_disposition(
+    body: Body,
+    disposition: &str,
+    filename: &str,
+    content_type: &str,
+) -> Response {
+    let content_type =
+        http::HeaderValue::from_str(content_type).expect("invalid content type header value");
+    let content_disposition =
+        http::HeaderValue::from_str(&content_disposition(disposition, filename))
+            .expect("content disposition header value is always valid");
+
+    let mut response = Response::new(body);
+    let headers = response.headers_mut();
+    headers.insert(http::header::CONTENT_TYPE, content_type);
+    headers.insert(http::header::CONTENT_DISPOSITION, content_disposition);
+    response
+}
+
+/// Builds a `Content-Disposition` header value (RFC 6266) with the given
+/// disposition type and filename.
+fn content_disposition(disposition: &str, filename: &str) -> String {
+    use std::fmt::Write;
+
+    let ascii_filename: String = filename
+        .chars()
+        .map(|c| {
+            if (c.is_ascii_graphic() && c != '"' && c != '\\') || c == ' ' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    let mut value = format!("{disposition}; filename=\"{ascii_filename}\"");
+    if ascii_filename != filename {
+        // RFC 5987 `ext-value`; `attr-char` is alphanumeric or one of the
+        // characters below, everything else is percent-encoded
+        value.push_str("; filename*=UTF-8''");
+        for byte in filename.bytes() {
+            if byte.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&byte) {
+                value.push(char::from(byte));
+            } else {
+                write!(value, "%{byte:02X}").expect("writing to a String never fails");
+            }
+        }
+    }
+    value
 }
 
 /// A redirect response.
@@ -181,6 +499,184 @@ mod tests {
         }
     }
 
+    #[test]
+    fn response_cache_for() {
+        let before = SystemTime::now();
+        let response = Response::new(Body::empty()).cache_for(Duration::from_hours(1));
+
+        assert_eq!(
+            response.headers().get(http::header::CACHE_CONTROL).unwrap(),
+            "public, max-age=3600"
+        );
+        let expires = httpdate::parse_http_date(
+            response
+                .headers()
+                .get(http::header::EXPIRES)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+        )
+        .unwrap();
+        // HTTP dates have a resolution of one second
+        let expected = before + Duration::from_hours(1);
+        assert!(expires + Duration::from_secs(1) >= expected);
+        assert!(expires <= expected + Duration::from_mins(1));
+    }
+
+    #[test]
+    fn response_no_cache() {
+        let response = Response::new(Body::empty())
+            .cache_for(Duration::from_hours(1))
+            .no_cache();
+
+        assert_eq!(
+            response.headers().get(http::header::CACHE_CONTROL).unwrap(),
+            "no-store, no-cache, must-revalidate"
+        );
+        assert!(!response.headers().contains_key(http::header::EXPIRES));
+    }
+
+    #[test]
+    fn response_cache_control() {
+        let response = Response::new(Body::empty())
+            .cache_for(Duration::from_hours(1))
+            .cache_control(
+                CacheControl::new()
+                    .public()
+                    .max_age(Duration::from_hours(365 * 24))
+                    .immutable(),
+            );
+
+        assert_eq!(
+            response.headers().get(http::header::CACHE_CONTROL).unwrap(),
+            "public, max-age=31536000, immutable"
+        );
+        assert_eq!(
+            response
+                .headers()
+                .get_all(http::header::CACHE_CONTROL)
+                .iter()
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn response_cache_control_empty_removes_header() {
+        let response = Response::new(Body::empty()).cache_control(CacheControl::new());
+        assert!(!response.headers().contains_key(http::header::CACHE_CONTROL));
+
+        let response = Response::new(Body::empty())
+            .cache_for(Duration::from_hours(1))
+            .cache_control(CacheControl::new());
+        assert!(!response.headers().contains_key(http::header::CACHE_CONTROL));
+        assert!(response.headers().contains_key(http::header::EXPIRES));
+    }
+
+    #[test]
+    fn response_cache_for_composes_with_into_response() {
+        let response = "Hello world!"
+            .with_header("X-Custom", "value")
+            .into_response()
+            .unwrap()
+            .cache_for(Duration::from_mins(1));
+
+        assert_eq!(response.headers().get("X-Custom").unwrap(), "value");
+        assert_eq!(
+            response.headers().get(http::header::CACHE_CONTROL).unwrap(),
+            "public, max-age=60"
+        );
+    }
+
+    #[test]
+    fn response_attachment() {
+        let response = Response::attachment(Body::fixed("data"), "data.bin", "application/x-bin");
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(http::header::CONTENT_TYPE).unwrap(),
+            "application/x-bin"
+        );
+        assert_eq!(
+            response
+                .headers()
+                .get(http::header::CONTENT_DISPOSITION)
+                .unwrap(),
+            "attachment; filename=\"data.bin\""
+        );
+    }
+
+    #[test]
+    fn response_inline() {
+        let response = Response::inline(Body::fixed("data"), "image.png", "image/png");
+
+        assert_eq!(
+            response
+                .headers()
+                .get(http::header::CONTENT_DISPOSITION)
+                .unwrap(),
+            "inline; filename=\"image.png\""
+        );
+    }
+
+    #[test]
+    fn content_disposition_escapes_filename() {
+        assert_eq!(
+            content_disposition("attachment", "raport \"końcowy\".csv"),
+            "attachment; filename=\"raport _ko_cowy_.csv\"; \
+             filename*=UTF-8''raport%20%22ko%C5%84cowy%22.csv"
+        );
+        assert_eq!(
+            content_disposition("attachment", "a\\b\r\n.txt"),
+            "attachment; filename=\"a_b__.txt\"; filename*=UTF-8''a%5Cb%0D%0A.txt"
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[cot::test]
+    async fn response_json_stream() {
+        #[derive(serde::Serialize)]
+        struct Item {
+            id: u32,
+        }
+
+        let items =
+            futures::stream::iter([Ok(Item { id: 1 }), Ok(Item { id: 2 }), Ok(Item { id: 3 })]);
+        let response = Response::json_stream(items);
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(http::header::CONTENT_TYPE).unwrap(),
+            JSON_CONTENT_TYPE
+        );
+        assert!(matches!(response.body().inner, BodyInner::Streaming(_)));
+        let body = response.into_body().into_bytes().await.unwrap();
+        assert_eq!(body, r#"[{"id":1},{"id":2},{"id":3}]"#);
+    }
+
+    #[cfg(feature = "json")]
+    #[cot::test]
+    async fn response_json_stream_empty() {
+        let response = Response::json_stream(futures::stream::empty::<crate::Result<u32>>());
+
+        let body = response.into_body().into_bytes().await.unwrap();
+        assert_eq!(body, "[]");
+    }
+
+    #[cfg(feature = "json")]
+    #[cot::test]
+    async fn response_json_stream_error() {
+        let items = futures::stream::iter([
+            Ok(1),
+            Err(crate::Error::internal("database connection lost")),
+            Ok(3),
+        ]);
+        let response = Response::json_stream(items);
+
+        let result = response.into_body().into_bytes().await;
+        assert!(result.is_err());
+    }
+
     #[test]
     fn response_new_redirect_struct() {
         let location = "http://example.com";
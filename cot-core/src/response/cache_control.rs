@@ -0,0 +1,312 @@
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+/// A `Cache-Control` header value.
+///
+/// This is a builder for the most commonly used `Cache-Control` response
+/// directives, which makes sure the directives are rendered in a correct
+/// format. Use it with
+/// [`ResponseExt::cache_control`](crate::response::ResponseExt::cache_control)
+/// to set the header on a response.
+///
+/// The directives are always rendered in the same order, regardless of the
+/// order in which the builder methods were called.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use cot::response::CacheControl;
+///
+/// let cache_control = CacheControl::new()
+///     .public()
+///     .max_age(Duration::from_hours(365 * 24))
+///     .immutable();
+///
+/// assert_eq!(
+///     cache_control.to_string(),
+///     "public, max-age=31536000, immutable"
+/// );
+/// ```
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct CacheControl {
+    visibility: Option<Visibility>,
+    no_store: bool,
+    no_cache: bool,
+    must_revalidate: bool,
+    max_age: Option<Duration>,
+    immutable: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum Visibility {
+    Public,
+    Private,
+}
+
+impl CacheControl {
+    /// Creates a new `Cache-Control` value without any directives.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::response::CacheControl;
+    ///
+    /// assert_eq!(CacheControl::new().to_string(), "");
+    /// ```
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            visibility: None,
+            no_store: false,
+            no_cache: false,
+            must_revalidate: false,
+            max_age: None,
+            immutable: false,
+        }
+    }
+
+    /// Adds the `public` directive, which allows the response to be stored by
+    /// shared caches, such as proxies and CDNs.
+    ///
+    /// This replaces the [`private`](Self::private) directive, if it has been
+    /// set before.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::response::CacheControl;
+    ///
+    /// assert_eq!(CacheControl::new().public().to_string(), "public");
+    /// ```
+    #[must_use]
+    pub const fn public(mut self) -> Self {
+        self.visibility = Some(Visibility::Public);
+        self
+    }
+
+    /// Adds the `private` directive, which allows the response to be stored
+    /// only by the browser, and not by any shared caches. This should be used
+    /// for responses personalized for the user.
+    ///
+    /// This replaces the [`public`](Self::public) directive, if it has been
+    /// set before.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::response::CacheControl;
+    ///
+    /// assert_eq!(CacheControl::new().private().to_string(), "private");
+    /// ```
+    #[must_use]
+    pub const fn private(mut self) -> Self {
+        self.visibility = Some(Visibility::Private);
+        self
+    }
+
+    /// Adds the `max-age` directive, which specifies how long the response
+    /// remains fresh. The duration is rounded down to whole seconds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::response::CacheControl;
+    ///
+    /// assert_eq!(
+    ///     CacheControl::new()
+    ///         .max_age(Duration::from_mins(5))
+    ///         .to_string(),
+    ///     "max-age=300"
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Adds the `no-store` directive, which prevents the response from being
+    /// stored by any cache.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::response::CacheControl;
+    ///
+    /// assert_eq!(CacheControl::new().no_store().to_string(), "no-store");
+    /// ```
+    #[must_use]
+    pub const fn no_store(mut self) -> Self {
+        self.no_store = true;
+        self
+    }
+
+    /// Adds the `no-cache` directive, which allows the response to be stored,
+    /// but requires the caches to revalidate it with the server before each
+    /// use.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::response::CacheControl;
+    ///
+    /// assert_eq!(CacheControl::new().no_cache().to_string(), "no-cache");
+    /// ```
+    #[must_use]
+    pub const fn no_cache(mut self) -> Self {
+        self.no_cache = true;
+        self
+    }
+
+    /// Adds the `must-revalidate` directive, which prevents the caches from
+    /// using the response after it has become stale without revalidating it
+    /// with the server first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::response::CacheControl;
+    ///
+    /// assert_eq!(
+    ///     CacheControl::new().must_revalidate().to_string(),
+    ///     "must-revalidate"
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn must_revalidate(mut self) -> Self {
+        self.must_revalidate = true;
+        self
+    }
+
+    /// Adds the `immutable` directive, which indicates that the response will
+    /// not change while it's fresh, so the browsers don't need to revalidate
+    /// it even when the user reloads the page.
+    ///
+    /// This is useful for static files with a content hash in their URLs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::response::CacheControl;
+    ///
+    /// assert_eq!(CacheControl::new().immutable().to_string(), "immutable");
+    /// ```
+    #[must_use]
+    pub const fn immutable(mut self) -> Self {
+        self.immutable = true;
+        self
+    }
+
+    /// Returns `true` if no directives have been set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::response::CacheControl;
+    ///
+    /// assert!(CacheControl::new().is_empty());
+    /// assert!(!CacheControl::new().no_store().is_empty());
+    /// ```
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.visibility.is_none()
+            && !self.no_store
+            && !self.no_cache
+            && !self.must_revalidate
+            && self.max_age.is_none()
+            && !self.immutable
+    }
+
+    /// Converts the directives to a header value.
+    pub(crate) fn to_header_value(self) -> http::HeaderValue {
+        http::HeaderValue::from_str(&self.to_string())
+            .expect("cache control header value is always valid")
+    }
+}
+
+impl Display for CacheControl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut separator = "";
+        let mut write_directive =
+            |f: &mut Formatter<'_>, directive: &dyn Display| -> std::fmt::Result {
+                write!(f, "{separator}{directive}")?;
+                separator = ", ";
+                Ok(())
+            };
+
+        match self.visibility {
+            Some(Visibility::Public) => write_directive(f, &"public")?,
+            Some(Visibility::Private) => write_directive(f, &"private")?,
+            None => {}
+        }
+        if self.no_store {
+            write_directive(f, &"no-store")?;
+        }
+        if self.no_cache {
+            write_directive(f, &"no-cache")?;
+        }
+        if self.must_revalidate {
+            write_directive(f, &"must-revalidate")?;
+        }
+        if let Some(max_age) = self.max_age {
+            write_directive(f, &format_args!("max-age={}", max_age.as_secs()))?;
+        }
+        if self.immutable {
+            write_directive(f, &"immutable")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_control_empty() {
+        assert_eq!(CacheControl::new().to_string(), "");
+        assert_eq!(CacheControl::default(), CacheControl::new());
+        assert!(CacheControl::new().is_empty());
+        assert!(!CacheControl::new().max_age(Duration::ZERO).is_empty());
+    }
+
+    #[test]
+    fn cache_control_all_directives() {
+        let cache_control = CacheControl::new()
+            .immutable()
+            .max_age(Duration::from_millis(90_500))
+            .must_revalidate()
+            .no_cache()
+            .no_store()
+            .private();
+
+        assert_eq!(
+            cache_control.to_string(),
+            "private, no-store, no-cache, must-revalidate, max-age=90, immutable"
+        );
+    }
+
+    #[test]
+    fn cache_control_visibility_is_replaced() {
+        assert_eq!(CacheControl::new().private().public().to_string(), "public");
+        assert_eq!(
+            CacheControl::new().public().private().to_string(),
+            "private"
+        );
+    }
+
+    #[test]
+    fn cache_control_header_value() {
+        let value = CacheControl::new()
+            .public()
+            .max_age(Duration::from_hours(1))
+            .to_header_value();
+
+        assert_eq!(value, "public, max-age=3600");
+    }
+}
@@ -287,6 +287,28 @@ impl IntoResponse for StatusCode {
     }
 }
 
+impl<T: IntoResponse> IntoResponse for (StatusCode, T) {
+    /// Create a response with the given status code.
+    ///
+    /// This converts the second element of the tuple into a response and
+    /// overrides its status code with the first element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::StatusCode;
+    /// use cot::response::IntoResponse;
+    ///
+    /// let response = (StatusCode::CREATED, "created").into_response().unwrap();
+    ///
+    /// assert_eq!(response.status(), StatusCode::CREATED);
+    /// ```
+    fn into_response(self) -> crate::Result<Response> {
+        let (status, inner) = self;
+        inner.with_status(status).into_response()
+    }
+}
+
 impl IntoResponse for http::HeaderMap {
     fn into_response(self) -> crate::Result<Response> {
         ().into_response().map(|mut resp| {
@@ -358,22 +380,55 @@ impl<D: serde::Serialize> IntoResponse for crate::json::Json<D> {
     /// let response = json.into_response();
     /// ```
     fn into_response(self) -> crate::Result<Response> {
-        // a "reasonable default" for a JSON response size
-        const DEFAULT_JSON_SIZE: usize = 128;
-
-        let mut buf = Vec::with_capacity(DEFAULT_JSON_SIZE);
-        let mut serializer = serde_json::Serializer::new(&mut buf);
-        serde_path_to_error::serialize(&self.0, &mut serializer).map_err(JsonSerializeError)?;
-        let data = String::from_utf8(buf).expect("JSON serialization always returns valid UTF-8");
+        json_response(&self.0)
+    }
+}
 
-        data.with_content_type(JSON_CONTENT_TYPE).into_response()
+#[cfg(feature = "json")]
+impl<D: serde::Serialize> IntoResponse for crate::json::RenamedJson<D> {
+    /// Create a new JSON response with renamed struct fields.
+    ///
+    /// This creates a new [`Response`] object with a content type of
+    /// `application/json` and given body, with the struct field names
+    /// renamed according to the [`RenameRule`](crate::json::RenameRule).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::json::{Json, RenameRule};
+    /// use cot::response::IntoResponse;
+    ///
+    /// #[derive(serde::Serialize)]
+    /// struct Data {
+    ///     hello_world: &'static str,
+    /// }
+    ///
+    /// let json = Json(Data { hello_world: "!" }).rename_fields(RenameRule::CamelCase);
+    ///
+    /// let response = json.into_response();
+    /// ```
+    fn into_response(self) -> crate::Result<Response> {
+        json_response(&self)
     }
 }
 
+#[cfg(feature = "json")]
+fn json_response<D: serde::Serialize + ?Sized>(data: &D) -> crate::Result<Response> {
+    // a "reasonable default" for a JSON response size
+    const DEFAULT_JSON_SIZE: usize = 128;
+
+    let mut buf = Vec::with_capacity(DEFAULT_JSON_SIZE);
+    let mut serializer = serde_json::Serializer::new(&mut buf);
+    serde_path_to_error::serialize(data, &mut serializer).map_err(JsonSerializeError)?;
+    let data = String::from_utf8(buf).expect("JSON serialization always returns valid UTF-8");
+
+    data.with_content_type(JSON_CONTENT_TYPE).into_response()
+}
+
 #[cfg(feature = "json")]
 #[derive(Debug, thiserror::Error)]
 #[error("JSON serialization error: {0}")]
-struct JsonSerializeError(serde_path_to_error::Error<serde_json::Error>);
+pub(super) struct JsonSerializeError(pub(super) serde_path_to_error::Error<serde_json::Error>);
 #[cfg(feature = "json")]
 impl_into_cot_error!(JsonSerializeError, INTERNAL_SERVER_ERROR);
 
@@ -623,6 +678,18 @@ mod tests {
         assert_eq!(response.into_body().into_bytes().await.unwrap().len(), 0);
     }
 
+    #[cot::test]
+    async fn test_status_code_tuple_into_response() {
+        let response = (StatusCode::CREATED, "created").into_response().unwrap();
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(
+            response.headers().get(http::header::CONTENT_TYPE).unwrap(),
+            "text/plain; charset=utf-8"
+        );
+        assert_eq!(response.into_body().into_bytes().await.unwrap(), "created");
+    }
+
     #[cot::test]
     async fn test_header_map_into_response() {
         let mut headers = HeaderMap::new();
@@ -825,4 +892,35 @@ mod tests {
         let expected_json = r#"{"key":"value"}"#;
         assert_eq!(body_bytes, expected_json.as_bytes());
     }
+
+    #[cfg(feature = "json")]
+    #[cot::test]
+    async fn test_renamed_json_into_response() {
+        use serde::Serialize;
+
+        use crate::json::{Json, RenameRule};
+
+        #[derive(Serialize)]
+        struct TestData {
+            user_name: String,
+            login_count: i32,
+        }
+
+        let data = TestData {
+            user_name: "test".to_string(),
+            login_count: 123,
+        };
+        let json = Json(data).rename_fields(RenameRule::CamelCase);
+        let response = json.into_response().unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(http::header::CONTENT_TYPE).unwrap(),
+            JSON_CONTENT_TYPE
+        );
+
+        let body_bytes = response.into_body().into_bytes().await.unwrap();
+        let expected_json = r#"{"userName":"test","loginCount":123}"#;
+        assert_eq!(body_bytes, expected_json.as_bytes());
+    }
 }
@@ -2,7 +2,7 @@ use cot_codegen::model::FieldOpts;
 use darling::FromDeriveInput;
 use heck::ToSnakeCase;
 use proc_macro2::TokenStream;
-use quote::{ToTokens, quote};
+use quote::{ToTokens, quote, quote_spanned};
 
 use crate::cot_ident;
 
@@ -16,17 +16,62 @@ pub(super) fn impl_admin_model_for_struct(ast: &syn::DeriveInput) -> TokenStream
 
     let mut builder = opts.as_form_derive_builder();
     for field in opts.fields() {
-        builder.push_field(field);
+        if let Err(err) = builder.push_field(field) {
+            return err.into_compile_error();
+        }
+    }
+    if let Err(err) = builder.push_list_fields(&opts) {
+        return err.into_compile_error();
     }
 
     quote!(#builder)
 }
 
 #[derive(Debug, FromDeriveInput)]
-#[darling(forward_attrs(allow, doc, cfg), supports(struct_named))]
+#[darling(
+    attributes(admin),
+    forward_attrs(allow, doc, cfg),
+    supports(struct_named)
+)]
 struct AdminModelOpts {
     ident: syn::Ident,
     data: darling::ast::Data<darling::util::Ignored, FieldOpts>,
+    #[darling(default, with = parse_field_list)]
+    search_fields: Vec<syn::LitStr>,
+    #[darling(default, with = parse_field_list)]
+    list_filter: Vec<syn::LitStr>,
+    #[darling(default, with = parse_field_list)]
+    list_display: Vec<syn::LitStr>,
+    #[darling(default, with = parse_field_list)]
+    ordering: Vec<syn::LitStr>,
+}
+
+/// Parses a list of field names, such as `["title", "content"]`.
+fn parse_field_list(meta: &syn::Meta) -> darling::Result<Vec<syn::LitStr>> {
+    let syn::Meta::NameValue(syn::MetaNameValue {
+        value: syn::Expr::Array(array),
+        ..
+    }) = meta
+    else {
+        return Err(
+            darling::Error::custom("expected a list of field names, e.g. `[\"title\"]`")
+                .with_span(meta),
+        );
+    };
+
+    array
+        .elems
+        .iter()
+        .map(|elem| match elem {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(lit),
+                ..
+            }) => Ok(lit.clone()),
+            _ => {
+                Err(darling::Error::custom("expected a field name string literal").with_span(elem))
+            }
+        })
+        .collect()
 }
 
 impl AdminModelOpts {
@@ -42,6 +87,10 @@ impl AdminModelOpts {
         AdminModelDeriveBuilder {
             name: self.ident.clone(),
             primary_key: None,
+            search_fields: Vec::new(),
+            list_filter_fields: Vec::new(),
+            list_display: Vec::new(),
+            ordering: Vec::new(),
         }
     }
 }
@@ -50,6 +99,29 @@ impl AdminModelOpts {
 struct AdminModelDeriveBuilder {
     name: syn::Ident,
     primary_key: Option<FieldOpts>,
+    search_fields: Vec<syn::Ident>,
+    list_filter_fields: Vec<(syn::Ident, syn::Type)>,
+    list_display: Vec<ListDisplayColumn>,
+    /// The default ordering of the list view; `true` means descending.
+    ordering: Vec<(syn::Ident, bool)>,
+}
+
+/// A column displayed in the admin list view.
+#[derive(Debug)]
+enum ListDisplayColumn {
+    /// A model field; the list view can be sorted by it.
+    Field(syn::Ident),
+    /// A `&self` method of the model returning a value implementing
+    /// [`Display`](std::fmt::Display).
+    Method(syn::Ident),
+}
+
+impl ListDisplayColumn {
+    fn name(&self) -> String {
+        match self {
+            Self::Field(ident) | Self::Method(ident) => ident.to_string(),
+        }
+    }
 }
 
 impl ToTokens for AdminModelDeriveBuilder {
@@ -67,10 +139,230 @@ impl ToTokens for AdminModelDeriveBuilder {
 }
 
 impl AdminModelDeriveBuilder {
-    fn push_field(&mut self, field: &FieldOpts) {
+    fn push_field(&mut self, field: &FieldOpts) -> syn::Result<()> {
         if field.primary_key.is_present() {
+            if self.primary_key.is_some() {
+                return Err(syn::Error::new_spanned(
+                    &field.ident,
+                    "`AdminModel` cannot be derived for models with a composite primary key",
+                ));
+            }
             self.primary_key = Some(field.clone());
         }
+
+        Ok(())
+    }
+
+    fn push_list_fields(&mut self, opts: &AdminModelOpts) -> syn::Result<()> {
+        let fields = opts.fields();
+        let find_field = |name: &syn::LitStr| {
+            fields
+                .iter()
+                .find(|field| {
+                    field
+                        .ident
+                        .as_ref()
+                        .is_some_and(|ident| *ident == name.value())
+                })
+                .ok_or_else(|| {
+                    syn::Error::new(
+                        name.span(),
+                        format!("field `{}` does not exist in the model", name.value()),
+                    )
+                })
+        };
+
+        for name in &opts.search_fields {
+            let field = find_field(name)?;
+            self.search_fields
+                .push(field.ident.clone().expect("Only structs are supported"));
+        }
+        for name in &opts.list_filter {
+            let field = find_field(name)?;
+            self.list_filter_fields.push((
+                field.ident.clone().expect("Only structs are supported"),
+                field.ty.clone(),
+            ));
+        }
+        for name in &opts.list_display {
+            // anything that isn't a field is assumed to be a method
+            let column = match find_field(name) {
+                Ok(field) => ListDisplayColumn::Field(
+                    field.ident.clone().expect("Only structs are supported"),
+                ),
+                Err(_) => ListDisplayColumn::Method(name.parse()?),
+            };
+            self.list_display.push(column);
+        }
+        for name in &opts.ordering {
+            let value = name.value();
+            let (field_name, descending) = match value.strip_prefix('-') {
+                Some(field_name) => (field_name, true),
+                None => (value.as_str(), false),
+            };
+            let field = find_field(&syn::LitStr::new(field_name, name.span()))?;
+            self.ordering.push((
+                field.ident.clone().expect("Only structs are supported"),
+                descending,
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn build_list_query_fn(&self) -> TokenStream {
+        let crate_ident = cot_ident();
+        let name = &self.name;
+
+        let search = if self.search_fields.is_empty() {
+            quote! {}
+        } else {
+            let search_exprs = self.search_fields.iter().map(|field| {
+                quote! {
+                    #crate_ident::db::query::Expr::ilike(
+                        <#name as #crate_ident::db::Model>::Fields::#field.as_expr(),
+                        #crate_ident::db::query::Expr::value(pattern.clone()),
+                    )
+                }
+            });
+
+            quote! {
+                if let ::core::option::Option::Some(pattern) = list_filters.search_pattern() {
+                    let search_exprs = [#(#search_exprs),*];
+                    conditions.extend(
+                        search_exprs
+                            .into_iter()
+                            .reduce(#crate_ident::db::query::Expr::or),
+                    );
+                }
+            }
+        };
+
+        let filters = self.list_filter_fields.iter().map(|(field, ty)| {
+            let field_name = field.to_string();
+
+            quote! {
+                if let ::core::option::Option::Some(value) = list_filters.filter(#field_name) {
+                    let parsed = <#ty as ::std::str::FromStr>::from_str(value).map_err(|_| {
+                        #crate_ident::Error::with_status(
+                            ::std::format!("invalid value for the `{}` filter: `{value}`", #field_name),
+                            #crate_ident::StatusCode::BAD_REQUEST,
+                        )
+                    })?;
+                    conditions.push(#crate_ident::db::query::Expr::eq(
+                        <#name as #crate_ident::db::Model>::Fields::#field.as_expr(),
+                        #crate_ident::db::query::Expr::value(parsed),
+                    ));
+                }
+            }
+        });
+
+        let ordering = self.build_ordering();
+
+        quote! {
+            fn list_query(
+                request: &#crate_ident::request::Request,
+            ) -> #crate_ident::Result<#crate_ident::db::query::Query<#name>> {
+                use #crate_ident::db::Model;
+
+                #[allow(unused_variables, unused_mut)]
+                let list_filters = #crate_ident::admin::ListFilters::from_request(request);
+                #[allow(unused_mut)]
+                let mut conditions: ::std::vec::Vec<#crate_ident::db::query::Expr> = ::std::vec::Vec::new();
+                #search
+                #(#filters)*
+
+                let mut query = <#name as Model>::objects();
+                if let ::core::option::Option::Some(condition) =
+                    conditions.into_iter().reduce(#crate_ident::db::query::Expr::and)
+                {
+                    query.filter(condition);
+                }
+                #ordering
+                Ok(query)
+            }
+        }
+    }
+
+    /// Builds the statements applying the sort order selected in the list view
+    /// (or the default ordering) to `query`.
+    fn build_ordering(&self) -> TokenStream {
+        let name = &self.name;
+
+        let order_by = |field: &syn::Ident, descending: bool| {
+            let order = if descending {
+                quote! { desc }
+            } else {
+                quote! { asc }
+            };
+            quote! {
+                query.order_by(<#name as Model>::Fields::#field.#order());
+            }
+        };
+        let default_ordering = self
+            .ordering
+            .iter()
+            .map(|(field, descending)| order_by(field, *descending));
+        let ordering = if self.sortable_fields().next().is_none() {
+            quote! { #(#default_ordering)* }
+        } else {
+            let sort_arms = self.sortable_fields().map(|field| {
+                let asc_name = field.to_string();
+                let desc_name = format!("-{field}");
+                let asc = order_by(field, false);
+                let desc = order_by(field, true);
+                quote! {
+                    ::core::option::Option::Some(#asc_name) => { #asc }
+                    ::core::option::Option::Some(#desc_name) => { #desc }
+                }
+            });
+            quote! {
+                match list_filters.sort() {
+                    #(#sort_arms)*
+                    _ => { #(#default_ordering)* }
+                }
+            }
+        };
+        // make the order of the objects deterministic, so that the pagination
+        // is consistent
+        let pk_ordering = self.primary_key.as_ref().map(|primary_key| {
+            order_by(
+                primary_key
+                    .ident
+                    .as_ref()
+                    .expect("Only structs are supported"),
+                false,
+            )
+        });
+
+        quote! {
+            #ordering
+            #pk_ordering
+        }
+    }
+
+    fn sortable_fields(&self) -> impl Iterator<Item = &syn::Ident> {
+        self.list_display.iter().filter_map(|column| match column {
+            ListDisplayColumn::Field(field) => Some(field),
+            ListDisplayColumn::Method(_) => None,
+        })
+    }
+
+    fn build_list_display_values_fn(&self) -> TokenStream {
+        let values = self.list_display.iter().map(|column| match column {
+            ListDisplayColumn::Field(field) => quote_spanned! {field.span()=>
+                ::std::string::ToString::to_string(&self.#field)
+            },
+            ListDisplayColumn::Method(method) => quote_spanned! {method.span()=>
+                ::std::string::ToString::to_string(&self.#method())
+            },
+        });
+
+        quote! {
+            fn list_display_values(&self) -> ::std::vec::Vec<::std::string::String> {
+                ::std::vec![#(#values),*]
+            }
+        }
     }
 
     #[expect(clippy::too_many_lines)] // it's mainly the AdminModel impl
@@ -94,6 +386,16 @@ impl AdminModelDeriveBuilder {
             .into_compile_error();
         };
 
+        let search_field_names = self.search_fields.iter().map(ToString::to_string);
+        let list_filter_field_names = self
+            .list_filter_fields
+            .iter()
+            .map(|(field, _)| field.to_string());
+        let list_display_names = self.list_display.iter().map(ListDisplayColumn::name);
+        let sortable_field_names = self.sortable_fields().map(ToString::to_string);
+        let list_query_fn = self.build_list_query_fn();
+        let list_display_values_fn = self.build_list_display_values_fn();
+
         quote! {
             #[#crate_ident::__private::async_trait]
             impl #crate_ident::admin::AdminModel for #name {
@@ -103,7 +405,7 @@ impl AdminModelDeriveBuilder {
                     use #crate_ident::db::Model;
                     use #crate_ident::request::RequestExt;
 
-                    Ok(Self::objects().count(request.context().database()).await?)
+                    Ok(list_query(request)?.count(request.context().database()).await?)
                 }
 
                 async fn get_objects(
@@ -113,7 +415,7 @@ impl AdminModelDeriveBuilder {
                     use #crate_ident::db::Model;
                     use #crate_ident::request::RequestExt;
 
-                    Ok(Self::objects().limit(pagination.limit()).offset(pagination.offset()).all(request.context().database()).await?)
+                    Ok(list_query(request)?.limit(pagination.limit()).offset(pagination.offset()).all(request.context().database()).await?)
                 }
 
                 async fn get_object_by_id(
@@ -127,7 +429,7 @@ impl AdminModelDeriveBuilder {
 
                     let id = parse_id::<Self>(id)?;
 
-                    Ok(#crate_ident::db::query!(Self, $#pk_name == id).get(request.context().database()).await?)
+                    Ok(#crate_ident::db::query!(Self, $#pk_name == id).first(request.context().database()).await?)
                 }
 
                 fn name() -> &'static str {
@@ -138,6 +440,22 @@ impl AdminModelDeriveBuilder {
                     #name_slug
                 }
 
+                fn search_fields() -> &'static [&'static str] {
+                    &[#(#search_field_names),*]
+                }
+
+                fn list_filter_fields() -> &'static [&'static str] {
+                    &[#(#list_filter_field_names),*]
+                }
+
+                fn list_display() -> &'static [&'static str] {
+                    &[#(#list_display_names),*]
+                }
+
+                fn sortable_fields() -> &'static [&'static str] {
+                    &[#(#sortable_field_names),*]
+                }
+
                 fn id(&self) -> ::std::string::String {
                     use ::std::string::ToString;
 
@@ -148,6 +466,8 @@ impl AdminModelDeriveBuilder {
                     ::std::format!("{self}")
                 }
 
+                #list_display_values_fn
+
                 fn form_context() -> ::std::boxed::Box<dyn #crate_ident::form::FormContext>
                 where
                     Self: Sized,
@@ -206,6 +526,8 @@ impl AdminModelDeriveBuilder {
                 }
             }
 
+            #list_query_fn
+
             fn parse_id<T>(id: &str) -> #crate_ident::Result<<T as #crate_ident::db::Model>::PrimaryKey>
             where
                 T: #crate_ident::db::Model,
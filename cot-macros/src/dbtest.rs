@@ -1,62 +1,228 @@
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
-use syn::ItemFn;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{ItemFn, LitStr, Token};
 
-pub(super) fn fn_to_dbtest(test_function_decl: ItemFn) -> syn::Result<TokenStream> {
-    let test_fn = &test_function_decl.sig.ident;
-    let sqlite_ident = format_ident!("{}_sqlite", test_fn);
-    let postgres_ident = format_ident!("{}_postgres", test_fn);
-    let mysql_ident = format_ident!("{}_mysql", test_fn);
+/// A database backend a `#[dbtest]` test can be run against.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Backend {
+    Sqlite,
+    Postgres,
+    MySql,
+}
+
+impl Backend {
+    const ALL: [Self; 3] = [Self::Sqlite, Self::Postgres, Self::MySql];
+
+    fn from_name(name: &LitStr) -> syn::Result<Self> {
+        match name.value().as_str() {
+            "sqlite" => Ok(Self::Sqlite),
+            "postgres" => Ok(Self::Postgres),
+            "mysql" => Ok(Self::MySql),
+            other => Err(syn::Error::new(
+                name.span(),
+                format!(
+                    "unknown database backend `{other}`; expected one of: \
+                    `sqlite`, `postgres`, `mysql`"
+                ),
+            )),
+        }
+    }
+
+    fn test_fn(self, test_function_decl: &ItemFn) -> TokenStream {
+        let test_fn = &test_function_decl.sig.ident;
+
+        match self {
+            Self::Sqlite => {
+                let sqlite_ident = format_ident!("{}_sqlite", test_fn);
+                quote! {
+                    #[::cot::test]
+                    #[cfg_attr(miri, ignore = "unsupported operation: can't call foreign function `sqlite3_open_v2`")]
+                    async fn #sqlite_ident() {
+                        let mut database = cot::test::TestDatabase::new_sqlite()
+                            .await
+                            .expect("failed to create SQLite test database");
+
+                        #test_fn(&mut database).await;
+
+                        database.cleanup().await.expect("failed to clean up SQLite test database");
+
+                        #test_function_decl
+                    }
+                }
+            }
+            Self::Postgres => {
+                let postgres_ident = format_ident!("{}_postgres", test_fn);
+                quote! {
+                    #[ignore = "Tests that use PostgreSQL are ignored by default"]
+                    #[::cot::test]
+                    async fn #postgres_ident() {
+                        let mut database = cot::test::TestDatabase::new_postgres(stringify!(#test_fn))
+                            .await
+                            .expect("failed to create PostgreSQL test database");
+
+                        #test_fn(&mut database).await;
+
+                        database.cleanup().await.expect("failed to clean up PostgreSQL test database");
+
+                        #test_function_decl
+                    }
+                }
+            }
+            Self::MySql => {
+                let mysql_ident = format_ident!("{}_mysql", test_fn);
+                quote! {
+                    #[ignore = "Tests that use MySQL are ignored by default"]
+                    #[::cot::test]
+                    async fn #mysql_ident() {
+                        let mut database = cot::test::TestDatabase::new_mysql(stringify!(#test_fn))
+                            .await
+                            .expect("failed to create MySQL test database");
+
+                        #test_fn(&mut database).await;
+
+                        database.cleanup().await.expect("failed to clean up MySQL test database");
+
+                        #test_function_decl
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The arguments of the `#[dbtest]` macro.
+///
+/// By default, the test is run against all the supported backends; this can
+/// be limited with `#[dbtest(backends = ["sqlite", "postgres"])]`.
+#[derive(Debug)]
+pub(super) struct DbTestArgs {
+    backends: Vec<Backend>,
+}
+
+impl Default for DbTestArgs {
+    fn default() -> Self {
+        Self {
+            backends: Backend::ALL.to_vec(),
+        }
+    }
+}
+
+impl Parse for DbTestArgs {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        if input.is_empty() {
+            return Ok(Self::default());
+        }
+
+        let name: syn::Ident = input.parse()?;
+        if name != "backends" {
+            return Err(syn::Error::new(
+                name.span(),
+                format!("unknown argument `{name}`; expected `backends`"),
+            ));
+        }
+        input.parse::<Token![=]>()?;
+        let content;
+        let brackets = syn::bracketed!(content in input);
+        let names = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+        input.parse::<Option<Token![,]>>()?;
+        if !input.is_empty() {
+            return Err(input.error("unexpected tokens after the `backends` argument"));
+        }
+
+        let mut backends = Vec::with_capacity(names.len());
+        for name in &names {
+            let backend = Backend::from_name(name)?;
+            if backends.contains(&backend) {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!("duplicate database backend `{}`", name.value()),
+                ));
+            }
+            backends.push(backend);
+        }
+        if backends.is_empty() {
+            return Err(syn::Error::new(
+                brackets.span.join(),
+                "at least one database backend must be specified",
+            ));
+        }
 
+        Ok(Self { backends })
+    }
+}
+
+pub(super) fn fn_to_dbtest(
+    args: &DbTestArgs,
+    test_function_decl: &ItemFn,
+) -> syn::Result<TokenStream> {
     if test_function_decl.sig.inputs.len() != 1 {
         return Err(syn::Error::new_spanned(
-            test_function_decl.sig.inputs,
+            &test_function_decl.sig.inputs,
             "Database test function must have exactly one argument",
         ));
     }
 
-    let result = quote! {
-        #[::cot::test]
-        #[cfg_attr(miri, ignore = "unsupported operation: can't call foreign function `sqlite3_open_v2`")]
-        async fn #sqlite_ident() {
-            let mut database = cot::test::TestDatabase::new_sqlite()
-                .await
-                .expect("failed to create SQLite test database");
+    let test_fns = args
+        .backends
+        .iter()
+        .map(|backend| backend.test_fn(test_function_decl));
 
-            #test_fn(&mut database).await;
+    Ok(quote! {
+        #(#test_fns)*
+    })
+}
 
-            database.cleanup().await.expect("failed to clean up SQLite test database");
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
 
-            #test_function_decl
-        }
+    use super::*;
 
-        #[ignore = "Tests that use PostgreSQL are ignored by default"]
-        #[::cot::test]
-        async fn #postgres_ident() {
-            let mut database = cot::test::TestDatabase::new_postgres(stringify!(#test_fn))
-                .await
-                .expect("failed to create PostgreSQL test database");
+    fn generated_fn_names(args: &DbTestArgs) -> Vec<String> {
+        let test_fn: ItemFn = parse_quote! {
+            async fn my_test(db: &mut TestDatabase) {}
+        };
+        let tokens = fn_to_dbtest(args, &test_fn).unwrap();
+        let file: syn::File = syn::parse2(tokens).unwrap();
 
-            #test_fn(&mut database).await;
+        file.items
+            .iter()
+            .map(|item| match item {
+                syn::Item::Fn(item_fn) => item_fn.sig.ident.to_string(),
+                _ => panic!("unexpected item generated: {item:?}"),
+            })
+            .collect()
+    }
 
-            database.cleanup().await.expect("failed to clean up PostgreSQL test database");
+    #[test]
+    fn dbtest_all_backends_by_default() {
+        let args: DbTestArgs = syn::parse2(TokenStream::new()).unwrap();
 
-            #test_function_decl
-        }
+        assert_eq!(
+            generated_fn_names(&args),
+            ["my_test_sqlite", "my_test_postgres", "my_test_mysql"]
+        );
+    }
 
-        #[ignore = "Tests that use MySQL are ignored by default"]
-        #[::cot::test]
-        async fn #mysql_ident() {
-            let mut database = cot::test::TestDatabase::new_mysql(stringify!(#test_fn))
-                .await
-                .expect("failed to create MySQL test database");
+    #[test]
+    fn dbtest_selected_backends() {
+        let args: DbTestArgs = parse_quote!(backends = ["sqlite", "postgres"]);
 
-            #test_fn(&mut database).await;
+        assert_eq!(
+            generated_fn_names(&args),
+            ["my_test_sqlite", "my_test_postgres"]
+        );
+    }
 
-            database.cleanup().await.expect("failed to clean up MySQL test database");
+    #[test]
+    fn dbtest_unknown_backend() {
+        let err = syn::parse2::<DbTestArgs>(quote!(backends = ["oracle"])).unwrap_err();
 
-            #test_function_decl
-        }
-    };
-    Ok(result)
+        assert_eq!(
+            err.to_string(),
+            "unknown database backend `oracle`; expected one of: `sqlite`, `postgres`, `mysql`"
+        );
+    }
 }
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use darling::util::PreservedStrExpr;
+use darling::util::{Override, PreservedStrExpr};
 use darling::{FromDeriveInput, FromField};
 use heck::ToTitleCase;
 use proc_macro2::TokenStream;
@@ -16,19 +16,86 @@ pub(super) fn impl_form_for_struct(ast: &syn::DeriveInput) -> TokenStream {
         }
     };
 
+    if opts.patch.is_some() {
+        let errors: TokenStream = opts
+            .fields()
+            .iter()
+            .filter(|field| option_inner_type(&field.ty).is_none())
+            .map(|field| {
+                syn::Error::new_spanned(&field.ty, "fields of a patch form must be `Option`s")
+                    .to_compile_error()
+            })
+            .collect();
+        if !errors.is_empty() {
+            return errors;
+        }
+    }
+
     let mut builder = opts.as_form_derive_builder();
-    for field in opts.fields() {
+    for field in ordered_fields(opts.fields()) {
         builder.push_field(field);
     }
 
     quote!(#builder)
 }
 
+/// Returns the fields in the order they should be rendered in.
+///
+/// The fields with an explicit `order` come first (sorted by it), followed by
+/// the rest in declaration order. Ungrouped fields are rendered before the
+/// grouped ones, and the groups follow the order of their first field.
+fn ordered_fields(mut fields: Vec<&Field>) -> Vec<&Field> {
+    // the sort is stable, so the fields without `order` keep declaration order
+    fields.sort_by_key(|field| field.order.map_or((true, 0), |order| (false, order)));
+
+    let (mut ordered, grouped): (Vec<_>, Vec<_>) =
+        fields.into_iter().partition(|field| field.group.is_none());
+    let mut groups: Vec<&str> = Vec::new();
+    for field in &grouped {
+        let group = field.group.as_deref().expect("only grouped fields here");
+        if !groups.contains(&group) {
+            groups.push(group);
+        }
+    }
+    for group in groups {
+        ordered.extend(
+            grouped
+                .iter()
+                .filter(|field| field.group.as_deref() == Some(group)),
+        );
+    }
+
+    ordered
+}
+
+/// Returns `T` if the given type is `Option<T>`.
+fn option_inner_type(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        syn::GenericArgument::Type(inner) if args.args.len() == 1 => Some(inner),
+        _ => None,
+    }
+}
+
 #[derive(Debug, FromDeriveInput)]
-#[darling(forward_attrs(allow, doc, cfg), supports(struct_named))]
+#[darling(
+    attributes(form),
+    forward_attrs(allow, doc, cfg),
+    supports(struct_named)
+)]
 struct FormOpts {
     ident: syn::Ident,
     data: darling::ast::Data<darling::util::Ignored, Field>,
+    patch: Option<Override<syn::Path>>,
 }
 
 impl FormOpts {
@@ -49,6 +116,11 @@ impl FormOpts {
             name: self.ident.clone(),
             context_struct_name: format_ident!("{}Context", self.ident),
             context_struct_errors_name: format_ident!("{}ContextErrors", self.ident),
+            patch: self.patch.is_some(),
+            patch_model: self
+                .patch
+                .as_ref()
+                .and_then(|patch| patch.as_ref().explicit().cloned()),
             fields_as_struct_fields: Vec::with_capacity(self.field_count()),
             fields_as_struct_fields_new: Vec::with_capacity(self.field_count()),
             fields_as_context_from_request: Vec::with_capacity(self.field_count()),
@@ -61,7 +133,9 @@ impl FormOpts {
             fields_as_has_errors: Vec::with_capacity(self.field_count()),
             fields_as_dyn_field_ref: Vec::with_capacity(self.field_count()),
             fields_as_display: Vec::with_capacity(self.field_count()),
+            field_groups_as_display: Vec::new(),
             fields_as_display_trait_bound: Vec::with_capacity(self.field_count()),
+            fields_as_assignments: Vec::with_capacity(self.field_count()),
         }
     }
 }
@@ -72,6 +146,9 @@ struct Field {
     ident: Option<syn::Ident>,
     ty: syn::Type,
     opts: Option<HashMap<syn::Ident, PreservedStrExpr>>,
+    widget: Option<syn::Path>,
+    order: Option<i32>,
+    group: Option<String>,
 }
 
 #[derive(Debug)]
@@ -79,6 +156,8 @@ struct FormDeriveBuilder {
     name: syn::Ident,
     context_struct_name: syn::Ident,
     context_struct_errors_name: syn::Ident,
+    patch: bool,
+    patch_model: Option<syn::Path>,
     fields_as_struct_fields: Vec<TokenStream>,
     fields_as_struct_fields_new: Vec<TokenStream>,
     fields_as_context_from_request: Vec<TokenStream>,
@@ -91,7 +170,9 @@ struct FormDeriveBuilder {
     fields_as_has_errors: Vec<TokenStream>,
     fields_as_dyn_field_ref: Vec<TokenStream>,
     fields_as_display: Vec<TokenStream>,
+    field_groups_as_display: Vec<(String, Vec<TokenStream>)>,
     fields_as_display_trait_bound: Vec<TokenStream>,
+    fields_as_assignments: Vec<TokenStream>,
 }
 
 impl ToTokens for FormDeriveBuilder {
@@ -99,12 +180,14 @@ impl ToTokens for FormDeriveBuilder {
         let form_impl = self.build_form_impl();
         let form_context_impl = self.build_form_context_impl();
         let errors_struct = self.build_errors_struct();
+        let patch_form_impl = self.build_patch_form_impl();
 
         let new_tokens = quote! {
             const _: () = {
                 #form_impl
                 #form_context_impl
                 #errors_struct
+                #patch_form_impl
             };
         };
 
@@ -116,14 +199,35 @@ impl FormDeriveBuilder {
     fn push_field(&mut self, field: &Field) {
         let crate_ident = cot_ident();
         let field_ident = field.ident.as_ref().unwrap();
-        let ty = &field.ty;
+        // in patch forms, the fields are `Option`s of the actual form values,
+        // where `None` means the field was not submitted
+        let ty = if self.patch {
+            option_inner_type(&field.ty).expect("patch form fields have been checked")
+        } else {
+            &field.ty
+        };
         let opts = &field.opts;
 
         let name = field_ident.to_string().to_title_case();
 
-        self.fields_as_struct_fields
-            .push(quote!(#field_ident: <#ty as #crate_ident::form::AsFormField>::Type));
+        let inner_field_ty = quote!(<#ty as #crate_ident::form::AsFormField>::Type);
+        let field_ty = match &field.widget {
+            Some(widget) => quote!(#crate_ident::form::WithWidget<#inner_field_ty, #widget>),
+            None => inner_field_ty.clone(),
+        };
 
+        self.fields_as_struct_fields
+            .push(quote!(#field_ident: #field_ty));
+
+        let wrap_in_widget = match &field.widget {
+            Some(widget) => quote! {
+                #crate_ident::form::WithWidget::new(
+                    field,
+                    <#widget as ::core::default::Default>::default(),
+                )
+            },
+            None => quote!(field),
+        };
         self.fields_as_struct_fields_new.push({
             let custom_options_setters: Vec<_> = if let Some(opts) = opts {
                 opts.iter()
@@ -144,26 +248,63 @@ impl FormDeriveBuilder {
                 type CustomOptions = <Field as #crate_ident::form::FormField>::CustomOptions;
                 let mut custom_options: CustomOptions = ::core::default::Default::default();
                 #( #custom_options_setters; )*
-                <#ty as #crate_ident::form::AsFormField>::new_field(options, custom_options)
+                let field = <#ty as #crate_ident::form::AsFormField>::new_field(options, custom_options);
+                #wrap_in_widget
             })
         });
 
+        let mark_submitted = if self.patch {
+            quote!(self.__submitted.insert(stringify!(#field_ident));)
+        } else {
+            quote!()
+        };
         self.fields_as_context_from_request
             .push(quote!(stringify!(#field_ident) => {
+                #mark_submitted
                 #crate_ident::form::FormField::set_value(&mut self.#field_ident, value).await?
             }));
 
         let val_ident = format_ident!("val_{}", field_ident);
-        self.fields_as_from_context_vars.push(quote! {
-            let #val_ident = <#ty as #crate_ident::form::AsFormField>::clean_value(&context.#field_ident).map_err(|error| {
+        let inner_field_ref = if field.widget.is_some() {
+            quote!(#crate_ident::form::WithWidget::field(&context.#field_ident))
+        } else {
+            quote!(&context.#field_ident)
+        };
+        let clean_value = quote! {
+            <#ty as #crate_ident::form::AsFormField>::clean_value(#inner_field_ref).map_err(|error| {
                 context.add_error(#crate_ident::form::FormErrorTarget::Field(stringify!(#field_ident)), error);
             })
-        });
+        };
+        if self.patch {
+            self.fields_as_from_context_vars.push(quote! {
+                let #val_ident = if context.__submitted.contains(stringify!(#field_ident)) {
+                    #clean_value.map(::core::option::Option::Some)
+                } else {
+                    ::core::result::Result::Ok(::core::option::Option::None)
+                }
+            });
+            self.fields_as_to_context.push(quote! {
+                if let ::core::option::Option::Some(value) = &self.#field_ident {
+                    context.#field_ident.set_value(#crate_ident::form::FormFieldValue::new_text(value.to_field_value())).await.expect("Setting value from text should never fail");
+                }
+            });
+        } else {
+            self.fields_as_from_context_vars
+                .push(quote!(let #val_ident = #clean_value));
+            self.fields_as_to_context
+                .push(quote!(context.#field_ident.set_value(#crate_ident::form::FormFieldValue::new_text(self.#field_ident.to_field_value())).await.expect("Setting value from text should never fail")));
+        }
         self.fields_as_from_context.push(
             quote!(#field_ident: #val_ident.expect("Errors should have been returned by now")),
         );
-        self.fields_as_to_context
-            .push(quote!(context.#field_ident.set_value(#crate_ident::form::FormFieldValue::new_text(self.#field_ident.to_field_value())).await.expect("Setting value from text should never fail")));
+
+        if let Some(model) = &self.patch_model {
+            self.fields_as_assignments.push(quote! {
+                if let ::core::option::Option::Some(value) = self.#field_ident {
+                    assignments.push(<#model as #crate_ident::db::Model>::Fields::#field_ident.set(value));
+                }
+            });
+        }
 
         self.fields_as_errors
             .push(quote!(#field_ident: Vec<#crate_ident::form::FormFieldValidationError>));
@@ -180,11 +321,26 @@ impl FormDeriveBuilder {
         self.fields_as_dyn_field_ref
             .push(quote!(&self.#field_ident as &dyn #crate_ident::form::DynFormField));
 
-        self.fields_as_display
-            .push(quote!(::core::fmt::Display::fmt(&self.#field_ident, f)?));
+        let field_display = quote! {
+            ::core::fmt::Display::fmt(&self.#field_ident, f)?;
+            #crate_ident::__private::form::write_errors(
+                f,
+                "field-errors",
+                &self.__errors.#field_ident,
+            )?
+        };
+        match &field.group {
+            None => self.fields_as_display.push(field_display),
+            Some(group) => match self.field_groups_as_display.last_mut() {
+                Some((last_group, fields)) if last_group == group => fields.push(field_display),
+                _ => self
+                    .field_groups_as_display
+                    .push((group.clone(), vec![field_display])),
+            },
+        }
 
         self.fields_as_display_trait_bound
-            .push(quote!(&'dummy <#ty as #crate_ident::form::AsFormField>::Type: ::core::fmt::Display + #crate_ident::__private::askama::filters::HtmlSafe));
+            .push(quote!(&'dummy #field_ty: ::core::fmt::Display + #crate_ident::__private::askama::filters::HtmlSafe));
     }
 
     fn build_form_impl(&self) -> TokenStream {
@@ -248,6 +404,17 @@ impl FormDeriveBuilder {
         let fields_as_has_errors = &self.fields_as_has_errors;
         let fields_as_dyn_field_ref = &self.fields_as_dyn_field_ref;
         let fields_as_display = &self.fields_as_display;
+        let field_groups_as_display =
+            self.field_groups_as_display
+                .iter()
+                .map(|(group, fields_as_display)| {
+                    let legend = group.to_title_case();
+                    quote! {
+                        #crate_ident::__private::form::write_fieldset_start(f, #legend)?;
+                        #( #fields_as_display; )*
+                        f.write_str("</fieldset>")?
+                    }
+                });
 
         // <'dummy> is here because we can't directly create trivial constraints in
         // where clauses
@@ -255,6 +422,14 @@ impl FormDeriveBuilder {
         // and the following comment for the details on the workaround being used here:
         // https://github.com/rust-lang/rust/issues/48214#issuecomment-2557829956
         let fields_as_display_trait_bound = &self.fields_as_display_trait_bound;
+        let (submitted_field, submitted_field_new) = if self.patch {
+            (
+                quote!(__submitted: ::std::collections::HashSet<&'static str>,),
+                quote!(__submitted: ::core::default::Default::default(),),
+            )
+        } else {
+            (quote!(), quote!())
+        };
         let display_where_clause = if fields_as_display_trait_bound.is_empty() {
             quote! {}
         } else {
@@ -272,6 +447,7 @@ impl FormDeriveBuilder {
             #[derive(::core::fmt::Debug)]
             pub struct #context_struct_name {
                 __errors: #context_struct_errors_name,
+                #submitted_field
                 #( #fields_as_struct_fields, )*
             }
 
@@ -281,6 +457,7 @@ impl FormDeriveBuilder {
                 fn new() -> Self {
                     Self {
                         __errors: ::core::default::Default::default(),
+                        #submitted_field_new
                         #( #fields_as_struct_fields_new, )*
                     }
                 }
@@ -351,7 +528,13 @@ impl FormDeriveBuilder {
             #[automatically_derived]
             impl #display_dummy_lifetime_decl ::core::fmt::Display for #context_struct_name #display_where_clause {
                 fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    #crate_ident::__private::form::write_errors(
+                        f,
+                        "form-errors",
+                        &self.__errors.__form,
+                    )?;
                     #( #fields_as_display; )*
+                    #( #field_groups_as_display; )*
 
                     Ok(())
                 }
@@ -362,6 +545,28 @@ impl FormDeriveBuilder {
         }
     }
 
+    fn build_patch_form_impl(&self) -> TokenStream {
+        let Some(model) = &self.patch_model else {
+            return quote! {};
+        };
+        let crate_ident = cot_ident();
+        let name = &self.name;
+        let fields_as_assignments = &self.fields_as_assignments;
+
+        quote! {
+            #[automatically_derived]
+            impl #crate_ident::form::PatchForm for #name {
+                type Model = #model;
+
+                fn into_assignments(self) -> ::std::vec::Vec<#crate_ident::db::query::Assignment> {
+                    let mut assignments = ::std::vec::Vec::new();
+                    #( #fields_as_assignments )*
+                    assignments
+                }
+            }
+        }
+    }
+
     fn build_errors_struct(&self) -> TokenStream {
         let crate_ident = cot_ident();
         let context_struct_errors_name = &self.context_struct_errors_name;
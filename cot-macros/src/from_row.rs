@@ -0,0 +1,79 @@
+use darling::{FromDeriveInput, FromField};
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::cot_ident;
+
+pub(super) fn impl_from_row_for_struct(ast: &syn::DeriveInput) -> TokenStream {
+    let opts = match FromRowOpts::from_derive_input(ast) {
+        Ok(val) => val,
+        Err(err) => {
+            return err.write_errors();
+        }
+    };
+    let cot = cot_ident();
+
+    let struct_name = &opts.ident;
+    let mut generics = opts.generics.clone();
+    if !generics.params.is_empty() {
+        let where_clause = generics.make_where_clause();
+        for field in opts.fields() {
+            let field_type = &field.ty;
+            where_clause
+                .predicates
+                .push(syn::parse_quote!(#field_type: #cot::db::FromDbValue));
+        }
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let initializers = opts.fields().into_iter().map(|field| {
+        let field_name = field
+            .ident
+            .as_ref()
+            .expect("only named fields are supported");
+        let field_type = &field.ty;
+        let column_name = field
+            .rename
+            .clone()
+            .unwrap_or_else(|| field_name.to_string());
+        quote! {
+            #field_name: row.get_by_name::<#field_type>(#column_name)?,
+        }
+    });
+
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics #cot::db::FromRow for #struct_name #ty_generics #where_clause {
+            fn from_row(row: #cot::db::Row) -> #cot::db::Result<Self> {
+                Ok(Self { #(#initializers)* })
+            }
+        }
+    }
+}
+
+#[derive(Debug, FromDeriveInput)]
+#[darling(supports(struct_named))]
+struct FromRowOpts {
+    ident: syn::Ident,
+    generics: syn::Generics,
+    data: darling::ast::Data<darling::util::Ignored, Field>,
+}
+
+impl FromRowOpts {
+    fn fields(&self) -> Vec<&Field> {
+        self.data
+            .as_ref()
+            .take_struct()
+            .expect("Only structs are supported")
+            .fields
+    }
+}
+
+#[derive(Debug, Clone, FromField)]
+#[darling(attributes(row))]
+struct Field {
+    ident: Option<syn::Ident>,
+    ty: syn::Type,
+    /// The name of the column to read the field from, if it's different from
+    /// the field name.
+    rename: Option<String>,
+}
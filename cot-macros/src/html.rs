@@ -0,0 +1,334 @@
+use std::collections::HashSet;
+
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::{Ident, LitStr, Token, braced, parenthesized};
+
+use crate::cot_ident;
+
+/// The input of the `html!` macro: a list of top-level nodes.
+#[derive(Debug)]
+pub(crate) struct HtmlInput {
+    nodes: Vec<Node>,
+}
+
+impl Parse for HtmlInput {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        Ok(Self {
+            nodes: parse_nodes(input)?,
+        })
+    }
+}
+
+#[derive(Debug)]
+enum Node {
+    /// A string literal, escaped at runtime.
+    Text(LitStr),
+    /// A parenthesized or braced Rust expression.
+    Expr(Box<syn::Expr>),
+    /// A nested element.
+    Element(Element),
+}
+
+impl Parse for Node {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(LitStr) {
+            Ok(Self::Text(input.parse()?))
+        } else if lookahead.peek(syn::token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            Ok(Self::Expr(Box::new(content.parse()?)))
+        } else if lookahead.peek(syn::token::Brace) {
+            let content;
+            braced!(content in input);
+            Ok(Self::Expr(Box::new(content.parse()?)))
+        } else if lookahead.peek(Token![<]) {
+            Ok(Self::Element(Element::parse_tag(input)?))
+        } else if lookahead.peek(Ident::peek_any) {
+            Ok(Self::Element(input.parse()?))
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+/// An element: `name attr="value" { children }` or `name attr="value";`, or
+/// `<name attr="value">children</name>` or `<name attr="value" />`.
+#[derive(Debug)]
+struct Element {
+    name: Name,
+    attributes: Vec<Attribute>,
+    children: Vec<Node>,
+}
+
+impl Element {
+    /// Parses an element written with the tag syntax, starting with `<`.
+    fn parse_tag(input: ParseStream<'_>) -> syn::Result<Self> {
+        input.parse::<Token![<]>()?;
+        let name: Name = input.parse()?;
+
+        let mut attributes = Vec::new();
+        while !input.peek(Token![>]) && !input.peek(Token![/]) {
+            if input.is_empty() {
+                return Err(input.error("expected `>` or `/>` after the tag"));
+            }
+            attributes.push(input.parse()?);
+        }
+        check_duplicate_attributes(&attributes)?;
+
+        if input.peek(Token![/]) {
+            input.parse::<Token![/]>()?;
+            input.parse::<Token![>]>()?;
+            return Ok(Self {
+                name,
+                attributes,
+                children: Vec::new(),
+            });
+        }
+        input.parse::<Token![>]>()?;
+
+        let mut children = Vec::new();
+        while !(input.peek(Token![<]) && input.peek2(Token![/])) {
+            if input.is_empty() {
+                return Err(syn::Error::new(
+                    name.span,
+                    format!("unclosed `<{}>` tag", name.value),
+                ));
+            }
+            children.push(input.parse()?);
+        }
+
+        input.parse::<Token![<]>()?;
+        input.parse::<Token![/]>()?;
+        let closing_name: Name = input.parse()?;
+        if closing_name.value != name.value {
+            return Err(syn::Error::new(
+                closing_name.span,
+                format!(
+                    "expected `</{}>`, found `</{}>`",
+                    name.value, closing_name.value
+                ),
+            ));
+        }
+        input.parse::<Token![>]>()?;
+
+        Ok(Self {
+            name,
+            attributes,
+            children,
+        })
+    }
+}
+
+impl Parse for Element {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let name = input.parse()?;
+
+        let mut attributes = Vec::new();
+        while !input.peek(syn::token::Brace) && !input.peek(Token![;]) {
+            if input.is_empty() {
+                return Err(input.error("expected `{` or `;` after the element"));
+            }
+            attributes.push(input.parse()?);
+        }
+        check_duplicate_attributes(&attributes)?;
+
+        let children = if input.peek(Token![;]) {
+            input.parse::<Token![;]>()?;
+            Vec::new()
+        } else {
+            let content;
+            braced!(content in input);
+            parse_nodes(&content)?
+        };
+
+        Ok(Self {
+            name,
+            attributes,
+            children,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Attribute {
+    name: Name,
+    value: Option<AttributeValue>,
+}
+
+impl Parse for Attribute {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let name = input.parse()?;
+        let value = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        Ok(Self { name, value })
+    }
+}
+
+/// Returns an error pointing at the second occurrence of any attribute that
+/// is specified more than once on the same element.
+///
+/// Attribute names are compared case-insensitively, as they are in HTML.
+fn check_duplicate_attributes(attributes: &[Attribute]) -> syn::Result<()> {
+    let mut seen = HashSet::new();
+    for attribute in attributes {
+        if !seen.insert(attribute.name.value.to_ascii_lowercase()) {
+            return Err(syn::Error::new(
+                attribute.name.span,
+                format!("duplicate attribute `{}`", attribute.name.value),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug)]
+enum AttributeValue {
+    Lit(LitStr),
+    Expr(Box<syn::Expr>),
+}
+
+impl Parse for AttributeValue {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(LitStr) {
+            Ok(Self::Lit(input.parse()?))
+        } else if lookahead.peek(syn::token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            Ok(Self::Expr(Box::new(content.parse()?)))
+        } else if lookahead.peek(syn::token::Brace) {
+            let content;
+            braced!(content in input);
+            Ok(Self::Expr(Box::new(content.parse()?)))
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+/// A tag or attribute name, such as `div` or `data-user-id`.
+///
+/// Keywords (e.g. `type`, `for`) are accepted, as are hyphen-separated
+/// segments.
+#[derive(Debug)]
+struct Name {
+    value: String,
+    span: Span,
+}
+
+impl Parse for Name {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let first = Ident::parse_any(input)?;
+        let span = first.span();
+        let mut value = first.to_string();
+        while input.peek(Token![-]) {
+            input.parse::<Token![-]>()?;
+            value.push('-');
+            value.push_str(&Ident::parse_any(input)?.to_string());
+        }
+
+        Ok(Self { value, span })
+    }
+}
+
+fn parse_nodes(input: ParseStream<'_>) -> syn::Result<Vec<Node>> {
+    let mut nodes = Vec::new();
+    while !input.is_empty() {
+        nodes.push(input.parse()?);
+    }
+    Ok(nodes)
+}
+
+pub(super) fn html_to_tokens(input: &HtmlInput) -> TokenStream {
+    let crate_name = cot_ident();
+    let output = Ident::new("__cot_html", Span::mixed_site());
+    let nodes = input
+        .nodes
+        .iter()
+        .map(|node| {
+            let html = node_to_html(node);
+            quote! { #output.push_str(#html.as_str()); }
+        })
+        .collect::<Vec<_>>();
+
+    quote! {
+        {
+            let mut #output = ::std::string::String::new();
+            #(#nodes)*
+            #crate_name::html::Html::new(#output)
+        }
+    }
+}
+
+/// Generates an expression that evaluates to the `Html` of a single node.
+fn node_to_html(node: &Node) -> TokenStream {
+    let crate_name = cot_ident();
+    match node {
+        Node::Text(text) => {
+            quote! { #crate_name::__private::html::escape(#text) }
+        }
+        Node::Expr(expr) => {
+            quote_spanned! {expr.span()=>
+                {
+                    #[allow(unused_imports)]
+                    use #crate_name::__private::html::{InterpolateEscaped as _, InterpolateRaw as _};
+                    (&#crate_name::__private::html::Interpolation(&(#expr))).to_html()
+                }
+            }
+        }
+        Node::Element(element) => {
+            let tag = element_to_tokens(element);
+            quote! { #tag.render() }
+        }
+    }
+}
+
+/// Generates an expression that evaluates to the `HtmlTag` of an element.
+fn element_to_tokens(element: &Element) -> TokenStream {
+    let crate_name = cot_ident();
+    let tag = Ident::new("__cot_tag", Span::mixed_site());
+    let name = LitStr::new(&element.name.value, element.name.span);
+
+    let attributes = element.attributes.iter().map(|attribute| {
+        let name = LitStr::new(&attribute.name.value, attribute.name.span);
+        match &attribute.value {
+            None => quote! { #tag.bool_attr(#name); },
+            Some(AttributeValue::Lit(value)) => quote! { #tag.attr(#name, #value); },
+            Some(AttributeValue::Expr(value)) => quote_spanned! {value.span()=>
+                #tag.attr(#name, ::std::string::ToString::to_string(&(#value)));
+            },
+        }
+    });
+
+    let children = element.children.iter().map(|child| match child {
+        Node::Text(text) => quote! { #tag.push_str(#text); },
+        Node::Element(element) => {
+            let child = element_to_tokens(element);
+            quote! { #tag.push_tag(#child); }
+        }
+        Node::Expr(_) => {
+            let html = node_to_html(child);
+            quote! { #tag.push_html(#html); }
+        }
+    });
+
+    quote! {
+        {
+            let mut #tag = #crate_name::html::HtmlTag::new(#name);
+            #(#attributes)*
+            #(#children)*
+            #tag
+        }
+    }
+}
@@ -4,6 +4,8 @@ mod cache;
 mod dbtest;
 mod form;
 mod from_request;
+mod from_row;
+mod html;
 mod main_fn;
 mod migration_op;
 mod model;
@@ -20,9 +22,11 @@ use syn::{DeriveInput, ItemFn, parse_macro_input};
 
 use crate::admin::impl_admin_model_for_struct;
 use crate::api_response_enum::{impl_api_operation_response_for_enum, impl_into_response_for_enum};
-use crate::dbtest::fn_to_dbtest;
+use crate::dbtest::{DbTestArgs, fn_to_dbtest};
 use crate::form::impl_form_for_struct;
 use crate::from_request::impl_from_request_head_for_struct;
+use crate::from_row::impl_from_row_for_struct;
+use crate::html::{HtmlInput, html_to_tokens};
 use crate::main_fn::{fn_to_cot_e2e_test, fn_to_cot_main, fn_to_cot_test};
 use crate::migration_op::fn_to_migration_op;
 use crate::model::impl_model_for_struct;
@@ -37,7 +41,7 @@ pub fn derive_form(input: TokenStream) -> TokenStream {
     token_stream.into()
 }
 
-#[proc_macro_derive(AdminModel)]
+#[proc_macro_derive(AdminModel, attributes(admin))]
 pub fn derive_admin_model(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
     let token_stream = impl_admin_model_for_struct(&ast);
@@ -77,10 +81,83 @@ pub fn query(input: TokenStream) -> TokenStream {
     query_to_tokens(query_input).into()
 }
 
+/// Builds `cot::html::Html` from a markup-like syntax.
+///
+/// Elements are written as `name attributes { children }`, or as
+/// `name attributes;` for elements without children. Alternatively, they can
+/// be written using the tag syntax known from HTML: `<name attributes>
+/// children </name>`, or `<name attributes />` for elements without children.
+/// The two syntaxes can be mixed freely.
+///
+/// Attribute values are either string literals or expressions in parentheses
+/// or braces; an attribute without a value is rendered as a boolean attribute.
+/// Children are string literals, nested elements, or expressions in
+/// parentheses or braces.
+///
+/// String literals and interpolated values are HTML-escaped, except for
+/// `Html` and `HtmlTag` values, which are embedded as-is. To insert a
+/// pre-sanitized HTML string, wrap it in `Html::new`.
+///
+/// # Examples
+///
+/// ```
+/// use cot::html;
+/// use cot::html::Html;
+///
+/// let name = "<Alice>";
+/// let greeting = Html::new("<b>Hi</b>");
+/// let html = html! {
+///     div class="container" data-id=(42) {
+///         (greeting) ", " (name)
+///         input type="checkbox" checked;
+///     }
+/// };
+///
+/// assert_eq!(
+///     html.as_str(),
+///     "<div class=\"container\" data-id=\"42\"><b>Hi</b>, &#60;Alice&#62;\
+///      <input type=\"checkbox\" checked/></div>"
+/// );
+/// ```
+///
+/// The same markup, using the tag syntax:
+///
+/// ```
+/// use cot::html;
+/// use cot::html::Html;
+///
+/// let name = "<Alice>";
+/// let greeting = Html::new("<b>Hi</b>");
+/// let html = html! {
+///     <div class="container" data-id={42}>
+///         {greeting} ", " {name}
+///         <input type="checkbox" checked />
+///     </div>
+/// };
+///
+/// assert_eq!(
+///     html.as_str(),
+///     "<div class=\"container\" data-id=\"42\"><b>Hi</b>, &#60;Alice&#62;\
+///      <input type=\"checkbox\" checked/></div>"
+/// );
+/// ```
+#[proc_macro]
+pub fn html(input: TokenStream) -> TokenStream {
+    let html_input = parse_macro_input!(input as HtmlInput);
+    html_to_tokens(&html_input).into()
+}
+
+/// Runs a database test against each of the supported database backends.
+///
+/// By default, one test function is generated for each of SQLite, PostgreSQL
+/// and MySQL. The list of backends can be limited with
+/// `#[dbtest(backends = ["sqlite", "postgres"])]`. The PostgreSQL and MySQL
+/// tests are ignored by default, since they need a running database server.
 #[proc_macro_attribute]
-pub fn dbtest(_args: TokenStream, input: TokenStream) -> TokenStream {
+pub fn dbtest(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as DbTestArgs);
     let fn_input = parse_macro_input!(input as ItemFn);
-    fn_to_dbtest(fn_input)
+    fn_to_dbtest(&args, &fn_input)
         .unwrap_or_else(syn::Error::into_compile_error)
         .into()
 }
@@ -176,6 +253,13 @@ pub fn derive_from_request_head(input: TokenStream) -> TokenStream {
     token_stream.into()
 }
 
+#[proc_macro_derive(FromRow, attributes(row))]
+pub fn derive_from_row(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    let token_stream = impl_from_row_for_struct(&ast);
+    token_stream.into()
+}
+
 #[proc_macro_derive(SelectChoice, attributes(select_choice))]
 pub fn derive_select_choice(input: TokenStream) -> TokenStream {
     let ast = syn::parse_macro_input!(input as DeriveInput);
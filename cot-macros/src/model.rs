@@ -1,4 +1,6 @@
-use cot_codegen::model::{Field, Model, ModelArgs, ModelOpts, ModelType};
+use cot_codegen::model::{
+    CREATED_AT_FIELD, Field, Model, ModelArgs, ModelOpts, ModelType, UPDATED_AT_FIELD,
+};
 use cot_codegen::symbol_resolver::{SymbolResolver, VisibleSymbol, VisibleSymbolKind};
 use darling::FromMeta;
 use darling::ast::NestedMeta;
@@ -75,13 +77,18 @@ struct ModelBuilder {
     name: Ident,
     vis: syn::Visibility,
     table_name: String,
-    pk_field: Field,
+    pk_fields: Vec<Field>,
     fields_struct_name: Ident,
     fields_as_columns: Vec<TokenStream>,
     fields_as_from_db: Vec<TokenStream>,
     fields_as_update_from_db: Vec<TokenStream>,
     fields_as_get_values: Vec<TokenStream>,
     fields_as_field_refs: Vec<TokenStream>,
+    fields_as_apply_defaults: Vec<TokenStream>,
+    fields_as_load_related: Vec<TokenStream>,
+    fields_as_generate_slugs: Vec<TokenStream>,
+    updated_at_ty: Option<syn::Type>,
+    created_at_column: Option<String>,
 }
 
 impl ToTokens for ModelBuilder {
@@ -109,13 +116,26 @@ impl ModelBuilder {
             name: model.name.clone(),
             vis: model.vis,
             table_name,
-            pk_field: model.pk_field.clone(),
+            pk_fields: model.pk_fields.clone(),
             fields_struct_name: format_ident!("{}Fields", model.name),
             fields_as_columns: Vec::with_capacity(field_count),
             fields_as_from_db: Vec::with_capacity(field_count),
             fields_as_update_from_db: Vec::with_capacity(field_count),
             fields_as_get_values: Vec::with_capacity(field_count),
             fields_as_field_refs: Vec::with_capacity(field_count),
+            fields_as_apply_defaults: Vec::new(),
+            fields_as_load_related: Vec::new(),
+            fields_as_generate_slugs: Vec::new(),
+            updated_at_ty: model
+                .fields
+                .iter()
+                .find(|field| model.timestamps && field.name == UPDATED_AT_FIELD)
+                .map(|field| field.ty.clone()),
+            created_at_column: model
+                .fields
+                .iter()
+                .find(|field| model.timestamps && field.name == CREATED_AT_FIELD)
+                .map(|field| field.column_name.clone()),
         };
         for field in &model.fields {
             model_builder.push_field(field);
@@ -156,6 +176,189 @@ impl ModelBuilder {
             pub const #name: #orm_ident::query::FieldRef<#ty> =
                 #orm_ident::query::FieldRef::<#ty>::new(#orm_ident::Identifier::new(#column_name));
         ));
+
+        if field.foreign_key.is_some() {
+            self.fields_as_load_related.push(quote!(
+                #column_name => #orm_ident::RelatedField::load_related(&mut self.#name, db_row)
+            ));
+        }
+
+        if let Some(default) = &field.default
+            && default.nullable
+        {
+            let expr = &default.expr;
+            self.fields_as_apply_defaults.push(quote!(
+                if self.#name.is_none() {
+                    self.#name = ::core::option::Option::Some(
+                        #orm_ident::query::IntoField::into_field(#expr)
+                    );
+                }
+            ));
+        }
+
+        if let Some(source) = &field.slugify_from {
+            self.fields_as_generate_slugs.push(quote!(
+                if self.#name.is_empty() {
+                    let source = ::std::string::ToString::to_string(&self.#source);
+                    let primary_key = #orm_ident::ModelPrimaryKey::to_db_field_values(
+                        &#orm_ident::Model::primary_key(self)
+                    );
+                    self.#name = slugs
+                        .generate::<Self>(
+                            #orm_ident::Identifier::new(#column_name),
+                            &source,
+                            primary_key,
+                        )
+                        .await?;
+                }
+            ));
+        }
+    }
+
+    #[must_use]
+    fn build_generate_slugs(&self) -> TokenStream {
+        if self.fields_as_generate_slugs.is_empty() {
+            return TokenStream::new();
+        }
+
+        let orm_ident = orm_ident();
+        let fields_as_generate_slugs = &self.fields_as_generate_slugs;
+
+        quote! {
+            async fn generate_slugs(
+                &mut self,
+                slugs: &mut #orm_ident::SlugGenerator<'_>,
+            ) -> #orm_ident::Result<()> {
+                #(#fields_as_generate_slugs)*
+                Ok(())
+            }
+        }
+    }
+
+    #[must_use]
+    fn build_load_related(&self) -> TokenStream {
+        if self.fields_as_load_related.is_empty() {
+            return TokenStream::new();
+        }
+
+        let orm_ident = orm_ident();
+        let fields_as_load_related = &self.fields_as_load_related;
+
+        quote! {
+            fn load_related(
+                &mut self,
+                column: #orm_ident::Identifier,
+                db_row: &#orm_ident::Row,
+            ) -> #orm_ident::Result<()> {
+                match column.as_str() {
+                    #(#fields_as_load_related,)*
+                    _ => panic!("Unknown foreign key column: {}", column),
+                }
+            }
+        }
+    }
+
+    #[must_use]
+    fn build_timestamps(&self) -> TokenStream {
+        let (Some(updated_at_ty), Some(created_at_column)) =
+            (&self.updated_at_ty, &self.created_at_column)
+        else {
+            return TokenStream::new();
+        };
+
+        let crate_ident = cot_ident();
+        let orm_ident = orm_ident();
+        let fields_struct_name = &self.fields_struct_name;
+        let created_at = format_ident!("{}", CREATED_AT_FIELD);
+        let updated_at = format_ident!("{}", UPDATED_AT_FIELD);
+
+        quote! {
+            const CREATED_AT_COLUMN: ::core::option::Option<#orm_ident::Identifier> =
+                ::core::option::Option::Some(#orm_ident::Identifier::new(#created_at_column));
+
+            fn update_timestamps(
+                &mut self,
+                now: #crate_ident::__private::chrono::DateTime<#crate_ident::__private::chrono::Utc>,
+                inserting: bool,
+            ) {
+                if inserting {
+                    self.#created_at = #orm_ident::TimestampField::from_utc(now);
+                }
+                self.#updated_at = #orm_ident::TimestampField::from_utc(now);
+            }
+
+            fn timestamp_assignments(
+                now: #crate_ident::__private::chrono::DateTime<#crate_ident::__private::chrono::Utc>,
+            ) -> Vec<#orm_ident::query::Assignment> {
+                vec![
+                    #fields_struct_name::#updated_at
+                        .set(<#updated_at_ty as #orm_ident::TimestampField>::from_utc(now)),
+                ]
+            }
+        }
+    }
+
+    #[must_use]
+    fn build_primary_key(&self) -> TokenStream {
+        let orm_ident = orm_ident();
+
+        if let [pk_field] = self.pk_fields.as_slice() {
+            let pk_field_name = &pk_field.name;
+            let pk_type = &pk_field.ty;
+
+            return quote! {
+                type PrimaryKey = #pk_type;
+
+                fn primary_key(&self) -> Self::PrimaryKey {
+                    ::core::clone::Clone::clone(&self.#pk_field_name)
+                }
+
+                fn set_primary_key(&mut self, primary_key: Self::PrimaryKey) {
+                    self.#pk_field_name = primary_key;
+                }
+
+                async fn get_by_primary_key<DB: #orm_ident::DatabaseBackend>(
+                    db: &DB,
+                    pk: Self::PrimaryKey,
+                ) -> #orm_ident::Result<Option<Self>> {
+                    #orm_ident::query!(Self, $#pk_field_name == pk)
+                        .first(db)
+                        .await
+                }
+            };
+        }
+
+        let pk_field_names: Vec<_> = self.pk_fields.iter().map(|field| &field.name).collect();
+        let pk_types = self.pk_fields.iter().map(|field| &field.ty);
+        let pk_bindings: Vec<_> = (0..self.pk_fields.len())
+            .map(|index| format_ident!("pk_{}", index))
+            .collect();
+        let pk_filter = std::iter::zip(&pk_field_names, &pk_bindings)
+            .map(|(name, binding)| quote!($#name == #binding))
+            .reduce(|filter, condition| quote!(#filter && #condition))
+            .expect("composite primary key should have at least two fields");
+
+        quote! {
+            type PrimaryKey = (#(#pk_types,)*);
+
+            fn primary_key(&self) -> Self::PrimaryKey {
+                (#(::core::clone::Clone::clone(&self.#pk_field_names),)*)
+            }
+
+            fn set_primary_key(&mut self, primary_key: Self::PrimaryKey) {
+                (#(self.#pk_field_names,)*) = primary_key;
+            }
+
+            async fn get_by_primary_key<DB: #orm_ident::DatabaseBackend>(
+                db: &DB,
+                pk: Self::PrimaryKey,
+            ) -> #orm_ident::Result<Option<Self>> {
+                let (#(#pk_bindings,)*) = pk;
+                #orm_ident::query!(Self, #pk_filter)
+                    .first(db)
+                    .await
+            }
+        }
     }
 
     #[must_use]
@@ -168,34 +371,43 @@ impl ModelBuilder {
         let table_name = &self.table_name;
         let fields_struct_name = &self.fields_struct_name;
         let fields_as_columns = &self.fields_as_columns;
-        let pk_field_name = &self.pk_field.name;
-        let pk_column_name = &self.pk_field.column_name;
-        let pk_type = &self.pk_field.ty;
+        let primary_key_name = if let [pk_field] = self.pk_fields.as_slice() {
+            let pk_column_name = &pk_field.column_name;
+            quote!(#orm_ident::Identifier::new(#pk_column_name))
+        } else {
+            let message = format!(
+                "`{name}` has a composite primary key, so `PRIMARY_KEY_NAME` cannot be used; \
+                use `PRIMARY_KEY_NAMES` instead"
+            );
+            quote!(::core::panic!(#message))
+        };
+        let pk_column_names = self.pk_fields.iter().map(|field| &field.column_name);
+        let primary_key_impl = self.build_primary_key();
         let fields_as_from_db = &self.fields_as_from_db;
         let fields_as_update_from_db = &self.fields_as_update_from_db;
         let fields_as_get_values = &self.fields_as_get_values;
+        let fields_as_apply_defaults = &self.fields_as_apply_defaults;
+        let load_related = self.build_load_related();
+        let generate_slugs = self.build_generate_slugs();
+        let timestamps = self.build_timestamps();
 
         quote! {
             #[#crate_ident::__private::async_trait]
             #[automatically_derived]
             impl #orm_ident::Model for #name {
                 type Fields = #fields_struct_name;
-                type PrimaryKey = #pk_type;
 
                 const COLUMNS: &'static [#orm_ident::Column] = &[
                     #(#fields_as_columns,)*
                 ];
                 const APP_NAME: &'static str = #app_name;
                 const TABLE_NAME: #orm_ident::Identifier = #orm_ident::Identifier::new(#table_name);
-                const PRIMARY_KEY_NAME: #orm_ident::Identifier = #orm_ident::Identifier::new(#pk_column_name);
-
-                fn primary_key(&self) -> &Self::PrimaryKey {
-                    &self.#pk_field_name
-                }
+                const PRIMARY_KEY_NAME: #orm_ident::Identifier = #primary_key_name;
+                const PRIMARY_KEY_NAMES: &'static [#orm_ident::Identifier] = &[
+                    #(#orm_ident::Identifier::new(#pk_column_names),)*
+                ];
 
-                fn set_primary_key(&mut self, primary_key: Self::PrimaryKey) {
-                    self.#pk_field_name = primary_key;
-                }
+                #primary_key_impl
 
                 fn from_db(db_row: #orm_ident::Row) -> #orm_ident::Result<Self> {
                     Ok(Self {
@@ -224,14 +436,15 @@ impl ModelBuilder {
                         .collect()
                 }
 
-                async fn get_by_primary_key<DB: #orm_ident::DatabaseBackend>(
-                    db: &DB,
-                    pk: Self::PrimaryKey,
-                ) -> #orm_ident::Result<Option<Self>> {
-                    #orm_ident::query!(Self, $#pk_field_name == pk)
-                        .get(db)
-                        .await
+                fn apply_defaults(&mut self) {
+                    #(#fields_as_apply_defaults)*
                 }
+
+                #load_related
+
+                #generate_slugs
+
+                #timestamps
             }
         }
     }
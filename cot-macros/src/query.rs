@@ -80,6 +80,10 @@ pub(super) fn expr_to_tokens(model_name: &syn::Type, expr: Expr) -> TokenStream
             )
             .to_compile_error(),
         },
+        Expr::Not { expr, .. } => {
+            let expr = expr_to_tokens(model_name, *expr);
+            quote!(#crate_name::db::query::Expr::not(#expr))
+        }
         Expr::And(lhs, rhs) => {
             let lhs = expr_to_tokens(model_name, *lhs);
             let rhs = expr_to_tokens(model_name, *rhs);
@@ -96,6 +100,10 @@ pub(super) fn expr_to_tokens(model_name: &syn::Type, expr: Expr) -> TokenStream
         Expr::Lte(lhs, rhs) => handle_binary_comparison(model_name, *lhs, *rhs, "lte", "ExprOrd"),
         Expr::Gt(lhs, rhs) => handle_binary_comparison(model_name, *lhs, *rhs, "gt", "ExprOrd"),
         Expr::Gte(lhs, rhs) => handle_binary_comparison(model_name, *lhs, *rhs, "gte", "ExprOrd"),
+        Expr::In(lhs, rhs) => handle_in(model_name, *lhs, &rhs),
+        Expr::Like(lhs, rhs) => {
+            handle_binary_comparison(model_name, *lhs, *rhs, "like", "ExprLike")
+        }
         Expr::Add(lhs, rhs) => handle_binary_comparison(model_name, *lhs, *rhs, "add", "ExprAdd"),
         Expr::Sub(lhs, rhs) => handle_binary_comparison(model_name, *lhs, *rhs, "sub", "ExprSub"),
         Expr::Mul(lhs, rhs) => handle_binary_comparison(model_name, *lhs, *rhs, "mul", "ExprMul"),
@@ -124,3 +132,29 @@ fn handle_binary_comparison(
     let rhs = expr_to_tokens(model_name, rhs);
     quote!(#crate_name::db::query::Expr::#bin_fn(#lhs, #rhs))
 }
+
+fn handle_in(model_name: &syn::Type, lhs: Expr, rhs: &Expr) -> TokenStream {
+    let crate_name = cot_ident();
+
+    let Some(rhs_tokens) = rhs.as_tokens() else {
+        return syn::Error::new_spanned(
+            rhs.as_tokens_full(),
+            "the right-hand side of `in` must be a list of values and can't reference database fields",
+        )
+        .to_compile_error();
+    };
+
+    if let Expr::FieldRef { ref field_name, .. } = lhs {
+        return quote!(#crate_name::db::query::ExprIn::is_in(<#model_name as #crate_name::db::Model>::Fields::#field_name, #rhs_tokens));
+    }
+
+    let lhs = expr_to_tokens(model_name, lhs);
+    quote! {
+        #crate_name::db::query::Expr::is_in(
+            #lhs,
+            ::std::iter::IntoIterator::into_iter(#rhs_tokens)
+                .map(#crate_name::db::query::Expr::value)
+                .collect(),
+        )
+    }
+}
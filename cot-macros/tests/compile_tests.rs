@@ -29,7 +29,7 @@ fn attr_model() {
     t.compile_fail("tests/ui/attr_model_enum.rs");
     t.compile_fail("tests/ui/attr_model_generic.rs");
     t.compile_fail("tests/ui/attr_model_no_pk.rs");
-    t.compile_fail("tests/ui/attr_model_multiple_pks.rs");
+    t.compile_fail("tests/ui/attr_model_composite_pk_auto.rs");
 }
 
 #[rustversion::attr(
@@ -45,6 +45,11 @@ fn derive_admin_model() {
     let t = trybuild::TestCases::new();
     t.pass("tests/ui/derive_admin_model.rs");
     t.pass("tests/ui/derive_admin_model_derive_first.rs");
+    t.pass("tests/ui/derive_admin_model_list_filters.rs");
+    t.pass("tests/ui/derive_admin_model_list_display.rs");
+    t.compile_fail("tests/ui/derive_admin_model_invalid_field.rs");
+    t.compile_fail("tests/ui/derive_admin_model_invalid_ordering.rs");
+    t.compile_fail("tests/ui/derive_admin_model_composite_pk.rs");
 }
 
 #[rustversion::attr(
@@ -66,6 +71,20 @@ fn func_query() {
     t.compile_fail("tests/ui/func_query_method_call_on_db_field.rs");
 }
 
+#[rustversion::attr(
+    not(nightly),
+    ignore = "only test on nightly for consistent error messages"
+)]
+#[test]
+#[cfg_attr(
+    miri,
+    ignore = "unsupported operation: extern static `pidfd_spawnp` is not supported by Miri"
+)]
+fn func_html() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/func_html_duplicate_attribute.rs");
+}
+
 #[rustversion::attr(
     not(nightly),
     ignore = "only test on nightly for consistent error messages"
@@ -96,6 +115,20 @@ fn derive_from_struct() {
     t.compile_fail("tests/ui/derive_from_request_head_enum.rs");
 }
 
+#[rustversion::attr(
+    not(nightly),
+    ignore = "only test on nightly for consistent error messages"
+)]
+#[test]
+#[cfg_attr(
+    miri,
+    ignore = "unsupported operation: extern static `pidfd_spawnp` is not supported by Miri"
+)]
+fn derive_from_row() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/derive_from_row.rs");
+}
+
 #[rustversion::attr(
     not(nightly),
     ignore = "only test on nightly for consistent error messages"
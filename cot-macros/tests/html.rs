@@ -0,0 +1,160 @@
+use cot::html;
+use cot::html::{Html, HtmlTag};
+
+#[test]
+fn test_html_empty() {
+    assert_eq!(html! {}, Html::new(""));
+}
+
+#[test]
+fn test_html_void_element() {
+    assert_eq!(html! { br; }.as_str(), "<br/>");
+}
+
+#[test]
+fn test_html_nested() {
+    let html = html! {
+        div {
+            ul {
+                li { "one" }
+                li { "two" }
+            }
+        }
+    };
+
+    assert_eq!(
+        html.as_str(),
+        "<div><ul><li>one</li><li>two</li></ul></div>"
+    );
+}
+
+#[test]
+fn test_html_multiple_top_level() {
+    let html = html! {
+        h1 { "Title" }
+        "text & more"
+        p { "Body" }
+    };
+
+    assert_eq!(html.as_str(), "<h1>Title</h1>text &#38; more<p>Body</p>");
+}
+
+#[test]
+fn test_html_attributes() {
+    let id = 5;
+    let html = html! {
+        input type="text" name="user-name" data-user-id=(id) value=("<x>") disabled;
+    };
+
+    assert_eq!(
+        html.as_str(),
+        "<input type=\"text\" name=\"user-name\" data-user-id=\"5\" value=\"&#60;x&#62;\" \
+         disabled/>"
+    );
+}
+
+#[test]
+fn test_html_interpolation_escaped() {
+    struct User {
+        name: String,
+    }
+
+    let user = User {
+        name: "<script>alert(1)</script>".to_owned(),
+    };
+    let html = html! {
+        div class="container" { (user.name) " " (&user.name) " " (42) }
+    };
+
+    assert_eq!(
+        html.as_str(),
+        "<div class=\"container\">&#60;script&#62;alert(1)&#60;/script&#62; \
+         &#60;script&#62;alert(1)&#60;/script&#62; 42</div>"
+    );
+}
+
+#[test]
+fn test_html_interpolation_raw() {
+    let raw = Html::new("<b>bold</b>");
+    let mut tag = HtmlTag::new("i");
+    tag.push_str("italic");
+
+    let html = html! {
+        p { (raw) (&raw) (tag) (&tag) }
+        (raw)
+    };
+
+    assert_eq!(
+        html.as_str(),
+        "<p><b>bold</b><b>bold</b><i>italic</i><i>italic</i></p><b>bold</b>"
+    );
+}
+
+#[test]
+fn test_html_nested_macro() {
+    let items = ["a", "<b>"];
+    let list = items
+        .iter()
+        .map(|item| html! { li { (item) } })
+        .map(|html| html.0)
+        .collect::<String>();
+
+    let html = html! { ul { (Html::new(list)) } };
+
+    assert_eq!(html.as_str(), "<ul><li>a</li><li>&#60;b&#62;</li></ul>");
+}
+
+#[test]
+fn test_html_tag_syntax() {
+    let text = "<Bob>";
+    let html = html! {
+        <div class="container">{ text } <span>{ "hi" }</span></div>
+    };
+
+    assert_eq!(
+        html.as_str(),
+        "<div class=\"container\">&#60;Bob&#62;<span>hi</span></div>"
+    );
+}
+
+#[test]
+fn test_html_tag_syntax_void_element() {
+    let name = "user-name";
+    let html = html! {
+        <input type="text" name={name} value={"<x>"} disabled />
+    };
+
+    assert_eq!(
+        html.as_str(),
+        "<input type=\"text\" name=\"user-name\" value=\"&#60;x&#62;\" disabled/>"
+    );
+}
+
+#[test]
+fn test_html_tag_syntax_raw() {
+    let raw = Html::new("<b>bold</b>");
+    let html = html! {
+        <p>{raw} {"<b>escaped</b>"}</p>
+    };
+
+    assert_eq!(
+        html.as_str(),
+        "<p><b>bold</b>&#60;b&#62;escaped&#60;/b&#62;</p>"
+    );
+}
+
+#[test]
+fn test_html_tag_syntax_mixed() {
+    let html = html! {
+        <ul>
+            li { "one" }
+            <li>"two"</li>
+        </ul>
+        p { <em>"three"</em> }
+    };
+
+    assert_eq!(
+        html.as_str(),
+        "<ul><li>one</li><li>two</li></ul><p><em>three</em></p>"
+    );
+}
@@ -1,4 +1,6 @@
-use cot::db::query::{Expr, ExprAdd, ExprDiv, ExprEq, ExprMul, ExprOrd, ExprSub, Query};
+use cot::db::query::{
+    Expr, ExprAdd, ExprDiv, ExprEq, ExprIn, ExprLike, ExprMul, ExprOrd, ExprSub, Query,
+};
 use cot::db::{model, query};
 
 #[model]
@@ -74,6 +76,96 @@ fn test_query_comparison() {
     );
 }
 
+#[test]
+fn test_query_bool_combinators() {
+    assert_eq!(
+        Query::<MyModel>::new().filter(Expr::or(
+            ExprEq::eq(<MyModel as cot::db::Model>::Fields::id, 1),
+            Expr::and(
+                ExprEq::eq(<MyModel as cot::db::Model>::Fields::id, 2),
+                Expr::not(ExprEq::eq(<MyModel as cot::db::Model>::Fields::name, "a"))
+            )
+        )),
+        query!(MyModel, $id == 1 || $id == 2 && !($name == "a"))
+    );
+
+    assert_eq!(
+        Query::<MyModel>::new().filter(Expr::and(
+            Expr::or(
+                ExprEq::eq(<MyModel as cot::db::Model>::Fields::id, 1),
+                ExprEq::eq(<MyModel as cot::db::Model>::Fields::id, 2)
+            ),
+            Expr::not(ExprOrd::gt(<MyModel as cot::db::Model>::Fields::price, 10))
+        )),
+        query!(MyModel, ($id == 1 || $id == 2) && !($price > 10))
+    );
+
+    let excluded = false;
+    assert_eq!(
+        Query::<MyModel>::new().filter(ExprEq::eq(
+            <MyModel as cot::db::Model>::Fields::quantity,
+            i64::from(!excluded)
+        )),
+        query!(MyModel, $quantity == i64::from(!excluded))
+    );
+}
+
+#[test]
+fn test_query_in() {
+    assert_eq!(
+        Query::<MyModel>::new().filter(ExprIn::is_in(
+            <MyModel as cot::db::Model>::Fields::id,
+            [1, 2, 3]
+        )),
+        query!(MyModel, $id in [1, 2, 3])
+    );
+
+    let ids = vec![4, 5];
+    assert_eq!(
+        Query::<MyModel>::new().filter(ExprIn::is_in(
+            <MyModel as cot::db::Model>::Fields::id,
+            vec![4, 5]
+        )),
+        query!(MyModel, $id in ids)
+    );
+
+    let empty: Vec<i32> = Vec::new();
+    assert_eq!(
+        Query::<MyModel>::new().filter(Expr::is_in(Expr::field("id"), Vec::new())),
+        query!(MyModel, $id in empty)
+    );
+
+    assert_eq!(
+        Query::<MyModel>::new().filter(Expr::is_in(
+            Expr::add(
+                <MyModel as cot::db::Model>::Fields::quantity.as_expr(),
+                <MyModel as cot::db::Model>::Fields::price.as_expr()
+            ),
+            vec![Expr::value(10i64), Expr::value(20i64)]
+        )),
+        query!(MyModel, $quantity + $price in [10i64, 20i64])
+    );
+}
+
+#[test]
+fn test_query_like() {
+    assert_eq!(
+        Query::<MyModel>::new().filter(ExprLike::like(
+            <MyModel as cot::db::Model>::Fields::name,
+            "%foo%"
+        )),
+        query!(MyModel, $name like "%foo%")
+    );
+
+    assert_eq!(
+        Query::<MyModel>::new().filter(Expr::and(
+            ExprLike::like(<MyModel as cot::db::Model>::Fields::name, "a%"),
+            ExprOrd::gte(<MyModel as cot::db::Model>::Fields::quantity, 5)
+        )),
+        query!(MyModel, $name like "a%" && $quantity >= 5)
+    );
+}
+
 #[test]
 fn test_query_add_fields() {
     assert_eq!(
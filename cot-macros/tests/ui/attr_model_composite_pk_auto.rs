@@ -1,9 +1,9 @@
-use cot::db::model;
+use cot::db::{Auto, model};
 
 #[model]
 struct MyModel {
     #[model(primary_key)]
-    id: i64,
+    id: Auto<i64>,
     #[model(primary_key)]
     id_2: i64,
     name: String,
@@ -0,0 +1,22 @@
+use std::fmt::Display;
+
+use cot::admin::AdminModel;
+use cot::db::model;
+use cot::form::Form;
+
+#[model]
+#[derive(Debug, Form, AdminModel)]
+struct Membership {
+    #[model(primary_key)]
+    user_id: i32,
+    #[model(primary_key)]
+    group_id: i32,
+}
+
+impl Display for Membership {
+    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        unimplemented!()
+    }
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+use std::fmt::Display;
+
+use cot::admin::AdminModel;
+use cot::db::model;
+use cot::form::Form;
+
+#[model]
+#[derive(Debug, Form, AdminModel)]
+#[admin(search_fields = ["name", "nonexistent"])]
+struct MyModel {
+    #[model(primary_key)]
+    id: i32,
+    name: String,
+}
+
+impl Display for MyModel {
+    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        unimplemented!()
+    }
+}
+
+fn main() {}
@@ -0,0 +1,41 @@
+use std::fmt::Display;
+
+use cot::admin::AdminModel;
+use cot::db::{Auto, model};
+use cot::form::Form;
+
+#[model]
+#[derive(Debug, Form, AdminModel)]
+#[admin(list_display = ["id", "title", "status_label"], ordering = ["-views", "title"])]
+struct MyModel {
+    #[model(primary_key)]
+    id: Auto<i32>,
+    title: String,
+    published: bool,
+    views: i64,
+}
+
+impl MyModel {
+    fn status_label(&self) -> &'static str {
+        if self.published { "Published" } else { "Draft" }
+    }
+}
+
+impl Display for MyModel {
+    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        unimplemented!()
+    }
+}
+
+fn main() {
+    assert_eq!(MyModel::list_display(), &["id", "title", "status_label"]);
+    assert_eq!(MyModel::sortable_fields(), &["id", "title"]);
+
+    let model = MyModel {
+        id: Auto::fixed(1),
+        title: "Hello".to_owned(),
+        published: true,
+        views: 0,
+    };
+    assert_eq!(model.list_display_values(), ["1", "Hello", "Published"]);
+}
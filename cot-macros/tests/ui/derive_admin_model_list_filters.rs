@@ -0,0 +1,28 @@
+use std::fmt::Display;
+
+use cot::admin::AdminModel;
+use cot::db::{Auto, LimitedString, model};
+use cot::form::Form;
+
+#[model]
+#[derive(Debug, Form, AdminModel)]
+#[admin(search_fields = ["title", "slug"], list_filter = ["published", "views"])]
+struct MyModel {
+    #[model(primary_key)]
+    id: Auto<i32>,
+    title: String,
+    slug: LimitedString<32>,
+    published: bool,
+    views: i64,
+}
+
+impl Display for MyModel {
+    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        unimplemented!()
+    }
+}
+
+fn main() {
+    assert_eq!(MyModel::search_fields(), &["title", "slug"]);
+    assert_eq!(MyModel::list_filter_fields(), &["published", "views"]);
+}
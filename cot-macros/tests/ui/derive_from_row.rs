@@ -0,0 +1,21 @@
+use cot::db::{FromRow, LimitedString};
+
+#[derive(FromRow)]
+struct NameCount {
+    name: String,
+    #[row(rename = "name_count")]
+    count: i64,
+    nickname: Option<LimitedString<32>>,
+}
+
+#[derive(FromRow)]
+struct Wrapper<T> {
+    value: T,
+}
+
+fn assert_from_row<T: FromRow>() {}
+
+fn main() {
+    assert_from_row::<NameCount>();
+    assert_from_row::<Wrapper<i32>>();
+}
@@ -0,0 +1,7 @@
+use cot::html;
+
+fn main() {
+    html! {
+        div class="a" id="main" class="b" { "Hello" }
+    };
+}
@@ -267,6 +267,7 @@ pub enum TestLanguage {
     AskamaTemplate,
     Bash,
     Html,
+    Json,
 }
 
 impl TryFrom<&str> for TestLanguage {
@@ -279,6 +280,7 @@ impl TryFrom<&str> for TestLanguage {
             "html.j2" => Ok(Self::AskamaTemplate),
             "bash" => Ok(Self::Bash),
             "html" => Ok(Self::Html),
+            "json" => Ok(Self::Json),
             _ => Err(TestLanguageFromStringError(value.to_string())),
         }
     }
@@ -15,6 +15,41 @@ use bytes::Bytes;
 /// **must** implement [`Model`](crate::db::Model) and
 /// [`Form`] traits. These can also be derived using the `#[model]` and
 /// `#[derive(Form)]` attributes.
+///
+/// # Searching and filtering
+///
+/// The list of objects displayed in the admin panel can be made searchable
+/// by listing the fields to search in the `search_fields` attribute. The
+/// search is case-insensitive and matches the objects that contain the search
+/// query in any of the listed fields. The fields must be of type `String` or
+/// [`LimitedString`](crate::db::LimitedString).
+///
+/// Similarly, the `list_filter` attribute lists the fields that the objects
+/// can be filtered by. The filter value entered by the user is parsed using
+/// the [`FromStr`](std::str::FromStr) implementation of the field type, and
+/// only the objects with the field equal to that value are displayed.
+///
+/// ```
+/// use cot::admin::AdminModel;
+/// use cot::db::{Auto, model};
+/// use cot::form::Form;
+///
+/// #[derive(Debug, Form, AdminModel)]
+/// #[admin(search_fields = ["title", "content"], list_filter = ["published"])]
+/// #[model]
+/// struct BlogPost {
+///     #[model(primary_key)]
+///     id: Auto<i32>,
+///     title: String,
+///     content: String,
+///     published: bool,
+/// }
+/// # impl std::fmt::Display for BlogPost {
+/// #     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+/// #         write!(f, "{}", self.title)
+/// #     }
+/// # }
+/// ```
 pub use cot_macros::AdminModel;
 use derive_more::Debug;
 use serde::Deserialize;
@@ -154,7 +189,24 @@ pub struct Pagination {
 }
 
 impl Pagination {
-    fn new(limit: u64, page: u64) -> Self {
+    /// Creates a new pagination with the given number of objects per page
+    /// and the 1-based page number.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::admin::Pagination;
+    ///
+    /// let pagination = Pagination::new(10, 3);
+    /// assert_eq!(pagination.limit(), 10);
+    /// assert_eq!(pagination.offset(), 20);
+    /// ```
+    #[must_use]
+    pub fn new(limit: u64, page: u64) -> Self {
         assert!(page > 0, "Page number must be greater than 0");
 
         Self {
@@ -182,6 +234,156 @@ struct PaginationParams {
     page_size: Option<u64>,
 }
 
+/// The search query and the filters selected in the admin list view.
+///
+/// These are read from the query string of the request: the search query is
+/// passed in the `q` parameter, the column to sort by is passed in the `sort`
+/// parameter (prefixed with `-` for descending order), and the value of each
+/// filter is passed in the parameter named after the filtered field.
+///
+/// This is used by the implementations of [`AdminModel`] generated by the
+/// [`AdminModel`](macro@AdminModel) derive macro to filter the list of
+/// objects.
+///
+/// # Examples
+///
+/// ```
+/// use cot::admin::ListFilters;
+/// use cot::test::TestRequestBuilder;
+///
+/// let request = TestRequestBuilder::get("/admin/post/?q=hello&sort=-title&status=draft").build();
+/// let filters = ListFilters::from_request(&request);
+///
+/// assert_eq!(filters.search(), Some("hello"));
+/// assert_eq!(filters.sort(), Some("-title"));
+/// assert_eq!(filters.filter("status"), Some("draft"));
+/// assert_eq!(filters.filter("author"), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ListFilters {
+    search: Option<String>,
+    sort: Option<String>,
+    filters: Vec<(String, String)>,
+}
+
+impl ListFilters {
+    const SEARCH_PARAM: &'static str = "q";
+    const SORT_PARAM: &'static str = "sort";
+    const RESERVED_PARAMS: [&'static str; 4] =
+        [Self::SEARCH_PARAM, Self::SORT_PARAM, "page", "page_size"];
+
+    /// Reads the search query, the sort order and the filters from the query
+    /// string of the request.
+    ///
+    /// Empty values are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::admin::ListFilters;
+    /// use cot::test::TestRequestBuilder;
+    ///
+    /// let request = TestRequestBuilder::get("/admin/post/?q=&page=2").build();
+    /// let filters = ListFilters::from_request(&request);
+    ///
+    /// assert_eq!(filters, ListFilters::default());
+    /// ```
+    #[must_use]
+    pub fn from_request(request: &Request) -> Self {
+        let query = request.uri().query().unwrap_or_default();
+
+        let mut list_filters = Self::default();
+        for (name, value) in form_urlencoded::parse(query.as_bytes()) {
+            if value.is_empty() {
+                continue;
+            }
+
+            if name == Self::SEARCH_PARAM {
+                list_filters.search = Some(value.into_owned());
+            } else if name == Self::SORT_PARAM {
+                list_filters.sort = Some(value.into_owned());
+            } else if !Self::RESERVED_PARAMS.contains(&name.as_ref()) {
+                list_filters
+                    .filters
+                    .push((name.into_owned(), value.into_owned()));
+            }
+        }
+        list_filters
+    }
+
+    /// Returns the search query, if any.
+    #[must_use]
+    pub fn search(&self) -> Option<&str> {
+        self.search.as_deref()
+    }
+
+    /// Returns the `LIKE` pattern matching the values containing the search
+    /// query, if any.
+    ///
+    /// The `LIKE` wildcards in the search query are escaped, so they only
+    /// match themselves.
+    #[must_use]
+    pub fn search_pattern(&self) -> Option<String> {
+        self.search
+            .as_deref()
+            .map(|search| format!("%{}%", crate::db::query::escape_like(search)))
+    }
+
+    /// Returns the name of the column to sort by, if any.
+    ///
+    /// The name is prefixed with `-` if the list should be sorted in
+    /// descending order.
+    #[must_use]
+    pub fn sort(&self) -> Option<&str> {
+        self.sort.as_deref()
+    }
+
+    /// Returns the value of the filter for the given field, if any.
+    #[must_use]
+    pub fn filter(&self, field: &str) -> Option<&str> {
+        self.filters
+            .iter()
+            .find(|(name, _)| name == field)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the query string parameters (ending with `&` if not empty)
+    /// that need to be preserved when navigating between the pages of the
+    /// list view.
+    fn to_query_string(&self, filter_fields: &[&str]) -> String {
+        let mut query_string = self.to_query_string_without_sort(filter_fields);
+        if let Some(sort) = &self.sort {
+            query_string.push_str(
+                &form_urlencoded::Serializer::new(String::new())
+                    .append_pair(Self::SORT_PARAM, sort)
+                    .finish(),
+            );
+            query_string.push('&');
+        }
+        query_string
+    }
+
+    /// Like [`Self::to_query_string`], but without the sort order, so that
+    /// a different one can be appended.
+    fn to_query_string_without_sort(&self, filter_fields: &[&str]) -> String {
+        let mut serializer = form_urlencoded::Serializer::new(String::new());
+        if let Some(search) = &self.search {
+            serializer.append_pair(Self::SEARCH_PARAM, search);
+        }
+        for &field in filter_fields {
+            if let Some(value) = self.filter(field) {
+                serializer.append_pair(field, value);
+            }
+        }
+
+        let mut query_string = serializer.finish();
+        if !query_string.is_empty() {
+            query_string.push('&');
+        }
+        query_string
+    }
+}
+
 async fn view_model(
     base_context: BaseContext,
     managers: AdminModelManagers,
@@ -201,6 +403,9 @@ async fn view_model(
         page_size: &'a u64,
         total_object_counts: u64,
         total_pages: u64,
+        list_filters: ListFilters,
+        filter_query: String,
+        columns: Vec<ListColumn<'a>>,
     }
 
     const DEFAULT_PAGE_SIZE: u64 = 10;
@@ -223,6 +428,10 @@ async fn view_model(
 
     let objects = manager.get_objects(&request, pagination).await?;
 
+    let list_filters = ListFilters::from_request(&request);
+    let filter_query = list_filters.to_query_string(manager.list_filter_fields());
+    let columns = ListColumn::for_model(&*manager, &list_filters, page_size);
+
     let template = ModelTemplate {
         ctx: &base_context,
         model: &*manager,
@@ -231,11 +440,65 @@ async fn view_model(
         page_size: &page_size,
         total_object_counts,
         total_pages,
+        list_filters,
+        filter_query,
+        columns,
     };
 
     Html::new(template.render()?).into_response()
 }
 
+/// A column of the admin list view.
+#[derive(Debug)]
+struct ListColumn<'a> {
+    name: &'a str,
+    /// The link that sorts the list by this column, if it is sortable.
+    sort_link: Option<String>,
+    /// The arrow indicating the current sort order, if the list is sorted by
+    /// this column.
+    sort_indicator: &'static str,
+}
+
+impl<'a> ListColumn<'a> {
+    fn for_model(
+        model: &'a dyn AdminModelManager,
+        list_filters: &ListFilters,
+        page_size: u64,
+    ) -> Vec<Self> {
+        let query = list_filters.to_query_string_without_sort(model.list_filter_fields());
+
+        model
+            .list_display()
+            .iter()
+            .map(|&name| {
+                if !model.sortable_fields().contains(&name) {
+                    return Self {
+                        name,
+                        sort_link: None,
+                        sort_indicator: "",
+                    };
+                }
+
+                let descending = format!("-{name}");
+                let (sort, sort_indicator) = match list_filters.sort() {
+                    Some(sort) if sort == name => (descending.as_str(), "▲"),
+                    Some(sort) if sort == descending => (name, "▼"),
+                    _ => (name, ""),
+                };
+                let sort_query = form_urlencoded::Serializer::new(String::new())
+                    .append_pair(ListFilters::SORT_PARAM, sort)
+                    .finish();
+
+                Self {
+                    name,
+                    sort_link: Some(format!("?{query}{sort_query}&page_size={page_size}")),
+                    sort_indicator,
+                }
+            })
+            .collect()
+    }
+}
+
 async fn create_model_instance(
     base_context: BaseContext,
     managers: AdminModelManagers,
@@ -409,6 +672,35 @@ pub trait AdminModelManager: Send + Sync {
     /// Returns the URL slug for the model.
     fn url_name(&self) -> &str;
 
+    /// Returns the names of the fields that can be searched in the list view.
+    ///
+    /// If this is not empty, a search box is displayed in the list view.
+    fn search_fields(&self) -> &[&str] {
+        &[]
+    }
+
+    /// Returns the names of the fields that the list view can be filtered by.
+    ///
+    /// If this is not empty, a filter sidebar is displayed in the list view.
+    fn list_filter_fields(&self) -> &[&str] {
+        &[]
+    }
+
+    /// Returns the names of the columns displayed in the list view.
+    ///
+    /// If this is empty, the list view displays the ID and the display text
+    /// of each object.
+    fn list_display(&self) -> &[&str] {
+        &[]
+    }
+
+    /// Returns the names of the columns that the list view can be sorted by.
+    ///
+    /// This should be a subset of [`Self::list_display`].
+    fn sortable_fields(&self) -> &[&str] {
+        &[]
+    }
+
     /// Returns the list of objects of this model.
     async fn get_objects(
         &self,
@@ -493,6 +785,22 @@ impl<T: AdminModel + Send + Sync + 'static> AdminModelManager for DefaultAdminMo
         T::url_name()
     }
 
+    fn search_fields(&self) -> &[&str] {
+        T::search_fields()
+    }
+
+    fn list_filter_fields(&self) -> &[&str] {
+        T::list_filter_fields()
+    }
+
+    fn list_display(&self) -> &[&str] {
+        T::list_display()
+    }
+
+    fn sortable_fields(&self) -> &[&str] {
+        T::sortable_fields()
+    }
+
     async fn get_total_object_counts(&self, request: &Request) -> cot::Result<u64> {
         T::get_total_object_counts(request).await
     }
@@ -555,11 +863,17 @@ impl<T: AdminModel + Send + Sync + 'static> AdminModelManager for DefaultAdminMo
 )]
 pub trait AdminModel: Any + Send + 'static {
     /// Get the objects of this model.
+    ///
+    /// The objects should be filtered according to the search query and the
+    /// filters selected in the list view (see [`ListFilters`]).
     async fn get_objects(request: &Request, pagination: Pagination) -> cot::Result<Vec<Self>>
     where
         Self: Sized;
 
     /// Get the total count of objects of this model.
+    ///
+    /// Like in [`Self::get_objects`], only the objects matching the search
+    /// query and the filters should be counted.
     async fn get_total_object_counts(request: &Request) -> cot::Result<u64>
     where
         Self: Sized;
@@ -579,12 +893,70 @@ pub trait AdminModel: Any + Send + 'static {
     where
         Self: Sized;
 
+    /// Get the names of the fields that can be searched in the admin list
+    /// view.
+    ///
+    /// The search query is available via [`ListFilters::search`] in
+    /// [`Self::get_objects`] and [`Self::get_total_object_counts`].
+    #[must_use]
+    fn search_fields() -> &'static [&'static str]
+    where
+        Self: Sized,
+    {
+        &[]
+    }
+
+    /// Get the names of the fields that the admin list view can be filtered
+    /// by.
+    ///
+    /// The selected filter values are available via [`ListFilters::filter`]
+    /// in [`Self::get_objects`] and [`Self::get_total_object_counts`].
+    #[must_use]
+    fn list_filter_fields() -> &'static [&'static str]
+    where
+        Self: Sized,
+    {
+        &[]
+    }
+
+    /// Get the names of the columns displayed in the admin list view.
+    ///
+    /// The values of the columns for each object are returned by
+    /// [`Self::list_display_values`]. If this is empty, the list view
+    /// displays the ID and the display text of each object instead.
+    #[must_use]
+    fn list_display() -> &'static [&'static str]
+    where
+        Self: Sized,
+    {
+        &[]
+    }
+
+    /// Get the names of the columns that the admin list view can be sorted
+    /// by.
+    ///
+    /// The selected column is available via [`ListFilters::sort`] in
+    /// [`Self::get_objects`].
+    #[must_use]
+    fn sortable_fields() -> &'static [&'static str]
+    where
+        Self: Sized,
+    {
+        &[]
+    }
+
     /// Get the ID of this model instance as a [`String`].
     fn id(&self) -> String;
 
     /// Get the display text of this model instance.
     fn display(&self) -> String;
 
+    /// Get the values of the columns displayed in the admin list view for
+    /// this model instance, in the same order as in [`Self::list_display`].
+    fn list_display_values(&self) -> Vec<String> {
+        Vec::new()
+    }
+
     /// Get the form context for this model.
     fn form_context() -> Box<dyn FormContext>
     where
@@ -713,3 +1085,61 @@ impl App for AdminApp {
         )]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::TestRequestBuilder;
+
+    #[test]
+    fn list_filters_from_request() {
+        let request =
+            TestRequestBuilder::get("/admin/post/?q=hello+world&status=draft&page=2&author=")
+                .build();
+
+        let filters = ListFilters::from_request(&request);
+
+        assert_eq!(filters.search(), Some("hello world"));
+        assert_eq!(filters.filter("status"), Some("draft"));
+        assert_eq!(filters.filter("page"), None);
+        assert_eq!(filters.filter("author"), None);
+    }
+
+    #[test]
+    fn list_filters_search_pattern() {
+        let request = TestRequestBuilder::get("/admin/post/?q=100%25_off%5C").build();
+        let filters = ListFilters::from_request(&request);
+
+        assert_eq!(filters.search_pattern().as_deref(), Some(r"%100\%\_off\\%"));
+        assert_eq!(ListFilters::default().search_pattern(), None);
+    }
+
+    #[test]
+    fn list_filters_to_query_string() {
+        let request = TestRequestBuilder::get("/admin/post/?q=a%26b&status=draft&other=x").build();
+        let filters = ListFilters::from_request(&request);
+
+        assert_eq!(
+            filters.to_query_string(&["status", "published"]),
+            "q=a%26b&status=draft&"
+        );
+        assert_eq!(ListFilters::default().to_query_string(&["status"]), "");
+    }
+
+    #[test]
+    fn list_filters_to_query_string_with_sort() {
+        let request = TestRequestBuilder::get("/admin/post/?sort=-title&status=draft").build();
+        let filters = ListFilters::from_request(&request);
+
+        assert_eq!(filters.sort(), Some("-title"));
+        assert_eq!(filters.filter("sort"), None);
+        assert_eq!(
+            filters.to_query_string(&["status"]),
+            "status=draft&sort=-title&"
+        );
+        assert_eq!(
+            filters.to_query_string_without_sort(&["status"]),
+            "status=draft&"
+        );
+    }
+}
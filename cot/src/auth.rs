@@ -5,9 +5,13 @@
 //! verification.
 //!
 //! For the default way to store users in the database, see the [`db`] module.
+//! For stateless authentication with JSON Web Tokens, see the `jwt` module
+//! (requires the `jwt` feature).
 
 #[cfg(feature = "db")]
 pub mod db;
+#[cfg(feature = "jwt")]
+pub mod jwt;
 
 use std::any::Any;
 use std::borrow::Cow;
@@ -54,6 +58,12 @@ pub enum AuthError {
     /// supported.
     #[error("{ERROR_PREFIX} tried to get a user by an unsupported user ID type")]
     UserIdTypeNotSupported,
+    /// The account is locked because of too many failed login attempts.
+    #[error("{ERROR_PREFIX} account is locked until {locked_until}")]
+    AccountLocked {
+        /// The time when the account is unlocked.
+        locked_until: chrono::DateTime<chrono::Utc>,
+    },
 }
 impl_into_cot_error!(AuthError, UNAUTHORIZED);
 
@@ -808,6 +818,9 @@ impl Auth {
     /// in the session object and can be accessed using the [`user`](Self::user)
     /// method.
     ///
+    /// To prevent session fixation attacks, the session ID is changed on
+    /// login. The data already stored in the session is preserved.
+    ///
     /// # Errors
     ///
     /// Returns an error if the user object cannot be stored in the session
@@ -1310,6 +1323,33 @@ mod tests {
         assert!(id_1 != id_2);
     }
 
+    /// Test that the session data survives the session ID change on login
+    #[cot::test]
+    async fn login_cycle_id_preserves_session_data() {
+        let mut request = test_request(MockUser::new);
+        let session = Session::from_request(&request).clone();
+        session.insert("cart", vec![1, 2, 3]).await.unwrap();
+        session.save().await.unwrap();
+        let id_before = session.id();
+        assert!(id_before.is_some());
+
+        let auth = Auth::from_request(&mut request).await.unwrap();
+        let mut mock_user = MockUser::new();
+        mock_user.expect_id().return_const(UserId::Int(1));
+        mock_user.expect_session_auth_hash().return_const(None);
+        mock_user
+            .expect_username()
+            .return_const(Some(Cow::from("mockuser")));
+        auth.login(Box::new(mock_user)).await.unwrap();
+
+        session.save().await.unwrap();
+        assert_ne!(session.id(), id_before);
+        assert_eq!(
+            session.get::<Vec<i32>>("cart").await.unwrap(),
+            Some(vec![1, 2, 3])
+        );
+    }
+
     /// Test that the user is logged out when there is an invalid user ID in the
     /// session (can happen if the user is deleted from the database)
     #[cot::test]
@@ -1,7 +1,10 @@
 //! Database-backed user authentication backend.
 //!
 //! This module provides a user type and an authentication backend that stores
-//! user data in a database using the Cot ORM.
+//! user data in a database using the Cot ORM, as well as long-lived API tokens
+//! for authenticating API clients (see [`ApiToken`]), email address
+//! verification (see [`DatabaseUser::email_verification_token`]), and
+//! password resets (see [`DatabaseUser::password_reset_token`]).
 
 use std::any::Any;
 use std::borrow::Cow;
@@ -20,13 +23,25 @@ use crate::auth::{
     AuthBackend, AuthError, PasswordHash, PasswordVerificationResult, Result, SessionAuthHash,
     User, UserId,
 };
-use crate::common_types::Password;
+use crate::common_types::{Email, Password};
 use crate::config::SecretKey;
 use crate::db::migrations::SyncDynMigration;
 use crate::db::{Database, DatabaseBackend, LimitedString, Model, model, query};
 use crate::form::Form;
 
+mod api_token;
+mod email_verification;
+mod lockout;
 pub mod migrations;
+mod password_reset;
+mod signed_token;
+
+pub use api_token::{
+    ApiToken, ApiTokenError, ApiTokenMiddleware, ApiTokenService, ApiTokenUser,
+    CreateApiTokenError, MissingScopeError,
+};
+pub use lockout::LockoutPolicy;
+use lockout::LoginFailure;
 
 pub(crate) const MAX_USERNAME_LENGTH: u32 = 255;
 
@@ -39,6 +54,9 @@ pub struct DatabaseUser {
     #[model(unique)]
     username: LimitedString<MAX_USERNAME_LENGTH>,
     password: PasswordHash,
+    email: Option<Email>,
+    #[model(default = false)]
+    email_verified: bool,
 }
 
 /// An error that occurs when creating a user.
@@ -61,6 +79,8 @@ impl DatabaseUser {
             id,
             username,
             password: PasswordHash::from_password(password),
+            email: None,
+            email_verified: false,
         }
     }
 
@@ -250,7 +270,7 @@ impl DatabaseUser {
     /// ```
     pub async fn get_by_id<DB: DatabaseBackend>(db: &DB, id: i64) -> Result<Option<Self>> {
         let db_user = query!(DatabaseUser, $id == id)
-            .get(db)
+            .first(db)
             .await
             .map_err(AuthError::backend_error)?;
 
@@ -301,7 +321,7 @@ impl DatabaseUser {
             AuthError::backend_error(CreateUserError::UsernameTooLong(username.len()))
         })?;
         let db_user = query!(DatabaseUser, $username == username)
-            .get(db)
+            .first(db)
             .await
             .map_err(AuthError::backend_error)?;
 
@@ -310,48 +330,136 @@ impl DatabaseUser {
 
     /// Authenticates a user using the provided credentials.
     ///
+    /// The failed login attempts are tracked using the default
+    /// [`LockoutPolicy`]; see [`Self::authenticate_with_lockout_policy`] for
+    /// details.
+    ///
     /// # Errors
     ///
     /// Returns an error if there was an error querying the database.
+    ///
+    /// Returns [`AuthError::AccountLocked`] if the account is locked because of
+    /// too many failed login attempts.
     pub async fn authenticate<DB: DatabaseBackend>(
         db: &DB,
         credentials: &DatabaseUserCredentials,
+    ) -> Result<Option<Self>> {
+        Self::authenticate_with_lockout_policy(db, credentials, &LockoutPolicy::default()).await
+    }
+
+    /// Authenticates a user using the provided credentials, locking the
+    /// account out after too many failed attempts according to the given
+    /// policy.
+    ///
+    /// The lockout is checked before the password is verified, so the
+    /// password of a locked account can't be guessed. The failed attempts are
+    /// counted per username, regardless of whether the user exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there was an error querying the database.
+    ///
+    /// Returns [`AuthError::AccountLocked`] if the account is locked because of
+    /// too many failed login attempts.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::auth::AuthError;
+    /// use cot::auth::db::{DatabaseUser, DatabaseUserCredentials, LockoutPolicy};
+    /// use cot::common_types::Password;
+    /// use cot::db::Database;
+    ///
+    /// async fn login(db: Database) -> cot::Result<()> {
+    ///     DatabaseUser::create_user(&db, "testuser", &Password::new("password123")).await?;
+    ///     let policy = LockoutPolicy::new(2, Duration::from_secs(60), Duration::from_secs(60));
+    ///
+    ///     let invalid = DatabaseUserCredentials::new("testuser".to_string(), Password::new("wrong"));
+    ///     for _ in 0..2 {
+    ///         let user = DatabaseUser::authenticate_with_lockout_policy(&db, &invalid, &policy).await?;
+    ///         assert!(user.is_none());
+    ///     }
+    ///
+    ///     let valid =
+    ///         DatabaseUserCredentials::new("testuser".to_string(), Password::new("password123"));
+    ///     let result = DatabaseUser::authenticate_with_lockout_policy(&db, &valid, &policy).await;
+    ///     assert!(matches!(result, Err(AuthError::AccountLocked { .. })));
+    ///
+    ///     Ok(())
+    /// }
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> cot::Result<()> {
+    /// #     use cot::test::TestDatabase;
+    /// #     let mut test_database = TestDatabase::new_sqlite().await?;
+    /// #     test_database.with_auth().run_migrations().await;
+    /// #     login(test_database.database()).await?;
+    /// #     test_database.cleanup().await?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub async fn authenticate_with_lockout_policy<DB: DatabaseBackend>(
+        db: &DB,
+        credentials: &DatabaseUserCredentials,
+        lockout_policy: &LockoutPolicy,
     ) -> Result<Option<Self>> {
         let username = credentials.username();
         let username_limited = LimitedString::<MAX_USERNAME_LENGTH>::new(username.to_string())
             .map_err(|_| {
                 AuthError::backend_error(CreateUserError::UsernameTooLong(username.len()))
             })?;
+
+        let now = chrono::Utc::now();
+        let failure = if lockout_policy.is_enabled() {
+            LoginFailure::get_by_username(db, &username_limited).await?
+        } else {
+            None
+        };
+        if let Some(locked_until) = failure
+            .as_ref()
+            .and_then(|failure| failure.locked_until(now))
+        {
+            // SECURITY: Don't verify the password of a locked account, so that it can't
+            // be guessed during the lockout, but still run the hashing function so that
+            // the response takes as long as a regular login attempt.
+            verify_dummy_password(credentials.password());
+            return Err(AuthError::AccountLocked { locked_until });
+        }
+
         let user = query!(DatabaseUser, $username == username_limited)
-            .get(db)
+            .first(db)
             .await
             .map_err(AuthError::backend_error)?;
 
-        if let Some(mut user) = user {
+        let user = if let Some(mut user) = user {
             let password_hash = &user.password;
             match password_hash.verify(credentials.password()) {
-                PasswordVerificationResult::Ok => Ok(Some(user)),
+                PasswordVerificationResult::Ok => Some(user),
                 PasswordVerificationResult::OkObsolete(new_hash) => {
                     user.password = new_hash;
                     user.save(db).await.map_err(AuthError::backend_error)?;
-                    Ok(Some(user))
+                    Some(user)
                 }
-                PasswordVerificationResult::Invalid => Ok(None),
+                PasswordVerificationResult::Invalid => None,
             }
         } else {
             // SECURITY: If no user was found, run the same hashing function to prevent
-            // timing attacks from being used to determine if a user exists. Additionally,
-            // do something with the result to prevent the compiler from optimizing out the
-            // operation.
-            // TODO: benchmark this to make sure it works as expected
-            let dummy_hash = PasswordHash::from_password(credentials.password());
-            if let PasswordVerificationResult::Invalid = dummy_hash.verify(credentials.password()) {
-                unreachable!(
-                    "Password hash verification should never fail for a newly generated hash"
-                );
+            // timing attacks from being used to determine if a user exists.
+            verify_dummy_password(credentials.password());
+            None
+        };
+
+        if user.is_some() {
+            if let Some(failure) = failure {
+                failure.reset(db).await?;
             }
-            Ok(None)
+        } else if lockout_policy.is_enabled() {
+            LoginFailure::record(db, username_limited, lockout_policy, now).await?;
         }
+
+        Ok(user)
     }
 
     /// Returns the ID of the user.
@@ -436,6 +544,107 @@ impl DatabaseUser {
     pub fn username(&self) -> &str {
         &self.username
     }
+
+    /// Returns the email address of the user, if set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cot::auth::db::DatabaseUser;
+    /// use cot::html::Html;
+    ///
+    /// async fn view(user: DatabaseUser) -> Html {
+    ///     match user.email() {
+    ///         Some(email) => Html::new(format!("Email: {email}")),
+    ///         None => Html::new("No email address"),
+    ///     }
+    /// }
+    /// ```
+    #[must_use]
+    pub fn email(&self) -> Option<&Email> {
+        self.email.as_ref()
+    }
+
+    /// Sets the email address of the user.
+    ///
+    /// If the email address is different from the current one, it's marked as
+    /// not verified. Remember to call [`save()`][cot::db::Model::save] after
+    /// calling this function - otherwise, the email address will never be
+    /// saved to the database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cot::auth::db::DatabaseUser;
+    /// use cot::common_types::{Email, Password};
+    /// use cot::db::{Database, Model};
+    /// use cot::html::Html;
+    ///
+    /// async fn view(db: Database) -> cot::Result<Html> {
+    ///     let mut user =
+    ///         DatabaseUser::create_user(&db, "testuser", &Password::new("password123")).await?;
+    ///     user.set_email(Some(Email::new("testuser@example.com").expect("valid email")));
+    ///     user.save(&db).await?;
+    ///
+    ///     assert!(!user.is_email_verified());
+    ///
+    ///     Ok(Html::new("Email changed!"))
+    /// }
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> cot::Result<()> {
+    /// #     use cot::test::TestDatabase;
+    /// #     let mut test_database = TestDatabase::new_sqlite().await?;
+    /// #     test_database.with_auth().run_migrations().await;
+    /// #     view(test_database.database()).await?;
+    /// #     test_database.cleanup().await?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn set_email(&mut self, email: Option<Email>) {
+        if self.email != email {
+            self.email = email;
+            self.email_verified = false;
+        }
+    }
+
+    /// Returns whether the user has confirmed that they own their email
+    /// address.
+    ///
+    /// See [`DatabaseUser::email_verification_token`] and
+    /// [`DatabaseUser::verify_email`] for how to verify the email address.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cot::auth::db::DatabaseUser;
+    /// use cot::html::Html;
+    ///
+    /// async fn view(user: DatabaseUser) -> Html {
+    ///     if user.is_email_verified() {
+    ///         Html::new("Welcome!")
+    ///     } else {
+    ///         Html::new("Please verify your email address.")
+    ///     }
+    /// }
+    /// ```
+    #[must_use]
+    pub fn is_email_verified(&self) -> bool {
+        self.email.is_some() && self.email_verified
+    }
+}
+
+/// Hashes and verifies the password without comparing it to any user's
+/// password, to make the operation take the same time as a regular password
+/// verification.
+fn verify_dummy_password(password: &Password) {
+    // Do something with the result to prevent the compiler from optimizing out the
+    // operation.
+    // TODO: benchmark this to make sure it works as expected
+    let dummy_hash = PasswordHash::from_password(password);
+    if let PasswordVerificationResult::Invalid = dummy_hash.verify(password) {
+        unreachable!("Password hash verification should never fail for a newly generated hash");
+    }
 }
 
 impl User for DatabaseUser {
@@ -543,9 +752,13 @@ impl DatabaseUserCredentials {
 ///
 /// This backend supports authenticating users using the
 /// [`DatabaseUserCredentials`] struct and ignores all other credential types.
+///
+/// Accounts are locked out after repeated failed login attempts according to
+/// the [`LockoutPolicy`], which can be changed with [`Self::lockout_policy`].
 #[derive(Debug, Clone)]
 pub struct DatabaseUserBackend {
     database: Database,
+    lockout_policy: LockoutPolicy,
 }
 
 impl DatabaseUserBackend {
@@ -572,7 +785,41 @@ impl DatabaseUserBackend {
     /// ```
     #[must_use]
     pub fn new(database: Database) -> Self {
-        Self { database }
+        Self {
+            database,
+            lockout_policy: LockoutPolicy::default(),
+        }
+    }
+
+    /// Sets the policy for locking the accounts out after failed login
+    /// attempts.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// use cot::Project;
+    /// use cot::auth::AuthBackend;
+    /// use cot::auth::db::{DatabaseUserBackend, LockoutPolicy};
+    /// use cot::project::AuthBackendContext;
+    ///
+    /// struct HelloProject;
+    /// impl Project for HelloProject {
+    ///     fn auth_backend(&self, context: &AuthBackendContext) -> Arc<dyn AuthBackend> {
+    ///         Arc::new(
+    ///             DatabaseUserBackend::new(context.database().clone()).lockout_policy(
+    ///                 LockoutPolicy::new(10, Duration::from_secs(60), Duration::from_secs(300)),
+    ///             ),
+    ///         )
+    ///     }
+    /// }
+    /// ```
+    #[must_use]
+    pub fn lockout_policy(mut self, lockout_policy: LockoutPolicy) -> Self {
+        self.lockout_policy = lockout_policy;
+        self
     }
 }
 
@@ -584,9 +831,13 @@ impl AuthBackend for DatabaseUserBackend {
     ) -> Result<Option<Box<dyn User + Send + Sync>>> {
         if let Some(credentials) = credentials.downcast_ref::<DatabaseUserCredentials>() {
             #[expect(trivial_casts)] // Upcast to the correct Box type
-            Ok(DatabaseUser::authenticate(&self.database, credentials)
-                .await
-                .map(|user| user.map(|user| Box::new(user) as Box<dyn User + Send + Sync>))?)
+            Ok(DatabaseUser::authenticate_with_lockout_policy(
+                &self.database,
+                credentials,
+                &self.lockout_policy,
+            )
+            .await
+            .map(|user| user.map(|user| Box::new(user) as Box<dyn User + Send + Sync>))?)
         } else {
             Err(AuthError::CredentialsTypeNotSupported)
         }
@@ -668,7 +919,7 @@ impl App for DatabaseUserApp {
 mod tests {
     use super::*;
     use crate::config::SecretKey;
-    use crate::db::MockDatabaseBackend;
+    use crate::db::{MockDatabaseBackend, RowsNum, StatementResult};
 
     #[test]
     #[cfg_attr(miri, ignore)]
@@ -750,6 +1001,7 @@ mod tests {
             &Password::new("password123"),
         );
 
+        mock_db.expect_get::<LoginFailure>().returning(|_| Ok(None));
         mock_db
             .expect_get::<DatabaseUser>()
             .returning(move |_| Ok(Some(user.clone())));
@@ -763,14 +1015,31 @@ mod tests {
         assert_eq!(result.unwrap().username(), "testuser");
     }
 
+    fn expect_login_failure_recorded(mock_db: &mut MockDatabaseBackend) {
+        mock_db
+            .expect_delete::<LoginFailure>()
+            .times(1)
+            .returning(|_| Ok(StatementResult::new(RowsNum(0))));
+        mock_db
+            .expect_bulk_update::<LoginFailure>()
+            .times(2)
+            .returning(|_, _| Ok(StatementResult::new(RowsNum(0))));
+        mock_db
+            .expect_insert::<LoginFailure>()
+            .times(1)
+            .returning(|_| Ok(()));
+    }
+
     #[cot::test]
     #[cfg_attr(miri, ignore)]
     async fn authenticate_non_existing() {
         let mut mock_db = MockDatabaseBackend::new();
 
+        mock_db.expect_get::<LoginFailure>().returning(|_| Ok(None));
         mock_db
             .expect_get::<DatabaseUser>()
             .returning(move |_| Ok(None));
+        expect_login_failure_recorded(&mut mock_db);
 
         let credentials =
             DatabaseUserCredentials::new("testuser".to_string(), Password::new("password123"));
@@ -790,9 +1059,11 @@ mod tests {
             &Password::new("password123"),
         );
 
+        mock_db.expect_get::<LoginFailure>().returning(|_| Ok(None));
         mock_db
             .expect_get::<DatabaseUser>()
             .returning(move |_| Ok(Some(user.clone())));
+        expect_login_failure_recorded(&mut mock_db);
 
         let credentials =
             DatabaseUserCredentials::new("testuser".to_string(), Password::new("invalid"));
@@ -801,4 +1072,25 @@ mod tests {
             .unwrap();
         assert!(result.is_none());
     }
+
+    #[cot::test]
+    #[cfg_attr(miri, ignore)]
+    async fn authenticate_lockout_disabled() {
+        let mut mock_db = MockDatabaseBackend::new();
+
+        mock_db
+            .expect_get::<DatabaseUser>()
+            .returning(move |_| Ok(None));
+
+        let credentials =
+            DatabaseUserCredentials::new("testuser".to_string(), Password::new("password123"));
+        let result = DatabaseUser::authenticate_with_lockout_policy(
+            &mock_db,
+            &credentials,
+            &LockoutPolicy::disabled(),
+        )
+        .await
+        .unwrap();
+        assert!(result.is_none());
+    }
 }
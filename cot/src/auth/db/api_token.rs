@@ -0,0 +1,604 @@
+//! Long-lived API tokens stored in the database.
+
+use std::borrow::Cow;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use chrono::{DateTime, Utc};
+// Importing `Auto` from `cot` instead of `crate` so that the migration generator
+// can figure out it's an autogenerated field
+use cot::db::Auto;
+use cot_core::error::impl_into_cot_error;
+use futures_core::future::BoxFuture;
+use rand::rngs::{StdRng, SysRng};
+use rand::{Rng, SeedableRng};
+use thiserror::Error;
+use tower::Service;
+
+use crate::auth::db::DatabaseUser;
+use crate::auth::{AuthError, Result, User, UserId};
+use crate::db::{DatabaseBackend, ForeignKey, LimitedString, Model, model, query};
+use crate::request::extractors::FromRequestHead;
+use crate::request::{Request, RequestExt, RequestHead};
+use crate::response::Response;
+
+pub(crate) const TOKEN_HASH_LENGTH: u32 = 64;
+const ERROR_PREFIX: &str = "failed to authenticate with an API token:";
+
+/// An API token that authenticates requests on behalf of a
+/// [`DatabaseUser`].
+///
+/// Only the hash of the token is stored in the database; the token itself is
+/// returned once by [`ApiToken::create`] and can't be retrieved later. Each
+/// token has a list of scopes that can be checked by the request handlers
+/// (see [`ApiTokenUser::require_scope`]) and an optional expiration time.
+///
+/// The tokens are validated by [`ApiTokenMiddleware`].
+#[derive(Debug, Clone)]
+#[model]
+pub struct ApiToken {
+    #[model(primary_key)]
+    id: Auto<i64>,
+    #[model(unique)]
+    token_hash: LimitedString<TOKEN_HASH_LENGTH>,
+    #[model(foreign_key(on_delete = "cascade"))]
+    user: ForeignKey<DatabaseUser>,
+    scopes: String,
+    expires_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+/// An error that occurs when creating an API token.
+#[derive(Debug, Clone, Error)]
+#[non_exhaustive]
+pub enum CreateApiTokenError {
+    /// The scope is empty or contains whitespace.
+    #[error("invalid API token scope: `{0}`")]
+    InvalidScope(String),
+}
+
+impl ApiToken {
+    /// Creates a new API token for the given user and saves it to the
+    /// database.
+    ///
+    /// Returns the token model along with the plaintext token, which should be
+    /// shown to the user. The plaintext token is not stored anywhere, so it
+    /// can't be retrieved again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the scopes is empty or contains whitespace,
+    /// or if the token could not be saved.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cot::auth::db::{ApiToken, DatabaseUser};
+    /// use cot::common_types::Password;
+    /// use cot::db::Database;
+    /// use cot::html::Html;
+    ///
+    /// async fn view(db: Database) -> cot::Result<Html> {
+    ///     let user = DatabaseUser::create_user(&db, "testuser", &Password::new("password123")).await?;
+    ///     let (token, plaintext) = ApiToken::create(&db, &user, &["read"], None).await?;
+    ///
+    ///     Ok(Html::new(format!("Your API token: {plaintext}")))
+    /// }
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> cot::Result<()> {
+    /// #     use cot::test::TestDatabase;
+    /// #     let mut test_database = TestDatabase::new_sqlite().await?;
+    /// #     test_database.with_auth().run_migrations().await;
+    /// #     view(test_database.database()).await?;
+    /// #     test_database.cleanup().await?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub async fn create<DB: DatabaseBackend>(
+        db: &DB,
+        user: &DatabaseUser,
+        scopes: &[&str],
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<(Self, String)> {
+        if let Some(scope) = scopes
+            .iter()
+            .find(|scope| scope.is_empty() || scope.contains(char::is_whitespace))
+        {
+            return Err(AuthError::backend_error(CreateApiTokenError::InvalidScope(
+                (*scope).to_owned(),
+            )));
+        }
+
+        let token = generate_token();
+        let mut api_token = Self {
+            id: Auto::auto(),
+            token_hash: hash_token(&token),
+            user: ForeignKey::from(user),
+            scopes: scopes.join(" "),
+            expires_at,
+            created_at: Utc::now(),
+        };
+        api_token
+            .insert(db)
+            .await
+            .map_err(AuthError::backend_error)?;
+
+        Ok((api_token, token))
+    }
+
+    /// Retrieves the API token matching the given plaintext token. It returns
+    /// [`None`] if the token does not exist.
+    ///
+    /// Note that this doesn't check whether the token has expired.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there was an error querying the database.
+    pub async fn get_by_token<DB: DatabaseBackend>(db: &DB, token: &str) -> Result<Option<Self>> {
+        let token_hash = hash_token(token);
+        let api_token = query!(ApiToken, $token_hash == token_hash)
+            .first(db)
+            .await
+            .map_err(AuthError::backend_error)?;
+
+        Ok(api_token)
+    }
+
+    /// Revokes the token by removing it from the database.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token could not be removed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cot::auth::db::{ApiToken, DatabaseUser};
+    /// use cot::common_types::Password;
+    /// use cot::db::Database;
+    /// use cot::html::Html;
+    ///
+    /// async fn view(db: Database) -> cot::Result<Html> {
+    ///     let user = DatabaseUser::create_user(&db, "testuser", &Password::new("password123")).await?;
+    ///     let (token, plaintext) = ApiToken::create(&db, &user, &["read"], None).await?;
+    ///     token.revoke(&db).await?;
+    ///
+    ///     assert!(ApiToken::get_by_token(&db, &plaintext).await?.is_none());
+    ///     Ok(Html::new("Token revoked!"))
+    /// }
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> cot::Result<()> {
+    /// #     use cot::test::TestDatabase;
+    /// #     let mut test_database = TestDatabase::new_sqlite().await?;
+    /// #     test_database.with_auth().run_migrations().await;
+    /// #     view(test_database.database()).await?;
+    /// #     test_database.cleanup().await?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub async fn revoke<DB: DatabaseBackend>(&self, db: &DB) -> Result<()> {
+        let id = self.id();
+        query!(ApiToken, $id == id)
+            .delete(db)
+            .await
+            .map_err(AuthError::backend_error)?;
+
+        Ok(())
+    }
+
+    /// Returns the ID of the token.
+    #[must_use]
+    pub fn id(&self) -> i64 {
+        match self.id {
+            Auto::Fixed(id) => id,
+            Auto::Auto => unreachable!("ApiToken constructed with an unknown ID"),
+        }
+    }
+
+    /// Returns the ID of the user the token belongs to.
+    #[must_use]
+    pub fn user_id(&self) -> i64 {
+        match self.user.primary_key() {
+            Auto::Fixed(id) => id,
+            Auto::Auto => unreachable!("ApiToken constructed with an unknown user ID"),
+        }
+    }
+
+    /// Returns the scopes of the token.
+    pub fn scopes(&self) -> impl Iterator<Item = &str> {
+        self.scopes.split_whitespace()
+    }
+
+    /// Returns whether the token has the given scope.
+    #[must_use]
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes().any(|token_scope| token_scope == scope)
+    }
+
+    /// Returns the time the token expires at, or [`None`] if it never
+    /// expires.
+    #[must_use]
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+
+    /// Returns whether the token has expired.
+    #[must_use]
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now())
+    }
+
+    /// Returns the time the token was created at.
+    #[must_use]
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+fn generate_token() -> String {
+    let mut rng =
+        StdRng::try_from_rng(&mut SysRng).expect("failed to initialize random number generator");
+    let mut token = [0u8; 32];
+    rng.fill_bytes(&mut token);
+    hex::encode(token)
+}
+
+fn hash_token(token: &str) -> LimitedString<TOKEN_HASH_LENGTH> {
+    let hash = blake3::hash(token.as_bytes());
+    LimitedString::new(hash.to_hex().to_string()).expect("BLAKE3 hex digest is 64 characters long")
+}
+
+/// An error that occurs when validating an API token.
+///
+/// This results in a `401 Unauthorized` response.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ApiTokenError {
+    /// The request doesn't contain a bearer token in the `Authorization`
+    /// header.
+    #[error("{ERROR_PREFIX} missing bearer token")]
+    MissingToken,
+    /// The token doesn't exist (or has been revoked), or its user doesn't
+    /// exist anymore.
+    #[error("{ERROR_PREFIX} invalid token")]
+    InvalidToken,
+    /// The token has expired.
+    #[error("{ERROR_PREFIX} token has expired")]
+    Expired,
+}
+impl_into_cot_error!(ApiTokenError, UNAUTHORIZED);
+
+/// An error returned by [`ApiTokenUser::require_scope`] when the token doesn't
+/// have the required scope.
+///
+/// This results in a `403 Forbidden` response.
+#[derive(Debug, Error)]
+#[error("API token is missing the required scope `{scope}`")]
+pub struct MissingScopeError {
+    scope: String,
+}
+impl_into_cot_error!(MissingScopeError, FORBIDDEN);
+
+impl MissingScopeError {
+    /// Returns the scope that was required.
+    #[must_use]
+    pub fn scope(&self) -> &str {
+        &self.scope
+    }
+}
+
+/// A user authenticated with an [`ApiToken`].
+///
+/// This is added to the request extensions by [`ApiTokenMiddleware`] and can
+/// be used as an extractor in the request handlers.
+///
+/// # Panics
+///
+/// The extractor panics if [`ApiTokenMiddleware`] is not enabled for the
+/// route.
+///
+/// # Examples
+///
+/// ```
+/// use cot::auth::db::ApiTokenUser;
+/// use cot::html::Html;
+///
+/// async fn index(user: ApiTokenUser) -> cot::Result<Html> {
+///     user.require_scope("read")?;
+///
+///     Ok(Html::new(format!("Hello, {}!", user.user().username())))
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ApiTokenUser {
+    token: Arc<ApiToken>,
+    user: Arc<DatabaseUser>,
+}
+
+impl ApiTokenUser {
+    /// Returns the token the request was authenticated with.
+    #[must_use]
+    pub fn token(&self) -> &ApiToken {
+        &self.token
+    }
+
+    /// Returns the user the token belongs to.
+    #[must_use]
+    pub fn user(&self) -> &DatabaseUser {
+        &self.user
+    }
+
+    /// Returns whether the token has the given scope.
+    #[must_use]
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.token.has_scope(scope)
+    }
+
+    /// Checks whether the token has the given scope.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`MissingScopeError`] if the token doesn't have the scope.
+    pub fn require_scope(&self, scope: &str) -> std::result::Result<(), MissingScopeError> {
+        if self.has_scope(scope) {
+            Ok(())
+        } else {
+            Err(MissingScopeError {
+                scope: scope.to_owned(),
+            })
+        }
+    }
+}
+
+impl User for ApiTokenUser {
+    fn id(&self) -> Option<UserId> {
+        User::id(&*self.user)
+    }
+
+    fn username(&self) -> Option<Cow<'_, str>> {
+        User::username(&*self.user)
+    }
+
+    fn is_active(&self) -> bool {
+        self.user.is_active()
+    }
+
+    fn is_authenticated(&self) -> bool {
+        self.user.is_authenticated()
+    }
+}
+
+impl FromRequestHead for ApiTokenUser {
+    async fn from_request_head(head: &RequestHead) -> cot::Result<Self> {
+        let user = head
+            .extensions
+            .get::<ApiTokenUser>()
+            .expect("ApiTokenMiddleware not enabled for the route/project")
+            .clone();
+
+        Ok(user)
+    }
+}
+
+/// A middleware that authenticates the requests with an [`ApiToken`].
+///
+/// The middleware reads the token from the `Authorization: Bearer <token>`
+/// header and looks it up in the project database. If the token exists and
+/// hasn't expired, an [`ApiTokenUser`] is added to the request extensions.
+/// Otherwise, the request is rejected with an [`ApiTokenError`], resulting in
+/// a `401 Unauthorized` response.
+///
+/// # Examples
+///
+/// ```
+/// use cot::Project;
+/// use cot::auth::db::ApiTokenMiddleware;
+/// use cot::project::{MiddlewareContext, RootHandler, RootHandlerBuilder};
+///
+/// struct MyProject;
+/// impl Project for MyProject {
+///     fn middlewares(
+///         &self,
+///         handler: RootHandlerBuilder,
+///         context: &MiddlewareContext,
+///     ) -> RootHandler {
+///         handler.middleware(ApiTokenMiddleware::new()).build()
+///     }
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, Default)]
+pub struct ApiTokenMiddleware;
+
+impl ApiTokenMiddleware {
+    /// Creates a new [`ApiTokenMiddleware`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::auth::db::ApiTokenMiddleware;
+    ///
+    /// let middleware = ApiTokenMiddleware::new();
+    /// ```
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> tower::Layer<S> for ApiTokenMiddleware {
+    type Service = ApiTokenService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ApiTokenService { inner }
+    }
+}
+
+/// Service that authenticates the requests with an [`ApiToken`].
+///
+/// Used by [`ApiTokenMiddleware`].
+#[derive(Debug, Clone)]
+pub struct ApiTokenService<S> {
+    inner: S,
+}
+
+impl<S> Service<Request> for ApiTokenService<S>
+where
+    S: Service<Request, Response = Response, Error = crate::Error> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = S::Response;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, std::result::Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request) -> Self::Future {
+        // See `AuthService::call` for why the inner service is replaced
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(async move {
+            match authenticate_request(&req).await {
+                Ok(user) => {
+                    req.extensions_mut().insert(user);
+                    inner.call(req).await
+                }
+                Err(error) => {
+                    crate::middleware::drain_request_body(req).await;
+                    Err(error)
+                }
+            }
+        })
+    }
+}
+
+/// Finds the user authenticated by the bearer token of the request.
+async fn authenticate_request(req: &Request) -> crate::Result<ApiTokenUser> {
+    let token = req.bearer_token().ok_or(ApiTokenError::MissingToken)?;
+    let database = req.context().database();
+
+    let api_token = ApiToken::get_by_token(database, token)
+        .await?
+        .ok_or(ApiTokenError::InvalidToken)?;
+    if api_token.is_expired() {
+        return Err(ApiTokenError::Expired.into());
+    }
+    let user = DatabaseUser::get_by_id(database, api_token.user_id())
+        .await?
+        .ok_or(ApiTokenError::InvalidToken)?;
+
+    Ok(ApiTokenUser {
+        token: Arc::new(api_token),
+        user: Arc::new(user),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common_types::Password;
+    use crate::db::MockDatabaseBackend;
+
+    fn test_token(scopes: &str, expires_at: Option<DateTime<Utc>>) -> ApiToken {
+        ApiToken {
+            id: Auto::fixed(1),
+            token_hash: hash_token("token"),
+            user: ForeignKey::PrimaryKey(Auto::fixed(2)),
+            scopes: scopes.to_owned(),
+            expires_at,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn generate_token_unique() {
+        let token = generate_token();
+
+        assert_eq!(token.len(), 64);
+        assert_ne!(token, generate_token());
+    }
+
+    #[test]
+    fn hash_token_deterministic() {
+        assert_eq!(hash_token("token"), hash_token("token"));
+        assert_ne!(hash_token("token"), hash_token("other token"));
+    }
+
+    #[test]
+    fn scopes() {
+        let token = test_token("read write", None);
+
+        assert_eq!(token.scopes().collect::<Vec<_>>(), ["read", "write"]);
+        assert!(token.has_scope("read"));
+        assert!(!token.has_scope("admin"));
+        assert_eq!(token.user_id(), 2);
+    }
+
+    #[test]
+    fn is_expired() {
+        assert!(!test_token("", None).is_expired());
+        assert!(!test_token("", Some(Utc::now() + chrono::Duration::hours(1))).is_expired());
+        assert!(test_token("", Some(Utc::now() - chrono::Duration::hours(1))).is_expired());
+    }
+
+    #[test]
+    fn require_scope() {
+        let user = ApiTokenUser {
+            token: Arc::new(test_token("read", None)),
+            user: Arc::new(DatabaseUser::new(
+                Auto::fixed(2),
+                LimitedString::new("testuser").unwrap(),
+                &Password::new("password123"),
+            )),
+        };
+
+        assert!(user.require_scope("read").is_ok());
+        assert_eq!(user.require_scope("write").unwrap_err().scope(), "write");
+        assert_eq!(User::id(&user), Some(UserId::Int(2)));
+    }
+
+    #[cot::test]
+    #[cfg_attr(miri, ignore)]
+    async fn create_invalid_scope() {
+        let mock_db = MockDatabaseBackend::new();
+        let user = DatabaseUser::new(
+            Auto::fixed(1),
+            LimitedString::new("testuser").unwrap(),
+            &Password::new("password123"),
+        );
+
+        let result = ApiToken::create(&mock_db, &user, &["read write"], None).await;
+        assert!(result.is_err());
+    }
+    #[cot::test]
+    async fn middleware_rejection_drains_request_body() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        use futures_util::StreamExt;
+        use tower::{Layer, ServiceExt};
+
+        let exhausted = Arc::new(AtomicBool::new(false));
+        let exhausted_clone = Arc::clone(&exhausted);
+        let chunks =
+            futures_util::stream::iter(["first"]).chain(futures_util::stream::once(async move {
+                exhausted_clone.store(true, Ordering::SeqCst);
+                "last"
+            }));
+        let mut request = crate::test::TestRequestBuilder::post("/").build();
+        *request.body_mut() = crate::Body::from_stream(chunks);
+        let service =
+            ApiTokenMiddleware::new().layer(tower::service_fn(|_request: Request| async move {
+                Ok::<_, crate::Error>(Response::new(crate::Body::empty()))
+            }));
+
+        let error = service.oneshot(request).await.unwrap_err();
+
+        assert_eq!(error.status_code(), http::StatusCode::UNAUTHORIZED);
+        assert!(exhausted.load(Ordering::SeqCst));
+    }
+}
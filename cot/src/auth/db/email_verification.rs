@@ -0,0 +1,210 @@
+//! Verifying the email addresses of the users.
+
+use std::time::Duration;
+
+use chrono::Utc;
+
+use crate::auth::db::{DatabaseUser, signed_token};
+use crate::auth::{AuthError, Result};
+use crate::config::SecretKey;
+use crate::db::{DatabaseBackend, Model};
+
+const EMAIL_VERIFICATION_CONTEXT: &str = "cot.rs email verification token v1";
+
+impl DatabaseUser {
+    /// Generates a token confirming that the user owns their email address.
+    ///
+    /// The token is signed with the secret key and is tied to the user and
+    /// their current email address, so no additional database table is
+    /// needed. It becomes invalid after `valid_for` has passed, or when the
+    /// user's email address changes. Typically, the token is sent to the user
+    /// as part of a link, which should be handled by a view calling
+    /// [`DatabaseUser::verify_email`].
+    ///
+    /// Returns [`None`] if the user has no email address.
+    ///
+    /// # Rate limiting
+    ///
+    /// Cot does not limit how often the verification emails can be sent. If
+    /// you provide an endpoint that resends the verification email, make sure
+    /// to limit how often it can be used (for instance, by storing the time
+    /// the last email was sent in the session or in your own model), so that
+    /// it can't be abused to flood the user's inbox or to exhaust your email
+    /// sending quota.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::auth::db::DatabaseUser;
+    /// use cot::common_types::{Email, Password};
+    /// use cot::db::Model;
+    /// use cot::html::Html;
+    /// use cot::request::{Request, RequestExt};
+    ///
+    /// async fn sign_up(request: Request) -> cot::Result<Html> {
+    ///     let db = request.context().database();
+    ///     let mut user = DatabaseUser::create_user(db, "testuser", &Password::new("password123"))
+    ///         .await?;
+    ///     user.set_email(Some(Email::new("testuser@example.com").expect("valid email")));
+    ///     user.save(db).await?;
+    ///
+    ///     let token = user
+    ///         .email_verification_token(
+    ///             &request.project_config().secret_key,
+    ///             Duration::from_secs(24 * 60 * 60),
+    ///         )
+    ///         .expect("the user has an email address");
+    ///     // ... send the email with a link containing the token
+    ///
+    ///     Ok(Html::new("Check your inbox!"))
+    /// }
+    /// ```
+    #[must_use]
+    pub fn email_verification_token(
+        &self,
+        secret_key: &SecretKey,
+        valid_for: Duration,
+    ) -> Option<String> {
+        let email = self.email.as_ref()?;
+        let expires_at = chrono::TimeDelta::from_std(valid_for)
+            .ok()
+            .and_then(|valid_for| Utc::now().checked_add_signed(valid_for))
+            .unwrap_or(chrono::DateTime::<Utc>::MAX_UTC);
+
+        Some(signed_token::create(
+            EMAIL_VERIFICATION_CONTEXT,
+            secret_key,
+            self.id(),
+            expires_at,
+            &email.email(),
+        ))
+    }
+
+    /// Verifies a token generated by
+    /// [`DatabaseUser::email_verification_token`] and marks the email address
+    /// of the user it was generated for as verified.
+    ///
+    /// Returns the user whose email address was verified, or [`None`] if the
+    /// token is invalid, has expired, or the user's email address has changed
+    /// since the token was generated. Verifying an already verified email
+    /// address again succeeds as well.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there was an error querying or updating the
+    /// database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cot::auth::db::DatabaseUser;
+    /// use cot::html::Html;
+    /// use cot::request::extractors::Path;
+    /// use cot::request::{Request, RequestExt};
+    ///
+    /// async fn verify_email(request: Request, Path(token): Path<String>) -> cot::Result<Html> {
+    ///     let user = DatabaseUser::verify_email(
+    ///         request.context().database(),
+    ///         &request.project_config().secret_key,
+    ///         &token,
+    ///     )
+    ///     .await?;
+    ///
+    ///     match user {
+    ///         Some(user) => Ok(Html::new(format!(
+    ///             "Thank you, {}! Your email address has been verified.",
+    ///             user.username()
+    ///         ))),
+    ///         None => Ok(Html::new("The verification link is invalid or has expired.")),
+    ///     }
+    /// }
+    /// ```
+    pub async fn verify_email<DB: DatabaseBackend>(
+        db: &DB,
+        secret_key: &SecretKey,
+        token: &str,
+    ) -> Result<Option<Self>> {
+        let now = Utc::now();
+        let Some(token) = signed_token::UnverifiedToken::parse(token, now) else {
+            return Ok(None);
+        };
+        let Some(mut user) = Self::get_by_id(db, token.user_id()).await? else {
+            return Ok(None);
+        };
+        let Some(email) = &user.email else {
+            return Ok(None);
+        };
+        if !token.verify(EMAIL_VERIFICATION_CONTEXT, secret_key, &email.email()) {
+            return Ok(None);
+        }
+
+        if !user.email_verified {
+            user.email_verified = true;
+            user.update(db).await.map_err(AuthError::backend_error)?;
+        }
+
+        Ok(Some(user))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cot::db::Auto;
+
+    use super::*;
+    use crate::common_types::{Email, Password};
+    use crate::db::LimitedString;
+
+    fn user() -> DatabaseUser {
+        let mut user = DatabaseUser::new(
+            Auto::fixed(1),
+            LimitedString::new("testuser").unwrap(),
+            &Password::new("password123"),
+        );
+        user.set_email(Some(Email::new("testuser@example.com").unwrap()));
+        user
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn token_requires_email() {
+        let mut user = user();
+        let secret_key = SecretKey::new(b"supersecretkey");
+        assert!(
+            user.email_verification_token(&secret_key, Duration::from_secs(60))
+                .is_some()
+        );
+
+        user.set_email(None);
+        assert!(
+            user.email_verification_token(&secret_key, Duration::from_secs(60))
+                .is_none()
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn token_tied_to_email() {
+        let mut user = user();
+        let secret_key = SecretKey::new(b"supersecretkey");
+        let token = user
+            .email_verification_token(&secret_key, Duration::from_secs(60))
+            .unwrap();
+        let parsed = signed_token::UnverifiedToken::parse(&token, Utc::now()).unwrap();
+        assert_eq!(parsed.user_id(), 1);
+        assert!(parsed.verify(
+            EMAIL_VERIFICATION_CONTEXT,
+            &secret_key,
+            "testuser@example.com"
+        ));
+
+        user.set_email(Some(Email::new("other@example.com").unwrap()));
+        assert!(!parsed.verify(
+            EMAIL_VERIFICATION_CONTEXT,
+            &secret_key,
+            &user.email().unwrap().email()
+        ));
+    }
+}
@@ -0,0 +1,323 @@
+//! Locking accounts out after repeated failed login attempts.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+// Importing `Auto` from `cot` instead of `crate` so that the migration generator
+// can figure out it's an autogenerated field
+use cot::db::Auto;
+
+use crate::auth::db::MAX_USERNAME_LENGTH;
+use crate::auth::{AuthError, Result};
+use crate::db::query::ExprAdd;
+use crate::db::{DatabaseBackend, DatabaseError, LimitedString, Model, RowsNum, model, query};
+
+/// Controls when an account is locked out after failed login attempts.
+///
+/// When there are `max_failures` failed login attempts for a username within
+/// `window`, the account is locked for `lockout_duration`. While an account is
+/// locked, logging in fails with [`AuthError::AccountLocked`], even if the
+/// password is correct. The failure count is reset by a successful login.
+///
+/// The failures are tracked by username, regardless of whether the user
+/// exists, so the lockout can't be used to find out which usernames are
+/// registered. The failures are removed from the database once both the window
+/// and the lockout have passed.
+///
+/// The default policy locks the account for 15 minutes after 5 failed
+/// attempts within 15 minutes.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use cot::auth::db::{DatabaseUserBackend, LockoutPolicy};
+/// use cot::db::Database;
+///
+/// # async fn backend(database: Database) {
+/// let policy = LockoutPolicy::new(3, Duration::from_secs(60), Duration::from_secs(600));
+/// let backend = DatabaseUserBackend::new(database).lockout_policy(policy);
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LockoutPolicy {
+    max_failures: u32,
+    window: Duration,
+    lockout_duration: Duration,
+}
+
+impl LockoutPolicy {
+    const DEFAULT_MAX_FAILURES: u32 = 5;
+    const DEFAULT_WINDOW: Duration = Duration::from_secs(15 * 60);
+    const DEFAULT_LOCKOUT_DURATION: Duration = Duration::from_secs(15 * 60);
+
+    /// Creates a policy locking the account for `lockout_duration` after
+    /// `max_failures` failed login attempts within `window`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_failures` is zero; use [`Self::disabled`] to disable the
+    /// lockout instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::auth::db::LockoutPolicy;
+    ///
+    /// let policy = LockoutPolicy::new(3, Duration::from_secs(60), Duration::from_secs(600));
+    /// assert_eq!(policy.max_failures(), 3);
+    /// ```
+    #[must_use]
+    pub const fn new(max_failures: u32, window: Duration, lockout_duration: Duration) -> Self {
+        assert!(max_failures > 0, "max_failures must not be zero");
+        Self {
+            max_failures,
+            window,
+            lockout_duration,
+        }
+    }
+
+    /// A policy that never locks the accounts out and doesn't track the
+    /// failed login attempts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::auth::db::LockoutPolicy;
+    ///
+    /// assert!(!LockoutPolicy::disabled().is_enabled());
+    /// ```
+    #[must_use]
+    pub const fn disabled() -> Self {
+        Self {
+            max_failures: 0,
+            window: Duration::ZERO,
+            lockout_duration: Duration::ZERO,
+        }
+    }
+
+    /// Returns whether the policy locks the accounts out at all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::auth::db::LockoutPolicy;
+    ///
+    /// assert!(LockoutPolicy::default().is_enabled());
+    /// ```
+    #[must_use]
+    pub const fn is_enabled(&self) -> bool {
+        self.max_failures > 0
+    }
+
+    /// Returns the number of failed login attempts after which the account is
+    /// locked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::auth::db::LockoutPolicy;
+    ///
+    /// assert_eq!(LockoutPolicy::default().max_failures(), 5);
+    /// ```
+    #[must_use]
+    pub const fn max_failures(&self) -> u32 {
+        self.max_failures
+    }
+
+    /// Returns the time window in which the failed login attempts are
+    /// counted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::auth::db::LockoutPolicy;
+    ///
+    /// assert_eq!(LockoutPolicy::default().window(), Duration::from_secs(15 * 60));
+    /// ```
+    #[must_use]
+    pub const fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Returns how long the account stays locked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::auth::db::LockoutPolicy;
+    ///
+    /// assert_eq!(
+    ///     LockoutPolicy::default().lockout_duration(),
+    ///     Duration::from_secs(15 * 60)
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn lockout_duration(&self) -> Duration {
+        self.lockout_duration
+    }
+}
+
+impl Default for LockoutPolicy {
+    fn default() -> Self {
+        Self::new(
+            Self::DEFAULT_MAX_FAILURES,
+            Self::DEFAULT_WINDOW,
+            Self::DEFAULT_LOCKOUT_DURATION,
+        )
+    }
+}
+
+/// The failed login attempts for a username.
+#[derive(Debug, Clone)]
+#[model]
+pub(crate) struct LoginFailure {
+    #[model(primary_key)]
+    id: Auto<i64>,
+    #[model(unique)]
+    username: LimitedString<MAX_USERNAME_LENGTH>,
+    failures: i32,
+    first_failure_at: DateTime<Utc>,
+    locked_until: Option<DateTime<Utc>>,
+}
+
+impl LoginFailure {
+    pub(crate) async fn get_by_username<DB: DatabaseBackend>(
+        db: &DB,
+        username: &LimitedString<MAX_USERNAME_LENGTH>,
+    ) -> Result<Option<Self>> {
+        let username = username.clone();
+        query!(LoginFailure, $username == username)
+            .first(db)
+            .await
+            .map_err(AuthError::backend_error)
+    }
+
+    /// Returns the time until which the account is locked, or [`None`] if it
+    /// isn't locked at the given time.
+    pub(crate) fn locked_until(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.locked_until.filter(|&locked_until| locked_until > now)
+    }
+
+    /// Records a failed login attempt, locking the account if there were too
+    /// many of them.
+    ///
+    /// The failure count is incremented in the database, so that the
+    /// concurrent login attempts are all counted. The rows of the usernames
+    /// whose failures can't lock the account anymore are removed, so that the
+    /// attempts to log in with random usernames don't accumulate.
+    pub(crate) async fn record<DB: DatabaseBackend>(
+        db: &DB,
+        username: LimitedString<MAX_USERNAME_LENGTH>,
+        policy: &LockoutPolicy,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let expired_before =
+            sub_duration(now, policy.window.saturating_add(policy.lockout_duration));
+        query!(LoginFailure, $first_failure_at <= expired_before)
+            .delete(db)
+            .await
+            .map_err(AuthError::backend_error)?;
+
+        // a new window starts after the previous one has passed or the
+        // previous lockout has expired
+        let window_start = sub_duration(now, policy.window);
+        let expired_lockout = Some(now);
+        let reset_username = username.clone();
+        query!(
+            LoginFailure,
+            $username == reset_username
+                && ($first_failure_at <= window_start || $locked_until <= expired_lockout)
+        )
+        .update(
+            db,
+            &[
+                <Self as Model>::Fields::failures.set(0),
+                <Self as Model>::Fields::first_failure_at.set(now),
+                <Self as Model>::Fields::locked_until.set(None::<DateTime<Utc>>),
+            ],
+        )
+        .await
+        .map_err(AuthError::backend_error)?;
+
+        let filter_username = username.clone();
+        let filter = query!(LoginFailure, $username == filter_username);
+        let increment = [
+            <Self as Model>::Fields::failures.set_expr(<Self as Model>::Fields::failures.add(1))
+        ];
+        let result = filter
+            .update(db, &increment)
+            .await
+            .map_err(AuthError::backend_error)?;
+        if result.rows_affected() == RowsNum(0) {
+            let mut failure = Self {
+                id: Auto::auto(),
+                username,
+                failures: 1,
+                first_failure_at: now,
+                locked_until: None,
+            };
+            match failure.insert(db).await {
+                Ok(()) => {}
+                // the row has been inserted by a concurrent login attempt
+                Err(DatabaseError::UniqueViolation) => {
+                    filter
+                        .update(db, &increment)
+                        .await
+                        .map_err(AuthError::backend_error)?;
+                }
+                Err(error) => return Err(AuthError::backend_error(error)),
+            }
+        }
+
+        let Some(failure) = filter.first(db).await.map_err(AuthError::backend_error)? else {
+            // the failures have been reset by a concurrent successful login
+            return Ok(());
+        };
+        if failure.locked_until(now).is_none()
+            && u32::try_from(failure.failures).unwrap_or_default() >= policy.max_failures
+        {
+            filter
+                .update(
+                    db,
+                    &[<Self as Model>::Fields::locked_until
+                        .set(Some(add_duration(now, policy.lockout_duration)))],
+                )
+                .await
+                .map_err(AuthError::backend_error)?;
+        }
+
+        Ok(())
+    }
+
+    /// Forgets the failed login attempts after a successful login.
+    pub(crate) async fn reset<DB: DatabaseBackend>(self, db: &DB) -> Result<()> {
+        let username = self.username;
+        query!(LoginFailure, $username == username)
+            .delete(db)
+            .await
+            .map_err(AuthError::backend_error)?;
+        Ok(())
+    }
+}
+
+fn add_duration(time: DateTime<Utc>, duration: Duration) -> DateTime<Utc> {
+    chrono::TimeDelta::from_std(duration)
+        .ok()
+        .and_then(|duration| time.checked_add_signed(duration))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
+fn sub_duration(time: DateTime<Utc>, duration: Duration) -> DateTime<Utc> {
+    chrono::TimeDelta::from_std(duration)
+        .ok()
+        .and_then(|duration| time.checked_sub_signed(duration))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC)
+}
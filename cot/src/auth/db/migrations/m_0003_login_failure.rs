@@ -0,0 +1,80 @@
+//! Generated by cot CLI 0.6.0 on 2026-10-16 13:12:48+00:00
+
+#[derive(Debug, Copy, Clone)]
+pub(super) struct Migration;
+impl ::cot::db::migrations::Migration for Migration {
+    const APP_NAME: &'static str = "cot";
+    const MIGRATION_NAME: &'static str = "m_0003_login_failure";
+    const DEPENDENCIES: &'static [::cot::db::migrations::MigrationDependency] =
+        &[::cot::db::migrations::MigrationDependency::migration(
+            "cot",
+            "m_0002_api_token",
+        )];
+    const OPERATIONS: &'static [::cot::db::migrations::Operation] = &[
+        ::cot::db::migrations::Operation::create_model()
+            .table_name(::cot::db::Identifier::new("cot__login_failure"))
+            .fields(
+                &[
+                    ::cot::db::migrations::Field::new(
+                            ::cot::db::Identifier::new("id"),
+                            <cot::db::Auto<i64> as ::cot::db::DatabaseField>::TYPE,
+                        )
+                        .set_null(
+                            <cot::db::Auto<i64> as ::cot::db::DatabaseField>::NULLABLE,
+                        )
+                        .auto()
+                        .primary_key(),
+                    ::cot::db::migrations::Field::new(
+                            ::cot::db::Identifier::new("username"),
+                            <crate::db::LimitedString<
+                                { crate::auth::db::MAX_USERNAME_LENGTH },
+                            > as ::cot::db::DatabaseField>::TYPE,
+                        )
+                        .set_null(
+                            <crate::db::LimitedString<
+                                { crate::auth::db::MAX_USERNAME_LENGTH },
+                            > as ::cot::db::DatabaseField>::NULLABLE,
+                        )
+                        .unique(),
+                    ::cot::db::migrations::Field::new(
+                            ::cot::db::Identifier::new("failures"),
+                            <i32 as ::cot::db::DatabaseField>::TYPE,
+                        )
+                        .set_null(<i32 as ::cot::db::DatabaseField>::NULLABLE),
+                    ::cot::db::migrations::Field::new(
+                            ::cot::db::Identifier::new("first_failure_at"),
+                            <chrono::DateTime<chrono::Utc> as ::cot::db::DatabaseField>::TYPE,
+                        )
+                        .set_null(
+                            <chrono::DateTime<
+                                chrono::Utc,
+                            > as ::cot::db::DatabaseField>::NULLABLE,
+                        ),
+                    ::cot::db::migrations::Field::new(
+                            ::cot::db::Identifier::new("locked_until"),
+                            <Option<
+                                chrono::DateTime<chrono::Utc>,
+                            > as ::cot::db::DatabaseField>::TYPE,
+                        )
+                        .set_null(
+                            <Option<
+                                chrono::DateTime<chrono::Utc>,
+                            > as ::cot::db::DatabaseField>::NULLABLE,
+                        ),
+                ],
+            )
+            .build(),
+    ];
+}
+
+#[derive(::core::fmt::Debug)]
+#[::cot::db::model(model_type = "migration")]
+struct _LoginFailure {
+    #[model(primary_key)]
+    id: cot::db::Auto<i64>,
+    #[model(unique)]
+    username: crate::db::LimitedString<{ crate::auth::db::MAX_USERNAME_LENGTH }>,
+    failures: i32,
+    first_failure_at: chrono::DateTime<chrono::Utc>,
+    locked_until: Option<chrono::DateTime<chrono::Utc>>,
+}
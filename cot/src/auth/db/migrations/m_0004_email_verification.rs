@@ -0,0 +1,51 @@
+//! Generated by cot CLI 0.6.0 on 2026-10-16 15:02:31+00:00
+
+#[derive(Debug, Copy, Clone)]
+pub(super) struct Migration;
+impl ::cot::db::migrations::Migration for Migration {
+    const APP_NAME: &'static str = "cot";
+    const MIGRATION_NAME: &'static str = "m_0004_email_verification";
+    const DEPENDENCIES: &'static [::cot::db::migrations::MigrationDependency] =
+        &[::cot::db::migrations::MigrationDependency::migration(
+            "cot",
+            "m_0003_login_failure",
+        )];
+    const OPERATIONS: &'static [::cot::db::migrations::Operation] = &[
+        ::cot::db::migrations::Operation::add_field()
+            .table_name(::cot::db::Identifier::new("cot__database_user"))
+            .field(
+                ::cot::db::migrations::Field::new(
+                    ::cot::db::Identifier::new("email"),
+                    <Option<crate::common_types::Email> as ::cot::db::DatabaseField>::TYPE,
+                )
+                .set_null(
+                    <Option<crate::common_types::Email> as ::cot::db::DatabaseField>::NULLABLE,
+                ),
+            )
+            .build(),
+        ::cot::db::migrations::Operation::add_field()
+            .table_name(::cot::db::Identifier::new("cot__database_user"))
+            .field(
+                ::cot::db::migrations::Field::new(
+                    ::cot::db::Identifier::new("email_verified"),
+                    <bool as ::cot::db::DatabaseField>::TYPE,
+                )
+                .set_null(<bool as ::cot::db::DatabaseField>::NULLABLE)
+                .default_value(::cot::db::migrations::DefaultValue::Bool(false)),
+            )
+            .build(),
+    ];
+}
+
+#[derive(::core::fmt::Debug)]
+#[::cot::db::model(model_type = "migration")]
+struct _DatabaseUser {
+    #[model(primary_key)]
+    id: cot::db::Auto<i64>,
+    #[model(unique)]
+    username: crate::db::LimitedString<{ crate::auth::db::MAX_USERNAME_LENGTH }>,
+    password: crate::auth::PasswordHash,
+    email: Option<crate::common_types::Email>,
+    #[model(default = false)]
+    email_verified: bool,
+}
@@ -0,0 +1,250 @@
+//! Resetting forgotten passwords.
+
+use std::time::Duration;
+
+use chrono::Utc;
+
+use crate::auth::db::{DatabaseUser, signed_token};
+use crate::auth::{AuthError, Result};
+use crate::common_types::Password;
+use crate::config::SecretKey;
+use crate::db::{DatabaseBackend, Model};
+
+const PASSWORD_RESET_CONTEXT: &str = "cot.rs password reset token v1";
+
+impl DatabaseUser {
+    /// Generates a token allowing the user to set a new password without
+    /// knowing the current one.
+    ///
+    /// The token is signed with the secret key and is tied to the user and
+    /// their current password hash, so no additional database table is
+    /// needed, and the token can only be used once: it becomes invalid when
+    /// the password changes, as well as after `valid_for` has passed. The token
+    /// only contains URL-safe characters, so it can be sent to the user's
+    /// email address as part of a link.
+    ///
+    /// The link should be handled by a view that checks the token with
+    /// [`DatabaseUser::verify_password_reset_token`] before displaying a form
+    /// for the new password, and then sets it with
+    /// [`DatabaseUser::reset_password`].
+    ///
+    /// Just like when sending the verification emails (see
+    /// [`DatabaseUser::email_verification_token`]), make sure to limit how
+    /// often the password reset emails can be requested.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::auth::db::DatabaseUser;
+    /// use cot::html::Html;
+    /// use cot::request::{Request, RequestExt};
+    ///
+    /// async fn forgot_password(request: Request) -> cot::Result<Html> {
+    ///     let user = DatabaseUser::get_by_username(request.context().database(), "testuser").await?;
+    ///     if let Some(user) = user {
+    ///         let token = user.password_reset_token(
+    ///             &request.project_config().secret_key,
+    ///             Duration::from_secs(60 * 60),
+    ///         );
+    ///         // ... send the email with a link containing the token
+    ///     }
+    ///
+    ///     // don't reveal whether the user exists
+    ///     Ok(Html::new("If the account exists, we have sent you an email."))
+    /// }
+    /// ```
+    #[must_use]
+    pub fn password_reset_token(&self, secret_key: &SecretKey, valid_for: Duration) -> String {
+        let expires_at = chrono::TimeDelta::from_std(valid_for)
+            .ok()
+            .and_then(|valid_for| Utc::now().checked_add_signed(valid_for))
+            .unwrap_or(chrono::DateTime::<Utc>::MAX_UTC);
+
+        signed_token::create(
+            PASSWORD_RESET_CONTEXT,
+            secret_key,
+            self.id(),
+            expires_at,
+            self.password.as_str(),
+        )
+    }
+
+    /// Checks a token generated by [`DatabaseUser::password_reset_token`]
+    /// without using it.
+    ///
+    /// Returns the user the token was generated for, or [`None`] if the token
+    /// is invalid, has expired, or has already been used (i.e., the password
+    /// has changed since the token was generated).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there was an error querying the database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cot::auth::db::DatabaseUser;
+    /// use cot::html::Html;
+    /// use cot::request::extractors::Path;
+    /// use cot::request::{Request, RequestExt};
+    ///
+    /// async fn reset_password_form(
+    ///     request: Request,
+    ///     Path(token): Path<String>,
+    /// ) -> cot::Result<Html> {
+    ///     let user = DatabaseUser::verify_password_reset_token(
+    ///         request.context().database(),
+    ///         &request.project_config().secret_key,
+    ///         &token,
+    ///     )
+    ///     .await?;
+    ///
+    ///     match user {
+    ///         Some(user) => Ok(Html::new(format!(
+    ///             "<h1>Set a new password for {}</h1>...",
+    ///             user.username()
+    ///         ))),
+    ///         None => Ok(Html::new("The reset link is invalid or has expired.")),
+    ///     }
+    /// }
+    /// ```
+    pub async fn verify_password_reset_token<DB: DatabaseBackend>(
+        db: &DB,
+        secret_key: &SecretKey,
+        token: &str,
+    ) -> Result<Option<Self>> {
+        let Some(token) = signed_token::UnverifiedToken::parse(token, Utc::now()) else {
+            return Ok(None);
+        };
+        let Some(user) = Self::get_by_id(db, token.user_id()).await? else {
+            return Ok(None);
+        };
+        if !token.verify(PASSWORD_RESET_CONTEXT, secret_key, user.password.as_str()) {
+            return Ok(None);
+        }
+
+        Ok(Some(user))
+    }
+
+    /// Sets a new password for the user using a token generated by
+    /// [`DatabaseUser::password_reset_token`].
+    ///
+    /// The new password is hashed the same way as in
+    /// [`DatabaseUser::set_password`], and the token can't be used again.
+    /// Returns the user whose password was changed, or [`None`] if the token
+    /// is invalid, has expired, or has already been used.
+    ///
+    /// # Sessions
+    ///
+    /// Changing the password logs the user out of all their active sessions,
+    /// because the session authentication hash of a [`DatabaseUser`] is
+    /// derived from the password hash (see
+    /// [`User::session_auth_hash`](crate::auth::User::session_auth_hash)). If
+    /// you want the user to stay logged in on the device they have reset the
+    /// password on, log the returned user in again with
+    /// [`Auth::login`](crate::auth::Auth::login).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there was an error querying or updating the
+    /// database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cot::auth::Auth;
+    /// use cot::auth::db::DatabaseUser;
+    /// use cot::common_types::Password;
+    /// use cot::html::Html;
+    /// use cot::request::extractors::Path;
+    /// use cot::request::{Request, RequestExt};
+    ///
+    /// async fn reset_password(
+    ///     auth: Auth,
+    ///     request: Request,
+    ///     Path(token): Path<String>,
+    /// ) -> cot::Result<Html> {
+    ///     // in a real application, read the new password from a form
+    ///     let new_password = Password::new("new_password");
+    ///     let user = DatabaseUser::reset_password(
+    ///         request.context().database(),
+    ///         &request.project_config().secret_key,
+    ///         &token,
+    ///         &new_password,
+    ///     )
+    ///     .await?;
+    ///
+    ///     match user {
+    ///         Some(user) => {
+    ///             // optionally, keep the user logged in on this device
+    ///             auth.login(Box::new(user)).await?;
+    ///             Ok(Html::new("Your password has been changed."))
+    ///         }
+    ///         None => Ok(Html::new("The reset link is invalid or has expired.")),
+    ///     }
+    /// }
+    /// ```
+    pub async fn reset_password<DB: DatabaseBackend>(
+        db: &DB,
+        secret_key: &SecretKey,
+        token: &str,
+        new_password: &Password,
+    ) -> Result<Option<Self>> {
+        let Some(mut user) = Self::verify_password_reset_token(db, secret_key, token).await? else {
+            return Ok(None);
+        };
+
+        user.set_password(new_password);
+        user.update(db).await.map_err(AuthError::backend_error)?;
+
+        Ok(Some(user))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cot::db::Auto;
+
+    use super::*;
+    use crate::db::LimitedString;
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn token_tied_to_password() {
+        let mut user = DatabaseUser::new(
+            Auto::fixed(1),
+            LimitedString::new("testuser").unwrap(),
+            &Password::new("password123"),
+        );
+        let secret_key = SecretKey::new(b"supersecretkey");
+        let token = user.password_reset_token(&secret_key, Duration::from_secs(60));
+
+        let parsed = signed_token::UnverifiedToken::parse(&token, Utc::now()).unwrap();
+        assert_eq!(parsed.user_id(), 1);
+        assert!(parsed.verify(PASSWORD_RESET_CONTEXT, &secret_key, user.password.as_str()));
+
+        user.set_password(&Password::new("password123"));
+        assert!(!parsed.verify(PASSWORD_RESET_CONTEXT, &secret_key, user.password.as_str()));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn token_not_usable_for_email_verification() {
+        let user = DatabaseUser::new(
+            Auto::fixed(1),
+            LimitedString::new("testuser").unwrap(),
+            &Password::new("password123"),
+        );
+        let secret_key = SecretKey::new(b"supersecretkey");
+        let token = user.password_reset_token(&secret_key, Duration::from_secs(60));
+
+        let parsed = signed_token::UnverifiedToken::parse(&token, Utc::now()).unwrap();
+        assert!(!parsed.verify(
+            "cot.rs email verification token v1",
+            &secret_key,
+            user.password.as_str()
+        ));
+    }
+}
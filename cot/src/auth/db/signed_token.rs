@@ -0,0 +1,128 @@
+//! Stateless, expiring tokens signed with the project secret key.
+//!
+//! A token contains the user ID and the expiration time, and is signed along
+//! with a value bound to the user (e.g., their email address), so that it
+//! becomes invalid when that value changes. This way, the tokens don't need to
+//! be stored in the database.
+
+use chrono::{DateTime, Utc};
+
+use crate::config::SecretKey;
+
+/// Creates a token for the given user, valid until `expires_at`.
+pub(super) fn create(
+    context: &str,
+    secret_key: &SecretKey,
+    user_id: i64,
+    expires_at: DateTime<Utc>,
+    bound_to: &str,
+) -> String {
+    let message = format!("{user_id}.{}", expires_at.timestamp());
+    let signature = sign(context, secret_key, &message, bound_to);
+    format!("{message}.{}", signature.to_hex())
+}
+
+/// A token that has the right format and hasn't expired, but whose signature
+/// hasn't been verified yet.
+#[derive(Debug)]
+pub(super) struct UnverifiedToken<'a> {
+    user_id: i64,
+    message: &'a str,
+    signature: blake3::Hash,
+}
+
+impl<'a> UnverifiedToken<'a> {
+    /// Parses the token, returning [`None`] if it's malformed or has expired
+    /// at the given time.
+    pub(super) fn parse(token: &'a str, now: DateTime<Utc>) -> Option<Self> {
+        let (message, signature) = token.rsplit_once('.')?;
+        let signature = blake3::Hash::from_hex(signature).ok()?;
+        let (user_id, expires_at) = message.split_once('.')?;
+        let user_id = user_id.parse().ok()?;
+        let expires_at = DateTime::from_timestamp(expires_at.parse().ok()?, 0)?;
+        if expires_at <= now {
+            return None;
+        }
+
+        Some(Self {
+            user_id,
+            message,
+            signature,
+        })
+    }
+
+    /// Returns the ID of the user the token was issued for.
+    pub(super) fn user_id(&self) -> i64 {
+        self.user_id
+    }
+
+    /// Returns whether the token was signed with the given key and context
+    /// for the given value.
+    pub(super) fn verify(&self, context: &str, secret_key: &SecretKey, bound_to: &str) -> bool {
+        // `blake3::Hash` comparisons are constant-time
+        sign(context, secret_key, self.message, bound_to) == self.signature
+    }
+}
+
+fn sign(context: &str, secret_key: &SecretKey, message: &str, bound_to: &str) -> blake3::Hash {
+    let key = blake3::derive_key(context, secret_key.as_bytes());
+    let mut hasher = blake3::Hasher::new_keyed(&key);
+    hasher.update(message.as_bytes());
+    // the message never contains a NUL byte, so this separates it unambiguously
+    hasher.update(b"\0");
+    hasher.update(bound_to.as_bytes());
+    hasher.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+
+    use super::*;
+
+    const CONTEXT: &str = "cot.rs signed token test";
+
+    #[test]
+    fn create_and_verify() {
+        let key = SecretKey::new(b"supersecretkey");
+        let now = Utc::now();
+        let token = create(CONTEXT, &key, 42, now + TimeDelta::hours(1), "value");
+
+        let parsed = UnverifiedToken::parse(&token, now).unwrap();
+        assert_eq!(parsed.user_id(), 42);
+        assert!(parsed.verify(CONTEXT, &key, "value"));
+        assert!(!parsed.verify(CONTEXT, &key, "other value"));
+        assert!(!parsed.verify("other context", &key, "value"));
+        assert!(!parsed.verify(CONTEXT, &SecretKey::new(b"otherkey"), "value"));
+    }
+
+    #[test]
+    fn expired() {
+        let key = SecretKey::new(b"supersecretkey");
+        let now = Utc::now();
+        let token = create(CONTEXT, &key, 42, now - TimeDelta::seconds(1), "value");
+
+        assert!(UnverifiedToken::parse(&token, now).is_none());
+    }
+
+    #[test]
+    fn tampered() {
+        let key = SecretKey::new(b"supersecretkey");
+        let now = Utc::now();
+        let token = create(CONTEXT, &key, 42, now + TimeDelta::hours(1), "value");
+        let tampered = token.replacen("42.", "43.", 1);
+
+        let parsed = UnverifiedToken::parse(&tampered, now).unwrap();
+        assert!(!parsed.verify(CONTEXT, &key, "value"));
+    }
+
+    #[test]
+    fn malformed() {
+        let now = Utc::now();
+
+        assert!(UnverifiedToken::parse("", now).is_none());
+        assert!(UnverifiedToken::parse("42", now).is_none());
+        assert!(UnverifiedToken::parse("42.123.nothex", now).is_none());
+        assert!(UnverifiedToken::parse(&format!("x.123.{}", "0".repeat(64)), now).is_none());
+    }
+}
@@ -0,0 +1,762 @@
+//! JSON Web Token (JWT) authentication.
+//!
+//! This module provides stateless authentication for APIs using signed
+//! [JSON Web Tokens](https://datatracker.ietf.org/doc/html/rfc7519). A token is
+//! issued with [`JwtConfig::issue`] (e.g., by a login endpoint) and sent back
+//! by the client in the `Authorization: Bearer <token>` header. The
+//! [`JwtMiddleware`] validates the token and makes the authenticated
+//! [`JwtUser`] available to the request handlers.
+//!
+//! Both the HMAC-based `HS256` and the RSA-based `RS256` algorithms are
+//! supported.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use cot::auth::UserId;
+//! use cot::auth::jwt::{Claims, JwtConfig, JwtUser};
+//! use cot::html::Html;
+//!
+//! let config = JwtConfig::hs256(b"my secret key");
+//! let token = config.issue(&Claims::new(UserId::Int(42), Duration::from_secs(3600)))?;
+//!
+//! async fn index(user: JwtUser) -> Html {
+//!     Html::new(format!("Hello, user {:?}!", user.claims().user_id()))
+//! }
+//! # Ok::<(), cot::auth::jwt::JwtIssueError>(())
+//! ```
+
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use cot_core::error::impl_into_cot_error;
+use derive_more::with_trait::Debug;
+use futures_core::future::BoxFuture;
+use jsonwebtoken::errors::ErrorKind;
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tower::Service;
+
+use crate::auth::{User, UserId};
+use crate::request::extractors::FromRequestHead;
+use crate::request::{Request, RequestExt, RequestHead};
+use crate::response::Response;
+
+const ERROR_PREFIX: &str = "failed to authenticate with a JWT:";
+const RESERVED_CLAIMS: [&str; 3] = ["sub", "exp", "iat"];
+
+/// An error that occurs when validating a JWT.
+///
+/// This results in a `401 Unauthorized` response.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum JwtError {
+    /// The request doesn't contain a bearer token in the `Authorization`
+    /// header.
+    #[error("{ERROR_PREFIX} missing bearer token")]
+    MissingToken,
+    /// The token has expired.
+    #[error("{ERROR_PREFIX} token has expired")]
+    Expired,
+    /// The token is not valid yet (its `nbf` claim is in the future).
+    #[error("{ERROR_PREFIX} token is not valid yet")]
+    NotYetValid,
+    /// The token signature doesn't match the configured key.
+    #[error("{ERROR_PREFIX} invalid token signature")]
+    InvalidSignature,
+    /// The token is malformed, uses an unexpected algorithm, or is missing
+    /// required claims.
+    #[error("{ERROR_PREFIX} invalid token: {reason}")]
+    InvalidToken {
+        /// The reason why the token is invalid.
+        reason: String,
+    },
+}
+impl_into_cot_error!(JwtError, UNAUTHORIZED);
+
+impl From<jsonwebtoken::errors::Error> for JwtError {
+    fn from(error: jsonwebtoken::errors::Error) -> Self {
+        match error.kind() {
+            ErrorKind::ExpiredSignature => Self::Expired,
+            ErrorKind::ImmatureSignature => Self::NotYetValid,
+            ErrorKind::InvalidSignature => Self::InvalidSignature,
+            ErrorKind::InvalidAlgorithm => Self::InvalidToken {
+                reason: "unexpected signing algorithm".to_owned(),
+            },
+            ErrorKind::MissingRequiredClaim(claim) => Self::InvalidToken {
+                reason: format!("missing required claim `{claim}`"),
+            },
+            _ => Self::InvalidToken {
+                reason: error.to_string(),
+            },
+        }
+    }
+}
+
+/// An error that occurs when issuing a JWT.
+#[derive(Debug, Error)]
+#[error("failed to issue a JWT: {0}")]
+pub struct JwtIssueError(#[source] Box<dyn std::error::Error + Send + Sync>);
+impl_into_cot_error!(JwtIssueError);
+
+/// An error that occurs when a JWT key can't be parsed.
+#[derive(Debug, Error)]
+#[error("invalid JWT key: {0}")]
+pub struct JwtKeyError(#[source] jsonwebtoken::errors::Error);
+impl_into_cot_error!(JwtKeyError);
+
+/// The keys and settings used to issue and validate JWTs.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use cot::auth::jwt::JwtConfig;
+///
+/// let config = JwtConfig::hs256(b"my secret key").leeway(Duration::from_secs(30));
+/// ```
+#[derive(Debug, Clone)]
+pub struct JwtConfig {
+    algorithm: Algorithm,
+    #[debug("..")]
+    encoding_key: Option<EncodingKey>,
+    #[debug("..")]
+    decoding_key: DecodingKey,
+    leeway: Duration,
+}
+
+impl JwtConfig {
+    const DEFAULT_LEEWAY: Duration = Duration::from_secs(60);
+
+    /// Creates a config that signs the tokens with HMAC-SHA256 using the given
+    /// secret.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::auth::jwt::JwtConfig;
+    ///
+    /// let config = JwtConfig::hs256(b"my secret key");
+    /// ```
+    #[must_use]
+    pub fn hs256(secret: impl AsRef<[u8]>) -> Self {
+        let secret = secret.as_ref();
+        Self::new(
+            Algorithm::HS256,
+            Some(EncodingKey::from_secret(secret)),
+            DecodingKey::from_secret(secret),
+        )
+    }
+
+    /// Creates a config that signs the tokens with RSA-SHA256 using the given
+    /// PEM-encoded private and public keys.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either of the keys is not a valid PEM-encoded RSA
+    /// key.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use cot::auth::jwt::JwtConfig;
+    ///
+    /// let config = JwtConfig::rs256(
+    ///     &std::fs::read("private_key.pem").unwrap(),
+    ///     &std::fs::read("public_key.pem").unwrap(),
+    /// )?;
+    /// # Ok::<(), cot::auth::jwt::JwtKeyError>(())
+    /// ```
+    pub fn rs256(private_key_pem: &[u8], public_key_pem: &[u8]) -> Result<Self, JwtKeyError> {
+        Ok(Self::new(
+            Algorithm::RS256,
+            Some(EncodingKey::from_rsa_pem(private_key_pem).map_err(JwtKeyError)?),
+            DecodingKey::from_rsa_pem(public_key_pem).map_err(JwtKeyError)?,
+        ))
+    }
+
+    /// Creates a config that only validates the tokens signed with RSA-SHA256,
+    /// using the given PEM-encoded public key.
+    ///
+    /// This is useful when the tokens are issued by another service. Calling
+    /// [`Self::issue`] on such a config returns an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is not a valid PEM-encoded RSA public key.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use cot::auth::jwt::JwtConfig;
+    ///
+    /// let config = JwtConfig::rs256_public_key(&std::fs::read("public_key.pem").unwrap())?;
+    /// # Ok::<(), cot::auth::jwt::JwtKeyError>(())
+    /// ```
+    pub fn rs256_public_key(public_key_pem: &[u8]) -> Result<Self, JwtKeyError> {
+        Ok(Self::new(
+            Algorithm::RS256,
+            None,
+            DecodingKey::from_rsa_pem(public_key_pem).map_err(JwtKeyError)?,
+        ))
+    }
+
+    fn new(
+        algorithm: Algorithm,
+        encoding_key: Option<EncodingKey>,
+        decoding_key: DecodingKey,
+    ) -> Self {
+        Self {
+            algorithm,
+            encoding_key,
+            decoding_key,
+            leeway: Self::DEFAULT_LEEWAY,
+        }
+    }
+
+    /// Sets the leeway allowed when checking the token expiration time, to
+    /// account for the clock skew between the servers. The default is 60
+    /// seconds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::auth::jwt::JwtConfig;
+    ///
+    /// let config = JwtConfig::hs256(b"my secret key").leeway(Duration::from_secs(5));
+    /// ```
+    #[must_use]
+    pub fn leeway(mut self, leeway: Duration) -> Self {
+        self.leeway = leeway;
+        self
+    }
+
+    /// Issues a signed token containing the given claims.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the config doesn't have a private key (see
+    /// [`Self::rs256_public_key`]), or if the token can't be signed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::auth::UserId;
+    /// use cot::auth::jwt::{Claims, JwtConfig};
+    ///
+    /// let config = JwtConfig::hs256(b"my secret key");
+    /// let claims = Claims::new(UserId::Int(42), Duration::from_secs(3600)).with_claim("admin", true);
+    /// let token = config.issue(&claims)?;
+    /// # Ok::<(), cot::auth::jwt::JwtIssueError>(())
+    /// ```
+    pub fn issue(&self, claims: &Claims) -> Result<String, JwtIssueError> {
+        let encoding_key = self
+            .encoding_key
+            .as_ref()
+            .ok_or_else(|| JwtIssueError("no private key configured".into()))?;
+
+        jsonwebtoken::encode(&Header::new(self.algorithm), claims, encoding_key)
+            .map_err(|error| JwtIssueError(Box::new(error)))
+    }
+
+    /// Validates the given token and returns its claims.
+    ///
+    /// This checks the token signature, as well as its expiration time (and
+    /// its "not before" time, if present), allowing for the configured
+    /// leeway.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token is malformed, its signature is invalid,
+    /// or it has expired.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::auth::UserId;
+    /// use cot::auth::jwt::{Claims, JwtConfig};
+    ///
+    /// let config = JwtConfig::hs256(b"my secret key");
+    /// let token = config
+    ///     .issue(&Claims::new(UserId::Int(42), Duration::from_secs(3600)))
+    ///     .unwrap();
+    ///
+    /// let claims = config.validate(&token)?;
+    /// assert_eq!(claims.user_id(), &UserId::Int(42));
+    /// # Ok::<(), cot::auth::jwt::JwtError>(())
+    /// ```
+    pub fn validate(&self, token: &str) -> Result<Claims, JwtError> {
+        let mut validation = Validation::new(self.algorithm);
+        validation.leeway = self.leeway.as_secs();
+        validation.validate_nbf = true;
+        validation.set_required_spec_claims(&["exp", "sub"]);
+
+        let token_data = jsonwebtoken::decode::<Claims>(token, &self.decoding_key, &validation)?;
+        Ok(token_data.claims)
+    }
+}
+
+/// The claims stored in a JWT.
+///
+/// Apart from the user ID, the issue time, and the expiration time, the
+/// claims can contain arbitrary custom data.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use cot::auth::UserId;
+/// use cot::auth::jwt::Claims;
+///
+/// let claims = Claims::new(UserId::Int(42), Duration::from_secs(3600)).with_claim("role", "admin");
+/// assert_eq!(claims.get("role").unwrap(), "admin");
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Claims {
+    sub: UserId,
+    exp: i64,
+    iat: i64,
+    #[serde(flatten)]
+    extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Claims {
+    /// Creates new claims for the given user, expiring after the given time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::auth::UserId;
+    /// use cot::auth::jwt::Claims;
+    ///
+    /// let claims = Claims::new(UserId::Int(42), Duration::from_secs(3600));
+    /// ```
+    #[must_use]
+    pub fn new(user_id: UserId, expires_in: Duration) -> Self {
+        let now = Utc::now().timestamp();
+        let expires_in = i64::try_from(expires_in.as_secs()).unwrap_or(i64::MAX);
+
+        Self {
+            sub: user_id,
+            exp: now.saturating_add(expires_in),
+            iat: now,
+            extra: serde_json::Map::new(),
+        }
+    }
+
+    /// Adds a custom claim.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the claim name is one of the claims managed by this type:
+    /// `sub`, `exp`, or `iat`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::auth::UserId;
+    /// use cot::auth::jwt::Claims;
+    ///
+    /// let claims = Claims::new(UserId::Int(42), Duration::from_secs(3600)).with_claim("admin", true);
+    /// ```
+    #[must_use]
+    pub fn with_claim(
+        mut self,
+        name: impl Into<String>,
+        value: impl Into<serde_json::Value>,
+    ) -> Self {
+        let name = name.into();
+        assert!(
+            !RESERVED_CLAIMS.contains(&name.as_str()),
+            "the `{name}` claim can't be set directly"
+        );
+        self.extra.insert(name, value.into());
+        self
+    }
+
+    /// Returns the ID of the user the token was issued for (the `sub` claim).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::auth::UserId;
+    /// use cot::auth::jwt::Claims;
+    ///
+    /// let claims = Claims::new(UserId::Int(42), Duration::from_secs(3600));
+    /// assert_eq!(claims.user_id(), &UserId::Int(42));
+    /// ```
+    #[must_use]
+    pub fn user_id(&self) -> &UserId {
+        &self.sub
+    }
+
+    /// Returns the time the token was issued at (the `iat` claim).
+    #[must_use]
+    pub fn issued_at(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(self.iat, 0)
+    }
+
+    /// Returns the time the token expires at (the `exp` claim).
+    #[must_use]
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(self.exp, 0)
+    }
+
+    /// Returns the value of a custom claim, or [`None`] if it's not present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::auth::UserId;
+    /// use cot::auth::jwt::Claims;
+    ///
+    /// let claims = Claims::new(UserId::Int(42), Duration::from_secs(3600)).with_claim("admin", true);
+    /// assert_eq!(claims.get("admin"), Some(&serde_json::Value::Bool(true)));
+    /// assert_eq!(claims.get("role"), None);
+    /// ```
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&serde_json::Value> {
+        self.extra.get(name)
+    }
+}
+
+/// A user authenticated with a JWT.
+///
+/// This is added to the request extensions by [`JwtMiddleware`] and can be
+/// used as an extractor in the request handlers.
+///
+/// # Panics
+///
+/// The extractor panics if [`JwtMiddleware`] is not enabled for the route.
+///
+/// # Examples
+///
+/// ```
+/// use cot::auth::User;
+/// use cot::auth::jwt::JwtUser;
+/// use cot::html::Html;
+///
+/// async fn index(user: JwtUser) -> Html {
+///     Html::new(format!("Hello, user {:?}!", user.id()))
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct JwtUser {
+    claims: Arc<Claims>,
+}
+
+impl JwtUser {
+    /// Returns the claims of the token the user was authenticated with.
+    #[must_use]
+    pub fn claims(&self) -> &Claims {
+        &self.claims
+    }
+}
+
+impl User for JwtUser {
+    fn id(&self) -> Option<UserId> {
+        Some(self.claims.sub.clone())
+    }
+
+    fn is_active(&self) -> bool {
+        true
+    }
+
+    fn is_authenticated(&self) -> bool {
+        true
+    }
+}
+
+impl FromRequestHead for JwtUser {
+    async fn from_request_head(head: &RequestHead) -> cot::Result<Self> {
+        let user = head
+            .extensions
+            .get::<JwtUser>()
+            .expect("JwtMiddleware not enabled for the route/project")
+            .clone();
+
+        Ok(user)
+    }
+}
+
+/// A middleware that authenticates the requests with a JWT.
+///
+/// The middleware reads the token from the `Authorization: Bearer <token>`
+/// header and validates it using the given [`JwtConfig`]. If the token is
+/// valid, a [`JwtUser`] is added to the request extensions. Otherwise, the
+/// request is rejected with a [`JwtError`], resulting in a
+/// `401 Unauthorized` response describing why the token was rejected.
+///
+/// # Examples
+///
+/// ```
+/// use cot::Project;
+/// use cot::auth::jwt::{JwtConfig, JwtMiddleware};
+/// use cot::project::{MiddlewareContext, RootHandler, RootHandlerBuilder};
+///
+/// struct MyProject;
+/// impl Project for MyProject {
+///     fn middlewares(
+///         &self,
+///         handler: RootHandlerBuilder,
+///         context: &MiddlewareContext,
+///     ) -> RootHandler {
+///         handler
+///             .middleware(JwtMiddleware::new(JwtConfig::hs256(
+///                 context.config().secret_key.as_bytes(),
+///             )))
+///             .build()
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct JwtMiddleware {
+    config: Arc<JwtConfig>,
+}
+
+impl JwtMiddleware {
+    /// Creates a new [`JwtMiddleware`] validating the tokens with the given
+    /// config.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::auth::jwt::{JwtConfig, JwtMiddleware};
+    ///
+    /// let middleware = JwtMiddleware::new(JwtConfig::hs256(b"my secret key"));
+    /// ```
+    #[must_use]
+    pub fn new(config: JwtConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+        }
+    }
+}
+
+impl<S> tower::Layer<S> for JwtMiddleware {
+    type Service = JwtService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        JwtService {
+            inner,
+            config: Arc::clone(&self.config),
+        }
+    }
+}
+
+/// Service that authenticates the requests with a JWT.
+///
+/// Used by [`JwtMiddleware`].
+#[derive(Debug, Clone)]
+pub struct JwtService<S> {
+    inner: S,
+    config: Arc<JwtConfig>,
+}
+
+impl<S> Service<Request> for JwtService<S>
+where
+    S: Service<Request, Response = Response, Error = crate::Error> + Send,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request) -> Self::Future {
+        let claims = req
+            .bearer_token()
+            .ok_or(JwtError::MissingToken)
+            .and_then(|token| self.config.validate(token));
+
+        match claims {
+            Ok(claims) => {
+                req.extensions_mut().insert(JwtUser {
+                    claims: Arc::new(claims),
+                });
+                Box::pin(self.inner.call(req))
+            }
+            Err(error) => Box::pin(async move {
+                crate::middleware::drain_request_body(req).await;
+                Err(error.into())
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{HeaderValue, header};
+    use tower::{Layer, ServiceExt};
+
+    use super::*;
+    use crate::test::TestRequestBuilder;
+    use crate::{Body, StatusCode};
+
+    const SECRET: &[u8] = b"test secret";
+
+    fn expired_claims(seconds_ago: i64) -> Claims {
+        let mut claims = Claims::new(UserId::Int(1), Duration::ZERO);
+        claims.exp -= seconds_ago;
+        claims
+    }
+
+    #[test]
+    fn issue_and_validate() {
+        let config = JwtConfig::hs256(SECRET);
+        let claims = Claims::new(UserId::String("alice".to_owned()), Duration::from_secs(60))
+            .with_claim("admin", true);
+
+        let token = config.issue(&claims).unwrap();
+        let validated = config.validate(&token).unwrap();
+
+        assert_eq!(validated, claims);
+        assert_eq!(validated.user_id(), &UserId::String("alice".to_owned()));
+        assert_eq!(validated.get("admin"), Some(&serde_json::Value::Bool(true)));
+    }
+
+    #[test]
+    fn validate_expired() {
+        let config = JwtConfig::hs256(SECRET).leeway(Duration::ZERO);
+        let token = config.issue(&expired_claims(10)).unwrap();
+
+        assert!(matches!(config.validate(&token), Err(JwtError::Expired)));
+    }
+
+    #[test]
+    fn validate_expired_within_leeway() {
+        let config = JwtConfig::hs256(SECRET).leeway(Duration::from_secs(60));
+        let token = config.issue(&expired_claims(10)).unwrap();
+
+        assert!(config.validate(&token).is_ok());
+    }
+
+    #[test]
+    fn validate_invalid_signature() {
+        let token = JwtConfig::hs256(b"other secret")
+            .issue(&Claims::new(UserId::Int(1), Duration::from_secs(60)))
+            .unwrap();
+
+        assert!(matches!(
+            JwtConfig::hs256(SECRET).validate(&token),
+            Err(JwtError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn validate_malformed() {
+        assert!(matches!(
+            JwtConfig::hs256(SECRET).validate("not a token"),
+            Err(JwtError::InvalidToken { .. })
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "the `exp` claim can't be set directly")]
+    fn with_reserved_claim() {
+        let _ = Claims::new(UserId::Int(1), Duration::from_secs(60)).with_claim("exp", 0);
+    }
+
+    #[cot::test]
+    async fn middleware() {
+        let config = JwtConfig::hs256(SECRET);
+        let token = config
+            .issue(&Claims::new(UserId::Int(42), Duration::from_secs(60)))
+            .unwrap();
+        let service =
+            JwtMiddleware::new(config).layer(tower::service_fn(|request: Request| async move {
+                let user = request.extensions().get::<JwtUser>().unwrap();
+                assert_eq!(user.id(), Some(UserId::Int(42)));
+                assert!(user.is_authenticated());
+                Ok::<_, crate::Error>(Response::new(Body::empty()))
+            }));
+
+        let mut request = TestRequestBuilder::get("/").build();
+        request.headers_mut().insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {token}")).unwrap(),
+        );
+        let response = service.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[cot::test]
+    async fn middleware_rejects_request() {
+        let service = JwtMiddleware::new(JwtConfig::hs256(SECRET)).layer(tower::service_fn(
+            |_request: Request| async move { Ok::<_, crate::Error>(Response::new(Body::empty())) },
+        ));
+
+        let error = service
+            .clone()
+            .oneshot(TestRequestBuilder::get("/").build())
+            .await
+            .unwrap_err();
+        assert_eq!(error.status_code(), StatusCode::UNAUTHORIZED);
+        assert!(matches!(
+            error.inner().downcast_ref::<JwtError>(),
+            Some(JwtError::MissingToken)
+        ));
+
+        let mut request = TestRequestBuilder::get("/").build();
+        request.headers_mut().insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer invalid"),
+        );
+        let error = service.oneshot(request).await.unwrap_err();
+        assert_eq!(error.status_code(), StatusCode::UNAUTHORIZED);
+        assert!(matches!(
+            error.inner().downcast_ref::<JwtError>(),
+            Some(JwtError::InvalidToken { .. })
+        ));
+    }
+
+    #[cot::test]
+    async fn middleware_rejection_drains_request_body() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        use futures_util::StreamExt;
+
+        let exhausted = Arc::new(AtomicBool::new(false));
+        let exhausted_clone = Arc::clone(&exhausted);
+        let chunks =
+            futures_util::stream::iter(["first"]).chain(futures_util::stream::once(async move {
+                exhausted_clone.store(true, Ordering::SeqCst);
+                "last"
+            }));
+        let mut request = TestRequestBuilder::post("/").build();
+        *request.body_mut() = Body::from_stream(chunks);
+        let service = JwtMiddleware::new(JwtConfig::hs256(SECRET)).layer(tower::service_fn(
+            |_request: Request| async move { Ok::<_, crate::Error>(Response::new(Body::empty())) },
+        ));
+
+        let error = service.oneshot(request).await.unwrap_err();
+
+        assert_eq!(error.status_code(), StatusCode::UNAUTHORIZED);
+        assert!(exhausted.load(Ordering::SeqCst));
+    }
+}
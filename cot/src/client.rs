@@ -0,0 +1,298 @@
+//! HTTP client for making outbound requests.
+//!
+//! The [`Client`] sends requests and receives responses using the same
+//! [`Body`] type as the request handlers, so a response received from another
+//! service can be inspected, transformed, or passed on as is. The connections
+//! are pooled and reused across the requests, so a single client should be
+//! created and shared (it's cheap to clone).
+//!
+//! When the `opentelemetry` feature is enabled, the trace context of the
+//! current span is added to each outgoing request, so that the downstream
+//! services can continue the trace.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use cot::client::Client;
+//!
+//! # async fn example() -> cot::Result<()> {
+//! let client = Client::new()?;
+//! let response = client.get("http://localhost:8000/api/items").await?;
+//! let body = response.into_body().into_bytes().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use cot_core::error::impl_into_cot_error;
+use futures_util::TryStreamExt;
+use http_body_util::BodyDataStream;
+use thiserror::Error;
+
+use crate::config::ClientConfig;
+use crate::project::ProjectContext;
+use crate::response::Response;
+use crate::{Body, Error};
+
+#[derive(Debug, Error)]
+#[error("could not create the HTTP client: {0}")]
+struct BuildClientError(reqwest::Error);
+impl_into_cot_error!(BuildClientError);
+
+#[derive(Debug, Error)]
+#[error("invalid HTTP request: {0}")]
+struct InvalidRequestError(Box<dyn std::error::Error + Send + Sync>);
+impl_into_cot_error!(InvalidRequestError);
+
+#[derive(Debug, Error)]
+#[error("could not send the HTTP request: {0}")]
+struct SendRequestError(reqwest::Error);
+impl_into_cot_error!(SendRequestError, BAD_GATEWAY);
+
+#[derive(Debug, Error)]
+#[error("could not read the HTTP response body: {0}")]
+struct ReadResponseBodyError(reqwest::Error);
+impl_into_cot_error!(ReadResponseBodyError, BAD_GATEWAY);
+
+/// An HTTP client with a connection pool.
+///
+/// Cloning the client is cheap, and the clones share the same connection pool.
+///
+/// Each request is limited by the [timeout](ClientConfig::timeout) of the
+/// client, which includes reading the response body. Since the server waits
+/// for the requests being handled before shutting down, this makes sure that
+/// a handler waiting for a response from another service can't hold up the
+/// graceful shutdown for longer than the timeout. Use
+/// [`from_context`](Self::from_context) to create a client configured in the
+/// [`client`](crate::config::ProjectConfig::client) section of the project
+/// config.
+///
+/// # Examples
+///
+/// ```no_run
+/// use cot::Body;
+/// use cot::client::Client;
+///
+/// # async fn example() -> cot::Result<()> {
+/// let client = Client::new()?;
+/// let request = http::Request::post("http://localhost:8000/api/items")
+///     .header(http::header::CONTENT_TYPE, "application/json")
+///     .body(Body::fixed(r#"{"name": "item"}"#))
+///     .unwrap();
+/// let response = client.send(request).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Client {
+    inner: reqwest::Client,
+}
+
+impl Client {
+    /// Creates a new client with the default configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the TLS backend can't be initialized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::client::Client;
+    ///
+    /// let client = Client::new()?;
+    /// # Ok::<(), cot::Error>(())
+    /// ```
+    pub fn new() -> crate::Result<Self> {
+        Self::from_config(&ClientConfig::default())
+    }
+
+    /// Creates a new client with the given configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the TLS backend can't be initialized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::client::Client;
+    /// use cot::config::ClientConfig;
+    ///
+    /// let config = ClientConfig::builder()
+    ///     .timeout(Duration::from_secs(5))
+    ///     .build();
+    /// let client = Client::from_config(&config)?;
+    /// # Ok::<(), cot::Error>(())
+    /// ```
+    pub fn from_config(config: &ClientConfig) -> crate::Result<Self> {
+        let mut builder = reqwest::Client::builder().pool_idle_timeout(config.pool_idle_timeout);
+        if let Some(timeout) = config.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(connect_timeout) = config.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+        if let Some(max_idle) = config.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max_idle);
+        }
+
+        let inner = builder.build().map_err(BuildClientError)?;
+        Ok(Self { inner })
+    }
+
+    /// Creates a new client configured in the
+    /// [`client`](crate::config::ProjectConfig::client) section of the
+    /// project config.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the TLS backend can't be initialized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use async_trait::async_trait;
+    /// use cot::App;
+    /// use cot::client::Client;
+    /// use cot::project::ProjectContext;
+    ///
+    /// struct MyApp;
+    ///
+    /// #[async_trait]
+    /// impl App for MyApp {
+    ///     fn name(&self) -> &str {
+    ///         "my_app"
+    ///     }
+    ///
+    ///     async fn on_startup(&self, context: &ProjectContext) -> cot::Result<()> {
+    ///         let client = Client::from_context(context)?;
+    ///         // ...
+    ///         Ok(())
+    ///     }
+    /// }
+    /// ```
+    pub fn from_context(context: &ProjectContext) -> crate::Result<Self> {
+        Self::from_config(&context.config().client)
+    }
+
+    /// Sends a `GET` request to the given URI.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URI is invalid, or if the request could not be
+    /// sent (for instance, because the connection could not be established or
+    /// the request timed out).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use cot::client::Client;
+    ///
+    /// # async fn example() -> cot::Result<()> {
+    /// let client = Client::new()?;
+    /// let response = client.get("http://localhost:8000/api/items").await?;
+    /// assert!(response.status().is_success());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get(&self, uri: &str) -> crate::Result<Response> {
+        let request = http::Request::get(uri)
+            .body(Body::empty())
+            .map_err(|error| InvalidRequestError(Box::new(error)))?;
+        self.send(request).await
+    }
+
+    /// Sends the given request and returns the response.
+    ///
+    /// The response is returned as soon as its headers are received; the body
+    /// is streamed as it's read.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request is invalid (for instance, if its URI
+    /// is not absolute), or if it could not be sent (for instance, because the
+    /// connection could not be established or the request timed out).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use cot::Body;
+    /// use cot::client::Client;
+    ///
+    /// # async fn example() -> cot::Result<()> {
+    /// let client = Client::new()?;
+    /// let request = http::Request::delete("http://localhost:8000/api/items/1")
+    ///     .body(Body::empty())
+    ///     .unwrap();
+    /// let response = client.send(request).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send(&self, request: http::Request<Body>) -> crate::Result<Response> {
+        #[cfg_attr(not(feature = "opentelemetry"), expect(unused_mut))]
+        let mut request = request;
+        #[cfg(feature = "opentelemetry")]
+        crate::opentelemetry::inject_trace_context(request.headers_mut());
+
+        let request = reqwest::Request::try_from(request.map(reqwest::Body::wrap))
+            .map_err(|error| InvalidRequestError(Box::new(error)))?;
+        let response = self
+            .inner
+            .execute(request)
+            .await
+            .map_err(SendRequestError)?;
+
+        Ok(http::Response::from(response).map(|body| {
+            Body::streaming(
+                BodyDataStream::new(body)
+                    .map_err(|error| Error::from(ReadResponseBodyError(error))),
+            )
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cot::test]
+    #[cfg_attr(miri, ignore)]
+    async fn get_invalid_uri() {
+        let client = Client::new().unwrap();
+
+        let error = client.get("not a uri").await.unwrap_err();
+
+        assert!(error.to_string().contains("invalid HTTP request"));
+    }
+
+    #[cot::test]
+    #[cfg_attr(miri, ignore)]
+    async fn send_relative_uri() {
+        let client = Client::new().unwrap();
+        let request = http::Request::get("/relative").body(Body::empty()).unwrap();
+
+        let error = client.send(request).await.unwrap_err();
+
+        assert!(error.to_string().contains("invalid HTTP request"));
+    }
+
+    #[cot::test]
+    #[cfg_attr(miri, ignore)]
+    async fn send_connection_refused() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        drop(listener);
+        let client = Client::new().unwrap();
+
+        let error = client.get(&format!("http://{address}/")).await.unwrap_err();
+
+        assert!(
+            error
+                .to_string()
+                .contains("could not send the HTTP request")
+        );
+        assert_eq!(error.status_code(), http::StatusCode::BAD_GATEWAY);
+    }
+}
@@ -504,6 +504,23 @@ impl Email {
             .map_err(EmailParseError)
     }
 
+    /// Creates a new `Email` from a value submitted in a form.
+    ///
+    /// The value is trimmed and converted to lowercase before being parsed.
+    /// Unlike [`Email::new`], this only accepts a bare address: display names
+    /// (such as `Name <name@example.org>`) and IP address domains are
+    /// rejected, as they are never a valid value of an
+    /// `<input type="email">` element.
+    pub(crate) fn from_form_value(value: &str) -> Result<Email, EmailParseError> {
+        let options = email_address::Options::default()
+            .without_display_text()
+            .with_no_domain_literal();
+
+        EmailAddress::parse_with_options(&value.trim().to_lowercase(), options)
+            .map(Self)
+            .map_err(EmailParseError)
+    }
+
     /// Returns the email address as a string.
     ///
     /// # Examples
@@ -0,0 +1,707 @@
+//! CSV responses.
+//!
+//! This module provides the [`Csv`] responder, which streams a sequence of
+//! records to the client as a CSV file download. Records are serialized with
+//! [`serde`]: the header row is built from the field names of the first record
+//! and every record is written as a single row, without buffering the whole
+//! dataset in memory.
+//!
+//! # Examples
+//!
+//! ```
+//! use cot::csv::Csv;
+//! use cot::response::IntoResponse;
+//! use serde::Serialize;
+//!
+//! #[derive(Serialize)]
+//! struct Row {
+//!     id: i32,
+//!     name: &'static str,
+//! }
+//!
+//! async fn export() -> impl IntoResponse {
+//!     let rows = vec![Row { id: 1, name: "foo" }, Row { id: 2, name: "bar" }];
+//!     Csv::new("export.csv", rows)
+//! }
+//! ```
+
+use std::fmt::Display;
+use std::pin::Pin;
+
+use bytes::Bytes;
+use cot_core::error::impl_into_cot_error;
+use derive_more::Debug;
+use futures_core::Stream;
+use futures_util::StreamExt;
+use serde::Serialize;
+use serde::ser::Impossible;
+use thiserror::Error;
+
+use crate::Body;
+use crate::response::{IntoResponse, Response, ResponseExt};
+
+const CSV_CONTENT_TYPE: &str = "text/csv; charset=utf-8";
+
+/// An error that can occur while writing CSV data.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum CsvError {
+    /// A record could not be serialized as a CSV row.
+    #[error("could not serialize the record as CSV: {0}")]
+    Serialize(String),
+    /// A record has a different number of fields than the header row.
+    #[error("record has {found} fields, but the header has {expected}")]
+    FieldCountMismatch {
+        /// The number of fields in the header row.
+        expected: usize,
+        /// The number of fields in the record.
+        found: usize,
+    },
+}
+impl_into_cot_error!(CsvError, INTERNAL_SERVER_ERROR);
+
+impl serde::ser::Error for CsvError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self::Serialize(msg.to_string())
+    }
+}
+
+type RecordStream<T> = Pin<Box<dyn Stream<Item = crate::Result<T>> + Send>>;
+
+/// A responder that streams records as a CSV file download.
+///
+/// The records have to be structs (or maps with string keys) whose fields are
+/// scalar values: strings, numbers, booleans, characters, unit enum variants,
+/// or [`Option`]s of these (where [`None`] becomes an empty cell).
+///
+/// The response has the `text/csv` content type and a `Content-Disposition`
+/// header that makes browsers download it under the given filename. If there
+/// are no records, the response body is empty (there is no header row, as the
+/// field names are taken from the first record).
+///
+/// # Examples
+///
+/// ```
+/// use cot::csv::Csv;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Row {
+///     id: i32,
+///     name: String,
+/// }
+///
+/// let csv = Csv::new(
+///     "export.csv",
+///     vec![Row {
+///         id: 1,
+///         name: "foo".to_string(),
+///     }],
+/// );
+/// ```
+#[derive(Debug)]
+pub struct Csv<T> {
+    filename: String,
+    #[debug("..")]
+    records: RecordStream<T>,
+}
+
+impl<T: Serialize + Send + 'static> Csv<T> {
+    /// Creates a CSV response from an iterator of records.
+    ///
+    /// The iterator is consumed lazily, as the response body is being sent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::csv::Csv;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Row {
+    ///     id: i32,
+    /// }
+    ///
+    /// let csv = Csv::new("numbers.csv", (1..=100).map(|id| Row { id }));
+    /// ```
+    #[must_use]
+    pub fn new<F, I>(filename: F, records: I) -> Self
+    where
+        F: Into<String>,
+        I: IntoIterator<Item = T>,
+        I::IntoIter: Send + 'static,
+    {
+        Self::from_stream(
+            filename,
+            futures_util::stream::iter(records.into_iter().map(Ok)),
+        )
+    }
+
+    /// Creates a CSV response from a stream of records.
+    ///
+    /// If the stream yields an error, the response body is terminated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::csv::Csv;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Row {
+    ///     id: i32,
+    /// }
+    ///
+    /// let rows = futures_util::stream::iter([Ok(Row { id: 1 }), Ok(Row { id: 2 })]);
+    /// let csv = Csv::from_stream("rows.csv", rows);
+    /// ```
+    #[must_use]
+    pub fn from_stream<F, S>(filename: F, records: S) -> Self
+    where
+        F: Into<String>,
+        S: Stream<Item = crate::Result<T>> + Send + 'static,
+    {
+        Self {
+            filename: filename.into(),
+            records: Box::pin(records),
+        }
+    }
+}
+
+impl<T: Serialize + Send + 'static> IntoResponse for Csv<T> {
+    /// Creates a streaming CSV response.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::csv::Csv;
+    /// use cot::response::IntoResponse;
+    ///
+    /// let response = Csv::new("empty.csv", Vec::<()>::new()).into_response();
+    /// ```
+    fn into_response(self) -> crate::Result<Response> {
+        let mut columns = None;
+        let body = self.records.map(move |record| {
+            let record = record?;
+            let fields = serialize_record(&record)?;
+
+            let mut buf = String::new();
+            match columns {
+                None => {
+                    columns = Some(fields.len());
+                    write_row(&mut buf, fields.iter().map(|(name, _)| name.as_str()));
+                }
+                Some(expected) if expected != fields.len() => {
+                    return Err(CsvError::FieldCountMismatch {
+                        expected,
+                        found: fields.len(),
+                    }
+                    .into());
+                }
+                Some(_) => {}
+            }
+            write_row(&mut buf, fields.iter().map(|(_, value)| value.as_str()));
+
+            Ok(Bytes::from(buf))
+        });
+
+        Ok(Response::attachment(
+            Body::streaming(body),
+            &self.filename,
+            CSV_CONTENT_TYPE,
+        ))
+    }
+}
+
+fn write_row<'a>(buf: &mut String, cells: impl Iterator<Item = &'a str>) {
+    for (i, cell) in cells.enumerate() {
+        if i > 0 {
+            buf.push(',');
+        }
+        if cell.contains([',', '"', '\r', '\n']) {
+            buf.push('"');
+            buf.push_str(&cell.replace('"', "\"\""));
+            buf.push('"');
+        } else {
+            buf.push_str(cell);
+        }
+    }
+    buf.push_str("\r\n");
+}
+
+fn serialize_record<T: Serialize>(record: &T) -> Result<Vec<(String, String)>, CsvError> {
+    let mut serializer = RecordSerializer { fields: Vec::new() };
+    record.serialize(&mut serializer)?;
+    Ok(serializer.fields)
+}
+
+fn unsupported(kind: &str) -> CsvError {
+    CsvError::Serialize(format!("{kind} values are not supported"))
+}
+
+/// Serializes a record (a struct or a map) into a list of `(name, value)`
+/// pairs.
+struct RecordSerializer {
+    fields: Vec<(String, String)>,
+}
+
+macro_rules! unsupported_record {
+    ($($method:ident($($ty:ty),*)),* $(,)?) => {
+        $(
+            fn $method(self, $(_: $ty),*) -> Result<Self::Ok, Self::Error> {
+                Err(CsvError::Serialize(
+                    "records must be structs or maps".to_string(),
+                ))
+            }
+        )*
+    };
+}
+
+impl<'a> serde::Serializer for &'a mut RecordSerializer {
+    type Ok = ();
+    type Error = CsvError;
+    type SerializeSeq = Impossible<(), CsvError>;
+    type SerializeTuple = Impossible<(), CsvError>;
+    type SerializeTupleStruct = Impossible<(), CsvError>;
+    type SerializeTupleVariant = Impossible<(), CsvError>;
+    type SerializeMap = MapSerializer<'a>;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Impossible<(), CsvError>;
+
+    unsupported_record!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_none(),
+        serialize_unit(),
+        serialize_unit_struct(&'static str),
+        serialize_unit_variant(&'static str, u32, &'static str),
+    );
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), CsvError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), CsvError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<(), CsvError> {
+        Err(unsupported("enum"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, CsvError> {
+        Err(unsupported("sequence"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, CsvError> {
+        Err(unsupported("tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, CsvError> {
+        Err(unsupported("tuple struct"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, CsvError> {
+        Err(unsupported("enum"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, CsvError> {
+        Ok(MapSerializer {
+            record: self,
+            key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, CsvError> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, CsvError> {
+        Err(unsupported("enum"))
+    }
+}
+
+impl serde::ser::SerializeStruct for &mut RecordSerializer {
+    type Ok = ();
+    type Error = CsvError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), CsvError> {
+        let value = value.serialize(CellSerializer)?;
+        self.fields.push((key.to_owned(), value));
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), CsvError> {
+        Ok(())
+    }
+}
+
+struct MapSerializer<'a> {
+    record: &'a mut RecordSerializer,
+    key: Option<String>,
+}
+
+impl serde::ser::SerializeMap for MapSerializer<'_> {
+    type Ok = ();
+    type Error = CsvError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), CsvError> {
+        self.key = Some(key.serialize(CellSerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), CsvError> {
+        let key = self
+            .key
+            .take()
+            .expect("serialize_value called before serialize_key");
+        let value = value.serialize(CellSerializer)?;
+        self.record.fields.push((key, value));
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), CsvError> {
+        Ok(())
+    }
+}
+
+/// Serializes a single scalar value into the text of a CSV cell.
+struct CellSerializer;
+
+impl CellSerializer {
+    #[expect(
+        clippy::unnecessary_wraps,
+        reason = "matches the signature of the serializer methods"
+    )]
+    fn display<T: Display>(value: T) -> Result<String, CsvError> {
+        Ok(value.to_string())
+    }
+}
+
+impl serde::Serializer for CellSerializer {
+    type Ok = String;
+    type Error = CsvError;
+    type SerializeSeq = Impossible<String, CsvError>;
+    type SerializeTuple = Impossible<String, CsvError>;
+    type SerializeTupleStruct = Impossible<String, CsvError>;
+    type SerializeTupleVariant = Impossible<String, CsvError>;
+    type SerializeMap = Impossible<String, CsvError>;
+    type SerializeStruct = Impossible<String, CsvError>;
+    type SerializeStructVariant = Impossible<String, CsvError>;
+
+    fn serialize_bool(self, v: bool) -> Result<String, CsvError> {
+        Self::display(v)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<String, CsvError> {
+        Self::display(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<String, CsvError> {
+        Self::display(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<String, CsvError> {
+        Self::display(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<String, CsvError> {
+        Self::display(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<String, CsvError> {
+        Self::display(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<String, CsvError> {
+        Self::display(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<String, CsvError> {
+        Self::display(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<String, CsvError> {
+        Self::display(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<String, CsvError> {
+        Self::display(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<String, CsvError> {
+        Self::display(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<String, CsvError> {
+        Self::display(v)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<String, CsvError> {
+        Self::display(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<String, CsvError> {
+        Self::display(v)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<String, CsvError> {
+        Self::display(v)
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String, CsvError> {
+        Err(unsupported("byte array"))
+    }
+
+    fn serialize_none(self) -> Result<String, CsvError> {
+        Ok(String::new())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<String, CsvError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<String, CsvError> {
+        Ok(String::new())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, CsvError> {
+        Ok(String::new())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String, CsvError> {
+        Self::display(variant)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String, CsvError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, CsvError> {
+        Err(unsupported("nested enum"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, CsvError> {
+        Err(unsupported("nested sequence"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, CsvError> {
+        Err(unsupported("nested tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, CsvError> {
+        Err(unsupported("nested tuple struct"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, CsvError> {
+        Err(unsupported("nested enum"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, CsvError> {
+        Err(unsupported("nested map"))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, CsvError> {
+        Err(unsupported("nested struct"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, CsvError> {
+        Err(unsupported("nested enum"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[derive(Serialize)]
+    enum Status {
+        Active,
+    }
+
+    #[derive(Serialize)]
+    struct Row {
+        id: i32,
+        name: &'static str,
+        score: Option<f64>,
+        status: Status,
+    }
+
+    async fn body_string(response: Response) -> crate::Result<String> {
+        let bytes = response.into_body().into_bytes().await?;
+        Ok(String::from_utf8(bytes.to_vec()).unwrap())
+    }
+
+    #[cot::test]
+    async fn csv_response() {
+        let rows = vec![
+            Row {
+                id: 1,
+                name: "foo",
+                score: Some(1.5),
+                status: Status::Active,
+            },
+            Row {
+                id: 2,
+                name: "bar, \"baz\"\nqux",
+                score: None,
+                status: Status::Active,
+            },
+        ];
+
+        let response = Csv::new("export.csv", rows).into_response().unwrap();
+
+        assert_eq!(
+            response.headers().get(http::header::CONTENT_TYPE).unwrap(),
+            CSV_CONTENT_TYPE
+        );
+        assert_eq!(
+            response
+                .headers()
+                .get(http::header::CONTENT_DISPOSITION)
+                .unwrap(),
+            "attachment; filename=\"export.csv\""
+        );
+        assert_eq!(
+            body_string(response).await.unwrap(),
+            "id,name,score,status\r\n\
+             1,foo,1.5,Active\r\n\
+             2,\"bar, \"\"baz\"\"\nqux\",,Active\r\n"
+        );
+    }
+
+    #[cot::test]
+    async fn csv_response_from_map_stream() {
+        let rows = futures_util::stream::iter([
+            Ok(BTreeMap::from([("a", 1), ("b", 2)])),
+            Ok(BTreeMap::from([("a", 3), ("b", 4)])),
+        ]);
+
+        let response = Csv::from_stream("map.csv", rows).into_response().unwrap();
+
+        assert_eq!(
+            body_string(response).await.unwrap(),
+            "a,b\r\n1,2\r\n3,4\r\n"
+        );
+    }
+
+    #[cot::test]
+    async fn csv_response_empty() {
+        let response = Csv::new("empty.csv", Vec::<Row>::new())
+            .into_response()
+            .unwrap();
+
+        assert_eq!(body_string(response).await.unwrap(), "");
+    }
+
+    #[cot::test]
+    async fn csv_response_field_count_mismatch() {
+        let rows = vec![
+            BTreeMap::from([("a", 1), ("b", 2)]),
+            BTreeMap::from([("a", 3)]),
+        ];
+
+        let response = Csv::new("map.csv", rows).into_response().unwrap();
+
+        assert!(body_string(response).await.is_err());
+    }
+
+    #[cot::test]
+    async fn csv_response_nested_value() {
+        #[derive(Serialize)]
+        struct Nested {
+            values: Vec<i32>,
+        }
+
+        let response = Csv::new("nested.csv", vec![Nested { values: vec![1] }])
+            .into_response()
+            .unwrap();
+
+        assert!(body_string(response).await.is_err());
+    }
+}
@@ -4,6 +4,8 @@
 //! the error types that can occur when interacting with the database.
 
 mod fields;
+#[cfg(feature = "json")]
+pub mod fixtures;
 #[cfg(feature = "mysql")]
 pub mod impl_mysql;
 #[cfg(feature = "postgres")]
@@ -14,11 +16,14 @@ pub mod migrations;
 pub mod query;
 mod relations;
 mod sea_query_db;
+mod slug;
+mod transaction;
 
 use std::fmt::{Display, Formatter};
 use std::hash::Hash;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use cot_core::error::impl_into_cot_error;
@@ -113,6 +118,36 @@ use cot_core::error::impl_into_cot_error;
 /// }
 /// ```
 ///
+/// ## `timestamps`
+/// The `timestamps` parameter makes the ORM manage the `created_at` and
+/// `updated_at` fields of the model, which have to be declared in the
+/// struct. `created_at` is set when the instance is inserted into the
+/// database, and `updated_at` every time it is saved. Both fields are set to
+/// the same time, read from the application clock, whenever the ORM writes
+/// the instance. The bulk operations ([`Model::bulk_insert`] and
+/// [`Query::update`](query::Query::update)) update the timestamps as well.
+///
+/// The fields can be of any type implementing [`TimestampField`], such as
+/// `chrono::DateTime<chrono::Utc>`. The values they are initialized with are
+/// overwritten when the instance is saved.
+///
+/// Note that [`Model::save`] only sets `created_at` for instances with an
+/// [`Auto`] primary key that hasn't been assigned yet; use [`Model::insert`]
+/// to insert instances with a manually assigned primary key.
+///
+/// ```
+/// use cot::db::{Auto, model};
+///
+/// #[model(timestamps)]
+/// struct Post {
+///     #[model(primary_key)]
+///     id: Auto<i32>,
+///     title: String,
+///     created_at: chrono::DateTime<chrono::Utc>,
+///     updated_at: chrono::DateTime<chrono::Utc>,
+/// }
+/// ```
+///
 /// # Field Attributes
 /// In addition to the struct-level attributes, you can also specify field-level
 /// attributes using the `#[model(...)]` attribute, which is used to specify
@@ -169,6 +204,115 @@ use cot_core::error::impl_into_cot_error;
 /// }
 /// ```
 ///
+/// ## `default`
+/// The `default` attribute specifies the default value of a field.
+///
+/// If the value is a constant literal (a boolean, a number, or a string), it
+/// is emitted as the `DEFAULT` clause of the column in the generated
+/// migrations. This makes it possible to add a new non-nullable column to a
+/// table that already contains rows, and applies to rows inserted by other
+/// means than the ORM.
+///
+/// For [`Option`] fields, the default value is also applied in Rust: fields
+/// set to [`None`] are set to the default value before the model is saved.
+/// This means that such fields can't be set to `NULL` through the ORM. Any
+/// expression can be used as the default of an [`Option`] field; the
+/// expressions that are not constant literals (e.g. function calls) are only
+/// applied in Rust. Since the expression is also copied to the generated
+/// migrations, use fully qualified paths in it.
+///
+/// Non-constant defaults can't be used on non-[`Option`] fields, because
+/// these are always set in Rust. Defaults can't be used on primary key fields;
+/// use [`Auto`] to let the database generate the primary key instead.
+///
+/// ```
+/// use cot::db::{Auto, model};
+///
+/// #[model]
+/// struct Post {
+///     #[model(primary_key)]
+///     id: Auto<i32>,
+///     #[model(default = 0)]
+///     views: i64,
+///     #[model(default = "draft")]
+///     status: Option<String>,
+///     #[model(default = chrono::Utc::now().fixed_offset())]
+///     published_at: Option<chrono::DateTime<chrono::FixedOffset>>,
+/// }
+/// ```
+///
+/// ## `slugify_from`
+/// The `slugify_from` attribute makes a [`Slug`] field be generated from
+/// another field of the model when the model is saved and the slug is empty.
+/// The value of the source field, which has to implement
+/// [`Display`](std::fmt::Display), is converted with [`slugify`], and a
+/// numeric suffix is appended if the slug is already used by another row of
+/// the table.
+///
+/// ```
+/// use cot::db::{Auto, Slug, model};
+///
+/// #[model]
+/// struct Post {
+///     #[model(primary_key)]
+///     id: Auto<i32>,
+///     title: String,
+///     #[model(slugify_from = "title")]
+///     slug: Slug,
+/// }
+/// ```
+///
+/// ## `choices`
+/// The `choices` attribute makes the migration generator emit a `CHECK`
+/// constraint that only allows the identifiers of the variants of the enum
+/// stored in a [`Choice`] field, so that invalid values can't be inserted
+/// into the column by other means than the ORM, such as raw SQL. The enum
+/// has to derive [`SelectChoice`](crate::form::fields::SelectChoice) and be
+/// defined in the same crate as the model. When the variants change, the
+/// next generated migration updates the constraint.
+///
+/// Altering the constraint of an existing column is only supported on
+/// PostgreSQL.
+///
+/// ```
+/// use cot::db::{Auto, Choice, model};
+/// use cot::form::fields::SelectChoice;
+///
+/// #[derive(Debug, Clone, SelectChoice)]
+/// enum Status {
+///     #[select_choice(id = "draft")]
+///     Draft,
+///     #[select_choice(id = "published")]
+///     Published,
+/// }
+///
+/// #[model]
+/// struct Post {
+///     #[model(primary_key)]
+///     id: Auto<i32>,
+///     #[model(choices)]
+///     status: Choice<Status>,
+/// }
+/// ```
+///
+/// ## `decimal`
+/// The `decimal` attribute sets the precision (the total number of digits)
+/// and the scale (the number of digits after the decimal point) of the
+/// column storing a `Decimal` field in the generated migrations. It requires
+/// the `decimal` feature.
+///
+/// ```
+/// use cot::db::{Auto, Decimal, model};
+///
+/// #[model]
+/// struct Product {
+///     #[model(primary_key)]
+///     id: Auto<i32>,
+///     #[model(decimal(precision = 10, scale = 2))]
+///     price: Decimal,
+/// }
+/// ```
+///
 /// ## `foreign_key`
 ///
 /// The `foreign_key` attribute configures the referential integrity behavior
@@ -365,6 +509,49 @@ pub use cot_macros::model;
 /// let _ = query!(Customer, $id >= 5);
 /// ```
 ///
+/// ## Logical operators
+///
+/// Conditions can be combined with `&&` and `||`, and negated with `!`. The
+/// operators have the same precedence as in Rust (so `&&` binds tighter than
+/// `||`), and the generated SQL is parenthesized accordingly. Parentheses can
+/// be used to group conditions explicitly.
+///
+/// ```
+/// use cot::db::{model, query};
+///
+/// # #[model]
+/// # struct Customer {
+/// #     #[model(primary_key)]
+/// #     id: i32,
+/// #     full_name: String,
+/// #     is_active: bool,
+/// # }
+/// let _ = query!(Customer, $is_active == true && $id > 5);
+/// let _ = query!(Customer, $id == 1 || $id == 2 && !$is_active);
+/// let _ = query!(Customer, ($id == 1 || $id == 2) && !($full_name == "Jon Doe"));
+/// ```
+///
+/// ## Set membership and pattern matching
+///
+/// `in` checks whether a field is one of the given values; `like` matches a
+/// string field against an SQL pattern. An empty `in` list matches no rows.
+///
+/// ```
+/// use cot::db::{model, query};
+///
+/// # #[model]
+/// # struct Customer {
+/// #     #[model(primary_key)]
+/// #     id: i32,
+/// #     full_name: String,
+/// # }
+/// let ids = vec![1, 2, 3];
+///
+/// let _ = query!(Customer, $id in [1, 2, 3]);
+/// let _ = query!(Customer, $id in ids);
+/// let _ = query!(Customer, $full_name like "Jon%");
+/// ```
+///
 /// ## Arithmetic operators
 ///
 /// Query expressions also support arithmetic over fields and values.
@@ -423,18 +610,36 @@ pub use cot_macros::query;
 use derive_more::{Debug, Deref, Display};
 #[cfg(test)]
 use mockall::automock;
-use query::Query;
-pub use relations::{ForeignKey, ForeignKeyOnDeletePolicy, ForeignKeyOnUpdatePolicy};
+use query::{Assignment, Query};
+pub use relations::{ForeignKey, ForeignKeyOnDeletePolicy, ForeignKeyOnUpdatePolicy, RelatedField};
 use sea_query::{
     ColumnRef, ExprTrait, Iden, IntoColumnRef, OnConflict, ReturningClause, SchemaStatementBuilder,
     SimpleExpr,
 };
 use sea_query_sqlx::{SqlxBinder, SqlxValues};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+pub use slug::{Slug, SlugGenerator, slugify};
+pub use transaction::Transaction;
+/// A fixed-precision decimal number, re-exported from the
+/// [`rust_decimal`] crate.
+///
+/// Unlike [`f64`], it represents decimal fractions (such as amounts of money)
+/// exactly. When used as a model field, it's stored as `NUMERIC` in
+/// PostgreSQL, `DECIMAL` in MySQL, and `TEXT` in SQLite. The precision and
+/// scale of the column can be set with the
+/// `#[model(decimal(precision = ..., scale = ...))]` attribute.
+///
+/// In SQLite, the values are stored in their normalized form (without the
+/// trailing zeros), so the equality filters work as expected. The range
+/// filters (`<`, `>`, `<=`, `>=`) and ordering compare the text instead of
+/// the numbers there, though, so they don't give correct results.
+#[cfg(feature = "decimal")]
+pub use rust_decimal::Decimal;
 use sqlx::{Type, TypeInfo};
 use thiserror::Error;
-use tracing::{Instrument, Level, span, trace};
+use tracing::{Instrument, Level, debug, span, trace, warn};
 
+use crate::config::DatabaseConfig;
 #[cfg(feature = "mysql")]
 use crate::db::impl_mysql::{DatabaseMySql, MySqlRow, MySqlValueRef};
 #[cfg(feature = "postgres")]
@@ -472,11 +677,22 @@ pub enum DatabaseError {
     #[error("{ERROR_PREFIX} error when applying migrations: {0}")]
     MigrationError(#[from] migrations::MigrationEngineError),
     /// A record could not be found in the database.
-    #[error("{ERROR_PREFIX} record with primary key `{primary_key}` not found in the database")]
+    #[error(
+        "{ERROR_PREFIX} record with primary key `{}` not found in the database",
+        display_primary_key(.primary_key)
+    )]
     RecordNotFound {
-        /// The primary key of the record that was not found.
-        primary_key: DbValue,
+        /// The values of the primary key columns of the record that was not
+        /// found.
+        primary_key: Vec<DbValue>,
     },
+    /// A query that was expected to return exactly one row didn't return any.
+    #[error("{ERROR_PREFIX} query returned no rows, but exactly one was expected")]
+    NotFound,
+    /// A query that was expected to return exactly one row returned more than
+    /// one.
+    #[error("{ERROR_PREFIX} query returned more than one row, but exactly one was expected")]
+    MultipleObjects,
     /// Foreign Key could not be retrieved from the database because the record
     /// was not found.
     #[error("{ERROR_PREFIX} error retrieving a Foreign Key from the database: record not found")]
@@ -514,6 +730,48 @@ pub enum DatabaseError {
         /// The actual number of rows returned.
         actual: usize,
     },
+    /// The columns returned by a raw query don't match the columns of the
+    /// model.
+    #[error(
+        "{ERROR_PREFIX} query returned columns {found:?}, but the model expects columns \
+        {expected:?} (in this order)"
+    )]
+    UnexpectedColumns {
+        /// The names of the columns the model expects.
+        expected: Vec<String>,
+        /// The names of the columns returned by the query.
+        found: Vec<String>,
+    },
+    /// A column read by name is missing from the row returned by a query.
+    #[error(
+        "{ERROR_PREFIX} query didn't return the column `{column}`; it returned columns {found:?}"
+    )]
+    MissingColumn {
+        /// The name of the missing column.
+        column: String,
+        /// The names of the columns returned by the query.
+        found: Vec<String>,
+    },
+    /// The operation is not supported by the database backend.
+    #[error("{ERROR_PREFIX} {feature} is not supported by this database backend")]
+    NotSupported {
+        /// The name of the unsupported feature.
+        feature: &'static str,
+    },
+    /// [`Database::begin`] was called on a database that is already bound to
+    /// a transaction.
+    #[error("{ERROR_PREFIX} transactions cannot be nested")]
+    NestedTransaction,
+    /// A statement was executed in a transaction that has already been
+    /// committed or rolled back.
+    #[error("{ERROR_PREFIX} the transaction has already been committed or rolled back")]
+    TransactionFinished,
+    /// The database didn't respond to [`Database::ping`] in time.
+    #[error("{ERROR_PREFIX} the database didn't respond within {}", humantime::format_duration(*.timeout))]
+    PingTimeout {
+        /// The time the database was given to respond.
+        timeout: Duration,
+    },
 }
 impl_into_cot_error!(DatabaseError, INTERNAL_SERVER_ERROR);
 
@@ -525,6 +783,16 @@ impl DatabaseError {
     }
 }
 
+fn display_primary_key(values: &[DbValue]) -> String {
+    match values {
+        [value] => value.to_string(),
+        values => {
+            let values: Vec<_> = values.iter().map(ToString::to_string).collect();
+            format!("({})", values.join(", "))
+        }
+    }
+}
+
 /// An alias for [`Result`] that uses [`DatabaseError`] as the error type.
 pub type Result<T> = std::result::Result<T, DatabaseError>;
 
@@ -571,7 +839,10 @@ pub trait Model: Sized + Send + 'static {
     type Fields;
 
     /// The primary key type of the model.
-    type PrimaryKey: PrimaryKey;
+    ///
+    /// This is a tuple of the primary key field types if the model has a
+    /// composite primary key.
+    type PrimaryKey: ModelPrimaryKey;
 
     /// The name of the app in which this model is defined.
     const APP_NAME: &'static str;
@@ -580,11 +851,30 @@ pub trait Model: Sized + Send + 'static {
     const TABLE_NAME: Identifier;
 
     /// The name of the primary key column in the database.
+    ///
+    /// If the model has a composite primary key, there is no single primary
+    /// key column, so the models generated by the [`model`] macro fail to
+    /// compile when this constant is used; use [`Self::PRIMARY_KEY_NAMES`]
+    /// instead.
     const PRIMARY_KEY_NAME: Identifier;
 
+    /// The names of all the primary key columns in the database.
+    ///
+    /// This contains more than one column if the model has a composite
+    /// primary key, i.e. multiple fields marked with `#[model(primary_key)]`.
+    const PRIMARY_KEY_NAMES: &'static [Identifier] = &[Self::PRIMARY_KEY_NAME];
+
     /// The columns of the model.
     const COLUMNS: &'static [Column];
 
+    /// The column storing the time the instance was inserted, which is left
+    /// untouched when [`Model::save`] or [`Database::bulk_insert_or_update`]
+    /// update an existing row.
+    ///
+    /// The [`model`] macro sets this to the `created_at` column for the
+    /// models marked with `#[model(timestamps)]`.
+    const CREATED_AT_COLUMN: Option<Identifier> = None;
+
     /// Creates a model instance from a database row.
     ///
     /// # Errors
@@ -605,7 +895,7 @@ pub trait Model: Sized + Send + 'static {
     fn update_from_db(&mut self, db_row: Row, columns: &[usize]) -> Result<()>;
 
     /// Returns the primary key of the model.
-    fn primary_key(&self) -> &Self::PrimaryKey;
+    fn primary_key(&self) -> Self::PrimaryKey;
 
     /// Used by the ORM to set the primary key of the model after it has been
     /// saved to the database.
@@ -614,6 +904,71 @@ pub trait Model: Sized + Send + 'static {
     /// Gets the values of the model for the given columns.
     fn get_values(&self, columns: &[usize]) -> Vec<&dyn ToDbFieldValue>;
 
+    /// Applies the application-side default values to the fields that are
+    /// unset.
+    ///
+    /// This is called by the ORM before the model instance is written to the
+    /// database. The [`model`] macro implements it for the nullable fields
+    /// marked with `#[model(default = ...)]`, setting them to the default
+    /// value if they are [`None`].
+    fn apply_defaults(&mut self) {}
+
+    /// Generates the slugs of the fields marked with
+    /// `#[model(slugify_from = "...")]` that are empty.
+    ///
+    /// This is called by the ORM before the model instance is written to the
+    /// database. The [`model`] macro implements it for the models having such
+    /// fields, generating a unique slug from the source field with the given
+    /// [`SlugGenerator`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the existing slugs could not be retrieved from the
+    /// database.
+    async fn generate_slugs(&mut self, slugs: &mut SlugGenerator<'_>) -> Result<()> {
+        let _ = slugs;
+        Ok(())
+    }
+
+    /// Sets the automatically managed timestamp fields to the given time.
+    ///
+    /// This is called by the ORM before the model instance is written to the
+    /// database. The [`model`] macro implements it for the models marked with
+    /// `#[model(timestamps)]`, setting `updated_at` to `now`, and also
+    /// `created_at` if the instance is being inserted.
+    fn update_timestamps(&mut self, now: chrono::DateTime<chrono::Utc>, inserting: bool) {
+        let _ = (now, inserting);
+    }
+
+    /// Returns the assignments for the automatically managed timestamp fields
+    /// that are added to the bulk updates done with [`Query::update`].
+    ///
+    /// The [`model`] macro implements it for the models marked with
+    /// `#[model(timestamps)]`, returning an assignment setting `updated_at` to
+    /// `now`.
+    #[must_use]
+    fn timestamp_assignments(now: chrono::DateTime<chrono::Utc>) -> Vec<Assignment> {
+        let _ = now;
+        Vec::new()
+    }
+
+    /// Replaces the foreign key stored in the given column with the related
+    /// model read from the row.
+    ///
+    /// This is called by the ORM for the relations requested with
+    /// [`Query::select_related`]; the row only contains the columns of the
+    /// related model. The [`model`] macro implements it for all the
+    /// [`ForeignKey`] fields of the model.
+    ///
+    /// # Errors
+    ///
+    /// This method can return an error if the data in the row is not
+    /// compatible with the related model.
+    fn load_related(&mut self, column: Identifier, db_row: &Row) -> Result<()> {
+        let _ = (column, db_row);
+        Ok(())
+    }
+
     /// Returns a query for all objects of this model.
     #[must_use]
     fn objects() -> Query<Self> {
@@ -817,12 +1172,68 @@ impl Column {
 
 /// A marker trait that denotes that a type can be used as a primary key in a
 /// database.
+///
+/// Composite primary keys (tuples) don't implement this trait, so the models
+/// using them can't be referenced by a [`ForeignKey`].
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be used as a single-column primary key",
+    note = "models with a composite primary key cannot be referenced by a `ForeignKey`"
+)]
 pub trait PrimaryKey: DatabaseField + Clone {}
 
+/// A trait for the types that can be used as the primary key of a [`Model`].
+///
+/// This is implemented for all the [`PrimaryKey`] types, as well as for the
+/// tuples of them, which are used as the composite primary keys.
+pub trait ModelPrimaryKey: Clone {
+    /// Returns the values of the primary key columns, in the order of
+    /// [`Model::PRIMARY_KEY_NAMES`].
+    fn to_db_field_values(&self) -> Vec<DbFieldValue>;
+}
+
+impl<T: PrimaryKey> ModelPrimaryKey for T {
+    fn to_db_field_values(&self) -> Vec<DbFieldValue> {
+        vec![self.to_db_field_value()]
+    }
+}
+
+macro_rules! impl_model_primary_key_for_tuple {
+    ($($ty:ident),+) => {
+        impl<$($ty: PrimaryKey),+> ModelPrimaryKey for ($($ty,)+) {
+            fn to_db_field_values(&self) -> Vec<DbFieldValue> {
+                #[expect(non_snake_case)]
+                let ($($ty,)+) = self;
+                vec![$($ty.to_db_field_value()),+]
+            }
+        }
+    };
+}
+
+impl_model_primary_key_for_tuple!(A, B);
+impl_model_primary_key_for_tuple!(A, B, C);
+impl_model_primary_key_for_tuple!(A, B, C, D);
+
+/// Returns whether any of the primary key columns is to be generated by the
+/// database.
+fn is_auto_primary_key<T: ModelPrimaryKey>(primary_key: &T) -> bool {
+    primary_key
+        .to_db_field_values()
+        .iter()
+        .any(DbFieldValue::is_auto)
+}
+
+/// Returns the condition matching the row with the given primary key values.
+fn primary_key_condition<T: Model>(values: Vec<DbValue>) -> SimpleExpr {
+    std::iter::zip(T::PRIMARY_KEY_NAMES, values)
+        .map(|(column, value)| sea_query::Expr::col(*column).eq(value))
+        .reduce(|condition, other| condition.and(other))
+        .expect("models should have at least one primary key column")
+}
+
 /// A row structure that holds the data of a single row retrieved from the
 /// database.
 #[non_exhaustive]
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Row {
     /// A row from a SQLite database.
     #[cfg(feature = "sqlite")]
@@ -864,6 +1275,146 @@ impl Row {
 
         Ok(result)
     }
+
+    /// Gets the value of the column with the given name and converts it to the
+    /// given type. If there are multiple columns with the same name, the first
+    /// one is used.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`DatabaseError::MissingColumn`] if the row doesn't
+    /// contain a column with the given name.
+    ///
+    /// This method can also return an error if the value of the column is not
+    /// compatible with the Rust type.
+    pub fn get_by_name<T: FromDbValue>(&self, column: &str) -> Result<T> {
+        let column_names = self.column_names();
+        let index = column_names
+            .iter()
+            .position(|name| *name == column)
+            .ok_or_else(|| DatabaseError::MissingColumn {
+                column: column.to_owned(),
+                found: column_names.iter().map(|&name| name.to_owned()).collect(),
+            })?;
+
+        self.get(index)
+    }
+
+    /// Returns a view of this row that skips the first `offset` columns, so
+    /// that index `0` refers to the column at `offset` in this row.
+    ///
+    /// This is used to read related models from the columns appended to the
+    /// row by a join.
+    #[must_use]
+    pub(crate) fn with_offset(&self, offset: usize) -> Self {
+        match self {
+            #[cfg(feature = "sqlite")]
+            Row::Sqlite(sqlite_row) => Row::Sqlite(sqlite_row.with_offset(offset)),
+            #[cfg(feature = "postgres")]
+            Row::Postgres(postgres_row) => Row::Postgres(postgres_row.with_offset(offset)),
+            #[cfg(feature = "mysql")]
+            Row::MySql(mysql_row) => Row::MySql(mysql_row.with_offset(offset)),
+        }
+    }
+
+    /// Returns the names of the columns in the row, in the order they were
+    /// returned by the database.
+    #[must_use]
+    pub fn column_names(&self) -> Vec<&str> {
+        match self {
+            #[cfg(feature = "sqlite")]
+            Row::Sqlite(sqlite_row) => sqlite_row.column_names(),
+            #[cfg(feature = "postgres")]
+            Row::Postgres(postgres_row) => postgres_row.column_names(),
+            #[cfg(feature = "mysql")]
+            Row::MySql(mysql_row) => mysql_row.column_names(),
+        }
+    }
+}
+
+/// A trait for types that can be created from a database row.
+///
+/// This is used by [`Database::raw_query`] to convert the rows returned by a
+/// raw SQL query. It is implemented for all [`Model`]s, and can be derived or
+/// implemented manually for other types, such as the results of aggregate
+/// queries.
+///
+/// # Deriving
+///
+/// The derive macro reads each field of a struct from the column with the same
+/// name, regardless of the order of the columns. Use `#[row(rename = "...")]`
+/// to read a field from a column with a different name. Fields of type
+/// `Option<T>` are set to `None` when the column is `NULL`. If a column is
+/// missing from the row, [`DatabaseError::MissingColumn`] is returned.
+///
+/// ```
+/// use cot::db::FromRow;
+///
+/// #[derive(FromRow)]
+/// struct NameCount {
+///     name: String,
+///     #[row(rename = "name_count")]
+///     count: i64,
+///     nickname: Option<String>,
+/// }
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// use cot::db::{FromRow, Row};
+///
+/// struct NameCount {
+///     name: String,
+///     count: i64,
+/// }
+///
+/// impl FromRow for NameCount {
+///     fn from_row(row: Row) -> cot::db::Result<Self> {
+///         Ok(Self {
+///             name: row.get(0)?,
+///             count: row.get(1)?,
+///         })
+///     }
+/// }
+/// ```
+pub trait FromRow: Sized {
+    /// Creates an instance of the type from a database row.
+    ///
+    /// # Errors
+    ///
+    /// This method can return an error if the data in the row is not compatible
+    /// with the type.
+    fn from_row(row: Row) -> Result<Self>;
+}
+
+/// Derive macro for the [`FromRow`] trait.
+///
+/// See the [`FromRow`] trait documentation for details.
+pub use cot_macros::FromRow;
+
+impl<T: Model> FromRow for T {
+    /// Creates a model instance from a database row, checking first that the
+    /// row's columns match the model's columns.
+    fn from_row(row: Row) -> Result<Self> {
+        let found = row.column_names();
+        let matches = found.len() >= T::COLUMNS.len()
+            && T::COLUMNS
+                .iter()
+                .zip(&found)
+                .all(|(column, name)| column.name.as_str() == *name);
+        if !matches {
+            return Err(DatabaseError::UnexpectedColumns {
+                expected: T::COLUMNS
+                    .iter()
+                    .map(|column| column.name.as_str().to_owned())
+                    .collect(),
+                found: found.into_iter().map(ToOwned::to_owned).collect(),
+            });
+        }
+
+        T::from_db(row)
+    }
 }
 
 /// A trait denoting that some type can be used as a field in a database.
@@ -951,6 +1502,44 @@ pub trait ToDbFieldValue {
     fn to_db_field_value(&self) -> DbFieldValue;
 }
 
+/// A trait for the field types that can store the timestamps set
+/// automatically for models with `#[model(timestamps)]`.
+///
+/// See the [`model`] macro documentation for more details.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be used as a timestamp field",
+    label = "`{Self}` cannot be set to the current time",
+    note = "use `chrono::DateTime<chrono::Utc>` as the type of the timestamp fields"
+)]
+pub trait TimestampField {
+    /// Converts the current time to the value of the field.
+    fn from_utc(datetime: chrono::DateTime<chrono::Utc>) -> Self;
+}
+
+impl TimestampField for chrono::DateTime<chrono::Utc> {
+    fn from_utc(datetime: chrono::DateTime<chrono::Utc>) -> Self {
+        datetime
+    }
+}
+
+impl TimestampField for chrono::DateTime<chrono::FixedOffset> {
+    fn from_utc(datetime: chrono::DateTime<chrono::Utc>) -> Self {
+        datetime.fixed_offset()
+    }
+}
+
+impl TimestampField for chrono::NaiveDateTime {
+    fn from_utc(datetime: chrono::DateTime<chrono::Utc>) -> Self {
+        datetime.naive_utc()
+    }
+}
+
+impl<T: TimestampField> TimestampField for Option<T> {
+    fn from_utc(datetime: chrono::DateTime<chrono::Utc>) -> Self {
+        Some(T::from_utc(datetime))
+    }
+}
+
 /// Represents a value for a field in the database.
 ///
 /// This enum is used to indicate whether a field's value should be explicitly
@@ -1122,6 +1711,8 @@ trait SqlxRowRef {
         Self: 'r;
 
     fn get_raw(&self, index: usize) -> Result<Self::ValueRef<'_>>;
+
+    fn column_names(&self) -> Vec<&str>;
 }
 
 /// A trait for accessing raw database values from a specific database backend.
@@ -1189,6 +1780,17 @@ pub trait SqlxValueRef<'r>: Sized {
 /// It is used to execute queries and interact with the database. The connection
 /// is established when the structure is created and closed when
 /// [`Self::close()`] is called.
+///
+/// # Prepared statements
+///
+/// `Database` holds a pool of connections. Each connection keeps its own
+/// cache of prepared statements, keyed by their SQL, so the statements
+/// generated for model operations and queries are only prepared once per
+/// connection and then reused. Since a connection is only used by one task at
+/// a time, the cache doesn't need any synchronization, and it's safe to clone
+/// and share `Database` between threads. The cache is bounded: when it's full,
+/// the least recently used statement is evicted. Its size can be configured
+/// using [`DatabaseConfig::statement_cache_capacity`].
 #[derive(Debug, Clone)]
 pub struct Database {
     inner: Arc<DatabaseImpl>,
@@ -1230,35 +1832,122 @@ impl Database {
     /// }
     /// ```
     pub async fn new<T: Into<String>>(url: T) -> Result<Self> {
-        let url = url.into();
-
-        #[cfg(feature = "sqlite")]
-        if url.starts_with("sqlite:") {
-            let inner = DatabaseSqlite::new(&url).await?;
-            return Ok(Self {
-                inner: Arc::new(DatabaseImpl::Sqlite(inner)),
-            });
-        }
-
-        #[cfg(feature = "postgres")]
-        if url.starts_with("postgresql:") {
-            let inner = DatabasePostgres::new(&url).await?;
-            return Ok(Self {
-                inner: Arc::new(DatabaseImpl::Postgres(inner)),
-            });
-        }
+        Self::connect(&url.into(), None, QueryLogging::default()).await
+    }
 
-        #[cfg(feature = "mysql")]
-        if url.starts_with("mysql:") {
-            let inner = DatabaseMySql::new(&url).await?;
-            return Ok(Self {
-                inner: Arc::new(DatabaseImpl::MySql(inner)),
-            });
-        }
+    /// Creates a new database connection using the given configuration.
+    ///
+    /// Besides the connection options, this applies the query logging
+    /// settings from the configuration: see
+    /// [`DatabaseConfig::log_queries`] and
+    /// [`DatabaseConfig::slow_query_threshold`].
+    ///
+    /// # Errors
+    ///
+    /// This method can return an error if the connection to the database could
+    /// not be established.
+    ///
+    /// This method can return an error if the database URL is invalid.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the database URL is not set or not
+    /// supported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::config::DatabaseConfig;
+    /// use cot::db::Database;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let config = DatabaseConfig::builder()
+    ///         .url("sqlite::memory:")
+    ///         .statement_cache_capacity(500)
+    ///         .build();
+    ///     let db = Database::from_config(&config).await.unwrap();
+    /// }
+    /// ```
+    pub async fn from_config(config: &DatabaseConfig) -> Result<Self> {
+        let url = config.url.as_ref().expect("Database URL is required");
+
+        Self::connect(
+            url.as_str(),
+            config.statement_cache_capacity,
+            QueryLogging::from_config(config),
+        )
+        .await
+    }
+
+    async fn connect(
+        url: &str,
+        statement_cache_capacity: Option<usize>,
+        query_logging: QueryLogging,
+    ) -> Result<Self> {
+        #[cfg(feature = "sqlite")]
+        if url.starts_with("sqlite:") {
+            let inner = DatabaseSqlite::new(url, statement_cache_capacity, query_logging).await?;
+            return Ok(Self {
+                inner: Arc::new(DatabaseImpl::Sqlite(inner)),
+            });
+        }
+
+        #[cfg(feature = "postgres")]
+        if url.starts_with("postgresql:") {
+            let inner = DatabasePostgres::new(url, statement_cache_capacity, query_logging).await?;
+            return Ok(Self {
+                inner: Arc::new(DatabaseImpl::Postgres(inner)),
+            });
+        }
+
+        #[cfg(feature = "mysql")]
+        if url.starts_with("mysql:") {
+            let inner = DatabaseMySql::new(url, statement_cache_capacity, query_logging).await?;
+            return Ok(Self {
+                inner: Arc::new(DatabaseImpl::MySql(inner)),
+            });
+        }
 
         panic!("Unsupported database URL: {url}");
     }
 
+    /// The time the database is given to respond to [`Self::ping`].
+    pub const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Checks whether the database is reachable by running a trivial query
+    /// (`SELECT 1`) on a connection from the pool.
+    ///
+    /// This is useful for readiness checks. The database is given
+    /// [`Self::PING_TIMEOUT`] to respond, so that an unresponsive database
+    /// doesn't block the caller indefinitely.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DatabaseError::PingTimeout`] if the database doesn't respond
+    /// in time, or any error returned by the database engine if the query
+    /// fails, for example, because the connection has been lost.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::Database;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> cot::Result<()> {
+    /// let db = Database::new("sqlite::memory:").await?;
+    /// db.ping().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn ping(&self) -> Result<()> {
+        let timeout = Self::PING_TIMEOUT;
+        match tokio::time::timeout(timeout, self.raw("SELECT 1")).await {
+            Ok(result) => result.map(|_| ()),
+            Err(_elapsed) => Err(DatabaseError::PingTimeout { timeout }),
+        }
+    }
+
     /// Closes the database connection.
     ///
     /// This method should be called when the database connection is no longer
@@ -1292,6 +1981,148 @@ impl Database {
         }
     }
 
+    /// Starts a new transaction.
+    ///
+    /// The statements executed through the returned [`Transaction`] (which
+    /// dereferences to a [`Database`]) are applied atomically when it's
+    /// [committed](Transaction::commit). If the transaction is dropped
+    /// without being committed, it's rolled back.
+    ///
+    /// On SQLite, the transaction takes the write lock on the database right
+    /// away, so the other transactions wait until it finishes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DatabaseError::NestedTransaction`] if this database is
+    /// already bound to a transaction.
+    ///
+    /// This method can return an error if the transaction could not be
+    /// started, for instance because there was a problem with the database
+    /// connection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::{Database, Model, model};
+    ///
+    /// #[model]
+    /// struct Account {
+    ///     #[model(primary_key)]
+    ///     id: i32,
+    ///     balance: i64,
+    /// }
+    ///
+    /// # async fn example(db: &Database) -> cot::db::Result<()> {
+    /// let transaction = db.begin().await?;
+    /// let mut from = Account::get_by_primary_key(&*transaction, 1).await?.unwrap();
+    /// let mut to = Account::get_by_primary_key(&*transaction, 2).await?.unwrap();
+    /// from.balance -= 100;
+    /// to.balance += 100;
+    /// from.save(&*transaction).await?;
+    /// to.save(&*transaction).await?;
+    /// transaction.commit().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn begin(&self) -> Result<Transaction> {
+        let inner = match &*self.inner {
+            #[cfg(feature = "sqlite")]
+            DatabaseImpl::Sqlite(inner) => DatabaseImpl::Sqlite(inner.begin().await?),
+            #[cfg(feature = "postgres")]
+            DatabaseImpl::Postgres(inner) => DatabaseImpl::Postgres(inner.begin().await?),
+            #[cfg(feature = "mysql")]
+            DatabaseImpl::MySql(inner) => DatabaseImpl::MySql(inner.begin().await?),
+        };
+
+        Ok(Transaction::new(Self {
+            inner: Arc::new(inner),
+        }))
+    }
+
+    /// Returns whether this database is bound to a transaction, i.e. whether
+    /// it has been created with [`Self::begin`].
+    pub(crate) fn in_transaction(&self) -> bool {
+        match &*self.inner {
+            #[cfg(feature = "sqlite")]
+            DatabaseImpl::Sqlite(inner) => inner.in_transaction(),
+            #[cfg(feature = "postgres")]
+            DatabaseImpl::Postgres(inner) => inner.in_transaction(),
+            #[cfg(feature = "mysql")]
+            DatabaseImpl::MySql(inner) => inner.in_transaction(),
+        }
+    }
+
+    async fn commit(&self) -> Result<()> {
+        match &*self.inner {
+            #[cfg(feature = "sqlite")]
+            DatabaseImpl::Sqlite(inner) => inner.commit().await,
+            #[cfg(feature = "postgres")]
+            DatabaseImpl::Postgres(inner) => inner.commit().await,
+            #[cfg(feature = "mysql")]
+            DatabaseImpl::MySql(inner) => inner.commit().await,
+        }
+    }
+
+    async fn rollback(&self) -> Result<()> {
+        match &*self.inner {
+            #[cfg(feature = "sqlite")]
+            DatabaseImpl::Sqlite(inner) => inner.rollback().await,
+            #[cfg(feature = "postgres")]
+            DatabaseImpl::Postgres(inner) => inner.rollback().await,
+            #[cfg(feature = "mysql")]
+            DatabaseImpl::MySql(inner) => inner.rollback().await,
+        }
+    }
+
+    /// Registers a hook that is called after every SQL statement executed
+    /// through this database connection.
+    ///
+    /// The hook receives a [`QueryRecord`] containing the statement, its bound
+    /// values and the time it took to execute. This is useful for debugging,
+    /// e.g. to find N+1 query problems. The hook is shared by all clones of
+    /// this `Database`. The hooks are called regardless of the query logging
+    /// settings (see [`DatabaseConfig::log_queries`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// use cot::db::Database;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> cot::Result<()> {
+    /// let database = Database::new("sqlite::memory:").await?;
+    ///
+    /// let queries = Arc::new(Mutex::new(Vec::new()));
+    /// let queries_hook = Arc::clone(&queries);
+    /// database.on_query(move |record| {
+    ///     queries_hook.lock().unwrap().push(record.sql().to_owned());
+    /// });
+    ///
+    /// database.raw("SELECT 1").await?;
+    /// assert_eq!(*queries.lock().unwrap(), vec!["SELECT 1"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn on_query<F>(&self, hook: F)
+    where
+        F: Fn(&QueryRecord) + Send + Sync + 'static,
+    {
+        self.query_hooks().add(Arc::new(hook));
+    }
+
+    fn query_hooks(&self) -> &QueryHooks {
+        match &*self.inner {
+            #[cfg(feature = "sqlite")]
+            DatabaseImpl::Sqlite(inner) => inner.query_hooks(),
+            #[cfg(feature = "postgres")]
+            DatabaseImpl::Postgres(inner) => inner.query_hooks(),
+            #[cfg(feature = "mysql")]
+            DatabaseImpl::MySql(inner) => inner.query_hooks(),
+        }
+    }
+
     /// Inserts a new row into the database.
     ///
     /// # Errors
@@ -1328,6 +2159,11 @@ impl Database {
     }
 
     async fn insert_or_update_impl<T: Model>(&self, data: &mut T, update: bool) -> Result<()> {
+        data.apply_defaults();
+        data.generate_slugs(&mut SlugGenerator::new(self)).await?;
+        let inserting = !update || is_auto_primary_key(&data.primary_key());
+        data.update_timestamps(chrono::Utc::now(), inserting);
+
         let column_identifiers = T::COLUMNS
             .iter()
             .map(|column| Identifier::from(column.name.as_str()));
@@ -1369,10 +2205,16 @@ impl Database {
             )?
             .or_default_values()
             .to_owned();
-        if update && !value_identifiers.is_empty() {
+        // an existing row keeps the time it was created at
+        let update_columns: Vec<_> = value_identifiers
+            .iter()
+            .copied()
+            .filter(|identifier| Some(*identifier) != T::CREATED_AT_COLUMN)
+            .collect();
+        if update && !update_columns.is_empty() {
             insert_statement.on_conflict(
-                OnConflict::column(T::PRIMARY_KEY_NAME)
-                    .update_columns(value_identifiers)
+                OnConflict::columns(T::PRIMARY_KEY_NAMES.iter().copied())
+                    .update_columns(update_columns)
                     .to_owned(),
             );
         }
@@ -1391,10 +2233,11 @@ impl Database {
                 let row_id = result
                     .last_inserted_row_id
                     .expect("expected last inserted row ID if RETURNING clause is not supported");
+                // the primary key is the auto column, so it can't be composite
                 let query = sea_query::Query::select()
                     .from(T::TABLE_NAME)
                     .columns(auto_col_identifiers)
-                    .and_where(sea_query::Expr::col(T::PRIMARY_KEY_NAME).eq(row_id))
+                    .and_where(sea_query::Expr::col(T::PRIMARY_KEY_NAMES[0]).eq(row_id))
                     .to_owned();
                 self.fetch_option(&query).await?.expect(
                     "expected a row returned from a SELECT if RETURNING clause is not supported",
@@ -1404,9 +2247,9 @@ impl Database {
         }
 
         if update {
-            trace!(primary_key = ?data.primary_key().to_db_field_value(), "Inserted or updated row");
+            trace!(primary_key = ?data.primary_key().to_db_field_values(), "Inserted or updated row");
         } else {
-            trace!(primary_key = ?data.primary_key().to_db_field_value(), "Inserted row");
+            trace!(primary_key = ?data.primary_key().to_db_field_values(), "Inserted row");
         }
 
         Ok(())
@@ -1427,13 +2270,17 @@ impl Database {
             Level::TRACE,
             "update",
             table = %T::TABLE_NAME,
-            primary_key = ?data.primary_key().to_db_field_value(),
+            primary_key = ?data.primary_key().to_db_field_values(),
         );
 
         Self::update_impl(self, data).instrument(span).await
     }
 
     async fn update_impl<T: Model>(&self, data: &mut T) -> Result<()> {
+        data.apply_defaults();
+        data.generate_slugs(&mut SlugGenerator::new(self)).await?;
+        data.update_timestamps(chrono::Utc::now(), false);
+
         let column_identifiers = T::COLUMNS
             .iter()
             .map(|column| Identifier::from(column.name.as_str()));
@@ -1457,14 +2304,16 @@ impl Database {
             }
         });
 
-        let primary_key = data
+        let primary_key: Vec<_> = data
             .primary_key()
-            .to_db_field_value()
-            .expect_value("primary key cannot be auto when updating");
+            .to_db_field_values()
+            .into_iter()
+            .map(|value| value.expect_value("primary key cannot be auto when updating"))
+            .collect();
         let update_statement = sea_query::Query::update()
             .table(T::TABLE_NAME)
             .values(statement_values)
-            .and_where(sea_query::Expr::col(T::PRIMARY_KEY_NAME).eq(primary_key.clone()))
+            .and_where(primary_key_condition::<T>(primary_key.clone()))
             .to_owned();
 
         let result = self.execute_statement(&update_statement).await?;
@@ -1520,6 +2369,15 @@ impl Database {
             return Ok(());
         }
 
+        let now = chrono::Utc::now();
+        let mut slugs = SlugGenerator::new(self);
+        for instance in data.iter_mut() {
+            instance.apply_defaults();
+            instance.generate_slugs(&mut slugs).await?;
+            let inserting = !update || is_auto_primary_key(&instance.primary_key());
+            instance.update_timestamps(now, inserting);
+        }
+
         let max_params = match &*self.inner {
             // https://sqlite.org/limits.html#max_variable_number
             // Assuming SQLite > 3.32.0 (2020-05-22)
@@ -1634,13 +2492,16 @@ impl Database {
         }
 
         if update {
+            // an existing row keeps the time it was created at
             let update_cols: Vec<_> = value_identifiers
                 .iter()
-                .filter(|id| **id != T::PRIMARY_KEY_NAME)
+                .filter(|id| {
+                    !T::PRIMARY_KEY_NAMES.contains(id) && Some(**id) != T::CREATED_AT_COLUMN
+                })
                 .copied()
                 .collect();
             insert_statement.on_conflict(
-                OnConflict::column(T::PRIMARY_KEY_NAME)
+                OnConflict::columns(T::PRIMARY_KEY_NAMES.iter().copied())
                     .update_columns(update_cols)
                     .to_owned(),
             );
@@ -1676,17 +2537,19 @@ impl Database {
             // Fetch the inserted rows using a SELECT query
             // Note: This assumes IDs are consecutive, which is generally safe for
             // auto_increment but could fail with concurrent inserts
+            // the primary key is the auto column, so it can't be composite
+            let primary_key_name = T::PRIMARY_KEY_NAMES[0];
             let query = sea_query::Query::select()
                 .from(T::TABLE_NAME)
                 .columns(auto_col_identifiers.iter().cloned())
                 .and_where(
-                    sea_query::Expr::col(T::PRIMARY_KEY_NAME).gte(first_id).and(
-                        sea_query::Expr::col(T::PRIMARY_KEY_NAME).lt(first_id
+                    sea_query::Expr::col(primary_key_name).gte(first_id).and(
+                        sea_query::Expr::col(primary_key_name).lt(first_id
                             + <u64 as TryFrom<usize>>::try_from(chunk.len())
                                 .expect("chunk length fits in u64")),
                     ),
                 )
-                .order_by(T::PRIMARY_KEY_NAME, sea_query::Order::Asc)
+                .order_by(primary_key_name, sea_query::Order::Asc)
                 .to_owned();
 
             let rows = self.fetch_all(&query).await?;
@@ -1728,11 +2591,17 @@ impl Database {
         let mut select = sea_query::Query::select();
         select.columns(columns_to_get).from(T::TABLE_NAME);
         query.add_filter_to_statement(&mut select);
+        query.add_order_by_to_statement(&mut select);
         query.add_limit_to_statement(&mut select);
         query.add_offset_to_statement(&mut select);
+        self.add_lock_to_statement(query, &mut select)?;
+        let select = query.add_related_to_statement(select);
 
         let rows = self.fetch_all(&select).await?;
-        let result = rows.into_iter().map(T::from_db).collect::<Result<_>>()?;
+        let result = rows
+            .into_iter()
+            .map(|row| query.model_from_db(row))
+            .collect::<Result<_>>()?;
 
         Ok(result)
     }
@@ -1754,12 +2623,15 @@ impl Database {
         let mut select = sea_query::Query::select();
         select.columns(columns_to_get).from(T::TABLE_NAME);
         query.add_filter_to_statement(&mut select);
+        query.add_order_by_to_statement(&mut select);
         select.limit(1);
+        self.add_lock_to_statement(query, &mut select)?;
+        let select = query.add_related_to_statement(select);
 
         let row = self.fetch_option(&select).await?;
 
         let result = match row {
-            Some(row) => Some(T::from_db(row)?),
+            Some(row) => Some(query.model_from_db(row)?),
             None => None,
         };
         Ok(result)
@@ -1781,6 +2653,7 @@ impl Database {
         select.expr(sea_query::Expr::value(1)).from(T::TABLE_NAME);
         query.add_filter_to_statement(&mut select);
         select.limit(1);
+        self.add_lock_to_statement(query, &mut select)?;
 
         let rows = self.fetch_option(&select).await?;
 
@@ -1806,6 +2679,121 @@ impl Database {
         self.execute_statement(&delete).await
     }
 
+    /// Deletes all rows that match the given query and returns the deleted
+    /// rows.
+    ///
+    /// On backends that support the `RETURNING` clause (SQLite and
+    /// PostgreSQL), this is done in a single statement. On MySQL, the
+    /// matching rows are locked and fetched before being deleted, in a
+    /// transaction (unless this is already run in one).
+    ///
+    /// # Errors
+    ///
+    /// This method can return an error if the query is invalid.
+    ///
+    /// This method can return an error if the data in the database is not
+    /// compatible with the model (usually meaning the migrations haven't been
+    /// generated or applied).
+    ///
+    /// Can return an error if the database connection is lost.
+    pub async fn delete_returning<T: Model>(&self, query: &Query<T>) -> Result<Vec<T>> {
+        let mut delete = sea_query::Query::delete();
+        delete.from_table(T::TABLE_NAME);
+        query.add_filter_to_statement(&mut delete);
+
+        if self.supports_returning() {
+            let columns = T::COLUMNS
+                .iter()
+                .map(|column| column.name.into_column_ref())
+                .collect();
+            delete.returning(ReturningClause::Columns(columns));
+
+            let rows = self.fetch_all(&delete).await?;
+            rows.into_iter().map(T::from_db).collect()
+        } else if self.in_transaction() {
+            self.select_and_delete(query, &delete).await
+        } else {
+            let transaction = self.begin().await?;
+            let deleted = transaction.select_and_delete(query, &delete).await?;
+            transaction.commit().await?;
+
+            Ok(deleted)
+        }
+    }
+
+    /// Selects the rows matching the query and then deletes them, for the
+    /// databases that don't support the `RETURNING` clause.
+    ///
+    /// This should be run in a transaction; the selected rows are locked, so
+    /// that the rows returned are exactly the ones deleted.
+    async fn select_and_delete<T: Model>(
+        &self,
+        query: &Query<T>,
+        delete: &sea_query::DeleteStatement,
+    ) -> Result<Vec<T>> {
+        let columns_to_get: Vec<_> = T::COLUMNS.iter().map(|column| column.name).collect();
+        let mut select = sea_query::Query::select();
+        select.columns(columns_to_get).from(T::TABLE_NAME);
+        query.add_filter_to_statement(&mut select);
+        if self.supports_row_locking() {
+            select.lock(sea_query::LockType::Update);
+        }
+
+        let rows = self.fetch_all(&select).await?;
+        let deleted = rows.into_iter().map(T::from_db).collect::<Result<_>>()?;
+        self.execute_statement(delete).await?;
+
+        Ok(deleted)
+    }
+
+    /// Updates all rows that match the given query with the given
+    /// assignments.
+    ///
+    /// If `assignments` is empty, no statement is executed and zero affected
+    /// rows are returned.
+    ///
+    /// # Errors
+    ///
+    /// This method can return an error if the query is invalid.
+    ///
+    /// This method can return an error if the model doesn't exist in the
+    /// database (usually meaning the migrations haven't been generated or
+    /// applied).
+    ///
+    /// Can return an error if the database connection is lost.
+    pub async fn bulk_update<T: Model>(
+        &self,
+        query: &Query<T>,
+        assignments: &[Assignment],
+    ) -> Result<StatementResult> {
+        if assignments.is_empty() {
+            return Ok(StatementResult::new(RowsNum(0)));
+        }
+
+        let timestamp_assignments = T::timestamp_assignments(chrono::Utc::now())
+            .into_iter()
+            .filter(|timestamp| {
+                !assignments
+                    .iter()
+                    .any(|assignment| assignment.identifier() == timestamp.identifier())
+            });
+
+        let mut update = sea_query::Query::update();
+        update.table(T::TABLE_NAME).values(
+            assignments
+                .iter()
+                .cloned()
+                .chain(timestamp_assignments)
+                .map(|assignment| assignment.as_sea_query_value()),
+        );
+        query.add_filter_to_statement(&mut update);
+
+        let result = self.execute_statement(&update).await?;
+        trace!(count = *result.rows_affected, "Updated rows");
+
+        Ok(result)
+    }
+
     /// Executes a raw SQL query.
     ///
     /// # Errors
@@ -1877,17 +2865,110 @@ impl Database {
         Ok(result)
     }
 
-    async fn fetch_option<T>(&self, statement: &T) -> Result<Option<Row>>
-    where
-        T: SqlxBinder + Send + Sync,
-    {
-        let result = match &*self.inner {
+    /// Executes a raw SQL query with parameters and converts the returned rows
+    /// to `T`.
+    ///
+    /// This is useful for queries that can't be expressed with [`Query`], such
+    /// as the ones using window functions or common table expressions. The
+    /// values are passed to the database as bound parameters, so they are
+    /// never interpolated into the SQL.
+    ///
+    /// `T` can be any [`FromRow`] implementor, including all [`Model`]s. When
+    /// `T` is a model, the query must return the model's columns first, with
+    /// the same names and in the same order as the fields of the model (which
+    /// is the case for `SELECT * FROM <model table>`); otherwise,
+    /// [`DatabaseError::UnexpectedColumns`] is returned. Use aliases (`AS`) to
+    /// rename computed columns as needed.
+    ///
+    /// To execute statements that don't return rows, use [`Self::raw_with`].
+    ///
+    /// # Errors
+    ///
+    /// This method can return an error if the query is invalid, or if the
+    /// returned rows can't be converted to `T`.
+    ///
+    /// Can return an error if the database connection is lost.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::{Auto, Database, Model, model};
+    ///
+    /// #[model]
+    /// struct Item {
+    ///     #[model(primary_key)]
+    ///     id: Auto<i32>,
+    ///     name: String,
+    /// }
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> cot::Result<()> {
+    /// let db = Database::new("sqlite::memory:").await?;
+    /// db.raw("CREATE TABLE cot__item (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT)")
+    ///     .await?;
+    /// db.raw_with(
+    ///     "INSERT INTO cot__item (name) VALUES (?), (?)",
+    ///     &[&"a", &"b"],
+    /// )
+    /// .await?;
+    ///
+    /// let items: Vec<Item> = db
+    ///     .raw_query("SELECT * FROM cot__item WHERE name = ?", &[&"b"])
+    ///     .await?;
+    /// assert_eq!(items.len(), 1);
+    /// assert_eq!(items[0].name, "b");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn raw_query<T: FromRow>(
+        &self,
+        query: &str,
+        values: &[&dyn ToDbValue],
+    ) -> Result<Vec<T>> {
+        let values = values
+            .iter()
+            .map(ToDbValue::to_db_value)
+            .collect::<Vec<_>>();
+        let values = SqlxValues(sea_query::Values(values));
+
+        let rows: Vec<Row> = match &*self.inner {
             #[cfg(feature = "sqlite")]
-            DatabaseImpl::Sqlite(inner) => inner.fetch_option(statement).await?.map(Row::Sqlite),
+            DatabaseImpl::Sqlite(inner) => inner
+                .raw_fetch_all(query, values)
+                .await?
+                .into_iter()
+                .map(Row::Sqlite)
+                .collect(),
             #[cfg(feature = "postgres")]
-            DatabaseImpl::Postgres(inner) => {
-                inner.fetch_option(statement).await?.map(Row::Postgres)
-            }
+            DatabaseImpl::Postgres(inner) => inner
+                .raw_fetch_all(query, values)
+                .await?
+                .into_iter()
+                .map(Row::Postgres)
+                .collect(),
+            #[cfg(feature = "mysql")]
+            DatabaseImpl::MySql(inner) => inner
+                .raw_fetch_all(query, values)
+                .await?
+                .into_iter()
+                .map(Row::MySql)
+                .collect(),
+        };
+
+        rows.into_iter().map(T::from_row).collect()
+    }
+
+    async fn fetch_option<T>(&self, statement: &T) -> Result<Option<Row>>
+    where
+        T: SqlxBinder + Send + Sync,
+    {
+        let result = match &*self.inner {
+            #[cfg(feature = "sqlite")]
+            DatabaseImpl::Sqlite(inner) => inner.fetch_option(statement).await?.map(Row::Sqlite),
+            #[cfg(feature = "postgres")]
+            DatabaseImpl::Postgres(inner) => {
+                inner.fetch_option(statement).await?.map(Row::Postgres)
+            }
             #[cfg(feature = "mysql")]
             DatabaseImpl::MySql(inner) => inner.fetch_option(statement).await?.map(Row::MySql),
         };
@@ -1895,7 +2976,44 @@ impl Database {
         Ok(result)
     }
 
-    fn supports_returning(&self) -> bool {
+    fn add_lock_to_statement<T: Model>(
+        &self,
+        query: &Query<T>,
+        statement: &mut sea_query::SelectStatement,
+    ) -> Result<()> {
+        if query.has_lock() && !self.supports_row_locking() {
+            return Err(DatabaseError::NotSupported {
+                feature: "row locking (`SELECT ... FOR UPDATE`)",
+            });
+        }
+
+        query.add_lock_to_statement(statement);
+        Ok(())
+    }
+
+    pub(crate) fn supports_row_locking(&self) -> bool {
+        match &*self.inner {
+            #[cfg(feature = "sqlite")]
+            DatabaseImpl::Sqlite(_) => false,
+            #[cfg(feature = "postgres")]
+            DatabaseImpl::Postgres(_) => true,
+            #[cfg(feature = "mysql")]
+            DatabaseImpl::MySql(_) => true,
+        }
+    }
+
+    fn supports_altering_check_constraints(&self) -> bool {
+        match &*self.inner {
+            #[cfg(feature = "sqlite")]
+            DatabaseImpl::Sqlite(_) => false,
+            #[cfg(feature = "postgres")]
+            DatabaseImpl::Postgres(_) => true,
+            #[cfg(feature = "mysql")]
+            DatabaseImpl::MySql(_) => false,
+        }
+    }
+
+    pub(crate) fn supports_returning(&self) -> bool {
         match &*self.inner {
             #[cfg(feature = "sqlite")]
             DatabaseImpl::Sqlite(_) => true,
@@ -1968,6 +3086,19 @@ impl Database {
 
         Ok(result)
     }
+
+    /// Returns the SQL code of the given schema statement for the database
+    /// backend, without executing it.
+    fn build_schema<T: SchemaStatementBuilder>(&self, statement: T) -> String {
+        match &*self.inner {
+            #[cfg(feature = "sqlite")]
+            DatabaseImpl::Sqlite(_) => DatabaseSqlite::build_schema(statement),
+            #[cfg(feature = "postgres")]
+            DatabaseImpl::Postgres(_) => DatabasePostgres::build_schema(statement),
+            #[cfg(feature = "mysql")]
+            DatabaseImpl::MySql(_) => DatabaseMySql::build_schema(statement),
+        }
+    }
 }
 
 impl ColumnTypeMapper for Database {
@@ -2088,6 +3219,38 @@ pub trait DatabaseBackend: Send + Sync {
     ///
     /// Can return an error if the database connection is lost.
     async fn delete<T: Model>(&self, query: &Query<T>) -> Result<StatementResult>;
+
+    /// Deletes all rows that match the given query and returns the deleted
+    /// rows.
+    ///
+    /// # Errors
+    ///
+    /// This method can return an error if the query is invalid.
+    ///
+    /// This method can return an error if the data in the database is not
+    /// compatible with the model (usually meaning the migrations haven't been
+    /// generated or applied).
+    ///
+    /// Can return an error if the database connection is lost.
+    async fn delete_returning<T: Model>(&self, query: &Query<T>) -> Result<Vec<T>>;
+
+    /// Updates all rows that match the given query with the given
+    /// assignments.
+    ///
+    /// # Errors
+    ///
+    /// This method can return an error if the query is invalid.
+    ///
+    /// This method can return an error if the model doesn't exist in the
+    /// database (usually meaning the migrations haven't been generated or
+    /// applied).
+    ///
+    /// Can return an error if the database connection is lost.
+    async fn bulk_update<T: Model>(
+        &self,
+        query: &Query<T>,
+        assignments: &[Assignment],
+    ) -> Result<StatementResult>;
 }
 
 #[async_trait]
@@ -2127,6 +3290,18 @@ impl DatabaseBackend for Database {
     async fn delete<T: Model>(&self, query: &Query<T>) -> Result<StatementResult> {
         Database::delete(self, query).await
     }
+
+    async fn delete_returning<T: Model>(&self, query: &Query<T>) -> Result<Vec<T>> {
+        Database::delete_returning(self, query).await
+    }
+
+    async fn bulk_update<T: Model>(
+        &self,
+        query: &Query<T>,
+        assignments: &[Assignment],
+    ) -> Result<StatementResult> {
+        Database::bulk_update(self, query, assignments).await
+    }
 }
 
 /// Result of a statement execution.
@@ -2139,8 +3314,7 @@ pub struct StatementResult {
 impl StatementResult {
     /// Creates a new statement result with the given number of rows affected.
     #[must_use]
-    #[cfg(test)]
-    fn new(rows_affected: RowsNum) -> Self {
+    pub(crate) fn new(rows_affected: RowsNum) -> Self {
         Self {
             rows_affected,
             last_inserted_row_id: None,
@@ -2164,6 +3338,145 @@ impl StatementResult {
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Deref, Display)]
 pub struct RowsNum(pub u64);
 
+/// A record of an SQL statement executed by the [`Database`].
+///
+/// Records are passed to the hooks registered with [`Database::on_query`].
+///
+/// # Examples
+///
+/// ```
+/// use cot::db::Database;
+///
+/// # #[tokio::main]
+/// # async fn main() -> cot::Result<()> {
+/// let database = Database::new("sqlite::memory:").await?;
+/// database.on_query(|record| {
+///     println!(
+///         "{} ({:?}): {:?}",
+///         record.sql(),
+///         record.elapsed(),
+///         record.values()
+///     );
+/// });
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryRecord {
+    sql: String,
+    values: Vec<DbValue>,
+    elapsed: std::time::Duration,
+}
+
+impl QueryRecord {
+    #[must_use]
+    fn new(sql: &str, values: Vec<DbValue>, elapsed: std::time::Duration) -> Self {
+        Self {
+            sql: sql.to_owned(),
+            values,
+            elapsed,
+        }
+    }
+
+    /// Returns the SQL of the executed statement.
+    #[must_use]
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    /// Returns the values bound to the statement's parameters.
+    #[must_use]
+    pub fn values(&self) -> &[DbValue] {
+        &self.values
+    }
+
+    /// Returns the time it took to execute the statement.
+    #[must_use]
+    pub fn elapsed(&self) -> std::time::Duration {
+        self.elapsed
+    }
+}
+
+/// Which of the executed statements are logged, and how.
+#[derive(Debug, Copy, Clone, Default)]
+pub(crate) struct QueryLogging {
+    enabled: bool,
+    values: bool,
+    slow_query_threshold: Option<Duration>,
+}
+
+impl QueryLogging {
+    fn from_config(config: &DatabaseConfig) -> Self {
+        Self {
+            enabled: config.log_queries,
+            values: config.log_query_values,
+            slow_query_threshold: config.slow_query_threshold,
+        }
+    }
+
+    pub(crate) fn log(&self, record: &QueryRecord) {
+        if self.enabled {
+            if self.values {
+                debug!(
+                    sql = record.sql(),
+                    params = record.values().len(),
+                    values = ?record.values(),
+                    elapsed = ?record.elapsed(),
+                    "Executed query"
+                );
+            } else {
+                debug!(
+                    sql = record.sql(),
+                    params = record.values().len(),
+                    elapsed = ?record.elapsed(),
+                    "Executed query"
+                );
+            }
+        }
+
+        if let Some(threshold) = self.slow_query_threshold
+            && record.elapsed() > threshold
+        {
+            warn!(
+                sql = record.sql(),
+                elapsed = ?record.elapsed(),
+                threshold = ?threshold,
+                "Slow query"
+            );
+        }
+    }
+}
+
+type QueryHook = Arc<dyn Fn(&QueryRecord) + Send + Sync>;
+
+/// The list of hooks registered with [`Database::on_query`].
+#[derive(Debug, Default)]
+pub(crate) struct QueryHooks {
+    #[debug("...")]
+    hooks: std::sync::RwLock<Vec<QueryHook>>,
+}
+
+impl QueryHooks {
+    fn add(&self, hook: QueryHook) {
+        // the list can't be left in an inconsistent state, so it's safe to
+        // ignore the poison
+        self.hooks
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push(hook);
+    }
+
+    pub(crate) fn notify(&self, record: &QueryRecord) {
+        let hooks = self
+            .hooks
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        for hook in hooks.iter() {
+            hook(record);
+        }
+    }
+}
+
 /// A wrapper over a value that can be either a fixed value or be automatically
 /// generated by the database.
 ///
@@ -2457,6 +3770,165 @@ impl<const LIMIT: u32> fake::Dummy<fake::Faker> for LimitedString<LIMIT> {
     }
 }
 
+/// A wrapper over a value that is stored as JSON in the database.
+///
+/// The wrapped value is serialized when the model is saved and deserialized
+/// when it is loaded, so it can be any type implementing [`Serialize`] and
+/// [`DeserializeOwned`](serde::de::DeserializeOwned), such as a struct, a
+/// vector, or [`serde_json::Value`].
+///
+/// # Database
+///
+/// This type is represented by the `JSONB` type in PostgreSQL, `JSON` in
+/// MySQL, and `TEXT` in SQLite. The values inside the JSON document can be
+/// used in queries with [`Expr::json_get`](query::Expr::json_get).
+///
+/// # Examples
+///
+/// ```
+/// use cot::db::{Json, model};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, Clone, Serialize, Deserialize)]
+/// struct Settings {
+///     theme: String,
+///     notifications: bool,
+/// }
+///
+/// #[model]
+/// struct Profile {
+///     #[model(primary_key)]
+///     id: i32,
+///     settings: Json<Settings>,
+/// }
+/// ```
+#[cfg(feature = "json")]
+#[derive(
+    Debug,
+    Default,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Deref,
+    derive_more::DerefMut,
+    Serialize,
+    Deserialize,
+)]
+#[serde(transparent)]
+pub struct Json<T>(pub T);
+
+#[cfg(feature = "json")]
+impl<T> Json<T> {
+    /// Returns the wrapped value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::Json;
+    ///
+    /// let json = Json(vec![1, 2, 3]);
+    /// assert_eq!(json.into_inner(), vec![1, 2, 3]);
+    /// ```
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T> From<T> for Json<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+/// A wrapper over an enum deriving
+/// [`SelectChoice`](crate::form::fields::SelectChoice) that is stored in the
+/// database as the [`id`](crate::form::fields::SelectChoice::id) of the
+/// selected variant.
+///
+/// # Database
+///
+/// This type is represented by the `TEXT` type in all the databases. Mark the
+/// field with `#[model(choices)]` to have the migration generator emit a
+/// `CHECK` constraint that only allows the identifiers of the enum variants
+/// to be stored in the column. The constraint is updated in the generated
+/// migrations whenever the variants of the enum change.
+///
+/// # Examples
+///
+/// ```
+/// use cot::db::{Choice, model};
+/// use cot::form::fields::SelectChoice;
+///
+/// #[derive(Debug, Clone, PartialEq, Eq, SelectChoice)]
+/// enum Status {
+///     #[select_choice(id = "draft")]
+///     Draft,
+///     #[select_choice(id = "published")]
+///     Published,
+/// }
+///
+/// #[model]
+/// struct Article {
+///     #[model(primary_key)]
+///     id: i32,
+///     #[model(choices)]
+///     status: Choice<Status>,
+/// }
+/// ```
+#[derive(
+    Debug,
+    Default,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Deref,
+    derive_more::DerefMut,
+    Serialize,
+    Deserialize,
+)]
+#[serde(transparent)]
+pub struct Choice<T>(pub T);
+
+impl<T> Choice<T> {
+    /// Returns the wrapped value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::Choice;
+    /// use cot::form::fields::SelectChoice;
+    ///
+    /// #[derive(Debug, PartialEq, SelectChoice)]
+    /// enum Status {
+    ///     Draft,
+    ///     Published,
+    /// }
+    ///
+    /// let choice = Choice(Status::Draft);
+    /// assert_eq!(choice.into_inner(), Status::Draft);
+    /// ```
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Choice<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
 /// A type that represents a column type in the database.
 ///
 /// # Examples
@@ -2538,10 +4010,26 @@ pub enum ColumnType {
     Blob,
     /// A string column type with a maximum length.
     String(u32),
+    /// A JSON column type.
+    ///
+    /// This is represented as `JSONB` in PostgreSQL, `JSON` in MySQL, and
+    /// `TEXT` in SQLite.
+    Json,
+    /// A fixed-point decimal column type with an optional precision (the
+    /// total number of digits) and scale (the number of digits after the
+    /// decimal point).
+    ///
+    /// This is represented as `NUMERIC` in PostgreSQL, `DECIMAL` in MySQL,
+    /// and `TEXT` in SQLite, so that the values are stored exactly. MySQL
+    /// requires the precision and scale to be specified; if they are not,
+    /// `DECIMAL(65, 28)` is used.
+    Decimal(Option<(u32, u32)>),
 }
 
 #[cfg(test)]
 mod tests {
+    use tracing_test::traced_test;
+
     use super::*;
 
     #[test]
@@ -2659,4 +4147,298 @@ mod tests {
             serde_json::from_str("null");
         assert!(deserialized.is_err());
     }
+
+    #[cfg(feature = "sqlite")]
+    fn sqlite_backend(db: &Database) -> &DatabaseSqlite {
+        match &*db.inner {
+            DatabaseImpl::Sqlite(inner) => inner,
+            #[cfg(any(feature = "postgres", feature = "mysql"))]
+            _ => unreachable!("not an SQLite database"),
+        }
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[cot::test]
+    #[cfg_attr(
+        miri,
+        ignore = "unsupported operation: can't call foreign function `sqlite3_open_v2` on OS `linux`"
+    )]
+    async fn statement_is_prepared_once() {
+        let db = Database::new("sqlite::memory:").await.unwrap();
+        let backend = sqlite_backend(&db);
+        let cached_before = backend.cached_statements_size().await.unwrap();
+
+        for i in 0..100 {
+            db.raw_with("SELECT ?", &[&i]).await.unwrap();
+            backend.wait_for_idle_connections().await;
+        }
+
+        assert_eq!(
+            backend.cached_statements_size().await.unwrap(),
+            cached_before + 1
+        );
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[cot::test]
+    #[cfg_attr(
+        miri,
+        ignore = "unsupported operation: can't call foreign function `sqlite3_open_v2` on OS `linux`"
+    )]
+    async fn statement_cache_is_bounded() {
+        let config = DatabaseConfig::builder()
+            .url("sqlite::memory:")
+            .statement_cache_capacity(2)
+            .build();
+        let db = Database::from_config(&config).await.unwrap();
+
+        let backend = sqlite_backend(&db);
+
+        for i in 0..5 {
+            db.raw_with(&format!("SELECT {i}, ?"), &[&i]).await.unwrap();
+            backend.wait_for_idle_connections().await;
+        }
+
+        assert_eq!(backend.cached_statements_size().await.unwrap(), 2);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[cot::test]
+    #[cfg_attr(
+        miri,
+        ignore = "unsupported operation: can't call foreign function `sqlite3_open_v2` on OS `linux`"
+    )]
+    async fn row_locking_not_supported_on_sqlite() {
+        #[model]
+        struct TestModel {
+            #[model(primary_key)]
+            id: i32,
+        }
+
+        let db = Database::new("sqlite::memory:").await.unwrap();
+
+        let result = TestModel::objects().for_update().all(&db).await;
+
+        assert!(matches!(result, Err(DatabaseError::NotSupported { .. })));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[model]
+    struct TransactionItem {
+        #[model(primary_key)]
+        id: i32,
+    }
+
+    #[cfg(feature = "sqlite")]
+    async fn transaction_test_database() -> Database {
+        let db = Database::new("sqlite::memory:").await.unwrap();
+        db.raw(&format!(
+            "CREATE TABLE {} (id INTEGER PRIMARY KEY)",
+            TransactionItem::TABLE_NAME
+        ))
+        .await
+        .unwrap();
+        db
+    }
+
+    #[cfg(feature = "sqlite")]
+    async fn insert_transaction_item(db: &Database, id: i32) -> Result<()> {
+        TransactionItem { id }.insert(db).await
+    }
+
+    #[cfg(feature = "sqlite")]
+    async fn transaction_item_ids(db: &Database) -> Vec<i32> {
+        let mut ids: Vec<_> = TransactionItem::objects()
+            .all(db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|item| item.id)
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[cot::test]
+    #[cfg_attr(
+        miri,
+        ignore = "unsupported operation: can't call foreign function `sqlite3_open_v2` on OS `linux`"
+    )]
+    async fn transaction_commit() {
+        let db = transaction_test_database().await;
+
+        let transaction = db.begin().await.unwrap();
+        assert!(transaction.in_transaction());
+        insert_transaction_item(&transaction, 1).await.unwrap();
+        assert_eq!(transaction_item_ids(&transaction).await, [1]);
+        transaction.commit().await.unwrap();
+
+        assert!(!db.in_transaction());
+        assert_eq!(transaction_item_ids(&db).await, [1]);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[cot::test]
+    #[cfg_attr(
+        miri,
+        ignore = "unsupported operation: can't call foreign function `sqlite3_open_v2` on OS `linux`"
+    )]
+    async fn transaction_rollback() {
+        let db = transaction_test_database().await;
+
+        let transaction = db.begin().await.unwrap();
+        insert_transaction_item(&transaction, 1).await.unwrap();
+        transaction.rollback().await.unwrap();
+
+        assert_eq!(transaction_item_ids(&db).await, Vec::<i32>::new());
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[cot::test]
+    #[cfg_attr(
+        miri,
+        ignore = "unsupported operation: can't call foreign function `sqlite3_open_v2` on OS `linux`"
+    )]
+    async fn transaction_rolled_back_on_drop() {
+        let db = transaction_test_database().await;
+
+        let transaction = db.begin().await.unwrap();
+        insert_transaction_item(&transaction, 1).await.unwrap();
+        drop(transaction);
+
+        let transaction = db.begin().await.unwrap();
+        insert_transaction_item(&transaction, 2).await.unwrap();
+        transaction.commit().await.unwrap();
+
+        assert_eq!(transaction_item_ids(&db).await, [2]);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[cot::test]
+    #[cfg_attr(
+        miri,
+        ignore = "unsupported operation: can't call foreign function `sqlite3_open_v2` on OS `linux`"
+    )]
+    async fn transaction_nested_begin() {
+        let db = transaction_test_database().await;
+        let transaction = db.begin().await.unwrap();
+
+        let result = transaction.begin().await;
+
+        assert!(matches!(result, Err(DatabaseError::NestedTransaction)));
+        transaction.commit().await.unwrap();
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[cot::test]
+    #[cfg_attr(
+        miri,
+        ignore = "unsupported operation: can't call foreign function `sqlite3_open_v2` on OS `linux`"
+    )]
+    async fn transaction_finished_through_clone() {
+        let db = transaction_test_database().await;
+        let transaction = db.begin().await.unwrap();
+        let clone = (*transaction).clone();
+
+        transaction.commit().await.unwrap();
+
+        let result = insert_transaction_item(&clone, 1).await;
+        assert!(matches!(result, Err(DatabaseError::TransactionFinished)));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[cot::test]
+    #[cfg_attr(
+        miri,
+        ignore = "unsupported operation: can't call foreign function `sqlite3_open_v2` on OS `linux`"
+    )]
+    async fn ping_closed_database() {
+        let db = Database::new("sqlite::memory:").await.unwrap();
+        db.ping().await.unwrap();
+
+        db.close().await.unwrap();
+
+        assert!(db.ping().await.is_err());
+    }
+
+    #[test]
+    fn ping_timeout_error_message() {
+        let error = DatabaseError::PingTimeout {
+            timeout: Duration::from_secs(5),
+        };
+
+        assert_eq!(
+            error.to_string(),
+            "database error: the database didn't respond within 5s"
+        );
+    }
+
+    fn query_record(elapsed: Duration) -> QueryRecord {
+        QueryRecord::new(
+            "SELECT * FROM users WHERE email = ?",
+            vec!["john@example.com".to_db_value()],
+            elapsed,
+        )
+    }
+
+    #[test]
+    #[traced_test]
+    fn query_logging_disabled() {
+        let logging = QueryLogging::default();
+
+        logging.log(&query_record(Duration::from_millis(1)));
+
+        assert!(!logs_contain("Executed query"));
+        assert!(!logs_contain("Slow query"));
+    }
+
+    #[test]
+    #[traced_test]
+    fn query_logging_without_values() {
+        let logging = QueryLogging {
+            enabled: true,
+            ..QueryLogging::default()
+        };
+
+        logging.log(&query_record(Duration::from_millis(1)));
+
+        assert!(logs_contain("Executed query"));
+        assert!(logs_contain("SELECT * FROM users WHERE email = ?"));
+        assert!(logs_contain("params=1"));
+        assert!(!logs_contain("john@example.com"));
+    }
+
+    #[test]
+    #[traced_test]
+    fn query_logging_with_values() {
+        let logging = QueryLogging {
+            enabled: true,
+            values: true,
+            ..QueryLogging::default()
+        };
+
+        logging.log(&query_record(Duration::from_millis(1)));
+
+        assert!(logs_contain("Executed query"));
+        assert!(logs_contain("john@example.com"));
+    }
+
+    #[test]
+    #[traced_test]
+    fn query_logging_slow_query() {
+        let logging = QueryLogging {
+            slow_query_threshold: Some(Duration::from_millis(100)),
+            ..QueryLogging::default()
+        };
+
+        logging.log(&query_record(Duration::from_millis(10)));
+        assert!(!logs_contain("Slow query"));
+
+        logging.log(&query_record(Duration::from_millis(150)));
+        assert!(logs_contain("WARN"));
+        assert!(logs_contain("Slow query"));
+        assert!(!logs_contain("Executed query"));
+        assert!(!logs_contain("john@example.com"));
+    }
 }
@@ -14,7 +14,12 @@ use crate::db::{
 };
 
 mod chrono_fields;
+mod choice_fields;
 mod chrono_wrapper;
+#[cfg(feature = "decimal")]
+mod decimal_fields;
+#[cfg(feature = "json")]
+mod json_fields;
 
 macro_rules! impl_from_sqlite_default {
     () => {
@@ -333,12 +338,18 @@ impl<const LIMIT: u32> FromDbValue for Option<LimitedString<LIMIT>> {
     }
 }
 
-impl<T: Model + Send + Sync> DatabaseField for ForeignKey<T> {
+impl<T: Model + Send + Sync> DatabaseField for ForeignKey<T>
+where
+    T::PrimaryKey: PrimaryKey,
+{
     const NULLABLE: bool = T::PrimaryKey::NULLABLE;
     const TYPE: ColumnType = T::PrimaryKey::TYPE;
 }
 
-impl<T: Model + Send + Sync> FromDbValue for ForeignKey<T> {
+impl<T: Model + Send + Sync> FromDbValue for ForeignKey<T>
+where
+    T::PrimaryKey: PrimaryKey,
+{
     #[cfg(feature = "sqlite")]
     fn from_sqlite(value: SqliteValueRef<'_>) -> Result<Self> {
         T::PrimaryKey::from_sqlite(value).map(ForeignKey::PrimaryKey)
@@ -355,7 +366,10 @@ impl<T: Model + Send + Sync> FromDbValue for ForeignKey<T> {
     }
 }
 
-impl<T: Model + Send + Sync> ToDbFieldValue for ForeignKey<T> {
+impl<T: Model + Send + Sync> ToDbFieldValue for ForeignKey<T>
+where
+    T::PrimaryKey: PrimaryKey,
+{
     fn to_db_field_value(&self) -> DbFieldValue {
         self.primary_key().to_db_field_value()
     }
@@ -383,6 +397,7 @@ where
 
 impl<T: Model + Send + Sync> ToDbFieldValue for Option<ForeignKey<T>>
 where
+    T::PrimaryKey: PrimaryKey,
     Option<T::PrimaryKey>: ToDbFieldValue,
 {
     fn to_db_field_value(&self) -> DbFieldValue {
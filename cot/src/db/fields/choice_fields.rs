@@ -0,0 +1,115 @@
+#[cfg(feature = "mysql")]
+use crate::db::impl_mysql::MySqlValueRef;
+#[cfg(feature = "postgres")]
+use crate::db::impl_postgres::PostgresValueRef;
+#[cfg(feature = "sqlite")]
+use crate::db::impl_sqlite::SqliteValueRef;
+use crate::db::{
+    Choice, ColumnType, DatabaseError, DatabaseField, DbValue, FromDbValue, Result, SqlxValueRef,
+    ToDbValue,
+};
+use crate::form::fields::SelectChoice;
+
+fn choice_from_str<T: SelectChoice>(value: &str) -> Result<Choice<T>> {
+    T::from_str(value)
+        .map(Choice)
+        .map_err(DatabaseError::value_decode)
+}
+
+impl<T: SelectChoice + Send + Sync> DatabaseField for Choice<T> {
+    const TYPE: ColumnType = ColumnType::Text;
+}
+
+impl<T: SelectChoice> FromDbValue for Choice<T> {
+    #[cfg(feature = "sqlite")]
+    fn from_sqlite(value: SqliteValueRef<'_>) -> Result<Self> {
+        choice_from_str(&value.get::<String>()?)
+    }
+
+    #[cfg(feature = "postgres")]
+    fn from_postgres(value: PostgresValueRef<'_>) -> Result<Self> {
+        choice_from_str(&value.get::<String>()?)
+    }
+
+    #[cfg(feature = "mysql")]
+    fn from_mysql(value: MySqlValueRef<'_>) -> Result<Self> {
+        choice_from_str(&value.get::<String>()?)
+    }
+}
+
+impl<T: SelectChoice> FromDbValue for Option<Choice<T>> {
+    #[cfg(feature = "sqlite")]
+    fn from_sqlite(value: SqliteValueRef<'_>) -> Result<Self> {
+        value
+            .get::<Option<String>>()?
+            .map(|value| choice_from_str(&value))
+            .transpose()
+    }
+
+    #[cfg(feature = "postgres")]
+    fn from_postgres(value: PostgresValueRef<'_>) -> Result<Self> {
+        value
+            .get::<Option<String>>()?
+            .map(|value| choice_from_str(&value))
+            .transpose()
+    }
+
+    #[cfg(feature = "mysql")]
+    fn from_mysql(value: MySqlValueRef<'_>) -> Result<Self> {
+        value
+            .get::<Option<String>>()?
+            .map(|value| choice_from_str(&value))
+            .transpose()
+    }
+}
+
+impl<T: SelectChoice + Send + Sync> ToDbValue for Choice<T> {
+    fn to_db_value(&self) -> DbValue {
+        self.0.id().into()
+    }
+}
+
+impl<T: SelectChoice + Send + Sync> ToDbValue for Option<Choice<T>> {
+    fn to_db_value(&self) -> DbValue {
+        self.as_ref().map(|choice| choice.0.id()).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::{Choice, ColumnType, DatabaseField, DbValue, ToDbValue};
+    use crate::form::fields::SelectChoice;
+
+    #[derive(Debug, PartialEq, SelectChoice)]
+    enum Status {
+        #[select_choice(id = "draft")]
+        Draft,
+        #[select_choice(id = "published")]
+        Published,
+    }
+
+    #[test]
+    fn choice_column_type() {
+        assert_eq!(<Choice<Status> as DatabaseField>::TYPE, ColumnType::Text);
+        const {
+            assert!(!<Choice<Status> as DatabaseField>::NULLABLE);
+            assert!(<Option<Choice<Status>> as DatabaseField>::NULLABLE);
+        }
+    }
+
+    #[test]
+    fn choice_to_db_value() {
+        assert_eq!(
+            Choice(Status::Published).to_db_value(),
+            DbValue::from("published".to_owned())
+        );
+        assert_eq!(
+            Some(Choice(Status::Draft)).to_db_value(),
+            DbValue::from(Some("draft".to_owned()))
+        );
+        assert_eq!(
+            None::<Choice<Status>>.to_db_value(),
+            DbValue::from(None::<String>)
+        );
+    }
+}
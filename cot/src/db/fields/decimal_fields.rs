@@ -0,0 +1,83 @@
+use rust_decimal::Decimal;
+
+use crate::db::fields::{impl_from_postgres_default, impl_to_db_value_default};
+#[cfg(feature = "mysql")]
+use crate::db::impl_mysql::MySqlValueRef;
+#[cfg(feature = "sqlite")]
+use crate::db::impl_sqlite::SqliteValueRef;
+use crate::db::{ColumnType, DatabaseError, DatabaseField, FromDbValue, Result, SqlxValueRef};
+
+/// Parses a decimal stored as text in SQLite.
+///
+/// The values are stored normalized, but the rows written by other tools may
+/// contain trailing zeros, so the parsed value is normalized as well.
+#[cfg(feature = "sqlite")]
+fn decimal_from_str(value: &str) -> Result<Decimal> {
+    value
+        .parse::<Decimal>()
+        .map(|decimal| decimal.normalize())
+        .map_err(DatabaseError::value_decode)
+}
+
+impl DatabaseField for Decimal {
+    const TYPE: ColumnType = ColumnType::Decimal(None);
+}
+
+impl FromDbValue for Decimal {
+    // SQLite has no decimal type, so the value is stored as text to keep it
+    // exact
+    #[cfg(feature = "sqlite")]
+    fn from_sqlite(value: SqliteValueRef<'_>) -> Result<Self> {
+        decimal_from_str(&value.get::<String>()?)
+    }
+
+    impl_from_postgres_default!();
+
+    #[cfg(feature = "mysql")]
+    fn from_mysql(value: MySqlValueRef<'_>) -> Result<Self> {
+        value.get::<Self>()
+    }
+}
+
+impl FromDbValue for Option<Decimal> {
+    #[cfg(feature = "sqlite")]
+    fn from_sqlite(value: SqliteValueRef<'_>) -> Result<Self> {
+        value
+            .get::<Option<String>>()?
+            .map(|value| decimal_from_str(&value))
+            .transpose()
+    }
+
+    impl_from_postgres_default!();
+
+    #[cfg(feature = "mysql")]
+    fn from_mysql(value: MySqlValueRef<'_>) -> Result<Self> {
+        value.get::<Self>()
+    }
+}
+
+impl_to_db_value_default!(Decimal);
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+
+    use crate::db::{ColumnType, DatabaseField, DbValue, ToDbValue};
+
+    #[test]
+    fn decimal_column_type() {
+        assert_eq!(<Decimal as DatabaseField>::TYPE, ColumnType::Decimal(None));
+        const {
+            assert!(!<Decimal as DatabaseField>::NULLABLE);
+            assert!(<Option<Decimal> as DatabaseField>::NULLABLE);
+        }
+    }
+
+    #[test]
+    fn decimal_to_db_value() {
+        let value = Decimal::new(123_456, 2);
+
+        assert_eq!(value.to_db_value(), DbValue::Decimal(Some(value)));
+        assert_eq!(None::<Decimal>.to_db_value(), DbValue::Decimal(None));
+    }
+}
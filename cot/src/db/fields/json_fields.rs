@@ -0,0 +1,148 @@
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+#[cfg(feature = "mysql")]
+use crate::db::impl_mysql::MySqlValueRef;
+#[cfg(feature = "postgres")]
+use crate::db::impl_postgres::PostgresValueRef;
+#[cfg(feature = "sqlite")]
+use crate::db::impl_sqlite::SqliteValueRef;
+use crate::db::{
+    ColumnType, DatabaseError, DatabaseField, DbValue, FromDbValue, Json, Result, SqlxValueRef,
+    ToDbValue,
+};
+
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+fn json_from_value<T: DeserializeOwned>(value: serde_json::Value) -> Result<Json<T>> {
+    serde_json::from_value(value)
+        .map(Json)
+        .map_err(DatabaseError::value_decode)
+}
+
+#[cfg(feature = "sqlite")]
+fn json_from_str<T: DeserializeOwned>(value: &str) -> Result<Json<T>> {
+    serde_json::from_str(value)
+        .map(Json)
+        .map_err(DatabaseError::value_decode)
+}
+
+impl<T: Serialize + DeserializeOwned + Send + Sync> DatabaseField for Json<T> {
+    const TYPE: ColumnType = ColumnType::Json;
+}
+
+impl<T: DeserializeOwned> FromDbValue for Json<T> {
+    // SQLite stores JSON as plain text
+    #[cfg(feature = "sqlite")]
+    fn from_sqlite(value: SqliteValueRef<'_>) -> Result<Self> {
+        json_from_str(&value.get::<String>()?)
+    }
+
+    #[cfg(feature = "postgres")]
+    fn from_postgres(value: PostgresValueRef<'_>) -> Result<Self> {
+        json_from_value(value.get::<serde_json::Value>()?)
+    }
+
+    #[cfg(feature = "mysql")]
+    fn from_mysql(value: MySqlValueRef<'_>) -> Result<Self> {
+        json_from_value(value.get::<serde_json::Value>()?)
+    }
+}
+
+impl<T: DeserializeOwned> FromDbValue for Option<Json<T>> {
+    #[cfg(feature = "sqlite")]
+    fn from_sqlite(value: SqliteValueRef<'_>) -> Result<Self> {
+        value
+            .get::<Option<String>>()?
+            .map(|value| json_from_str(&value))
+            .transpose()
+    }
+
+    #[cfg(feature = "postgres")]
+    fn from_postgres(value: PostgresValueRef<'_>) -> Result<Self> {
+        value
+            .get::<Option<serde_json::Value>>()?
+            .map(json_from_value)
+            .transpose()
+    }
+
+    #[cfg(feature = "mysql")]
+    fn from_mysql(value: MySqlValueRef<'_>) -> Result<Self> {
+        value
+            .get::<Option<serde_json::Value>>()?
+            .map(json_from_value)
+            .transpose()
+    }
+}
+
+impl<T: Serialize + Send + Sync> ToDbValue for Json<T> {
+    /// Serializes the wrapped value to JSON.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value cannot be serialized to JSON, for instance because
+    /// it is a map with non-string keys.
+    fn to_db_value(&self) -> DbValue {
+        serde_json::to_value(&self.0)
+            .expect("failed to serialize the value of a Json field")
+            .into()
+    }
+}
+
+impl<T: Serialize + Send + Sync> ToDbValue for Option<Json<T>> {
+    fn to_db_value(&self) -> DbValue {
+        match self {
+            Some(json) => json.to_db_value(),
+            None => None::<serde_json::Value>.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use crate::db::{ColumnType, DatabaseField, DbValue, Json, ToDbValue};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Settings {
+        theme: String,
+        notifications: bool,
+    }
+
+    #[test]
+    fn json_column_type() {
+        assert_eq!(<Json<Settings> as DatabaseField>::TYPE, ColumnType::Json);
+        const {
+            assert!(!<Json<Settings> as DatabaseField>::NULLABLE);
+            assert!(<Option<Json<Settings>> as DatabaseField>::NULLABLE);
+        }
+    }
+
+    #[test]
+    fn json_to_db_value() {
+        let json = Json(Settings {
+            theme: "dark".to_owned(),
+            notifications: true,
+        });
+
+        assert_eq!(
+            json.to_db_value(),
+            DbValue::from(serde_json::json!({"theme": "dark", "notifications": true}))
+        );
+        assert_eq!(
+            None::<Json<Settings>>.to_db_value(),
+            DbValue::from(None::<serde_json::Value>)
+        );
+    }
+
+    #[test]
+    fn json_serde_transparent() {
+        let json = Json(vec![1, 2, 3]);
+
+        assert_eq!(serde_json::to_string(&json).unwrap(), "[1,2,3]");
+        assert_eq!(
+            serde_json::from_str::<Json<Vec<i32>>>("[1,2,3]").unwrap(),
+            json
+        );
+    }
+}
@@ -0,0 +1,426 @@
+//! Loading fixture data into the database.
+//!
+//! Fixtures are JSON files describing the rows to be inserted into the
+//! database, useful for populating the database for local development and
+//! tests. A fixture file contains a list of entries, each of them describing a
+//! single model instance:
+//!
+//! ```json
+//! [
+//!     {"model": "blog__author", "key": "alice", "fields": {"name": "Alice"}},
+//!     {
+//!         "model": "blog__post",
+//!         "fields": {"title": "Hello", "author": {"$ref": "alice"}}
+//!     }
+//! ]
+//! ```
+//!
+//! * `model` is the name of the model's table, as set by the
+//!   [`model`](crate::db::model) macro (the app name and the model name
+//!   converted to snake case, joined with `__`).
+//! * `fields` contains the values of the model's fields, deserialized with the
+//!   model's [`Deserialize`](serde::Deserialize) implementation. The
+//!   [`Auto`](crate::db::Auto) primary key can be omitted to let the database
+//!   generate it if it's marked with `#[serde(default)]`.
+//! * `key` is an optional symbolic name of the entry. The entries below can
+//!   refer to it with `{"$ref": "<key>"}`, which is replaced with the primary
+//!   key of the referenced row; this is typically used for
+//!   [`ForeignKey`](crate::db::ForeignKey) fields.
+//!
+//! Loading a fixture is idempotent: entries with an explicitly specified
+//! primary key are inserted or updated, and the other ones are only inserted
+//! if there is no row having the same values of the fields listed in the
+//! entry. Fields set to `null` are not compared.
+//!
+//! Each fixture is loaded in a single transaction, so if any of its entries
+//! can't be loaded, none of them are.
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::path::Path;
+
+use async_trait::async_trait;
+use cot_core::error::impl_into_cot_error;
+use derive_more::Debug;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+use crate::db::query::{Expr, Query};
+use crate::db::{Database, DatabaseError, DbFieldValue, Model, ModelPrimaryKey, ToDbFieldValue};
+
+const REFERENCE_KEY: &str = "$ref";
+
+/// An error that can occur when loading fixtures.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum FixtureError {
+    /// The fixture file could not be read.
+    #[error("could not read the fixture file: {0}")]
+    Io(#[from] std::io::Error),
+    /// The fixture is not valid JSON or doesn't match the fixture format.
+    #[error("invalid fixture: {0}")]
+    Parse(#[from] serde_json::Error),
+    /// The entry refers to a model that is not registered.
+    #[error("unknown fixture model: `{0}`")]
+    UnknownModel(String),
+    /// The entry refers to a key that is not defined by any entry above it.
+    #[error("unknown fixture reference: `{0}`")]
+    UnknownReference(String),
+    /// The same key is defined by more than one entry.
+    #[error("duplicate fixture key: `{0}`")]
+    DuplicateKey(String),
+    /// The fields of an entry could not be deserialized into the model.
+    #[error("invalid fields of a `{model}` fixture entry: {source}")]
+    InvalidFields {
+        /// The name of the model.
+        model: String,
+        /// The deserialization error.
+        source: serde_json::Error,
+    },
+    /// A database error occurred while loading the fixture.
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+}
+
+impl_into_cot_error!(FixtureError);
+
+/// A convenience alias for results returned by fixture operations.
+pub type FixtureResult<T> = Result<T, FixtureError>;
+
+/// A model that instances can be loaded from fixtures.
+///
+/// This is typically implemented by [`DefaultFixtureModel`], and returned by
+/// [`App::fixture_models`](crate::App::fixture_models) so that the fixtures
+/// can be loaded with the `db seed` command of the project's CLI.
+#[async_trait]
+pub trait FixtureModel: Send + Sync {
+    /// Returns the name of the model used in the fixture files.
+    fn name(&self) -> &str;
+
+    /// Loads a single model instance with the given fields into the database
+    /// and returns its primary key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the fields could not be deserialized into the model
+    /// or there was a problem with the database.
+    async fn load(
+        &self,
+        db: &Database,
+        fields: serde_json::Map<String, serde_json::Value>,
+    ) -> FixtureResult<serde_json::Value>;
+}
+
+/// A default implementation of [`FixtureModel`] for a [`Model`] implementing
+/// [`Deserialize`](serde::Deserialize).
+///
+/// # Examples
+///
+/// ```
+/// use cot::db::fixtures::DefaultFixtureModel;
+/// use cot::db::{Auto, model};
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, Deserialize)]
+/// #[model]
+/// struct Author {
+///     #[model(primary_key)]
+///     #[serde(default)]
+///     id: Auto<i32>,
+///     name: String,
+/// }
+///
+/// let fixture_model = DefaultFixtureModel::<Author>::new();
+/// ```
+#[derive(Debug)]
+pub struct DefaultFixtureModel<T> {
+    phantom_data: PhantomData<T>,
+}
+
+impl<T> Default for DefaultFixtureModel<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> DefaultFixtureModel<T> {
+    /// Creates a new instance of the default fixture model.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            phantom_data: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<T> FixtureModel for DefaultFixtureModel<T>
+where
+    T: Model + DeserializeOwned + Sync,
+    T::PrimaryKey: Serialize,
+{
+    fn name(&self) -> &str {
+        T::TABLE_NAME.as_str()
+    }
+
+    async fn load(
+        &self,
+        db: &Database,
+        fields: serde_json::Map<String, serde_json::Value>,
+    ) -> FixtureResult<serde_json::Value> {
+        let mut instance: T = serde_json::from_value(serde_json::Value::Object(fields.clone()))
+            .map_err(|source| FixtureError::InvalidFields {
+                model: self.name().to_owned(),
+                source,
+            })?;
+
+        if instance
+            .primary_key()
+            .to_db_field_values()
+            .iter()
+            .any(DbFieldValue::is_auto)
+        {
+            let existing = match lookup_filter(&instance, &fields) {
+                Some(filter) => Query::<T>::new().filter(filter).first(db).await?,
+                None => None,
+            };
+            if let Some(existing) = existing {
+                instance = existing;
+            } else {
+                db.insert(&mut instance).await?;
+            }
+        } else {
+            db.insert_or_update(&mut instance).await?;
+        }
+
+        Ok(serde_json::to_value(instance.primary_key())?)
+    }
+}
+
+/// Returns the filter matching the rows with the same values of the fields
+/// listed in the fixture entry as the given instance.
+fn lookup_filter<T: Model>(
+    instance: &T,
+    fields: &serde_json::Map<String, serde_json::Value>,
+) -> Option<Expr> {
+    let indices: Vec<_> = (0..T::COLUMNS.len()).collect();
+    let values = instance.get_values(&indices);
+
+    std::iter::zip(T::COLUMNS, values)
+        .filter(|(column, _)| fields.contains_key(column.name.as_str()))
+        .filter_map(|(column, value)| match value.to_db_field_value() {
+            DbFieldValue::Value(value) if value.is_some() => {
+                Some(Expr::eq(Expr::field(column.name), Expr::Value(value)))
+            }
+            _ => None,
+        })
+        .reduce(Expr::and)
+}
+
+/// A set of [`FixtureModel`]s that fixtures can be loaded for.
+///
+/// # Examples
+///
+/// ```
+/// use cot::db::fixtures::Fixtures;
+/// use cot::db::{Auto, Database, ForeignKey, model};
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, Deserialize)]
+/// #[model(table_name = "author")]
+/// struct Author {
+///     #[model(primary_key)]
+///     #[serde(default)]
+///     id: Auto<i32>,
+///     name: String,
+/// }
+///
+/// #[derive(Debug, Deserialize)]
+/// #[model(table_name = "post")]
+/// struct Post {
+///     #[model(primary_key)]
+///     #[serde(default)]
+///     id: Auto<i32>,
+///     title: String,
+///     author: ForeignKey<Author>,
+/// }
+///
+/// # async fn load(db: &Database) -> cot::Result<()> {
+/// // the table names are prefixed with the name of the app
+/// let mut fixtures = Fixtures::new();
+/// fixtures.register::<Author>().register::<Post>();
+///
+/// fixtures
+///     .load(
+///         db,
+///         r#"[
+///             {"model": "blog__author", "key": "alice", "fields": {"name": "Alice"}},
+///             {"model": "blog__post", "fields": {"title": "Hello", "author": {"$ref": "alice"}}}
+///         ]"#,
+///     )
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct Fixtures {
+    #[debug("..")]
+    models: HashMap<String, Box<dyn FixtureModel>>,
+}
+
+impl Fixtures {
+    /// Creates an empty set of fixture models.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a model using the [`DefaultFixtureModel`].
+    pub fn register<T>(&mut self) -> &mut Self
+    where
+        T: Model + DeserializeOwned + Sync,
+        T::PrimaryKey: Serialize,
+    {
+        self.add_model(Box::new(DefaultFixtureModel::<T>::new()))
+    }
+
+    /// Registers a fixture model.
+    pub fn add_model(&mut self, model: Box<dyn FixtureModel>) -> &mut Self {
+        self.models.insert(model.name().to_owned(), model);
+        self
+    }
+
+    /// Loads the fixture from the file at the given path.
+    ///
+    /// Returns the number of loaded entries.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file could not be read or the fixture could
+    /// not be loaded. See [`Self::load`] for details.
+    pub async fn load_file<P: AsRef<Path>>(&self, db: &Database, path: P) -> FixtureResult<usize> {
+        let data = tokio::fs::read_to_string(path).await?;
+        self.load(db, &data).await
+    }
+
+    /// Loads the fixture from the given JSON string.
+    ///
+    /// Returns the number of loaded entries. See the [module
+    /// documentation](self) for the format of the fixture.
+    ///
+    /// The entries are loaded in a transaction, which is rolled back if any
+    /// of them can't be loaded. If `db` is already bound to a transaction, the
+    /// entries are loaded in it instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the fixture is not valid, refers to a model that
+    /// is not registered or to an undefined key, or there was a problem with
+    /// the database.
+    pub async fn load(&self, db: &Database, data: &str) -> FixtureResult<usize> {
+        let entries: Vec<FixtureEntry> = serde_json::from_str(data)?;
+
+        if db.in_transaction() {
+            self.load_entries(db, &entries).await?;
+        } else {
+            let transaction = db.begin().await?;
+            self.load_entries(&transaction, &entries).await?;
+            transaction.commit().await?;
+        }
+
+        Ok(entries.len())
+    }
+
+    async fn load_entries(&self, db: &Database, entries: &[FixtureEntry]) -> FixtureResult<()> {
+        let mut keys = HashMap::new();
+
+        for entry in entries {
+            let model = self
+                .models
+                .get(&entry.model)
+                .ok_or_else(|| FixtureError::UnknownModel(entry.model.clone()))?;
+
+            let fields = entry
+                .fields
+                .iter()
+                .map(|(name, value)| Ok((name.clone(), resolve_reference(value, &keys)?)))
+                .collect::<FixtureResult<_>>()?;
+            let primary_key = model.load(db, fields).await?;
+
+            if let Some(key) = &entry.key
+                && keys.insert(key.clone(), primary_key).is_some()
+            {
+                return Err(FixtureError::DuplicateKey(key.clone()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FixtureEntry {
+    model: String,
+    #[serde(default)]
+    key: Option<String>,
+    #[serde(default)]
+    fields: serde_json::Map<String, serde_json::Value>,
+}
+
+fn resolve_reference(
+    value: &serde_json::Value,
+    keys: &HashMap<String, serde_json::Value>,
+) -> FixtureResult<serde_json::Value> {
+    if let Some(object) = value.as_object()
+        && object.len() == 1
+        && let Some(serde_json::Value::String(key)) = object.get(REFERENCE_KEY)
+    {
+        return keys
+            .get(key)
+            .cloned()
+            .ok_or_else(|| FixtureError::UnknownReference(key.clone()));
+    }
+
+    Ok(value.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn resolve_reference_replaces_ref() {
+        let keys = HashMap::from([("alice".to_owned(), json!(1))]);
+
+        assert_eq!(
+            resolve_reference(&json!({"$ref": "alice"}), &keys).unwrap(),
+            json!(1)
+        );
+        assert_eq!(
+            resolve_reference(&json!("text"), &keys).unwrap(),
+            json!("text")
+        );
+        assert_eq!(
+            resolve_reference(&json!({"$ref": "alice", "other": 1}), &keys).unwrap(),
+            json!({"$ref": "alice", "other": 1})
+        );
+    }
+
+    #[test]
+    fn resolve_reference_unknown() {
+        let error = resolve_reference(&json!({"$ref": "bob"}), &HashMap::new()).unwrap_err();
+
+        assert!(matches!(error, FixtureError::UnknownReference(key) if key == "bob"));
+    }
+
+    #[test]
+    fn fixture_entry_rejects_unknown_fields() {
+        let result = serde_json::from_str::<FixtureEntry>(r#"{"model": "a", "pk": 1}"#);
+
+        assert!(result.is_err());
+    }
+}
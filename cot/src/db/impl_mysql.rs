@@ -11,6 +11,12 @@ impl DatabaseMySql {
         Ok(())
     }
 
+    async fn begin_transaction(
+        pool: &sqlx::mysql::MySqlPool,
+    ) -> sqlx::Result<sqlx::Transaction<'static, sqlx::mysql::MySql>> {
+        pool.begin().await
+    }
+
     fn prepare_values(_values: &mut sea_query_sqlx::SqlxValues) {
         // No changes are needed for MySQL
     }
@@ -29,6 +35,10 @@ impl DatabaseMySql {
             ColumnType::DateTime | ColumnType::DateTimeWithTimeZone => {
                 return sea_query::ColumnType::custom("DATETIME(6)");
             }
+            ColumnType::Json => return sea_query::ColumnType::Json,
+            // MySQL defaults to `DECIMAL(10, 0)`, which would drop the fractional part; 28
+            // is the maximum scale of `rust_decimal::Decimal`
+            ColumnType::Decimal(None) => return sea_query::ColumnType::Decimal(Some((65, 28))),
             _ => {}
         }
 
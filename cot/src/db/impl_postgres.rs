@@ -10,6 +10,12 @@ impl DatabasePostgres {
         Ok(())
     }
 
+    async fn begin_transaction(
+        pool: &sqlx::postgres::PgPool,
+    ) -> sqlx::Result<sqlx::Transaction<'static, sqlx::postgres::Postgres>> {
+        pool.begin().await
+    }
+
     fn prepare_values(values: &mut sea_query_sqlx::SqlxValues) {
         for value in &mut values.0.0 {
             Self::tinyint_to_smallint(value);
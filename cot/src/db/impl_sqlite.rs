@@ -17,10 +17,60 @@ impl DatabaseSqlite {
             .await
     }
 
-    fn prepare_values(_values: &mut SqlxValues) {
-        // No changes are needed for SQLite
+    /// Waits until all the connections have been returned to the pool, so
+    /// that the next query reuses an existing connection.
+    #[cfg(test)]
+    pub(super) async fn wait_for_idle_connections(&self) {
+        while self.db_connection.num_idle() < self.db_connection.size() as usize {
+            tokio::task::yield_now().await;
+        }
     }
 
+    #[cfg(test)]
+    pub(super) async fn cached_statements_size(&self) -> crate::db::Result<usize> {
+        use sqlx::Connection;
+
+        self.wait_for_idle_connections().await;
+        let connection = self.db_connection.acquire().await?;
+        let size = connection.cached_statements_size();
+        drop(connection);
+        self.wait_for_idle_connections().await;
+
+        Ok(size)
+    }
+
+    /// Starts a transaction that takes the write lock right away. With a plain
+    /// `BEGIN`, two transactions that read and then write concurrently would
+    /// deadlock, and SQLite would fail one of them instead of waiting.
+    async fn begin_transaction(
+        pool: &sqlx::sqlite::SqlitePool,
+    ) -> sqlx::Result<sqlx::Transaction<'static, sqlx::sqlite::Sqlite>> {
+        pool.begin_with("BEGIN IMMEDIATE").await
+    }
+
+    fn prepare_values(values: &mut SqlxValues) {
+        for value in &mut values.0.0 {
+            Self::decimal_to_string(value);
+        }
+    }
+
+    /// SQLite has no decimal type, so decimals are stored as text to keep
+    /// them exact. `sea-query-sqlx` would otherwise bind them as `f64`.
+    ///
+    /// The decimals are normalized, so that the same number (such as `1.5`
+    /// and `1.50`) is always stored as the same string and can be compared
+    /// for equality.
+    #[cfg(feature = "decimal")]
+    fn decimal_to_string(value: &mut sea_query::Value) {
+        if let sea_query::Value::Decimal(decimal) = value {
+            *value =
+                sea_query::Value::String(decimal.map(|decimal| decimal.normalize().to_string()));
+        }
+    }
+
+    #[cfg(not(feature = "decimal"))]
+    fn decimal_to_string(_value: &mut sea_query::Value) {}
+
     #[expect(clippy::unnecessary_wraps)] // to have a unified interface between database impls
     fn last_inserted_row_id_for(result: &sqlx::sqlite::SqliteQueryResult) -> Option<u64> {
         #[expect(clippy::cast_sign_loss)]
@@ -32,6 +82,13 @@ impl DatabaseSqlite {
         &self,
         column_type: crate::db::ColumnType,
     ) -> sea_query::ColumnType {
-        sea_query::ColumnType::from(column_type)
+        match column_type {
+            // SQLite has no JSON type, and its `NUMERIC` type affinity would store decimals
+            // as floating-point numbers, losing precision
+            crate::db::ColumnType::Json | crate::db::ColumnType::Decimal(_) => {
+                sea_query::ColumnType::Text
+            }
+            _ => sea_query::ColumnType::from(column_type),
+        }
     }
 }
@@ -2,15 +2,17 @@
 
 mod sorter;
 
+use std::collections::HashMap;
 use std::fmt;
-use std::fmt::{Debug, Formatter};
+use std::fmt::{Debug, Formatter, Write};
 use std::future::Future;
 
 pub use cot_macros::migration_op;
-use sea_query::{ColumnDef, StringLen};
+use sea_query::{ColumnDef, ExprTrait, StringLen};
 use thiserror::Error;
-use tracing::{Level, info};
+use tracing::{Level, info, warn};
 
+use crate::config::MigrationDriftPolicy;
 use crate::db::migrations::sorter::{MigrationSorter, MigrationSorterError};
 use crate::db::relations::{ForeignKeyOnDeletePolicy, ForeignKeyOnUpdatePolicy};
 use crate::db::{Auto, ColumnType, Database, DatabaseField, Identifier, Result, model, query};
@@ -20,11 +22,33 @@ use crate::db::{Auto, ColumnType, Database, DatabaseField, Identifier, Result, m
 #[non_exhaustive]
 pub enum MigrationEngineError {
     /// An error occurred while determining the correct order of migrations.
-    #[error("error while determining the correct order of migrations")]
+    #[error("error while determining the correct order of migrations: {0}")]
     MigrationSortError(#[from] MigrationSorterError),
     /// A custom error occurred during a migration.
     #[error("error running migration: {0}")]
     Custom(String),
+    /// A migration has been modified after it was applied to the database.
+    #[error(
+        "migration {migration} of app {app} has been modified after it was applied \
+        (recorded checksum: {recorded}, current checksum: {current})"
+    )]
+    MigrationDrift {
+        /// The name of the app the migration belongs to.
+        app: String,
+        /// The name of the migration.
+        migration: String,
+        /// The checksum stored in the database when the migration was applied.
+        recorded: String,
+        /// The checksum of the current contents of the migration.
+        current: String,
+    },
+    /// There are migrations that have not been applied to the database yet.
+    #[error(
+        "{} migration(s) have not been applied to the database yet: {}",
+        .0.len(),
+        .0.join(", ")
+    )]
+    PendingMigrations(Vec<String>),
 }
 
 /// A migration engine responsible for managing and applying database
@@ -64,6 +88,7 @@ pub enum MigrationEngineError {
 #[derive(Debug)]
 pub struct MigrationEngine {
     migrations: Vec<MigrationWrapper>,
+    drift_policy: MigrationDriftPolicy,
 }
 
 impl MigrationEngine {
@@ -115,7 +140,33 @@ impl MigrationEngine {
 
     fn from_wrapper(mut migrations: Vec<MigrationWrapper>) -> Result<Self> {
         Self::sort_migrations(&mut migrations)?;
-        Ok(Self { migrations })
+        Ok(Self {
+            migrations,
+            drift_policy: MigrationDriftPolicy::default(),
+        })
+    }
+
+    /// Sets the action taken when a migration has been modified after it was
+    /// applied to the database.
+    ///
+    /// The default is [`MigrationDriftPolicy::Warn`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::config::MigrationDriftPolicy;
+    /// use cot::db::migrations::{MigrationEngine, SyncDynMigration};
+    ///
+    /// # fn main() -> cot::Result<()> {
+    /// let migrations: Vec<Box<SyncDynMigration>> = Vec::new();
+    /// let engine = MigrationEngine::new(migrations)?.with_drift_policy(MigrationDriftPolicy::Error);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_drift_policy(mut self, drift_policy: MigrationDriftPolicy) -> Self {
+        self.drift_policy = drift_policy;
+        self
     }
 
     /// Sorts the migrations by app name and migration name to ensure that the
@@ -135,13 +186,17 @@ impl MigrationEngine {
     ///
     /// This method will also create the `cot__migrations` table if it does
     /// not exist that is used to keep track of which migrations have been
-    /// applied.
+    /// applied. The table also stores a checksum of each applied migration;
+    /// if an applied migration has been modified since, this is handled
+    /// according to the [drift policy](Self::with_drift_policy).
     ///
     /// # Errors
     ///
     /// Returns an error if any of the migrations fail to apply, or if there is
     /// an error while interacting with the database, or if there is an
-    /// error while marking a migration as applied.
+    /// error while marking a migration as applied. With
+    /// [`MigrationDriftPolicy::Error`], also returns an error if an applied
+    /// migration has been modified.
     ///
     /// # Examples
     ///
@@ -177,9 +232,7 @@ impl MigrationEngine {
     pub async fn run(&self, database: &Database) -> Result<()> {
         info!("Running migrations");
 
-        CREATE_APPLIED_MIGRATIONS_MIGRATION
-            .forwards(database)
-            .await?;
+        Self::prepare_applied_migrations_table(database).await?;
 
         for migration in &self.migrations {
             let span = tracing::span!(
@@ -190,7 +243,12 @@ impl MigrationEngine {
             );
             let _enter = span.enter();
 
-            if Self::is_migration_applied(database, migration).await? {
+            let checksum = migration_checksum(migration.operations());
+            if let Some(applied_migration) =
+                Self::get_applied_migration(database, migration).await?
+            {
+                self.verify_checksum(database, migration, applied_migration, &checksum)
+                    .await?;
                 info!(
                     "Migration {} for app {} is already applied",
                     migration.name(),
@@ -209,33 +267,298 @@ impl MigrationEngine {
                 operation.forwards(database).await?;
             }
 
-            Self::mark_migration_applied(database, migration).await?;
+            Self::mark_migration_applied(database, migration, checksum).await?;
         }
 
         Ok(())
     }
 
-    async fn is_migration_applied(
+    /// Returns the migrations that have not been applied to the database yet,
+    /// in the order they would be applied.
+    ///
+    /// Unlike [`Self::run`], this only reads from the database: if the
+    /// `cot__migrations` table does not exist, all the migrations are
+    /// considered pending. The checksums of the applied migrations are
+    /// verified, except for the ones applied before checksums were recorded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is an error while interacting with the
+    /// database. With [`MigrationDriftPolicy::Error`], also returns an error
+    /// if an applied migration has been modified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::Database;
+    /// use cot::db::migrations::{MigrationEngine, SyncDynMigration};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> cot::Result<()> {
+    /// let migrations: Vec<Box<SyncDynMigration>> = Vec::new();
+    /// let engine = MigrationEngine::new(migrations)?;
+    /// let database = Database::new("sqlite::memory:").await?;
+    /// for migration in engine.pending_migrations(&database).await? {
+    ///     println!("{}::{}", migration.app_name(), migration.name());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn pending_migrations(&self, database: &Database) -> Result<Vec<&dyn DynMigration>> {
+        let applied = Self::read_applied_migrations(database).await?;
+
+        let mut pending: Vec<&dyn DynMigration> = Vec::new();
+        for migration in &self.migrations {
+            let key = (migration.app_name().to_owned(), migration.name().to_owned());
+            match applied.get(&key) {
+                Some(Some(recorded)) => {
+                    let checksum = migration_checksum(migration.operations());
+                    self.check_drift(migration, recorded, &checksum)?;
+                }
+                Some(None) => {}
+                None => pending.push(migration),
+            }
+        }
+
+        Ok(pending)
+    }
+
+    /// Returns the plans of the migrations that have not been applied to the
+    /// database yet, in the order they would be applied, including the SQL
+    /// statements each of them would execute.
+    ///
+    /// Like [`Self::pending_migrations`], this only reads from the database,
+    /// but the checksums of the applied migrations are not verified.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is an error while interacting with the
+    /// database, or if any of the pending operations is not supported by the
+    /// database backend.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::Database;
+    /// use cot::db::migrations::{MigrationEngine, SyncDynMigration};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> cot::Result<()> {
+    /// let migrations: Vec<Box<SyncDynMigration>> = Vec::new();
+    /// let engine = MigrationEngine::new(migrations)?;
+    /// let database = Database::new("sqlite::memory:").await?;
+    /// for plan in engine.plan(&database).await? {
+    ///     println!("{plan}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn plan(&self, database: &Database) -> Result<Vec<MigrationPlan>> {
+        let applied = Self::read_applied_migrations(database).await?;
+
+        let mut plans = Vec::new();
+        for migration in &self.migrations {
+            let key = (migration.app_name().to_owned(), migration.name().to_owned());
+            if applied.contains_key(&key) {
+                continue;
+            }
+
+            let operations = migration
+                .operations()
+                .iter()
+                .map(|operation| operation.forwards_plan(database))
+                .collect::<Result<_>>()?;
+            plans.push(MigrationPlan {
+                app_name: key.0,
+                name: key.1,
+                operations,
+            });
+        }
+
+        Ok(plans)
+    }
+
+    /// Verifies that all the migrations have been applied to the database,
+    /// without applying any of them.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MigrationEngineError::PendingMigrations`] if there are
+    /// migrations that have not been applied yet, or any error returned by
+    /// [`Self::pending_migrations`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::Database;
+    /// use cot::db::migrations::{MigrationEngine, SyncDynMigration};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> cot::Result<()> {
+    /// let migrations: Vec<Box<SyncDynMigration>> = Vec::new();
+    /// let engine = MigrationEngine::new(migrations)?;
+    /// let database = Database::new("sqlite::memory:").await?;
+    /// engine.check(&database).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn check(&self, database: &Database) -> Result<()> {
+        let pending = self.pending_migrations(database).await?;
+        if pending.is_empty() {
+            Ok(())
+        } else {
+            let names = pending
+                .iter()
+                .map(|migration| format!("{}::{}", migration.app_name(), migration.name()))
+                .collect();
+            Err(MigrationEngineError::PendingMigrations(names).into())
+        }
+    }
+
+    /// Creates the `cot__migrations` table if it does not exist.
+    async fn prepare_applied_migrations_table(database: &Database) -> Result<()> {
+        CREATE_APPLIED_MIGRATIONS_MIGRATION
+            .forwards(database)
+            .await?;
+        Self::ensure_checksum_column(database).await
+    }
+
+    /// Adds the `checksum` column to a `cot__migrations` table created by a
+    /// version of Cot that didn't store migration checksums.
+    async fn ensure_checksum_column(database: &Database) -> Result<()> {
+        let statement = sea_query::Query::select()
+            .column(APPLIED_MIGRATION_CHECKSUM_COLUMN)
+            .from(APPLIED_MIGRATIONS_TABLE)
+            .limit(1)
+            .to_owned();
+
+        match database.fetch_option(&statement).await {
+            Ok(_) => Ok(()),
+            // the table has just been created if it didn't exist, so only the
+            // column can be missing here
+            Err(error) if is_undefined_table_or_column(&error) => {
+                info!("Adding the checksum column to the applied migrations table");
+                ADD_APPLIED_MIGRATION_CHECKSUM_MIGRATION
+                    .forwards(database)
+                    .await
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Returns the recorded checksums of the applied migrations, keyed by
+    /// their app and migration names, without modifying the database.
+    ///
+    /// If the `cot__migrations` table does not exist, no migrations are
+    /// returned. If it was created by a version of Cot that didn't store
+    /// migration checksums, all the checksums are `None`.
+    async fn read_applied_migrations(database: &Database) -> Result<AppliedMigrationChecksums> {
+        let statement = sea_query::Query::select()
+            .columns([
+                APPLIED_MIGRATION_APP_COLUMN,
+                APPLIED_MIGRATION_NAME_COLUMN,
+                APPLIED_MIGRATION_CHECKSUM_COLUMN,
+            ])
+            .from(APPLIED_MIGRATIONS_TABLE)
+            .to_owned();
+        match database.fetch_all(&statement).await {
+            Ok(rows) => {
+                return rows
+                    .iter()
+                    .map(|row| {
+                        Ok((
+                            (row.get::<String>(0)?, row.get::<String>(1)?),
+                            row.get::<Option<String>>(2)?,
+                        ))
+                    })
+                    .collect();
+            }
+            Err(error) if is_undefined_table_or_column(&error) => {}
+            Err(error) => return Err(error),
+        }
+
+        // either there is no checksum column, or no table at all
+        let statement = sea_query::Query::select()
+            .columns([APPLIED_MIGRATION_APP_COLUMN, APPLIED_MIGRATION_NAME_COLUMN])
+            .from(APPLIED_MIGRATIONS_TABLE)
+            .to_owned();
+        match database.fetch_all(&statement).await {
+            Ok(rows) => rows
+                .iter()
+                .map(|row| Ok(((row.get::<String>(0)?, row.get::<String>(1)?), None)))
+                .collect(),
+            Err(error) if is_undefined_table_or_column(&error) => Ok(HashMap::new()),
+            Err(error) => Err(error),
+        }
+    }
+
+    async fn get_applied_migration(
         database: &Database,
         migration: &MigrationWrapper,
-    ) -> Result<bool> {
+    ) -> Result<Option<AppliedMigration>> {
         query!(
             AppliedMigration,
             $app == migration.app_name() && $name == migration.name()
         )
-        .exists(database)
+        .first(database)
         .await
     }
 
+    async fn verify_checksum(
+        &self,
+        database: &Database,
+        migration: &MigrationWrapper,
+        mut applied_migration: AppliedMigration,
+        checksum: &str,
+    ) -> Result<()> {
+        match &applied_migration.checksum {
+            None => {
+                // the migration was applied before checksums were recorded, so the
+                // best we can do is to trust its current contents
+                applied_migration.checksum = Some(checksum.to_owned());
+                database.update(&mut applied_migration).await?;
+            }
+            Some(recorded) => self.check_drift(migration, recorded, checksum)?,
+        }
+
+        Ok(())
+    }
+
+    /// Handles a migration that has been modified since it was applied
+    /// according to the drift policy.
+    fn check_drift(
+        &self,
+        migration: &MigrationWrapper,
+        recorded: &str,
+        current: &str,
+    ) -> Result<()> {
+        if recorded != current {
+            let error = MigrationEngineError::MigrationDrift {
+                app: migration.app_name().to_owned(),
+                migration: migration.name().to_owned(),
+                recorded: recorded.to_owned(),
+                current: current.to_owned(),
+            };
+            match self.drift_policy {
+                MigrationDriftPolicy::Warn => warn!("{error}"),
+                MigrationDriftPolicy::Error => return Err(error.into()),
+            }
+        }
+
+        Ok(())
+    }
+
     async fn mark_migration_applied(
         database: &Database,
         migration: &MigrationWrapper,
+        checksum: String,
     ) -> Result<()> {
         let mut applied_migration = AppliedMigration {
             id: Auto::auto(),
             app: migration.app_name().to_string(),
             name: migration.name().to_string(),
             applied: chrono::Utc::now().into(),
+            checksum: Some(checksum),
         };
 
         database.insert(&mut applied_migration).await?;
@@ -430,6 +753,32 @@ impl Operation {
         RemoveModelBuilder::new()
     }
 
+    /// Returns a builder for an operation that changes the values allowed in
+    /// a field by its `CHECK` constraint (see [`Field::choices`]).
+    ///
+    /// This operation is only supported on PostgreSQL.
+    ///
+    /// Typically, you shouldn't need to use this directly. Instead, in most
+    /// cases, this can be automatically generated by the Cot CLI.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::Identifier;
+    /// use cot::db::migrations::Operation;
+    ///
+    /// const OPERATION: Operation = Operation::alter_field_choices()
+    ///     .table_name(Identifier::new("blog__post"))
+    ///     .field_name(Identifier::new("status"))
+    ///     .old_choices(&["draft", "published"])
+    ///     .new_choices(&["draft", "published", "archived"])
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub const fn alter_field_choices() -> AlterFieldChoicesBuilder {
+        AlterFieldChoicesBuilder::new()
+    }
+
     /// Returns a builder for a custom operation.
     ///
     /// # Examples
@@ -487,21 +836,7 @@ impl Operation {
                 fields,
                 if_not_exists,
             } => {
-                let mut query = sea_query::Table::create().table(*table_name).to_owned();
-                for field in *fields {
-                    query.col(field.as_column_def(database));
-                    if let Some(foreign_key) = field.foreign_key {
-                        query.foreign_key(
-                            sea_query::ForeignKeyCreateStatement::new()
-                                .from_tbl(*table_name)
-                                .from_col(field.name)
-                                .to_tbl(foreign_key.model)
-                                .to_col(foreign_key.field)
-                                .on_delete(foreign_key.on_delete.into())
-                                .on_update(foreign_key.on_update.into()),
-                        );
-                    }
-                }
+                let mut query = create_table_statement(*table_name, fields, database);
                 if *if_not_exists {
                     query.if_not_exists();
                 }
@@ -528,6 +863,21 @@ impl Operation {
                 let query = sea_query::Table::drop().table(*table_name).to_owned();
                 database.execute_schema(query).await?;
             }
+            OperationInner::AlterFieldChoices {
+                table_name,
+                field_name,
+                old_choices,
+                new_choices,
+            } => {
+                alter_choices_constraint(
+                    database,
+                    *table_name,
+                    *field_name,
+                    *old_choices,
+                    *new_choices,
+                )
+                .await?;
+            }
             OperationInner::Custom {
                 forwards,
                 backwards: _,
@@ -539,6 +889,97 @@ impl Operation {
         Ok(())
     }
 
+    /// Returns the SQL statements this operation executes when run
+    /// [forwards](Self::forwards) on the given database, without executing
+    /// them.
+    ///
+    /// The statements are generated for the database backend in use. Custom
+    /// operations run arbitrary Rust code, so the statements they execute
+    /// can't be determined in advance; [`OperationPlan::Custom`] is returned
+    /// for them instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the operation is not supported by the database
+    /// backend.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::migrations::{Field, Operation, OperationPlan};
+    /// use cot::db::{Database, DatabaseField, Identifier};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> cot::Result<()> {
+    /// const OPERATION: Operation = Operation::add_field()
+    ///     .table_name(Identifier::new("todoapp__my_model"))
+    ///     .field(Field::new(
+    ///         Identifier::new("title"),
+    ///         <String as DatabaseField>::TYPE,
+    ///     ))
+    ///     .build();
+    ///
+    /// let database = Database::new("sqlite::memory:").await?;
+    /// let OperationPlan::Sql(statements) = OPERATION.forwards_plan(&database)? else {
+    ///     unreachable!();
+    /// };
+    /// assert_eq!(statements.len(), 1);
+    /// assert!(statements[0].starts_with(r#"ALTER TABLE "todoapp__my_model" ADD COLUMN"#));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn forwards_plan(&self, database: &Database) -> Result<OperationPlan> {
+        let statements = match &self.inner {
+            OperationInner::CreateModel {
+                table_name,
+                fields,
+                if_not_exists,
+            } => {
+                let mut query = create_table_statement(*table_name, fields, database);
+                if *if_not_exists {
+                    query.if_not_exists();
+                }
+                vec![database.build_schema(query)]
+            }
+            OperationInner::AddField { table_name, field } => {
+                let query = sea_query::Table::alter()
+                    .table(*table_name)
+                    .add_column(field.as_column_def(database))
+                    .to_owned();
+                vec![database.build_schema(query)]
+            }
+            OperationInner::RemoveField { table_name, field } => {
+                let query = sea_query::Table::alter()
+                    .table(*table_name)
+                    .drop_column(field.name)
+                    .to_owned();
+                vec![database.build_schema(query)]
+            }
+            OperationInner::RemoveModel {
+                table_name,
+                fields: _,
+            } => {
+                let query = sea_query::Table::drop().table(*table_name).to_owned();
+                vec![database.build_schema(query)]
+            }
+            OperationInner::AlterFieldChoices {
+                table_name,
+                field_name,
+                old_choices,
+                new_choices,
+            } => alter_choices_constraint_sql(
+                database,
+                *table_name,
+                *field_name,
+                *old_choices,
+                *new_choices,
+            )?,
+            OperationInner::Custom { .. } => return Ok(OperationPlan::Custom),
+        };
+
+        Ok(OperationPlan::Sql(statements))
+    }
+
     /// Runs the operation backwards, undoing the changes made by the forwards
     /// operation.
     ///
@@ -595,23 +1036,24 @@ impl Operation {
                 database.execute_schema(query).await?;
             }
             OperationInner::RemoveModel { table_name, fields } => {
-                let mut query = sea_query::Table::create().table(*table_name).to_owned();
-                for field in *fields {
-                    query.col(field.as_column_def(database));
-                    if let Some(foreign_key) = field.foreign_key {
-                        query.foreign_key(
-                            sea_query::ForeignKeyCreateStatement::new()
-                                .from_tbl(*table_name)
-                                .from_col(field.name)
-                                .to_tbl(foreign_key.model)
-                                .to_col(foreign_key.field)
-                                .on_delete(foreign_key.on_delete.into())
-                                .on_update(foreign_key.on_update.into()),
-                        );
-                    }
-                }
+                let query = create_table_statement(*table_name, fields, database);
                 database.execute_schema(query).await?;
             }
+            OperationInner::AlterFieldChoices {
+                table_name,
+                field_name,
+                old_choices,
+                new_choices,
+            } => {
+                alter_choices_constraint(
+                    database,
+                    *table_name,
+                    *field_name,
+                    *new_choices,
+                    *old_choices,
+                )
+                .await?;
+            }
             OperationInner::Custom {
                 forwards: _,
                 backwards,
@@ -628,6 +1070,132 @@ impl Operation {
         }
         Ok(())
     }
+
+    /// Writes a textual description of the operation used to calculate the
+    /// migration checksum.
+    fn write_description(&self, out: &mut String) -> fmt::Result {
+        match &self.inner {
+            OperationInner::CreateModel {
+                table_name,
+                fields,
+                if_not_exists,
+            } => {
+                writeln!(
+                    out,
+                    "create_model {} if_not_exists={if_not_exists}",
+                    table_name.as_str()
+                )?;
+                for field in *fields {
+                    field.write_description(out)?;
+                }
+            }
+            OperationInner::AddField { table_name, field } => {
+                writeln!(out, "add_field {}", table_name.as_str())?;
+                field.write_description(out)?;
+            }
+            OperationInner::RemoveField { table_name, field } => {
+                writeln!(out, "remove_field {}", table_name.as_str())?;
+                field.write_description(out)?;
+            }
+            OperationInner::RemoveModel { table_name, fields } => {
+                writeln!(out, "remove_model {}", table_name.as_str())?;
+                for field in *fields {
+                    field.write_description(out)?;
+                }
+            }
+            OperationInner::AlterFieldChoices {
+                table_name,
+                field_name,
+                old_choices,
+                new_choices,
+            } => {
+                write!(
+                    out,
+                    "alter_field_choices {} {} old=",
+                    table_name.as_str(),
+                    field_name.as_str(),
+                )?;
+                write_choices_description(out, *old_choices)?;
+                write!(out, " new=")?;
+                write_choices_description(out, *new_choices)?;
+                writeln!(out)?;
+            }
+            OperationInner::Custom {
+                forwards: _,
+                backwards,
+            } => {
+                writeln!(out, "custom backwards={}", backwards.is_some())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// What an [`Operation`] does when it's run, as returned by
+/// [`Operation::forwards_plan`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OperationPlan {
+    /// The operation executes the given SQL statements.
+    Sql(Vec<String>),
+    /// The operation runs custom Rust code, so the statements it executes
+    /// can't be determined without running it.
+    Custom,
+}
+
+/// A migration that hasn't been applied to the database yet, along with the
+/// plans of its operations, as returned by [`MigrationEngine::plan`].
+///
+/// The [`Display`](fmt::Display) implementation formats the plan as an SQL
+/// script, with the migration name and the custom operations written as SQL
+/// comments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationPlan {
+    app_name: String,
+    name: String,
+    operations: Vec<OperationPlan>,
+}
+
+impl MigrationPlan {
+    /// Returns the name of the app the migration belongs to.
+    #[must_use]
+    pub fn app_name(&self) -> &str {
+        &self.app_name
+    }
+
+    /// Returns the name of the migration.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the plans of the migration's operations, in the order they
+    /// would be run.
+    #[must_use]
+    pub fn operations(&self) -> &[OperationPlan] {
+        &self.operations
+    }
+}
+
+impl fmt::Display for MigrationPlan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "-- Migration {}::{}", self.app_name, self.name)?;
+        for operation in &self.operations {
+            match operation {
+                OperationPlan::Sql(statements) => {
+                    for statement in statements {
+                        writeln!(f, "{statement};")?;
+                    }
+                }
+                OperationPlan::Custom => {
+                    writeln!(f, "-- Custom operation (runs Rust code)")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// A context for a custom migration operation.
@@ -679,12 +1247,129 @@ enum OperationInner {
         table_name: Identifier,
         fields: &'static [Field],
     },
+    /// Change the values allowed in a field by its `CHECK` constraint.
+    AlterFieldChoices {
+        table_name: Identifier,
+        field_name: Identifier,
+        old_choices: Option<&'static [&'static str]>,
+        new_choices: Option<&'static [&'static str]>,
+    },
     Custom {
         forwards: CustomOperationFn,
         backwards: Option<CustomOperationFn>,
     },
 }
 
+/// Returns the statement creating the table with the given fields.
+///
+/// If multiple fields are marked as primary keys, a composite `PRIMARY KEY`
+/// constraint containing all of them is added to the table.
+fn create_table_statement<T: ColumnTypeMapper>(
+    table_name: Identifier,
+    fields: &[Field],
+    mapper: &T,
+) -> sea_query::TableCreateStatement {
+    let composite_primary_key = fields.iter().filter(|field| field.primary_key).count() > 1;
+
+    let mut query = sea_query::Table::create().table(table_name).to_owned();
+    let mut primary_key = sea_query::Index::create();
+    for field in fields {
+        if composite_primary_key && field.primary_key {
+            primary_key.col(field.name);
+            let column = Field {
+                primary_key: false,
+                ..*field
+            };
+            query.col(column.as_column_def(mapper));
+        } else {
+            query.col(field.as_column_def(mapper));
+        }
+        if let Some(foreign_key) = field.foreign_key {
+            query.foreign_key(
+                sea_query::ForeignKeyCreateStatement::new()
+                    .from_tbl(table_name)
+                    .from_col(field.name)
+                    .to_tbl(foreign_key.model)
+                    .to_col(foreign_key.field)
+                    .on_delete(foreign_key.on_delete.into())
+                    .on_update(foreign_key.on_update.into()),
+            );
+        }
+    }
+    if composite_primary_key {
+        query.primary_key(&mut primary_key);
+    }
+    query
+}
+
+/// Replaces the `CHECK` constraint restricting the values of a column,
+/// removing it if `old_choices` is set, and adding a new one if `new_choices`
+/// is set.
+///
+/// The constraint uses the name PostgreSQL gives to the constraints defined
+/// inline in the column definition, so that the constraints created along with
+/// the column can be altered too.
+async fn alter_choices_constraint(
+    database: &Database,
+    table_name: Identifier,
+    field_name: Identifier,
+    old_choices: Option<&'static [&'static str]>,
+    new_choices: Option<&'static [&'static str]>,
+) -> Result<()> {
+    let statements =
+        alter_choices_constraint_sql(database, table_name, field_name, old_choices, new_choices)?;
+    for statement in statements {
+        database.raw(&statement).await?;
+    }
+
+    Ok(())
+}
+
+/// Returns the SQL statements executed by [`alter_choices_constraint`].
+fn alter_choices_constraint_sql(
+    database: &Database,
+    table_name: Identifier,
+    field_name: Identifier,
+    old_choices: Option<&'static [&'static str]>,
+    new_choices: Option<&'static [&'static str]>,
+) -> Result<Vec<String>> {
+    if !database.supports_altering_check_constraints() {
+        return Err(crate::db::DatabaseError::NotSupported {
+            feature: "altering the choices of an existing field",
+        });
+    }
+
+    let table = quote_identifier(table_name.as_str());
+    let constraint = quote_identifier(&format!(
+        "{}_{}_check",
+        table_name.as_str(),
+        field_name.as_str()
+    ));
+    let mut statements = Vec::new();
+    if old_choices.is_some() {
+        statements.push(format!(
+            "ALTER TABLE {table} DROP CONSTRAINT IF EXISTS {constraint}"
+        ));
+    }
+    if let Some(choices) = new_choices {
+        let values = choices
+            .iter()
+            .map(|value| format!("'{}'", value.replace('\'', "''")))
+            .collect::<Vec<_>>()
+            .join(", ");
+        statements.push(format!(
+            "ALTER TABLE {table} ADD CONSTRAINT {constraint} CHECK ({} IN ({values}))",
+            quote_identifier(field_name.as_str()),
+        ));
+    }
+
+    Ok(statements)
+}
+
+fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
 /// A field in a model.
 #[expect(clippy::struct_excessive_bools)]
 #[derive(Debug, Copy, Clone)]
@@ -702,6 +1387,10 @@ pub struct Field {
     pub null: bool,
     /// Whether the column has a unique constraint
     pub unique: bool,
+    /// The default value of the column in the database
+    pub default_value: Option<DefaultValue>,
+    /// The values allowed in the column by a `CHECK` constraint
+    pub choices: Option<&'static [&'static str]>,
     foreign_key: Option<ForeignKeyReference>,
 }
 
@@ -730,6 +1419,8 @@ impl Field {
             auto_value: false,
             null: false,
             unique: false,
+            default_value: None,
+            choices: None,
             foreign_key: None,
         }
     }
@@ -894,6 +1585,141 @@ impl Field {
         self
     }
 
+    /// Sets the default value of the column in the database.
+    ///
+    /// # Cot CLI Usage
+    ///
+    /// Typically, you shouldn't need to use this directly. Instead, in most
+    /// cases, this can be automatically generated by the Cot CLI when you mark
+    /// your field with a `#[model(default = ...)]` attribute with a constant
+    /// value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::migrations::{DefaultValue, Field};
+    /// use cot::db::{DatabaseField, Identifier};
+    ///
+    /// let field = Field::new(Identifier::new("views"), <i64 as DatabaseField>::TYPE)
+    ///     .default_value(DefaultValue::Int(0));
+    /// ```
+    #[must_use]
+    pub const fn default_value(mut self, value: DefaultValue) -> Self {
+        self.default_value = Some(value);
+        self
+    }
+
+    /// Restricts the values that can be stored in the column with a `CHECK`
+    /// constraint.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `values` is empty.
+    ///
+    /// # Cot CLI Usage
+    ///
+    /// Typically, you shouldn't need to use this directly. Instead, in most
+    /// cases, this can be automatically generated by the Cot CLI when you mark
+    /// a [`Choice`](crate::db::Choice) field with a `#[model(choices)]`
+    /// attribute.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::migrations::Field;
+    /// use cot::db::{DatabaseField, Identifier};
+    ///
+    /// let field = Field::new(Identifier::new("status"), <String as DatabaseField>::TYPE)
+    ///     .choices(&["draft", "published"]);
+    /// ```
+    #[must_use]
+    pub const fn choices(mut self, values: &'static [&'static str]) -> Self {
+        assert!(!values.is_empty(), "the list of choices must not be empty");
+
+        self.choices = Some(values);
+        self
+    }
+
+    /// Sets the precision (the total number of digits) and the scale (the
+    /// number of digits after the decimal point) of a decimal column.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the field is not a decimal column, if
+    /// `precision` is zero, or if `scale` is greater than `precision`.
+    ///
+    /// # Cot CLI Usage
+    ///
+    /// Typically, you shouldn't need to use this directly. Instead, in most
+    /// cases, this can be automatically generated by the Cot CLI when you mark
+    /// a [`Decimal`](crate::db::Decimal) field with a
+    /// `#[model(decimal(precision = ..., scale = ...))]` attribute.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::migrations::Field;
+    /// use cot::db::{DatabaseField, Decimal, Identifier};
+    ///
+    /// let field = Field::new(Identifier::new("price"), <Decimal as DatabaseField>::TYPE)
+    ///     .decimal_precision(10, 2);
+    /// ```
+    #[cfg(feature = "decimal")]
+    #[must_use]
+    pub const fn decimal_precision(mut self, precision: u32, scale: u32) -> Self {
+        assert!(
+            matches!(self.ty, ColumnType::Decimal(_)),
+            "precision can only be set on decimal fields"
+        );
+        assert!(precision > 0, "the precision must be greater than zero");
+        assert!(
+            scale <= precision,
+            "the scale must not be greater than the precision"
+        );
+
+        self.ty = ColumnType::Decimal(Some((precision, scale)));
+        self
+    }
+
+    fn write_description(&self, out: &mut String) -> fmt::Result {
+        write!(out, "  field {} ", self.name.as_str())?;
+        write_column_type_description(out, self.ty)?;
+        write!(
+            out,
+            " primary_key={} auto={} null={} unique={} default=",
+            self.primary_key, self.auto_value, self.null, self.unique,
+        )?;
+        match self.default_value {
+            Some(default_value) => default_value.write_description(out)?,
+            None => write!(out, "none")?,
+        }
+        if let Some(choices) = self.choices {
+            write!(out, " choices=")?;
+            write_choices_description(out, Some(choices))?;
+        }
+        if let Some(foreign_key) = &self.foreign_key {
+            let on_delete = match foreign_key.on_delete {
+                ForeignKeyOnDeletePolicy::NoAction => "no_action",
+                ForeignKeyOnDeletePolicy::Restrict => "restrict",
+                ForeignKeyOnDeletePolicy::Cascade => "cascade",
+                ForeignKeyOnDeletePolicy::SetNone => "set_none",
+            };
+            let on_update = match foreign_key.on_update {
+                ForeignKeyOnUpdatePolicy::NoAction => "no_action",
+                ForeignKeyOnUpdatePolicy::Restrict => "restrict",
+                ForeignKeyOnUpdatePolicy::Cascade => "cascade",
+                ForeignKeyOnUpdatePolicy::SetNone => "set_none",
+            };
+            write!(
+                out,
+                " foreign_key={}.{} on_delete={on_delete} on_update={on_update}",
+                foreign_key.model.as_str(),
+                foreign_key.field.as_str(),
+            )?;
+        }
+        writeln!(out)
+    }
+
     fn as_column_def<T: ColumnTypeMapper>(&self, mapper: &T) -> ColumnDef {
         let mut def =
             ColumnDef::new_with_type(self.name, mapper.sea_query_column_type_for(self.ty));
@@ -911,10 +1737,60 @@ impl Field {
         if self.unique {
             def.unique_key();
         }
+        if let Some(default_value) = self.default_value {
+            def.default(default_value.as_sea_query_value());
+        }
+        if let Some(choices) = self.choices {
+            def.check(sea_query::Expr::col(self.name).is_in(choices.iter().copied()));
+        }
         def
     }
 }
 
+/// A constant default value of a column in the database, emitted as the
+/// `DEFAULT` clause of the column definition.
+///
+/// # Cot CLI Usage
+///
+/// Typically, you shouldn't need to use this directly. Instead, in most
+/// cases, this can be automatically generated by the Cot CLI when you mark
+/// your field with a `#[model(default = ...)]` attribute with a constant
+/// value.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum DefaultValue {
+    /// A boolean value.
+    Bool(bool),
+    /// An integer value.
+    Int(i64),
+    /// A floating-point value.
+    Float(f64),
+    /// A string value.
+    String(&'static str),
+}
+
+impl DefaultValue {
+    fn as_sea_query_value(self) -> sea_query::Value {
+        match self {
+            Self::Bool(value) => value.into(),
+            Self::Int(value) => value.into(),
+            Self::Float(value) => value.into(),
+            Self::String(value) => value.into(),
+        }
+    }
+
+    /// Writes a textual description of the value used to calculate the
+    /// migration checksum.
+    fn write_description(self, out: &mut String) -> fmt::Result {
+        match self {
+            Self::Bool(value) => write!(out, "bool:{value}"),
+            Self::Int(value) => write!(out, "int:{value}"),
+            Self::Float(value) => write!(out, "float:{value}"),
+            Self::String(value) => write!(out, "string:{}:{value}", value.len()),
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 struct ForeignKeyReference {
     model: Identifier,
@@ -1547,54 +2423,165 @@ impl RemoveModelBuilder {
     /// Typically, you shouldn't need to use this directly. Instead, in most
     /// cases, this can be automatically generated by the Cot CLI.
     ///
-    /// # Examples
-    ///
-    /// ```
-    /// use cot::db::migrations::{Field, Operation};
-    /// use cot::db::{DatabaseField, Identifier};
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::migrations::{Field, Operation};
+    /// use cot::db::{DatabaseField, Identifier};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> cot::Result<()> {
+    /// const FIELDS: &[Field] = &[
+    ///     Field::new(Identifier::new("id"), <i32 as DatabaseField>::TYPE)
+    ///         .primary_key()
+    ///         .auto(),
+    ///     Field::new(Identifier::new("name"), <String as DatabaseField>::TYPE),
+    /// ];
+    ///
+    /// // First create the table
+    /// const CREATE_OPERATION: Operation = Operation::create_model()
+    ///     .table_name(Identifier::new("todoapp__my_model"))
+    ///     .fields(FIELDS)
+    ///     .build();
+    /// let database = cot::db::Database::new("sqlite::memory:").await?;
+    /// CREATE_OPERATION.forwards(&database).await?;
+    ///
+    /// // Then remove it
+    /// const OPERATION: Operation = Operation::remove_model()
+    ///     .table_name(Identifier::new("todoapp__my_model"))
+    ///     .fields(FIELDS)
+    ///     .build();
+    ///
+    /// OPERATION.forwards(&database).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn table_name(mut self, table_name: Identifier) -> Self {
+        self.table_name = Some(table_name);
+        self
+    }
+
+    /// Sets the fields to store for potential backwards migration.
+    ///
+    /// # Cot CLI Usage
+    ///
+    /// Typically, you shouldn't need to use this directly. Instead, in most
+    /// cases, this can be automatically generated by the Cot CLI.
+    #[must_use]
+    pub const fn fields(mut self, fields: &'static [Field]) -> Self {
+        self.fields = Some(fields);
+        self
+    }
+
+    /// Builds the operation.
+    ///
+    /// # Cot CLI Usage
+    ///
+    /// Typically, you shouldn't need to use this directly. Instead, in most
+    /// cases, this can be automatically generated by the Cot CLI.
+    #[must_use]
+    pub const fn build(self) -> Operation {
+        Operation::new(OperationInner::RemoveModel {
+            table_name: unwrap_builder_option!(self, table_name),
+            fields: unwrap_builder_option!(self, fields),
+        })
+    }
+}
+
+/// A builder for changing the values allowed in a field by its `CHECK`
+/// constraint.
+///
+/// Typically, you shouldn't need to use this directly. Instead, in most
+/// cases, this can be automatically generated by the Cot CLI.
+///
+/// # Examples
+///
+/// ```
+/// use cot::db::Identifier;
+/// use cot::db::migrations::Operation;
+///
+/// const OPERATION: Operation = Operation::alter_field_choices()
+///     .table_name(Identifier::new("blog__post"))
+///     .field_name(Identifier::new("status"))
+///     .old_choices(&["draft", "published"])
+///     .new_choices(&["draft", "published", "archived"])
+///     .build();
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct AlterFieldChoicesBuilder {
+    table_name: Option<Identifier>,
+    field_name: Option<Identifier>,
+    old_choices: Option<&'static [&'static str]>,
+    new_choices: Option<&'static [&'static str]>,
+}
+
+impl Default for AlterFieldChoicesBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AlterFieldChoicesBuilder {
+    #[must_use]
+    const fn new() -> Self {
+        Self {
+            table_name: None,
+            field_name: None,
+            old_choices: None,
+            new_choices: None,
+        }
+    }
+
+    /// Sets the name of the table containing the field.
+    ///
+    /// # Cot CLI Usage
+    ///
+    /// Typically, you shouldn't need to use this directly. Instead, in most
+    /// cases, this can be automatically generated by the Cot CLI.
+    #[must_use]
+    pub const fn table_name(mut self, table_name: Identifier) -> Self {
+        self.table_name = Some(table_name);
+        self
+    }
+
+    /// Sets the name of the field to alter.
+    ///
+    /// # Cot CLI Usage
     ///
-    /// # #[tokio::main]
-    /// # async fn main() -> cot::Result<()> {
-    /// const FIELDS: &[Field] = &[
-    ///     Field::new(Identifier::new("id"), <i32 as DatabaseField>::TYPE)
-    ///         .primary_key()
-    ///         .auto(),
-    ///     Field::new(Identifier::new("name"), <String as DatabaseField>::TYPE),
-    /// ];
+    /// Typically, you shouldn't need to use this directly. Instead, in most
+    /// cases, this can be automatically generated by the Cot CLI.
+    #[must_use]
+    pub const fn field_name(mut self, field_name: Identifier) -> Self {
+        self.field_name = Some(field_name);
+        self
+    }
+
+    /// Sets the values allowed in the field before the operation is applied.
     ///
-    /// // First create the table
-    /// const CREATE_OPERATION: Operation = Operation::create_model()
-    ///     .table_name(Identifier::new("todoapp__my_model"))
-    ///     .fields(FIELDS)
-    ///     .build();
-    /// let database = cot::db::Database::new("sqlite::memory:").await?;
-    /// CREATE_OPERATION.forwards(&database).await?;
+    /// If not set, the field is assumed not to have a `CHECK` constraint.
     ///
-    /// // Then remove it
-    /// const OPERATION: Operation = Operation::remove_model()
-    ///     .table_name(Identifier::new("todoapp__my_model"))
-    ///     .fields(FIELDS)
-    ///     .build();
+    /// # Cot CLI Usage
     ///
-    /// OPERATION.forwards(&database).await?;
-    /// # Ok(())
-    /// # }
-    /// ```
+    /// Typically, you shouldn't need to use this directly. Instead, in most
+    /// cases, this can be automatically generated by the Cot CLI.
     #[must_use]
-    pub const fn table_name(mut self, table_name: Identifier) -> Self {
-        self.table_name = Some(table_name);
+    pub const fn old_choices(mut self, choices: &'static [&'static str]) -> Self {
+        self.old_choices = Some(choices);
         self
     }
 
-    /// Sets the fields to store for potential backwards migration.
+    /// Sets the values allowed in the field after the operation is applied.
+    ///
+    /// If not set, the `CHECK` constraint is removed from the field.
     ///
     /// # Cot CLI Usage
     ///
     /// Typically, you shouldn't need to use this directly. Instead, in most
     /// cases, this can be automatically generated by the Cot CLI.
     #[must_use]
-    pub const fn fields(mut self, fields: &'static [Field]) -> Self {
-        self.fields = Some(fields);
+    pub const fn new_choices(mut self, choices: &'static [&'static str]) -> Self {
+        self.new_choices = Some(choices);
         self
     }
 
@@ -1606,9 +2593,11 @@ impl RemoveModelBuilder {
     /// cases, this can be automatically generated by the Cot CLI.
     #[must_use]
     pub const fn build(self) -> Operation {
-        Operation::new(OperationInner::RemoveModel {
+        Operation::new(OperationInner::AlterFieldChoices {
             table_name: unwrap_builder_option!(self, table_name),
-            fields: unwrap_builder_option!(self, fields),
+            field_name: unwrap_builder_option!(self, field_name),
+            old_choices: self.old_choices,
+            new_choices: self.new_choices,
         })
     }
 }
@@ -1702,6 +2691,12 @@ pub trait Migration {
     const MIGRATION_NAME: &'static str;
 
     /// The list of dependencies of the migration.
+    ///
+    /// The dependencies can refer to migrations (or models) of other apps,
+    /// e.g. when a model has a foreign key to a model defined in another app.
+    /// The [`MigrationEngine`] makes sure the dependencies are applied first;
+    /// it returns an error listing the migrations involved if the
+    /// dependencies form a cycle.
     const DEPENDENCIES: &'static [MigrationDependency];
 
     /// The list of operations to apply in the migration.
@@ -1882,6 +2877,8 @@ impl From<ColumnType> for sea_query::ColumnType {
             ColumnType::Text => Self::Text,
             ColumnType::Blob => Self::Blob,
             ColumnType::String(len) => Self::String(StringLen::N(len)),
+            ColumnType::Json => Self::JsonBinary,
+            ColumnType::Decimal(precision) => Self::Decimal(precision),
         }
     }
 }
@@ -2002,24 +2999,148 @@ struct AppliedMigration {
     app: String,
     name: String,
     applied: chrono::DateTime<chrono::FixedOffset>,
+    checksum: Option<String>,
+}
+
+/// The recorded checksums of the applied migrations, keyed by their app and
+/// migration names.
+type AppliedMigrationChecksums = HashMap<(String, String), Option<String>>;
+
+/// Returns whether the error was caused by querying a table or a column that
+/// does not exist.
+fn is_undefined_table_or_column(error: &crate::db::DatabaseError) -> bool {
+    let crate::db::DatabaseError::DatabaseEngineError(error) = error else {
+        return false;
+    };
+    let Some(error) = error.as_database_error() else {
+        return false;
+    };
+
+    match error.code().as_deref() {
+        // PostgreSQL: undefined_table, undefined_column
+        Some("42P01" | "42703") => true,
+        // MySQL: ER_NO_SUCH_TABLE, ER_BAD_FIELD_ERROR
+        Some("42S02" | "42S22") => true,
+        // SQLite reports both as a generic SQLITE_ERROR
+        Some("1") => {
+            let message = error.message();
+            message.starts_with("no such table") || message.starts_with("no such column")
+        }
+        _ => false,
+    }
 }
 
+const APPLIED_MIGRATIONS_TABLE: Identifier = Identifier::new("cot__migrations");
+const APPLIED_MIGRATION_APP_COLUMN: Identifier = Identifier::new("app");
+const APPLIED_MIGRATION_NAME_COLUMN: Identifier = Identifier::new("name");
+const APPLIED_MIGRATION_CHECKSUM_COLUMN: Identifier = Identifier::new("checksum");
+const APPLIED_MIGRATION_CHECKSUM_FIELD: Field = Field::new(
+    APPLIED_MIGRATION_CHECKSUM_COLUMN,
+    <Option<String> as DatabaseField>::TYPE,
+)
+.null();
+
 const CREATE_APPLIED_MIGRATIONS_MIGRATION: Operation = Operation::create_model()
-    .table_name(Identifier::new("cot__migrations"))
+    .table_name(APPLIED_MIGRATIONS_TABLE)
     .fields(&[
         Field::new(Identifier::new("id"), <Auto<i32> as DatabaseField>::TYPE)
             .primary_key()
             .auto(),
-        Field::new(Identifier::new("app"), <String as DatabaseField>::TYPE),
-        Field::new(Identifier::new("name"), <String as DatabaseField>::TYPE),
+        Field::new(
+            APPLIED_MIGRATION_APP_COLUMN,
+            <String as DatabaseField>::TYPE,
+        ),
+        Field::new(
+            APPLIED_MIGRATION_NAME_COLUMN,
+            <String as DatabaseField>::TYPE,
+        ),
         Field::new(
             Identifier::new("applied"),
             <chrono::DateTime<chrono::FixedOffset> as DatabaseField>::TYPE,
         ),
+        APPLIED_MIGRATION_CHECKSUM_FIELD,
     ])
     .if_not_exists()
     .build();
 
+const ADD_APPLIED_MIGRATION_CHECKSUM_MIGRATION: Operation = Operation::add_field()
+    .table_name(APPLIED_MIGRATIONS_TABLE)
+    .field(APPLIED_MIGRATION_CHECKSUM_FIELD)
+    .build();
+
+/// Writes a textual description of the column type used to calculate the
+/// migration checksum.
+///
+/// The description is written out explicitly (rather than using the `Debug`
+/// implementation), so that the checksums of the applied migrations don't
+/// change along with the representation of the type.
+fn write_column_type_description(out: &mut String, ty: ColumnType) -> fmt::Result {
+    let name = match ty {
+        ColumnType::Boolean => "boolean",
+        ColumnType::TinyInteger => "tiny_integer",
+        ColumnType::SmallInteger => "small_integer",
+        ColumnType::Integer => "integer",
+        ColumnType::BigInteger => "big_integer",
+        ColumnType::TinyUnsignedInteger => "tiny_unsigned_integer",
+        ColumnType::SmallUnsignedInteger => "small_unsigned_integer",
+        ColumnType::UnsignedInteger => "unsigned_integer",
+        ColumnType::BigUnsignedInteger => "big_unsigned_integer",
+        ColumnType::Float => "float",
+        ColumnType::Double => "double",
+        ColumnType::Time => "time",
+        ColumnType::Date => "date",
+        ColumnType::DateTime => "date_time",
+        ColumnType::DateTimeWithTimeZone => "date_time_with_time_zone",
+        ColumnType::Text => "text",
+        ColumnType::Blob => "blob",
+        ColumnType::Json => "json",
+        ColumnType::String(max_length) => return write!(out, "string({max_length})"),
+        ColumnType::Decimal(None) => "decimal",
+        ColumnType::Decimal(Some((precision, scale))) => {
+            return write!(out, "decimal({precision},{scale})");
+        }
+    };
+    out.write_str(name)
+}
+
+/// Writes a textual description of the field choices used to calculate the
+/// migration checksum. Each choice is prefixed with its length, so that the
+/// description is unambiguous regardless of the characters it contains.
+fn write_choices_description(
+    out: &mut String,
+    choices: Option<&'static [&'static str]>,
+) -> fmt::Result {
+    let Some(choices) = choices else {
+        return write!(out, "none");
+    };
+
+    write!(out, "[")?;
+    for (index, choice) in choices.iter().enumerate() {
+        if index > 0 {
+            write!(out, ",")?;
+        }
+        write!(out, "{}:{choice}", choice.len())?;
+    }
+    write!(out, "]")
+}
+
+/// Calculates the checksum of a migration's operations.
+///
+/// The checksum is calculated from a textual description of the operations
+/// rather than their in-memory representation, so that it stays the same
+/// across compilations. The functions of custom operations can't be compared,
+/// so only the presence of the backwards function is taken into account.
+fn migration_checksum(operations: &[Operation]) -> String {
+    let mut description = String::new();
+    for operation in operations {
+        operation
+            .write_description(&mut description)
+            .expect("writing to a String should never fail");
+    }
+
+    blake3::hash(description.as_bytes()).to_hex().to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use cot::test::TestDatabase;
@@ -2076,6 +3197,282 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    /// [`TestMigration`] after a field has been added to it.
+    struct ModifiedTestMigration;
+
+    impl Migration for ModifiedTestMigration {
+        const APP_NAME: &'static str = "testapp";
+        const MIGRATION_NAME: &'static str = "m_0001_initial";
+        const DEPENDENCIES: &'static [MigrationDependency] = &[];
+        const OPERATIONS: &'static [Operation] = &[Operation::create_model()
+            .table_name(Identifier::new("testapp__test_model"))
+            .fields(&[
+                Field::new(Identifier::new("id"), <i32 as DatabaseField>::TYPE)
+                    .primary_key()
+                    .auto(),
+                Field::new(Identifier::new("name"), <String as DatabaseField>::TYPE),
+                Field::new(Identifier::new("email"), <String as DatabaseField>::TYPE),
+            ])
+            .build()];
+    }
+
+    #[cot_macros::dbtest]
+    async fn test_migration_engine_detects_drift(test_db: &mut TestDatabase) {
+        let database = test_db.database();
+        MigrationEngine::new([TestMigration])
+            .unwrap()
+            .run(&database)
+            .await
+            .unwrap();
+
+        let result = MigrationEngine::new([ModifiedTestMigration])
+            .unwrap()
+            .with_drift_policy(MigrationDriftPolicy::Error)
+            .run(&database)
+            .await;
+        assert!(matches!(
+            result,
+            Err(crate::db::DatabaseError::MigrationError(
+                MigrationEngineError::MigrationDrift { .. }
+            ))
+        ));
+
+        // only a warning is logged by default
+        let result = MigrationEngine::new([ModifiedTestMigration])
+            .unwrap()
+            .run(&database)
+            .await;
+        assert!(result.is_ok());
+
+        // the unmodified migration is still accepted
+        let result = MigrationEngine::new([TestMigration])
+            .unwrap()
+            .with_drift_policy(MigrationDriftPolicy::Error)
+            .run(&database)
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[cot_macros::dbtest]
+    async fn test_migration_engine_check_pending(test_db: &mut TestDatabase) {
+        let database = test_db.database();
+        #[expect(trivial_casts)] // cast to the correct trait object type
+        let engine = MigrationEngine::new([
+            &TestMigration as &SyncDynMigration,
+            &DummyMigration as &SyncDynMigration,
+        ])
+        .unwrap();
+        MigrationEngine::new([TestMigration])
+            .unwrap()
+            .run(&database)
+            .await
+            .unwrap();
+
+        let pending = engine.pending_migrations(&database).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].name(), "m_0002_custom");
+
+        let result = engine.check(&database).await;
+        assert!(matches!(
+            result,
+            Err(crate::db::DatabaseError::MigrationError(
+                MigrationEngineError::PendingMigrations(ref names)
+            )) if names == &["testapp::m_0002_custom"]
+        ));
+    }
+
+    #[cot_macros::dbtest]
+    async fn test_migration_engine_check_up_to_date(test_db: &mut TestDatabase) {
+        let database = test_db.database();
+        let engine = MigrationEngine::new([TestMigration]).unwrap();
+        engine.run(&database).await.unwrap();
+
+        assert!(
+            engine
+                .pending_migrations(&database)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(engine.check(&database).await.is_ok());
+    }
+
+    #[cot_macros::dbtest]
+    async fn test_migration_engine_check_is_read_only(test_db: &mut TestDatabase) {
+        let database = test_db.database();
+        let engine = MigrationEngine::new([TestMigration]).unwrap();
+
+        let pending = engine.pending_migrations(&database).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].name(), "m_0001_initial");
+        assert!(engine.check(&database).await.is_err());
+
+        // checking doesn't create the applied migrations table
+        assert!(database.raw("SELECT * FROM cot__migrations").await.is_err());
+    }
+
+    #[cot_macros::dbtest]
+    async fn test_migration_engine_plan(test_db: &mut TestDatabase) {
+        let database = test_db.database();
+        #[expect(trivial_casts)] // cast to the correct trait object type
+        let engine = MigrationEngine::new([
+            &TestMigration as &SyncDynMigration,
+            &DummyMigration as &SyncDynMigration,
+        ])
+        .unwrap();
+
+        let plans = engine.plan(&database).await.unwrap();
+        assert_eq!(plans.len(), 2);
+        assert_eq!(plans[0].name(), "m_0001_initial");
+        let [OperationPlan::Sql(statements)] = plans[0].operations() else {
+            panic!(
+                "expected a single SQL operation: {:?}",
+                plans[0].operations()
+            );
+        };
+        assert_eq!(statements.len(), 1);
+        assert!(statements[0].starts_with("CREATE TABLE"));
+        assert!(statements[0].contains("testapp__test_model"));
+        assert_eq!(plans[1].name(), "m_0002_custom");
+        assert!(plans[1].operations().is_empty());
+
+        // planning doesn't touch the database
+        assert!(database.raw("SELECT * FROM cot__migrations").await.is_err());
+        assert!(
+            database
+                .raw("SELECT * FROM testapp__test_model")
+                .await
+                .is_err()
+        );
+
+        MigrationEngine::new([TestMigration])
+            .unwrap()
+            .run(&database)
+            .await
+            .unwrap();
+        let plans = engine.plan(&database).await.unwrap();
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].name(), "m_0002_custom");
+    }
+
+    #[cot::test]
+    #[cfg_attr(
+        miri,
+        ignore = "unsupported operation: can't call foreign function `sqlite3_open_v2`"
+    )]
+    async fn migration_plan_display() {
+        let test_db = TestDatabase::new_sqlite().await.unwrap();
+
+        #[migration_op]
+        async fn forwards(_ctx: MigrationContext<'_>) -> Result<()> {
+            panic!("this should not be called");
+        }
+
+        struct CustomMigration;
+
+        impl Migration for CustomMigration {
+            const APP_NAME: &'static str = "testapp";
+            const MIGRATION_NAME: &'static str = "m_0002_populate";
+            const DEPENDENCIES: &'static [MigrationDependency] = &[];
+            const OPERATIONS: &'static [Operation] = &[
+                Operation::remove_field()
+                    .table_name(Identifier::new("testapp__test_model"))
+                    .field(Field::new(
+                        Identifier::new("name"),
+                        <String as DatabaseField>::TYPE,
+                    ))
+                    .build(),
+                Operation::custom(forwards).build(),
+            ];
+        }
+
+        let engine = MigrationEngine::new([CustomMigration]).unwrap();
+        let plans = engine.plan(&test_db.database()).await.unwrap();
+
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].operations()[1], OperationPlan::Custom);
+        assert_eq!(
+            plans[0].to_string(),
+            "-- Migration testapp::m_0002_populate\n\
+            ALTER TABLE \"testapp__test_model\" DROP COLUMN \"name\";\n\
+            -- Custom operation (runs Rust code)\n"
+        );
+    }
+
+    #[cot_macros::dbtest]
+    async fn test_migration_engine_upgrades_applied_migrations_table(test_db: &mut TestDatabase) {
+        const CREATE_LEGACY_APPLIED_MIGRATIONS: Operation = Operation::create_model()
+            .table_name(APPLIED_MIGRATIONS_TABLE)
+            .fields(&[
+                Field::new(Identifier::new("id"), <Auto<i32> as DatabaseField>::TYPE)
+                    .primary_key()
+                    .auto(),
+                Field::new(Identifier::new("app"), <String as DatabaseField>::TYPE),
+                Field::new(Identifier::new("name"), <String as DatabaseField>::TYPE),
+                Field::new(
+                    Identifier::new("applied"),
+                    <chrono::DateTime<chrono::FixedOffset> as DatabaseField>::TYPE,
+                ),
+            ])
+            .build();
+
+        let database = test_db.database();
+        CREATE_LEGACY_APPLIED_MIGRATIONS
+            .forwards(&database)
+            .await
+            .unwrap();
+
+        MigrationEngine::new([TestMigration])
+            .unwrap()
+            .run(&database)
+            .await
+            .unwrap();
+        let result = MigrationEngine::new([ModifiedTestMigration])
+            .unwrap()
+            .with_drift_policy(MigrationDriftPolicy::Error)
+            .run(&database)
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_migration_checksum() {
+        let checksum = migration_checksum(TestMigration::OPERATIONS);
+
+        assert_eq!(checksum.len(), 64);
+        assert_eq!(checksum, migration_checksum(TestMigration::OPERATIONS));
+        assert_ne!(
+            checksum,
+            migration_checksum(ModifiedTestMigration::OPERATIONS)
+        );
+        assert_ne!(checksum, migration_checksum(&[]));
+    }
+
+    #[test]
+    fn test_field_description() {
+        let field = Field::new(Identifier::new("price"), ColumnType::Decimal(Some((10, 2))))
+            .set_null(true)
+            .default_value(DefaultValue::String("a,b"));
+        let mut description = String::new();
+        field.write_description(&mut description).unwrap();
+
+        assert_eq!(
+            description,
+            "  field price decimal(10,2) primary_key=false auto=false null=true unique=false \
+            default=string:3:a,b\n"
+        );
+    }
+
+    #[test]
+    fn test_field_description_choices() {
+        let mut description = String::new();
+        write_choices_description(&mut description, Some(&["a,b", "c"])).unwrap();
+        write_choices_description(&mut description, None).unwrap();
+
+        assert_eq!(description, "[3:a,b,1:c]none");
+    }
+
     #[test]
     fn test_operation_create_model() {
         const OPERATION_CREATE_MODEL_FIELDS: &[Field; 2] = &[
@@ -2313,6 +3710,89 @@ mod tests {
         assert!(!spec.auto_increment);
         assert_ne!(spec.nullable, Some(true));
         assert!(!spec.unique);
+        assert_eq!(spec.default, None);
+    }
+
+    #[test]
+    fn test_field_to_column_def_with_default() {
+        let field = Field::new(Identifier::new("views"), ColumnType::BigInteger)
+            .default_value(DefaultValue::Int(0));
+
+        let mut mapper = MockColumnTypeMapper::new();
+        mapper
+            .expect_sea_query_column_type_for()
+            .return_const(sea_query::ColumnType::BigInteger);
+        let column_def = field.as_column_def(&mapper);
+
+        assert_eq!(
+            column_def.get_column_spec().default,
+            Some(sea_query::Expr::val(0_i64))
+        );
+    }
+
+    #[test]
+    fn test_field_to_column_def_with_choices() {
+        let field = Field::new(Identifier::new("status"), ColumnType::Text)
+            .choices(&["draft", "published"]);
+
+        let mut mapper = MockColumnTypeMapper::new();
+        mapper
+            .expect_sea_query_column_type_for()
+            .return_const(sea_query::ColumnType::Text);
+        let column_def = field.as_column_def(&mapper);
+
+        assert!(column_def.get_column_spec().check.is_some());
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn field_decimal_precision() {
+        let field = Field::new(Identifier::new("price"), ColumnType::Decimal(None))
+            .decimal_precision(10, 2);
+
+        assert_eq!(field.ty, ColumnType::Decimal(Some((10, 2))));
+        assert_eq!(
+            sea_query::ColumnType::from(field.ty),
+            sea_query::ColumnType::Decimal(Some((10, 2)))
+        );
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    #[should_panic(expected = "precision can only be set on decimal fields")]
+    fn field_decimal_precision_non_decimal() {
+        let _ = Field::new(Identifier::new("price"), ColumnType::Double).decimal_precision(10, 2);
+    }
+
+    #[test]
+    fn field_description_with_choices() {
+        let field = Field::new(Identifier::new("status"), ColumnType::Text)
+            .choices(&["draft", "published"]);
+
+        let mut description = String::new();
+        field.write_description(&mut description).unwrap();
+
+        assert!(description.ends_with(" choices=[\"draft\", \"published\"]\n"));
+    }
+
+    #[test]
+    fn default_value_as_sea_query_value() {
+        assert_eq!(
+            DefaultValue::Bool(true).as_sea_query_value(),
+            sea_query::Value::Bool(Some(true))
+        );
+        assert_eq!(
+            DefaultValue::Int(-5).as_sea_query_value(),
+            sea_query::Value::BigInt(Some(-5))
+        );
+        assert_eq!(
+            DefaultValue::Float(1.5).as_sea_query_value(),
+            sea_query::Value::Double(Some(1.5))
+        );
+        assert_eq!(
+            DefaultValue::String("test").as_sea_query_value(),
+            sea_query::Value::String(Some("test".to_owned()))
+        );
     }
 
     #[test]
@@ -11,8 +11,12 @@ use crate::utils::graph::{Graph, apply_permutation};
 #[derive(Debug, Clone, PartialEq, Eq, Error)]
 #[non_exhaustive]
 pub enum MigrationSorterError {
-    #[error("cycle detected in migrations")]
-    CycleDetected(#[from] crate::utils::graph::CycleDetected),
+    #[error(
+        "cycle detected in migration dependencies: {} \
+        (each migration depends on the next one)",
+        format_migration_cycle(.cycle)
+    )]
+    CycleDetected { cycle: Vec<String> },
     #[error("dependency not found: {}", format_migration_dependency(.0))]
     InvalidDependency(MigrationDependency),
     #[error("migration defined twice: {app_name}::{migration_name}")]
@@ -40,6 +44,15 @@ fn format_migration_dependency(dependency: &MigrationDependency) -> String {
     }
 }
 
+fn format_migration_cycle(cycle: &[String]) -> String {
+    let mut formatted = cycle.join(" -> ");
+    if let Some(first) = cycle.first() {
+        formatted.push_str(" -> ");
+        formatted.push_str(first);
+    }
+    formatted
+}
+
 /// Sorts migrations topologically based on their dependencies.
 #[derive(Debug)]
 pub(super) struct MigrationSorter<'a, T> {
@@ -74,7 +87,20 @@ impl<'a, T: DynMigration> MigrationSorter<'a, T> {
             }
         }
 
-        let mut sorted_indices = graph.toposort()?;
+        let mut sorted_indices = graph.toposort().map_err(|error| {
+            // the edges go from the dependencies to the dependents, so reverse
+            // the cycle to list each migration before its dependency
+            let cycle = error
+                .cycle()
+                .iter()
+                .rev()
+                .map(|&index| {
+                    let migration = &self.migrations[index];
+                    format!("{}::{}", migration.app_name(), migration.name())
+                })
+                .collect();
+            MigrationSorterError::CycleDetected { cycle }
+        })?;
         apply_permutation(self.migrations, &mut sorted_indices);
 
         Ok(())
@@ -314,10 +340,52 @@ mod tests {
         ];
 
         let mut sorter = MigrationSorter::new(&mut migrations);
-        assert!(matches!(
+        assert_eq!(
             sorter.toposort().unwrap_err(),
-            MigrationSorterError::CycleDetected(_)
-        ));
+            MigrationSorterError::CycleDetected {
+                cycle: vec!["app1::migration1".to_owned(), "app1::migration2".to_owned()]
+            }
+        );
+    }
+
+    #[test]
+    fn cycle_detection_across_apps() {
+        let mut migrations = vec![
+            TestMigration::new(
+                "auth",
+                "m_0001_initial",
+                [MigrationDependency::model(
+                    "blog",
+                    Identifier::new("blog__post"),
+                )],
+                [Operation::create_model()
+                    .table_name(Identifier::new("auth__user"))
+                    .fields(&[])
+                    .build()],
+            ),
+            TestMigration::new(
+                "blog",
+                "m_0001_initial",
+                [MigrationDependency::model(
+                    "auth",
+                    Identifier::new("auth__user"),
+                )],
+                [Operation::create_model()
+                    .table_name(Identifier::new("blog__post"))
+                    .fields(&[])
+                    .build()],
+            ),
+            TestMigration::new("blog", "m_0002_unrelated", [], []),
+        ];
+
+        let error = MigrationSorter::new(&mut migrations).sort().unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "cycle detected in migration dependencies: \
+            auth::m_0001_initial -> blog::m_0001_initial -> auth::m_0001_initial \
+            (each migration depends on the next one)"
+        );
     }
 
     #[test]
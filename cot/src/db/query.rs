@@ -7,9 +7,10 @@ use sea_query::{ExprTrait, IntoColumnRef};
 
 use crate::db;
 use crate::db::{
-    Auto, Database, DatabaseBackend, DbFieldValue, DbValue, ForeignKey, FromDbValue, Identifier,
-    Model, StatementResult, ToDbFieldValue,
+    Auto, Column, Database, DatabaseBackend, DbFieldValue, DbValue, ForeignKey, FromDbValue,
+    Identifier, LimitedString, Model, RelatedField, Row, StatementResult, ToDbFieldValue,
 };
+use crate::html::Html;
 
 /// A query that can be executed on a database. Can be used to filter, update,
 /// or delete rows.
@@ -34,9 +35,98 @@ pub struct Query<T> {
     filter: Option<Expr>,
     limit: Option<u64>,
     offset: Option<u64>,
+    order_by: Vec<OrderBy>,
+    lock: Option<RowLock>,
+    related: Vec<Relation>,
     phantom_data: PhantomData<fn() -> T>,
 }
 
+/// The row locking mode of a query.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum RowLock {
+    /// `FOR UPDATE`: wait for the rows locked by other transactions.
+    Wait,
+    /// `FOR UPDATE SKIP LOCKED`: skip the rows locked by other transactions.
+    SkipLocked,
+    /// `FOR UPDATE NOWAIT`: fail if any row is locked by another transaction.
+    NoWait,
+}
+
+/// A foreign key relation that is loaded together with the queried rows.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+struct Relation {
+    /// The foreign key column of the queried model.
+    column: Identifier,
+    /// The table of the related model.
+    table: Identifier,
+    /// The primary key column of the related model.
+    primary_key: Identifier,
+    /// The columns of the related model.
+    columns: &'static [Column],
+}
+
+/// The alias of the (filtered) queried table in queries with related models.
+const BASE_TABLE_ALIAS: &str = "cot__base";
+
+/// A single page of the results of a query, returned by
+/// [`Query::paginate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T> {
+    items: Vec<T>,
+    page: u32,
+    page_size: u32,
+    total_count: u64,
+    total_pages: u32,
+}
+
+impl<T> Page<T> {
+    /// Returns the rows on this page.
+    #[must_use]
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Consumes the page and returns the rows on it.
+    #[must_use]
+    pub fn into_items(self) -> Vec<T> {
+        self.items
+    }
+
+    /// Returns the 1-based number of this page.
+    #[must_use]
+    pub fn page(&self) -> u32 {
+        self.page
+    }
+
+    /// Returns the maximum number of rows on a page.
+    #[must_use]
+    pub fn page_size(&self) -> u32 {
+        self.page_size
+    }
+
+    /// Returns the number of rows matching the query on all the pages.
+    #[must_use]
+    pub fn total_count(&self) -> u64 {
+        self.total_count
+    }
+
+    /// Returns the number of pages.
+    #[must_use]
+    pub fn total_pages(&self) -> u32 {
+        self.total_pages
+    }
+
+    /// Renders the links to the other pages using
+    /// [`html::pagination`](crate::html::pagination).
+    ///
+    /// `url_for` is called with a 1-based page number to get the URL of that
+    /// page.
+    #[must_use]
+    pub fn pagination(&self, url_for: impl Fn(u32) -> String) -> Html {
+        crate::html::pagination(self.page, self.total_pages, url_for)
+    }
+}
+
 // manual implementation to avoid `T: Debug` in the trait bounds
 impl<T> Debug for Query<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -44,6 +134,9 @@ impl<T> Debug for Query<T> {
             .field("filter", &self.filter)
             .field("limit", &self.limit)
             .field("offset", &self.offset)
+            .field("order_by", &self.order_by)
+            .field("lock", &self.lock)
+            .field("related", &self.related)
             .field("phantom_data", &self.phantom_data)
             .finish()
     }
@@ -56,6 +149,9 @@ impl<T> Clone for Query<T> {
             filter: self.filter.clone(),
             limit: self.limit,
             offset: self.offset,
+            order_by: self.order_by.clone(),
+            lock: self.lock,
+            related: self.related.clone(),
             phantom_data: PhantomData,
         }
     }
@@ -99,6 +195,9 @@ impl<T: Model> Query<T> {
             filter: None,
             limit: None,
             offset: None,
+            order_by: Vec::new(),
+            lock: None,
+            related: Vec::new(),
             phantom_data: PhantomData,
         }
     }
@@ -172,6 +271,192 @@ impl<T: Model> Query<T> {
         self
     }
 
+    /// Add an ordering of the query results.
+    ///
+    /// Can be called multiple times to order by several columns; the results
+    /// are ordered by the first column, then by the second one, and so on.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cot::db::query::{OrderBy, Query};
+    /// use cot::db::{Model, model};
+    ///
+    /// #[model]
+    /// struct User {
+    ///     #[model(primary_key)]
+    ///     id: i32,
+    ///     name: String,
+    ///     age: i32,
+    /// }
+    ///
+    /// let query = Query::<User>::new()
+    ///     .order_by(<User as Model>::Fields::age.desc())
+    ///     .order_by(<User as Model>::Fields::name.asc());
+    /// ```
+    pub fn order_by(&mut self, order_by: OrderBy) -> &mut Self {
+        self.order_by.push(order_by);
+        self
+    }
+
+    /// Load the model referenced by the given foreign key together with the
+    /// queried rows.
+    ///
+    /// Without this, accessing the related model of each row with
+    /// [`ForeignKey::get`] issues a separate query per row. With
+    /// `select_related`, the related table is joined in the same query and
+    /// the foreign keys of the returned models contain the related model
+    /// instances (so [`ForeignKey::model`] returns [`Some`]).
+    ///
+    /// Can be called multiple times to load several relations. Nullable
+    /// foreign keys (`Option<ForeignKey<T>>`) are supported; the ones set to
+    /// [`None`] are left untouched.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cot::db::query::Query;
+    /// use cot::db::{Auto, ForeignKey, Model, model};
+    ///
+    /// #[model]
+    /// struct User {
+    ///     #[model(primary_key)]
+    ///     id: Auto<i32>,
+    ///     name: String,
+    /// }
+    ///
+    /// #[model]
+    /// struct TodoItem {
+    ///     #[model(primary_key)]
+    ///     id: Auto<i32>,
+    ///     owner: ForeignKey<User>,
+    /// }
+    ///
+    /// # async fn example(db: &cot::db::Database) -> cot::db::Result<()> {
+    /// let todos = TodoItem::objects()
+    ///     .select_related(<TodoItem as Model>::Fields::owner)
+    ///     .all(db)
+    ///     .await?;
+    /// for todo in &todos {
+    ///     println!("{}", todo.owner.model().unwrap().name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[expect(
+        clippy::needless_pass_by_value,
+        reason = "field references are constants passed by value, like in `ExprEq`"
+    )]
+    pub fn select_related<F: RelatedField>(&mut self, field: FieldRef<F>) -> &mut Self {
+        let relation = Relation {
+            column: field.identifier,
+            table: F::Target::TABLE_NAME,
+            primary_key: F::Target::PRIMARY_KEY_NAME,
+            columns: F::Target::COLUMNS,
+        };
+        if !self.related.contains(&relation) {
+            self.related.push(relation);
+        }
+        self
+    }
+
+    /// Lock the selected rows for update (`SELECT ... FOR UPDATE`).
+    ///
+    /// This makes other transactions trying to modify or lock the rows wait
+    /// until the current transaction ends, which prevents lost updates when a
+    /// row is read, modified, and written back.
+    ///
+    /// The lock is only held until the end of the transaction the query is
+    /// executed in. Unless the query is executed in a transaction started
+    /// with [`Database::begin`](db::Database::begin), it runs in its own
+    /// implicit transaction and the lock is released as soon as the query
+    /// completes.
+    ///
+    /// # Backend support
+    ///
+    /// Row locking is supported on PostgreSQL and MySQL. SQLite doesn't
+    /// support it (it locks the whole database when writing instead), so
+    /// executing a query with row locking on SQLite returns
+    /// [`DatabaseError::NotSupported`](db::DatabaseError::NotSupported).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cot::db::model;
+    /// use cot::db::query::Query;
+    ///
+    /// #[model]
+    /// struct User {
+    ///     #[model(primary_key)]
+    ///     id: i32,
+    ///     name: String,
+    ///     age: i32,
+    /// }
+    ///
+    /// let query = Query::<User>::new().for_update();
+    /// ```
+    pub fn for_update(&mut self) -> &mut Self {
+        self.lock = Some(RowLock::Wait);
+        self
+    }
+
+    /// Lock the selected rows for update, skipping the rows that are already
+    /// locked by other transactions (`SELECT ... FOR UPDATE SKIP LOCKED`).
+    ///
+    /// This implies [`Self::for_update`] and is typically used to implement
+    /// job queues, where multiple workers pick up the rows that aren't
+    /// currently processed by anyone else. See [`Self::for_update`] for the
+    /// backend support and transaction caveats.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cot::db::model;
+    /// use cot::db::query::Query;
+    ///
+    /// #[model]
+    /// struct User {
+    ///     #[model(primary_key)]
+    ///     id: i32,
+    ///     name: String,
+    ///     age: i32,
+    /// }
+    ///
+    /// let query = Query::<User>::new().for_update().skip_locked();
+    /// ```
+    pub fn skip_locked(&mut self) -> &mut Self {
+        self.lock = Some(RowLock::SkipLocked);
+        self
+    }
+
+    /// Lock the selected rows for update, failing immediately if any of them
+    /// is already locked by another transaction (`SELECT ... FOR UPDATE
+    /// NOWAIT`).
+    ///
+    /// This implies [`Self::for_update`]. See [`Self::for_update`] for the
+    /// backend support and transaction caveats.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cot::db::model;
+    /// use cot::db::query::Query;
+    ///
+    /// #[model]
+    /// struct User {
+    ///     #[model(primary_key)]
+    ///     id: i32,
+    ///     name: String,
+    ///     age: i32,
+    /// }
+    ///
+    /// let query = Query::<User>::new().for_update().nowait();
+    /// ```
+    pub fn nowait(&mut self) -> &mut Self {
+        self.lock = Some(RowLock::NoWait);
+        self
+    }
+
     /// Execute the query and return all results.
     ///
     /// # Errors
@@ -181,16 +466,81 @@ impl<T: Model> Query<T> {
         db.query(self).await
     }
 
-    /// Execute the query and return the first result.
+    /// Execute the query and return the first result, or `None` if the query
+    /// doesn't match any rows.
+    ///
+    /// Only a single row is fetched from the database (`LIMIT 1`).
     ///
     /// # Errors
     ///
     /// Returns an error if the query fails.
-    pub async fn get<DB: DatabaseBackend>(&self, db: &DB) -> db::Result<Option<T>> {
-        // TODO panic/error if more than one result
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cot::db::{Auto, Database, model, query};
+    ///
+    /// #[model]
+    /// struct User {
+    ///     #[model(primary_key)]
+    ///     id: Auto<i32>,
+    ///     name: String,
+    /// }
+    ///
+    /// async fn find_user(db: &Database, name: &str) -> cot::db::Result<Option<User>> {
+    ///     query!(User, $name == name).first(db).await
+    /// }
+    /// ```
+    pub async fn first<DB: DatabaseBackend>(&self, db: &DB) -> db::Result<Option<T>> {
         db.get(self).await
     }
 
+    /// Execute the query and return its only result.
+    ///
+    /// Unlike [`Self::first`], this requires the query to match exactly one
+    /// row, which makes it suitable for lookups by a unique column. The limit
+    /// set on the query, if any, is ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DatabaseError::NotFound`](db::DatabaseError::NotFound) if the
+    /// query doesn't match any rows, and
+    /// [`DatabaseError::MultipleObjects`](db::DatabaseError::MultipleObjects)
+    /// if it matches more than one row.
+    ///
+    /// Returns an error if the query fails.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cot::db::{Auto, Database, model, query};
+    ///
+    /// #[model]
+    /// struct User {
+    ///     #[model(primary_key)]
+    ///     id: Auto<i32>,
+    ///     #[model(unique)]
+    ///     name: String,
+    /// }
+    ///
+    /// async fn get_user(db: &Database, name: &str) -> cot::db::Result<User> {
+    ///     query!(User, $name == name).get(db).await
+    /// }
+    /// ```
+    pub async fn get<DB: DatabaseBackend>(&self, db: &DB) -> db::Result<T> {
+        // fetch one extra row so that we can tell whether the result is unique,
+        // regardless of the limit set on the query
+        let mut query = self.clone();
+        query.limit(2);
+
+        let mut results = db.query(&query).await?;
+        match results.len() {
+            0 => Err(db::DatabaseError::NotFound),
+            1 => Ok(results.remove(0)),
+            _ => Err(db::DatabaseError::MultipleObjects),
+        }
+    }
+
     /// Execute the query and return the number of results.
     ///
     /// # Errors
@@ -222,6 +572,10 @@ impl<T: Model> Query<T> {
 
     /// Delete all rows that match the query.
     ///
+    /// The number of deleted rows can be retrieved with
+    /// [`StatementResult::rows_affected`], which allows to detect the case
+    /// when nothing matched the query.
+    ///
     /// # Errors
     ///
     /// Returns an error if the query fails.
@@ -229,6 +583,308 @@ impl<T: Model> Query<T> {
         db.delete(self).await
     }
 
+    /// Delete all rows that match the query and return the deleted rows.
+    ///
+    /// On backends that support the `RETURNING` clause (SQLite and
+    /// PostgreSQL), this is done in a single statement. On MySQL, the
+    /// matching rows are locked and fetched before being deleted, in a
+    /// transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub async fn delete_returning<DB: DatabaseBackend>(&self, db: &DB) -> db::Result<Vec<T>> {
+        db.delete_returning(self).await
+    }
+
+    /// Update all rows that match the query, setting the given fields.
+    ///
+    /// The number of updated rows can be retrieved with
+    /// [`StatementResult::rows_affected`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::{Model, model, query};
+    ///
+    /// #[model]
+    /// struct TodoItem {
+    ///     #[model(primary_key)]
+    ///     id: i32,
+    ///     done: bool,
+    /// }
+    ///
+    /// # async fn example(db: &cot::db::Database) -> cot::db::Result<()> {
+    /// let result = query!(TodoItem, $done == false)
+    ///     .update(db, &[<TodoItem as Model>::Fields::done.set(true)])
+    ///     .await?;
+    /// println!("Marked {} items as done", result.rows_affected());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn update<DB: DatabaseBackend>(
+        &self,
+        db: &DB,
+        assignments: &[Assignment],
+    ) -> db::Result<StatementResult> {
+        db.bulk_update(self, assignments).await
+    }
+
+    /// Return the only row that matches the query, or insert a new one if
+    /// there are none.
+    ///
+    /// If no rows match the query, the model returned by `create` is inserted
+    /// into the database. The returned boolean is `true` if the model was
+    /// created, and `false` if it already existed.
+    ///
+    /// The query and the insert are executed in a transaction. If inserting
+    /// the model fails with
+    /// [`DatabaseError::UniqueViolation`](db::DatabaseError::UniqueViolation)
+    /// (because the same row has been inserted concurrently since it was
+    /// queried for), the transaction is rolled back and the row inserted by
+    /// the other connection is returned instead. On PostgreSQL and MySQL,
+    /// this only prevents duplicates if the columns used in the filter have a
+    /// unique constraint; SQLite doesn't run the write transactions
+    /// concurrently.
+    ///
+    /// If `db` is already a [`Transaction`](db::Transaction), its transaction
+    /// is used instead of starting a new one. A unique constraint violation
+    /// is then returned as an error, since the transaction can't be used
+    /// after the failed insert on PostgreSQL.
+    ///
+    /// # Errors
+    ///
+    /// Returns
+    /// [`DatabaseError::MultipleObjects`](db::DatabaseError::MultipleObjects)
+    /// if the query matches more than one row.
+    ///
+    /// Returns an error if the query, the insert, or the transaction fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::{Auto, model, query};
+    ///
+    /// #[model]
+    /// struct TodoItem {
+    ///     #[model(primary_key)]
+    ///     id: Auto<i32>,
+    ///     #[model(unique)]
+    ///     title: String,
+    /// }
+    ///
+    /// # async fn example(db: &cot::db::Database) -> cot::db::Result<()> {
+    /// let (item, created) = query!(TodoItem, $title == "Buy milk")
+    ///     .get_or_create(db, || TodoItem {
+    ///         id: Auto::auto(),
+    ///         title: "Buy milk".to_owned(),
+    ///     })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_or_create<F>(&self, db: &Database, create: F) -> db::Result<(T, bool)>
+    where
+        F: FnOnce() -> T,
+    {
+        if db.in_transaction() {
+            return self.get_or_insert(db, create).await;
+        }
+
+        let transaction = db.begin().await?;
+        match self.get_or_insert(&transaction, create).await {
+            Ok(result) => {
+                transaction.commit().await?;
+                Ok(result)
+            }
+            Err(db::DatabaseError::UniqueViolation) => {
+                transaction.rollback().await?;
+                let model = self.get(db).await.map_err(not_found_as_unique_violation)?;
+                Ok((model, false))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Update the only row that matches the query, or insert a new one if
+    /// there are none.
+    ///
+    /// If a row matches the query, `update` is called on it and the modified
+    /// model is saved to the database. Otherwise, the model returned by
+    /// `create` is inserted. The returned boolean is `true` if the model was
+    /// created, and `false` if an existing one was updated.
+    ///
+    /// This runs in a transaction in the same way as
+    /// [`Self::get_or_create`]. On PostgreSQL and MySQL, the existing row is
+    /// locked with [`Self::for_update`] until it's updated. If the row has
+    /// been inserted concurrently, it's fetched and updated in a new
+    /// transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns
+    /// [`DatabaseError::MultipleObjects`](db::DatabaseError::MultipleObjects)
+    /// if the query matches more than one row.
+    ///
+    /// Returns an error if the query, the insert, the update, or the
+    /// transaction fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::{Auto, model, query};
+    ///
+    /// #[model]
+    /// struct TodoItem {
+    ///     #[model(primary_key)]
+    ///     id: Auto<i32>,
+    ///     #[model(unique)]
+    ///     title: String,
+    ///     done: bool,
+    /// }
+    ///
+    /// # async fn example(db: &cot::db::Database) -> cot::db::Result<()> {
+    /// let (item, created) = query!(TodoItem, $title == "Buy milk")
+    ///     .update_or_create(
+    ///         db,
+    ///         |item| item.done = true,
+    ///         || TodoItem {
+    ///             id: Auto::auto(),
+    ///             title: "Buy milk".to_owned(),
+    ///             done: true,
+    ///         },
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn update_or_create<U, F>(
+        &self,
+        db: &Database,
+        update: U,
+        create: F,
+    ) -> db::Result<(T, bool)>
+    where
+        U: FnOnce(&mut T),
+        F: FnOnce() -> T,
+    {
+        let mut query = self.clone();
+        if db.supports_row_locking() {
+            query.for_update();
+        }
+
+        if db.in_transaction() {
+            let (mut model, created) = query.get_or_insert(db, create).await?;
+            if !created {
+                update(&mut model);
+                db.update(&mut model).await?;
+            }
+            return Ok((model, created));
+        }
+
+        let transaction = db.begin().await?;
+        let (transaction, mut model, created) =
+            match query.get_or_insert(&transaction, create).await {
+                Ok((model, created)) => (transaction, model, created),
+                Err(db::DatabaseError::UniqueViolation) => {
+                    transaction.rollback().await?;
+                    let transaction = db.begin().await?;
+                    let model = query
+                        .get(&*transaction)
+                        .await
+                        .map_err(not_found_as_unique_violation)?;
+                    (transaction, model, false)
+                }
+                Err(err) => return Err(err),
+            };
+        if !created {
+            update(&mut model);
+            transaction.update(&mut model).await?;
+        }
+        transaction.commit().await?;
+
+        Ok((model, created))
+    }
+
+    async fn get_or_insert<F>(&self, db: &Database, create: F) -> db::Result<(T, bool)>
+    where
+        F: FnOnce() -> T,
+    {
+        match self.get(db).await {
+            Ok(model) => return Ok((model, false)),
+            Err(db::DatabaseError::NotFound) => {}
+            Err(err) => return Err(err),
+        }
+
+        let mut model = create();
+        db.insert(&mut model).await?;
+        Ok((model, true))
+    }
+
+    /// Execute the query for a single page of results.
+    ///
+    /// The pages are numbered from 1, and each page contains up to
+    /// `page_size` rows; page 0 is treated as the first page. Any limit and
+    /// offset set on the query are replaced. The returned [`Page`] also
+    /// contains the total number of pages, so that it can be passed straight
+    /// to [`Page::pagination`] to render the links to the other pages.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page_size` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::{Auto, Database, Model, model};
+    /// use cot::html::Html;
+    ///
+    /// #[model]
+    /// struct TodoItem {
+    ///     #[model(primary_key)]
+    ///     id: Auto<i32>,
+    ///     title: String,
+    /// }
+    ///
+    /// async fn todo_pagination(db: &Database, page: u32) -> cot::db::Result<Html> {
+    ///     let page = TodoItem::objects().paginate(db, page, 20).await?;
+    ///     for item in page.items() {
+    ///         println!("{}", item.title);
+    ///     }
+    ///     Ok(page.pagination(|page| format!("/todos?page={page}")))
+    /// }
+    /// ```
+    pub async fn paginate(&self, db: &Database, page: u32, page_size: u32) -> db::Result<Page<T>> {
+        assert!(page_size > 0, "Page size must be greater than 0");
+        let page = page.max(1);
+
+        let total_count = self.count(db).await?;
+        let total_pages =
+            u32::try_from(total_count.div_ceil(u64::from(page_size))).unwrap_or(u32::MAX);
+
+        let mut query = self.clone();
+        query
+            .limit(u64::from(page_size))
+            .offset(u64::from(page - 1) * u64::from(page_size));
+        let items = query.all(db).await?;
+
+        Ok(Page {
+            items,
+            page,
+            page_size,
+            total_count,
+            total_pages,
+        })
+    }
+
     pub(super) fn add_filter_to_statement<S: sea_query::ConditionalStatement>(
         &self,
         statement: &mut S,
@@ -249,6 +905,140 @@ impl<T: Model> Query<T> {
             statement.offset(offset);
         }
     }
+
+    pub(super) fn add_order_by_to_statement(&self, statement: &mut sea_query::SelectStatement) {
+        for order_by in &self.order_by {
+            statement.order_by(order_by.column, order_by.as_sea_query_order());
+        }
+    }
+
+    /// Wraps the given statement selecting the columns of `T` in a statement
+    /// that additionally selects the columns of the related models, joining
+    /// them by their primary keys.
+    ///
+    /// The original statement is used as a subquery, so that its filters,
+    /// limit, and offset apply to the rows of `T` only, and the column names
+    /// in the filters aren't ambiguous.
+    pub(super) fn add_related_to_statement(
+        &self,
+        statement: sea_query::SelectStatement,
+    ) -> sea_query::SelectStatement {
+        if self.related.is_empty() {
+            return statement;
+        }
+
+        let mut select = sea_query::Query::select();
+        select
+            .columns(
+                T::COLUMNS
+                    .iter()
+                    .map(|column| (sea_query::Alias::new(BASE_TABLE_ALIAS), column.name)),
+            )
+            .from_subquery(statement, sea_query::Alias::new(BASE_TABLE_ALIAS));
+
+        for (index, relation) in self.related.iter().enumerate() {
+            let alias = format!("cot__related_{index}");
+            select
+                .columns(
+                    relation
+                        .columns
+                        .iter()
+                        .map(|column| (sea_query::Alias::new(&alias), column.name)),
+                )
+                .join_as(
+                    sea_query::JoinType::LeftJoin,
+                    relation.table,
+                    sea_query::Alias::new(&alias),
+                    sea_query::Expr::col((
+                        sea_query::Alias::new(BASE_TABLE_ALIAS),
+                        relation.column,
+                    ))
+                    .equals((sea_query::Alias::new(&alias), relation.primary_key)),
+                );
+        }
+        for order_by in &self.order_by {
+            select.order_by(
+                (sea_query::Alias::new(BASE_TABLE_ALIAS), order_by.column),
+                order_by.as_sea_query_order(),
+            );
+        }
+
+        select
+    }
+
+    /// Creates a model instance from a row returned by a statement built with
+    /// [`Self::add_related_to_statement`], loading the related models.
+    pub(super) fn model_from_db(&self, db_row: Row) -> db::Result<T> {
+        if self.related.is_empty() {
+            return T::from_db(db_row);
+        }
+
+        let mut model = T::from_db(db_row.clone())?;
+        let mut offset = T::COLUMNS.len();
+        for relation in &self.related {
+            model.load_related(relation.column, &db_row.with_offset(offset))?;
+            offset += relation.columns.len();
+        }
+
+        Ok(model)
+    }
+
+    pub(super) fn has_lock(&self) -> bool {
+        self.lock.is_some()
+    }
+
+    pub(super) fn add_lock_to_statement(&self, statement: &mut sea_query::SelectStatement) {
+        match self.lock {
+            Some(RowLock::Wait) => {
+                statement.lock(sea_query::LockType::Update);
+            }
+            Some(RowLock::SkipLocked) => {
+                statement.lock_with_behavior(
+                    sea_query::LockType::Update,
+                    sea_query::LockBehavior::SkipLocked,
+                );
+            }
+            Some(RowLock::NoWait) => {
+                statement.lock_with_behavior(
+                    sea_query::LockType::Update,
+                    sea_query::LockBehavior::Nowait,
+                );
+            }
+            None => {}
+        }
+    }
+}
+
+/// Escapes the `LIKE` wildcards (`%` and `_`) and backslashes in `value`, so
+/// that it matches literally when used in an [`Expr::ILike`] pattern.
+///
+/// # Examples
+///
+/// ```
+/// use cot::db::query::escape_like;
+///
+/// assert_eq!(escape_like("100%"), r"100\%");
+/// ```
+#[must_use]
+pub fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for char in value.chars() {
+        if matches!(char, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(char);
+    }
+    escaped
+}
+
+/// Maps [`DatabaseError::NotFound`](db::DatabaseError::NotFound) returned when
+/// fetching a row which was reported to exist by a unique constraint
+/// violation (but has been deleted since) back to the violation.
+fn not_found_as_unique_violation(err: db::DatabaseError) -> db::DatabaseError {
+    match err {
+        db::DatabaseError::NotFound => db::DatabaseError::UniqueViolation,
+        err => err,
+    }
 }
 
 /// An expression that can be used to filter, update, or delete rows.
@@ -371,6 +1161,27 @@ pub enum Expr {
     /// );
     /// ```
     Or(Box<Expr>, Box<Expr>),
+    /// A `NOT` expression.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cot::db::{model, query};
+    /// use cot::db::query::{Expr, Query};
+    ///
+    /// #[model]
+    /// struct MyModel {
+    ///     #[model(primary_key)]
+    ///     id: i32,
+    /// };
+    ///
+    /// let expr = Expr::not(Expr::gt(Expr::field("id"), Expr::value(10)));
+    /// assert_eq!(
+    ///     <Query<MyModel>>::new().filter(expr),
+    ///     query!(MyModel, !($id > 10))
+    /// );
+    /// ```
+    Not(Box<Expr>),
     /// An `=` expression.
     ///
     /// # Example
@@ -503,6 +1314,94 @@ pub enum Expr {
     /// );
     /// ```
     Gte(Box<Expr>, Box<Expr>),
+    /// An `IN` expression.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cot::db::{model, query};
+    /// use cot::db::query::{Expr, Query};
+    ///
+    /// #[model]
+    /// struct MyModel {
+    ///     #[model(primary_key)]
+    ///     id: i32,
+    /// };
+    ///
+    /// let expr = Expr::is_in(Expr::field("id"), vec![Expr::value(1), Expr::value(2)]);
+    ///
+    /// assert_eq!(
+    ///     <Query<MyModel>>::new().filter(expr),
+    ///     query!(MyModel, $id in [1, 2])
+    /// );
+    /// ```
+    In(Box<Expr>, Vec<Expr>),
+    /// A `LIKE` expression.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cot::db::{model, query};
+    /// use cot::db::query::{Expr, Query};
+    ///
+    /// #[model]
+    /// struct MyModel {
+    ///     #[model(primary_key)]
+    ///     id: i32,
+    ///     name: String,
+    /// };
+    ///
+    /// let expr = Expr::like(Expr::field("name"), Expr::value("%foo%"));
+    ///
+    /// assert_eq!(
+    ///     <Query<MyModel>>::new().filter(expr),
+    ///     query!(MyModel, $name like "%foo%")
+    /// );
+    /// ```
+    Like(Box<Expr>, Box<Expr>),
+    /// A case-insensitive `LIKE` expression.
+    ///
+    /// This is translated to `LOWER(lhs) LIKE LOWER(rhs) ESCAPE '\'`, which
+    /// behaves the same way on all the supported database backends. A
+    /// backslash in the pattern makes the following `%`, `_` or backslash
+    /// match literally.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cot::db::model;
+    /// use cot::db::query::{Expr, Query};
+    ///
+    /// #[model]
+    /// struct MyModel {
+    ///     #[model(primary_key)]
+    ///     id: i32,
+    ///     name: String,
+    /// };
+    ///
+    /// let expr = Expr::ilike(Expr::field("name"), Expr::value("%foo%"));
+    /// let query = <Query<MyModel>>::new().filter(expr);
+    /// ```
+    ILike(Box<Expr>, Box<Expr>),
+    /// A `->>` expression, extracting a value from a JSON document as text.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cot::db::query::{Expr, Query};
+    /// use cot::db::{Json, model};
+    ///
+    /// #[model]
+    /// struct MyModel {
+    ///     #[model(primary_key)]
+    ///     id: i32,
+    ///     data: Json<serde_json::Value>,
+    /// };
+    ///
+    /// let expr = Expr::eq(Expr::json_get(Expr::field("data"), "name"), Expr::value("foo"));
+    /// let query = <Query<MyModel>>::new().filter(expr);
+    /// ```
+    JsonGet(Box<Expr>, String),
     /// A `+` expression.
     ///
     /// # Example
@@ -716,6 +1615,35 @@ impl Expr {
         Self::Or(Box::new(lhs), Box::new(rhs))
     }
 
+    /// Create a new `NOT` expression.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cot::db::{model, query};
+    /// use cot::db::query::{Expr, Query};
+    ///
+    /// #[model]
+    /// struct MyModel {
+    ///     #[model(primary_key)]
+    ///     id: i32,
+    /// };
+    ///
+    /// let expr = Expr::not(Expr::gt(Expr::field("id"), Expr::value(10)));
+    /// assert_eq!(
+    ///     <Query<MyModel>>::new().filter(expr),
+    ///     query!(MyModel, !($id > 10))
+    /// );
+    /// ```
+    #[must_use]
+    #[expect(
+        clippy::should_implement_trait,
+        reason = "constructor for the SQL operator, like `Expr::eq`"
+    )]
+    pub fn not(expr: Self) -> Self {
+        Self::Not(Box::new(expr))
+    }
+
     /// Create a new `=` expression.
     ///
     /// # Example
@@ -872,6 +1800,124 @@ impl Expr {
         Self::Gte(Box::new(lhs), Box::new(rhs))
     }
 
+    /// Create a new `IN` expression.
+    ///
+    /// An `IN` expression with an empty list of values never matches any row.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cot::db::{model, query};
+    /// use cot::db::query::{Expr, Query};
+    ///
+    /// #[model]
+    /// struct MyModel {
+    ///     #[model(primary_key)]
+    ///     id: i32,
+    /// };
+    ///
+    /// let expr = Expr::is_in(Expr::field("id"), vec![Expr::value(1), Expr::value(2)]);
+    ///
+    /// assert_eq!(
+    ///     <Query<MyModel>>::new().filter(expr),
+    ///     query!(MyModel, $id in [1, 2])
+    /// );
+    /// ```
+    #[must_use]
+    pub fn is_in(lhs: Self, values: Vec<Self>) -> Self {
+        Self::In(Box::new(lhs), values)
+    }
+
+    /// Create a new `LIKE` expression.
+    ///
+    /// In the pattern, `%` matches any sequence of characters and `_` matches
+    /// any single character. Note that the case sensitivity of the match
+    /// depends on the database: it is case-insensitive for ASCII characters on
+    /// SQLite and MySQL (with the default collation), and case-sensitive on
+    /// PostgreSQL.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cot::db::{model, query};
+    /// use cot::db::query::{Expr, Query};
+    ///
+    /// #[model]
+    /// struct MyModel {
+    ///     #[model(primary_key)]
+    ///     id: i32,
+    ///     name: String,
+    /// };
+    ///
+    /// let expr = Expr::like(Expr::field("name"), Expr::value("%foo%"));
+    ///
+    /// assert_eq!(
+    ///     <Query<MyModel>>::new().filter(expr),
+    ///     query!(MyModel, $name like "%foo%")
+    /// );
+    /// ```
+    #[must_use]
+    pub fn like(lhs: Self, rhs: Self) -> Self {
+        Self::Like(Box::new(lhs), Box::new(rhs))
+    }
+
+    /// Create a new case-insensitive `LIKE` expression.
+    ///
+    /// In the pattern, `%` matches any sequence of characters and `_` matches
+    /// any single character. To match these characters (or a backslash)
+    /// literally, prefix them with a backslash; [`escape_like`] does this for
+    /// a whole string.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cot::db::model;
+    /// use cot::db::query::{Expr, Query};
+    ///
+    /// #[model]
+    /// struct MyModel {
+    ///     #[model(primary_key)]
+    ///     id: i32,
+    ///     name: String,
+    /// };
+    ///
+    /// let expr = Expr::ilike(Expr::field("name"), Expr::value("%FOO%"));
+    /// let query = <Query<MyModel>>::new().filter(expr);
+    /// ```
+    #[must_use]
+    pub fn ilike(lhs: Self, rhs: Self) -> Self {
+        Self::ILike(Box::new(lhs), Box::new(rhs))
+    }
+
+    /// Create a new `->>` expression, extracting the value of the `key`
+    /// member of a JSON document as text.
+    ///
+    /// This is mostly useful with [`Json`](crate::db::Json) fields in
+    /// PostgreSQL, where the document is stored as `JSONB`. SQLite (since
+    /// version 3.38) supports the same operator. MySQL expects a JSON path
+    /// (such as `$.key`) instead of a plain key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cot::db::query::{Expr, Query};
+    /// use cot::db::{Json, model};
+    ///
+    /// #[model]
+    /// struct MyModel {
+    ///     #[model(primary_key)]
+    ///     id: i32,
+    ///     data: Json<serde_json::Value>,
+    /// };
+    ///
+    /// let expr = Expr::eq(Expr::json_get(Expr::field("data"), "name"), Expr::value("foo"));
+    /// let query = <Query<MyModel>>::new().filter(expr);
+    /// ```
+    #[must_use]
+    pub fn json_get(lhs: Self, key: impl Into<String>) -> Self {
+        Self::JsonGet(Box::new(lhs), key.into())
+    }
+
     /// Create a new `+` expression.
     ///
     /// # Example
@@ -1010,12 +2056,32 @@ impl Expr {
             Self::Value(value) => (*value).clone().into(),
             Self::And(lhs, rhs) => lhs.as_sea_query_expr().and(rhs.as_sea_query_expr()),
             Self::Or(lhs, rhs) => lhs.as_sea_query_expr().or(rhs.as_sea_query_expr()),
+            Self::Not(expr) => expr.as_sea_query_expr().not(),
             Self::Eq(lhs, rhs) => lhs.as_sea_query_expr().eq(rhs.as_sea_query_expr()),
             Self::Ne(lhs, rhs) => lhs.as_sea_query_expr().ne(rhs.as_sea_query_expr()),
             Self::Lt(lhs, rhs) => lhs.as_sea_query_expr().lt(rhs.as_sea_query_expr()),
             Self::Lte(lhs, rhs) => lhs.as_sea_query_expr().lte(rhs.as_sea_query_expr()),
             Self::Gt(lhs, rhs) => lhs.as_sea_query_expr().gt(rhs.as_sea_query_expr()),
             Self::Gte(lhs, rhs) => lhs.as_sea_query_expr().gte(rhs.as_sea_query_expr()),
+            Self::In(lhs, values) => lhs
+                .as_sea_query_expr()
+                .is_in(values.iter().map(Self::as_sea_query_expr)),
+            Self::Like(lhs, rhs) => lhs
+                .as_sea_query_expr()
+                .binary(sea_query::BinOper::Like, rhs.as_sea_query_expr()),
+            Self::ILike(lhs, rhs) => {
+                let pattern = sea_query::SimpleExpr::Binary(
+                    Box::new(sea_query::Func::lower(rhs.as_sea_query_expr()).into()),
+                    sea_query::BinOper::Escape,
+                    Box::new(sea_query::SimpleExpr::Constant('\\'.into())),
+                );
+                sea_query::SimpleExpr::from(sea_query::Func::lower(lhs.as_sea_query_expr()))
+                    .binary(sea_query::BinOper::Like, pattern)
+            }
+            Self::JsonGet(lhs, key) => lhs.as_sea_query_expr().binary(
+                sea_query::BinOper::Custom("->>"),
+                sea_query::SimpleExpr::from(key.clone()),
+            ),
             Self::Add(lhs, rhs) => lhs.as_sea_query_expr().add(rhs.as_sea_query_expr()),
             Self::Sub(lhs, rhs) => lhs.as_sea_query_expr().sub(rhs.as_sea_query_expr()),
             Self::Mul(lhs, rhs) => lhs.as_sea_query_expr().mul(rhs.as_sea_query_expr()),
@@ -1052,6 +2118,187 @@ impl<T> FieldRef<T> {
     pub fn as_expr(&self) -> Expr {
         Expr::Field(self.identifier)
     }
+
+    /// Returns an ascending ordering by this field, to be used with
+    /// [`Query::order_by`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::query::OrderBy;
+    /// use cot::db::{Identifier, Model, model};
+    ///
+    /// #[model]
+    /// struct MyModel {
+    ///     #[model(primary_key)]
+    ///     id: i32,
+    ///     name: String,
+    /// };
+    ///
+    /// assert_eq!(
+    ///     <MyModel as Model>::Fields::name.asc(),
+    ///     OrderBy::asc(Identifier::new("name"))
+    /// );
+    /// ```
+    #[must_use]
+    pub fn asc(&self) -> OrderBy {
+        OrderBy::asc(self.identifier)
+    }
+
+    /// Returns a descending ordering by this field, to be used with
+    /// [`Query::order_by`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::query::OrderBy;
+    /// use cot::db::{Identifier, Model, model};
+    ///
+    /// #[model]
+    /// struct MyModel {
+    ///     #[model(primary_key)]
+    ///     id: i32,
+    ///     name: String,
+    /// };
+    ///
+    /// assert_eq!(
+    ///     <MyModel as Model>::Fields::name.desc(),
+    ///     OrderBy::desc(Identifier::new("name"))
+    /// );
+    /// ```
+    #[must_use]
+    pub fn desc(&self) -> OrderBy {
+        OrderBy::desc(self.identifier)
+    }
+}
+
+/// An ordering of the query results by a single column.
+///
+/// Typically created with [`FieldRef::asc`] or [`FieldRef::desc`], and passed
+/// to [`Query::order_by`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct OrderBy {
+    column: Identifier,
+    descending: bool,
+}
+
+impl OrderBy {
+    /// Creates an ascending ordering by the given column.
+    #[must_use]
+    pub const fn asc(column: Identifier) -> Self {
+        Self {
+            column,
+            descending: false,
+        }
+    }
+
+    /// Creates a descending ordering by the given column.
+    #[must_use]
+    pub const fn desc(column: Identifier) -> Self {
+        Self {
+            column,
+            descending: true,
+        }
+    }
+
+    fn as_sea_query_order(self) -> sea_query::Order {
+        if self.descending {
+            sea_query::Order::Desc
+        } else {
+            sea_query::Order::Asc
+        }
+    }
+}
+
+impl<T: ToDbFieldValue + 'static> FieldRef<T> {
+    /// Creates an assignment that sets the field to the given value, to be
+    /// used with [`Query::update`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::query::{Assignment, Expr};
+    /// use cot::db::{Identifier, model};
+    ///
+    /// #[model]
+    /// struct MyModel {
+    ///     #[model(primary_key)]
+    ///     id: i32,
+    ///     name: String,
+    /// };
+    ///
+    /// let assignment = <MyModel as cot::db::Model>::Fields::name.set("John");
+    ///
+    /// assert_eq!(
+    ///     assignment,
+    ///     Assignment::new(Identifier::new("name"), Expr::value("John"))
+    /// );
+    /// ```
+    #[must_use]
+    pub fn set<V: IntoField<T>>(self, value: V) -> Assignment {
+        Assignment::new(self.identifier, Expr::value(value.into_field()))
+    }
+
+    /// Creates an assignment that sets the field to the result of the given
+    /// expression, to be used with [`Query::update`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::model;
+    /// use cot::db::query::{Expr, ExprAdd};
+    ///
+    /// #[model]
+    /// struct MyModel {
+    ///     #[model(primary_key)]
+    ///     id: i32,
+    ///     views: i64,
+    /// };
+    ///
+    /// // views = views + 1
+    /// let assignment = <MyModel as cot::db::Model>::Fields::views
+    ///     .set_expr(<MyModel as cot::db::Model>::Fields::views.add(1));
+    /// ```
+    #[must_use]
+    pub fn set_expr(self, expr: Expr) -> Assignment {
+        Assignment::new(self.identifier, expr)
+    }
+}
+
+/// A single `column = value` assignment in a bulk update query.
+///
+/// Typically created with [`FieldRef::set`] or [`FieldRef::set_expr`], and
+/// passed to [`Query::update`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assignment {
+    identifier: Identifier,
+    value: Expr,
+}
+
+impl Assignment {
+    /// Creates a new assignment that sets the given column to the result of
+    /// the given expression.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::Identifier;
+    /// use cot::db::query::{Assignment, Expr};
+    ///
+    /// let assignment = Assignment::new(Identifier::new("name"), Expr::value("John"));
+    /// ```
+    #[must_use]
+    pub fn new(identifier: Identifier, value: Expr) -> Self {
+        Self { identifier, value }
+    }
+
+    pub(super) fn identifier(&self) -> Identifier {
+        self.identifier
+    }
+
+    pub(super) fn as_sea_query_value(&self) -> (Identifier, sea_query::SimpleExpr) {
+        (self.identifier, self.value.as_sea_query_expr())
+    }
 }
 
 /// A trait for types that can be compared in database expressions.
@@ -1335,6 +2582,99 @@ impl<T: ToDbFieldValue + Ord + 'static> ExprOrd<T> for FieldRef<T> {
     }
 }
 
+/// A trait for database types that can be checked for being in a list of
+/// values.
+pub trait ExprIn<T> {
+    /// Creates an expression that checks if the field is equal to any of the
+    /// given values.
+    ///
+    /// If `values` is empty, the expression never matches any row.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::query::{Expr, ExprIn, Query};
+    /// use cot::db::{model, query};
+    ///
+    /// #[model]
+    /// struct MyModel {
+    ///     #[model(primary_key)]
+    ///     id: i32,
+    /// };
+    ///
+    /// let expr = <MyModel as cot::db::Model>::Fields::id.is_in([1, 2, 3]);
+    ///
+    /// assert_eq!(
+    ///     <Query<MyModel>>::new().filter(expr),
+    ///     query!(MyModel, $id in [1, 2, 3])
+    /// );
+    /// ```
+    #[expect(
+        clippy::wrong_self_convention,
+        reason = "mirrors the SQL operator name, as in sea-query"
+    )]
+    fn is_in<I, V>(self, values: I) -> Expr
+    where
+        I: IntoIterator<Item = V>,
+        V: IntoField<T>;
+}
+
+impl<T: ToDbFieldValue + 'static> ExprIn<T> for FieldRef<T> {
+    fn is_in<I, V>(self, values: I) -> Expr
+    where
+        I: IntoIterator<Item = V>,
+        V: IntoField<T>,
+    {
+        let values = values
+            .into_iter()
+            .map(|value| Expr::value(value.into_field()))
+            .collect();
+        Expr::is_in(self.as_expr(), values)
+    }
+}
+
+/// A trait for database types that can be matched against a `LIKE` pattern.
+pub trait ExprLike {
+    /// Creates an expression that checks if the field matches the given
+    /// `LIKE` pattern.
+    ///
+    /// See [`Expr::like`] for the pattern syntax.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::query::{Expr, ExprLike, Query};
+    /// use cot::db::{model, query};
+    ///
+    /// #[model]
+    /// struct MyModel {
+    ///     #[model(primary_key)]
+    ///     id: i32,
+    ///     name: String,
+    /// };
+    ///
+    /// let expr = <MyModel as cot::db::Model>::Fields::name.like("%foo%");
+    ///
+    /// assert_eq!(
+    ///     <Query<MyModel>>::new().filter(expr),
+    ///     query!(MyModel, $name like "%foo%")
+    /// );
+    /// ```
+    fn like<V: Into<String>>(self, pattern: V) -> Expr;
+}
+
+impl ExprLike for FieldRef<String> {
+    fn like<V: Into<String>>(self, pattern: V) -> Expr {
+        Expr::like(self.as_expr(), Expr::value(pattern.into()))
+    }
+}
+
+impl<const LIMIT: u32> ExprLike for FieldRef<LimitedString<LIMIT>> {
+    fn like<V: Into<String>>(self, pattern: V) -> Expr {
+        Expr::like(self.as_expr(), Expr::value(pattern.into()))
+    }
+}
+
 macro_rules! impl_expr {
     ($ty:ty, $trait:ident, $method:ident) => {
         impl $trait<$ty> for FieldRef<$ty> {
@@ -1478,6 +2818,182 @@ mod tests {
         assert_eq!(query.offset.unwrap(), 10);
     }
 
+    #[test]
+    fn query_order_by() {
+        let mut query: Query<MockModel> = Query::new();
+        query
+            .order_by(<MockModel as Model>::Fields::id.desc())
+            .order_by(OrderBy::asc(Identifier::new("name")));
+
+        let mut statement = sea_query::Query::select();
+        statement.column("id").from("test");
+        query.add_order_by_to_statement(&mut statement);
+
+        assert_eq!(
+            statement.to_string(sea_query::SqliteQueryBuilder),
+            r#"SELECT "id" FROM "test" ORDER BY "id" DESC, "name" ASC"#
+        );
+    }
+
+    #[test]
+    fn expr_in_like_sql() {
+        let to_sql = |expr: Expr| {
+            sea_query::Query::select()
+                .column("id")
+                .from("test")
+                .and_where(expr.as_sea_query_expr())
+                .to_string(sea_query::SqliteQueryBuilder)
+        };
+
+        assert_eq!(
+            to_sql(Expr::is_in(
+                Expr::field("id"),
+                vec![Expr::value(1), Expr::value(2)]
+            )),
+            r#"SELECT "id" FROM "test" WHERE "id" IN (1, 2)"#
+        );
+        assert_eq!(
+            to_sql(Expr::is_in(Expr::field("id"), Vec::new())),
+            r#"SELECT "id" FROM "test" WHERE 1 = 2"#
+        );
+        assert_eq!(
+            to_sql(Expr::like(Expr::field("name"), Expr::value("%foo%"))),
+            r#"SELECT "id" FROM "test" WHERE "name" LIKE '%foo%'"#
+        );
+        assert_eq!(
+            to_sql(Expr::ilike(Expr::field("name"), Expr::value("%Foo%"))),
+            r#"SELECT "id" FROM "test" WHERE LOWER("name") LIKE LOWER('%Foo%') ESCAPE '\'"#
+        );
+    }
+
+    #[test]
+    fn escape_like_special_characters() {
+        assert_eq!(escape_like("foo"), "foo");
+        assert_eq!(escape_like(r"100%_a\b"), r"100\%\_a\\b");
+    }
+
+    #[test]
+    fn expr_json_get_sql() {
+        let expr = Expr::eq(
+            Expr::json_get(Expr::field("data"), "name"),
+            Expr::value("foo"),
+        );
+
+        let sql = sea_query::Query::select()
+            .column("id")
+            .from("test")
+            .and_where(expr.as_sea_query_expr())
+            .to_string(sea_query::PostgresQueryBuilder);
+
+        assert!(sql.contains(r#""data" ->> 'name'"#), "{sql}");
+        assert!(sql.ends_with("= 'foo'"), "{sql}");
+    }
+
+    #[test]
+    fn query_bool_combinators_sql() {
+        #[model]
+        struct Todo {
+            #[model(primary_key)]
+            id: i32,
+            title: String,
+            done: bool,
+        }
+
+        let to_sql = |query: &Query<Todo>| {
+            let mut statement = sea_query::Query::select();
+            statement.column("id").from("todo");
+            query.add_filter_to_statement(&mut statement);
+            statement.to_string(sea_query::SqliteQueryBuilder)
+        };
+
+        assert_eq!(
+            to_sql(cot_macros::query!(Todo, $done == true && $title like "%x%")),
+            r#"SELECT "id" FROM "todo" WHERE "done" = TRUE AND "title" LIKE '%x%'"#
+        );
+        assert_eq!(
+            to_sql(cot_macros::query!(Todo, $id == 1 || $id == 2 && !$done)),
+            r#"SELECT "id" FROM "todo" WHERE "id" = 1 OR ("id" = 2 AND (NOT "done"))"#
+        );
+        assert_eq!(
+            to_sql(cot_macros::query!(Todo, ($id == 1 || $id == 2) && !$done)),
+            r#"SELECT "id" FROM "todo" WHERE ("id" = 1 OR "id" = 2) AND (NOT "done")"#
+        );
+        assert_eq!(
+            to_sql(cot_macros::query!(Todo, !($id == 1 || $title == "a"))),
+            r#"SELECT "id" FROM "todo" WHERE NOT ("id" = 1 OR "title" = 'a')"#
+        );
+    }
+
+    #[test]
+    fn query_select_related_sql() {
+        #[model]
+        struct Author {
+            #[model(primary_key)]
+            id: i32,
+        }
+
+        #[model]
+        struct Book {
+            #[model(primary_key)]
+            id: i32,
+            author: ForeignKey<Author>,
+        }
+
+        let mut query = Query::<Book>::new();
+        query
+            .filter(Expr::eq(Expr::field("id"), Expr::value(1)))
+            .select_related(<Book as Model>::Fields::author)
+            .select_related(<Book as Model>::Fields::author);
+
+        let mut statement = sea_query::Query::select();
+        statement.columns(["id", "author"]).from(Book::TABLE_NAME);
+        query.add_filter_to_statement(&mut statement);
+        let statement = query.add_related_to_statement(statement);
+
+        assert_eq!(
+            statement.to_string(sea_query::SqliteQueryBuilder),
+            r#"SELECT "cot__base"."id", "cot__base"."author", "cot__related_0"."id" FROM (SELECT "id", "author" FROM "cot__book" WHERE "id" = 1) AS "cot__base" LEFT JOIN "cot__author" AS "cot__related_0" ON "cot__base"."author" = "cot__related_0"."id""#
+        );
+    }
+
+    #[test]
+    fn query_for_update() {
+        let mut query: Query<MockModel> = Query::new();
+        assert!(!query.has_lock());
+
+        query.for_update();
+        assert_eq!(query.lock, Some(RowLock::Wait));
+        query.skip_locked();
+        assert_eq!(query.lock, Some(RowLock::SkipLocked));
+        query.nowait();
+        assert_eq!(query.lock, Some(RowLock::NoWait));
+    }
+
+    #[test]
+    fn query_add_lock_to_statement() {
+        let to_sql = |query: &Query<MockModel>| {
+            let mut select = sea_query::Query::select();
+            select.column("id").from("test");
+            query.add_lock_to_statement(&mut select);
+            select.to_string(sea_query::PostgresQueryBuilder)
+        };
+
+        let mut query: Query<MockModel> = Query::new();
+        assert_eq!(to_sql(&query), r#"SELECT "id" FROM "test""#);
+        assert_eq!(
+            to_sql(query.for_update()),
+            r#"SELECT "id" FROM "test" FOR UPDATE"#
+        );
+        assert_eq!(
+            to_sql(query.skip_locked()),
+            r#"SELECT "id" FROM "test" FOR UPDATE SKIP LOCKED"#
+        );
+        assert_eq!(
+            to_sql(query.nowait()),
+            r#"SELECT "id" FROM "test" FOR UPDATE NOWAIT"#
+        );
+    }
+
     #[cot::test]
     async fn query_all() {
         let mut db = MockDatabaseBackend::new();
@@ -1490,16 +3006,66 @@ mod tests {
     }
 
     #[cot::test]
-    async fn query_get() {
+    async fn query_first() {
         let mut db = MockDatabaseBackend::new();
         db.expect_get().returning(|_| Ok(Option::<MockModel>::None));
         let query: Query<MockModel> = Query::new();
 
-        let result = query.get(&db).await;
+        let result = query.first(&db).await;
 
         assert_eq!(result.unwrap(), Option::<MockModel>::None);
     }
 
+    #[cot::test]
+    async fn query_get() {
+        let mut db = MockDatabaseBackend::new();
+        db.expect_query()
+            .withf(|query: &Query<MockModel>| query.limit == Some(2))
+            .returning(|_| Ok(vec![MockModel { id: 1 }]));
+        let query: Query<MockModel> = Query::new();
+
+        let result = query.get(&db).await;
+
+        assert_eq!(result.unwrap(), MockModel { id: 1 });
+    }
+
+    #[cot::test]
+    async fn query_get_not_found() {
+        let mut db = MockDatabaseBackend::new();
+        db.expect_query().returning(|_| Ok(Vec::<MockModel>::new()));
+        let query: Query<MockModel> = Query::new();
+
+        let result = query.get(&db).await;
+
+        assert!(matches!(result, Err(db::DatabaseError::NotFound)));
+    }
+
+    #[cot::test]
+    async fn query_get_multiple_objects() {
+        let mut db = MockDatabaseBackend::new();
+        db.expect_query()
+            .returning(|_| Ok(vec![MockModel { id: 1 }, MockModel { id: 2 }]));
+        let query: Query<MockModel> = Query::new();
+
+        let result = query.get(&db).await;
+
+        assert!(matches!(result, Err(db::DatabaseError::MultipleObjects)));
+    }
+
+    #[cot::test]
+    async fn query_get_multiple_objects_with_limit() {
+        let mut db = MockDatabaseBackend::new();
+        db.expect_query()
+            .withf(|query: &Query<MockModel>| query.limit == Some(2))
+            .returning(|_| Ok(vec![MockModel { id: 1 }, MockModel { id: 2 }]));
+        let mut query: Query<MockModel> = Query::new();
+        query.limit(1);
+
+        let result = query.get(&db).await;
+
+        assert!(matches!(result, Err(db::DatabaseError::MultipleObjects)));
+    }
+
     #[cot::test]
     async fn query_exists() {
         let mut db = MockDatabaseBackend::new();
@@ -1524,6 +3090,66 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[cot::test]
+    async fn query_delete_returning() {
+        let mut db = MockDatabaseBackend::new();
+        db.expect_delete_returning()
+            .returning(|_: &Query<MockModel>| Ok(Vec::new()));
+        let query: Query<MockModel> = Query::new();
+
+        let result = query.delete_returning(&db).await;
+
+        assert!(result.unwrap().is_empty());
+    }
+
+    #[cot::test]
+    async fn query_update() {
+        let mut db = MockDatabaseBackend::new();
+        db.expect_bulk_update()
+            .withf(|_: &Query<MockModel>, assignments: &[Assignment]| {
+                assignments
+                    == [Assignment::new(
+                        Identifier::new("name"),
+                        Expr::value("John"),
+                    )]
+            })
+            .returning(|_: &Query<MockModel>, _| Ok(StatementResult::new(RowsNum(3))));
+        let query: Query<MockModel> = Query::new();
+
+        let result = query
+            .update(
+                &db,
+                &[Assignment::new(
+                    Identifier::new("name"),
+                    Expr::value("John"),
+                )],
+            )
+            .await;
+
+        assert_eq!(result.unwrap().rows_affected(), RowsNum(3));
+    }
+
+    #[test]
+    fn field_ref_set() {
+        let field = FieldRef::<String>::new(Identifier::new("name"));
+
+        assert_eq!(
+            field.set("John"),
+            Assignment::new(Identifier::new("name"), Expr::value("John"))
+        );
+    }
+
+    #[test]
+    fn field_ref_set_expr() {
+        let field = FieldRef::<i32>::new(Identifier::new("views"));
+        let expr = Expr::add(Expr::field("views"), Expr::value(1));
+
+        assert_eq!(
+            field.set_expr(expr.clone()),
+            Assignment::new(Identifier::new("views"), expr)
+        );
+    }
+
     #[test]
     fn expr_field() {
         let expr = Expr::field("name");
@@ -1,4 +1,6 @@
-use crate::db::{DatabaseBackend, DatabaseError, Model, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::db::{DatabaseBackend, DatabaseError, Model, Result, Row};
 
 /// A foreign key to another model.
 ///
@@ -50,9 +52,9 @@ pub enum ForeignKey<T: Model> {
 
 impl<T: Model> ForeignKey<T> {
     /// Returns the primary key of the referenced model.
-    pub fn primary_key(&self) -> &T::PrimaryKey {
+    pub fn primary_key(&self) -> T::PrimaryKey {
         match self {
-            Self::PrimaryKey(pk) => pk,
+            Self::PrimaryKey(pk) => pk.clone(),
             Self::Model(model) => model.primary_key(),
         }
     }
@@ -109,6 +111,46 @@ impl<T: Model> ForeignKey<T> {
     }
 }
 
+/// A model field that references another model and can be eagerly loaded
+/// with [`Query::select_related`](crate::db::query::Query::select_related).
+///
+/// This is implemented for [`ForeignKey<T>`] and `Option<ForeignKey<T>>`.
+pub trait RelatedField {
+    /// The model referenced by this field.
+    type Target: Model;
+
+    /// Replaces the stored primary key with the referenced model read from
+    /// the given row.
+    ///
+    /// If the field is an `Option` set to [`None`], the row is ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data in the row is not compatible with the
+    /// referenced model.
+    fn load_related(&mut self, db_row: &Row) -> Result<()>;
+}
+
+impl<T: Model> RelatedField for ForeignKey<T> {
+    type Target = T;
+
+    fn load_related(&mut self, db_row: &Row) -> Result<()> {
+        *self = Self::Model(Box::new(T::from_db(db_row.clone())?));
+        Ok(())
+    }
+}
+
+impl<T: Model> RelatedField for Option<ForeignKey<T>> {
+    type Target = T;
+
+    fn load_related(&mut self, db_row: &Row) -> Result<()> {
+        match self {
+            Some(foreign_key) => foreign_key.load_related(db_row),
+            None => Ok(()),
+        }
+    }
+}
+
 impl<T: Model> PartialEq for ForeignKey<T>
 where
     T::PrimaryKey: PartialEq,
@@ -128,7 +170,31 @@ impl<T: Model> From<T> for ForeignKey<T> {
 
 impl<T: Model> From<&T> for ForeignKey<T> {
     fn from(model: &T) -> Self {
-        Self::PrimaryKey(model.primary_key().clone())
+        Self::PrimaryKey(model.primary_key())
+    }
+}
+
+impl<T: Model> Serialize for ForeignKey<T>
+where
+    T::PrimaryKey: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.primary_key().serialize(serializer)
+    }
+}
+
+impl<'de, T: Model> Deserialize<'de> for ForeignKey<T>
+where
+    T::PrimaryKey: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::PrimaryKey::deserialize(deserializer).map(Self::PrimaryKey)
     }
 }
 
@@ -213,7 +279,21 @@ mod tests {
     fn test_primary_key() {
         let fk = ForeignKey::<TestModel>::PrimaryKey(Auto::fixed(1));
 
-        assert_eq!(fk.primary_key(), &Auto::fixed(1));
+        assert_eq!(fk.primary_key(), Auto::fixed(1));
+    }
+
+    #[test]
+    fn serialize_primary_key() {
+        let fk = ForeignKey::<TestModel>::Model(Box::new(TestModel { id: Auto::fixed(1) }));
+
+        assert_eq!(serde_json::to_string(&fk).unwrap(), "1");
+    }
+
+    #[test]
+    fn deserialize_primary_key() {
+        let fk: ForeignKey<TestModel> = serde_json::from_str("1").unwrap();
+
+        assert_eq!(fk, ForeignKey::PrimaryKey(Auto::fixed(1)));
     }
 
     #[test]
@@ -222,7 +302,7 @@ mod tests {
         let fk = ForeignKey::Model(Box::new(model.clone()));
 
         assert_eq!(fk.model().unwrap(), &model);
-        assert_eq!(fk.primary_key(), &Auto::fixed(1));
+        assert_eq!(fk.primary_key(), Auto::fixed(1));
     }
 
     #[test]
@@ -261,6 +341,6 @@ mod tests {
         let model = TestModel { id: Auto::fixed(1) };
         let fk: ForeignKey<TestModel> = ForeignKey::from(&model);
 
-        assert_eq!(fk.primary_key(), &Auto::fixed(1));
+        assert_eq!(fk.primary_key(), Auto::fixed(1));
     }
 }
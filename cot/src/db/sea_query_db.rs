@@ -4,6 +4,7 @@
 /// they need to be implemented in a separate `impl` block. These methods are:
 /// * `prepare_values`
 /// * `sea_query_column_type_for`
+/// * `begin_transaction`
 macro_rules! impl_sea_query_db_backend {
     ($db_name:ident : $sqlx_db_ty:ty, $pool_ty:ty, $row_name:ident, $value_ref_name:ident, $query_builder:expr) => {
         /// A wrapper over [`$sqlx_db_ty`] that serves an in internal implementation of
@@ -11,17 +12,82 @@ macro_rules! impl_sea_query_db_backend {
         #[derive(Debug)]
         pub(super) struct $db_name {
             db_connection: $pool_ty,
+            query_hooks: std::sync::Arc<crate::db::QueryHooks>,
+            query_logging: crate::db::QueryLogging,
+            /// The transaction all the statements are executed in, if the
+            /// backend has been created with [`Self::begin`]. It's `None`
+            /// once the transaction has been committed or rolled back.
+            transaction: Option<tokio::sync::Mutex<Option<sqlx::Transaction<'static, $sqlx_db_ty>>>>,
         }
 
         impl $db_name {
-            pub(super) async fn new(url: &str) -> crate::db::Result<Self> {
-                let db_connection = <$pool_ty>::connect(url).await?;
+            pub(super) async fn new(
+                url: &str,
+                statement_cache_capacity: Option<usize>,
+                query_logging: crate::db::QueryLogging,
+            ) -> crate::db::Result<Self> {
+                let mut options: <<$sqlx_db_ty as sqlx::Database>::Connection as sqlx::Connection>::Options =
+                    url.parse()?;
+                if let Some(capacity) = statement_cache_capacity {
+                    options = options.statement_cache_capacity(capacity);
+                }
+                let db_connection = <$pool_ty>::connect_with(options).await?;
 
-                let db = Self { db_connection };
+                let db = Self {
+                    db_connection,
+                    query_hooks: std::sync::Arc::default(),
+                    query_logging,
+                    transaction: None,
+                };
                 db.init().await?;
                 Ok(db)
             }
 
+            pub(super) fn query_hooks(&self) -> &crate::db::QueryHooks {
+                &self.query_hooks
+            }
+
+            /// Starts a transaction and returns a backend executing all the
+            /// statements in it.
+            pub(super) async fn begin(&self) -> crate::db::Result<Self> {
+                if self.transaction.is_some() {
+                    return Err(crate::db::DatabaseError::NestedTransaction);
+                }
+
+                let transaction = Self::begin_transaction(&self.db_connection).await?;
+                Ok(Self {
+                    db_connection: self.db_connection.clone(),
+                    query_hooks: std::sync::Arc::clone(&self.query_hooks),
+                    query_logging: self.query_logging,
+                    transaction: Some(tokio::sync::Mutex::new(Some(transaction))),
+                })
+            }
+
+            pub(super) fn in_transaction(&self) -> bool {
+                self.transaction.is_some()
+            }
+
+            pub(super) async fn commit(&self) -> crate::db::Result<()> {
+                self.take_transaction().await?.commit().await?;
+                Ok(())
+            }
+
+            pub(super) async fn rollback(&self) -> crate::db::Result<()> {
+                self.take_transaction().await?.rollback().await?;
+                Ok(())
+            }
+
+            async fn take_transaction(
+                &self,
+            ) -> crate::db::Result<sqlx::Transaction<'static, $sqlx_db_ty>> {
+                let transaction = match &self.transaction {
+                    Some(transaction) => transaction.lock().await.take(),
+                    None => None,
+                };
+
+                transaction.ok_or(crate::db::DatabaseError::TransactionFinished)
+            }
+
             pub(super) async fn close(&self) -> crate::db::Result<()> {
                 self.db_connection.close().await;
                 Ok(())
@@ -32,11 +98,14 @@ macro_rules! impl_sea_query_db_backend {
                 statement: &T,
             ) -> crate::db::Result<Option<$row_name>> {
                 let (sql, values) = Self::build_sql(statement);
+                let query_values = values.0.clone();
 
-                let row = Self::sqlx_query_with(&sql, values)
-                    .fetch_optional(&self.db_connection)
-                    .await
-                    .map_err(|err| crate::db::sea_query_db::map_sqlx_error(err))?;
+                let query = Self::sqlx_query_with(&sql, values);
+                let row = crate::db::sea_query_db::with_executor!(self, |executor| {
+                    self.observe_query(&sql, query_values, query.fetch_optional(executor))
+                        .await
+                })
+                .map_err(|err| crate::db::sea_query_db::map_sqlx_error(err))?;
                 Ok(row.map($row_name::new))
             }
 
@@ -46,12 +115,24 @@ macro_rules! impl_sea_query_db_backend {
             ) -> crate::db::Result<Vec<$row_name>> {
                 let (sql, values) = Self::build_sql(statement);
 
-                let result = Self::sqlx_query_with(&sql, values)
-                    .fetch_all(&self.db_connection)
-                    .await?
-                    .into_iter()
-                    .map($row_name::new)
-                    .collect();
+                self.raw_fetch_all(&sql, values).await
+            }
+
+            pub(super) async fn raw_fetch_all(
+                &self,
+                sql: &str,
+                values: sea_query_sqlx::SqlxValues,
+            ) -> crate::db::Result<Vec<$row_name>> {
+                let query_values = values.0.clone();
+
+                let query = Self::sqlx_query_with(sql, values);
+                let result = crate::db::sea_query_db::with_executor!(self, |executor| {
+                    self.observe_query(sql, query_values, query.fetch_all(executor))
+                        .await
+                })?
+                .into_iter()
+                .map($row_name::new)
+                .collect();
                 Ok(result)
             }
 
@@ -60,20 +141,31 @@ macro_rules! impl_sea_query_db_backend {
                 statement: &T,
             ) -> crate::db::Result<crate::db::StatementResult> {
                 let (sql, mut values) = Self::build_sql(statement);
+                let query_values = values.0.clone();
                 Self::prepare_values(&mut values);
 
-                self.execute_sqlx(Self::sqlx_query_with(&sql, values)).await
+                self.execute_sqlx(&sql, query_values, Self::sqlx_query_with(&sql, values))
+                    .await
             }
 
             pub(super) async fn execute_schema<T: sea_query::SchemaStatementBuilder>(
                 &self,
                 statement: T,
             ) -> crate::db::Result<crate::db::StatementResult> {
-                let sql = statement.build($query_builder);
-                tracing::debug!("Schema modification: {}", sql);
+                let sql = Self::build_schema(statement);
 
-                self.execute_sqlx(sqlx::query(sqlx::AssertSqlSafe(sql)))
-                    .await
+                self.execute_sqlx(
+                    &sql,
+                    sea_query::Values(Vec::new()),
+                    sqlx::query(sqlx::AssertSqlSafe(sql.clone())),
+                )
+                .await
+            }
+
+            pub(super) fn build_schema<T: sea_query::SchemaStatementBuilder>(
+                statement: T,
+            ) -> String {
+                statement.build($query_builder)
             }
 
             pub(super) async fn raw_with(
@@ -81,20 +173,26 @@ macro_rules! impl_sea_query_db_backend {
                 sql: &str,
                 values: sea_query_sqlx::SqlxValues,
             ) -> crate::db::Result<crate::db::StatementResult> {
-                self.execute_sqlx(Self::sqlx_query_with(sql, values)).await
+                let query_values = values.0.clone();
+
+                self.execute_sqlx(sql, query_values, Self::sqlx_query_with(sql, values))
+                    .await
             }
 
             async fn execute_sqlx<'a, A>(
                 &self,
+                sql: &str,
+                values: sea_query::Values,
                 sqlx_statement: sqlx::query::Query<'a, $sqlx_db_ty, A>,
             ) -> crate::db::Result<crate::db::StatementResult>
             where
                 A: 'a + sqlx::IntoArguments<$sqlx_db_ty>,
             {
-                let result = sqlx_statement
-                    .execute(&self.db_connection)
-                    .await
-                    .map_err(|err| crate::db::sea_query_db::map_sqlx_error(err))?;
+                let result = crate::db::sea_query_db::with_executor!(self, |executor| {
+                    self.observe_query(sql, values, sqlx_statement.execute(executor))
+                        .await
+                })
+                .map_err(|err| crate::db::sea_query_db::map_sqlx_error(err))?;
                 let result = crate::db::StatementResult {
                     rows_affected: crate::db::RowsNum(result.rows_affected()),
                     last_inserted_row_id: Self::last_inserted_row_id_for(&result),
@@ -104,6 +202,25 @@ macro_rules! impl_sea_query_db_backend {
                 Ok(result)
             }
 
+            /// Awaits the given query future, measuring how long it took, and
+            /// reports the executed statement to the query log and to the
+            /// registered query hooks.
+            async fn observe_query<F: std::future::Future>(
+                &self,
+                sql: &str,
+                values: sea_query::Values,
+                query: F,
+            ) -> F::Output {
+                let start = std::time::Instant::now();
+                let result = query.await;
+                let record = crate::db::QueryRecord::new(sql, values.0, start.elapsed());
+
+                self.query_logging.log(&record);
+                self.query_hooks.notify(&record);
+
+                result
+            }
+
             fn build_sql<T>(statement: &T) -> (String, sea_query_sqlx::SqlxValues)
             where
                 T: sea_query_sqlx::SqlxBinder + Send + Sync,
@@ -118,7 +235,6 @@ macro_rules! impl_sea_query_db_backend {
                 mut values: sea_query_sqlx::SqlxValues,
             ) -> sqlx::query::Query<'_, $sqlx_db_ty, sea_query_sqlx::SqlxValues> {
                 Self::prepare_values(&mut values);
-                tracing::debug!("Query: `{}` (values: {:?})", sql, values);
 
                 sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
             }
@@ -127,16 +243,30 @@ macro_rules! impl_sea_query_db_backend {
         #[doc = "A wrapper for the internal row type used by [`"]
         #[doc = stringify!($sqlx_db_ty)]
         #[doc = "`] to provide a unified interface for the database operations."]
-        #[derive(derive_more::Debug)]
+        #[derive(Clone, derive_more::Debug)]
         pub struct $row_name {
             #[debug("...")]
-            inner: <$sqlx_db_ty as sqlx::Database>::Row,
+            inner: std::sync::Arc<<$sqlx_db_ty as sqlx::Database>::Row>,
+            offset: usize,
         }
 
         impl $row_name {
             #[must_use]
             fn new(inner: <$sqlx_db_ty as sqlx::Database>::Row) -> Self {
-                Self { inner }
+                Self {
+                    inner: std::sync::Arc::new(inner),
+                    offset: 0,
+                }
+            }
+
+            /// Returns a view of this row that skips the first `offset`
+            /// columns.
+            #[must_use]
+            pub(crate) fn with_offset(&self, offset: usize) -> Self {
+                Self {
+                    inner: std::sync::Arc::clone(&self.inner),
+                    offset: self.offset + offset,
+                }
             }
         }
 
@@ -145,7 +275,17 @@ macro_rules! impl_sea_query_db_backend {
 
             fn get_raw(&self, index: usize) -> crate::db::Result<Self::ValueRef<'_>> {
                 use sqlx::Row;
-                Ok($value_ref_name::new(self.inner.try_get_raw(index)?))
+                Ok($value_ref_name::new(self.inner.try_get_raw(self.offset + index)?))
+            }
+
+            fn column_names(&self) -> Vec<&str> {
+                use sqlx::{Column, Row};
+                self.inner
+                    .columns()
+                    .iter()
+                    .skip(self.offset)
+                    .map(Column::name)
+                    .collect()
             }
         }
 
@@ -175,6 +315,31 @@ macro_rules! impl_sea_query_db_backend {
     };
 }
 
+/// Evaluates `$body` with `$executor` bound to the connection of the
+/// transaction the backend executes the statements in, or to the connection
+/// pool if it's not bound to a transaction.
+///
+/// Returns [`DatabaseError::TransactionFinished`](crate::db::DatabaseError::TransactionFinished)
+/// from the enclosing function if the transaction has already been committed
+/// or rolled back.
+macro_rules! with_executor {
+    ($self:ident, |$executor:ident| $body:expr) => {
+        match &$self.transaction {
+            Some(transaction) => {
+                let mut transaction = transaction.lock().await;
+                match transaction.as_deref_mut() {
+                    Some($executor) => $body,
+                    None => return Err(crate::db::DatabaseError::TransactionFinished),
+                }
+            }
+            None => {
+                let $executor = &$self.db_connection;
+                $body
+            }
+        }
+    };
+}
+
 pub(crate) fn map_sqlx_error(err: sqlx::Error) -> crate::db::DatabaseError {
     if err
         .as_database_error()
@@ -186,4 +351,4 @@ pub(crate) fn map_sqlx_error(err: sqlx::Error) -> crate::db::DatabaseError {
     crate::db::DatabaseError::from(err)
 }
 
-pub(super) use impl_sea_query_db_backend;
+pub(super) use {impl_sea_query_db_backend, with_executor};
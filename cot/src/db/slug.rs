@@ -0,0 +1,332 @@
+use std::collections::HashSet;
+
+use derive_more::{Deref, Display};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "mysql")]
+use crate::db::impl_mysql::MySqlValueRef;
+#[cfg(feature = "postgres")]
+use crate::db::impl_postgres::PostgresValueRef;
+#[cfg(feature = "sqlite")]
+use crate::db::impl_sqlite::SqliteValueRef;
+use crate::db::{
+    ColumnType, Database, DatabaseField, DbFieldValue, DbValue, FromDbValue, Identifier, Model,
+    Result, SqlxValueRef, ToDbValue,
+};
+
+/// Converts a string to a URL-safe slug.
+///
+/// The string is transliterated to ASCII, converted to lowercase, and all the
+/// sequences of characters other than letters and digits are replaced with a
+/// single hyphen. Apostrophes are removed, so that words like "don't" are not
+/// split. The leading and trailing hyphens are removed.
+///
+/// # Examples
+///
+/// ```
+/// use cot::db::slugify;
+///
+/// assert_eq!(slugify("Hello, World!"), "hello-world");
+/// assert_eq!(slugify("  Don't Panic  "), "dont-panic");
+/// assert_eq!(slugify("Crème brûlée"), "creme-brulee");
+/// ```
+#[must_use]
+pub fn slugify(value: &str) -> String {
+    let mut slug = String::with_capacity(value.len());
+    let mut pending_hyphen = false;
+
+    for c in deunicode::deunicode(value).chars() {
+        if c.is_ascii_alphanumeric() {
+            if pending_hyphen && !slug.is_empty() {
+                slug.push('-');
+            }
+            pending_hyphen = false;
+            slug.push(c.to_ascii_lowercase());
+        } else if c != '\'' {
+            pending_hyphen = true;
+        }
+    }
+
+    slug
+}
+
+/// A URL-safe identifier of a model instance, such as `my-first-post`.
+///
+/// Slugs are usually derived from another field of the model, such as the
+/// title. When a `Slug` field is marked with
+/// `#[model(slugify_from = "...")]`, the slug is generated automatically from
+/// the given field when the model is saved and the slug is empty. The
+/// generated slug is unique in the table: if the slug is already used by
+/// another row, a numeric suffix is appended to it (`my-first-post-2`,
+/// `my-first-post-3`, etc.).
+///
+/// # Database
+///
+/// This type is represented by the `TEXT` type in the database.
+///
+/// # Examples
+///
+/// ```
+/// use cot::db::{Auto, Slug, model};
+///
+/// #[model]
+/// struct Post {
+///     #[model(primary_key)]
+///     id: Auto<i32>,
+///     title: String,
+///     #[model(slugify_from = "title")]
+///     slug: Slug,
+/// }
+///
+/// let post = Post {
+///     id: Auto::auto(),
+///     title: "My First Post".to_owned(),
+///     slug: Slug::default(),
+/// };
+/// ```
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Deref,
+    Display,
+    Serialize,
+    Deserialize,
+)]
+#[serde(transparent)]
+pub struct Slug(String);
+
+impl Slug {
+    /// Creates a new slug from the given string using [`slugify`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::db::Slug;
+    ///
+    /// let slug = Slug::new("My First Post");
+    /// assert_eq!(slug, "my-first-post");
+    /// ```
+    #[must_use]
+    pub fn new(value: &str) -> Self {
+        Self(slugify(value))
+    }
+
+    /// Returns the slug as a string slice.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Converts the slug into an owned [`String`].
+    #[must_use]
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl PartialEq<&str> for Slug {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<str> for Slug {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<String> for Slug {
+    fn eq(&self, other: &String) -> bool {
+        self.0 == *other
+    }
+}
+
+impl DatabaseField for Slug {
+    const TYPE: ColumnType = ColumnType::Text;
+}
+
+impl FromDbValue for Slug {
+    #[cfg(feature = "sqlite")]
+    fn from_sqlite(value: SqliteValueRef<'_>) -> Result<Self> {
+        value.get::<String>().map(Self)
+    }
+
+    #[cfg(feature = "postgres")]
+    fn from_postgres(value: PostgresValueRef<'_>) -> Result<Self> {
+        value.get::<String>().map(Self)
+    }
+
+    #[cfg(feature = "mysql")]
+    fn from_mysql(value: MySqlValueRef<'_>) -> Result<Self> {
+        value.get::<String>().map(Self)
+    }
+}
+
+impl FromDbValue for Option<Slug> {
+    #[cfg(feature = "sqlite")]
+    fn from_sqlite(value: SqliteValueRef<'_>) -> Result<Self> {
+        value.get::<Option<String>>().map(|value| value.map(Slug))
+    }
+
+    #[cfg(feature = "postgres")]
+    fn from_postgres(value: PostgresValueRef<'_>) -> Result<Self> {
+        value.get::<Option<String>>().map(|value| value.map(Slug))
+    }
+
+    #[cfg(feature = "mysql")]
+    fn from_mysql(value: MySqlValueRef<'_>) -> Result<Self> {
+        value.get::<Option<String>>().map(|value| value.map(Slug))
+    }
+}
+
+impl ToDbValue for Slug {
+    fn to_db_value(&self) -> DbValue {
+        self.0.clone().into()
+    }
+}
+
+impl ToDbValue for Option<Slug> {
+    fn to_db_value(&self) -> DbValue {
+        self.clone().map(Slug::into_string).into()
+    }
+}
+
+/// Generates unique slugs for the fields marked with
+/// `#[model(slugify_from = "...")]`.
+///
+/// This is passed to [`Model::generate_slugs`] by the ORM before a model
+/// instance is written to the database. It remembers the slugs it has
+/// generated, so the instances saved together (e.g. with
+/// [`Model::bulk_insert`]) get distinct slugs, too.
+#[derive(Debug)]
+pub struct SlugGenerator<'a> {
+    db: &'a Database,
+    generated: HashSet<(Identifier, String)>,
+}
+
+impl<'a> SlugGenerator<'a> {
+    pub(crate) fn new(db: &'a Database) -> Self {
+        Self {
+            db,
+            generated: HashSet::new(),
+        }
+    }
+
+    /// Generates a slug from `source` that is not used in the `column` of
+    /// the model's table by any row other than the one with the given primary
+    /// key values.
+    ///
+    /// If the source doesn't contain any letters or digits, the model name is
+    /// used instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the existing slugs could not be retrieved from the
+    /// database.
+    pub async fn generate<T: Model>(
+        &mut self,
+        column: Identifier,
+        source: &str,
+        primary_key: Vec<DbFieldValue>,
+    ) -> Result<Slug> {
+        let mut base = slugify(source);
+        if base.is_empty() {
+            let model_name = T::TABLE_NAME.as_str().rsplit("__").next().unwrap_or("");
+            base = slugify(model_name);
+        }
+
+        let mut statement = sea_query::Query::select();
+        statement.column(column).from(T::TABLE_NAME).and_where(
+            sea_query::Expr::col(column)
+                .eq(base.clone())
+                .or(sea_query::Expr::col(column).like(format!("{base}-%"))),
+        );
+        let primary_key: Option<Vec<_>> = primary_key
+            .into_iter()
+            .map(|value| match value {
+                DbFieldValue::Value(value) => Some(value),
+                DbFieldValue::Auto => None,
+            })
+            .collect();
+        if let Some(primary_key) = primary_key {
+            let other_row = std::iter::zip(T::PRIMARY_KEY_NAMES, primary_key)
+                .map(|(column, value)| sea_query::Expr::col(*column).ne(value))
+                .reduce(|condition, other| condition.or(other))
+                .expect("models should have at least one primary key column");
+            statement.and_where(other_row);
+        }
+        let mut taken = HashSet::new();
+        for row in self.db.fetch_all(&statement).await? {
+            taken.insert(row.get::<String>(0)?);
+        }
+
+        let mut slug = base.clone();
+        let mut suffix = 2_u64;
+        while taken.contains(&slug) || self.generated.contains(&(column, slug.clone())) {
+            slug = format!("{base}-{suffix}");
+            suffix += 1;
+        }
+
+        self.generated.insert((column, slug.clone()));
+        Ok(Slug(slug))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slugify_ascii() {
+        assert_eq!(slugify("Hello World"), "hello-world");
+        assert_eq!(slugify("Hello, World!"), "hello-world");
+        assert_eq!(slugify("  --Rust 1.0 -- released--  "), "rust-1-0-released");
+        assert_eq!(
+            slugify("snake_case and CamelCase"),
+            "snake-case-and-camelcase"
+        );
+        assert_eq!(slugify("Don't stop"), "dont-stop");
+    }
+
+    #[test]
+    fn slugify_unicode() {
+        assert_eq!(slugify("Zażółć gęślą jaźń"), "zazolc-gesla-jazn");
+        assert_eq!(slugify("Ünïcödé Straße"), "unicode-strasse");
+        assert_eq!(slugify("Don’t panic"), "dont-panic");
+    }
+
+    #[test]
+    fn slugify_empty() {
+        assert_eq!(slugify(""), "");
+        assert_eq!(slugify("!?-- "), "");
+    }
+
+    #[test]
+    fn slug_new() {
+        let slug = Slug::new("My First Post");
+
+        assert_eq!(slug, "my-first-post");
+        assert_eq!(slug.as_str(), "my-first-post");
+        assert_eq!(slug.into_string(), "my-first-post");
+    }
+
+    #[test]
+    fn slug_default_is_empty() {
+        assert!(Slug::default().is_empty());
+    }
+
+    #[test]
+    fn slug_to_db_value() {
+        assert_eq!(
+            Slug::new("Hello").to_db_value(),
+            DbValue::String(Some("hello".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use derive_more::Deref;
+
+use crate::db::{Database, Result};
+
+/// A database transaction, started with [`Database::begin`].
+///
+/// The transaction dereferences to a [`Database`] executing all the
+/// statements in the transaction, so it can be passed to any method
+/// accepting a database (using `&*transaction`). The changes are only
+/// visible to the other connections once the transaction is
+/// [committed](Self::commit). If the transaction is dropped without being
+/// committed, it's rolled back.
+///
+/// Note that the transaction is only finished once all the clones of the
+/// [`Database`] it dereferences to are dropped, too.
+///
+/// # Examples
+///
+/// ```
+/// use cot::db::{Database, Model, model};
+///
+/// #[model]
+/// struct Item {
+///     #[model(primary_key)]
+///     id: i32,
+///     name: String,
+/// }
+///
+/// # async fn example(db: &Database) -> cot::db::Result<()> {
+/// let transaction = db.begin().await?;
+/// let mut item = Item {
+///     id: 1,
+///     name: "Item".to_owned(),
+/// };
+/// item.insert(&*transaction).await?;
+/// transaction.commit().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Deref)]
+pub struct Transaction {
+    database: Database,
+}
+
+impl Transaction {
+    pub(super) fn new(database: Database) -> Self {
+        Self { database }
+    }
+
+    /// Commits the transaction, making its changes visible to the other
+    /// connections.
+    ///
+    /// # Errors
+    ///
+    /// Returns
+    /// [`DatabaseError::TransactionFinished`](crate::db::DatabaseError::TransactionFinished)
+    /// if the transaction has already been finished through a clone of the
+    /// database it dereferences to.
+    ///
+    /// This method can return an error if the transaction could not be
+    /// committed, for instance because there was a problem with the database
+    /// connection.
+    pub async fn commit(self) -> Result<()> {
+        self.database.commit().await
+    }
+
+    /// Rolls back the transaction, discarding its changes.
+    ///
+    /// This is the same as dropping the transaction, but it waits for the
+    /// rollback to finish and reports its errors.
+    ///
+    /// # Errors
+    ///
+    /// Returns
+    /// [`DatabaseError::TransactionFinished`](crate::db::DatabaseError::TransactionFinished)
+    /// if the transaction has already been finished through a clone of the
+    /// database it dereferences to.
+    ///
+    /// This method can return an error if the transaction could not be
+    /// rolled back, for instance because there was a problem with the
+    /// database connection.
+    pub async fn rollback(self) -> Result<()> {
+        self.database.rollback().await
+    }
+}
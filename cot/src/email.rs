@@ -4,6 +4,9 @@
 //! [`EmailMessage`] values through a chosen transport backend
 //! (see [`transport`] submodule for available backends).
 //!
+//! In tests, [`TestMailer`](crate::test::TestMailer) can be used as the
+//! transport to record the sent messages and make assertions about them.
+//!
 //! # Examples
 //!
 //! Send using the console transport backend (prints nicely formatted messages):
@@ -80,6 +83,9 @@ pub struct EmailMessage {
     /// The body content of the email.
     #[builder(setter(into))]
     body: String,
+    /// The HTML version of the body, sent alongside the plain text body.
+    #[builder(setter(into, strip_option))]
+    html: Option<String>,
     /// The sender's email address.
     from: crate::common_types::Email,
     /// The primary recipients of the email.
@@ -115,6 +121,181 @@ impl EmailMessage {
     pub fn builder() -> EmailMessageBuilder {
         EmailMessageBuilder::default()
     }
+
+    /// Returns the subject of the email.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::common_types::Email;
+    /// use cot::email::EmailMessage;
+    ///
+    /// let message = EmailMessage::builder()
+    ///     .from(Email::try_from("no-reply@example.com").unwrap())
+    ///     .subject("Greetings")
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(message.subject(), "Greetings");
+    /// ```
+    #[must_use]
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    /// Returns the plain text body of the email.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::common_types::Email;
+    /// use cot::email::EmailMessage;
+    ///
+    /// let message = EmailMessage::builder()
+    ///     .from(Email::try_from("no-reply@example.com").unwrap())
+    ///     .body("Hello from cot!")
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(message.body(), "Hello from cot!");
+    /// ```
+    #[must_use]
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    /// Returns the HTML body of the email, if set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::common_types::Email;
+    /// use cot::email::EmailMessage;
+    ///
+    /// let message = EmailMessage::builder()
+    ///     .from(Email::try_from("no-reply@example.com").unwrap())
+    ///     .html("<p>Hello from cot!</p>")
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(message.html(), Some("<p>Hello from cot!</p>"));
+    /// ```
+    #[must_use]
+    pub fn html(&self) -> Option<&str> {
+        self.html.as_deref()
+    }
+
+    /// Returns the sender of the email.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::common_types::Email;
+    /// use cot::email::EmailMessage;
+    ///
+    /// let message = EmailMessage::builder()
+    ///     .from(Email::try_from("no-reply@example.com").unwrap())
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(message.from().email(), "no-reply@example.com");
+    /// ```
+    #[must_use]
+    pub fn from(&self) -> &crate::common_types::Email {
+        &self.from
+    }
+
+    /// Returns the primary recipients of the email.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::common_types::Email;
+    /// use cot::email::EmailMessage;
+    ///
+    /// let message = EmailMessage::builder()
+    ///     .from(Email::try_from("no-reply@example.com").unwrap())
+    ///     .to(vec![Email::try_from("user@example.com").unwrap()])
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(message.to()[0].email(), "user@example.com");
+    /// ```
+    #[must_use]
+    pub fn to(&self) -> &[crate::common_types::Email] {
+        &self.to
+    }
+
+    /// Returns the carbon copy (CC) recipients of the email.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::common_types::Email;
+    /// use cot::email::EmailMessage;
+    ///
+    /// let message = EmailMessage::builder()
+    ///     .from(Email::try_from("no-reply@example.com").unwrap())
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(message.cc().is_empty());
+    /// ```
+    #[must_use]
+    pub fn cc(&self) -> &[crate::common_types::Email] {
+        &self.cc
+    }
+
+    /// Returns the blind carbon copy (BCC) recipients of the email.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::common_types::Email;
+    /// use cot::email::EmailMessage;
+    ///
+    /// let message = EmailMessage::builder()
+    ///     .from(Email::try_from("no-reply@example.com").unwrap())
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(message.bcc().is_empty());
+    /// ```
+    #[must_use]
+    pub fn bcc(&self) -> &[crate::common_types::Email] {
+        &self.bcc
+    }
+
+    /// Returns the reply-to addresses of the email.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::common_types::Email;
+    /// use cot::email::EmailMessage;
+    ///
+    /// let message = EmailMessage::builder()
+    ///     .from(Email::try_from("no-reply@example.com").unwrap())
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(message.reply_to().is_empty());
+    /// ```
+    #[must_use]
+    pub fn reply_to(&self) -> &[crate::common_types::Email] {
+        &self.reply_to
+    }
+
+    /// Returns the attachments of the email.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::common_types::Email;
+    /// use cot::email::EmailMessage;
+    ///
+    /// let message = EmailMessage::builder()
+    ///     .from(Email::try_from("no-reply@example.com").unwrap())
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(message.attachments().is_empty());
+    /// ```
+    #[must_use]
+    pub fn attachments(&self) -> &[AttachmentData] {
+        &self.attachments
+    }
 }
 
 impl EmailMessageBuilder {
@@ -147,6 +328,7 @@ impl EmailMessageBuilder {
 
         let subject = self.subject.clone().unwrap_or_default();
         let body = self.body.clone().unwrap_or_default();
+        let html = self.html.clone().flatten();
 
         let to = self.to.clone().unwrap_or_default();
         let cc = self.cc.clone().unwrap_or_default();
@@ -157,6 +339,7 @@ impl EmailMessageBuilder {
         Ok(EmailMessage {
             subject,
             body,
+            html,
             from,
             to,
             cc,
@@ -382,6 +565,7 @@ mod tests {
             .expect("should build with defaults");
         assert_eq!(msg.subject, "");
         assert_eq!(msg.body, "");
+        assert!(msg.html.is_none());
         assert!(msg.to.is_empty());
         assert!(msg.cc.is_empty());
         assert!(msg.bcc.is_empty());
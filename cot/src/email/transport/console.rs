@@ -142,6 +142,13 @@ impl fmt::Display for EmailMessage {
         } else {
             writeln!(f, "{}", self.body.trim_end())?;
         }
+        if let Some(html) = &self.html {
+            writeln!(
+                f,
+                "──────────────────────────── HTML ────────────────────────────"
+            )?;
+            writeln!(f, "{}", html.trim_end())?;
+        }
         writeln!(
             f,
             "────────────────────────────────────────────────────────"
@@ -272,6 +279,23 @@ mod tests {
         assert!(!rendered.contains("Reply-To:"));
 
         assert!(rendered.contains("<empty>"));
+        assert!(!rendered.contains("HTML"));
         assert!(rendered.contains("Attachments: -"));
     }
+
+    #[cot::test]
+    async fn display_message_with_html_renders_html_section() {
+        let msg = EmailMessage::builder()
+            .from(Addr::new("sender@example.com").unwrap())
+            .body("Plain body")
+            .html("<p>HTML body</p>\n")
+            .build()
+            .unwrap();
+
+        let rendered = format!("{msg}");
+
+        assert!(rendered.contains("Plain body"));
+        assert!(rendered.contains(" HTML "));
+        assert!(rendered.contains("<p>HTML body</p>"));
+    }
 }
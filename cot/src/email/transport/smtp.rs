@@ -217,7 +217,12 @@ fn convert_email_message_to_lettre_message(
         builder = builder.reply_to(mb);
     }
 
-    let mut mixed = MultiPart::mixed().singlepart(SinglePart::plain(message.body));
+    let mut mixed = match message.html {
+        Some(html) => {
+            MultiPart::mixed().multipart(MultiPart::alternative_plain_html(message.body, html))
+        }
+        None => MultiPart::mixed().singlepart(SinglePart::plain(message.body)),
+    };
 
     for attach in message.attachments {
         let mime: ContentType = attach.content_type.parse().unwrap_or_else(|_| {
@@ -310,6 +315,31 @@ mod tests {
         assert!(formatted.contains("This is the body."),);
     }
 
+    #[cot::test]
+    async fn try_from_with_html_uses_alternative_parts() {
+        let msg = EmailMessage::builder()
+            .from(crate::common_types::Email::new("from@example.com").unwrap())
+            .to(vec![
+                crate::common_types::Email::new("to@example.com").unwrap(),
+            ])
+            .subject("HTML Test")
+            .body("Plain body")
+            .html("<p>HTML body</p>")
+            .build()
+            .unwrap();
+
+        let built: Message =
+            convert_email_message_to_lettre_message(msg).expect("conversion to lettre::Message");
+
+        let formatted = String::from_utf8_lossy(&built.formatted()).to_string();
+
+        assert!(formatted.contains("Content-Type: multipart/alternative"));
+        assert!(formatted.contains("Content-Type: text/plain"));
+        assert!(formatted.contains("Content-Type: text/html"));
+        assert!(formatted.contains("Plain body"));
+        assert!(formatted.contains("<p>HTML body</p>"));
+    }
+
     #[cot::test]
     async fn try_from_includes_cc_and_reply_to_headers() {
         let msg = EmailMessage::builder()
@@ -6,7 +6,7 @@ use cot_core::error::backtrace::{__cot_create_backtrace, Backtrace};
 use tracing::{Level, error, warn};
 
 use crate::config::ProjectConfig;
-use crate::error::NotFound;
+use crate::error::{NotFound, UncaughtPanic};
 use crate::router::Router;
 use crate::{Error, Result, StatusCode, Template};
 
@@ -302,6 +302,10 @@ pub(super) fn handle_response_error(
     if error.status_code() == StatusCode::NOT_FOUND {
         return handle_not_found(error, diagnostics);
     }
+    // panics caught by `CatchPanicMiddleware` are returned as errors
+    if let Some(panic) = error.inner().downcast_ref::<UncaughtPanic>() {
+        return handle_response_panic(&panic.payload(), diagnostics);
+    }
 
     log_error(
         error,
@@ -401,6 +405,18 @@ pub(super) fn error_page_panic_hook(info: &PanicHookInfo<'_>) {
     PANIC_BACKTRACE.replace(Some(backtrace));
 }
 
+/// Returns the location and the backtrace of the last panic on the current
+/// thread, as recorded by [`error_page_panic_hook`].
+///
+/// Unlike [`handle_response_panic`], this doesn't consume them, so that they
+/// can still be displayed on the error page.
+pub(crate) fn last_panic_info() -> (Option<String>, Option<Backtrace>) {
+    (
+        PANIC_LOCATION.with_borrow(Clone::clone),
+        PANIC_BACKTRACE.with_borrow(Clone::clone),
+    )
+}
+
 fn log_error(error: &Error, request_data: Option<&RequestData>) {
     let span = tracing::span!(Level::ERROR,
         "request_error",
@@ -531,6 +547,19 @@ mod tests {
         assert!(logs_contain("Test handler error"));
     }
 
+    #[test]
+    #[traced_test]
+    fn test_handle_response_error_uncaught_panic() {
+        let diagnostics = create_diagnostics();
+        let error = Error::from(UncaughtPanic::new(Box::new("Test caught panic")));
+
+        let response = handle_response_error(&error, &diagnostics);
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(logs_contain("Request handler panicked"));
+        assert!(logs_contain("Test caught panic"));
+    }
+
     #[test]
     #[traced_test]
     fn test_handle_response_panic_logging() {
@@ -23,6 +23,7 @@
 mod field_value;
 /// Built-in form fields that can be used in a form.
 pub mod fields;
+mod widget;
 
 use std::borrow::Cow;
 use std::fmt::Display;
@@ -51,6 +52,100 @@ use cot_core::headers::{MULTIPART_FORM_CONTENT_TYPE, URLENCODED_FORM_CONTENT_TYP
 /// Note that even if the form is not rendered in a template, you will still be
 /// able to render the fields individually.
 ///
+/// # Custom widgets
+///
+/// A field can be rendered using a custom [`FormWidget`] instead of the
+/// default rendering of its field type by adding the `#[form(widget = ...)]`
+/// attribute to it. See the [`FormWidget`] documentation for an example.
+///
+/// # Field order and grouping
+///
+/// By default, the fields are rendered (and returned by
+/// [`FormContext::fields`]) in declaration order. The `#[form(order = N)]`
+/// attribute moves a field before all the fields without an explicit order;
+/// the fields with an explicit order are sorted by it.
+///
+/// Fields marked with `#[form(group = "name")]` are rendered after the
+/// ungrouped fields, wrapped in a `<fieldset>` with the title-cased group name
+/// as its `<legend>`. The groups are rendered in the order of their first
+/// field.
+///
+/// ```
+/// use cot::form::{Form, FormContext};
+///
+/// #[derive(Form)]
+/// struct SignupForm {
+///     #[form(group = "contact")]
+///     email: String,
+///     #[form(group = "contact", order = 1)]
+///     phone_number: String,
+///     #[form(order = 1)]
+///     username: String,
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let context = <SignupForm as Form>::Context::new();
+/// let ids: Vec<_> = context.fields().map(|field| field.dyn_id()).collect();
+/// assert_eq!(ids, ["username", "phone_number", "email"]);
+/// assert!(
+///     context
+///         .to_string()
+///         .contains("<fieldset><legend>Contact</legend>")
+/// );
+/// # }
+/// ```
+///
+/// # Patch forms
+///
+/// Forms used to edit existing objects can be marked with `#[form(patch)]` to
+/// only update the fields that were actually submitted. In a patch form, every
+/// field has to be an [`Option`] of the actual field type: a field that
+/// wasn't submitted at all is [`None`], while a submitted field is validated
+/// as usual and wrapped in [`Some`]. This makes it possible to distinguish a
+/// missing field from a field that has been cleared: for an
+/// `Option<Option<String>>` field, an empty value results in `Some(None)`.
+///
+/// If the form updates a database model, the model can be given as
+/// `#[form(patch = MyModel)]`. The form then also implements [`PatchForm`],
+/// which converts the submitted fields into assignments for
+/// [`Query::update`](crate::db::query::Query::update). The form fields must
+/// have the same names as the model fields.
+///
+/// ```
+/// use cot::db::{Auto, Model, model, query};
+/// use cot::form::{Form, FormResult, PatchForm};
+/// use cot::request::Request;
+///
+/// #[model]
+/// struct Article {
+///     #[model(primary_key)]
+///     id: Auto<i32>,
+///     title: String,
+///     subtitle: Option<String>,
+/// }
+///
+/// #[derive(Form)]
+/// #[form(patch = Article)]
+/// struct ArticleEditForm {
+///     title: Option<String>,
+///     subtitle: Option<Option<String>>,
+/// }
+///
+/// async fn edit_article(
+///     db: &cot::db::Database,
+///     request: &mut Request,
+///     id: i32,
+/// ) -> cot::Result<()> {
+///     if let FormResult::Ok(form) = ArticleEditForm::from_request(request).await? {
+///         query!(Article, $id == id)
+///             .update(db, &form.into_assignments())
+///             .await?;
+///     }
+///     Ok(())
+/// }
+/// ```
+///
 /// # Safety
 ///
 /// The implementation of [`Display`] for the form context that this derive
@@ -62,6 +157,7 @@ use derive_more::with_trait::Debug;
 pub use field_value::{FormFieldValue, FormFieldValueError};
 use http_body_util::BodyExt;
 use thiserror::Error;
+pub use widget::{FormWidget, WithWidget};
 
 use crate::request::{Request, RequestExt};
 
@@ -178,6 +274,13 @@ pub enum FormFieldValidationError {
     /// The field value is invalid.
     #[error("Value is not valid for this field.")]
     InvalidValue(String),
+    /// The field value is not in the expected format.
+    #[error("Enter a valid value in the {format} format.")]
+    InvalidFormat {
+        /// A human-readable description of the expected format, such as
+        /// `YYYY-MM-DD`.
+        format: &'static str,
+    },
     /// An error occurred while getting the field value.
     #[error("Error getting field value: {0}")]
     FormFieldValueError(#[from] FormFieldValueError),
@@ -194,6 +297,25 @@ impl FormFieldValidationError {
         Self::InvalidValue(value.into())
     }
 
+    /// Creates a new `FormFieldValidationError` for a field value that is not
+    /// in the expected format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::form::FormFieldValidationError;
+    ///
+    /// let error = FormFieldValidationError::invalid_format("YYYY-MM-DD");
+    /// assert_eq!(
+    ///     error.to_string(),
+    ///     "Enter a valid value in the YYYY-MM-DD format."
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn invalid_format(format: &'static str) -> Self {
+        Self::InvalidFormat { format }
+    }
+
     /// Creates a new `FormFieldValidationError` for a field value that is too
     /// long.
     #[must_use]
@@ -307,6 +429,47 @@ pub trait Form: Sized {
     /// or obtained externally, such as from a database.
     async fn to_context(&self) -> Self::Context;
 
+    /// Creates the context for the form with the given initial values.
+    ///
+    /// This is useful for rendering a form for the first time with some of the
+    /// fields pre-filled, such as with default values. Unlike
+    /// [`Self::to_context`], this doesn't require a value for each field; the
+    /// fields that are not listed stay empty. The values are given as
+    /// `(field_id, value)` pairs in the same format as in the form data, and
+    /// the unknown field IDs are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::form::Form;
+    ///
+    /// #[derive(Form)]
+    /// struct ContactForm {
+    ///     name: String,
+    ///     message: String,
+    /// }
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let context = ContactForm::with_initial(&[("message", "Hello!")]).await;
+    /// assert!(context.to_string().contains("value=\"Hello!\""));
+    /// # }
+    /// ```
+    async fn with_initial(values: &[(&str, &str)]) -> Self::Context {
+        let mut context = Self::Context::new();
+
+        for &(field_id, value) in values {
+            if let Err(err) = context
+                .set_value(field_id, FormFieldValue::new_text(value))
+                .await
+            {
+                context.add_error(FormErrorTarget::Field(field_id), err);
+            }
+        }
+
+        context
+    }
+
     /// Builds the context for the form from a request.
     ///
     /// Note that this doesn't try to convert the values from the form fields
@@ -332,6 +495,26 @@ pub trait Form: Sized {
     }
 }
 
+/// A form describing a partial update of a database model.
+///
+/// This trait is implemented by the [`Form`](derive@Form) derive macro for the
+/// forms marked with `#[form(patch = MyModel)]`. See the "Patch forms" section
+/// of the derive macro documentation for details.
+#[cfg(feature = "db")]
+pub trait PatchForm: Form {
+    /// The model updated by the form.
+    type Model: crate::db::Model;
+
+    /// Converts the form into the assignments for the fields that have been
+    /// submitted, to be used with
+    /// [`Query::update`](crate::db::query::Query::update).
+    ///
+    /// The fields that haven't been submitted are left out, so the
+    /// corresponding columns are left unchanged. If no fields have been
+    /// submitted, the returned list is empty.
+    fn into_assignments(self) -> Vec<crate::db::query::Assignment>;
+}
+
 async fn form_data(request: &mut Request) -> Result<FormData<'_>, FormError> {
     let form_data = if content_type_str(request).starts_with(MULTIPART_FORM_CONTENT_TYPE) {
         let multipart = multipart_form_data(request)?;
@@ -1,6 +1,7 @@
 mod attrs;
 mod chrono;
 mod files;
+mod honeypot;
 mod select;
 
 use std::fmt::{Debug, Display, Formatter};
@@ -16,6 +17,7 @@ pub use chrono::{
     DateTimeWithTimezoneFieldOptions, TimeField, TimeFieldOptions,
 };
 pub use files::{FileField, FileFieldOptions, InMemoryUploadedFile};
+pub use honeypot::{HONEYPOT_CLASS, Honeypot, HoneypotField, HoneypotFieldOptions};
 pub(crate) use select::check_required_multiple;
 pub use select::{
     SelectAsFormField, SelectChoice, SelectField, SelectFieldOptions, SelectMultipleField,
@@ -25,7 +27,7 @@ pub use select::{
 use crate::auth::PasswordHash;
 use crate::common_types::{Email, Password, Url};
 #[cfg(feature = "db")]
-use crate::db::{Auto, ForeignKey, LimitedString, Model};
+use crate::db::{Auto, Choice, ForeignKey, LimitedString, Model, Slug};
 use crate::form::{AsFormField, FormField, FormFieldOptions, FormFieldValidationError};
 use crate::html::HtmlTag;
 
@@ -140,6 +142,28 @@ impl<const LEN: u32> AsFormField for LimitedString<LEN> {
     }
 }
 
+#[cfg(feature = "db")]
+impl AsFormField for Slug {
+    type Type = StringField;
+
+    fn clean_value(field: &Self::Type) -> Result<Self, FormFieldValidationError> {
+        let value = check_required(field)?;
+
+        if let Some(max_length) = field.custom_options.max_length
+            && value.len() > max_length as usize
+        {
+            return Err(FormFieldValidationError::maximum_length_exceeded(
+                max_length,
+            ));
+        }
+        Ok(Slug::new(value))
+    }
+
+    fn to_field_value(&self) -> String {
+        self.to_string()
+    }
+}
+
 impl_form_field!(PasswordField, PasswordFieldOptions, "a password");
 
 /// Custom options for a [`PasswordField`].
@@ -280,7 +304,7 @@ impl AsFormField for Email {
             return Err(FormFieldValidationError::maximum_length_exceeded(max));
         }
 
-        Ok(value.parse()?)
+        Ok(Email::from_form_value(value)?)
     }
 
     fn to_field_value(&self) -> String {
@@ -301,6 +325,10 @@ pub struct IntegerFieldOptions<T> {
     /// The maximum value of the field. Used to set the `max` attribute in the
     /// HTML input element.
     pub max: Option<T>,
+    /// The granularity of the values of the field. Used to set the `step`
+    /// attribute in the HTML input element; if `None`, the browser's default
+    /// (`1`) is used.
+    pub step: Option<Step<T>>,
 }
 
 impl<T: Integer> Default for IntegerFieldOptions<T> {
@@ -308,6 +336,7 @@ impl<T: Integer> Default for IntegerFieldOptions<T> {
         Self {
             min: T::MIN,
             max: T::MAX,
+            step: None,
         }
     }
 }
@@ -326,6 +355,13 @@ impl<T: Integer> Display for IntegerField<T> {
         if let Some(max) = &self.custom_options.max {
             tag.attr("max", max.to_string());
         }
+        if let Some(step) = &self.custom_options.step {
+            let step_value = match step {
+                Step::Any => "any".to_owned(),
+                Step::Value(value) => value.to_string(),
+            };
+            tag.attr("step", step_value);
+        }
         if let Some(value) = &self.value {
             tag.attr("value", value);
         }
@@ -471,6 +507,9 @@ pub struct BoolFieldOptions {
     pub must_be_true: Option<bool>,
 }
 
+const BOOL_TRUE_VALUES: [&str; 3] = ["true", "on", "1"];
+const BOOL_FALSE_VALUES: [&str; 3] = ["false", "off", "0"];
+
 impl Display for BoolField {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut bool_input = HtmlTag::input("checkbox");
@@ -478,17 +517,17 @@ impl Display for BoolField {
         bool_input.attr("id", self.id());
         bool_input.attr("value", "1");
 
-        if self.custom_options.must_be_true.unwrap_or(false) {
-            bool_input.bool_attr("required");
-            return write!(f, "{}", bool_input.render());
-        }
-
         if let Some(value) = &self.value
-            && value == "1"
+            && BOOL_TRUE_VALUES.contains(&value.as_str())
         {
             bool_input.bool_attr("checked");
         }
 
+        if self.custom_options.must_be_true.unwrap_or(false) {
+            bool_input.bool_attr("required");
+            return write!(f, "{}", bool_input.render());
+        }
+
         // Web browsers don't send anything when a checkbox is unchecked, so we
         // need to add a hidden input to send a "false" value.
         let mut hidden_input = HtmlTag::input("hidden");
@@ -521,13 +560,13 @@ impl AsFormField for bool {
     }
 
     fn clean_value(field: &Self::Type) -> Result<Self, FormFieldValidationError> {
-        let value = check_required(field)?;
-        let value = if ["true", "on", "1"].contains(&value) {
-            true
-        } else if ["false", "off", "0"].contains(&value) {
-            false
-        } else {
-            return Err(FormFieldValidationError::invalid_value(value));
+        // Web browsers don't send anything when a checkbox is unchecked, so a
+        // missing value means `false`.
+        let value = match field.value() {
+            None | Some("") => false,
+            Some(value) if BOOL_TRUE_VALUES.contains(&value) => true,
+            Some(value) if BOOL_FALSE_VALUES.contains(&value) => false,
+            Some(value) => return Err(FormFieldValidationError::invalid_value(value)),
         };
 
         if field.custom_options.must_be_true.unwrap_or(false) && !value {
@@ -601,6 +640,29 @@ impl<T: AsFormField> AsFormField for Auto<T> {
     }
 }
 
+#[cfg(feature = "db")]
+impl<T: AsFormField> AsFormField for Choice<T> {
+    type Type = T::Type;
+
+    fn new_field(
+        options: FormFieldOptions,
+        custom_options: <Self::Type as FormField>::CustomOptions,
+    ) -> Self::Type {
+        T::new_field(options, custom_options)
+    }
+
+    fn clean_value(field: &Self::Type) -> Result<Self, FormFieldValidationError>
+    where
+        Self: Sized,
+    {
+        T::clean_value(field).map(Choice)
+    }
+
+    fn to_field_value(&self) -> String {
+        self.0.to_field_value()
+    }
+}
+
 #[cfg(feature = "db")]
 impl<T> AsFormField for ForeignKey<T>
 where
@@ -658,6 +720,10 @@ pub struct FloatFieldOptions<T> {
     /// The maximum value of the field. Used to set the `max` attribute in the
     /// HTML input element.
     pub max: Option<T>,
+    /// The granularity of the values of the field. Used to set the `step`
+    /// attribute in the HTML input element; if `None`, the browser's default
+    /// (`1`) is used.
+    pub step: Option<Step<T>>,
 }
 
 impl<T: Float> Default for FloatFieldOptions<T> {
@@ -665,6 +731,7 @@ impl<T: Float> Default for FloatFieldOptions<T> {
         Self {
             min: T::MIN,
             max: T::MAX,
+            step: None,
         }
     }
 }
@@ -684,6 +751,13 @@ impl<T: Float> Display for FloatField<T> {
         if let Some(max) = &self.custom_options.max {
             tag.attr("max", max.to_string());
         }
+        if let Some(step) = &self.custom_options.step {
+            let step_value = match step {
+                Step::Any => "any".to_owned(),
+                Step::Value(value) => value.to_string(),
+            };
+            tag.attr("step", step_value);
+        }
         if let Some(value) = &self.value {
             tag.attr("value", value);
         }
@@ -877,6 +951,25 @@ mod tests {
         assert_eq!(value, Err(FormFieldValidationError::Required));
     }
 
+    #[cfg(feature = "db")]
+    #[cot::test]
+    async fn slug_field_clean_value() {
+        let mut field = StringField::with_options(
+            FormFieldOptions {
+                id: "test".to_owned(),
+                name: "test".to_owned(),
+                required: true,
+            },
+            StringFieldOptions { max_length: None },
+        );
+        field
+            .set_value(FormFieldValue::new_text("My First Post"))
+            .await
+            .unwrap();
+        let value = Slug::clean_value(&field).unwrap();
+        assert_eq!(value, "my-first-post");
+    }
+
     #[test]
     fn password_field_render() {
         let field = PasswordField::with_options(
@@ -983,6 +1076,61 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[cot::test]
+    async fn email_field_clean_normalizes() {
+        let mut field = EmailField::with_options(
+            FormFieldOptions {
+                id: "email_test".to_owned(),
+                name: "email_test".to_owned(),
+                required: true,
+            },
+            EmailFieldOptions::default(),
+        );
+
+        for (input, expected) in [
+            ("  User@Example.COM ", "user@example.com"),
+            (
+                "john.doe+cot@mail.example.co.uk",
+                "john.doe+cot@mail.example.co.uk",
+            ),
+        ] {
+            field
+                .set_value(FormFieldValue::new_text(input))
+                .await
+                .unwrap();
+            let email = Email::clean_value(&field).unwrap();
+
+            assert_eq!(email.as_str(), expected);
+        }
+    }
+
+    #[cot::test]
+    async fn email_field_clean_rejects_non_address() {
+        let mut field = EmailField::with_options(
+            FormFieldOptions {
+                id: "email_test".to_owned(),
+                name: "email_test".to_owned(),
+                required: true,
+            },
+            EmailFieldOptions::default(),
+        );
+
+        for input in [
+            "John Doe <john@example.com>",
+            "john@[127.0.0.1]",
+            "john@@example.com",
+            "@example.com",
+            "john@",
+        ] {
+            field
+                .set_value(FormFieldValue::new_text(input))
+                .await
+                .unwrap();
+
+            assert!(Email::clean_value(&field).is_err(), "{input} was accepted");
+        }
+    }
+
     #[cot::test]
     async fn email_field_clean_exceeds_max_length() {
         let mut field = EmailField::with_options(
@@ -1073,6 +1221,7 @@ mod tests {
             IntegerFieldOptions {
                 min: Some(1),
                 max: Some(10),
+                step: Some(Step::Value(2)),
             },
         );
         let html = field.to_string();
@@ -1080,6 +1229,7 @@ mod tests {
         assert!(html.contains("required"));
         assert!(html.contains("min=\"1\""));
         assert!(html.contains("max=\"10\""));
+        assert!(html.contains("step=\"2\""));
     }
 
     #[cot::test]
@@ -1093,6 +1243,7 @@ mod tests {
             IntegerFieldOptions {
                 min: Some(1),
                 max: Some(10),
+                step: None,
             },
         );
         field
@@ -1114,6 +1265,7 @@ mod tests {
             IntegerFieldOptions {
                 min: Some(10),
                 max: Some(50),
+                step: None,
             },
         );
         field
@@ -1138,6 +1290,7 @@ mod tests {
             IntegerFieldOptions {
                 min: Some(10),
                 max: Some(50),
+                step: None,
             },
         );
         field
@@ -1151,6 +1304,27 @@ mod tests {
         ));
     }
 
+    #[cot::test]
+    async fn integer_field_clean_value_not_a_number() {
+        let mut field = IntegerField::<i32>::with_options(
+            FormFieldOptions {
+                id: "test".to_owned(),
+                name: "test".to_owned(),
+                required: true,
+            },
+            IntegerFieldOptions::default(),
+        );
+        field
+            .set_value(FormFieldValue::new_text("ten"))
+            .await
+            .unwrap();
+        let value = i32::clean_value(&field);
+        assert!(matches!(
+            value,
+            Err(FormFieldValidationError::InvalidValue(value)) if value == "ten"
+        ));
+    }
+
     #[test]
     fn bool_field_render() {
         let field = BoolField::with_options(
@@ -1187,6 +1361,58 @@ mod tests {
         assert!(html.contains("required"));
     }
 
+    #[test]
+    fn bool_field_clean_value_missing() {
+        let field = BoolField::with_options(
+            FormFieldOptions {
+                id: "test".to_owned(),
+                name: "test".to_owned(),
+                required: false,
+            },
+            BoolFieldOptions::default(),
+        );
+
+        assert_eq!(bool::clean_value(&field), Ok(false));
+    }
+
+    #[test]
+    fn bool_field_clean_value_missing_must_be_true() {
+        let field = BoolField::with_options(
+            FormFieldOptions {
+                id: "test".to_owned(),
+                name: "test".to_owned(),
+                required: false,
+            },
+            BoolFieldOptions {
+                must_be_true: Some(true),
+            },
+        );
+
+        assert_eq!(
+            bool::clean_value(&field),
+            Err(FormFieldValidationError::BooleanRequiredToBeTrue)
+        );
+    }
+
+    #[cot::test]
+    async fn bool_field_clean_value_on() {
+        let mut field = BoolField::with_options(
+            FormFieldOptions {
+                id: "test".to_owned(),
+                name: "test".to_owned(),
+                required: false,
+            },
+            BoolFieldOptions::default(),
+        );
+        field
+            .set_value(FormFieldValue::new_text("on"))
+            .await
+            .unwrap();
+
+        assert_eq!(bool::clean_value(&field), Ok(true));
+        assert!(field.to_string().contains("checked"));
+    }
+
     #[cot::test]
     async fn bool_field_clean_value() {
         let mut field = BoolField::with_options(
@@ -1218,6 +1444,7 @@ mod tests {
             FloatFieldOptions {
                 min: Some(1.5),
                 max: Some(10.7),
+                step: Some(Step::Any),
             },
         );
         let html = field.to_string();
@@ -1225,6 +1452,7 @@ mod tests {
         assert!(html.contains("required"));
         assert!(html.contains("min=\"1.5\""));
         assert!(html.contains("max=\"10.7\""));
+        assert!(html.contains("step=\"any\""));
     }
 
     #[cot::test]
@@ -1239,6 +1467,7 @@ mod tests {
             FloatFieldOptions {
                 min: Some(1.0),
                 max: Some(10.0),
+                step: None,
             },
         );
         field
@@ -1260,6 +1489,7 @@ mod tests {
             FloatFieldOptions {
                 min: Some(5.0),
                 max: Some(10.0),
+                step: None,
             },
         );
         field
@@ -1284,6 +1514,7 @@ mod tests {
             FloatFieldOptions {
                 min: Some(5.0),
                 max: Some(10.0),
+                step: None,
             },
         );
         field
@@ -1308,6 +1539,7 @@ mod tests {
             FloatFieldOptions {
                 min: Some(1.0),
                 max: Some(10.0),
+                step: None,
             },
         );
         let bad_inputs = ["NaN", "inf"];
@@ -1338,6 +1570,7 @@ mod tests {
             FloatFieldOptions {
                 min: Some(1.0),
                 max: Some(10.0),
+                step: None,
             },
         );
         field.set_value(FormFieldValue::new_text("")).await.unwrap();
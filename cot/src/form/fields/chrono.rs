@@ -100,6 +100,10 @@ const BROWSER_DATE_FMT: &str = "%Y-%m-%d";
 const BROWSER_TIME_FMT: &str = "%H:%M:%S";
 const BROWSER_TIME_WITHOUT_SEC_FMT: &str = "%H:%M";
 
+const DATETIME_FORMAT_DESCRIPTION: &str = "YYYY-MM-DDTHH:MM";
+const DATE_FORMAT_DESCRIPTION: &str = "YYYY-MM-DD";
+const TIME_FORMAT_DESCRIPTION: &str = "HH:MM";
+
 fn parse_datetime_with_fallback(value: &str) -> Result<NaiveDateTime, ParseError> {
     NaiveDateTime::parse_from_str(value, BROWSER_DATETIME_FMT)
         .or_else(|_| NaiveDateTime::parse_from_str(value, BROWSER_DATETIME_WITHOUT_SEC_FMT))
@@ -215,7 +219,8 @@ impl AsFormField for NaiveDateTime {
         Self: Sized,
     {
         let value = check_required(field)?;
-        let date_time = parse_datetime_with_fallback(value)?;
+        let date_time = parse_datetime_with_fallback(value)
+            .map_err(|_| FormFieldValidationError::invalid_format(DATETIME_FORMAT_DESCRIPTION))?;
         let opts = &field.custom_options;
 
         if let Some(min) = &opts.min
@@ -234,7 +239,7 @@ impl AsFormField for NaiveDateTime {
     }
 
     fn to_field_value(&self) -> String {
-        self.to_string()
+        self.format(BROWSER_DATETIME_FMT).to_string()
     }
 }
 
@@ -343,6 +348,22 @@ pub struct DateTimeWithTimezoneFieldOptions {
     pub prefer_latest: Option<bool>,
 }
 
+impl DateTimeWithTimezoneField {
+    /// Returns the timezone the browser-supplied values are interpreted in.
+    fn timezone(&self) -> Tz {
+        self.custom_options.timezone.unwrap_or(Tz::UTC)
+    }
+
+    /// Formats the datetime as the local time in the field's timezone, which
+    /// is the format the browsers expect.
+    fn format_local(&self, date_time: &DateTime<FixedOffset>, format: &str) -> String {
+        date_time
+            .with_timezone(&self.timezone())
+            .format(format)
+            .to_string()
+    }
+}
+
 impl Display for DateTimeWithTimezoneField {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut tag = HtmlTag::input("datetime-local");
@@ -355,17 +376,13 @@ impl Display for DateTimeWithTimezoneField {
         if let Some(max) = self.custom_options.max {
             tag.attr(
                 "max",
-                max.naive_local()
-                    .format(BROWSER_DATETIME_WITHOUT_SEC_FMT)
-                    .to_string(),
+                self.format_local(&max, BROWSER_DATETIME_WITHOUT_SEC_FMT),
             );
         }
         if let Some(min) = self.custom_options.min {
             tag.attr(
                 "min",
-                min.naive_local()
-                    .format(BROWSER_DATETIME_WITHOUT_SEC_FMT)
-                    .to_string(),
+                self.format_local(&min, BROWSER_DATETIME_WITHOUT_SEC_FMT),
             );
         }
 
@@ -376,7 +393,16 @@ impl Display for DateTimeWithTimezoneField {
         }
 
         if let Some(value) = &self.value {
-            tag.attr("value", value);
+            // the values set with `to_field_value` carry their offset, which
+            // the browsers don't support
+            match DateTime::parse_from_rfc3339(value) {
+                Ok(date_time) => {
+                    tag.attr("value", self.format_local(&date_time, BROWSER_DATETIME_FMT));
+                }
+                Err(_) => {
+                    tag.attr("value", value);
+                }
+            }
         }
 
         if let Some(step) = &self.custom_options.step {
@@ -399,30 +425,13 @@ impl AsFormField for DateTime<FixedOffset> {
         Self: Sized,
     {
         let value = check_required(field)?;
-        // Browsers only support naive datetime.
-        let naive = parse_datetime_with_fallback(value)?;
-        // default to UTC if offset(timezone) is not provided.
-        let tz = field.custom_options.timezone.unwrap_or(Tz::UTC);
-
-        let date_time = match tz.from_local_datetime(&naive) {
-            LocalResult::Single(dt) => dt,
-            LocalResult::Ambiguous(dt1, dt2) => {
-                if let Some(prefer_latest) = field.custom_options.prefer_latest {
-                    if prefer_latest { dt2 } else { dt1 }
-                } else {
-                    return Err(FormFieldValidationError::ambiguous_datetime(naive));
-                }
-            }
-            LocalResult::None => {
-                return Err(FormFieldValidationError::non_existent_local_datetime(
-                    naive, tz,
-                ));
-            }
+        let date_time = match DateTime::parse_from_rfc3339(value) {
+            // the value has been set with `to_field_value`
+            Ok(date_time) => date_time,
+            Err(_) => parse_local_datetime(field, value)?,
         };
 
         let opts = &field.custom_options;
-        // transform the timezone into a fixed offset.
-        let date_time = date_time.with_timezone(&date_time.offset().fix());
 
         if let Some(min) = &opts.min
             && date_time < *min
@@ -440,10 +449,43 @@ impl AsFormField for DateTime<FixedOffset> {
     }
 
     fn to_field_value(&self) -> String {
-        self.to_string()
+        // the offset is kept, so that the field can convert the value to its
+        // timezone when rendering it for the browser
+        self.to_rfc3339()
     }
 }
 
+/// Parses the naive datetime sent by the browser as the local time in the
+/// field's timezone.
+fn parse_local_datetime(
+    field: &DateTimeWithTimezoneField,
+    value: &str,
+) -> Result<DateTime<FixedOffset>, FormFieldValidationError> {
+    // Browsers only support naive datetime.
+    let naive = parse_datetime_with_fallback(value)
+        .map_err(|_| FormFieldValidationError::invalid_format(DATETIME_FORMAT_DESCRIPTION))?;
+    let tz = field.timezone();
+
+    let date_time = match tz.from_local_datetime(&naive) {
+        LocalResult::Single(dt) => dt,
+        LocalResult::Ambiguous(dt1, dt2) => {
+            if let Some(prefer_latest) = field.custom_options.prefer_latest {
+                if prefer_latest { dt2 } else { dt1 }
+            } else {
+                return Err(FormFieldValidationError::ambiguous_datetime(naive));
+            }
+        }
+        LocalResult::None => {
+            return Err(FormFieldValidationError::non_existent_local_datetime(
+                naive, tz,
+            ));
+        }
+    };
+
+    // transform the timezone into a fixed offset.
+    Ok(date_time.with_timezone(&date_time.offset().fix()))
+}
+
 impl HtmlSafe for DateTimeWithTimezoneField {}
 
 impl_form_field!(TimeField, TimeFieldOptions, "a time");
@@ -541,7 +583,8 @@ impl AsFormField for NaiveTime {
         Self: Sized,
     {
         let value = check_required(field)?;
-        let time = parse_time_with_fallback(value)?;
+        let time = parse_time_with_fallback(value)
+            .map_err(|_| FormFieldValidationError::invalid_format(TIME_FORMAT_DESCRIPTION))?;
         let opts = &field.custom_options;
 
         if let Some(min) = &opts.min
@@ -663,7 +706,7 @@ impl AsFormField for NaiveDate {
     {
         let value = check_required(field)?;
         let date = NaiveDate::parse_from_str(value, BROWSER_DATE_FMT)
-            .map_err(|err| FormFieldValidationError::from_string(err.to_string()))?;
+            .map_err(|_| FormFieldValidationError::invalid_format(DATE_FORMAT_DESCRIPTION))?;
         let opts = &field.custom_options;
 
         if let Some(min) = &opts.min
@@ -1509,7 +1552,47 @@ mod tests {
             .unwrap();
 
         let result = DateTime::<FixedOffset>::clean_value(&field);
-        assert!(result.is_err());
+        assert_eq!(
+            result,
+            Err(FormFieldValidationError::invalid_format("YYYY-MM-DDTHH:MM"))
+        );
+    }
+
+    #[test]
+    fn datetime_to_field_value() {
+        let naive =
+            NaiveDateTime::parse_from_str("2025-05-27T13:03:05", BROWSER_DATETIME_FMT).unwrap();
+        assert_eq!(naive.to_field_value(), "2025-05-27T13:03:05");
+
+        let with_offset = DateTime::parse_from_rfc3339("2025-05-27T13:03:05+02:00").unwrap();
+        assert_eq!(with_offset.to_field_value(), "2025-05-27T13:03:05+02:00");
+    }
+
+    #[cot::test]
+    async fn datetime_with_tz_field_value_round_trip() {
+        let mut field = DateTimeWithTimezoneField::with_options(
+            FormFieldOptions {
+                id: "dt".into(),
+                name: "dt".into(),
+                required: true,
+            },
+            DateTimeWithTimezoneFieldOptions {
+                timezone: Some(Tz::America__New_York),
+                ..Default::default()
+            },
+        );
+        let date_time = DateTime::parse_from_rfc3339("2025-05-27T13:03:05+05:30").unwrap();
+
+        field
+            .set_value(FormFieldValue::new_text(date_time.to_field_value()))
+            .await
+            .unwrap();
+
+        // 07:33:05 UTC is 03:33:05 in New York (EDT)
+        assert!(field.to_string().contains("value=\"2025-05-27T03:33:05\""));
+        let cleaned = DateTime::<FixedOffset>::clean_value(&field).unwrap();
+        assert_eq!(cleaned, date_time);
+        assert_eq!(cleaned.offset(), date_time.offset());
     }
 
     #[cot::test]
@@ -1735,4 +1818,26 @@ mod tests {
             FormFieldValidationError::MaximumValueExceeded { .. }
         ));
     }
+
+    #[cot::test]
+    async fn date_field_clean_invalid_format() {
+        let mut field = DateField::with_options(
+            FormFieldOptions {
+                id: "d".into(),
+                name: "d".into(),
+                required: true,
+            },
+            DateFieldOptions::default(),
+        );
+        field
+            .set_value(FormFieldValue::new_text("27/05/2025"))
+            .await
+            .unwrap();
+        let err = NaiveDate::clean_value(&field).unwrap_err();
+        assert_eq!(err, FormFieldValidationError::invalid_format("YYYY-MM-DD"));
+        assert_eq!(
+            err.to_string(),
+            "Enter a valid value in the YYYY-MM-DD format."
+        );
+    }
 }
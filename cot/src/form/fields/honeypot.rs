@@ -0,0 +1,216 @@
+use std::fmt::{Display, Formatter};
+
+use askama::filters::HtmlSafe;
+
+use crate::form::fields::impl_form_field;
+use crate::form::{AsFormField, FormField, FormFieldOptions, FormFieldValidationError};
+use crate::html::HtmlTag;
+
+impl_form_field!(HoneypotField, HoneypotFieldOptions, "a honeypot");
+
+/// Custom options for a [`HoneypotField`].
+#[derive(Debug, Default, Copy, Clone)]
+pub struct HoneypotFieldOptions {
+    /// Whether a filled honeypot should pass the validation. If set to
+    /// `true`, the form is valid even if a bot filled the honeypot, so that
+    /// the bot doesn't learn that it has been detected; the handler then has
+    /// to check [`Honeypot::is_filled`] and pretend the submission succeeded.
+    /// Defaults to `false`, meaning that a filled honeypot makes the form
+    /// invalid.
+    pub silent: Option<bool>,
+}
+
+/// The CSS class of the element wrapping the honeypot input.
+pub const HONEYPOT_CLASS: &str = "cot-honeypot";
+
+impl Display for HoneypotField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut label = HtmlTag::new("label");
+        label.attr("for", self.id());
+        label.push_str("Leave this field empty");
+
+        let mut input = HtmlTag::input("text");
+        input.attr("name", self.id());
+        input.attr("id", self.id());
+        input.attr("tabindex", "-1");
+        input.attr("autocomplete", "off");
+        if let Some(value) = &self.value {
+            input.attr("value", value);
+        }
+
+        // `display: none` is avoided, as some bots skip the inputs hidden this way
+        let mut wrapper = HtmlTag::new("div");
+        wrapper.add_class(HONEYPOT_CLASS);
+        wrapper.aria("hidden", "true");
+        wrapper.attr(
+            "style",
+            "position: absolute; left: -10000px; width: 1px; height: 1px; overflow: hidden;",
+        );
+        wrapper.push_tag(label);
+        wrapper.push_tag(input);
+
+        write!(f, "{}", wrapper.render())
+    }
+}
+
+impl HtmlSafe for HoneypotField {}
+
+/// A honeypot form field, used to detect spam bots.
+///
+/// The field is rendered as a text input that is hidden from the users, so
+/// real users leave it empty, while bots filling all the inputs they find
+/// fill it as well. By default, a filled honeypot makes the form invalid; see
+/// [`HoneypotFieldOptions::silent`] to accept such submissions instead and
+/// handle them with [`Honeypot::is_filled`].
+///
+/// The input is hidden with inline styles, so it works without any
+/// additional CSS. It's wrapped in an element with the [`HONEYPOT_CLASS`]
+/// class, which can be used to hide it in a stylesheet instead. Avoid
+/// `display: none`, as some bots ignore the inputs hidden this way.
+///
+/// The name of the input is the name of the form field, so it's a good idea
+/// to name it like something bots are willing to fill, such as `website` or
+/// `phone`, and change it from time to time.
+///
+/// This is a lightweight spam defense and pairs well with the CSRF
+/// protection.
+///
+/// # Examples
+///
+/// ```
+/// use cot::form::Form;
+/// use cot::form::fields::Honeypot;
+///
+/// #[derive(Form)]
+/// struct CommentForm {
+///     comment: String,
+///     website: Honeypot,
+/// }
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Honeypot {
+    filled: bool,
+}
+
+impl Honeypot {
+    /// Returns whether the honeypot was filled, meaning that the form was
+    /// most likely submitted by a bot.
+    ///
+    /// This can only return `true` if the field has the
+    /// [`silent`](HoneypotFieldOptions::silent) option set; otherwise, the
+    /// form is invalid in such case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::form::fields::Honeypot;
+    ///
+    /// assert!(!Honeypot::default().is_filled());
+    /// ```
+    #[must_use]
+    pub fn is_filled(&self) -> bool {
+        self.filled
+    }
+}
+
+impl AsFormField for Honeypot {
+    type Type = HoneypotField;
+
+    fn new_field(
+        mut options: FormFieldOptions,
+        custom_options: <Self::Type as FormField>::CustomOptions,
+    ) -> Self::Type {
+        options.required = false;
+        Self::Type::with_options(options, custom_options)
+    }
+
+    fn clean_value(field: &Self::Type) -> Result<Self, FormFieldValidationError> {
+        let filled = field.value().is_some_and(|value| !value.is_empty());
+
+        if filled && !field.custom_options.silent.unwrap_or(false) {
+            return Err(FormFieldValidationError::from_static(
+                "This field must be left empty.",
+            ));
+        }
+        Ok(Self { filled })
+    }
+
+    fn to_field_value(&self) -> String {
+        String::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::form::FormFieldValue;
+
+    fn honeypot_field(silent: Option<bool>) -> HoneypotField {
+        Honeypot::new_field(
+            FormFieldOptions {
+                id: "website".to_owned(),
+                name: "Website".to_owned(),
+                required: true,
+            },
+            HoneypotFieldOptions { silent },
+        )
+    }
+
+    #[test]
+    fn honeypot_field_render() {
+        let field = honeypot_field(None);
+
+        let html = field.to_string();
+
+        assert!(html.contains("class=\"cot-honeypot\""));
+        assert!(html.contains("aria-hidden=\"true\""));
+        assert!(html.contains("name=\"website\""));
+        assert!(html.contains("tabindex=\"-1\""));
+        assert!(html.contains("autocomplete=\"off\""));
+        assert!(!html.contains("required"));
+        assert!(!html.contains("display: none"));
+    }
+
+    #[cot::test]
+    async fn honeypot_clean_value_empty() {
+        let mut field = honeypot_field(None);
+        assert_eq!(
+            Honeypot::clean_value(&field),
+            Ok(Honeypot { filled: false })
+        );
+
+        field.set_value(FormFieldValue::new_text("")).await.unwrap();
+        assert_eq!(
+            Honeypot::clean_value(&field),
+            Ok(Honeypot { filled: false })
+        );
+    }
+
+    #[cot::test]
+    async fn honeypot_clean_value_filled() {
+        let mut field = honeypot_field(None);
+        field
+            .set_value(FormFieldValue::new_text("https://spam.example.com"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            Honeypot::clean_value(&field),
+            Err(FormFieldValidationError::from_static(
+                "This field must be left empty."
+            ))
+        );
+    }
+
+    #[cot::test]
+    async fn honeypot_clean_value_filled_silent() {
+        let mut field = honeypot_field(Some(true));
+        field
+            .set_value(FormFieldValue::new_text("https://spam.example.com"))
+            .await
+            .unwrap();
+
+        let honeypot = Honeypot::clean_value(&field).unwrap();
+        assert!(honeypot.is_filled());
+    }
+}
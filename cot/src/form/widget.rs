@@ -0,0 +1,177 @@
+use std::fmt::{Debug, Display, Formatter};
+
+use askama::filters::HtmlSafe;
+
+use crate::form::{FormField, FormFieldOptions, FormFieldValue, FormFieldValueError};
+use crate::html::Html;
+
+/// A custom way of rendering a form field.
+///
+/// By default, each form field is rendered using the [`Display`]
+/// implementation of its [`FormField`] type. A widget replaces that rendering
+/// while keeping the parsing and validation of the field type, which allows
+/// using e.g. date pickers or rich text editors for the fields.
+///
+/// The widget can be attached to a field in a form deriving
+/// [`Form`](derive@crate::form::Form) with the `#[form(widget = MyWidget)]`
+/// attribute. The widget is created using its [`Default`] implementation.
+///
+/// # Safety
+///
+/// The returned HTML is rendered as-is, so the implementors are responsible
+/// for escaping the value of the field. The easiest way to do this is to use
+/// [`HtmlTag`](crate::html::HtmlTag), which escapes the attribute values and
+/// the text content.
+///
+/// # Examples
+///
+/// ```
+/// use cot::form::{Form, FormContext, FormFieldOptions, FormWidget};
+/// use cot::html::{Html, HtmlTag};
+///
+/// #[derive(Debug, Default)]
+/// struct TextareaWidget;
+///
+/// impl FormWidget for TextareaWidget {
+///     fn render(&self, options: &FormFieldOptions, value: Option<&str>) -> Html {
+///         let mut tag = HtmlTag::new("textarea");
+///         tag.attr("name", &options.id);
+///         tag.attr("id", &options.id);
+///         if options.required {
+///             tag.bool_attr("required");
+///         }
+///         tag.push_str(value.unwrap_or_default());
+///         tag.render()
+///     }
+/// }
+///
+/// #[derive(Form)]
+/// struct PostForm {
+///     title: String,
+///     #[form(widget = TextareaWidget)]
+///     content: String,
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let context = PostForm::with_initial(&[("content", "Hello, world!")]).await;
+/// assert!(context.to_string().contains(
+///     r#"<textarea name="content" id="content" required>Hello, world!</textarea>"#
+/// ));
+/// # }
+/// ```
+pub trait FormWidget: Debug + Default {
+    /// Renders the form field with the given options and current value.
+    ///
+    /// The [`FormFieldOptions::id`] should be used as the `name` of the HTML
+    /// form element, so that the submitted value is assigned to the field.
+    fn render(&self, options: &FormFieldOptions, value: Option<&str>) -> Html;
+}
+
+/// A form field rendered using a custom [`FormWidget`].
+///
+/// This wraps a [`FormField`] and delegates everything except for rendering to
+/// it. It is typically created by the [`Form`](derive@crate::form::Form)
+/// derive macro for the fields marked with `#[form(widget = ...)]`.
+#[derive(Debug)]
+pub struct WithWidget<F, W> {
+    field: F,
+    widget: W,
+}
+
+impl<F: FormField, W: FormWidget> WithWidget<F, W> {
+    /// Creates a new form field rendered using the given widget.
+    #[must_use]
+    pub fn new(field: F, widget: W) -> Self {
+        Self { field, widget }
+    }
+
+    /// Returns the wrapped form field.
+    #[must_use]
+    pub fn field(&self) -> &F {
+        &self.field
+    }
+
+    /// Returns the widget used to render the field.
+    #[must_use]
+    pub fn widget(&self) -> &W {
+        &self.widget
+    }
+}
+
+impl<F: FormField, W: FormWidget> FormField for WithWidget<F, W> {
+    type CustomOptions = F::CustomOptions;
+
+    fn with_options(options: FormFieldOptions, custom_options: Self::CustomOptions) -> Self {
+        Self::new(F::with_options(options, custom_options), W::default())
+    }
+
+    fn options(&self) -> &FormFieldOptions {
+        self.field.options()
+    }
+
+    fn value(&self) -> Option<&str> {
+        self.field.value()
+    }
+
+    fn set_value(
+        &mut self,
+        field: FormFieldValue<'_>,
+    ) -> impl Future<Output = Result<(), FormFieldValueError>> + Send {
+        self.field.set_value(field)
+    }
+}
+
+impl<F: FormField, W: FormWidget> Display for WithWidget<F, W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let html = self.widget.render(self.field.options(), self.field.value());
+        f.write_str(html.as_str())
+    }
+}
+
+impl<F: FormField, W: FormWidget> HtmlSafe for WithWidget<F, W> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::form::fields::{StringField, StringFieldOptions};
+    use crate::html::HtmlTag;
+
+    #[derive(Debug, Default)]
+    struct ColorWidget;
+
+    impl FormWidget for ColorWidget {
+        fn render(&self, options: &FormFieldOptions, value: Option<&str>) -> Html {
+            let mut tag = HtmlTag::input("color");
+            tag.attr("name", &options.id);
+            if let Some(value) = value {
+                tag.attr("value", value);
+            }
+            tag.render()
+        }
+    }
+
+    #[cot::test]
+    async fn with_widget_delegates_to_field() {
+        let mut field = WithWidget::<StringField, ColorWidget>::with_options(
+            FormFieldOptions {
+                id: "color".to_owned(),
+                name: "Color".to_owned(),
+                required: true,
+            },
+            StringFieldOptions::default(),
+        );
+        field
+            .set_value(FormFieldValue::new_text("#ff0000"))
+            .await
+            .unwrap();
+
+        assert_eq!(field.id(), "color");
+        assert_eq!(field.value(), Some("#ff0000"));
+        assert_eq!(field.field().value(), Some("#ff0000"));
+        assert_eq!(
+            field.to_string(),
+            r##"<input type="color" name="color" value="#ff0000"/>"##
+        );
+    }
+}
@@ -0,0 +1,1024 @@
+//! Internationalization (i18n) support.
+//!
+//! This module allows serving the project in multiple languages. The
+//! translated messages are stored in [`Catalog`]s, one for each locale, which
+//! are collected in [`Translations`]. The [`I18nMiddleware`] resolves the
+//! locale of each request and makes the translations available to the
+//! handlers through the [`I18n`] extractor, the [`t!`](crate::t) macro, and
+//! [`RequestExt::locale`](crate::request::RequestExt::locale).
+//!
+//! The locale of a request is resolved in the following order:
+//!
+//! 1. the locale set in the locale cookie (`locale` by default), if there are
+//!    translations for it; this allows the users to override the language,
+//! 2. the best match for the `Accept-Language` header among the locales with
+//!    translations,
+//! 3. the default locale.
+//!
+//! When a message is missing in the catalog for the resolved locale (e.g.,
+//! `de-AT`), it is looked up in the catalog for its language (`de`), and then
+//! in the catalog for the default locale. If it's missing there as well, the
+//! key itself is returned.
+//!
+//! # Catalog format
+//!
+//! The catalogs use a subset of the [Fluent](https://projectfluent.org/)
+//! syntax, supporting simple messages with variables:
+//!
+//! ```ftl
+//! # Comments start with a hash sign
+//! hello = Hello, { $name }!
+//! welcome =
+//!     Welcome to our website.
+//!     We hope you enjoy your stay.
+//! ```
+//!
+//! # Examples
+//!
+//! ```
+//! use cot::html::Html;
+//! use cot::i18n::{Catalog, I18n, I18nMiddleware, Translations};
+//! use cot::t;
+//!
+//! async fn hello(i18n: I18n) -> Html {
+//!     Html::new(t!(i18n, "hello", name = "Alice"))
+//! }
+//!
+//! # fn main() -> Result<(), cot::i18n::CatalogParseError> {
+//! let translations = Translations::new("en")
+//!     .add_catalog("en", Catalog::parse("hello = Hello, { $name }!")?)
+//!     .add_catalog("pl", Catalog::parse("hello = Cześć, { $name }!")?);
+//! let middleware = I18nMiddleware::new(translations);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::{Display, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use cot_core::error::impl_into_cot_error;
+use futures_core::future::BoxFuture;
+use thiserror::Error;
+use tower::Service;
+use tracing::debug;
+
+use crate::request::extractors::FromRequestHead;
+use crate::request::{Request, RequestExt, RequestHead};
+use crate::response::Response;
+use crate::utils::cookies::cookie_value;
+
+/// The default name of the cookie overriding the locale of a request.
+pub const DEFAULT_LOCALE_COOKIE_NAME: &str = "locale";
+
+/// An error that occurs when parsing a [`Catalog`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[non_exhaustive]
+pub enum CatalogParseError {
+    /// The line is not a comment, a message, or a continuation of a message.
+    #[error("line {line}: expected a message in the form `key = value`")]
+    InvalidLine {
+        /// The number of the line, starting from 1.
+        line: usize,
+    },
+    /// The message key contains invalid characters.
+    #[error("line {line}: invalid message key `{key}`")]
+    InvalidKey {
+        /// The number of the line, starting from 1.
+        line: usize,
+        /// The invalid key.
+        key: String,
+    },
+    /// The message key is defined more than once.
+    #[error("line {line}: message `{key}` is already defined")]
+    DuplicateKey {
+        /// The number of the line, starting from 1.
+        line: usize,
+        /// The duplicated key.
+        key: String,
+    },
+}
+
+/// An error that occurs when loading the translations.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum I18nError {
+    /// The catalog file or directory could not be read.
+    #[error("could not read translations from `{path}`")]
+    Io {
+        /// The path that could not be read.
+        path: PathBuf,
+        /// The I/O error.
+        #[source]
+        source: std::io::Error,
+    },
+    /// The catalog file could not be parsed.
+    #[error("could not parse the catalog `{path}`")]
+    Parse {
+        /// The path of the catalog file.
+        path: PathBuf,
+        /// The parse error.
+        #[source]
+        source: CatalogParseError,
+    },
+}
+impl_into_cot_error!(I18nError);
+
+/// The translated messages for a single locale.
+///
+/// # Examples
+///
+/// ```
+/// use cot::i18n::Catalog;
+///
+/// let catalog = Catalog::parse("hello = Hello, { $name }!")?;
+/// assert_eq!(catalog.get("hello"), Some("Hello, { $name }!"));
+/// # Ok::<(), cot::i18n::CatalogParseError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Catalog {
+    messages: HashMap<String, String>,
+}
+
+impl Catalog {
+    /// Creates an empty catalog.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::i18n::Catalog;
+    ///
+    /// let catalog = Catalog::new().insert("hello", "Hello!");
+    /// assert_eq!(catalog.get("hello"), Some("Hello!"));
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a catalog in the Fluent-like format described in the
+    /// [module documentation](self).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the catalog contains invalid lines, invalid keys,
+    /// or duplicated keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::i18n::Catalog;
+    ///
+    /// let catalog = Catalog::parse(
+    ///     "# greetings\n\
+    ///      hello = Hello!\n\
+    ///      bye = Goodbye!",
+    /// )?;
+    /// assert_eq!(catalog.get("bye"), Some("Goodbye!"));
+    /// # Ok::<(), cot::i18n::CatalogParseError>(())
+    /// ```
+    pub fn parse(source: &str) -> Result<Self, CatalogParseError> {
+        let mut messages: HashMap<String, String> = HashMap::new();
+        let mut current_key: Option<String> = None;
+
+        for (index, line) in source.lines().enumerate() {
+            let line_number = index + 1;
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            if line.starts_with([' ', '\t']) {
+                let key = current_key
+                    .as_ref()
+                    .ok_or(CatalogParseError::InvalidLine { line: line_number })?;
+                let value = messages
+                    .get_mut(key)
+                    .expect("the current message should be in the catalog");
+                if !value.is_empty() {
+                    value.push('\n');
+                }
+                value.push_str(trimmed);
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or(CatalogParseError::InvalidLine { line: line_number })?;
+            let key = key.trim();
+            if !is_valid_key(key) {
+                return Err(CatalogParseError::InvalidKey {
+                    line: line_number,
+                    key: key.to_owned(),
+                });
+            }
+            if messages.contains_key(key) {
+                return Err(CatalogParseError::DuplicateKey {
+                    line: line_number,
+                    key: key.to_owned(),
+                });
+            }
+
+            messages.insert(key.to_owned(), value.trim().to_owned());
+            current_key = Some(key.to_owned());
+        }
+
+        Ok(Self { messages })
+    }
+
+    /// Adds a message to the catalog, replacing the existing message with the
+    /// same key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::i18n::Catalog;
+    ///
+    /// let catalog = Catalog::new().insert("hello", "Hello, { $name }!");
+    /// assert_eq!(catalog.get("hello"), Some("Hello, { $name }!"));
+    /// ```
+    #[must_use]
+    pub fn insert(mut self, key: impl Into<String>, message: impl Into<String>) -> Self {
+        self.messages.insert(key.into(), message.into());
+        self
+    }
+
+    /// Returns the message with the given key, without substituting the
+    /// variables.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::i18n::Catalog;
+    ///
+    /// let catalog = Catalog::new().insert("hello", "Hello!");
+    /// assert_eq!(catalog.get("hello"), Some("Hello!"));
+    /// assert_eq!(catalog.get("bye"), None);
+    /// ```
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.messages.get(key).map(String::as_str)
+    }
+}
+
+fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+}
+
+/// The message catalogs for all the locales supported by the project.
+///
+/// # Examples
+///
+/// ```
+/// use cot::i18n::{Catalog, Translations};
+///
+/// let translations = Translations::new("en")
+///     .add_catalog("en", Catalog::new().insert("hello", "Hello!"))
+///     .add_catalog("pl", Catalog::new().insert("hello", "Cześć!"));
+///
+/// assert_eq!(translations.translate("pl", "hello", &[]), "Cześć!");
+/// // falls back to the default locale
+/// assert_eq!(translations.translate("de", "hello", &[]), "Hello!");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Translations {
+    default_locale: String,
+    locales: Vec<String>,
+    catalogs: HashMap<String, Catalog>,
+}
+
+impl Translations {
+    /// Creates translations with no catalogs and the given default locale.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::i18n::Translations;
+    ///
+    /// let translations = Translations::new("en");
+    /// assert_eq!(translations.default_locale(), "en");
+    /// ```
+    #[must_use]
+    pub fn new(default_locale: impl Into<String>) -> Self {
+        Self {
+            default_locale: default_locale.into(),
+            locales: Vec::new(),
+            catalogs: HashMap::new(),
+        }
+    }
+
+    /// Loads the catalogs from the `.ftl` files in the given directory, using
+    /// the file names (without the extension) as the locales, e.g.,
+    /// `locales/en.ftl` and `locales/pt-BR.ftl`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory or any of the catalog files could
+    /// not be read or parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use cot::i18n::Translations;
+    ///
+    /// let translations = Translations::load_dir("en", "locales")?;
+    /// # Ok::<(), cot::i18n::I18nError>(())
+    /// ```
+    pub fn load_dir(
+        default_locale: impl Into<String>,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, I18nError> {
+        let path = path.as_ref();
+        let io_error = |path: &Path| {
+            let path = path.to_owned();
+            move |source| I18nError::Io { path, source }
+        };
+
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(path).map_err(io_error(path))? {
+            let file_path = entry.map_err(io_error(path))?.path();
+            if file_path.extension().is_some_and(|ext| ext == "ftl") {
+                files.push(file_path);
+            }
+        }
+        // make the order of the locales deterministic
+        files.sort();
+
+        let mut translations = Self::new(default_locale);
+        for file_path in files {
+            let Some(locale) = file_path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let source = std::fs::read_to_string(&file_path).map_err(io_error(&file_path))?;
+            let catalog = Catalog::parse(&source).map_err(|source| I18nError::Parse {
+                path: file_path.clone(),
+                source,
+            })?;
+            translations = translations.add_catalog(locale, catalog);
+        }
+
+        Ok(translations)
+    }
+
+    /// Adds the catalog for the given locale. If there already is a catalog
+    /// for the locale, the messages are merged, with the new ones taking
+    /// precedence.
+    ///
+    /// The locales are matched case-insensitively.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::i18n::{Catalog, Translations};
+    ///
+    /// let translations =
+    ///     Translations::new("en").add_catalog("en", Catalog::new().insert("hello", "Hello!"));
+    /// ```
+    #[must_use]
+    pub fn add_catalog(mut self, locale: impl Into<String>, catalog: Catalog) -> Self {
+        let locale = locale.into();
+        match self.catalogs.get_mut(&locale.to_ascii_lowercase()) {
+            Some(existing) => existing.messages.extend(catalog.messages),
+            None => {
+                self.catalogs.insert(locale.to_ascii_lowercase(), catalog);
+                self.locales.push(locale);
+            }
+        }
+        self
+    }
+
+    /// Returns the default locale.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::i18n::Translations;
+    ///
+    /// assert_eq!(Translations::new("en").default_locale(), "en");
+    /// ```
+    #[must_use]
+    pub fn default_locale(&self) -> &str {
+        &self.default_locale
+    }
+
+    /// Returns the locales with a catalog, in the order they were added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::i18n::{Catalog, Translations};
+    ///
+    /// let translations = Translations::new("en")
+    ///     .add_catalog("en", Catalog::new())
+    ///     .add_catalog("pl", Catalog::new());
+    /// assert_eq!(translations.locales(), ["en", "pl"]);
+    /// ```
+    #[must_use]
+    pub fn locales(&self) -> &[String] {
+        &self.locales
+    }
+
+    /// Returns the message with the given key for the given locale, falling
+    /// back to the language of the locale and then to the default locale.
+    ///
+    /// The variables are not substituted; use [`Self::translate`] to get a
+    /// message ready to be displayed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::i18n::{Catalog, Translations};
+    ///
+    /// let translations = Translations::new("en")
+    ///     .add_catalog("en", Catalog::new().insert("hello", "Hello!"))
+    ///     .add_catalog("de", Catalog::new().insert("hello", "Hallo!"));
+    ///
+    /// assert_eq!(translations.message("de-AT", "hello"), Some("Hallo!"));
+    /// assert_eq!(translations.message("de-AT", "bye"), None);
+    /// ```
+    #[must_use]
+    pub fn message(&self, locale: &str, key: &str) -> Option<&str> {
+        let locale = locale.to_ascii_lowercase();
+        let language = locale.split(['-', '_']).next().unwrap_or_default();
+        let default_locale = self.default_locale.to_ascii_lowercase();
+
+        [locale.as_str(), language, default_locale.as_str()]
+            .into_iter()
+            .filter_map(|locale| self.catalogs.get(locale))
+            .find_map(|catalog| catalog.get(key))
+    }
+
+    /// Returns the message with the given key for the given locale, with the
+    /// variables substituted with the values from `args`.
+    ///
+    /// See [`Self::message`] for how the message is looked up. If the message
+    /// is missing, the key itself is returned. Variables without a value are
+    /// left as they are.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::i18n::{Catalog, Translations};
+    ///
+    /// let translations = Translations::new("en").add_catalog(
+    ///     "en",
+    ///     Catalog::new().insert("unread", "{ $name }, you have { $count } new messages."),
+    /// );
+    ///
+    /// assert_eq!(
+    ///     translations.translate("en", "unread", &[("name", &"Alice"), ("count", &3)]),
+    ///     "Alice, you have 3 new messages."
+    /// );
+    /// assert_eq!(translations.translate("en", "missing", &[]), "missing");
+    /// ```
+    #[must_use]
+    pub fn translate(&self, locale: &str, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        match self.message(locale, key) {
+            Some(message) => format_message(message, args),
+            None => {
+                debug!(locale, key, "Translation missing");
+                key.to_owned()
+            }
+        }
+    }
+
+    /// Returns the locale with a catalog that's equal (ignoring the case) to
+    /// the given one.
+    fn supported_locale(&self, locale: &str) -> Option<&str> {
+        self.locales
+            .iter()
+            .find(|supported| supported.eq_ignore_ascii_case(locale))
+            .map(String::as_str)
+    }
+}
+
+/// Substitutes the `{ $name }` placeholders in the message.
+fn format_message(message: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut result = String::with_capacity(message.len());
+    let mut rest = message;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+            rest = &rest[start..];
+            break;
+        };
+
+        let placeholder = &rest[start..=end];
+        let value = rest[start + 1..end]
+            .trim()
+            .strip_prefix('$')
+            .and_then(|name| args.iter().find(|(arg, _)| *arg == name))
+            .map(|(_, value)| value);
+        match value {
+            Some(value) => write!(result, "{value}").expect("writing to a String can't fail"),
+            None => result.push_str(placeholder),
+        }
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+
+    result
+}
+
+/// The translations and the locale resolved for the current request.
+///
+/// This is an extractor that requires the [`I18nMiddleware`] to be enabled.
+/// It can be passed to the templates to translate the messages there.
+///
+/// # Examples
+///
+/// ```
+/// use cot::html::Html;
+/// use cot::i18n::I18n;
+///
+/// async fn hello(i18n: I18n) -> Html {
+///     Html::new(format!("{} ({})", i18n.t("hello"), i18n.locale()))
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct I18n {
+    translations: Arc<Translations>,
+    locale: String,
+}
+
+impl I18n {
+    /// Returns the locale resolved for the request.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::html::Html;
+    /// use cot::i18n::I18n;
+    ///
+    /// async fn index(i18n: I18n) -> Html {
+    ///     Html::new(format!("<html lang=\"{}\"></html>", i18n.locale()))
+    /// }
+    /// ```
+    #[must_use]
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// Returns all the translations of the project.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::html::Html;
+    /// use cot::i18n::I18n;
+    ///
+    /// async fn languages(i18n: I18n) -> Html {
+    ///     Html::new(i18n.translations().locales().join(", "))
+    /// }
+    /// ```
+    #[must_use]
+    pub fn translations(&self) -> &Translations {
+        &self.translations
+    }
+
+    /// Returns the message with the given key translated to the locale of the
+    /// request.
+    ///
+    /// This is a shorthand for [`Self::translate`] without any variables.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::html::Html;
+    /// use cot::i18n::I18n;
+    ///
+    /// async fn hello(i18n: I18n) -> Html {
+    ///     Html::new(i18n.t("hello"))
+    /// }
+    /// ```
+    #[must_use]
+    pub fn t(&self, key: &str) -> String {
+        self.translate(key, &[])
+    }
+
+    /// Returns the message with the given key translated to the locale of the
+    /// request, with the variables substituted with the values from `args`.
+    ///
+    /// The [`t!`](crate::t) macro provides a more convenient syntax for this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::html::Html;
+    /// use cot::i18n::I18n;
+    ///
+    /// async fn hello(i18n: I18n) -> Html {
+    ///     Html::new(i18n.translate("hello", &[("name", &"Alice")]))
+    /// }
+    /// ```
+    #[must_use]
+    pub fn translate(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        self.translations.translate(&self.locale, key, args)
+    }
+}
+
+impl FromRequestHead for I18n {
+    async fn from_request_head(head: &RequestHead) -> cot::Result<Self> {
+        let i18n = head
+            .extensions
+            .get::<I18n>()
+            .expect("I18nMiddleware not enabled for the route/project")
+            .clone();
+
+        Ok(i18n)
+    }
+}
+
+/// Translates a message to the locale of the request.
+///
+/// The first argument is an [`I18n`] instance, the second one is the message
+/// key, and the rest are the values of the variables in the message, in the
+/// form `name = value`. The values can be of any type implementing
+/// [`Display`].
+///
+/// # Examples
+///
+/// ```
+/// use cot::html::Html;
+/// use cot::i18n::I18n;
+/// use cot::t;
+///
+/// async fn inbox(i18n: I18n) -> Html {
+///     let count = 3;
+///     Html::new(format!(
+///         "<h1>{}</h1><p>{}</p>",
+///         t!(i18n, "inbox-title"),
+///         t!(i18n, "unread", name = "Alice", count = count),
+///     ))
+/// }
+/// ```
+#[macro_export]
+macro_rules! t {
+    ($i18n:expr, $key:expr $(,)?) => {
+        $i18n.translate($key, &[])
+    };
+    ($i18n:expr, $key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $i18n.translate(
+            $key,
+            &[$((
+                ::core::stringify!($name),
+                &$value as &dyn ::core::fmt::Display,
+            )),+],
+        )
+    };
+}
+
+/// Middleware that resolves the locale of each request and makes the
+/// translations available through the [`I18n`] extractor.
+///
+/// See the [module documentation](self) for how the locale is resolved.
+///
+/// # Examples
+///
+/// ```
+/// use cot::i18n::{Catalog, I18nMiddleware, Translations};
+/// use cot::project::{MiddlewareContext, RootHandler, RootHandlerBuilder};
+/// use cot::Project;
+///
+/// struct MyProject;
+/// impl Project for MyProject {
+///     fn middlewares(
+///         &self,
+///         handler: RootHandlerBuilder,
+///         context: &MiddlewareContext,
+///     ) -> RootHandler {
+///         let translations = Translations::new("en")
+///             .add_catalog("en", Catalog::new().insert("hello", "Hello!"))
+///             .add_catalog("pl", Catalog::new().insert("hello", "Cześć!"));
+///
+///         handler.middleware(I18nMiddleware::new(translations)).build()
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct I18nMiddleware {
+    translations: Arc<Translations>,
+    cookie_name: String,
+}
+
+impl I18nMiddleware {
+    /// Creates a new middleware using the given translations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::i18n::{I18nMiddleware, Translations};
+    ///
+    /// let middleware = I18nMiddleware::new(Translations::new("en"));
+    /// ```
+    #[must_use]
+    pub fn new(translations: Translations) -> Self {
+        Self {
+            translations: Arc::new(translations),
+            cookie_name: DEFAULT_LOCALE_COOKIE_NAME.to_owned(),
+        }
+    }
+
+    /// Sets the name of the cookie that overrides the locale of the request.
+    /// The default is [`DEFAULT_LOCALE_COOKIE_NAME`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::i18n::{I18nMiddleware, Translations};
+    ///
+    /// let middleware = I18nMiddleware::new(Translations::new("en")).cookie_name("lang");
+    /// ```
+    #[must_use]
+    pub fn cookie_name(mut self, cookie_name: impl Into<String>) -> Self {
+        self.cookie_name = cookie_name.into();
+        self
+    }
+}
+
+impl<S> tower::Layer<S> for I18nMiddleware {
+    type Service = I18nService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        I18nService {
+            inner,
+            translations: Arc::clone(&self.translations),
+            cookie_name: self.cookie_name.clone(),
+        }
+    }
+}
+
+/// Service that resolves the locale of the requests.
+///
+/// Used by [`I18nMiddleware`].
+#[derive(Debug, Clone)]
+pub struct I18nService<S> {
+    inner: S,
+    translations: Arc<Translations>,
+    cookie_name: String,
+}
+
+impl<S> I18nService<S> {
+    fn resolve_locale(&self, request: &Request) -> String {
+        let from_cookie = cookie_value(request.headers(), &self.cookie_name)
+            .and_then(|locale| self.translations.supported_locale(locale));
+        if let Some(locale) = from_cookie {
+            return locale.to_owned();
+        }
+
+        let supported: Vec<&str> = self
+            .translations
+            .locales()
+            .iter()
+            .map(String::as_str)
+            .collect();
+        request
+            .preferred_language(&supported)
+            .unwrap_or(self.translations.default_locale())
+            .to_owned()
+    }
+}
+
+impl<S> Service<Request> for I18nService<S>
+where
+    S: Service<Request, Response = Response, Error = crate::Error> + Send,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request) -> Self::Future {
+        let locale = self.resolve_locale(&req);
+        req.extensions_mut().insert(I18n {
+            translations: Arc::clone(&self.translations),
+            locale,
+        });
+
+        Box::pin(self.inner.call(req))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{HeaderValue, header};
+    use tower::{Layer, ServiceExt};
+
+    use super::*;
+    use crate::Body;
+    use crate::test::TestRequestBuilder;
+
+    fn translations() -> Translations {
+        Translations::new("en")
+            .add_catalog(
+                "en",
+                Catalog::parse(
+                    "hello = Hello, { $name }!\n\
+                     only-english = Only in English",
+                )
+                .unwrap(),
+            )
+            .add_catalog("pl", Catalog::parse("hello = Cześć, { $name }!").unwrap())
+    }
+
+    #[test]
+    fn catalog_parse() {
+        let catalog = Catalog::parse(
+            "# comment\n\
+             \n\
+             simple = Simple message\n\
+             multiline =\n    First line\n    Second line\n\
+             spaces  =   trimmed  ",
+        )
+        .unwrap();
+
+        assert_eq!(catalog.get("simple"), Some("Simple message"));
+        assert_eq!(catalog.get("multiline"), Some("First line\nSecond line"));
+        assert_eq!(catalog.get("spaces"), Some("trimmed"));
+        assert_eq!(catalog.get("comment"), None);
+    }
+
+    #[test]
+    fn catalog_parse_errors() {
+        assert_eq!(
+            Catalog::parse("no equals sign"),
+            Err(CatalogParseError::InvalidLine { line: 1 })
+        );
+        assert_eq!(
+            Catalog::parse("  indented = value"),
+            Err(CatalogParseError::InvalidLine { line: 1 })
+        );
+        assert_eq!(
+            Catalog::parse("1key = value"),
+            Err(CatalogParseError::InvalidKey {
+                line: 1,
+                key: "1key".to_owned()
+            })
+        );
+        assert_eq!(
+            Catalog::parse("key = a\nkey = b"),
+            Err(CatalogParseError::DuplicateKey {
+                line: 2,
+                key: "key".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn translate_differs_between_locales() {
+        let translations = translations();
+
+        assert_eq!(
+            translations.translate("en", "hello", &[("name", &"Alice")]),
+            "Hello, Alice!"
+        );
+        assert_eq!(
+            translations.translate("pl", "hello", &[("name", &"Alice")]),
+            "Cześć, Alice!"
+        );
+    }
+
+    #[test]
+    fn translate_falls_back() {
+        let translations = translations();
+
+        // region falls back to the language
+        assert_eq!(
+            translations.translate("PL-pl", "hello", &[("name", &"Alice")]),
+            "Cześć, Alice!"
+        );
+        // missing message falls back to the default locale
+        assert_eq!(
+            translations.translate("pl", "only-english", &[]),
+            "Only in English"
+        );
+        // unknown locale falls back to the default locale
+        assert_eq!(
+            translations.translate("de", "hello", &[("name", &"Alice")]),
+            "Hello, Alice!"
+        );
+        // missing everywhere falls back to the key
+        assert_eq!(translations.translate("pl", "missing", &[]), "missing");
+    }
+
+    #[test]
+    fn format_message_placeholders() {
+        assert_eq!(
+            format_message("{$a} and { $b }", &[("a", &1), ("b", &"two")]),
+            "1 and two"
+        );
+        assert_eq!(format_message("{ $missing }", &[]), "{ $missing }");
+        assert_eq!(format_message("unclosed {", &[]), "unclosed {");
+        assert_eq!(format_message("no placeholders", &[]), "no placeholders");
+    }
+
+    #[test]
+    fn t_macro() {
+        let i18n = I18n {
+            translations: Arc::new(translations()),
+            locale: "pl".to_owned(),
+        };
+        let name = "Alice";
+
+        assert_eq!(t!(i18n, "hello", name = name), "Cześć, Alice!");
+        assert_eq!(t!(i18n, "only-english"), "Only in English");
+    }
+
+    #[test]
+    fn add_catalog_merges() {
+        let translations = Translations::new("en")
+            .add_catalog("en", Catalog::new().insert("a", "A").insert("b", "B"))
+            .add_catalog("EN", Catalog::new().insert("b", "B2"));
+
+        assert_eq!(translations.locales(), ["en"]);
+        assert_eq!(translations.message("en", "a"), Some("A"));
+        assert_eq!(translations.message("en", "b"), Some("B2"));
+    }
+
+    async fn resolved_locale(headers: &[(header::HeaderName, &'static str)]) -> String {
+        let middleware = I18nMiddleware::new(translations());
+        let service = middleware.layer(tower::service_fn(|request: Request| async move {
+            let locale = request.locale().unwrap().to_owned();
+            Ok::<_, crate::Error>(Response::new(Body::fixed(locale)))
+        }));
+
+        let mut request = TestRequestBuilder::get("/").build();
+        for (name, value) in headers {
+            request
+                .headers_mut()
+                .append(name, HeaderValue::from_static(value));
+        }
+
+        let response = service.oneshot(request).await.unwrap();
+        let body = response.into_body().into_bytes().await.unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[cot::test]
+    async fn middleware_resolves_locale() {
+        assert_eq!(resolved_locale(&[]).await, "en");
+        assert_eq!(
+            resolved_locale(&[(header::ACCEPT_LANGUAGE, "pl-PL, en;q=0.5")]).await,
+            "pl"
+        );
+        assert_eq!(
+            resolved_locale(&[(header::ACCEPT_LANGUAGE, "de")]).await,
+            "en"
+        );
+        assert_eq!(
+            resolved_locale(&[
+                (header::ACCEPT_LANGUAGE, "pl"),
+                (header::COOKIE, "session=abc; locale=EN")
+            ])
+            .await,
+            "en"
+        );
+        // unsupported locale in the cookie is ignored
+        assert_eq!(
+            resolved_locale(&[
+                (header::ACCEPT_LANGUAGE, "pl"),
+                (header::COOKIE, "locale=de")
+            ])
+            .await,
+            "pl"
+        );
+    }
+
+    #[test]
+    fn load_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("en.ftl"), "hello = Hello!").unwrap();
+        std::fs::write(dir.path().join("pt-BR.ftl"), "hello = Olá!").unwrap();
+        std::fs::write(dir.path().join("README.md"), "not a catalog").unwrap();
+
+        let translations = Translations::load_dir("en", dir.path()).unwrap();
+
+        assert_eq!(translations.locales(), ["en", "pt-BR"]);
+        assert_eq!(translations.message("pt-br", "hello"), Some("Olá!"));
+    }
+
+    #[test]
+    fn load_dir_parse_error() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("en.ftl"), "invalid").unwrap();
+
+        let error = Translations::load_dir("en", dir.path()).unwrap_err();
+
+        assert!(matches!(
+            error,
+            I18nError::Parse {
+                source: CatalogParseError::InvalidLine { line: 1 },
+                ..
+            }
+        ));
+    }
+}
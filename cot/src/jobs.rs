@@ -0,0 +1,717 @@
+//! A persistent job queue backed by the database.
+//!
+//! Jobs are used to defer work that doesn't have to happen while handling a
+//! request, such as sending emails. A job is a serializable struct
+//! implementing the [`Job`] trait. It is stored in the database by
+//! [`enqueue`] and executed in the background by the worker run by
+//! [`JobQueueApp`], which also registers the handlers for the job types.
+//!
+//! The jobs are claimed atomically, so the same job is never executed by two
+//! workers at the same time, even if multiple instances of the project share
+//! the database. On PostgreSQL, the jobs are claimed using
+//! `SELECT ... FOR UPDATE SKIP LOCKED`; on the other databases, an optimistic
+//! claim (an `UPDATE` conditional on the job not having been claimed in the
+//! meantime) is used instead.
+//!
+//! When a job fails, it is retried according to its
+//! [`retry_policy`](Job::retry_policy). After all the retries have failed,
+//! the job is moved to the dead-letter state, where it stays until it's
+//! requeued (see [`JobRecord::requeue`]) or deleted.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use cot::db::Database;
+//! use cot::html::Html;
+//! use cot::jobs::{Job, JobQueueApp, enqueue};
+//! use cot::project::ProjectContext;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct SendWelcomeEmail {
+//!     email: String,
+//! }
+//!
+//! impl Job for SendWelcomeEmail {
+//!     const NAME: &'static str = "send_welcome_email";
+//!
+//!     async fn run(self, context: Arc<ProjectContext>) -> cot::Result<()> {
+//!         // send the email using `context.email()`
+//!         Ok(())
+//!     }
+//! }
+//!
+//! async fn register(db: Database) -> cot::Result<Html> {
+//!     enqueue(
+//!         &db,
+//!         &SendWelcomeEmail {
+//!             email: "user@example.com".to_owned(),
+//!         },
+//!     )
+//!     .await?;
+//!
+//!     Ok(Html::new("Welcome!"))
+//! }
+//!
+//! // in `Project::register_apps`:
+//! let app = JobQueueApp::new().register::<SendWelcomeEmail>();
+//! ```
+
+pub mod migrations;
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+// Importing `Auto` from `cot` instead of `crate` so that the migration generator
+// can figure out it's an autogenerated field
+use cot::db::Auto;
+use cot_core::error::impl_into_cot_error;
+use derive_more::with_trait::Debug;
+use futures_core::future::BoxFuture;
+use futures_util::FutureExt;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+use tracing::{debug, error, warn};
+
+use crate::App;
+use crate::db::migrations::SyncDynMigration;
+use crate::db::{Database, DatabaseBackend, Model, RowsNum, model, query};
+use crate::error::UncaughtPanic;
+use crate::project::ProjectContext;
+use crate::tasks::{RetryPolicy, Schedule, Task};
+
+/// How long a claimed job is considered to be running. If the worker
+/// executing it crashes, the job is picked up again after this time.
+const JOB_LEASE: Duration = Duration::from_secs(5 * 60);
+/// How many times to try claiming a job before giving up when other workers
+/// keep claiming the same jobs (only used with the optimistic claims).
+const MAX_CLAIM_ATTEMPTS: usize = 5;
+
+/// A job that can be executed in the background.
+///
+/// The job is serialized to JSON when it is [enqueued](enqueue), so it should
+/// only contain the data needed to perform the work (such as the IDs of the
+/// database objects), rather than the objects themselves.
+///
+/// The job type has to be registered with [`JobQueueApp::register`] to be
+/// executed.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use cot::jobs::Job;
+/// use cot::project::ProjectContext;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct GenerateReport {
+///     report_id: i64,
+/// }
+///
+/// impl Job for GenerateReport {
+///     const NAME: &'static str = "generate_report";
+///
+///     async fn run(self, context: Arc<ProjectContext>) -> cot::Result<()> {
+///         // generate the report using `context.database()`
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait Job: Serialize + DeserializeOwned + Send + 'static {
+    /// The name identifying the job type in the database.
+    ///
+    /// It must be unique among the registered jobs and shouldn't change, as
+    /// the jobs already stored in the database wouldn't be matched with their
+    /// handler anymore.
+    const NAME: &'static str;
+
+    /// Returns the policy for retrying the job when it fails.
+    ///
+    /// By default, the job is retried 3 times, waiting 10 seconds before the
+    /// first retry and doubling the delay after each retry.
+    #[must_use]
+    fn retry_policy() -> RetryPolicy {
+        RetryPolicy::new(3, Duration::from_secs(10))
+    }
+
+    /// Executes the job.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the job failed; in that case, it is retried
+    /// according to [`Self::retry_policy`].
+    fn run(self, context: Arc<ProjectContext>) -> impl Future<Output = crate::Result<()>> + Send;
+}
+
+/// An error that occurs when enqueueing or executing a job.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum JobError {
+    /// The job could not be serialized.
+    #[error("failed to serialize job `{name}`")]
+    Serialize {
+        /// The name of the job.
+        name: &'static str,
+        /// The serialization error.
+        #[source]
+        source: serde_json::Error,
+    },
+    /// The job could not be deserialized.
+    #[error("failed to deserialize job `{name}`")]
+    Deserialize {
+        /// The name of the job.
+        name: &'static str,
+        /// The deserialization error.
+        #[source]
+        source: serde_json::Error,
+    },
+    /// There is no handler registered for the job.
+    #[error("no handler registered for job `{0}`")]
+    UnknownJob(String),
+}
+impl_into_cot_error!(JobError);
+
+/// The state of a job stored in the database.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum JobStatus {
+    /// The job is waiting to be executed.
+    Pending,
+    /// The job is being executed by a worker.
+    Running,
+    /// All the attempts to execute the job have failed.
+    Dead,
+}
+
+impl JobStatus {
+    const PENDING: &'static str = "pending";
+    const RUNNING: &'static str = "running";
+    const DEAD: &'static str = "dead";
+}
+
+/// A job stored in the database.
+#[derive(Debug, Clone)]
+#[model]
+pub struct JobRecord {
+    #[model(primary_key)]
+    id: Auto<i64>,
+    name: String,
+    payload: String,
+    status: String,
+    attempts: i32,
+    run_at: DateTime<Utc>,
+    last_error: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl JobRecord {
+    /// Returns the ID of the job.
+    #[must_use]
+    pub fn id(&self) -> i64 {
+        match self.id {
+            Auto::Fixed(id) => id,
+            Auto::Auto => unreachable!("JobRecord constructed with an unknown ID"),
+        }
+    }
+
+    /// Returns the name of the job type (see [`Job::NAME`]).
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the job serialized as JSON.
+    #[must_use]
+    pub fn payload(&self) -> &str {
+        &self.payload
+    }
+
+    /// Returns the state of the job.
+    #[must_use]
+    pub fn status(&self) -> JobStatus {
+        match self.status.as_str() {
+            JobStatus::RUNNING => JobStatus::Running,
+            JobStatus::DEAD => JobStatus::Dead,
+            _ => JobStatus::Pending,
+        }
+    }
+
+    /// Returns how many times the job has been started.
+    #[must_use]
+    pub fn attempts(&self) -> u32 {
+        u32::try_from(self.attempts).unwrap_or_default()
+    }
+
+    /// Returns the time the job is scheduled to run at.
+    #[must_use]
+    pub fn run_at(&self) -> DateTime<Utc> {
+        self.run_at
+    }
+
+    /// Returns the error of the last failed attempt, if any.
+    #[must_use]
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+
+    /// Returns the time the job was enqueued at.
+    #[must_use]
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// Returns the jobs in the dead-letter state, i.e., the ones that have
+    /// failed all their attempts.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there was an error querying the database.
+    pub async fn dead_letters<DB: DatabaseBackend>(db: &DB) -> crate::db::Result<Vec<Self>> {
+        let dead = JobStatus::DEAD;
+        query!(JobRecord, $status == dead).all(db).await
+    }
+
+    /// Moves a dead job back to the queue, resetting its attempt counter, so
+    /// that it's executed again as soon as possible.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the job could not be saved.
+    pub async fn requeue<DB: DatabaseBackend>(&mut self, db: &DB) -> crate::db::Result<()> {
+        self.status = JobStatus::PENDING.to_owned();
+        self.attempts = 0;
+        self.run_at = Utc::now();
+        self.save(db).await
+    }
+}
+
+/// Adds a job to the queue, to be executed as soon as possible.
+///
+/// # Errors
+///
+/// Returns an error if the job could not be serialized or saved.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use cot::db::Database;
+/// use cot::jobs::{Job, enqueue};
+/// use cot::project::ProjectContext;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Cleanup;
+///
+/// impl Job for Cleanup {
+///     const NAME: &'static str = "cleanup";
+///
+///     async fn run(self, context: Arc<ProjectContext>) -> cot::Result<()> {
+///         Ok(())
+///     }
+/// }
+///
+/// async fn view(db: Database) -> cot::Result<()> {
+///     enqueue(&db, &Cleanup).await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn enqueue<J: Job, DB: DatabaseBackend>(db: &DB, job: &J) -> crate::Result<JobRecord> {
+    enqueue_at(db, job, Utc::now()).await
+}
+
+/// Adds a job to the queue, to be executed at the given time (or as soon as
+/// possible after it).
+///
+/// # Errors
+///
+/// Returns an error if the job could not be serialized or saved.
+pub async fn enqueue_at<J: Job, DB: DatabaseBackend>(
+    db: &DB,
+    job: &J,
+    run_at: DateTime<Utc>,
+) -> crate::Result<JobRecord> {
+    let payload = serde_json::to_string(job).map_err(|source| JobError::Serialize {
+        name: J::NAME,
+        source,
+    })?;
+
+    let mut record = JobRecord {
+        id: Auto::auto(),
+        name: J::NAME.to_owned(),
+        payload,
+        status: JobStatus::PENDING.to_owned(),
+        attempts: 0,
+        run_at,
+        last_error: None,
+        created_at: Utc::now(),
+    };
+    record.insert(db).await?;
+
+    Ok(record)
+}
+
+/// An app that stores the job queue in the database and runs the worker
+/// executing the jobs.
+///
+/// The worker is run as a background [`Task`], polling the database for the
+/// jobs to execute.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use cot::config::{DatabaseConfig, ProjectConfig};
+/// use cot::jobs::{Job, JobQueueApp};
+/// use cot::project::{ProjectContext, RegisterAppsContext};
+/// use cot::{AppBuilder, Project};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Cleanup;
+///
+/// impl Job for Cleanup {
+///     const NAME: &'static str = "cleanup";
+///
+///     async fn run(self, context: Arc<ProjectContext>) -> cot::Result<()> {
+///         Ok(())
+///     }
+/// }
+///
+/// struct MyProject;
+/// impl Project for MyProject {
+///     fn config(&self, config_name: &str) -> cot::Result<ProjectConfig> {
+///         Ok(ProjectConfig::builder()
+///             .database(DatabaseConfig::builder().url("sqlite::memory:").build())
+///             .build())
+///     }
+///
+///     fn register_apps(&self, apps: &mut AppBuilder, _context: &RegisterAppsContext) {
+///         apps.register(JobQueueApp::new().register::<Cleanup>());
+///     }
+/// }
+///
+/// #[cot::main]
+/// fn main() -> impl Project {
+///     MyProject
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct JobQueueApp {
+    handlers: HashMap<&'static str, JobHandler>,
+    poll_interval: Duration,
+}
+
+impl Default for JobQueueApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JobQueueApp {
+    const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+    /// Creates a new job queue app with no jobs registered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::jobs::JobQueueApp;
+    ///
+    /// let app = JobQueueApp::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            handlers: HashMap::new(),
+            poll_interval: Self::DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Registers the handler for the given job type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a different job type with the same [`Job::NAME`] has already
+    /// been registered.
+    #[must_use]
+    pub fn register<J: Job>(mut self) -> Self {
+        let previous = self.handlers.insert(J::NAME, JobHandler::new::<J>());
+        assert!(
+            previous.is_none_or(|previous| previous.type_name == std::any::type_name::<J>()),
+            "job `{}` registered twice",
+            J::NAME
+        );
+        self
+    }
+
+    /// Sets how often the worker checks the database for new jobs when the
+    /// queue is empty. The default is 1 second.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::jobs::JobQueueApp;
+    ///
+    /// let app = JobQueueApp::new().poll_interval(Duration::from_millis(100));
+    /// ```
+    #[must_use]
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Returns a worker executing the jobs registered in this app.
+    ///
+    /// Normally, the worker is run automatically as a background task, so
+    /// this is only useful for running the jobs manually (e.g., in tests).
+    #[must_use]
+    pub fn worker(&self) -> JobWorker {
+        JobWorker {
+            handlers: Arc::new(self.handlers.clone()),
+        }
+    }
+}
+
+impl App for JobQueueApp {
+    fn name(&self) -> &'static str {
+        "cot_jobs"
+    }
+
+    fn migrations(&self) -> Vec<Box<SyncDynMigration>> {
+        cot::db::migrations::wrap_migrations(migrations::MIGRATIONS)
+    }
+
+    fn tasks(&self) -> Vec<Task> {
+        let worker = self.worker();
+        vec![Task::new(
+            "cot_job_worker",
+            Schedule::interval(self.poll_interval),
+            move |context| {
+                let worker = worker.clone();
+                async move {
+                    worker.run_pending(&context).await?;
+                    Ok(())
+                }
+            },
+        )]
+    }
+}
+
+type RunJobFn =
+    fn(Arc<ProjectContext>, &str) -> Result<BoxFuture<'static, crate::Result<()>>, JobError>;
+
+#[derive(Debug, Copy, Clone)]
+struct JobHandler {
+    type_name: &'static str,
+    #[debug("..")]
+    run: RunJobFn,
+    retry_policy: RetryPolicy,
+}
+
+impl JobHandler {
+    fn new<J: Job>() -> Self {
+        Self {
+            type_name: std::any::type_name::<J>(),
+            run: run_job::<J>,
+            retry_policy: J::retry_policy(),
+        }
+    }
+}
+
+fn run_job<J: Job>(
+    context: Arc<ProjectContext>,
+    payload: &str,
+) -> Result<BoxFuture<'static, crate::Result<()>>, JobError> {
+    let job: J = serde_json::from_str(payload).map_err(|source| JobError::Deserialize {
+        name: J::NAME,
+        source,
+    })?;
+
+    Ok(Box::pin(job.run(context)))
+}
+
+/// Executes the jobs stored in the database.
+///
+/// Created by [`JobQueueApp::worker`].
+#[derive(Debug, Clone)]
+pub struct JobWorker {
+    handlers: Arc<HashMap<&'static str, JobHandler>>,
+}
+
+impl JobWorker {
+    /// Executes the jobs that are due, one by one, until there are no more
+    /// jobs to execute. Returns the number of executed jobs (including the
+    /// failed ones).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there was an error accessing the database. The
+    /// errors returned by the jobs themselves are recorded in the database
+    /// instead.
+    pub async fn run_pending(&self, context: &Arc<ProjectContext>) -> crate::Result<usize> {
+        let mut executed = 0;
+        while self.run_next(context).await? {
+            executed += 1;
+        }
+
+        Ok(executed)
+    }
+
+    /// Claims and executes the next job that is due. Returns `false` if there
+    /// were no jobs to execute.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there was an error accessing the database.
+    pub async fn run_next(&self, context: &Arc<ProjectContext>) -> crate::Result<bool> {
+        let database = context.database();
+        let Some(mut job) = claim_next(database).await? else {
+            return Ok(false);
+        };
+        debug!(
+            job = job.name,
+            id = job.id(),
+            attempt = job.attempts,
+            "Running job"
+        );
+
+        let handler = self.handlers.get(job.name.as_str()).copied();
+        let result = match handler {
+            Some(handler) => {
+                let context = Arc::clone(context);
+                let payload = job.payload.clone();
+                // the async block makes sure that the panics in the function
+                // creating the future are caught as well
+                AssertUnwindSafe(async move { (handler.run)(context, &payload)?.await })
+                    .catch_unwind()
+                    .await
+                    .unwrap_or_else(|payload| Err(UncaughtPanic::new(payload).into()))
+            }
+            None => Err(JobError::UnknownJob(job.name.clone()).into()),
+        };
+
+        match result {
+            Ok(()) => {
+                let id = job.id();
+                query!(JobRecord, $id == id).delete(database).await?;
+            }
+            Err(error) => {
+                let retry_delay = handler.and_then(|handler| {
+                    handler.retry_policy.delay(job.attempts().saturating_sub(1))
+                });
+                job.last_error = Some(error.to_string());
+                if let Some(retry_delay) = retry_delay {
+                    warn!(
+                        job = job.name,
+                        id = job.id(),
+                        %error,
+                        ?retry_delay,
+                        "Job failed; retrying"
+                    );
+                    job.status = JobStatus::PENDING.to_owned();
+                    job.run_at =
+                        Utc::now() + chrono::Duration::from_std(retry_delay).unwrap_or_default();
+                } else {
+                    error!(
+                        job = job.name,
+                        id = job.id(),
+                        %error,
+                        "Job failed; moving it to the dead-letter state"
+                    );
+                    job.status = JobStatus::DEAD.to_owned();
+                }
+                job.save(database).await?;
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+/// Atomically marks the next due job as running and returns it.
+async fn claim_next(database: &Database) -> crate::db::Result<Option<JobRecord>> {
+    let now = Utc::now();
+    let lease_until =
+        now + chrono::Duration::from_std(JOB_LEASE).expect("job lease should fit in TimeDelta");
+
+    // Only PostgreSQL supports both `FOR UPDATE SKIP LOCKED` and `RETURNING`,
+    // which allows claiming a job in a single statement
+    if database.supports_row_locking() && database.supports_returning() {
+        let query = format!(
+            r#"UPDATE "{table}" SET "status" = $1, "run_at" = $2, "attempts" = "attempts" + 1
+WHERE "id" = (
+    SELECT "id" FROM "{table}"
+    WHERE "status" IN ($3, $4) AND "run_at" <= $5
+    ORDER BY "run_at", "id"
+    LIMIT 1
+    FOR UPDATE SKIP LOCKED
+)
+RETURNING *"#,
+            table = JobRecord::TABLE_NAME,
+        );
+        let jobs: Vec<JobRecord> = database
+            .raw_query(
+                &query,
+                &[
+                    &JobStatus::RUNNING,
+                    &lease_until,
+                    &JobStatus::PENDING,
+                    &JobStatus::RUNNING,
+                    &now,
+                ],
+            )
+            .await?;
+        return Ok(jobs.into_iter().next());
+    }
+
+    let pending = JobStatus::PENDING;
+    let running = JobStatus::RUNNING;
+    for _ in 0..MAX_CLAIM_ATTEMPTS {
+        let Some(mut job) =
+            query!(JobRecord, ($status == pending || $status == running) && $run_at <= now)
+                .order_by(<JobRecord as Model>::Fields::run_at.asc())
+                .order_by(<JobRecord as Model>::Fields::id.asc())
+                .first(database)
+                .await?
+        else {
+            return Ok(None);
+        };
+
+        // the attempt counter is increased with every claim, so it can be used
+        // to detect whether another worker has claimed the job in the meantime
+        let id = job.id();
+        let attempts = job.attempts;
+        let result = query!(JobRecord, $id == id && $attempts == attempts)
+            .update(
+                database,
+                &[
+                    <JobRecord as Model>::Fields::status.set(running),
+                    <JobRecord as Model>::Fields::run_at.set(lease_until),
+                    <JobRecord as Model>::Fields::attempts.set(attempts + 1),
+                ],
+            )
+            .await?;
+        if result.rows_affected() == RowsNum(1) {
+            job.status = running.to_owned();
+            job.run_at = lease_until;
+            job.attempts = attempts + 1;
+            return Ok(Some(job));
+        }
+    }
+
+    Ok(None)
+}
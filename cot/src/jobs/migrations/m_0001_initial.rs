@@ -0,0 +1,71 @@
+//! Generated by cot CLI 0.3.1 on 2026-10-16 12:04:31+00:00
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Migration;
+impl ::cot::db::migrations::Migration for Migration {
+    const APP_NAME: &'static str = "cot_jobs";
+    const MIGRATION_NAME: &'static str = "m_0001_initial";
+    const DEPENDENCIES: &'static [::cot::db::migrations::MigrationDependency] = &[];
+    const OPERATIONS: &'static [::cot::db::migrations::Operation] =
+        &[::cot::db::migrations::Operation::create_model()
+            .table_name(::cot::db::Identifier::new("cot__job_record"))
+            .fields(&[
+                ::cot::db::migrations::Field::new(
+                    ::cot::db::Identifier::new("id"),
+                    <cot::db::Auto<i64> as ::cot::db::DatabaseField>::TYPE,
+                )
+                .auto()
+                .primary_key()
+                .set_null(<cot::db::Auto<i64> as ::cot::db::DatabaseField>::NULLABLE),
+                ::cot::db::migrations::Field::new(
+                    ::cot::db::Identifier::new("name"),
+                    <String as ::cot::db::DatabaseField>::TYPE,
+                )
+                .set_null(<String as ::cot::db::DatabaseField>::NULLABLE),
+                ::cot::db::migrations::Field::new(
+                    ::cot::db::Identifier::new("payload"),
+                    <String as ::cot::db::DatabaseField>::TYPE,
+                )
+                .set_null(<String as ::cot::db::DatabaseField>::NULLABLE),
+                ::cot::db::migrations::Field::new(
+                    ::cot::db::Identifier::new("status"),
+                    <String as ::cot::db::DatabaseField>::TYPE,
+                )
+                .set_null(<String as ::cot::db::DatabaseField>::NULLABLE),
+                ::cot::db::migrations::Field::new(
+                    ::cot::db::Identifier::new("attempts"),
+                    <i32 as ::cot::db::DatabaseField>::TYPE,
+                )
+                .set_null(<i32 as ::cot::db::DatabaseField>::NULLABLE),
+                ::cot::db::migrations::Field::new(
+                    ::cot::db::Identifier::new("run_at"),
+                    <chrono::DateTime<chrono::Utc> as ::cot::db::DatabaseField>::TYPE,
+                )
+                .set_null(<chrono::DateTime<chrono::Utc> as ::cot::db::DatabaseField>::NULLABLE),
+                ::cot::db::migrations::Field::new(
+                    ::cot::db::Identifier::new("last_error"),
+                    <Option<String> as ::cot::db::DatabaseField>::TYPE,
+                )
+                .set_null(<Option<String> as ::cot::db::DatabaseField>::NULLABLE),
+                ::cot::db::migrations::Field::new(
+                    ::cot::db::Identifier::new("created_at"),
+                    <chrono::DateTime<chrono::Utc> as ::cot::db::DatabaseField>::TYPE,
+                )
+                .set_null(<chrono::DateTime<chrono::Utc> as ::cot::db::DatabaseField>::NULLABLE),
+            ])
+            .build()];
+}
+
+#[derive(::core::fmt::Debug)]
+#[::cot::db::model(model_type = "migration")]
+struct _JobRecord {
+    #[model(primary_key)]
+    id: cot::db::Auto<i64>,
+    name: String,
+    payload: String,
+    status: String,
+    attempts: i32,
+    run_at: chrono::DateTime<chrono::Utc>,
+    last_error: Option<String>,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
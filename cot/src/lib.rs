@@ -64,23 +64,38 @@ pub mod __private;
 pub mod admin;
 pub mod auth;
 pub mod cli;
+#[cfg(feature = "client")]
+pub mod client;
 pub mod common_types;
 pub mod config;
+pub mod csv;
 #[cfg(feature = "email")]
 pub mod email;
 mod error_page;
+pub mod i18n;
+#[cfg(all(feature = "db", feature = "json"))]
+pub mod jobs;
+mod logging;
+#[cfg(feature = "markdown")]
+pub mod markdown;
 pub mod middleware;
 #[cfg(feature = "openapi")]
 pub mod openapi;
+#[cfg(feature = "opentelemetry")]
+pub mod opentelemetry;
 pub mod project;
 pub mod request;
 pub mod router;
 mod serializers;
 pub mod session;
 pub mod static_files;
+pub mod tasks;
 #[cfg(feature = "test")]
 pub mod test;
+pub mod timezone;
 pub(crate) mod utils;
+#[cfg(feature = "xml")]
+pub mod xml;
 
 #[cfg(feature = "openapi")]
 pub use aide;
@@ -181,8 +196,9 @@ pub use cot_macros::e2e_test;
 /// }
 /// ```
 pub use cot_macros::main;
-pub use cot_macros::test;
+pub use cot_macros::{html, test};
 pub use http;
+pub use ipnet;
 #[cfg(feature = "openapi")]
 pub use schemars;
 pub use toml;
@@ -0,0 +1,229 @@
+//! Installing the global `tracing` subscriber based on the project config.
+
+use tracing::Subscriber;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{Layer, Registry};
+
+use crate::config::{LogFormat, LoggingConfig, ProjectConfig};
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Installs the global `tracing` subscriber configured according to the
+/// [`LoggingConfig`] of the project, unless logging has been disabled or a
+/// global subscriber has already been set.
+///
+/// With the `opentelemetry` feature enabled, the subscriber also exports the
+/// spans to the collector configured in the project config, if any.
+pub(crate) fn init(config: &ProjectConfig) -> crate::Result<()> {
+    if !config.logging.enabled {
+        return Ok(());
+    }
+
+    install(config)
+}
+
+/// Installs the global `tracing` subscriber configured according to the
+/// project config, even if logging has been disabled in it. Does nothing if a
+/// global subscriber has already been set.
+pub(crate) fn install(config: &ProjectConfig) -> crate::Result<()> {
+    if tracing::dispatcher::has_been_set() {
+        tracing::debug!("a global tracing subscriber is already set; ignoring the logging config");
+        return Ok(());
+    }
+
+    #[cfg_attr(not(feature = "opentelemetry"), expect(unused_mut))]
+    let mut layers: Vec<BoxedLayer> = Vec::new();
+    #[cfg(feature = "opentelemetry")]
+    layers.extend(crate::opentelemetry::layer(&config.opentelemetry)?);
+
+    let subscriber = subscriber(&config.logging, config.debug, std::io::stderr, layers);
+    if subscriber.try_init().is_err() {
+        tracing::debug!("a global tracing subscriber is already set; ignoring the logging config");
+    }
+
+    Ok(())
+}
+
+/// Flushes the data that hasn't been exported yet before the project exits.
+pub(crate) fn shutdown() {
+    #[cfg(feature = "opentelemetry")]
+    crate::opentelemetry::shutdown();
+}
+
+/// Creates the root span each request is handled in.
+///
+/// If the request has an `X-Request-Id` header, its value is stored in the
+/// `request_id` field, so that the span can be correlated with the logs of
+/// the other services handling the same request.
+///
+/// The `http.route` field is filled in by the router with the path pattern of
+/// the matched route (such as `/users/{id}`), and the `status` field with the
+/// status code of the response.
+///
+/// With the `opentelemetry` feature enabled, the span continues the trace
+/// referred to by the `traceparent` header of the request, if there is one.
+pub(crate) fn request_span<B>(request: &http::Request<B>) -> tracing::Span {
+    let span = tracing::info_span!(
+        "request",
+        method = %request.method(),
+        path = request.uri().path(),
+        request_id = request
+            .headers()
+            .get("x-request-id")
+            .and_then(|value| value.to_str().ok()),
+        otel.kind = "server",
+        http.route = tracing::field::Empty,
+        status = tracing::field::Empty,
+    );
+    #[cfg(feature = "opentelemetry")]
+    crate::opentelemetry::set_parent_from_headers(&span, request.headers());
+
+    span
+}
+
+fn subscriber<W>(
+    config: &LoggingConfig,
+    debug: bool,
+    make_writer: W,
+    mut layers: Vec<BoxedLayer>,
+) -> Box<dyn Subscriber + Send + Sync>
+where
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+{
+    let filter = Targets::new().with_default(config.level).with_targets(
+        config
+            .targets
+            .iter()
+            .map(|(target, level)| (target.clone(), *level)),
+    );
+    let fmt_layer = tracing_subscriber::fmt::layer().with_writer(make_writer);
+
+    let format = config.format.unwrap_or(if debug {
+        LogFormat::Text
+    } else {
+        LogFormat::Json
+    });
+    match format {
+        LogFormat::Text => layers.push(Box::new(fmt_layer)),
+        LogFormat::Json => layers.push(Box::new(fmt_layer.json())),
+    }
+
+    Box::new(tracing_subscriber::registry().with(layers).with(filter))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::config::LogLevel;
+
+    #[derive(Debug, Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Buffer {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn log_with(config: &LoggingConfig, debug: bool) -> String {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = subscriber(config, debug, move || writer.clone(), Vec::new());
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(target: "myapp", user_id = 42, "user logged in");
+            tracing::debug!(target: "myapp", "debug message");
+            tracing::debug!(target: "myapp::db", "query executed");
+            tracing::info!(target: "noisy", "noisy message");
+        });
+
+        buffer.contents()
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_format_produces_json_lines() {
+        let config = LoggingConfig::builder().format(LogFormat::Json).build();
+
+        let output = log_with(&config, true);
+
+        let lines: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["level"], "INFO");
+        assert_eq!(lines[0]["target"], "myapp");
+        assert_eq!(lines[0]["fields"]["message"], "user logged in");
+        assert_eq!(lines[0]["fields"]["user_id"], 42);
+        assert_eq!(lines[1]["fields"]["message"], "noisy message");
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn default_format_depends_on_debug() {
+        let config = LoggingConfig::default();
+
+        let release_output = log_with(&config, false);
+        for line in release_output.lines() {
+            serde_json::from_str::<serde_json::Value>(line).unwrap();
+        }
+
+        let debug_output = log_with(&config, true);
+        assert!(debug_output.contains("user logged in"));
+        assert!(
+            serde_json::from_str::<serde_json::Value>(debug_output.lines().next().unwrap())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn text_format() {
+        let config = LoggingConfig::builder().format(LogFormat::Text).build();
+
+        let output = log_with(&config, false);
+
+        assert_eq!(output.lines().count(), 2);
+        assert!(output.contains("INFO"));
+        assert!(output.contains("user logged in"));
+        assert!(output.contains("user_id"));
+    }
+
+    #[test]
+    fn level_and_target_filtering() {
+        let config = LoggingConfig::builder()
+            .format(LogFormat::Text)
+            .level(LogLevel::Info)
+            .targets(
+                [
+                    ("myapp::db".to_owned(), LogLevel::Debug),
+                    ("noisy".to_owned(), LogLevel::Off),
+                ]
+                .into(),
+            )
+            .build();
+
+        let output = log_with(&config, true);
+
+        assert!(output.contains("user logged in"));
+        assert!(!output.contains("debug message"));
+        assert!(output.contains("query executed"));
+        assert!(!output.contains("noisy message"));
+    }
+}
@@ -0,0 +1,134 @@
+//! Markdown rendering.
+//!
+//! This module converts Markdown into [`Html`], which can then be returned from
+//! a request handler or embedded in a template. The input is parsed according
+//! to the [CommonMark](https://commonmark.org/) specification, with the GitHub
+//! Flavored Markdown extensions for tables, strikethrough and autolinks
+//! enabled.
+//!
+//! [`markdown_to_html`] is safe to use with user-provided input: raw HTML in
+//! the source is omitted and links with dangerous schemes (such as
+//! `javascript:`) are dropped. If the input is trusted and raw HTML should be
+//! passed through as-is, use [`markdown_to_html_unsanitized`] instead.
+//!
+//! # Examples
+//!
+//! ```
+//! use cot::html::Html;
+//! use cot::markdown::markdown_to_html;
+//!
+//! async fn post() -> Html {
+//!     markdown_to_html("# Hello\n\nThis is *Cot*.")
+//! }
+//! ```
+
+use cot_core::html::Html;
+
+/// Renders Markdown into sanitized HTML.
+///
+/// Any raw HTML contained in the source (including `<script>` tags and inline
+/// event handlers) is omitted from the output, and links or images using
+/// dangerous URL schemes such as `javascript:` are rendered with an empty
+/// target. This makes the function suitable for rendering untrusted input,
+/// such as blog comments.
+///
+/// # Examples
+///
+/// ```
+/// use cot::markdown::markdown_to_html;
+///
+/// let html = markdown_to_html("Hello, **world**!<script>alert(1)</script>");
+/// assert_eq!(
+///     html.as_str(),
+///     "<p>Hello, <strong>world</strong>!<!-- raw HTML omitted -->alert(1)<!-- raw HTML omitted --></p>\n"
+/// );
+/// ```
+#[must_use]
+pub fn markdown_to_html(src: &str) -> Html {
+    render(src, false)
+}
+
+/// Renders Markdown into HTML without sanitizing it.
+///
+/// Raw HTML contained in the source is passed through to the output unchanged
+/// and URLs are not checked. **Never** use this function with input that
+/// comes from untrusted sources, as it makes the page vulnerable to
+/// cross-site scripting (XSS) attacks. Use [`markdown_to_html`] instead.
+///
+/// # Examples
+///
+/// ```
+/// use cot::markdown::markdown_to_html_unsanitized;
+///
+/// let html = markdown_to_html_unsanitized("Hello, <abbr>HTML</abbr>!");
+/// assert_eq!(html.as_str(), "<p>Hello, <abbr>HTML</abbr>!</p>\n");
+/// ```
+#[must_use]
+pub fn markdown_to_html_unsanitized(src: &str) -> Html {
+    render(src, true)
+}
+
+fn render(src: &str, allow_unsafe: bool) -> Html {
+    let mut options = comrak::Options::default();
+    options.extension.strikethrough = true;
+    options.extension.table = true;
+    options.extension.autolink = true;
+    options.extension.tagfilter = !allow_unsafe;
+    options.render.r#unsafe = allow_unsafe;
+
+    Html::new(comrak::markdown_to_html(src, &options))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_markdown() {
+        let html = markdown_to_html("# Title\n\n~~old~~ *new*\n\n| a |\n|---|\n| b |");
+
+        assert_eq!(
+            html.as_str(),
+            "<h1>Title</h1>\n\
+             <p><del>old</del> <em>new</em></p>\n\
+             <table>\n<thead>\n<tr>\n<th>a</th>\n</tr>\n</thead>\n\
+             <tbody>\n<tr>\n<td>b</td>\n</tr>\n</tbody>\n</table>\n"
+        );
+    }
+
+    #[test]
+    fn strips_raw_html() {
+        let html = markdown_to_html("<script>alert(1)</script>\n\n<img src=x onerror=alert(1)>");
+
+        assert!(!html.as_str().contains("<script"));
+        assert!(!html.as_str().contains("onerror"));
+    }
+
+    #[test]
+    fn strips_dangerous_links() {
+        let html = markdown_to_html("[click](javascript:alert(1)) ![img](vbscript:x)");
+
+        assert!(!html.as_str().contains("javascript:"));
+        assert!(!html.as_str().contains("vbscript:"));
+    }
+
+    #[test]
+    fn escapes_text() {
+        let html = markdown_to_html("`<b>` & 1 < 2");
+
+        assert_eq!(
+            html.as_str(),
+            "<p><code>&lt;b&gt;</code> &amp; 1 &lt; 2</p>\n"
+        );
+    }
+
+    #[test]
+    fn unsanitized_keeps_raw_html() {
+        let html = markdown_to_html_unsanitized("<div class=\"note\">\n\n*hi*\n\n</div>");
+
+        assert_eq!(
+            html.as_str(),
+            "<div class=\"note\">\n<p><em>hi</em></p>\n</div>\n"
+        );
+    }
+}
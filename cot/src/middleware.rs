@@ -19,9 +19,11 @@ use crate::Error;
 use crate::config::CacheType;
 use crate::config::{Expiry, SameSite, SessionStoreTypeConfig};
 use crate::project::MiddlewareContext;
-use crate::request::Request;
+use crate::request::{Request, RequestBodyExt};
 use crate::response::Response;
 use crate::session::store::SessionStoreWrapper;
+#[cfg(feature = "json")]
+use crate::session::store::cookie::{CookieSession, CookieStore};
 #[cfg(all(feature = "db", feature = "json"))]
 use crate::session::store::db::DbStore;
 #[cfg(feature = "json")]
@@ -30,9 +32,20 @@ use crate::session::store::memory::MemoryStore;
 #[cfg(feature = "redis")]
 use crate::session::store::redis::RedisStore;
 
+mod body_limit;
+mod catch_panic;
+mod concurrency_limit;
+mod etag;
 #[cfg(feature = "live-reload")]
 mod live_reload;
-
+mod maintenance_mode;
+mod method_override;
+mod security_headers;
+mod timeout;
+
+pub use body_limit::{BodyLimitMiddleware, BodyLimitService};
+pub use catch_panic::{CatchPanicMiddleware, CatchPanicService};
+pub use concurrency_limit::{ConcurrencyLimitMiddleware, ConcurrencyLimitService};
 /// Middleware that converts any error type to [`Error`].
 ///
 /// This is useful for converting a response from a middleware that is
@@ -96,8 +109,17 @@ pub use cot_core::middleware::IntoCotErrorLayer;
 pub use cot_core::middleware::IntoCotResponseLayer;
 #[doc(inline)]
 pub use cot_core::middleware::{IntoCotError, IntoCotResponse};
+pub use etag::{ETagMiddleware, ETagService};
 #[cfg(feature = "live-reload")]
 pub use live_reload::LiveReloadMiddleware;
+pub use maintenance_mode::{MaintenanceModeMiddleware, MaintenanceModeService};
+pub use method_override::{
+    METHOD_OVERRIDE_FIELD, METHOD_OVERRIDE_HEADER, MethodOverrideMiddleware, MethodOverrideService,
+};
+pub use security_headers::{SecurityHeadersMiddleware, SecurityHeadersService};
+pub use timeout::{TimeoutMiddleware, TimeoutService};
+
+const DEFAULT_SESSION_COOKIE_NAME: &str = "id";
 
 type DynamicSessionStore = SessionManagerLayer<SessionStoreWrapper, PlaintextCookie>;
 
@@ -107,6 +129,8 @@ type DynamicSessionStore = SessionManagerLayer<SessionStoreWrapper, PlaintextCoo
 #[derive(Debug, Clone)]
 pub struct SessionMiddleware {
     inner: DynamicSessionStore,
+    cookie_name: Cow<'static, str>,
+    timeouts: SessionTimeouts,
 }
 
 impl SessionMiddleware {
@@ -114,7 +138,11 @@ impl SessionMiddleware {
     #[must_use]
     pub fn new<S: SessionStore + Send + Sync + 'static>(store: S) -> Self {
         let layer = SessionManagerLayer::new(SessionStoreWrapper::new(Arc::new(store)));
-        SessionMiddleware { inner: layer }
+        SessionMiddleware {
+            inner: layer,
+            cookie_name: Cow::Borrowed(DEFAULT_SESSION_COOKIE_NAME),
+            timeouts: SessionTimeouts::default(),
+        }
     }
 
     /// Creates a new instance of [`SessionMiddleware`] from the application
@@ -151,14 +179,18 @@ impl SessionMiddleware {
         let boxed_store = Self::config_to_session_store(store_type, context);
         let arc_store = Arc::from(boxed_store);
         let layer = SessionManagerLayer::new(SessionStoreWrapper::new(arc_store));
-        let mut middleware = SessionMiddleware { inner: layer }
-            .secure(session_cfg.secure)
-            .path(session_cfg.path.clone())
-            .name(session_cfg.name.clone())
-            .http_only(session_cfg.http_only)
-            .always_save(session_cfg.always_save)
-            .same_site(session_cfg.same_site)
-            .expiry(session_cfg.expiry);
+        let mut middleware = SessionMiddleware {
+            inner: layer,
+            cookie_name: Cow::Borrowed(DEFAULT_SESSION_COOKIE_NAME),
+            timeouts: SessionTimeouts::default(),
+        }
+        .secure(session_cfg.secure)
+        .path(session_cfg.path.clone())
+        .name(session_cfg.name.clone())
+        .http_only(session_cfg.http_only)
+        .always_save(session_cfg.always_save)
+        .same_site(session_cfg.same_site)
+        .expiry(session_cfg.expiry);
 
         if let Some(domain) = session_cfg.domain.as_ref() {
             middleware = middleware.domain(domain.clone());
@@ -179,8 +211,10 @@ impl SessionMiddleware {
     /// ```
     #[must_use]
     pub fn secure(self, secure: bool) -> Self {
-        let layer = self.inner.with_secure(secure);
-        SessionMiddleware { inner: layer }
+        Self {
+            inner: self.inner.with_secure(secure),
+            ..self
+        }
     }
 
     /// Enables or disables the `HttpOnly` flag on the session cookie.
@@ -200,6 +234,7 @@ impl SessionMiddleware {
     pub fn http_only(self, http_only: bool) -> Self {
         Self {
             inner: self.inner.with_http_only(http_only),
+            ..self
         }
     }
 
@@ -218,6 +253,7 @@ impl SessionMiddleware {
     pub fn domain<D: Into<Cow<'static, str>>>(self, domain: D) -> Self {
         Self {
             inner: self.inner.with_domain(domain),
+            ..self
         }
     }
 
@@ -237,6 +273,7 @@ impl SessionMiddleware {
     pub fn same_site(self, same_site: SameSite) -> Self {
         Self {
             inner: self.inner.with_same_site(same_site.into()),
+            ..self
         }
     }
 
@@ -253,8 +290,11 @@ impl SessionMiddleware {
     /// ```
     #[must_use]
     pub fn name<N: Into<Cow<'static, str>>>(self, name: N) -> Self {
+        let name = name.into();
         Self {
-            inner: self.inner.with_name(name.into()),
+            inner: self.inner.with_name(name.clone()),
+            cookie_name: name,
+            ..self
         }
     }
 
@@ -273,6 +313,7 @@ impl SessionMiddleware {
     pub fn path<P: Into<Cow<'static, str>>>(self, path: P) -> Self {
         Self {
             inner: self.inner.with_path(path.into()),
+            ..self
         }
     }
 
@@ -293,6 +334,7 @@ impl SessionMiddleware {
     pub fn always_save(self, always_save: bool) -> Self {
         Self {
             inner: self.inner.with_always_save(always_save),
+            ..self
         }
     }
 
@@ -315,6 +357,69 @@ impl SessionMiddleware {
     pub fn expiry(self, expiry: Expiry) -> Self {
         Self {
             inner: self.inner.with_expiry(expiry.into()),
+            ..self
+        }
+    }
+
+    /// Sets the idle timeout for sessions.
+    ///
+    /// A session that hasn't been used for longer than the given duration is
+    /// discarded: its record is deleted from the store and the request is
+    /// handled as if there was no session. The timeout is refreshed on each
+    /// request that uses a non-empty session.
+    ///
+    /// Unlike [`Expiry::OnInactivity`], which only controls the lifetime of the
+    /// cookie and the record in the store, this is enforced on the server
+    /// side, using a timestamp stored in the session data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::middleware::SessionMiddleware;
+    /// use cot::session::store::memory::MemoryStore;
+    ///
+    /// let store = MemoryStore::new();
+    /// let middleware = SessionMiddleware::new(store).idle_timeout(Duration::from_mins(30));
+    /// ```
+    #[must_use]
+    pub fn idle_timeout(self, idle_timeout: std::time::Duration) -> Self {
+        Self {
+            timeouts: SessionTimeouts {
+                idle: Some(idle_timeout),
+                ..self.timeouts
+            },
+            ..self
+        }
+    }
+
+    /// Sets the absolute timeout for sessions.
+    ///
+    /// A session older than the given duration is discarded, regardless of
+    /// activity: its record is deleted from the store and the request is
+    /// handled as if there was no session. The session age is measured from
+    /// the moment the data was first stored in it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::middleware::SessionMiddleware;
+    /// use cot::session::store::memory::MemoryStore;
+    ///
+    /// let store = MemoryStore::new();
+    /// let middleware = SessionMiddleware::new(store).absolute_timeout(Duration::from_hours(8));
+    /// ```
+    #[must_use]
+    pub fn absolute_timeout(self, absolute_timeout: std::time::Duration) -> Self {
+        Self {
+            timeouts: SessionTimeouts {
+                absolute: Some(absolute_timeout),
+                ..self.timeouts
+            },
+            ..self
         }
     }
 
@@ -327,6 +432,11 @@ impl SessionMiddleware {
         match config {
             SessionStoreTypeConfig::Memory => Box::new(MemoryStore::new()),
             #[cfg(feature = "json")]
+            SessionStoreTypeConfig::Cookie => Box::new(
+                CookieStore::new(context.config().secret_key.clone())
+                    .fallback_secret_keys(context.config().fallback_secret_keys.clone()),
+            ),
+            #[cfg(feature = "json")]
             SessionStoreTypeConfig::File { path } => Box::new(
                 FileStore::new(path)
                     .unwrap_or_else(|err| panic!("could not create File store: {err}")),
@@ -358,15 +468,70 @@ impl Default for SessionMiddleware {
 }
 
 impl<S> tower::Layer<S> for SessionMiddleware {
-    type Service = <DynamicSessionStore as tower::Layer<
-        <SessionWrapperLayer as tower::Layer<S>>::Service,
-    >>::Service;
+    type Service = SessionCookieService<
+        <DynamicSessionStore as tower::Layer<SessionWrapper<SessionTimeoutService<S>>>>::Service,
+    >;
 
     fn layer(&self, inner: S) -> Self::Service {
         let session_wrapper_layer = SessionWrapperLayer::new();
         let layers = (&self.inner, session_wrapper_layer);
+        let inner = SessionTimeoutService {
+            inner,
+            timeouts: self.timeouts,
+        };
 
-        layers.layer(inner)
+        SessionCookieService {
+            inner: layers.layer(inner),
+            cookie_name: self.cookie_name.clone(),
+        }
+    }
+}
+
+/// Service struct that moves the session data between the session cookie and
+/// a [`CookieStore`].
+///
+/// This is only used inside [`SessionMiddleware`] and is a no-op unless the
+/// session is stored in a [`CookieStore`]. This shouldn't be useful on its
+/// own.
+#[derive(Debug, Clone)]
+pub struct SessionCookieService<S> {
+    inner: S,
+    #[cfg_attr(not(feature = "json"), expect(dead_code))]
+    cookie_name: Cow<'static, str>,
+}
+
+impl<ReqBody, ResBody, S> Service<http::Request<ReqBody>> for SessionCookieService<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>> + Send + 'static,
+    S::Future: Send,
+    ReqBody: Send + 'static,
+    ResBody: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    #[cfg(not(feature = "json"))]
+    fn call(&mut self, req: http::Request<ReqBody>) -> Self::Future {
+        Box::pin(self.inner.call(req))
+    }
+
+    #[cfg(feature = "json")]
+    fn call(&mut self, mut req: http::Request<ReqBody>) -> Self::Future {
+        let cookie_name = self.cookie_name.clone();
+        let cookie_session = CookieSession::from_request_headers(&cookie_name, req.headers_mut());
+        let future = self.inner.call(req);
+
+        Box::pin(async move {
+            let (result, cookie_session) = cookie_session.scope(future).await;
+            let mut response = result?;
+            cookie_session.apply_to_response_headers(&cookie_name, response.headers_mut());
+            Ok(response)
+        })
     }
 }
 
@@ -445,6 +610,90 @@ where
     }
 }
 
+const SESSION_CREATED_AT_KEY: &str = "_cot_session_created_at";
+const SESSION_LAST_ACTIVITY_KEY: &str = "_cot_session_last_activity";
+
+#[derive(Debug, Default, Copy, Clone)]
+struct SessionTimeouts {
+    idle: Option<std::time::Duration>,
+    absolute: Option<std::time::Duration>,
+}
+
+impl SessionTimeouts {
+    fn is_enabled(self) -> bool {
+        self.idle.is_some() || self.absolute.is_some()
+    }
+
+    fn is_expired(self, created_at: Option<i64>, last_activity: Option<i64>, now: i64) -> bool {
+        let elapsed = |since: Option<i64>, timeout: Option<std::time::Duration>| {
+            since.zip(timeout).is_some_and(|(since, timeout)| {
+                let elapsed = u64::try_from(now.saturating_sub(since)).unwrap_or_default();
+                std::time::Duration::from_secs(elapsed) >= timeout
+            })
+        };
+
+        elapsed(created_at, self.absolute) || elapsed(last_activity, self.idle)
+    }
+}
+
+/// Service struct that enforces the idle and absolute session timeouts.
+///
+/// This is only used inside [`SessionMiddleware`] when
+/// [`SessionMiddleware::idle_timeout`] or
+/// [`SessionMiddleware::absolute_timeout`] is set. This shouldn't be useful on
+/// its own.
+#[derive(Debug, Clone)]
+pub struct SessionTimeoutService<S> {
+    inner: S,
+    timeouts: SessionTimeouts,
+}
+
+impl<S> Service<Request> for SessionTimeoutService<S>
+where
+    S: Service<Request, Response = Response, Error = Error> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = S::Response;
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let timeouts = self.timeouts;
+        if !timeouts.is_enabled() {
+            return Box::pin(inner.call(req));
+        }
+
+        let session = crate::session::Session::from_request(&req).clone();
+        Box::pin(async move {
+            let now = time::OffsetDateTime::now_utc().unix_timestamp();
+            let created_at = session.get::<i64>(SESSION_CREATED_AT_KEY).await?;
+            let last_activity = session.get::<i64>(SESSION_LAST_ACTIVITY_KEY).await?;
+            if timeouts.is_expired(created_at, last_activity, now) {
+                session.flush().await?;
+                // `flush` keeps the session ID for the next record, so make
+                // sure a new session never reuses the ID of the expired one
+                session.cycle_id().await?;
+            }
+
+            let response = inner.call(req).await?;
+
+            if !session.is_empty().await {
+                if session.get::<i64>(SESSION_CREATED_AT_KEY).await?.is_none() {
+                    session.insert(SESSION_CREATED_AT_KEY, now).await?;
+                }
+                session.insert(SESSION_LAST_ACTIVITY_KEY, now).await?;
+            }
+            Ok(response)
+        })
+    }
+}
+
 /// A middleware that provides authentication functionality.
 ///
 /// This middleware is used to authenticate requests and add the authenticated
@@ -547,6 +796,14 @@ where
     }
 }
 
+/// Drains the body of a request that a middleware responds to without passing
+/// it on, so that the connection can be reused.
+pub(crate) async fn drain_request_body(mut request: Request) {
+    if let Err(error) = request.drain_body().await {
+        tracing::debug!(%error, "could not drain the request body");
+    }
+}
+
 // TODO: add Cot ORM-based session store
 
 #[cfg(test)]
@@ -639,6 +896,135 @@ mod tests {
         assert!(!cookie_value.contains("Secure;"));
     }
 
+    #[test]
+    fn session_timeouts_is_expired() {
+        let timeouts = SessionTimeouts {
+            idle: Some(std::time::Duration::from_mins(30)),
+            absolute: Some(std::time::Duration::from_hours(8)),
+        };
+        let now = 100_000;
+
+        assert!(!timeouts.is_expired(None, None, now));
+        assert!(!timeouts.is_expired(Some(now - 60), Some(now - 60), now));
+        assert!(timeouts.is_expired(Some(now - 60), Some(now - 30 * 60), now));
+        assert!(timeouts.is_expired(Some(now - 8 * 3600), Some(now), now));
+        assert!(!SessionTimeouts::default().is_expired(Some(0), Some(0), now));
+    }
+
+    fn session_timeout_svc(
+        store: MemoryStore,
+        middleware: impl FnOnce(SessionMiddleware) -> SessionMiddleware,
+    ) -> impl Service<
+        Request<Body>,
+        Response = Response,
+        Error = Error,
+        Future = impl Future<Output = Result<Response, Error>>,
+    > {
+        let svc = tower::service_fn(|req: Request<Body>| async move {
+            let session = req.extensions().get::<Session>().unwrap();
+            let user: Option<String> = session.get("user").await.unwrap();
+            if user.is_none() {
+                session.insert("user", "alice").await.unwrap();
+            }
+
+            Ok::<_, Error>(Response::new(Body::fixed(user.unwrap_or_default())))
+        });
+        middleware(SessionMiddleware::new(store).secure(false)).layer(svc)
+    }
+
+    async fn call_with_session_id(
+        svc: &mut (impl Service<Request<Body>, Response = Response, Error = Error> + Send),
+        session_id: Option<&tower_sessions::session::Id>,
+    ) -> Response {
+        let mut request = TestRequestBuilder::get("/").build();
+        if let Some(session_id) = session_id {
+            request.headers_mut().insert(
+                http::header::COOKIE,
+                format!("id={session_id}").parse().unwrap(),
+            );
+        }
+        svc.ready().await.unwrap().call(request).await.unwrap()
+    }
+
+    async fn session_record(
+        store: &MemoryStore,
+        response: &Response,
+    ) -> tower_sessions::session::Record {
+        let set_cookie = response
+            .headers()
+            .get(http::header::SET_COOKIE)
+            .unwrap()
+            .to_str()
+            .unwrap();
+        let session_id = set_cookie
+            .split(';')
+            .next()
+            .unwrap()
+            .strip_prefix("id=")
+            .unwrap()
+            .parse()
+            .unwrap();
+        store.load(&session_id).await.unwrap().unwrap()
+    }
+
+    #[cot::test]
+    async fn session_middleware_idle_timeout() {
+        let store = MemoryStore::new();
+        let mut svc = session_timeout_svc(store.clone(), |middleware| {
+            middleware.idle_timeout(std::time::Duration::from_mins(30))
+        });
+
+        let response = call_with_session_id(&mut svc, None).await;
+        let mut record = session_record(&store, &response).await;
+        assert!(record.data.contains_key(SESSION_LAST_ACTIVITY_KEY));
+
+        // an active session is kept
+        let response = call_with_session_id(&mut svc, Some(&record.id)).await;
+        let body = response.into_body().into_bytes().await.unwrap();
+        assert_eq!(body, "alice");
+
+        // simulate an hour of inactivity
+        let an_hour_ago = time::OffsetDateTime::now_utc().unix_timestamp() - 3600;
+        record
+            .data
+            .insert(SESSION_LAST_ACTIVITY_KEY.to_owned(), an_hour_ago.into());
+        store.save(&record).await.unwrap();
+
+        let response = call_with_session_id(&mut svc, Some(&record.id)).await;
+        let body = response.into_body().into_bytes().await.unwrap();
+        assert_eq!(body, "");
+        assert!(store.load(&record.id).await.unwrap().is_none());
+    }
+
+    #[cot::test]
+    async fn session_middleware_absolute_timeout() {
+        let store = MemoryStore::new();
+        let mut svc = session_timeout_svc(store.clone(), |middleware| {
+            middleware.absolute_timeout(std::time::Duration::from_hours(8))
+        });
+
+        let response = call_with_session_id(&mut svc, None).await;
+        let mut record = session_record(&store, &response).await;
+        let created_at = record.data[SESSION_CREATED_AT_KEY].clone();
+
+        // activity doesn't move the creation timestamp
+        call_with_session_id(&mut svc, Some(&record.id)).await;
+        let refreshed = store.load(&record.id).await.unwrap().unwrap();
+        assert_eq!(refreshed.data[SESSION_CREATED_AT_KEY], created_at);
+
+        // simulate the session being created a day ago
+        let a_day_ago = time::OffsetDateTime::now_utc().unix_timestamp() - 24 * 3600;
+        record
+            .data
+            .insert(SESSION_CREATED_AT_KEY.to_owned(), a_day_ago.into());
+        store.save(&record).await.unwrap();
+
+        let response = call_with_session_id(&mut svc, Some(&record.id)).await;
+        let body = response.into_body().into_bytes().await.unwrap();
+        assert_eq!(body, "");
+        assert!(store.load(&record.id).await.unwrap().is_none());
+    }
+
     #[cot::test]
     async fn auth_middleware_adds_auth() {
         let svc = tower::service_fn(|req: Request<Body>| async move {
@@ -756,6 +1142,7 @@ mod tests {
         let bootstrapper = Bootstrapper::new(TestProject)
             .with_config(config)
             .with_apps()
+            .expect("bootstrap failed")
             .with_database()
             .await
             .expect("bootstrap failed")
@@ -777,6 +1164,7 @@ mod tests {
         let bootstrapper = Bootstrapper::new(TestProject)
             .with_config(config)
             .with_apps()
+            .expect("bootstrap failed")
             .with_database()
             .await
             .expect("bootstrap failed")
@@ -788,6 +1176,97 @@ mod tests {
         create_svc_and_call_with_req(context).await;
     }
 
+    #[cfg(feature = "json")]
+    #[cot::test]
+    async fn session_middleware_cookie_config_to_session_store() {
+        let config = create_project_config(SessionStoreTypeConfig::Cookie);
+
+        let bootstrapper = Bootstrapper::new(TestProject)
+            .with_config(config)
+            .with_apps()
+            .expect("bootstrap failed")
+            .with_database()
+            .await
+            .expect("bootstrap failed")
+            .with_cache()
+            .await
+            .expect("bootstrap failed");
+        let context = bootstrapper.context();
+
+        create_svc_and_call_with_req(context).await;
+    }
+
+    #[cfg(feature = "json")]
+    fn cookie_store_svc() -> impl Service<
+        Request<Body>,
+        Response = Response,
+        Error = Error,
+        Future = impl Future<Output = Result<Response, Error>>,
+    > {
+        let svc = tower::service_fn(|req: Request<Body>| async move {
+            let session = req.extensions().get::<Session>().unwrap();
+            let visits: u32 = session.get("visits").await.unwrap().unwrap_or_default();
+            session.insert("visits", visits + 1).await.unwrap();
+
+            Ok::<_, Error>(Response::new(Body::fixed(visits.to_string())))
+        });
+        let store = CookieStore::new(crate::config::SecretKey::from("secret"));
+        SessionMiddleware::new(store).name("session").layer(svc)
+    }
+
+    #[cfg(feature = "json")]
+    fn session_cookie(response: &Response) -> String {
+        let set_cookie = response
+            .headers()
+            .get(http::header::SET_COOKIE)
+            .unwrap()
+            .to_str()
+            .unwrap();
+        set_cookie.split(';').next().unwrap().to_owned()
+    }
+
+    #[cfg(feature = "json")]
+    #[cot::test]
+    async fn session_middleware_cookie_store_round_trip() {
+        let mut svc = cookie_store_svc();
+
+        let request = TestRequestBuilder::get("/").build();
+        let response = svc.ready().await.unwrap().call(request).await.unwrap();
+        let cookie = session_cookie(&response);
+        assert!(cookie.starts_with("session="));
+        // the whole session is stored in the cookie, not just the session ID
+        assert_eq!(cookie.matches('.').count(), 2);
+
+        let mut request = TestRequestBuilder::get("/").build();
+        request
+            .headers_mut()
+            .insert(http::header::COOKIE, cookie.parse().unwrap());
+        let response = svc.ready().await.unwrap().call(request).await.unwrap();
+        let body = response.into_body().into_bytes().await.unwrap();
+        assert_eq!(body, "1");
+    }
+
+    #[cfg(feature = "json")]
+    #[cot::test]
+    async fn session_middleware_cookie_store_rejects_tampered_cookie() {
+        let mut svc = cookie_store_svc();
+
+        let request = TestRequestBuilder::get("/").build();
+        let response = svc.ready().await.unwrap().call(request).await.unwrap();
+        let cookie = session_cookie(&response);
+        // flip the last character of the signature
+        let (rest, last) = cookie.split_at(cookie.len() - 1);
+        let tampered = format!("{rest}{}", if last == "0" { "1" } else { "0" });
+
+        let mut request = TestRequestBuilder::get("/").build();
+        request
+            .headers_mut()
+            .insert(http::header::COOKIE, tampered.parse().unwrap());
+        let response = svc.ready().await.unwrap().call(request).await.unwrap();
+        let body = response.into_body().into_bytes().await.unwrap();
+        assert_eq!(body, "0");
+    }
+
     #[cfg(all(feature = "cache", feature = "redis"))]
     #[cot::test]
     #[ignore = "requires external Redis service"]
@@ -799,6 +1278,7 @@ mod tests {
         let bootstrapper = Bootstrapper::new(TestProject)
             .with_config(config)
             .with_apps()
+            .expect("bootstrap failed")
             .with_database()
             .await
             .expect("bootstrap failed")
@@ -821,6 +1301,7 @@ mod tests {
         let bootstrapper = Bootstrapper::new(TestProject)
             .with_config(config)
             .with_apps()
+            .expect("bootstrap failed")
             .with_database()
             .await
             .expect("bootstrap failed")
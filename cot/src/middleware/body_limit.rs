@@ -0,0 +1,248 @@
+use std::error::Error as StdError;
+use std::task::{Context, Poll};
+
+use futures_core::future::BoxFuture;
+use http::header;
+use http_body_util::{LengthLimitError, Limited};
+use tower::Service;
+
+use crate::error::impl_into_cot_error;
+use crate::request::Request;
+use crate::response::Response;
+use crate::{Body, Error};
+
+/// A middleware that limits the size of the request bodies.
+///
+/// This ensures that no handler can be forced to read a body larger than the
+/// limit, no matter how it reads it. Requests with a `Content-Length` header
+/// exceeding the limit are rejected right away, without calling the handler.
+/// For other requests (such as the ones using the chunked transfer encoding,
+/// or lying about their length), the body is wrapped in a reader that fails
+/// as soon as the limit is exceeded, so that the body is never buffered in
+/// full.
+///
+/// In both cases, the request results in a `413 Payload Too Large` error.
+///
+/// # Examples
+///
+/// ```
+/// use cot::Project;
+/// use cot::middleware::BodyLimitMiddleware;
+/// use cot::project::{MiddlewareContext, RootHandler, RootHandlerBuilder};
+///
+/// struct MyProject;
+/// impl Project for MyProject {
+///     fn middlewares(
+///         &self,
+///         handler: RootHandlerBuilder,
+///         context: &MiddlewareContext,
+///     ) -> RootHandler {
+///         handler
+///             // limit the request bodies to 2 MiB
+///             .middleware(BodyLimitMiddleware::new(2 * 1024 * 1024))
+///             .build()
+///     }
+/// }
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct BodyLimitMiddleware {
+    max_bytes: usize,
+}
+
+impl BodyLimitMiddleware {
+    /// Creates a new [`BodyLimitMiddleware`] allowing the request bodies of at
+    /// most `max_bytes` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::middleware::BodyLimitMiddleware;
+    ///
+    /// let middleware = BodyLimitMiddleware::new(1024);
+    /// ```
+    #[must_use]
+    pub const fn new(max_bytes: usize) -> Self {
+        Self { max_bytes }
+    }
+}
+
+impl<S> tower::Layer<S> for BodyLimitMiddleware {
+    type Service = BodyLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        BodyLimitService {
+            inner,
+            max_bytes: self.max_bytes,
+        }
+    }
+}
+
+/// Service that limits the size of the request bodies.
+///
+/// Used by [`BodyLimitMiddleware`].
+#[derive(Debug, Clone)]
+pub struct BodyLimitService<S> {
+    inner: S,
+    max_bytes: usize,
+}
+
+impl<S> Service<Request> for BodyLimitService<S>
+where
+    S: Service<Request, Response = Response, Error = Error> + Send,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let max_bytes = self.max_bytes;
+
+        let content_length = req
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<usize>().ok());
+        if content_length.is_some_and(|length| length > max_bytes) {
+            return Box::pin(async move { Err(PayloadTooLarge { max_bytes }.into()) });
+        }
+
+        let req = req.map(|body| Body::axum(axum::body::Body::new(Limited::new(body, max_bytes))));
+        let future = self.inner.call(req);
+
+        Box::pin(async move {
+            future.await.map_err(|error| {
+                if is_length_limit_error(&error) {
+                    PayloadTooLarge { max_bytes }.into()
+                } else {
+                    error
+                }
+            })
+        })
+    }
+}
+
+/// Checks whether the error was caused by the body exceeding the limit.
+///
+/// The handlers usually wrap the errors returned when reading the body, so the
+/// whole chain of the error sources is checked.
+fn is_length_limit_error(error: &Error) -> bool {
+    let mut source: Option<&(dyn StdError + 'static)> = error.source();
+    while let Some(error) = source {
+        if error.is::<LengthLimitError>() {
+            return true;
+        }
+        source = error.source();
+    }
+    false
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("request body is larger than the limit of {max_bytes} bytes")]
+struct PayloadTooLarge {
+    max_bytes: usize,
+}
+impl_into_cot_error!(PayloadTooLarge, PAYLOAD_TOO_LARGE);
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use futures::stream;
+    use http::HeaderValue;
+    use tower::{Layer, ServiceExt};
+
+    use super::*;
+    use crate::StatusCode;
+    use crate::test::TestRequestBuilder;
+
+    async fn call_with_middleware(request: Request) -> crate::Result<Response> {
+        let svc = tower::service_fn(|request: Request| async move {
+            let body = request.into_body().into_bytes().await?;
+            Ok::<_, Error>(Response::new(Body::fixed(body)))
+        });
+
+        BodyLimitMiddleware::new(8)
+            .layer(svc)
+            .oneshot(request)
+            .await
+    }
+
+    #[cot::test]
+    async fn body_limit_within_limit() {
+        let mut request = TestRequestBuilder::post("/").build();
+        *request.body_mut() = Body::fixed("12345678");
+
+        let response = call_with_middleware(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.into_body().into_bytes().await.unwrap(), "12345678");
+    }
+
+    #[cot::test]
+    async fn body_limit_content_length_exceeded() {
+        let handler_called = std::sync::atomic::AtomicBool::new(false);
+        let svc = tower::service_fn(|_request: Request| {
+            handler_called.store(true, std::sync::atomic::Ordering::SeqCst);
+            async { Ok::<_, Error>(Response::new(Body::empty())) }
+        });
+        let mut request = TestRequestBuilder::post("/").build();
+        request
+            .headers_mut()
+            .insert(header::CONTENT_LENGTH, HeaderValue::from_static("1000"));
+
+        let error = BodyLimitMiddleware::new(8)
+            .layer(svc)
+            .oneshot(request)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.status_code(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert!(!handler_called.load(std::sync::atomic::Ordering::SeqCst));
+    }
+
+    #[cot::test]
+    async fn body_limit_streaming_exceeded() {
+        let mut request = TestRequestBuilder::post("/").build();
+        *request.body_mut() = Body::streaming(stream::iter([
+            Ok(Bytes::from_static(b"12345")),
+            Ok(Bytes::from_static(b"67890")),
+        ]));
+
+        let error = call_with_middleware(request).await.unwrap_err();
+
+        assert_eq!(error.status_code(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[cot::test]
+    async fn body_limit_lying_content_length() {
+        let mut request = TestRequestBuilder::post("/").build();
+        *request.body_mut() = Body::fixed("1234567890");
+        request
+            .headers_mut()
+            .insert(header::CONTENT_LENGTH, HeaderValue::from_static("4"));
+
+        let error = call_with_middleware(request).await.unwrap_err();
+
+        assert_eq!(error.status_code(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[cot::test]
+    async fn body_limit_other_errors_kept() {
+        let svc = tower::service_fn(|_request: Request| async {
+            Err::<Response, _>(Error::internal("handler error"))
+        });
+        let request = TestRequestBuilder::post("/").build();
+
+        let error = BodyLimitMiddleware::new(8)
+            .layer(svc)
+            .oneshot(request)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -0,0 +1,226 @@
+use std::any::Any;
+use std::fmt::Write;
+use std::panic::AssertUnwindSafe;
+use std::task::{Context, Poll};
+
+use futures_core::future::BoxFuture;
+use futures_util::FutureExt;
+use tower::Service;
+use tracing::error;
+
+use crate::Error;
+use crate::error::UncaughtPanic;
+use crate::error_page::last_panic_info;
+use crate::request::Request;
+use crate::response::Response;
+
+/// A middleware that catches the panics in the request handlers and turns
+/// them into errors.
+///
+/// The panic is logged along with the request method and path, as well as the
+/// panic location and backtrace (if the panic hook is registered, see
+/// [`ProjectConfig::register_panic_hook`](crate::config::ProjectConfig::register_panic_hook)).
+/// The request then results in an [`UncaughtPanic`] error, which is rendered
+/// by the error page machinery as a `500 Internal Server Error` response, so
+/// a single misbehaving request doesn't affect the connection or the other
+/// requests.
+///
+/// While Cot also catches the panics in the root handler, this middleware
+/// allows the middlewares added after it (i.e., wrapping it) to see the panic
+/// as a regular error response. It can be also used to isolate a group of
+/// routes using [`Router::middleware`](crate::router::Router::middleware).
+///
+/// # Unwind safety
+///
+/// The request is moved into the handler and is never accessed again after
+/// the handler panics, so no broken state can be observed through it. Any
+/// state shared by the handler (such as the data behind an `Arc<Mutex<_>>`)
+/// might be left in an inconsistent state, though, just like with any other
+/// panic.
+///
+/// # Examples
+///
+/// ```
+/// use cot::Project;
+/// use cot::middleware::CatchPanicMiddleware;
+/// use cot::project::{MiddlewareContext, RootHandler, RootHandlerBuilder};
+///
+/// struct MyProject;
+/// impl Project for MyProject {
+///     fn middlewares(
+///         &self,
+///         handler: RootHandlerBuilder,
+///         context: &MiddlewareContext,
+///     ) -> RootHandler {
+///         handler.middleware(CatchPanicMiddleware::new()).build()
+///     }
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, Default)]
+pub struct CatchPanicMiddleware;
+
+impl CatchPanicMiddleware {
+    /// Creates a new [`CatchPanicMiddleware`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::middleware::CatchPanicMiddleware;
+    ///
+    /// let middleware = CatchPanicMiddleware::new();
+    /// ```
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> tower::Layer<S> for CatchPanicMiddleware {
+    type Service = CatchPanicService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CatchPanicService { inner }
+    }
+}
+
+/// Service that catches the panics in the inner service.
+///
+/// Used by [`CatchPanicMiddleware`].
+#[derive(Debug, Clone)]
+pub struct CatchPanicService<S> {
+    inner: S,
+}
+
+impl<S> Service<Request> for CatchPanicService<S>
+where
+    S: Service<Request, Response = Response, Error = Error> + Send,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let method = req.method().clone();
+        let path = req.uri().path().to_owned();
+
+        // the handler can panic both when creating the future and when polling it
+        let future = match std::panic::catch_unwind(AssertUnwindSafe(|| self.inner.call(req))) {
+            Ok(future) => future,
+            Err(payload) => {
+                log_panic(payload.as_ref(), &method, &path);
+                return Box::pin(async move { Err(UncaughtPanic::new(payload).into()) });
+            }
+        };
+
+        Box::pin(async move {
+            match AssertUnwindSafe(future).catch_unwind().await {
+                Ok(result) => result,
+                Err(payload) => {
+                    log_panic(payload.as_ref(), &method, &path);
+                    Err(UncaughtPanic::new(payload).into())
+                }
+            }
+        })
+    }
+}
+
+fn log_panic(payload: &(dyn Any + Send), method: &http::Method, path: &str) {
+    let panic_message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("<non-string panic payload>");
+    let (location, backtrace) = last_panic_info();
+    let backtrace = backtrace.map(|backtrace| {
+        let mut formatted = String::new();
+        for (index, frame) in backtrace.frames().iter().enumerate() {
+            let _ = writeln!(
+                formatted,
+                "{index:4}: {}\n      at {}",
+                frame.symbol_name(),
+                frame.location()
+            );
+        }
+        formatted
+    });
+
+    error!(
+        %method,
+        path,
+        panic_message,
+        location = location.as_deref().unwrap_or("<unknown>"),
+        backtrace = backtrace.as_deref().unwrap_or("<not captured>"),
+        "Request handler panicked!"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use tower::{Layer, ServiceExt};
+
+    use super::*;
+    use crate::test::TestRequestBuilder;
+    use crate::{Body, StatusCode};
+
+    #[cot::test]
+    async fn catch_panic_returns_error() {
+        let svc = tower::service_fn(|request: Request| async move {
+            if request.uri().path() == "/panic" {
+                panic!("handler panicked");
+            }
+            Ok::<_, Error>(Response::new(Body::fixed("OK")))
+        });
+        let service = CatchPanicMiddleware::new().layer(svc);
+
+        let error = service
+            .clone()
+            .oneshot(TestRequestBuilder::get("/panic").build())
+            .await
+            .unwrap_err();
+        assert_eq!(error.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+        let panic = error.inner().downcast_ref::<UncaughtPanic>().unwrap();
+        assert_eq!(
+            panic.payload().downcast_ref::<&str>(),
+            Some(&"handler panicked")
+        );
+
+        // the service keeps serving requests after a panic
+        let response = service
+            .oneshot(TestRequestBuilder::get("/").build())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[cot::test]
+    async fn catch_panic_in_call() {
+        #[derive(Clone)]
+        struct PanicInCall;
+
+        impl Service<Request> for PanicInCall {
+            type Response = Response;
+            type Error = Error;
+            type Future = std::future::Ready<Result<Response, Error>>;
+
+            fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn call(&mut self, _req: Request) -> Self::Future {
+                panic!("call panicked");
+            }
+        }
+
+        let error = CatchPanicMiddleware::new()
+            .layer(PanicInCall)
+            .oneshot(TestRequestBuilder::get("/").build())
+            .await
+            .unwrap_err();
+        assert!(error.inner().is::<UncaughtPanic>());
+    }
+}
@@ -0,0 +1,282 @@
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use cot_core::headers::PLAIN_TEXT_CONTENT_TYPE;
+use futures_core::future::BoxFuture;
+use http::{HeaderValue, header};
+use tokio::sync::Semaphore;
+use tower::Service;
+
+use crate::request::Request;
+use crate::response::Response;
+use crate::{Body, Error, StatusCode};
+
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// A middleware that limits the number of requests handled concurrently.
+///
+/// When the limit is reached, the new requests are not queued; instead, they
+/// are rejected right away with a `503 Service Unavailable` response with
+/// the `Retry-After` header set. This allows to shed the load when the server
+/// is overloaded, rather than accumulating requests that would likely time
+/// out anyway.
+///
+/// A request is considered to be in flight until its handler returns a
+/// response; sending the response body is not included.
+///
+/// The middleware can be cloned, and all the clones share the same limit.
+/// This can be used to keep a handle to the middleware for reporting the
+/// number of the requests in flight with [`in_flight`](Self::in_flight).
+///
+/// # Examples
+///
+/// ```
+/// use cot::Project;
+/// use cot::middleware::ConcurrencyLimitMiddleware;
+/// use cot::project::{MiddlewareContext, RootHandler, RootHandlerBuilder};
+///
+/// struct MyProject;
+/// impl Project for MyProject {
+///     fn middlewares(
+///         &self,
+///         handler: RootHandlerBuilder,
+///         context: &MiddlewareContext,
+///     ) -> RootHandler {
+///         handler
+///             .middleware(ConcurrencyLimitMiddleware::new(512))
+///             .build()
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConcurrencyLimitMiddleware {
+    limit: Arc<ConcurrencyLimit>,
+}
+
+#[derive(Debug)]
+struct ConcurrencyLimit {
+    semaphore: Arc<Semaphore>,
+    max_in_flight: usize,
+    retry_after: HeaderValue,
+}
+
+impl ConcurrencyLimitMiddleware {
+    /// Creates a new [`ConcurrencyLimitMiddleware`] allowing at most
+    /// `max_in_flight` requests to be handled at the same time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_in_flight` is larger than
+    /// [`Semaphore::MAX_PERMITS`](tokio::sync::Semaphore::MAX_PERMITS).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::middleware::ConcurrencyLimitMiddleware;
+    ///
+    /// let middleware = ConcurrencyLimitMiddleware::new(512);
+    /// ```
+    #[must_use]
+    pub fn new(max_in_flight: usize) -> Self {
+        Self {
+            limit: Arc::new(ConcurrencyLimit {
+                semaphore: Arc::new(Semaphore::new(max_in_flight)),
+                max_in_flight,
+                retry_after: retry_after_header(DEFAULT_RETRY_AFTER),
+            }),
+        }
+    }
+
+    /// Sets the value of the `Retry-After` header sent with the rejected
+    /// requests. The duration is rounded down to whole seconds.
+    ///
+    /// Defaults to 1 second.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::middleware::ConcurrencyLimitMiddleware;
+    ///
+    /// let middleware = ConcurrencyLimitMiddleware::new(512).retry_after(Duration::from_secs(5));
+    /// ```
+    #[must_use]
+    pub fn retry_after(self, retry_after: Duration) -> Self {
+        Self {
+            limit: Arc::new(ConcurrencyLimit {
+                semaphore: Arc::clone(&self.limit.semaphore),
+                max_in_flight: self.limit.max_in_flight,
+                retry_after: retry_after_header(retry_after),
+            }),
+        }
+    }
+
+    /// Returns the number of requests that are currently being handled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::middleware::ConcurrencyLimitMiddleware;
+    ///
+    /// let middleware = ConcurrencyLimitMiddleware::new(512);
+    /// assert_eq!(middleware.in_flight(), 0);
+    /// ```
+    #[must_use]
+    pub fn in_flight(&self) -> usize {
+        self.limit.max_in_flight - self.limit.semaphore.available_permits()
+    }
+
+    /// Returns the maximum number of requests that can be handled at the same
+    /// time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::middleware::ConcurrencyLimitMiddleware;
+    ///
+    /// let middleware = ConcurrencyLimitMiddleware::new(512);
+    /// assert_eq!(middleware.max_in_flight(), 512);
+    /// ```
+    #[must_use]
+    pub fn max_in_flight(&self) -> usize {
+        self.limit.max_in_flight
+    }
+}
+
+fn retry_after_header(retry_after: Duration) -> HeaderValue {
+    HeaderValue::from(retry_after.as_secs())
+}
+
+impl<S> tower::Layer<S> for ConcurrencyLimitMiddleware {
+    type Service = ConcurrencyLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ConcurrencyLimitService {
+            inner,
+            limit: Arc::clone(&self.limit),
+        }
+    }
+}
+
+/// Service that limits the number of requests handled concurrently.
+///
+/// Used by [`ConcurrencyLimitMiddleware`].
+#[derive(Debug, Clone)]
+pub struct ConcurrencyLimitService<S> {
+    inner: S,
+    limit: Arc<ConcurrencyLimit>,
+}
+
+impl<S> Service<Request> for ConcurrencyLimitService<S>
+where
+    S: Service<Request, Response = Response, Error = Error> + Send,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let Ok(permit) = Arc::clone(&self.limit.semaphore).try_acquire_owned() else {
+            let retry_after = self.limit.retry_after.clone();
+            return Box::pin(async move {
+                super::drain_request_body(req).await;
+                Ok(service_unavailable(retry_after))
+            });
+        };
+        let future = self.inner.call(req);
+
+        Box::pin(async move {
+            let response = future.await;
+            drop(permit);
+            response
+        })
+    }
+}
+
+fn service_unavailable(retry_after: HeaderValue) -> Response {
+    let mut response = Response::new(Body::fixed("Service Unavailable"));
+    *response.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(PLAIN_TEXT_CONTENT_TYPE),
+    );
+    headers.insert(header::RETRY_AFTER, retry_after);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use tower::{Layer, ServiceExt};
+
+    use super::*;
+    use crate::test::TestRequestBuilder;
+
+    #[cot::test]
+    async fn concurrency_limit_rejects_over_limit() {
+        let gate = Arc::new(Semaphore::new(0));
+        let svc = {
+            let gate = Arc::clone(&gate);
+            tower::service_fn(move |_req: Request| {
+                let gate = Arc::clone(&gate);
+                async move {
+                    let _permit = gate.acquire().await.unwrap();
+                    Ok::<_, Error>(Response::new(Body::fixed("OK")))
+                }
+            })
+        };
+        let middleware = ConcurrencyLimitMiddleware::new(2).retry_after(Duration::from_secs(5));
+        let service = middleware.layer(svc);
+
+        let in_flight_requests: Vec<_> = (0..2)
+            .map(|_| {
+                let service = service.clone();
+                tokio::spawn(service.oneshot(TestRequestBuilder::get("/").build()))
+            })
+            .collect();
+        while middleware.in_flight() < 2 {
+            tokio::task::yield_now().await;
+        }
+
+        let response = service
+            .clone()
+            .oneshot(TestRequestBuilder::get("/").build())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "5");
+
+        gate.add_permits(2);
+        for request in in_flight_requests {
+            let response = request.await.unwrap().unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        assert_eq!(middleware.in_flight(), 0);
+    }
+
+    #[cot::test]
+    async fn concurrency_limit_releases_on_error() {
+        let svc = tower::service_fn(|_req: Request| async {
+            Err::<Response, _>(Error::internal("handler error"))
+        });
+        let middleware = ConcurrencyLimitMiddleware::new(1);
+        let service = middleware.layer(svc);
+
+        for _ in 0..2 {
+            let error = service
+                .clone()
+                .oneshot(TestRequestBuilder::get("/").build())
+                .await
+                .unwrap_err();
+            assert_eq!(error.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+        }
+        assert_eq!(middleware.in_flight(), 0);
+    }
+}
@@ -0,0 +1,357 @@
+use std::task::{Context, Poll};
+
+use futures_core::future::BoxFuture;
+use http::{HeaderValue, Method, StatusCode, header};
+use tower::Service;
+
+use crate::request::Request;
+use crate::response::Response;
+use crate::{Body, Error};
+
+/// A middleware that adds weak `ETag` headers to the responses and answers
+/// conditional requests with `304 Not Modified`.
+///
+/// The `ETag` is computed by hashing the response body, so this works for any
+/// dynamically generated response (such as rendered HTML pages or JSON API
+/// responses) without the handlers having to do anything. When a `GET` or
+/// `HEAD` request contains an `If-None-Match` header matching the `ETag` of
+/// the response, the body is dropped and an empty `304 Not Modified` response
+/// is sent instead, saving the bandwidth.
+///
+/// Note that the handler is still called for every request, so this doesn't
+/// reduce the work done on the server. The following responses are passed
+/// through unchanged:
+///
+/// * responses to methods other than `GET` and `HEAD`,
+/// * responses with a status code other than `200 OK`,
+/// * responses marked with `Cache-Control: no-store`,
+/// * streaming responses, as their body can't be hashed without buffering it
+///   in memory.
+///
+/// If the handler sets the `ETag` header itself, it's left untouched and used
+/// when checking the `If-None-Match` header.
+///
+/// Since hashing the responses adds some CPU cost, the middleware is not
+/// enabled by default. It's usually best to only apply it to the routes that
+/// benefit from it, using [`Router::middleware`](crate::router::Router::middleware).
+///
+/// # Examples
+///
+/// ```
+/// use cot::html::Html;
+/// use cot::middleware::ETagMiddleware;
+/// use cot::router::{Route, Router};
+///
+/// async fn articles() -> Html {
+///     Html::new("<h1>Articles</h1>")
+/// }
+///
+/// let cached = Router::with_urls([Route::with_handler("/articles", articles)])
+///     .middleware(ETagMiddleware::new());
+/// let router = Router::with_urls([Route::with_router("", cached)]);
+/// ```
+#[derive(Debug, Copy, Clone, Default)]
+pub struct ETagMiddleware;
+
+impl ETagMiddleware {
+    /// Creates a new [`ETagMiddleware`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::middleware::ETagMiddleware;
+    ///
+    /// let middleware = ETagMiddleware::new();
+    /// ```
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> tower::Layer<S> for ETagMiddleware {
+    type Service = ETagService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ETagService { inner }
+    }
+}
+
+/// Service that adds `ETag` headers to the responses and handles the
+/// `If-None-Match` request header.
+///
+/// Used by [`ETagMiddleware`].
+#[derive(Debug, Clone)]
+pub struct ETagService<S> {
+    inner: S,
+}
+
+impl<S> Service<Request> for ETagService<S>
+where
+    S: Service<Request, Response = Response, Error = Error> + Send,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let conditional = matches!(*req.method(), Method::GET | Method::HEAD);
+        let if_none_match = req.headers().get(header::IF_NONE_MATCH).cloned();
+        let future = self.inner.call(req);
+
+        Box::pin(async move {
+            let mut response = future.await?;
+            if !conditional || !is_etag_applicable(&response) {
+                return Ok(response);
+            }
+
+            let etag = match response.headers().get(header::ETAG) {
+                Some(etag) => etag.clone(),
+                None => {
+                    let Some(body) = response.body().as_fixed() else {
+                        return Ok(response);
+                    };
+                    let etag = weak_etag(body);
+                    response.headers_mut().insert(header::ETAG, etag.clone());
+                    etag
+                }
+            };
+
+            if if_none_match.is_some_and(|if_none_match| etag_matches(&if_none_match, &etag)) {
+                return Ok(not_modified(response));
+            }
+            Ok(response)
+        })
+    }
+}
+
+fn is_etag_applicable(response: &Response) -> bool {
+    if response.status() != StatusCode::OK {
+        return false;
+    }
+
+    let no_store = response
+        .headers()
+        .get_all(header::CACHE_CONTROL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|directive| directive.trim().eq_ignore_ascii_case("no-store"));
+    !no_store
+}
+
+fn weak_etag(body: &[u8]) -> HeaderValue {
+    let hash = blake3::hash(body);
+    // 128 bits are more than enough to identify the versions of a response
+    let etag = format!("W/\"{}\"", hex::encode(&hash.as_bytes()[..16]));
+    HeaderValue::from_str(&etag).expect("hex-encoded ETag is always a valid header value")
+}
+
+/// Checks whether the `If-None-Match` header value matches the given `ETag`,
+/// using the weak comparison (as required for `If-None-Match`).
+fn etag_matches(if_none_match: &HeaderValue, etag: &HeaderValue) -> bool {
+    let Ok(if_none_match) = if_none_match.to_str() else {
+        return false;
+    };
+    let Ok(etag) = etag.to_str() else {
+        return false;
+    };
+    let etag = strip_weak_prefix(etag.trim());
+
+    if_none_match.trim() == "*"
+        || if_none_match
+            .split(',')
+            .any(|candidate| strip_weak_prefix(candidate.trim()) == etag)
+}
+
+fn strip_weak_prefix(etag: &str) -> &str {
+    etag.strip_prefix("W/").unwrap_or(etag)
+}
+
+fn not_modified(response: Response) -> Response {
+    let (mut parts, _body) = response.into_parts();
+    parts.status = StatusCode::NOT_MODIFIED;
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.remove(header::CONTENT_TYPE);
+
+    Response::from_parts(parts, Body::empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use tower::{Layer, ServiceExt};
+
+    use super::*;
+    use crate::response::ResponseExt;
+    use crate::test::TestRequestBuilder;
+
+    fn html_service(
+        body: &'static str,
+    ) -> impl Service<Request, Response = Response, Error = Error, Future: Send + 'static> + Send + Clone
+    {
+        tower::service_fn(move |_req: Request| async move {
+            Ok::<_, Error>(Response::new(Body::fixed(body)))
+        })
+    }
+
+    async fn get_with_etag<S>(service: S, if_none_match: Option<&str>) -> Response
+    where
+        S: Service<Request, Response = Response, Error = Error> + Send,
+        S::Future: Send + 'static,
+    {
+        let mut request = TestRequestBuilder::get("/").build();
+        if let Some(if_none_match) = if_none_match {
+            request.headers_mut().insert(
+                header::IF_NONE_MATCH,
+                HeaderValue::from_str(if_none_match).unwrap(),
+            );
+        }
+
+        ETagMiddleware::new()
+            .layer(service)
+            .oneshot(request)
+            .await
+            .unwrap()
+    }
+
+    #[cot::test]
+    async fn etag_added() {
+        let response = get_with_etag(html_service("Hello"), None).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response
+            .headers()
+            .get(header::ETAG)
+            .unwrap()
+            .to_str()
+            .unwrap();
+        assert!(etag.starts_with("W/\""));
+        assert!(etag.ends_with('"'));
+
+        // same body, same ETag
+        let other = get_with_etag(html_service("Hello"), None).await;
+        assert_eq!(other.headers().get(header::ETAG).unwrap(), etag);
+
+        // different body, different ETag
+        let other = get_with_etag(html_service("Hello!"), None).await;
+        assert_ne!(other.headers().get(header::ETAG).unwrap(), etag);
+    }
+
+    #[cot::test]
+    async fn etag_not_modified() {
+        let response = get_with_etag(html_service("Hello"), None).await;
+        let etag = response
+            .headers()
+            .get(header::ETAG)
+            .unwrap()
+            .to_str()
+            .unwrap();
+
+        let response = get_with_etag(html_service("Hello"), Some(etag)).await;
+
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers().get(header::ETAG).unwrap(), etag);
+        assert_eq!(
+            response.into_body().into_bytes().await.unwrap(),
+            Bytes::new()
+        );
+    }
+
+    #[cot::test]
+    async fn etag_matches_list_and_wildcard() {
+        let response = get_with_etag(html_service("Hello"), None).await;
+        let etag = response
+            .headers()
+            .get(header::ETAG)
+            .unwrap()
+            .to_str()
+            .unwrap();
+        let strong_etag = etag.strip_prefix("W/").unwrap();
+
+        let if_none_match = format!("\"other\", {strong_etag}");
+        let response = get_with_etag(html_service("Hello"), Some(&if_none_match)).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        let response = get_with_etag(html_service("Hello"), Some("*")).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        let response = get_with_etag(html_service("Hello"), Some("W/\"other\"")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[cot::test]
+    async fn etag_set_by_handler_is_kept() {
+        let service = tower::service_fn(|_req: Request| async {
+            let mut response = Response::new(Body::fixed("Hello"));
+            response
+                .headers_mut()
+                .insert(header::ETAG, HeaderValue::from_static("\"v1\""));
+            Ok::<_, Error>(response)
+        });
+
+        let response = get_with_etag(service.clone(), None).await;
+        assert_eq!(response.headers().get(header::ETAG).unwrap(), "\"v1\"");
+
+        let response = get_with_etag(service, Some("\"v1\"")).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    }
+
+    #[cot::test]
+    async fn etag_skips_streaming_body() {
+        let service = tower::service_fn(|_req: Request| async {
+            let stream = futures_util::stream::once(async { Ok(Bytes::from("Hello")) });
+            Ok::<_, Error>(Response::new(Body::streaming(stream)))
+        });
+
+        let response = get_with_etag(service, Some("*")).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!response.headers().contains_key(header::ETAG));
+    }
+
+    #[cot::test]
+    async fn etag_skips_non_ok_status() {
+        let service = tower::service_fn(|_req: Request| async {
+            let mut response = Response::new(Body::fixed("Not found"));
+            *response.status_mut() = StatusCode::NOT_FOUND;
+            Ok::<_, Error>(response)
+        });
+
+        let response = get_with_etag(service, Some("*")).await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert!(!response.headers().contains_key(header::ETAG));
+    }
+
+    #[cot::test]
+    async fn etag_skips_no_store() {
+        let service = tower::service_fn(|_req: Request| async {
+            Ok::<_, Error>(Response::new(Body::fixed("Secret")).no_cache())
+        });
+
+        let response = get_with_etag(service, Some("*")).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!response.headers().contains_key(header::ETAG));
+    }
+
+    #[cot::test]
+    async fn etag_skips_post() {
+        let request = TestRequestBuilder::post("/").build();
+
+        let response = ETagMiddleware::new()
+            .layer(html_service("Hello"))
+            .oneshot(request)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!response.headers().contains_key(header::ETAG));
+    }
+}
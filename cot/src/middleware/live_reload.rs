@@ -186,6 +186,7 @@ mod tests {
         let bootstrapper = Bootstrapper::new(TestProject)
             .with_config(config)
             .with_apps()
+            .unwrap()
             .with_database()
             .await
             .unwrap()
@@ -0,0 +1,557 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_core::future::BoxFuture;
+use http::{HeaderValue, StatusCode, header};
+use ipnet::IpNet;
+use tower::Service;
+
+use crate::project::MiddlewareContext;
+use crate::request::{Request, RequestExt};
+use crate::response::{Response, ResponseExt};
+use crate::{Body, Error};
+
+const DEFAULT_MAINTENANCE_HTML: &str = "<!DOCTYPE html>\
+<html lang=\"en\">\
+<head><meta charset=\"utf-8\"><title>Down for maintenance</title></head>\
+<body><h1>We'll be right back</h1>\
+<p>The site is undergoing maintenance. Please try again in a few minutes.</p></body>\
+</html>";
+
+/// A middleware that responds to all requests with a `503 Service
+/// Unavailable` page while the application is under maintenance (e.g. during
+/// a deployment).
+///
+/// The maintenance mode is active when it has been enabled with
+/// [`enabled`](Self::enabled), or when the
+/// [sentinel file](Self::sentinel_file) exists. The existence of the sentinel
+/// file is checked on every request, so the maintenance mode can be toggled
+/// without restarting the server.
+///
+/// While the maintenance mode is active, the requests to the
+/// [allowed paths](Self::allow_path) (such as health checks) and from the
+/// [allowed networks](Self::allow_ip) (so that it's possible to verify the
+/// deployment) are still passed to the request handlers. All the other
+/// requests get the maintenance page, which can be customized using
+/// [`html`](Self::html), with the `Retry-After` header if
+/// [`retry_after`](Self::retry_after) has been set.
+///
+/// The middleware can be configured using the
+/// [`middlewares.maintenance_mode`](crate::config::MaintenanceModeMiddlewareConfig)
+/// section of the project config with [`from_context`](Self::from_context).
+/// It should typically be applied as the outermost middleware, so that no
+/// other processing happens for the rejected requests.
+///
+/// # Examples
+///
+/// ```
+/// use cot::Project;
+/// use cot::middleware::MaintenanceModeMiddleware;
+/// use cot::project::{MiddlewareContext, RootHandler, RootHandlerBuilder};
+///
+/// struct MyProject;
+/// impl Project for MyProject {
+///     fn middlewares(
+///         &self,
+///         handler: RootHandlerBuilder,
+///         context: &MiddlewareContext,
+///     ) -> RootHandler {
+///         handler
+///             .middleware(
+///                 MaintenanceModeMiddleware::from_context(context)
+///                     .html("<h1>Deploying a new version, hold on!</h1>"),
+///             )
+///             .build()
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MaintenanceModeMiddleware {
+    mode: Arc<MaintenanceMode>,
+}
+
+#[derive(Debug, Clone)]
+struct MaintenanceMode {
+    enabled: bool,
+    sentinel_file: Option<PathBuf>,
+    retry_after: Option<Duration>,
+    allowed_paths: Vec<String>,
+    allowed_ips: Vec<IpNet>,
+    html: String,
+}
+
+impl MaintenanceModeMiddleware {
+    /// Creates a new [`MaintenanceModeMiddleware`].
+    ///
+    /// The maintenance mode is disabled by default; use
+    /// [`enabled`](Self::enabled) or [`sentinel_file`](Self::sentinel_file)
+    /// to enable it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::middleware::MaintenanceModeMiddleware;
+    ///
+    /// let middleware = MaintenanceModeMiddleware::new().enabled(true);
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            mode: Arc::new(MaintenanceMode {
+                enabled: false,
+                sentinel_file: None,
+                retry_after: None,
+                allowed_paths: Vec::new(),
+                allowed_ips: Vec::new(),
+                html: DEFAULT_MAINTENANCE_HTML.to_owned(),
+            }),
+        }
+    }
+
+    /// Creates a new [`MaintenanceModeMiddleware`] configured using the
+    /// [`middlewares.maintenance_mode`](crate::config::MaintenanceModeMiddlewareConfig)
+    /// section of the project config.
+    ///
+    /// # Examples
+    ///
+    /// ```toml
+    /// [middlewares.maintenance_mode]
+    /// sentinel_file = "/var/run/myapp/maintenance"
+    /// retry_after = "5m"
+    /// allowed_paths = ["/health"]
+    /// allowed_ips = ["10.0.0.0/8"]
+    /// ```
+    ///
+    /// ```
+    /// use cot::Project;
+    /// use cot::middleware::MaintenanceModeMiddleware;
+    /// use cot::project::{MiddlewareContext, RootHandler, RootHandlerBuilder};
+    ///
+    /// struct MyProject;
+    /// impl Project for MyProject {
+    ///     fn middlewares(
+    ///         &self,
+    ///         handler: RootHandlerBuilder,
+    ///         context: &MiddlewareContext,
+    ///     ) -> RootHandler {
+    ///         handler
+    ///             .middleware(MaintenanceModeMiddleware::from_context(context))
+    ///             .build()
+    ///     }
+    /// }
+    /// ```
+    #[must_use]
+    pub fn from_context(context: &MiddlewareContext) -> Self {
+        let config = &context.config().middlewares.maintenance_mode;
+
+        let mut middleware = Self::new().enabled(config.enabled);
+        if let Some(sentinel_file) = &config.sentinel_file {
+            middleware = middleware.sentinel_file(sentinel_file.clone());
+        }
+        if let Some(retry_after) = config.retry_after {
+            middleware = middleware.retry_after(retry_after);
+        }
+        for path in &config.allowed_paths {
+            middleware = middleware.allow_path(path.clone());
+        }
+        for network in &config.allowed_ips {
+            middleware = middleware.allow_ip(*network);
+        }
+        middleware
+    }
+
+    /// Enables or disables the maintenance mode.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::middleware::MaintenanceModeMiddleware;
+    ///
+    /// let middleware = MaintenanceModeMiddleware::new().enabled(true);
+    /// ```
+    #[must_use]
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        Arc::make_mut(&mut self.mode).enabled = enabled;
+        self
+    }
+
+    /// Sets the path to a file that enables the maintenance mode when it
+    /// exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::middleware::MaintenanceModeMiddleware;
+    ///
+    /// let middleware =
+    ///     MaintenanceModeMiddleware::new().sentinel_file("/var/run/myapp/maintenance");
+    /// ```
+    #[must_use]
+    pub fn sentinel_file<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        Arc::make_mut(&mut self.mode).sentinel_file = Some(path.into());
+        self
+    }
+
+    /// Sets the value of the `Retry-After` header sent with the maintenance
+    /// page. The duration is rounded down to whole seconds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::middleware::MaintenanceModeMiddleware;
+    ///
+    /// let middleware = MaintenanceModeMiddleware::new().retry_after(Duration::from_mins(5));
+    /// ```
+    #[must_use]
+    pub fn retry_after(mut self, retry_after: Duration) -> Self {
+        Arc::make_mut(&mut self.mode).retry_after = Some(retry_after);
+        self
+    }
+
+    /// Adds a path that is served normally even when the maintenance mode is
+    /// enabled.
+    ///
+    /// The path matches if it's equal to the request path, or if it ends
+    /// with a slash and the request path starts with it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::middleware::MaintenanceModeMiddleware;
+    ///
+    /// let middleware = MaintenanceModeMiddleware::new()
+    ///     .allow_path("/health")
+    ///     .allow_path("/static/");
+    /// ```
+    #[must_use]
+    pub fn allow_path<P: Into<String>>(mut self, path: P) -> Self {
+        Arc::make_mut(&mut self.mode)
+            .allowed_paths
+            .push(path.into());
+        self
+    }
+
+    /// Adds a network whose clients are served normally even when the
+    /// maintenance mode is enabled.
+    ///
+    /// The client address is determined using
+    /// [`RequestExt::remote_addr`], so the
+    /// [trusted proxies](crate::config::ProjectConfig::trusted_proxies) are
+    /// taken into account.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::middleware::MaintenanceModeMiddleware;
+    ///
+    /// let middleware = MaintenanceModeMiddleware::new().allow_ip("10.0.0.0/8".parse()?);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn allow_ip(mut self, network: IpNet) -> Self {
+        Arc::make_mut(&mut self.mode).allowed_ips.push(network);
+        self
+    }
+
+    /// Sets the HTML body of the maintenance page.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::middleware::MaintenanceModeMiddleware;
+    ///
+    /// let middleware =
+    ///     MaintenanceModeMiddleware::new().html("<h1>Deploying a new version, hold on!</h1>");
+    /// ```
+    #[must_use]
+    pub fn html<T: Into<String>>(mut self, html: T) -> Self {
+        Arc::make_mut(&mut self.mode).html = html.into();
+        self
+    }
+}
+
+impl Default for MaintenanceModeMiddleware {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> tower::Layer<S> for MaintenanceModeMiddleware {
+    type Service = MaintenanceModeService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        MaintenanceModeService {
+            inner,
+            mode: Arc::clone(&self.mode),
+        }
+    }
+}
+
+/// Service that responds with a maintenance page while the maintenance mode
+/// is active.
+///
+/// Used by [`MaintenanceModeMiddleware`].
+#[derive(Debug, Clone)]
+pub struct MaintenanceModeService<S> {
+    inner: S,
+    mode: Arc<MaintenanceMode>,
+}
+
+impl<S> Service<Request> for MaintenanceModeService<S>
+where
+    S: Service<Request, Response = Response, Error = Error> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        if !self.mode.can_be_active() || self.mode.is_allowed(&req) {
+            return Box::pin(self.inner.call(req));
+        }
+        if self.mode.enabled {
+            let response = self.mode.response();
+            return Box::pin(async move {
+                super::drain_request_body(req).await;
+                Ok(response)
+            });
+        }
+
+        // the inner service has been polled ready, so we need to use it for this
+        // request and leave a fresh clone in its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let mode = Arc::clone(&self.mode);
+        Box::pin(async move {
+            if mode.sentinel_file_exists().await {
+                super::drain_request_body(req).await;
+                Ok(mode.response())
+            } else {
+                inner.call(req).await
+            }
+        })
+    }
+}
+
+impl MaintenanceMode {
+    fn can_be_active(&self) -> bool {
+        self.enabled || self.sentinel_file.is_some()
+    }
+
+    fn is_allowed(&self, request: &Request) -> bool {
+        let path = request.uri().path();
+        let path_allowed = self.allowed_paths.iter().any(|allowed| {
+            path == allowed || (allowed.ends_with('/') && path.starts_with(allowed.as_str()))
+        });
+        if path_allowed {
+            return true;
+        }
+
+        request
+            .remote_addr()
+            .is_some_and(|ip| self.allowed_ips.iter().any(|network| network.contains(&ip)))
+    }
+
+    async fn sentinel_file_exists(&self) -> bool {
+        match &self.sentinel_file {
+            Some(path) => tokio::fs::try_exists(path).await.unwrap_or(false),
+            None => false,
+        }
+    }
+
+    fn response(&self) -> Response {
+        let mut response = Response::new(Body::fixed(self.html.clone())).no_cache();
+        *response.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
+        response.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("text/html; charset=utf-8"),
+        );
+        if let Some(retry_after) = self.retry_after {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, retry_after.as_secs().into());
+        }
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use tower::{Layer, ServiceExt};
+
+    use super::*;
+    use crate::config::{MaintenanceModeMiddlewareConfig, MiddlewareConfig, ProjectConfig};
+    use crate::test::TestRequestBuilder;
+    use crate::{Bootstrapper, Project};
+
+    async fn send(middleware: MaintenanceModeMiddleware, request: Request) -> Response {
+        let service = tower::service_fn(|_req: Request| async {
+            Ok::<_, Error>(Response::new(Body::fixed("OK")))
+        });
+
+        middleware.layer(service).oneshot(request).await.unwrap()
+    }
+
+    #[cot::test]
+    async fn maintenance_mode_disabled() {
+        let request = TestRequestBuilder::get("/").build();
+
+        let response = send(MaintenanceModeMiddleware::new(), request).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[cot::test]
+    async fn maintenance_mode_enabled() {
+        let middleware = MaintenanceModeMiddleware::new()
+            .enabled(true)
+            .retry_after(Duration::from_mins(5))
+            .html("<h1>Be right back</h1>");
+        let request = TestRequestBuilder::get("/").build();
+
+        let response = send(middleware, request).await;
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers().get(header::RETRY_AFTER).unwrap(), "300");
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/html; charset=utf-8"
+        );
+        assert_eq!(
+            response.into_body().into_bytes().await.unwrap(),
+            "<h1>Be right back</h1>"
+        );
+    }
+
+    #[cot::test]
+    async fn maintenance_mode_drains_request_body() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        use futures_util::StreamExt;
+
+        let exhausted = Arc::new(AtomicBool::new(false));
+        let exhausted_clone = Arc::clone(&exhausted);
+        let chunks = futures_util::stream::iter(["first", "second"]).chain(
+            futures_util::stream::once(async move {
+                exhausted_clone.store(true, Ordering::SeqCst);
+                "last"
+            }),
+        );
+        let mut request = TestRequestBuilder::post("/").build();
+        *request.body_mut() = Body::from_stream(chunks);
+
+        let response = send(MaintenanceModeMiddleware::new().enabled(true), request).await;
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert!(exhausted.load(Ordering::SeqCst));
+    }
+
+    #[cot::test]
+    async fn maintenance_mode_allowed_paths() {
+        let middleware = MaintenanceModeMiddleware::new()
+            .enabled(true)
+            .allow_path("/health")
+            .allow_path("/static/");
+
+        for (path, status) in [
+            ("/health", StatusCode::OK),
+            ("/static/style.css", StatusCode::OK),
+            ("/health/db", StatusCode::SERVICE_UNAVAILABLE),
+            ("/staticfile", StatusCode::SERVICE_UNAVAILABLE),
+        ] {
+            let request = TestRequestBuilder::get(path).build();
+            let response = send(middleware.clone(), request).await;
+            assert_eq!(response.status(), status, "path: {path}");
+        }
+    }
+
+    #[cot::test]
+    async fn maintenance_mode_allowed_ips() {
+        let middleware = MaintenanceModeMiddleware::new()
+            .enabled(true)
+            .allow_ip("10.0.0.0/8".parse().unwrap());
+
+        for (addr, status) in [
+            ("10.1.2.3:1234", StatusCode::OK),
+            ("192.168.0.1:1234", StatusCode::SERVICE_UNAVAILABLE),
+        ] {
+            let request = TestRequestBuilder::get("/")
+                .remote_addr(addr.parse::<SocketAddr>().unwrap())
+                .build();
+            let response = send(middleware.clone(), request).await;
+            assert_eq!(response.status(), status, "address: {addr}");
+        }
+    }
+
+    #[cot::test]
+    async fn maintenance_mode_sentinel_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let sentinel_file = temp_dir.path().join("maintenance");
+        let middleware = MaintenanceModeMiddleware::new().sentinel_file(&sentinel_file);
+
+        let request = TestRequestBuilder::get("/").build();
+        let response = send(middleware.clone(), request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        std::fs::write(&sentinel_file, "").unwrap();
+        let request = TestRequestBuilder::get("/").build();
+        let response = send(middleware.clone(), request).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        std::fs::remove_file(&sentinel_file).unwrap();
+        let request = TestRequestBuilder::get("/").build();
+        let response = send(middleware, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[cot::test]
+    async fn maintenance_mode_from_context() {
+        struct TestProject;
+        impl Project for TestProject {}
+
+        let config = ProjectConfig::builder()
+            .middlewares(
+                MiddlewareConfig::builder()
+                    .maintenance_mode(
+                        MaintenanceModeMiddlewareConfig::builder()
+                            .enabled(true)
+                            .retry_after(Duration::from_mins(1))
+                            .allowed_paths(vec!["/health".to_owned()])
+                            .build(),
+                    )
+                    .build(),
+            )
+            .build();
+        let bootstrapper = Bootstrapper::new(TestProject)
+            .with_config(config)
+            .with_apps()
+            .unwrap()
+            .with_database()
+            .await
+            .unwrap()
+            .with_cache()
+            .await
+            .unwrap();
+        let middleware = MaintenanceModeMiddleware::from_context(bootstrapper.context());
+
+        let request = TestRequestBuilder::get("/").build();
+        let response = send(middleware.clone(), request).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers().get(header::RETRY_AFTER).unwrap(), "60");
+
+        let request = TestRequestBuilder::get("/health").build();
+        let response = send(middleware, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,278 @@
+use std::task::{Context, Poll};
+
+use cot_core::headers::URLENCODED_FORM_CONTENT_TYPE;
+use futures_core::future::BoxFuture;
+use http::{HeaderName, Method, header};
+use tower::Service;
+
+use crate::request::Request;
+use crate::response::Response;
+use crate::{Body, Error};
+
+/// The name of the form field containing the overridden HTTP method.
+pub const METHOD_OVERRIDE_FIELD: &str = "_method";
+
+/// The name of the header containing the overridden HTTP method.
+pub const METHOD_OVERRIDE_HEADER: HeaderName = HeaderName::from_static("x-http-method-override");
+
+/// A middleware that allows HTML forms to send requests with HTTP methods other
+/// than `GET` and `POST`.
+///
+/// HTML forms can only be submitted using the `GET` and `POST` methods. This
+/// middleware allows a `POST` request to specify the method that should be
+/// used instead, so that it gets routed to the handler for that method. The
+/// method is read from the `X-HTTP-Method-Override` header or, if the header
+/// is not present, from the `_method` field of a form encoded as
+/// `application/x-www-form-urlencoded`:
+///
+/// ```html
+/// <form action="/todos/1" method="post">
+///     <input type="hidden" name="_method" value="DELETE">
+///     <button type="submit">Remove</button>
+/// </form>
+/// ```
+///
+/// Only `PUT`, `PATCH` and `DELETE` can be used as the overridden method, and
+/// the override is only honored for `POST` requests; for any other request
+/// the header and the form field are ignored. This makes sure that a plain
+/// link or an embedded resource (which always cause `GET` requests) can never
+/// trigger a state-changing handler, so that the cross-site request forgery
+/// protections applied to `POST` requests also cover the overridden methods.
+///
+/// Note that reading the `_method` field requires the whole request body to be
+/// read into memory; the body is then passed unchanged to the request handler.
+/// Multipart forms are not inspected, so they need to use the header instead.
+///
+/// Since the method needs to be overridden before the request is routed, the
+/// middleware has to be applied to the whole project in
+/// [`Project::middlewares`](crate::project::Project::middlewares).
+///
+/// # Examples
+///
+/// ```
+/// use cot::Project;
+/// use cot::middleware::MethodOverrideMiddleware;
+/// use cot::project::{MiddlewareContext, RootHandler, RootHandlerBuilder};
+///
+/// struct MyProject;
+/// impl Project for MyProject {
+///     fn middlewares(
+///         &self,
+///         handler: RootHandlerBuilder,
+///         context: &MiddlewareContext,
+///     ) -> RootHandler {
+///         handler.middleware(MethodOverrideMiddleware::new()).build()
+///     }
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, Default)]
+pub struct MethodOverrideMiddleware;
+
+impl MethodOverrideMiddleware {
+    /// Creates a new [`MethodOverrideMiddleware`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::middleware::MethodOverrideMiddleware;
+    ///
+    /// let middleware = MethodOverrideMiddleware::new();
+    /// ```
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> tower::Layer<S> for MethodOverrideMiddleware {
+    type Service = MethodOverrideService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        MethodOverrideService { inner }
+    }
+}
+
+/// Service that overrides the HTTP method of `POST` requests.
+///
+/// Used by [`MethodOverrideMiddleware`].
+#[derive(Debug, Clone)]
+pub struct MethodOverrideService<S> {
+    inner: S,
+}
+
+impl<S> Service<Request> for MethodOverrideService<S>
+where
+    S: Service<Request, Response = Response, Error = Error> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request) -> Self::Future {
+        if req.method() != Method::POST {
+            return Box::pin(self.inner.call(req));
+        }
+
+        if let Some(method) = req
+            .headers()
+            .get(METHOD_OVERRIDE_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_method)
+        {
+            *req.method_mut() = method;
+            return Box::pin(self.inner.call(req));
+        }
+
+        if !is_urlencoded_form(&req) {
+            return Box::pin(self.inner.call(req));
+        }
+
+        // the inner service has been polled ready, so we need to use it for this
+        // request and leave a fresh clone in its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        Box::pin(async move {
+            let body = std::mem::take(req.body_mut()).into_bytes().await?;
+            let method = form_urlencoded::parse(&body)
+                .find(|(key, _)| key == METHOD_OVERRIDE_FIELD)
+                .and_then(|(_, value)| parse_method(&value));
+            if let Some(method) = method {
+                *req.method_mut() = method;
+            }
+            *req.body_mut() = Body::fixed(body);
+
+            inner.call(req).await
+        })
+    }
+}
+
+fn is_urlencoded_form(request: &Request) -> bool {
+    request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .is_some_and(|value| value.trim() == URLENCODED_FORM_CONTENT_TYPE)
+}
+
+fn parse_method(value: &str) -> Option<Method> {
+    let value = value.trim();
+    [Method::PUT, Method::PATCH, Method::DELETE]
+        .into_iter()
+        .find(|method| method.as_str().eq_ignore_ascii_case(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{HeaderValue, StatusCode};
+    use tower::{Layer, ServiceExt};
+
+    use super::*;
+    use crate::form::{Form, FormResult};
+    use crate::html::Html;
+    use crate::request::extractors::RequestForm;
+    use crate::router::method::{delete, post};
+    use crate::router::{Route, Router};
+    use crate::test::TestRequestBuilder;
+
+    #[derive(Debug, Form)]
+    struct TodoForm {
+        title: String,
+    }
+
+    async fn method_handler(method: Method) -> Html {
+        Html::new(method.as_str())
+    }
+
+    async fn remove_todo(RequestForm(form): RequestForm<TodoForm>) -> Html {
+        match form {
+            FormResult::Ok(form) => Html::new(form.title),
+            FormResult::ValidationError(_) => Html::new("invalid form"),
+        }
+    }
+
+    async fn route(request: Request) -> crate::Result<Response> {
+        let router = Router::with_urls([
+            Route::with_handler("/", post(method_handler).delete(method_handler)),
+            Route::with_handler("/todos/remove", delete(remove_todo)),
+        ]);
+        let service = tower::service_fn(move |request: Request| {
+            let router = router.clone();
+            async move { router.handle(request).await }
+        });
+
+        MethodOverrideMiddleware::new()
+            .layer(service)
+            .oneshot(request)
+            .await
+    }
+
+    async fn route_body(request: Request) -> String {
+        let response = route(request).await.unwrap();
+        let body = response.into_body().into_bytes().await.unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[cot::test]
+    async fn method_override_form_field() {
+        let request = TestRequestBuilder::post("/")
+            .form_data(&[("_method", "DELETE")])
+            .build();
+
+        assert_eq!(route_body(request).await, "DELETE");
+    }
+
+    #[cot::test]
+    async fn method_override_form_field_keeps_body() {
+        let request = TestRequestBuilder::post("/todos/remove")
+            .form_data(&[("_method", "delete"), ("title", "Buy milk")])
+            .build();
+
+        assert_eq!(route_body(request).await, "Buy milk");
+    }
+
+    #[cot::test]
+    async fn method_override_header() {
+        let mut request = TestRequestBuilder::post("/").build();
+        request
+            .headers_mut()
+            .insert(METHOD_OVERRIDE_HEADER, HeaderValue::from_static("DELETE"));
+
+        assert_eq!(route_body(request).await, "DELETE");
+    }
+
+    #[cot::test]
+    async fn method_override_not_requested() {
+        let request = TestRequestBuilder::post("/")
+            .form_data(&[("title", "Buy milk")])
+            .build();
+
+        assert_eq!(route_body(request).await, "POST");
+    }
+
+    #[cot::test]
+    async fn method_override_disallowed_method() {
+        let request = TestRequestBuilder::post("/")
+            .form_data(&[("_method", "GET")])
+            .build();
+
+        assert_eq!(route_body(request).await, "POST");
+    }
+
+    #[cot::test]
+    async fn method_override_ignored_on_get() {
+        let mut request = TestRequestBuilder::get("/?_method=DELETE").build();
+        request
+            .headers_mut()
+            .insert(METHOD_OVERRIDE_HEADER, HeaderValue::from_static("DELETE"));
+
+        let error = route(request).await.unwrap_err();
+
+        assert_eq!(error.status_code(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+}
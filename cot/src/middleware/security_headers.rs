@@ -0,0 +1,464 @@
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures_core::future::BoxFuture;
+use http::{HeaderName, HeaderValue, header};
+use tower::Service;
+
+use crate::Error;
+use crate::request::{PeerAddr, Request};
+use crate::response::Response;
+
+const DEFAULT_CONTENT_TYPE_OPTIONS: &str = "nosniff";
+const DEFAULT_FRAME_OPTIONS: &str = "DENY";
+const DEFAULT_REFERRER_POLICY: &str = "strict-origin-when-cross-origin";
+const DEFAULT_STRICT_TRANSPORT_SECURITY: &str = "max-age=31536000; includeSubDomains";
+
+/// A middleware that adds common security-related headers to responses.
+///
+/// By default, the following headers are set:
+///
+/// * `X-Content-Type-Options: nosniff`
+/// * `X-Frame-Options: DENY`
+/// * `Referrer-Policy: strict-origin-when-cross-origin`
+/// * `Strict-Transport-Security: max-age=31536000; includeSubDomains` (only for
+///   requests received over HTTPS)
+///
+/// `Content-Security-Policy` is not set by default, since a useful policy
+/// depends heavily on the application; use
+/// [`content_security_policy`](Self::content_security_policy) to enable it.
+///
+/// Each header can be overridden or disabled (by passing [`None`]) using the
+/// builder methods. Headers that have already been set by the request handler
+/// are left untouched, so it's possible to override any of these for a
+/// specific response.
+///
+/// A request is considered to be received over HTTPS if its URI has the
+/// `https` scheme, or if it was forwarded by one of the
+/// [trusted proxies](crate::config::ProjectConfig::trusted_proxies) with the
+/// `X-Forwarded-Proto: https` header.
+///
+/// # Examples
+///
+/// ```
+/// use cot::Project;
+/// use cot::middleware::SecurityHeadersMiddleware;
+/// use cot::project::{MiddlewareContext, RootHandler, RootHandlerBuilder};
+///
+/// struct MyProject;
+/// impl Project for MyProject {
+///     fn middlewares(
+///         &self,
+///         handler: RootHandlerBuilder,
+///         context: &MiddlewareContext,
+///     ) -> RootHandler {
+///         handler
+///             .middleware(
+///                 SecurityHeadersMiddleware::new()
+///                     .frame_options(Some("SAMEORIGIN"))
+///                     .content_security_policy(Some("default-src 'self'")),
+///             )
+///             .build()
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SecurityHeadersMiddleware {
+    headers: Arc<SecurityHeaders>,
+}
+
+#[derive(Debug, Clone)]
+struct SecurityHeaders {
+    content_type_options: Option<HeaderValue>,
+    frame_options: Option<HeaderValue>,
+    referrer_policy: Option<HeaderValue>,
+    content_security_policy: Option<HeaderValue>,
+    strict_transport_security: Option<HeaderValue>,
+}
+
+impl SecurityHeadersMiddleware {
+    /// Creates a new [`SecurityHeadersMiddleware`] with the default headers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::middleware::SecurityHeadersMiddleware;
+    ///
+    /// let middleware = SecurityHeadersMiddleware::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            headers: Arc::new(SecurityHeaders {
+                content_type_options: Some(HeaderValue::from_static(DEFAULT_CONTENT_TYPE_OPTIONS)),
+                frame_options: Some(HeaderValue::from_static(DEFAULT_FRAME_OPTIONS)),
+                referrer_policy: Some(HeaderValue::from_static(DEFAULT_REFERRER_POLICY)),
+                content_security_policy: None,
+                strict_transport_security: Some(HeaderValue::from_static(
+                    DEFAULT_STRICT_TRANSPORT_SECURITY,
+                )),
+            }),
+        }
+    }
+
+    /// Sets the value of the `X-Content-Type-Options` header, or disables it
+    /// if [`None`] is passed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is not a valid header value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::middleware::SecurityHeadersMiddleware;
+    ///
+    /// let middleware = SecurityHeadersMiddleware::new().content_type_options(None);
+    /// ```
+    #[must_use]
+    pub fn content_type_options(mut self, value: Option<&str>) -> Self {
+        Arc::make_mut(&mut self.headers).content_type_options = to_header_value(value);
+        self
+    }
+
+    /// Sets the value of the `X-Frame-Options` header, or disables it if
+    /// [`None`] is passed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is not a valid header value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::middleware::SecurityHeadersMiddleware;
+    ///
+    /// let middleware = SecurityHeadersMiddleware::new().frame_options(Some("SAMEORIGIN"));
+    /// ```
+    #[must_use]
+    pub fn frame_options(mut self, value: Option<&str>) -> Self {
+        Arc::make_mut(&mut self.headers).frame_options = to_header_value(value);
+        self
+    }
+
+    /// Sets the value of the `Referrer-Policy` header, or disables it if
+    /// [`None`] is passed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is not a valid header value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::middleware::SecurityHeadersMiddleware;
+    ///
+    /// let middleware = SecurityHeadersMiddleware::new().referrer_policy(Some("no-referrer"));
+    /// ```
+    #[must_use]
+    pub fn referrer_policy(mut self, value: Option<&str>) -> Self {
+        Arc::make_mut(&mut self.headers).referrer_policy = to_header_value(value);
+        self
+    }
+
+    /// Sets the value of the `Content-Security-Policy` header, or disables it
+    /// if [`None`] is passed. The header is disabled by default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is not a valid header value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::middleware::SecurityHeadersMiddleware;
+    ///
+    /// let middleware = SecurityHeadersMiddleware::new()
+    ///     .content_security_policy(Some("default-src 'self'; img-src *"));
+    /// ```
+    #[must_use]
+    pub fn content_security_policy(mut self, value: Option<&str>) -> Self {
+        Arc::make_mut(&mut self.headers).content_security_policy = to_header_value(value);
+        self
+    }
+
+    /// Sets the value of the `Strict-Transport-Security` header, or disables
+    /// it if [`None`] is passed.
+    ///
+    /// Regardless of this setting, the header is only ever sent in responses
+    /// to requests received over HTTPS.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is not a valid header value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::middleware::SecurityHeadersMiddleware;
+    ///
+    /// let middleware = SecurityHeadersMiddleware::new()
+    ///     .strict_transport_security(Some("max-age=63072000; includeSubDomains; preload"));
+    /// ```
+    #[must_use]
+    pub fn strict_transport_security(mut self, value: Option<&str>) -> Self {
+        Arc::make_mut(&mut self.headers).strict_transport_security = to_header_value(value);
+        self
+    }
+}
+
+fn to_header_value(value: Option<&str>) -> Option<HeaderValue> {
+    value.map(|value| HeaderValue::from_str(value).expect("invalid security header value"))
+}
+
+impl Default for SecurityHeadersMiddleware {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> tower::Layer<S> for SecurityHeadersMiddleware {
+    type Service = SecurityHeadersService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        SecurityHeadersService {
+            inner,
+            headers: Arc::clone(&self.headers),
+        }
+    }
+}
+
+/// Service that adds security headers to the responses.
+///
+/// Used by [`SecurityHeadersMiddleware`].
+#[derive(Debug, Clone)]
+pub struct SecurityHeadersService<S> {
+    inner: S,
+    headers: Arc<SecurityHeaders>,
+}
+
+impl<S> Service<Request> for SecurityHeadersService<S>
+where
+    S: Service<Request, Response = Response, Error = Error> + Send,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let is_secure = is_secure(&req);
+        let headers = Arc::clone(&self.headers);
+        let future = self.inner.call(req);
+
+        Box::pin(async move {
+            let mut response = future.await?;
+
+            let security_headers = [
+                (
+                    header::X_CONTENT_TYPE_OPTIONS,
+                    &headers.content_type_options,
+                ),
+                (header::X_FRAME_OPTIONS, &headers.frame_options),
+                (header::REFERRER_POLICY, &headers.referrer_policy),
+                (
+                    header::CONTENT_SECURITY_POLICY,
+                    &headers.content_security_policy,
+                ),
+            ];
+            for (name, value) in security_headers {
+                set_header_if_missing(&mut response, name, value.as_ref());
+            }
+            if is_secure {
+                set_header_if_missing(
+                    &mut response,
+                    header::STRICT_TRANSPORT_SECURITY,
+                    headers.strict_transport_security.as_ref(),
+                );
+            }
+
+            Ok(response)
+        })
+    }
+}
+
+fn set_header_if_missing(response: &mut Response, name: HeaderName, value: Option<&HeaderValue>) {
+    if let Some(value) = value {
+        response
+            .headers_mut()
+            .entry(name)
+            .or_insert_with(|| value.clone());
+    }
+}
+
+fn is_secure(request: &Request) -> bool {
+    if request.uri().scheme() == Some(&http::uri::Scheme::HTTPS) {
+        return true;
+    }
+
+    let Some(PeerAddr(peer_addr)) = request.extensions().get::<PeerAddr>() else {
+        return false;
+    };
+    let is_trusted_proxy = request
+        .extensions()
+        .get::<Arc<crate::ProjectContext>>()
+        .is_some_and(|context| {
+            context
+                .config()
+                .trusted_proxies
+                .iter()
+                .any(|net| net.contains(&peer_addr.ip()))
+        });
+
+    is_trusted_proxy
+        && request
+            .headers()
+            .get("x-forwarded-proto")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(',').next())
+            .is_some_and(|proto| proto.trim().eq_ignore_ascii_case("https"))
+}
+
+#[cfg(test)]
+mod tests {
+    use tower::{Layer, ServiceExt};
+
+    use super::*;
+    use crate::Body;
+    use crate::config::ProjectConfig;
+    use crate::test::TestRequestBuilder;
+
+    async fn call_with_middleware(
+        middleware: SecurityHeadersMiddleware,
+        request: Request,
+    ) -> Response {
+        let svc = tower::service_fn(|_req: Request| async {
+            Ok::<_, Error>(Response::new(Body::empty()))
+        });
+
+        middleware.layer(svc).oneshot(request).await.unwrap()
+    }
+
+    #[cot::test]
+    async fn security_headers_defaults() {
+        let request = TestRequestBuilder::get("/").build();
+
+        let response = call_with_middleware(SecurityHeadersMiddleware::new(), request).await;
+
+        let headers = response.headers();
+        assert_eq!(headers[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+        assert_eq!(headers[header::X_FRAME_OPTIONS], "DENY");
+        assert_eq!(
+            headers[header::REFERRER_POLICY],
+            "strict-origin-when-cross-origin"
+        );
+        assert!(!headers.contains_key(header::CONTENT_SECURITY_POLICY));
+        assert!(!headers.contains_key(header::STRICT_TRANSPORT_SECURITY));
+    }
+
+    #[cot::test]
+    async fn security_headers_overridden() {
+        let request = TestRequestBuilder::get("/").build();
+        let middleware = SecurityHeadersMiddleware::new()
+            .frame_options(Some("SAMEORIGIN"))
+            .content_security_policy(Some("default-src 'self'"));
+
+        let response = call_with_middleware(middleware, request).await;
+
+        let headers = response.headers();
+        assert_eq!(headers[header::X_FRAME_OPTIONS], "SAMEORIGIN");
+        assert_eq!(
+            headers[header::CONTENT_SECURITY_POLICY],
+            "default-src 'self'"
+        );
+    }
+
+    #[cot::test]
+    async fn security_headers_disabled() {
+        let request = TestRequestBuilder::get("/").build();
+        let middleware = SecurityHeadersMiddleware::new().frame_options(None);
+
+        let response = call_with_middleware(middleware, request).await;
+
+        let headers = response.headers();
+        assert!(!headers.contains_key(header::X_FRAME_OPTIONS));
+        assert_eq!(headers[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+    }
+
+    #[cot::test]
+    async fn security_headers_keep_handler_headers() {
+        let svc = tower::service_fn(|_req: Request| async {
+            let mut response = Response::new(Body::empty());
+            response.headers_mut().insert(
+                header::X_FRAME_OPTIONS,
+                HeaderValue::from_static("SAMEORIGIN"),
+            );
+            Ok::<_, Error>(response)
+        });
+        let request = TestRequestBuilder::get("/").build();
+
+        let response = SecurityHeadersMiddleware::new()
+            .layer(svc)
+            .oneshot(request)
+            .await
+            .unwrap();
+
+        assert_eq!(response.headers()[header::X_FRAME_OPTIONS], "SAMEORIGIN");
+    }
+
+    #[cot::test]
+    async fn security_headers_hsts_over_https() {
+        let request = TestRequestBuilder::get("https://example.com/").build();
+
+        let response = call_with_middleware(SecurityHeadersMiddleware::new(), request).await;
+
+        assert_eq!(
+            response.headers()[header::STRICT_TRANSPORT_SECURITY],
+            "max-age=31536000; includeSubDomains"
+        );
+    }
+
+    #[cot::test]
+    async fn security_headers_hsts_forwarded_by_trusted_proxy() {
+        let config = ProjectConfig::builder()
+            .trusted_proxies(vec!["10.0.0.0/8".parse().unwrap()])
+            .build();
+        let mut request = TestRequestBuilder::get("/")
+            .config(config)
+            .remote_addr("10.0.0.1:12345".parse().unwrap())
+            .build();
+        request
+            .headers_mut()
+            .insert("x-forwarded-proto", HeaderValue::from_static("https"));
+
+        let response = call_with_middleware(SecurityHeadersMiddleware::new(), request).await;
+
+        assert!(
+            response
+                .headers()
+                .contains_key(header::STRICT_TRANSPORT_SECURITY)
+        );
+    }
+
+    #[cot::test]
+    async fn security_headers_hsts_forwarded_by_untrusted_source() {
+        let mut request = TestRequestBuilder::get("/")
+            .remote_addr("192.0.2.1:12345".parse().unwrap())
+            .build();
+        request
+            .headers_mut()
+            .insert("x-forwarded-proto", HeaderValue::from_static("https"));
+
+        let response = call_with_middleware(SecurityHeadersMiddleware::new(), request).await;
+
+        assert!(
+            !response
+                .headers()
+                .contains_key(header::STRICT_TRANSPORT_SECURITY)
+        );
+    }
+}
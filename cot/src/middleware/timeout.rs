@@ -0,0 +1,182 @@
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_core::future::BoxFuture;
+use tower::Service;
+
+use crate::Error;
+use crate::error::impl_into_cot_error;
+use crate::request::Request;
+use crate::response::Response;
+
+/// A middleware that limits the time a request handler can take to produce
+/// a response.
+///
+/// If the handler doesn't return a response within the timeout, it is
+/// cancelled (its future is dropped) and the request results in a
+/// `504 Gateway Timeout` error.
+///
+/// Note that the timeout only covers producing the response, i.e., the
+/// response status and headers. Once the handler returns a response, the
+/// response body is sent to the client regardless of the timeout, so a
+/// streaming response that has already started can't be timed out.
+///
+/// The middleware can be applied to all the requests using
+/// [`RootHandlerBuilder::middleware`](crate::project::RootHandlerBuilder::middleware),
+/// or to a group of routes using [`Router::middleware`](crate::router::Router::middleware).
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use cot::Project;
+/// use cot::middleware::TimeoutMiddleware;
+/// use cot::project::{MiddlewareContext, RootHandler, RootHandlerBuilder};
+///
+/// struct MyProject;
+/// impl Project for MyProject {
+///     fn middlewares(
+///         &self,
+///         handler: RootHandlerBuilder,
+///         context: &MiddlewareContext,
+///     ) -> RootHandler {
+///         handler
+///             .middleware(TimeoutMiddleware::new(Duration::from_secs(30)))
+///             .build()
+///     }
+/// }
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct TimeoutMiddleware {
+    timeout: Duration,
+}
+
+impl TimeoutMiddleware {
+    /// Creates a new [`TimeoutMiddleware`] with the given timeout.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::middleware::TimeoutMiddleware;
+    ///
+    /// let middleware = TimeoutMiddleware::new(Duration::from_secs(30));
+    /// ```
+    #[must_use]
+    pub const fn new(timeout: Duration) -> Self {
+        Self { timeout }
+    }
+}
+
+impl<S> tower::Layer<S> for TimeoutMiddleware {
+    type Service = TimeoutService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TimeoutService {
+            inner,
+            timeout: self.timeout,
+        }
+    }
+}
+
+/// Service that limits the time the inner service can take to respond.
+///
+/// Used by [`TimeoutMiddleware`].
+#[derive(Debug, Clone)]
+pub struct TimeoutService<S> {
+    inner: S,
+    timeout: Duration,
+}
+
+impl<S> Service<Request> for TimeoutService<S>
+where
+    S: Service<Request, Response = Response, Error = Error> + Send,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let timeout = self.timeout;
+        let future = self.inner.call(req);
+
+        Box::pin(async move {
+            match tokio::time::timeout(timeout, future).await {
+                Ok(result) => result,
+                Err(_elapsed) => Err(HandlerTimeout { timeout }.into()),
+            }
+        })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("request handler did not respond within {timeout:?}")]
+struct HandlerTimeout {
+    timeout: Duration,
+}
+impl_into_cot_error!(HandlerTimeout, GATEWAY_TIMEOUT);
+
+#[cfg(test)]
+mod tests {
+    use tower::{Layer, ServiceExt};
+
+    use super::*;
+    use crate::test::TestRequestBuilder;
+    use crate::{Body, StatusCode};
+
+    #[cot::test]
+    async fn timeout_not_exceeded() {
+        let svc = tower::service_fn(|_req: Request| async {
+            Ok::<_, Error>(Response::new(Body::fixed("OK")))
+        });
+        let request = TestRequestBuilder::get("/").build();
+
+        let response = TimeoutMiddleware::new(Duration::from_secs(10))
+            .layer(svc)
+            .oneshot(request)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[cot::test]
+    async fn timeout_exceeded() {
+        let svc = tower::service_fn(|_req: Request| async {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            Ok::<_, Error>(Response::new(Body::fixed("OK")))
+        });
+        let request = TestRequestBuilder::get("/").build();
+
+        let error = TimeoutMiddleware::new(Duration::from_millis(10))
+            .layer(svc)
+            .oneshot(request)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.status_code(), StatusCode::GATEWAY_TIMEOUT);
+    }
+
+    #[cot::test]
+    async fn timeout_handler_error_kept() {
+        let svc = tower::service_fn(|_req: Request| async {
+            Err::<Response, _>(Error::internal("handler error"))
+        });
+        let request = TestRequestBuilder::get("/").build();
+
+        let error = TimeoutMiddleware::new(Duration::from_secs(10))
+            .layer(svc)
+            .oneshot(request)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -0,0 +1,341 @@
+//! Exporting traces to an OpenTelemetry collector.
+//!
+//! When the [`opentelemetry`](crate::config::ProjectConfig::opentelemetry)
+//! section of the project config contains an endpoint, the spans created
+//! with [`tracing`] are exported to the collector using the OTLP protocol
+//! over HTTP. Each request is handled in a `request` span, which continues
+//! the trace referred to by the W3C `traceparent` header of the request, if
+//! there is one.
+//!
+//! When the project is started with [`run`](crate::run), the subscriber
+//! exporting the spans is installed once the config has been read. Use
+//! [`init_tracing`] to install it earlier.
+//!
+//! To make the downstream services a part of the same trace, pass the trace
+//! context on in the headers of the outgoing requests using
+//! [`inject_trace_context`].
+//!
+//! # Examples
+//!
+//! ```toml
+//! [opentelemetry]
+//! endpoint = "http://localhost:4318/v1/traces"
+//! service_name = "my-project"
+//! ```
+
+use std::sync::OnceLock;
+
+use cot_core::error::impl_into_cot_error;
+use http::{HeaderMap, HeaderName, HeaderValue};
+use opentelemetry::propagation::{Extractor, Injector, TextMapPropagator};
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use thiserror::Error;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::{Layer, Registry};
+
+use crate::config::{OpenTelemetryConfig, ProjectConfig};
+
+static TRACER_PROVIDER: OnceLock<SdkTracerProvider> = OnceLock::new();
+
+#[derive(Debug, Error)]
+#[error("could not create the OpenTelemetry span exporter: {0}")]
+struct ExporterBuildError(#[from] opentelemetry_otlp::ExporterBuildError);
+impl_into_cot_error!(ExporterBuildError);
+
+/// Installs the global `tracing` subscriber logging the events according to
+/// the [`logging`](ProjectConfig::logging) section of the project config and
+/// exporting the spans to the collector configured in its
+/// [`opentelemetry`](ProjectConfig::opentelemetry) section.
+///
+/// The subscriber is installed even if logging is not enabled in the project
+/// config. Call this before [`run`](crate::run) to have the spans created
+/// while the project starts exported as well; the project keeps the
+/// subscriber instead of installing its own. Does nothing if a global
+/// subscriber has already been set.
+///
+/// # Errors
+///
+/// Returns an error if the span exporter could not be created.
+///
+/// # Examples
+///
+/// ```no_run
+/// use cot::config::ProjectConfig;
+///
+/// # fn main() -> cot::Result<()> {
+/// let config = ProjectConfig::from_toml(
+///     r#"
+///     [opentelemetry]
+///     endpoint = "http://localhost:4318/v1/traces"
+///     service_name = "my-project"
+///     "#,
+/// )?;
+/// cot::opentelemetry::init_tracing(&config)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn init_tracing(config: &ProjectConfig) -> crate::Result<()> {
+    crate::logging::install(config)
+}
+
+/// Adds the trace context of the current span to the given headers, so that
+/// the service receiving a request with these headers can continue the
+/// trace.
+///
+/// This sets the W3C `traceparent` (and, if needed, `tracestate`) header. If
+/// there is no current span, or the spans are not exported, the headers are
+/// left untouched.
+///
+/// # Examples
+///
+/// ```
+/// use cot::opentelemetry::inject_trace_context;
+///
+/// let mut request = http::Request::get("http://example.com/api/items")
+///     .body(())
+///     .unwrap();
+/// inject_trace_context(request.headers_mut());
+/// ```
+pub fn inject_trace_context(headers: &mut HeaderMap) {
+    let context = tracing::Span::current().context();
+    TraceContextPropagator::new().inject_context(&context, &mut HeaderInjector(headers));
+}
+
+/// Makes the span a part of the trace referred to by the `traceparent`
+/// header, if the headers contain a valid one.
+pub(crate) fn set_parent_from_headers(span: &tracing::Span, headers: &HeaderMap) {
+    let parent = TraceContextPropagator::new().extract(&HeaderExtractor(headers));
+    if let Err(error) = span.set_parent(parent) {
+        tracing::debug!(%error, "could not set the parent of the request span");
+    }
+}
+
+/// Returns the layer exporting the spans to the collector configured in
+/// `config`, or `None` if no collector is configured.
+pub(crate) fn layer(
+    config: &OpenTelemetryConfig,
+) -> crate::Result<Option<Box<dyn Layer<Registry> + Send + Sync>>> {
+    let Some(endpoint) = &config.endpoint else {
+        return Ok(None);
+    };
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(endpoint)
+        .build()
+        .map_err(ExporterBuildError)?;
+    let mut resource = Resource::builder();
+    if let Some(service_name) = &config.service_name {
+        resource = resource.with_service_name(service_name.clone());
+    }
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(resource.build())
+        .build();
+    let tracer = provider.tracer("cot");
+    // keep the provider so that the remaining spans can be exported on shutdown
+    let _ = TRACER_PROVIDER.set(provider);
+
+    Ok(Some(Box::new(
+        tracing_opentelemetry::layer().with_tracer(tracer),
+    )))
+}
+
+/// Exports the remaining spans and shuts down the exporter.
+pub(crate) fn shutdown() {
+    if let Some(provider) = TRACER_PROVIDER.get()
+        && let Err(error) = provider.shutdown()
+    {
+        tracing::warn!(%error, "could not shut down the OpenTelemetry exporter");
+    }
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(HeaderName::as_str).collect()
+    }
+}
+
+struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+impl Injector for HeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(key.as_bytes()),
+            HeaderValue::from_str(&value),
+        ) {
+            self.0.insert(name, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry_sdk::trace::InMemorySpanExporter;
+    use tracing::Instrument;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+    use crate::router::{Route, Router};
+    use crate::test::TestRequestBuilder;
+
+    const TRACE_ID: &str = "0af7651916cd43dd8448eb211c80319c";
+    const PARENT_SPAN_ID: &str = "b7ad6b7169203331";
+
+    fn exporting_subscriber() -> (impl tracing::Subscriber, SdkTracerProvider, InMemorySpanExporter)
+    {
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+
+        (subscriber, provider, exporter)
+    }
+
+    #[test]
+    fn request_span_exported_with_incoming_parent() {
+        let (subscriber, provider, exporter) = exporting_subscriber();
+        let request = http::Request::get("/hello")
+            .header("traceparent", format!("00-{TRACE_ID}-{PARENT_SPAN_ID}-01"))
+            .body(())
+            .unwrap();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = crate::logging::request_span(&request);
+            let _enter = span.enter();
+            tracing::info!("handling the request");
+        });
+        provider.force_flush().unwrap();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].name, "request");
+        assert_eq!(spans[0].span_context.trace_id().to_string(), TRACE_ID);
+        assert_eq!(spans[0].parent_span_id.to_string(), PARENT_SPAN_ID);
+    }
+
+    #[test]
+    fn request_span_without_parent_starts_new_trace() {
+        let (subscriber, provider, exporter) = exporting_subscriber();
+        let request = http::Request::get("/hello").body(()).unwrap();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = crate::logging::request_span(&request);
+            let _enter = span.enter();
+        });
+        provider.force_flush().unwrap();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        assert_eq!(spans.len(), 1);
+        assert_ne!(spans[0].span_context.trace_id().to_string(), TRACE_ID);
+        assert_eq!(
+            spans[0].parent_span_id,
+            opentelemetry::trace::SpanId::INVALID
+        );
+    }
+
+    #[test]
+    fn request_span_is_server_span_with_request_id() {
+        let (subscriber, provider, exporter) = exporting_subscriber();
+        let request = http::Request::get("/hello")
+            .header("x-request-id", "req-123")
+            .body(())
+            .unwrap();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = crate::logging::request_span(&request);
+            let _enter = span.enter();
+        });
+        provider.force_flush().unwrap();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].span_kind, opentelemetry::trace::SpanKind::Server);
+        let request_id = spans[0]
+            .attributes
+            .iter()
+            .find(|attribute| attribute.key.as_str() == "request_id")
+            .unwrap();
+        assert_eq!(request_id.value.as_str(), "req-123");
+    }
+
+    #[cot::test]
+    async fn request_span_records_route() {
+        async fn user() -> crate::html::Html {
+            crate::html::Html::new("user")
+        }
+
+        let (subscriber, provider, exporter) = exporting_subscriber();
+        let router = Router::with_urls([Route::with_router(
+            "/users",
+            Router::with_urls([Route::with_handler("/{id}", user)]),
+        )]);
+        let request = TestRequestBuilder::get("/users/42").build();
+
+        {
+            let _guard = tracing::subscriber::set_default(subscriber);
+            let span = crate::logging::request_span(&request);
+            router.handle(request).instrument(span).await.unwrap();
+        }
+        provider.force_flush().unwrap();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        assert_eq!(spans.len(), 1);
+        let route = spans[0]
+            .attributes
+            .iter()
+            .find(|attribute| attribute.key.as_str() == "http.route")
+            .unwrap();
+        assert_eq!(route.value.as_str(), "/users/{id}");
+    }
+
+    #[test]
+    fn inject_trace_context_propagates_trace() {
+        let (subscriber, _provider, _exporter) = exporting_subscriber();
+        let request = http::Request::get("/hello")
+            .header("traceparent", format!("00-{TRACE_ID}-{PARENT_SPAN_ID}-01"))
+            .body(())
+            .unwrap();
+
+        let headers = tracing::subscriber::with_default(subscriber, || {
+            let span = crate::logging::request_span(&request);
+            let _enter = span.enter();
+
+            let mut headers = HeaderMap::new();
+            inject_trace_context(&mut headers);
+            headers
+        });
+
+        let traceparent = headers.get("traceparent").unwrap().to_str().unwrap();
+        assert!(traceparent.starts_with(&format!("00-{TRACE_ID}-")));
+        assert!(!traceparent.contains(PARENT_SPAN_ID));
+    }
+
+    #[test]
+    fn inject_trace_context_without_span() {
+        let mut headers = HeaderMap::new();
+
+        inject_trace_context(&mut headers);
+
+        assert!(headers.is_empty());
+    }
+
+    #[test]
+    fn layer_not_configured() {
+        let layer = layer(&OpenTelemetryConfig::default()).unwrap();
+
+        assert!(layer.is_none());
+    }
+}
@@ -10,6 +10,7 @@
 pub use aide::openapi::{Operation, RequestBody, Response as OpenApiResponse, StatusCode};
 pub use async_trait::async_trait;
 pub use bytes::Bytes;
+pub use chrono;
 pub use cot_macros::ModelHelper;
 pub use tokio;
 
@@ -27,3 +28,112 @@ pub use crate::utils::graph::apply_permutation;
 /// This is used in the CLI to specify the version of the crate to use in the
 /// `Cargo.toml` file when creating a new Cot project.
 pub const COT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Support code for the [`Form`](derive@crate::form::Form) derive macro.
+pub mod form {
+    use std::fmt::Formatter;
+
+    use crate::form::FormFieldValidationError;
+    use crate::html::HtmlTag;
+
+    /// Writes the validation errors as an HTML list with the given class, or
+    /// nothing if there are no errors.
+    ///
+    /// This is used when rendering the form contexts, so that the errors are
+    /// shown inline next to the fields they refer to.
+    pub fn write_errors(
+        f: &mut Formatter<'_>,
+        class: &str,
+        errors: &[FormFieldValidationError],
+    ) -> std::fmt::Result {
+        if errors.is_empty() {
+            return Ok(());
+        }
+
+        let mut list = HtmlTag::new("ul");
+        list.add_class(class);
+        for error in errors {
+            let mut item = HtmlTag::new("li");
+            item.push_str(error.to_string());
+            list.push_tag(item);
+        }
+
+        f.write_str(list.render().as_str())
+    }
+
+    /// Writes the opening `<fieldset>` tag of a field group, along with its
+    /// legend.
+    ///
+    /// The closing tag is written by the caller after rendering the fields in
+    /// the group.
+    pub fn write_fieldset_start(f: &mut Formatter<'_>, legend: &str) -> std::fmt::Result {
+        let mut legend_tag = HtmlTag::new("legend");
+        legend_tag.push_str(legend);
+
+        write!(f, "<fieldset>{}", legend_tag.render().as_str())
+    }
+}
+
+/// Support code for the [`html!`](crate::html!) macro.
+pub mod html {
+    use std::fmt::Display;
+
+    use askama::filters::Escaper;
+
+    use crate::html::{Html, HtmlTag};
+
+    /// Wraps a value interpolated in the [`html!`](crate::html!) macro.
+    ///
+    /// The macro calls `(&Interpolation(&value)).to_html()`; autoref-based
+    /// method resolution picks [`InterpolateRaw`] for HTML values and falls
+    /// back to [`InterpolateEscaped`] for anything implementing [`Display`].
+    #[derive(Debug)]
+    pub struct Interpolation<'a, T: ?Sized>(pub &'a T);
+
+    pub trait InterpolateRaw {
+        fn to_html(&self) -> Html;
+    }
+
+    impl InterpolateRaw for Interpolation<'_, Html> {
+        fn to_html(&self) -> Html {
+            self.0.clone()
+        }
+    }
+
+    impl InterpolateRaw for Interpolation<'_, &Html> {
+        fn to_html(&self) -> Html {
+            (*self.0).clone()
+        }
+    }
+
+    impl InterpolateRaw for Interpolation<'_, HtmlTag> {
+        fn to_html(&self) -> Html {
+            self.0.render()
+        }
+    }
+
+    impl InterpolateRaw for Interpolation<'_, &HtmlTag> {
+        fn to_html(&self) -> Html {
+            self.0.render()
+        }
+    }
+
+    pub trait InterpolateEscaped {
+        fn to_html(&self) -> Html;
+    }
+
+    impl<T: Display + ?Sized> InterpolateEscaped for &Interpolation<'_, T> {
+        fn to_html(&self) -> Html {
+            escape(&self.0.to_string())
+        }
+    }
+
+    #[must_use]
+    pub fn escape(text: &str) -> Html {
+        let mut result = String::new();
+        askama::filters::Html
+            .write_escaped_str(&mut result, text)
+            .expect("Failed to escape HTML text");
+        Html::new(result)
+    }
+}
@@ -20,7 +20,11 @@
 //!     MyProject
 //! }
 //! ```
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::convert::Infallible;
 use std::future::poll_fn;
+use std::net::SocketAddr;
 use std::panic::AssertUnwindSafe;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -33,10 +37,13 @@ use cot_core::handler::BoxedHandler;
 use cot_core::request::AppName;
 use derive_more::with_trait::Debug;
 use futures_util::FutureExt;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::graceful::GracefulShutdown;
+use hyper_util::service::TowerToHyperService;
 use thiserror::Error;
 use tower::util::BoxCloneSyncService;
-use tower::{Layer, Service};
-use tracing::{error, info, trace};
+use tower::{Layer, Service, ServiceExt};
+use tracing::{Instrument, error, info, trace};
 
 use crate::admin::AdminModelManager;
 #[cfg(feature = "db")]
@@ -47,13 +54,15 @@ use crate::cache::Cache;
 use crate::cli::Cli;
 #[cfg(feature = "cache")]
 use crate::config::CacheConfig;
-#[cfg(feature = "db")]
-use crate::config::DatabaseConfig;
 use crate::config::{AuthBackendConfig, ProjectConfig};
 #[cfg(feature = "db")]
+use crate::config::{DatabaseConfig, StartupMigrations};
+#[cfg(feature = "db")]
 use crate::db::Database;
+#[cfg(all(feature = "db", feature = "json"))]
+use crate::db::fixtures::FixtureModel;
 #[cfg(feature = "db")]
-use crate::db::migrations::{MigrationEngine, SyncDynMigration};
+use crate::db::migrations::{MigrationEngine, MigrationPlan, SyncDynMigration};
 #[cfg(feature = "email")]
 use crate::email::Email;
 use crate::error::UncaughtPanic;
@@ -61,11 +70,13 @@ use crate::error::handler::{DynErrorPageHandler, RequestOuterError};
 use crate::error_page::Diagnostics;
 use crate::html::Html;
 use crate::middleware::{IntoCotError, IntoCotErrorLayer, IntoCotResponse, IntoCotResponseLayer};
-use crate::request::{Request, RequestExt, RequestHead};
+use crate::request::{PeerAddr, Request, RequestExt, RequestHead};
 use crate::response::{IntoResponse, Response};
 use crate::router::{Route, Router, RouterService};
 use crate::static_files::StaticFile;
+use crate::tasks::{Task, TaskRunner};
 use crate::utils::accept_header_parser::AcceptHeaderParser;
+use crate::utils::graph::{Graph, apply_permutation};
 use crate::{Body, Error, cli, error_page};
 
 /// A building block for a Cot project.
@@ -102,6 +113,37 @@ pub trait App: Send + Sync {
     /// ```
     fn name(&self) -> &str;
 
+    /// Returns the names of the apps this app depends on. By default, it
+    /// returns an empty list.
+    ///
+    /// The apps are ordered so that each app comes after all of its
+    /// dependencies, regardless of the order they were registered in; this is
+    /// the order in which they are initialized and started (and the reverse
+    /// of the order in which they are shut down). Apps that don't depend on
+    /// each other keep their registration order. Bootstrapping the project
+    /// fails if a dependency is not registered, or if the dependencies form a
+    /// cycle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::App;
+    ///
+    /// struct BlogApp;
+    /// impl App for BlogApp {
+    ///     fn name(&self) -> &str {
+    ///         "blog"
+    ///     }
+    ///
+    ///     fn dependencies(&self) -> Vec<&'static str> {
+    ///         vec!["cot_auth"]
+    ///     }
+    /// }
+    /// ```
+    fn dependencies(&self) -> Vec<&'static str> {
+        vec![]
+    }
+
     /// Initializes the app.
     ///
     /// This method is called when the app is initialized. It can be used to
@@ -116,6 +158,97 @@ pub trait App: Send + Sync {
         Ok(())
     }
 
+    /// Called when the server is starting, after all the apps have been
+    /// initialized with [`init`](Self::init), but before any request is
+    /// handled.
+    ///
+    /// This can be used to prepare the resources that need to be ready when
+    /// the app starts serving requests, such as warming up the caches or
+    /// starting background tasks.
+    ///
+    /// The apps are started in the order they were registered in, with each
+    /// app started after its [dependencies](Self::dependencies). If this
+    /// method returns an error, the server is not started, and the apps that
+    /// have already been started are shut down with
+    /// [`on_shutdown`](Self::on_shutdown).
+    ///
+    /// Note that this is only called when running the server (e.g., with
+    /// [`run`] or [`run_at`]), not when using the test
+    /// [`Client`](crate::test::Client).
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the app fails to start.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use async_trait::async_trait;
+    /// use cot::App;
+    /// use cot::project::ProjectContext;
+    ///
+    /// struct MyApp;
+    ///
+    /// #[async_trait]
+    /// impl App for MyApp {
+    ///     fn name(&self) -> &str {
+    ///         "my_app"
+    ///     }
+    ///
+    ///     async fn on_startup(&self, context: &ProjectContext) -> cot::Result<()> {
+    ///         println!("Starting {}", self.name());
+    ///         Ok(())
+    ///     }
+    /// }
+    /// ```
+    #[expect(unused_variables)]
+    async fn on_startup(&self, context: &ProjectContext) -> crate::Result<()> {
+        Ok(())
+    }
+
+    /// Called when the server has shut down, after all the connections have
+    /// been closed.
+    ///
+    /// This can be used to clean up the resources prepared in
+    /// [`on_startup`](Self::on_startup), such as stopping background tasks or
+    /// flushing buffers.
+    ///
+    /// The apps are shut down in the reverse order they were started in, so
+    /// that an app can still use its dependencies. An error
+    /// returned by one app doesn't prevent the other apps from being shut
+    /// down; the first error is returned from the function running the
+    /// server.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the app fails to shut down cleanly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use async_trait::async_trait;
+    /// use cot::App;
+    /// use cot::project::ProjectContext;
+    ///
+    /// struct MyApp;
+    ///
+    /// #[async_trait]
+    /// impl App for MyApp {
+    ///     fn name(&self) -> &str {
+    ///         "my_app"
+    ///     }
+    ///
+    ///     async fn on_shutdown(&self, context: &ProjectContext) -> cot::Result<()> {
+    ///         println!("Shutting down {}", self.name());
+    ///         Ok(())
+    ///     }
+    /// }
+    /// ```
+    #[expect(unused_variables)]
+    async fn on_shutdown(&self, context: &ProjectContext) -> crate::Result<()> {
+        Ok(())
+    }
+
     /// Returns the router for the app. By default, it returns an empty router.
     ///
     /// # Examples
@@ -151,6 +284,42 @@ pub trait App: Send + Sync {
         vec![]
     }
 
+    /// Returns the models that can be loaded from fixtures with the `db seed`
+    /// CLI command. By default, it returns an empty list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::App;
+    /// use cot::db::fixtures::{DefaultFixtureModel, FixtureModel};
+    /// use cot::db::{Auto, model};
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// #[model]
+    /// struct Author {
+    ///     #[model(primary_key)]
+    ///     #[serde(default)]
+    ///     id: Auto<i32>,
+    ///     name: String,
+    /// }
+    ///
+    /// struct MyApp;
+    /// impl App for MyApp {
+    ///     fn name(&self) -> &str {
+    ///         "my_app"
+    ///     }
+    ///
+    ///     fn fixture_models(&self) -> Vec<Box<dyn FixtureModel>> {
+    ///         vec![Box::new(DefaultFixtureModel::<Author>::new())]
+    ///     }
+    /// }
+    /// ```
+    #[cfg(all(feature = "db", feature = "json"))]
+    fn fixture_models(&self) -> Vec<Box<dyn FixtureModel>> {
+        vec![]
+    }
+
     /// Returns the admin model managers for the app. By default, it returns an
     /// empty list.
     fn admin_model_managers(&self) -> Vec<Box<dyn AdminModelManager>> {
@@ -162,6 +331,47 @@ pub trait App: Send + Sync {
     fn static_files(&self) -> Vec<StaticFile> {
         vec![]
     }
+
+    /// Returns the background tasks that the app runs on a schedule. By
+    /// default, it returns an empty list.
+    ///
+    /// The tasks are started when the server starts, after
+    /// [`App::on_startup`] is called for all the apps, and are stopped when
+    /// the server shuts down gracefully, before [`App::on_shutdown`] is
+    /// called. See the [`tasks`](crate::tasks) module for more information.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// use cot::App;
+    /// use cot::project::ProjectContext;
+    /// use cot::tasks::{Schedule, Task};
+    ///
+    /// async fn cleanup(context: Arc<ProjectContext>) -> cot::Result<()> {
+    ///     Ok(())
+    /// }
+    ///
+    /// struct MyApp;
+    /// impl App for MyApp {
+    ///     fn name(&self) -> &str {
+    ///         "my_app"
+    ///     }
+    ///
+    ///     fn tasks(&self) -> Vec<Task> {
+    ///         vec![Task::new(
+    ///             "cleanup",
+    ///             Schedule::interval(Duration::from_secs(60)),
+    ///             cleanup,
+    ///         )]
+    ///     }
+    /// }
+    /// ```
+    fn tasks(&self) -> Vec<Task> {
+        vec![]
+    }
 }
 
 /// The main trait for a Cot project.
@@ -725,6 +935,7 @@ pub struct AppBuilder {
     #[debug("..")]
     apps: Vec<Box<dyn App>>,
     urls: Vec<Route>,
+    state: StateRegistry,
 }
 
 impl AppBuilder {
@@ -732,6 +943,7 @@ impl AppBuilder {
         Self {
             apps: Vec::new(),
             urls: Vec::new(),
+            state: StateRegistry::default(),
         }
     }
 
@@ -798,6 +1010,143 @@ impl AppBuilder {
         self.urls.push(Route::with_router(url_prefix, router));
         self.register(app);
     }
+
+    /// Registers a value shared by the whole project, such as an HTTP client
+    /// or a feature flag service.
+    ///
+    /// There can be one value of each type. The value can then be retrieved
+    /// in the request handlers using
+    /// [`RequestExt::state`](crate::request::RequestExt::state), or anywhere
+    /// the project context is available using [`ProjectContext::state`].
+    /// Since the value is shared between all the requests, use types that are
+    /// safe to use concurrently, such as atomics, or wrap it in a mutex.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a value of the same type has already been registered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::atomic::AtomicUsize;
+    ///
+    /// use cot::Project;
+    /// use cot::project::{AppBuilder, RegisterAppsContext};
+    ///
+    /// struct MyProject;
+    /// impl Project for MyProject {
+    ///     fn register_apps(&self, apps: &mut AppBuilder, _context: &RegisterAppsContext) {
+    ///         apps.register_state(AtomicUsize::new(0));
+    ///     }
+    /// }
+    /// ```
+    #[track_caller]
+    pub fn register_state<T: Send + Sync + 'static>(&mut self, state: T) {
+        self.state.insert(state);
+    }
+
+    /// Sorts the registered apps so that each app comes after its
+    /// dependencies, keeping the registration order otherwise.
+    fn sort_apps(&mut self) -> Result<(), AppDependencyError> {
+        let mut lookup = HashMap::with_capacity(self.apps.len());
+        for (index, app) in self.apps.iter().enumerate() {
+            lookup.entry(app.name()).or_insert(index);
+        }
+
+        let mut graph = Graph::new(self.apps.len());
+        for (index, app) in self.apps.iter().enumerate() {
+            for dependency in app.dependencies() {
+                let dependency_index = lookup.get(dependency).ok_or_else(|| {
+                    AppDependencyError::MissingDependency {
+                        app: app.name().to_owned(),
+                        dependency: dependency.to_owned(),
+                    }
+                })?;
+                graph.add_edge(*dependency_index, index);
+            }
+        }
+
+        let mut sorted_indices = graph.toposort().map_err(|error| {
+            // the edges go from the dependencies to the dependents, so reverse
+            // the cycle to list each app before its dependency
+            let cycle = error
+                .cycle()
+                .iter()
+                .rev()
+                .map(|&index| self.apps[index].name().to_owned())
+                .collect();
+            AppDependencyError::CycleDetected { cycle }
+        })?;
+        apply_permutation(&mut self.apps, &mut sorted_indices);
+
+        Ok(())
+    }
+}
+
+/// An error that occurs when the dependencies between the registered apps
+/// (declared with [`App::dependencies`]) can't be satisfied.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[non_exhaustive]
+pub enum AppDependencyError {
+    /// An app depends on an app that has not been registered.
+    #[error(
+        "app `{app}` depends on app `{dependency}`, which is not registered; \
+        register it with `AppBuilder::register` before bootstrapping the project"
+    )]
+    MissingDependency {
+        /// The name of the app declaring the dependency.
+        app: String,
+        /// The name of the missing app.
+        dependency: String,
+    },
+    /// The dependencies between the apps form a cycle.
+    #[error(
+        "cycle detected in app dependencies: {} (each app depends on the next one)",
+        format_app_cycle(.cycle)
+    )]
+    CycleDetected {
+        /// The names of the apps forming the cycle.
+        cycle: Vec<String>,
+    },
+}
+impl_into_cot_error!(AppDependencyError);
+
+fn format_app_cycle(cycle: &[String]) -> String {
+    let mut formatted = cycle.join(" -> ");
+    if let Some(first) = cycle.first() {
+        formatted.push_str(" -> ");
+        formatted.push_str(first);
+    }
+    formatted
+}
+
+/// A registry of values shared by the whole project, keyed by their type.
+///
+/// The values are registered using [`AppBuilder::register_state`] and can be
+/// retrieved using [`ProjectContext::state`] or
+/// [`RequestExt::state`](crate::request::RequestExt::state).
+#[derive(Debug, Default)]
+pub struct StateRegistry {
+    #[debug("..")]
+    values: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl StateRegistry {
+    #[track_caller]
+    fn insert<T: Send + Sync + 'static>(&mut self, value: T) {
+        let previous = self.values.insert(TypeId::of::<T>(), Box::new(value));
+        assert!(
+            previous.is_none(),
+            "State of type `{}` is already registered",
+            std::any::type_name::<T>()
+        );
+    }
+
+    fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.values
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref())
+    }
 }
 
 async fn default_error_handler(error: RequestOuterError) -> crate::Result<impl IntoResponse> {
@@ -941,8 +1290,11 @@ impl Bootstrapper<Uninitialized> {
 
         let common_options = cli.common_options();
         let self_with_context = self.with_config_name(common_options.config())?;
+        crate::logging::init(self_with_context.context().config())?;
 
-        cli.execute(self_with_context).await
+        let result = cli.execute(self_with_context).await;
+        crate::logging::shutdown();
+        result
     }
 
     /// Reads the configuration of the project and moves to the next
@@ -1086,7 +1438,7 @@ impl Bootstrapper<WithConfig> {
     /// # }
     /// ```
     pub async fn boot(self) -> cot::Result<Bootstrapper<Initialized>> {
-        self.with_apps().boot().await
+        self.with_apps()?.boot().await
     }
 
     /// Moves forward to the next phase of bootstrapping, the with-apps phase.
@@ -1094,6 +1446,12 @@ impl Bootstrapper<WithConfig> {
     /// See the [`BootstrapPhase`] and [`WithApps`] documentation for more
     /// details.
     ///
+    /// # Errors
+    ///
+    /// This method returns an error if the
+    /// [dependencies](App::dependencies) of the registered apps are not
+    /// registered, or if they form a cycle.
+    ///
     /// # Examples
     ///
     /// ```
@@ -1108,28 +1466,30 @@ impl Bootstrapper<WithConfig> {
     /// # async fn main() -> cot::Result<()> {
     /// let bootstrapper = Bootstrapper::new(MyProject)
     ///     .with_config(ProjectConfig::default())
-    ///     .with_apps()
+    ///     .with_apps()?
     ///     .boot()
     ///     .await?;
     /// # Ok(())
     /// # }
     /// ```
-    #[must_use]
-    pub fn with_apps(self) -> Bootstrapper<WithApps> {
+    pub fn with_apps(self) -> cot::Result<Bootstrapper<WithApps>> {
         let mut module_builder = AppBuilder::new();
         self.project
             .register_apps(&mut module_builder, &self.context);
+        module_builder.sort_apps()?;
 
         let router = Arc::new(Router::with_urls(module_builder.urls));
 
-        let context = self.context.with_apps(module_builder.apps, router);
+        let context = self
+            .context
+            .with_apps(module_builder.apps, router, module_builder.state);
 
-        Bootstrapper {
+        Ok(Bootstrapper {
             project: self.project,
             context,
             handler: self.handler,
             error_handler: self.error_handler,
-        }
+        })
     }
 }
 
@@ -1162,7 +1522,7 @@ impl Bootstrapper<WithApps> {
     /// # async fn main() -> cot::Result<()> {
     /// let bootstrapper = Bootstrapper::new(MyProject)
     ///     .with_config(ProjectConfig::default())
-    ///     .with_apps()
+    ///     .with_apps()?
     ///     .boot()
     ///     .await?;
     /// let bootstrapped_project = bootstrapper.finish();
@@ -1197,7 +1557,7 @@ impl Bootstrapper<WithApps> {
     /// # async fn main() -> cot::Result<()> {
     /// let bootstrapper = Bootstrapper::new(MyProject)
     ///     .with_config(ProjectConfig::default())
-    ///     .with_apps()
+    ///     .with_apps()?
     ///     .with_database()
     ///     .await?
     ///     .boot()
@@ -1207,7 +1567,7 @@ impl Bootstrapper<WithApps> {
     /// ```
     pub async fn with_database(self) -> cot::Result<Bootstrapper<WithDatabase>> {
         #[cfg(feature = "db")]
-        let database = Self::init_database(&self.context.config.database).await?;
+        let database = Self::init_database(&self.context.config).await?;
         let context = self.context.with_database(
             #[cfg(feature = "db")]
             database,
@@ -1222,14 +1582,22 @@ impl Bootstrapper<WithApps> {
     }
 
     #[cfg(feature = "db")]
-    async fn init_database(config: &DatabaseConfig) -> cot::Result<Option<Database>> {
-        match &config.url {
-            Some(url) => {
-                let database = Database::new(url.as_str()).await?;
-                Ok(Some(database))
-            }
-            None => Ok(None),
+    async fn init_database(config: &ProjectConfig) -> cot::Result<Option<Database>> {
+        if config.database.url.is_none() {
+            return Ok(None);
         }
+
+        let database = if config.debug || config.database.slow_query_threshold.is_none() {
+            Database::from_config(&config.database).await?
+        } else {
+            // slow query warnings are only meant to be used during development
+            let database_config = DatabaseConfig {
+                slow_query_threshold: None,
+                ..config.database.clone()
+            };
+            Database::from_config(&database_config).await?
+        };
+        Ok(Some(database))
     }
 }
 
@@ -1296,7 +1664,7 @@ impl Bootstrapper<WithDatabase> {
     /// # async fn main() -> cot::Result<()> {
     /// let bootstrapper = Bootstrapper::new(MyProject)
     ///     .with_config(ProjectConfig::default())
-    ///     .with_apps()
+    ///     .with_apps()?
     ///     .with_database()
     ///     .await?
     ///     .with_cache()
@@ -1532,6 +1900,8 @@ pub trait BootstrapPhase: sealed::Sealed {
     type Apps;
     /// The type of the router.
     type Router: Debug;
+    /// The type of the registry of the shared state.
+    type State: Debug;
     /// The type of the database.
     #[cfg(feature = "db")]
     type Database: Debug;
@@ -1560,6 +1930,7 @@ impl BootstrapPhase for Uninitialized {
     type Email = ();
     type Apps = ();
     type Router = ();
+    type State = ();
     #[cfg(feature = "db")]
     type Database = ();
     type AuthBackend = ();
@@ -1585,6 +1956,7 @@ impl BootstrapPhase for WithConfig {
     type Email = Email;
     type Apps = ();
     type Router = ();
+    type State = ();
     #[cfg(feature = "db")]
     type Database = ();
     type AuthBackend = ();
@@ -1610,6 +1982,7 @@ impl BootstrapPhase for WithApps {
     type Email = <WithConfig as BootstrapPhase>::Email;
     type Apps = Vec<Box<dyn App>>;
     type Router = Arc<Router>;
+    type State = StateRegistry;
     #[cfg(feature = "db")]
     type Database = ();
     type AuthBackend = ();
@@ -1635,6 +2008,7 @@ impl BootstrapPhase for WithDatabase {
     type Email = <WithApps as BootstrapPhase>::Email;
     type Apps = <WithApps as BootstrapPhase>::Apps;
     type Router = <WithApps as BootstrapPhase>::Router;
+    type State = <WithApps as BootstrapPhase>::State;
     #[cfg(feature = "db")]
     type Database = Option<Database>;
     type AuthBackend = <WithApps as BootstrapPhase>::AuthBackend;
@@ -1660,6 +2034,7 @@ impl BootstrapPhase for WithCache {
     type Email = <WithApps as BootstrapPhase>::Email;
     type Apps = <WithApps as BootstrapPhase>::Apps;
     type Router = <WithApps as BootstrapPhase>::Router;
+    type State = <WithApps as BootstrapPhase>::State;
     #[cfg(feature = "db")]
     type Database = <WithDatabase as BootstrapPhase>::Database;
     type AuthBackend = <WithApps as BootstrapPhase>::AuthBackend;
@@ -1685,6 +2060,7 @@ impl BootstrapPhase for Initialized {
     type Email = <WithDatabase as BootstrapPhase>::Email;
     type Apps = <WithDatabase as BootstrapPhase>::Apps;
     type Router = <WithDatabase as BootstrapPhase>::Router;
+    type State = <WithDatabase as BootstrapPhase>::State;
     #[cfg(feature = "db")]
     type Database = <WithDatabase as BootstrapPhase>::Database;
     type AuthBackend = Arc<dyn AuthBackend>;
@@ -1700,6 +2076,7 @@ pub struct ProjectContext<S: BootstrapPhase = Initialized> {
     #[debug("..")]
     apps: S::Apps,
     router: S::Router,
+    state: S::State,
     #[cfg(feature = "db")]
     database: S::Database,
     #[debug("..")]
@@ -1717,6 +2094,7 @@ impl ProjectContext<Uninitialized> {
             config: (),
             apps: (),
             router: (),
+            state: (),
             #[cfg(feature = "db")]
             database: (),
             auth_backend: (),
@@ -1739,6 +2117,7 @@ impl ProjectContext<Uninitialized> {
             config: Arc::new(config),
             apps: self.apps,
             router: self.router,
+            state: self.state,
             #[cfg(feature = "db")]
             database: self.database,
             auth_backend: self.auth_backend,
@@ -1778,11 +2157,17 @@ impl<S: BootstrapPhase<Config = Arc<ProjectConfig>>> ProjectContext<S> {
 
 impl ProjectContext<WithConfig> {
     #[must_use]
-    fn with_apps(self, apps: Vec<Box<dyn App>>, router: Arc<Router>) -> ProjectContext<WithApps> {
+    fn with_apps(
+        self,
+        apps: Vec<Box<dyn App>>,
+        router: Arc<Router>,
+        state: StateRegistry,
+    ) -> ProjectContext<WithApps> {
         ProjectContext {
             config: self.config,
             apps,
             router,
+            state,
             #[cfg(feature = "db")]
             database: self.database,
             auth_backend: self.auth_backend,
@@ -1826,6 +2211,7 @@ impl ProjectContext<WithApps> {
             config: self.config,
             apps: self.apps,
             router: self.router,
+            state: self.state,
             #[cfg(feature = "db")]
             database,
             auth_backend: self.auth_backend,
@@ -1844,6 +2230,7 @@ impl ProjectContext<WithDatabase> {
             config: self.config,
             apps: self.apps,
             router: self.router,
+            state: self.state,
             auth_backend: self.auth_backend,
             #[cfg(feature = "db")]
             database: self.database,
@@ -1862,6 +2249,7 @@ impl ProjectContext<WithCache> {
             config: self.config,
             apps: self.apps,
             router: self.router,
+            state: self.state,
             auth_backend,
             #[cfg(feature = "db")]
             database: self.database,
@@ -1878,6 +2266,7 @@ impl ProjectContext<Initialized> {
         config: <Initialized as BootstrapPhase>::Config,
         apps: <Initialized as BootstrapPhase>::Apps,
         router: <Initialized as BootstrapPhase>::Router,
+        state: <Initialized as BootstrapPhase>::State,
         auth_backend: <Initialized as BootstrapPhase>::AuthBackend,
         #[cfg(feature = "db")] database: <Initialized as BootstrapPhase>::Database,
         #[cfg(feature = "cache")] cache: <Initialized as BootstrapPhase>::Cache,
@@ -1887,6 +2276,7 @@ impl ProjectContext<Initialized> {
             config,
             apps,
             router,
+            state,
             #[cfg(feature = "db")]
             database,
             auth_backend,
@@ -1923,6 +2313,66 @@ impl<S: BootstrapPhase<Router = Arc<Router>>> ProjectContext<S> {
         &self.router
     }
 }
+impl<S: BootstrapPhase<State = StateRegistry>> ProjectContext<S> {
+    /// Returns the value of type `T` registered with
+    /// [`AppBuilder::register_state`].
+    ///
+    /// # Panics
+    ///
+    /// This method panics if no value of type `T` has been registered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    ///
+    /// use cot::request::{Request, RequestExt};
+    /// use cot::response::Response;
+    ///
+    /// async fn index(request: Request) -> cot::Result<Response> {
+    ///     let counter = request.context().state::<AtomicUsize>();
+    ///     counter.fetch_add(1, Ordering::Relaxed);
+    ///     // ...
+    /// #    unimplemented!()
+    /// }
+    /// ```
+    #[must_use]
+    #[track_caller]
+    pub fn state<T: Send + Sync + 'static>(&self) -> &T {
+        self.try_state().unwrap_or_else(|| {
+            panic!(
+                "State of type `{}` missing. Did you forget to register it with \
+                 `AppBuilder::register_state`?",
+                std::any::type_name::<T>()
+            )
+        })
+    }
+
+    /// Returns the value of type `T` registered with
+    /// [`AppBuilder::register_state`], or [`None`] if there isn't one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    ///
+    /// use cot::request::{Request, RequestExt};
+    /// use cot::response::Response;
+    ///
+    /// async fn index(request: Request) -> cot::Result<Response> {
+    ///     if let Some(counter) = request.context().try_state::<AtomicUsize>() {
+    ///         counter.fetch_add(1, Ordering::Relaxed);
+    ///     }
+    ///     // ...
+    /// #    unimplemented!()
+    /// }
+    /// ```
+    #[must_use]
+    pub fn try_state<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.state.get()
+    }
+}
+
 impl<S: BootstrapPhase<AuthBackend = Arc<dyn AuthBackend>>> ProjectContext<S> {
     /// Returns the authentication backend for the project.
     ///
@@ -2043,6 +2493,56 @@ impl<S: BootstrapPhase<Database = Option<Database>>> ProjectContext<S> {
     }
 }
 
+#[cfg(feature = "db")]
+fn migration_engine(
+    apps: &[Box<dyn App>],
+    config: &DatabaseConfig,
+) -> cot::Result<MigrationEngine> {
+    let mut migrations: Vec<Box<SyncDynMigration>> = Vec::new();
+    for app in apps {
+        migrations.extend(app.migrations());
+    }
+    Ok(MigrationEngine::new(migrations)?.with_drift_policy(config.migration_drift))
+}
+
+/// Applies the migrations of all the given apps to the database.
+#[cfg(feature = "db")]
+pub(crate) async fn run_migrations(
+    apps: &[Box<dyn App>],
+    database: &Database,
+    config: &DatabaseConfig,
+) -> cot::Result<()> {
+    migration_engine(apps, config)?.run(database).await?;
+
+    Ok(())
+}
+
+/// Verifies that the migrations of all the given apps have been applied to the
+/// database.
+#[cfg(feature = "db")]
+pub(crate) async fn check_migrations(
+    apps: &[Box<dyn App>],
+    database: &Database,
+    config: &DatabaseConfig,
+) -> cot::Result<()> {
+    migration_engine(apps, config)?.check(database).await?;
+
+    Ok(())
+}
+
+/// Returns the plans of the migrations of all the given apps that have not
+/// been applied to the database yet, without applying them.
+#[cfg(feature = "db")]
+pub(crate) async fn plan_migrations(
+    apps: &[Box<dyn App>],
+    database: &Database,
+    config: &DatabaseConfig,
+) -> cot::Result<Vec<MigrationPlan>> {
+    let plans = migration_engine(apps, config)?.plan(database).await?;
+
+    Ok(plans)
+}
+
 /// Runs the Cot project on the given address.
 ///
 /// This function takes a Cot project and an address string and runs the
@@ -2105,12 +2605,14 @@ pub async fn run_at_with_shutdown(
 
     #[cfg(feature = "db")]
     if let Some(database) = &context.database {
-        let mut migrations: Vec<Box<SyncDynMigration>> = Vec::new();
-        for app in &context.apps {
-            migrations.extend(app.migrations());
+        let config = &context.config().database;
+        if config.ping_on_startup {
+            database.ping().await?;
+        }
+        match config.startup_migrations {
+            StartupMigrations::Apply => run_migrations(&context.apps, database, config).await?,
+            StartupMigrations::Check => check_migrations(&context.apps, database, config).await?,
         }
-        let migration_engine = MigrationEngine::new(migrations)?;
-        migration_engine.run(database).await?;
     }
 
     let mut apps = std::mem::take(&mut context.apps);
@@ -2121,54 +2623,62 @@ pub async fn run_at_with_shutdown(
     }
     context.apps = apps;
 
+    start_apps(&context).await?;
+
     let context = Arc::new(context);
     let is_debug = context.config().debug;
     let register_panic_hook = context.config().register_panic_hook;
-    #[cfg(feature = "db")]
-    let context_cleanup = context.clone();
-
-    let handler = move |axum_request: axum::extract::Request| async move {
-        // todo root tracing span
-        // todo per-router error handlers
-        let request = request_axum_to_cot(axum_request, Arc::clone(&context));
-        let (head, request) = request.into_parts();
-        let head_for_error_handler = head.clone();
-        let request = Request::from_parts(head, request);
-
-        let (request_head, request) = request_parts_for_diagnostics(request);
-
-        let catch_unwind_response = AssertUnwindSafe(pass_to_axum(request, &mut handler))
-            .catch_unwind()
-            .await;
-
-        let response: Result<axum::response::Response, ErrorResponse> = match catch_unwind_response
-        {
-            Ok(Ok(response)) => Ok(response),
-            Ok(Err(error)) => Err(ErrorResponse::ErrorReturned(error)),
-            Err(error) => Err(ErrorResponse::Panic(error)),
-        };
-
-        match response {
-            Ok(response) => response,
-            Err(error_response) => {
-                if is_debug && accepts_html(request_head.as_ref()) {
-                    let diagnostics = Diagnostics::new(
-                        context.config().clone(),
-                        Arc::clone(&context.router),
-                        request_head,
-                    );
-
-                    build_cot_error_page(error_response, &diagnostics)
-                } else {
-                    build_custom_error_page(
-                        &mut error_handler,
-                        error_response,
-                        head_for_error_handler,
-                    )
-                    .await
+    let http2 = context.config().http2;
+    let context_cleanup = Arc::clone(&context);
+
+    let handler = move |axum_request: axum::extract::Request| {
+        let span = crate::logging::request_span(&axum_request);
+        async move {
+            // todo per-router error handlers
+            let request = request_axum_to_cot(axum_request, Arc::clone(&context));
+            let (head, request) = request.into_parts();
+            let head_for_error_handler = head.clone();
+            let request = Request::from_parts(head, request);
+
+            let (request_head, request) = request_parts_for_diagnostics(request);
+
+            let catch_unwind_response = AssertUnwindSafe(pass_to_axum(request, &mut handler))
+                .catch_unwind()
+                .await;
+
+            let response: Result<axum::response::Response, ErrorResponse> =
+                match catch_unwind_response {
+                    Ok(Ok(response)) => Ok(response),
+                    Ok(Err(error)) => Err(ErrorResponse::ErrorReturned(error)),
+                    Err(error) => Err(ErrorResponse::Panic(error)),
+                };
+
+            let response = match response {
+                Ok(response) => response,
+                Err(error_response) => {
+                    if is_debug && accepts_html(request_head.as_ref()) {
+                        let diagnostics = Diagnostics::new(
+                            context.config().clone(),
+                            Arc::clone(&context.router),
+                            request_head,
+                        );
+
+                        build_cot_error_page(error_response, &diagnostics)
+                    } else {
+                        build_custom_error_page(
+                            &mut error_handler,
+                            error_response,
+                            head_for_error_handler,
+                        )
+                        .await
+                    }
                 }
-            }
+            };
+            tracing::Span::current().record("status", response.status().as_u16());
+
+            response
         }
+        .instrument(span)
     };
 
     eprintln!(
@@ -2184,21 +2694,142 @@ pub async fn run_at_with_shutdown(
         };
         std::panic::set_hook(Box::new(new_hook));
     }
-    axum::serve(listener, handler.into_make_service())
-        .with_graceful_shutdown(shutdown_signal)
-        .await
-        .map_err(StartServerError)?;
+    let task_runner = TaskRunner::start(&context_cleanup);
+    serve(listener, handler.into_service(), http2, shutdown_signal).await;
     if register_panic_hook {
         let _ = std::panic::take_hook();
     }
+    task_runner.shutdown().await;
+    let shutdown_result = stop_apps(context_cleanup.apps(), &context_cleanup).await;
     #[cfg(feature = "db")]
     if let Some(database) = &context_cleanup.database {
         database.close().await?;
     }
 
+    shutdown_result
+}
+
+/// Calls [`App::on_startup`] for all the apps, in the registration order.
+///
+/// If one of the apps fails to start, the apps started before it are shut
+/// down.
+async fn start_apps(context: &ProjectContext) -> cot::Result<()> {
+    let apps = context.apps();
+    for (index, app) in apps.iter().enumerate() {
+        info!("Starting app: {}", app.name());
+
+        if let Err(error) = app.on_startup(context).await {
+            error!(app = app.name(), %error, "Failed to start the app");
+            // the errors are already logged; the startup error is more relevant
+            let _ = stop_apps(&apps[..index], context).await;
+            return Err(error);
+        }
+    }
+
     Ok(())
 }
 
+/// Calls [`App::on_shutdown`] for the given apps, in the reverse order.
+///
+/// All the apps are shut down even if some of them fail; the first error is
+/// returned.
+async fn stop_apps(apps: &[Box<dyn App>], context: &ProjectContext) -> cot::Result<()> {
+    let mut result = Ok(());
+    for app in apps.iter().rev() {
+        info!("Shutting down app: {}", app.name());
+
+        if let Err(error) = app.on_shutdown(context).await {
+            error!(app = app.name(), %error, "Failed to shut down the app");
+            if result.is_ok() {
+                result = Err(error);
+            }
+        }
+    }
+
+    result
+}
+
+/// Accepts the connections on the listener and serves them until the shutdown
+/// signal is received, then waits for the open connections to finish.
+///
+/// This is similar to [`axum::serve`], but allows to configure the supported
+/// HTTP versions.
+async fn serve<S>(
+    listener: tokio::net::TcpListener,
+    service: S,
+    http2: bool,
+    shutdown_signal: impl Future<Output = ()> + Send + 'static,
+) where
+    S: Service<axum::extract::Request, Response = axum::response::Response, Error = Infallible>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send,
+{
+    let mut builder = hyper_util::server::conn::auto::Builder::new(TokioExecutor::new());
+    if !http2 {
+        builder = builder.http1_only();
+    }
+    let graceful = GracefulShutdown::new();
+    let mut shutdown_signal = std::pin::pin!(shutdown_signal);
+
+    loop {
+        let (stream, remote_addr) = tokio::select! {
+            result = listener.accept() => match result {
+                Ok(connection) => connection,
+                Err(error) => {
+                    handle_accept_error(error).await;
+                    continue;
+                }
+            },
+            () = &mut shutdown_signal => break,
+        };
+        trace!(%remote_addr, "Accepted a connection");
+
+        let service =
+            service
+                .clone()
+                .map_request(move |request: http::Request<hyper::body::Incoming>| {
+                    let mut request = request.map(axum::body::Body::new);
+                    request
+                        .extensions_mut()
+                        .insert(axum::extract::ConnectInfo(remote_addr));
+                    request
+                });
+        let connection = builder
+            .serve_connection_with_upgrades(TokioIo::new(stream), TowerToHyperService::new(service))
+            .into_owned();
+        let connection = graceful.watch(connection);
+
+        tokio::spawn(async move {
+            if let Err(error) = connection.await {
+                trace!(%remote_addr, %error, "Connection error");
+            }
+        });
+    }
+
+    drop(listener);
+    graceful.shutdown().await;
+}
+
+async fn handle_accept_error(error: std::io::Error) {
+    // errors related to a single connection; the next one can be accepted
+    // right away
+    if matches!(
+        error.kind(),
+        std::io::ErrorKind::ConnectionRefused
+            | std::io::ErrorKind::ConnectionAborted
+            | std::io::ErrorKind::ConnectionReset
+    ) {
+        return;
+    }
+
+    // other errors, such as hitting the open files limit, are likely to happen
+    // again if we try to accept a connection immediately
+    error!(%error, "Failed to accept a connection");
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+}
+
 #[derive(Debug, Error)]
 #[error("failed to start the server: {0}")]
 pub(crate) struct StartServerError(#[from] pub(crate) std::io::Error);
@@ -2328,6 +2959,12 @@ fn request_axum_to_cot(
     context: Arc<ProjectContext>,
 ) -> Request {
     let mut request = axum_request.map(Body::axum);
+    if let Some(axum::extract::ConnectInfo(addr)) = request
+        .extensions_mut()
+        .remove::<axum::extract::ConnectInfo<SocketAddr>>(
+    ) {
+        request.extensions_mut().insert(PeerAddr(addr));
+    }
     prepare_request(&mut request, context);
     request
 }
@@ -2399,6 +3036,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn state_registry_get() {
+        let mut state = StateRegistry::default();
+        state.insert(42_u32);
+        state.insert(String::from("hello"));
+
+        assert_eq!(state.get::<u32>(), Some(&42));
+        assert_eq!(state.get::<String>().map(String::as_str), Some("hello"));
+        assert_eq!(state.get::<u64>(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "State of type `u32` is already registered")]
+    fn state_registry_insert_twice() {
+        let mut app_builder = AppBuilder::new();
+        app_builder.register_state(1_u32);
+        app_builder.register_state(2_u32);
+    }
+
+    #[test]
+    #[should_panic(expected = "State of type `u32` missing")]
+    fn project_context_state_missing() {
+        let context = ProjectContext::new()
+            .with_config(ProjectConfig::default())
+            .with_apps(vec![], Arc::new(Router::empty()), StateRegistry::default());
+
+        let _ = context.state::<u32>();
+    }
+
     #[cot::test]
     async fn app_default_impl() {
         let app = TestApp {};
@@ -2563,6 +3229,118 @@ mod tests {
         assert!(apps.apps.is_empty());
     }
 
+    struct DependentApp {
+        name: &'static str,
+        dependencies: Vec<&'static str>,
+    }
+
+    impl DependentApp {
+        fn new(name: &'static str, dependencies: Vec<&'static str>) -> Self {
+            Self { name, dependencies }
+        }
+    }
+
+    impl App for DependentApp {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn dependencies(&self) -> Vec<&'static str> {
+            self.dependencies.clone()
+        }
+    }
+
+    fn app_names(apps: &AppBuilder) -> Vec<&str> {
+        apps.apps.iter().map(|app| app.name()).collect()
+    }
+
+    #[test]
+    fn app_builder_sort_apps_keeps_registration_order() {
+        let mut apps = AppBuilder::new();
+        apps.register(DependentApp::new("a", vec![]));
+        apps.register(DependentApp::new("b", vec![]));
+        apps.register(DependentApp::new("c", vec!["a"]));
+
+        apps.sort_apps().unwrap();
+
+        assert_eq!(app_names(&apps), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn app_builder_sort_apps_dependencies_first() {
+        let mut apps = AppBuilder::new();
+        apps.register(DependentApp::new("blog", vec!["auth", "sessions"]));
+        apps.register(DependentApp::new("auth", vec!["sessions"]));
+        apps.register(DependentApp::new("sessions", vec![]));
+
+        apps.sort_apps().unwrap();
+
+        assert_eq!(app_names(&apps), vec!["sessions", "auth", "blog"]);
+    }
+
+    #[test]
+    fn app_builder_sort_apps_missing_dependency() {
+        let mut apps = AppBuilder::new();
+        apps.register(DependentApp::new("blog", vec!["auth"]));
+
+        let error = apps.sort_apps().unwrap_err();
+
+        assert_eq!(
+            error,
+            AppDependencyError::MissingDependency {
+                app: "blog".to_owned(),
+                dependency: "auth".to_owned(),
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "app `blog` depends on app `auth`, which is not registered; \
+            register it with `AppBuilder::register` before bootstrapping the project"
+        );
+    }
+
+    #[test]
+    fn app_builder_sort_apps_cycle() {
+        let mut apps = AppBuilder::new();
+        apps.register(DependentApp::new("a", vec!["b"]));
+        apps.register(DependentApp::new("b", vec!["a"]));
+
+        let error = apps.sort_apps().unwrap_err();
+
+        let AppDependencyError::CycleDetected { cycle } = &error else {
+            panic!("expected a cycle error, got {error:?}");
+        };
+        assert_eq!(cycle.len(), 2);
+        assert!(
+            error
+                .to_string()
+                .starts_with("cycle detected in app dependencies: ")
+        );
+    }
+
+    #[test]
+    fn bootstrapper_with_apps_missing_dependency() {
+        struct DependentProject;
+        impl Project for DependentProject {
+            fn register_apps(&self, apps: &mut AppBuilder, _context: &RegisterAppsContext) {
+                apps.register(DependentApp::new("blog", vec!["auth"]));
+            }
+        }
+
+        let result = Bootstrapper::new(DependentProject)
+            .with_config(ProjectConfig::default())
+            .with_apps();
+
+        let Err(error) = result else {
+            panic!("expected bootstrapping to fail");
+        };
+        assert!(
+            error
+                .to_string()
+                .contains("app `blog` depends on app `auth`, which is not registered")
+        );
+    }
+
     #[cot::test]
     async fn default_auth_backend() {
         let cache_memory = Cache::new(
@@ -2577,7 +3355,7 @@ mod tests {
                     .auth_backend(AuthBackendConfig::None)
                     .build(),
             )
-            .with_apps(vec![], Arc::new(Router::empty()))
+            .with_apps(vec![], Arc::new(Router::empty()), StateRegistry::default())
             .with_database(None)
             .with_cache(cache_memory);
 
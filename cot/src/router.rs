@@ -20,7 +20,8 @@
 //! )]);
 //! ```
 
-use std::collections::HashMap;
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Formatter;
 use std::future::Future;
 use std::pin::Pin;
@@ -28,17 +29,26 @@ use std::sync::Arc;
 use std::task::{Context, Poll};
 
 use cot_core::error::impl_into_cot_error;
-use cot_core::handler::{BoxRequestHandler, RequestHandler, into_box_request_handler};
+use cot_core::handler::{
+    BoxRequestHandler, BoxedHandler, RequestHandler, into_box_request_handler,
+};
 use cot_core::request::{AppName, RouteName};
 use derive_more::with_trait::Debug;
+use tower::util::BoxCloneSyncService;
+use tower::{Layer, Service, ServiceExt};
 use tracing::debug;
 
 use crate::error::NotFound;
 use crate::request::{PathParams, Request, RequestExt, RequestHead};
 use crate::response::Response;
+use crate::router::host::HostMatcher;
+use crate::router::method::MethodRouter;
+#[cfg(feature = "openapi")]
+use crate::router::method::openapi::ApiMethodRouter;
 use crate::router::path::{CaptureResult, PathMatcher, ReverseParamMap};
-use crate::{Error, ProjectContext, Result};
+use crate::{Error, Method, ProjectContext, Result};
 
+mod host;
 pub mod method;
 pub mod path;
 
@@ -66,8 +76,14 @@ pub struct Router {
     app_name: Option<AppName>,
     urls: Vec<Route>,
     names: HashMap<RouteName, Arc<PathMatcher>>,
+    #[debug("[{} middlewares]", middlewares.len())]
+    middlewares: Vec<RouterMiddleware>,
 }
 
+/// A middleware applied to the handlers of a [`Router`], type-erased as a
+/// function wrapping a handler.
+type RouterMiddleware = Arc<dyn Fn(BoxedHandler) -> BoxedHandler + Send + Sync>;
+
 impl Router {
     /// Create an empty router.
     ///
@@ -100,14 +116,46 @@ impl Router {
     ///
     /// let router = Router::with_urls([Route::with_handler_and_name("/", home, "home")]);
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if two routes have the same name. This includes the routes in
+    /// the nested routers, unless they belong to an app (in which case the
+    /// route names are namespaced by the app name).
+    ///
+    /// ```should_panic
+    /// use cot::request::Request;
+    /// use cot::response::Response;
+    /// use cot::router::{Route, Router};
+    ///
+    /// async fn home(request: Request) -> cot::Result<Response> {
+    ///     unimplemented!()
+    /// }
+    ///
+    /// let router = Router::with_urls([
+    ///     Route::with_handler_and_name("/", home, "home"),
+    ///     Route::with_handler_and_name("/home", home, "home"),
+    /// ]);
+    /// ```
     #[must_use]
     pub fn with_urls<T: Into<Vec<Route>>>(urls: T) -> Self {
         let urls = urls.into();
         let mut names = HashMap::new();
+        let mut namespace_names = HashSet::new();
 
         for url in &urls {
             if let Some(name) = &url.name {
                 names.insert(name.clone(), url.url.clone());
+                check_route_name_unique(&mut namespace_names, name);
+            }
+            if let RouteInner::Router(router) = &url.view
+                && router.app_name.is_none()
+            {
+                let mut nested_names = Vec::new();
+                router.collect_namespace_names(&mut nested_names);
+                for name in nested_names {
+                    check_route_name_unique(&mut namespace_names, name);
+                }
             }
         }
 
@@ -115,6 +163,150 @@ impl Router {
             app_name: None,
             urls,
             names,
+            middlewares: Vec::new(),
+        }
+    }
+
+    /// Adds a middleware to all the routes of this router.
+    ///
+    /// Unlike the middlewares added with
+    /// [`RootHandlerBuilder::middleware`](crate::project::RootHandlerBuilder::middleware),
+    /// which are applied to all the requests, this only applies to the
+    /// requests handled by the routes in this router (including the nested
+    /// routers). This makes it possible to apply a middleware to a group of
+    /// routes by nesting a router with [`Route::with_router`].
+    ///
+    /// The middleware has to be compatible with Cot, i.e., the service it
+    /// produces has to take a [`Request`] and return a [`Response`] or an
+    /// [`Error`]. Middlewares added later wrap the ones added earlier.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::middleware::TimeoutMiddleware;
+    /// use cot::request::Request;
+    /// use cot::response::Response;
+    /// use cot::router::{Route, Router};
+    ///
+    /// async fn report(request: Request) -> cot::Result<Response> {
+    ///     unimplemented!()
+    /// }
+    ///
+    /// let reports = Router::with_urls([Route::with_handler("/report", report)])
+    ///     .middleware(TimeoutMiddleware::new(Duration::from_secs(60)));
+    /// let router = Router::with_urls([Route::with_router("/reports", reports)]);
+    /// ```
+    #[must_use]
+    pub fn middleware<M>(mut self, middleware: M) -> Self
+    where
+        M: Layer<BoxedHandler> + Send + Sync + 'static,
+        M::Service:
+            Service<Request, Response = Response, Error = Error> + Clone + Send + Sync + 'static,
+        <M::Service as Service<Request>>::Future: Send + 'static,
+    {
+        self.middlewares.push(Arc::new(move |handler| {
+            BoxCloneSyncService::new(middleware.layer(handler))
+        }));
+        self
+    }
+
+    /// Collects the names of the routes that are reversed in the same
+    /// namespace as this router, i.e., the names of this router's routes and
+    /// the ones in the nested routers that don't belong to an app.
+    fn collect_namespace_names<'a>(&'a self, names: &mut Vec<&'a RouteName>) {
+        names.extend(self.names.keys());
+        for route in &self.urls {
+            if let RouteInner::Router(router) = &route.view
+                && router.app_name.is_none()
+            {
+                router.collect_namespace_names(names);
+            }
+        }
+    }
+
+    /// Returns an iterator over all the routes in this router, including the
+    /// ones in the nested routers.
+    ///
+    /// Unlike [`Self::routes`], which only returns the routes added directly
+    /// to this router, this walks the nested routers recursively and yields a
+    /// [`RouteInfo`] for each route that ends up in a handler, with the full
+    /// path (including the prefixes of the routers it's nested in), the name
+    /// and the HTTP methods of the route. The routes are yielded in the order
+    /// they are matched in.
+    ///
+    /// This is useful for listing the routes of a project, or for generating
+    /// sitemaps and other documents describing the available URLs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::Method;
+    /// use cot::html::Html;
+    /// use cot::router::method::post;
+    /// use cot::router::{Route, Router};
+    ///
+    /// async fn list_users() -> Html {
+    ///     unimplemented!()
+    /// }
+    ///
+    /// async fn create_user() -> Html {
+    ///     unimplemented!()
+    /// }
+    ///
+    /// let users = Router::with_urls([
+    ///     Route::with_handler_and_name("/", list_users, "list"),
+    ///     Route::with_handler_and_name("/new", post(create_user), "create"),
+    /// ]);
+    /// let router = Router::with_urls([Route::with_router("/users", users)]);
+    ///
+    /// let routes: Vec<_> = router.iter().collect();
+    /// assert_eq!(routes[0].path(), "/users/");
+    /// assert_eq!(routes[0].name(), Some("list"));
+    /// assert_eq!(routes[1].path(), "/users/new");
+    /// assert_eq!(routes[1].methods(), [Method::POST]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = RouteInfo> {
+        let mut infos = Vec::new();
+        self.collect_route_infos("", None, None, &mut infos);
+        infos.into_iter()
+    }
+
+    fn collect_route_infos(
+        &self,
+        prefix: &str,
+        host: Option<&str>,
+        app_name: Option<&AppName>,
+        infos: &mut Vec<RouteInfo>,
+    ) {
+        let app_name = self.app_name.as_ref().or(app_name);
+
+        for route in &self.urls {
+            let path = format!("{prefix}{}", route.url);
+            let route_host = route.host();
+            let host = route_host.as_deref().or(host);
+
+            if let RouteInner::Router(router) = &route.view {
+                router.collect_route_infos(&path, host, app_name, infos);
+            } else {
+                let handlers = route
+                    .handlers
+                    .as_ref()
+                    .expect("handler routes have handlers");
+                infos.push(RouteInfo {
+                    path: if path.is_empty() {
+                        "/".to_owned()
+                    } else {
+                        path
+                    },
+                    host: host.map(ToOwned::to_owned),
+                    app_name: app_name.map(|name| name.0.clone()),
+                    name: route.name().map(ToOwned::to_owned),
+                    methods: handlers.methods.clone(),
+                    handler: handlers.type_names.join(", "),
+                });
+            }
         }
     }
 
@@ -125,7 +317,13 @@ impl Router {
     async fn route(&self, mut request: Request, request_path: &str) -> Result<Response> {
         debug!("Routing request to {}", request_path);
 
-        if let Some(result) = self.get_handler(request_path) {
+        let host = request
+            .headers()
+            .get(http::header::HOST)
+            .and_then(|host| host.to_str().ok())
+            .or_else(|| request.uri().authority().map(http::uri::Authority::as_str))
+            .and_then(host::normalize_host);
+        if let Some(result) = self.get_handler(request_path, host.as_deref()) {
             let mut path_params = PathParams::new();
             for (key, value) in result.params.iter().rev() {
                 path_params.insert(key.clone(), value.clone());
@@ -137,15 +335,37 @@ impl Router {
             if let Some(name) = result.name {
                 request.extensions_mut().insert(name);
             }
-            result.handler.handle(request).await
+            tracing::Span::current().record("http.route", result.route_pattern().as_str());
+            if result.middlewares.is_empty() {
+                return result.handler.handle(request).await;
+            }
+
+            let handler = result.handler;
+            let mut service =
+                BoxCloneSyncService::new(tower::service_fn(move |request: Request| {
+                    let handler = Arc::clone(&handler);
+                    async move { handler.handle(request).await }
+                }));
+            for middleware in result.middlewares {
+                service = middleware(service);
+            }
+            service.oneshot(request).await
         } else {
             debug!("Not found: {}", request_path);
             Err(Error::from(NotFound::router()))
         }
     }
 
-    fn get_handler(&self, request_path: &str) -> Option<HandlerFound<'_>> {
+    fn get_handler(&self, request_path: &str, host: Option<&str>) -> Option<HandlerFound<'_>> {
         for route in &self.urls {
+            let host_params = match &route.host {
+                Some(host_matcher) => match host.and_then(|host| host_matcher.capture(host)) {
+                    Some(host_params) => host_params,
+                    None => continue,
+                },
+                None => Vec::new(),
+            };
+
             if let Some(matches) = route.url.capture(request_path) {
                 let matches_fully = matches.matches_fully();
 
@@ -153,32 +373,43 @@ impl Router {
                     RouteInner::Handler(handler) => {
                         if matches_fully {
                             return Some(HandlerFound {
-                                handler: &**handler,
+                                handler: Arc::clone(handler),
                                 app_name: self.app_name.clone(),
                                 name: route.name.clone(),
                                 params: Self::matches_to_path_params(&matches, Vec::new()),
+                                route: vec![&*route.url],
+                                middlewares: self.middlewares.iter().collect(),
                             });
                         }
                     }
                     RouteInner::Router(router) => {
-                        if let Some(result) = router.get_handler(matches.remaining_path) {
+                        if let Some(mut result) = router.get_handler(matches.remaining_path, host) {
+                            result.route.push(&route.url);
+                            result.middlewares.extend(&self.middlewares);
+                            let mut params = Self::matches_to_path_params(&matches, result.params);
+                            params.extend(host_params.into_iter().rev());
                             return Some(HandlerFound {
                                 handler: result.handler,
                                 app_name: result.app_name.or_else(|| self.app_name.clone()),
                                 name: result.name,
-                                params: Self::matches_to_path_params(&matches, result.params),
+                                params,
+                                route: result.route,
+                                middlewares: result.middlewares,
                             });
                         }
                     }
                     #[cfg(feature = "openapi")]
                     RouteInner::ApiHandler(handler) => {
                         if matches_fully {
-                            let handler: &(dyn BoxRequestHandler + Send + Sync) = &**handler;
+                            let handler: Arc<dyn BoxRequestHandler + Send + Sync> =
+                                Arc::clone(handler) as _;
                             return Some(HandlerFound {
                                 handler,
                                 app_name: self.app_name.clone(),
                                 name: route.name.clone(),
                                 params: Self::matches_to_path_params(&matches, Vec::new()),
+                                route: vec![&*route.url],
+                                middlewares: self.middlewares.iter().collect(),
                             });
                         }
                     }
@@ -444,6 +675,15 @@ impl Default for Router {
     }
 }
 
+fn check_route_name_unique<'a>(names: &mut HashSet<&'a RouteName>, name: &'a RouteName) {
+    assert!(
+        names.insert(name),
+        "duplicate route name `{}`: route names must be unique within a router and its nested \
+        routers, unless they belong to different apps",
+        name.0
+    );
+}
+
 #[derive(Debug, thiserror::Error)]
 #[error("failed to reverse route `{view_name}` due to view not existing")]
 struct NoViewToReverse {
@@ -455,10 +695,30 @@ impl_into_cot_error!(NoViewToReverse);
 #[derive(Debug)]
 struct HandlerFound<'a> {
     #[debug("handler(...)")]
-    handler: &'a (dyn BoxRequestHandler + Send + Sync),
+    handler: Arc<dyn BoxRequestHandler + Send + Sync>,
     app_name: Option<AppName>,
     name: Option<RouteName>,
     params: Vec<(String, String)>,
+    /// The path patterns of the matched route and of the routes of the
+    /// routers it was found in, from the innermost one to the outermost one.
+    route: Vec<&'a PathMatcher>,
+    /// The middlewares of the routers the handler was found in, from the
+    /// innermost router to the outermost one.
+    #[debug("[{} middlewares]", middlewares.len())]
+    middlewares: Vec<&'a RouterMiddleware>,
+}
+
+impl HandlerFound<'_> {
+    /// Returns the full path pattern of the matched route, such as
+    /// `/users/{id}`.
+    fn route_pattern(&self) -> String {
+        let pattern: String = self.route.iter().rev().map(ToString::to_string).collect();
+        if pattern.is_empty() {
+            "/".to_owned()
+        } else {
+            pattern
+        }
+    }
 }
 
 /// A service that routes requests to their respective views.
@@ -546,8 +806,10 @@ pub fn split_view_name(view_name: &str) -> (Option<&str>, &str) {
 #[derive(Debug, Clone)]
 pub struct Route {
     url: Arc<PathMatcher>,
+    host: Option<Arc<HostMatcher>>,
     view: RouteInner,
     name: Option<RouteName>,
+    handlers: Option<RouteHandlers>,
 }
 
 impl Route {
@@ -576,6 +838,8 @@ impl Route {
     {
         Self {
             url: Arc::new(PathMatcher::new(url)),
+            host: None,
+            handlers: Some(RouteHandlers::of(&handler)),
             view: RouteInner::Handler(Arc::new(into_box_request_handler(handler))),
             name: None,
         }
@@ -611,6 +875,8 @@ impl Route {
     {
         Self {
             url: Arc::new(PathMatcher::new(url)),
+            host: None,
+            handlers: Some(RouteHandlers::of(&handler)),
             view: RouteInner::ApiHandler(Arc::new(
                 crate::openapi::into_box_api_endpoint_request_handler(handler),
             )),
@@ -644,6 +910,8 @@ impl Route {
     {
         Self {
             url: Arc::new(PathMatcher::new(url)),
+            host: None,
+            handlers: Some(RouteHandlers::of(&handler)),
             view: RouteInner::Handler(Arc::new(into_box_request_handler(handler))),
             name: Some(RouteName(name.into())),
         }
@@ -680,6 +948,8 @@ impl Route {
     {
         Self {
             url: Arc::new(PathMatcher::new(url)),
+            host: None,
+            handlers: Some(RouteHandlers::of(&handler)),
             view: RouteInner::ApiHandler(Arc::new(
                 crate::openapi::into_box_api_endpoint_request_handler(handler),
             )),
@@ -707,8 +977,78 @@ impl Route {
     pub fn with_router(url: &str, router: Router) -> Self {
         Self {
             url: Arc::new(PathMatcher::new(url)),
+            host: None,
+            view: RouteInner::Router(router),
+            name: None,
+            handlers: None,
+        }
+    }
+
+    /// Create a new route with the given router that only matches the
+    /// requests sent to the given host.
+    ///
+    /// The host is read from the `Host` header (or the request URI authority,
+    /// as used by HTTP/2) before matching the path; the port is ignored and
+    /// the match is case-insensitive. The nested router then matches the full
+    /// request path. If the host doesn't match, the routing continues with
+    /// the next routes, so a route without a host placed after the host routes
+    /// serves as the default for all other hosts.
+    ///
+    /// The host pattern consists of labels separated by dots, each of which
+    /// can be:
+    ///
+    /// * a literal, such as `api`, matching the label exactly,
+    /// * a parameter, such as `{tenant}`, matching any single label, which is
+    ///   then available in the path parameters under the given name,
+    /// * a wildcard `*`, allowed only as the first label, matching one or more
+    ///   labels, which are then available in the path parameters under the
+    ///   name `subdomain`.
+    ///
+    /// Note that reversing the routes inside the nested router produces only
+    /// the path, without the host.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the host pattern is invalid, e.g., it's empty, contains an
+    /// empty label, or contains a wildcard anywhere but at the beginning.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::request::Request;
+    /// use cot::request::extractors::Path;
+    /// use cot::response::Response;
+    /// use cot::router::{Route, Router};
+    ///
+    /// async fn api_status(request: Request) -> cot::Result<Response> {
+    ///     unimplemented!()
+    /// }
+    ///
+    /// async fn tenant_home(Path(subdomain): Path<String>) -> cot::Result<Response> {
+    ///     unimplemented!()
+    /// }
+    ///
+    /// async fn home(request: Request) -> cot::Result<Response> {
+    ///     unimplemented!()
+    /// }
+    ///
+    /// let api = Router::with_urls([Route::with_handler("/status", api_status)]);
+    /// let tenants = Router::with_urls([Route::with_handler("/", tenant_home)]);
+    /// let router = Router::with_urls([
+    ///     Route::with_host("api.example.com", api),
+    ///     Route::with_host("*.example.com", tenants),
+    ///     // all the other hosts
+    ///     Route::with_handler("/", home),
+    /// ]);
+    /// ```
+    #[must_use]
+    pub fn with_host(host: &str, router: Router) -> Self {
+        Self {
+            url: Arc::new(PathMatcher::new("")),
+            host: Some(Arc::new(HostMatcher::new(host))),
             view: RouteInner::Router(router),
             name: None,
+            handlers: None,
         }
     }
 
@@ -755,6 +1095,22 @@ impl Route {
         self.name.as_ref().map(|name| name.0.as_str())
     }
 
+    /// Get the host pattern of this route, if it was created with the
+    /// [`Self::with_host`] function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::router::{Route, Router};
+    ///
+    /// let route = Route::with_host("*.example.com", Router::empty());
+    /// assert_eq!(route.host(), Some("*.example.com".to_string()));
+    /// ```
+    #[must_use]
+    pub fn host(&self) -> Option<String> {
+        self.host.as_ref().map(ToString::to_string)
+    }
+
     #[must_use]
     pub(crate) fn kind(&self) -> RouteKind {
         match &self.view {
@@ -776,6 +1132,129 @@ impl Route {
     }
 }
 
+/// The HTTP methods and the type names of the handlers of a route.
+#[derive(Debug, Clone)]
+struct RouteHandlers {
+    /// The methods handled by the route; empty if it handles any method.
+    methods: Vec<Method>,
+    type_names: Vec<&'static str>,
+}
+
+impl RouteHandlers {
+    fn of<H: 'static>(handler: &H) -> Self {
+        let any_handler: &dyn Any = handler;
+        if let Some(method_router) = any_handler.downcast_ref::<MethodRouter>() {
+            return Self::from_method_handlers(method_router.handlers());
+        }
+        #[cfg(feature = "openapi")]
+        if let Some(method_router) = any_handler.downcast_ref::<ApiMethodRouter>() {
+            return Self::from_method_handlers(method_router.handlers());
+        }
+
+        Self {
+            methods: Vec::new(),
+            type_names: vec![std::any::type_name::<H>()],
+        }
+    }
+
+    fn from_method_handlers(method_handlers: Vec<(Method, &'static str)>) -> Self {
+        let mut methods = Vec::with_capacity(method_handlers.len());
+        let mut type_names = Vec::new();
+        for (method, type_name) in method_handlers {
+            methods.push(method);
+            if !type_names.contains(&type_name) {
+                type_names.push(type_name);
+            }
+        }
+
+        Self {
+            methods,
+            type_names,
+        }
+    }
+}
+
+/// Information about a single route, as returned by [`Router::iter`].
+///
+/// # Examples
+///
+/// ```
+/// use cot::html::Html;
+/// use cot::router::{Route, Router};
+///
+/// async fn home() -> Html {
+///     unimplemented!()
+/// }
+///
+/// let router = Router::with_urls([Route::with_handler_and_name("/", home, "home")]);
+/// let route = router.iter().next().unwrap();
+/// assert_eq!(route.path(), "/");
+/// assert_eq!(route.name(), Some("home"));
+/// assert!(route.methods().is_empty());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteInfo {
+    path: String,
+    host: Option<String>,
+    app_name: Option<String>,
+    name: Option<String>,
+    methods: Vec<Method>,
+    handler: String,
+}
+
+impl RouteInfo {
+    /// Returns the full path of the route, including the prefixes of the
+    /// routers it's nested in.
+    #[must_use]
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the host pattern the route is restricted to, if it's nested in
+    /// a router added with [`Route::with_host`].
+    #[must_use]
+    pub fn host(&self) -> Option<&str> {
+        self.host.as_deref()
+    }
+
+    /// Returns the name of the app the route belongs to, if any.
+    ///
+    /// Together with [`Self::name`], this can be used to reverse the route
+    /// with the [`reverse!`](crate::reverse) macro.
+    #[must_use]
+    pub fn app_name(&self) -> Option<&str> {
+        self.app_name.as_deref()
+    }
+
+    /// Returns the name of the route, if it was created with
+    /// [`Route::with_handler_and_name`].
+    #[must_use]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the HTTP methods handled by the route.
+    ///
+    /// This is only known for the routes whose handler is a
+    /// [`MethodRouter`]; for the other routes, which handle any method, this
+    /// is empty.
+    #[must_use]
+    pub fn methods(&self) -> &[Method] {
+        &self.methods
+    }
+
+    /// Returns the type name of the handler of the route. If the route uses a
+    /// [`MethodRouter`] with different handlers for different methods, their
+    /// type names are separated by commas.
+    ///
+    /// The type name is meant for diagnostics only, as its exact format is not
+    /// guaranteed.
+    #[must_use]
+    pub fn handler(&self) -> &str {
+        &self.handler
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub(crate) enum RouteKind {
     Handler,
@@ -1175,6 +1654,164 @@ mod tests {
         assert_eq!(response.status(), StatusCode::OK);
     }
 
+    #[cot::test]
+    async fn router_middleware() {
+        let sub_router = Router::with_urls(vec![Route::with_handler("/test", MockHandler)])
+            .middleware(crate::middleware::SecurityHeadersMiddleware::new());
+        let router = Router::with_urls(vec![
+            Route::with_handler("/test", MockHandler),
+            Route::with_router("/sub", sub_router),
+        ]);
+
+        let response = router
+            .handle(TestRequestBuilder::get("/sub/test").build())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(
+            response
+                .headers()
+                .contains_key(http::header::X_FRAME_OPTIONS)
+        );
+
+        let response = router
+            .handle(TestRequestBuilder::get("/test").build())
+            .await
+            .unwrap();
+        assert!(
+            !response
+                .headers()
+                .contains_key(http::header::X_FRAME_OPTIONS)
+        );
+    }
+
+    #[test]
+    fn router_route_pattern() {
+        let sub_router = Router::with_urls(vec![Route::with_handler("/{id}", MockHandler)]);
+        let router = Router::with_urls(vec![
+            Route::with_handler("", MockHandler),
+            Route::with_router("/users", sub_router),
+        ]);
+
+        let handler = router.get_handler("/users/42", None).unwrap();
+        assert_eq!(handler.route_pattern(), "/users/{id}");
+        let handler = router.get_handler("", None).unwrap();
+        assert_eq!(handler.route_pattern(), "/");
+    }
+
+    #[cot::test]
+    async fn router_middleware_timeout() {
+        async fn slow(_request: Request) -> Result<Response> {
+            tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+            Html::new("OK").into_response()
+        }
+
+        let sub_router = Router::with_urls(vec![Route::with_handler("/slow", slow)]).middleware(
+            crate::middleware::TimeoutMiddleware::new(std::time::Duration::from_millis(10)),
+        );
+        let router = Router::with_urls(vec![Route::with_router("/sub", sub_router)]);
+
+        let error = router
+            .handle(TestRequestBuilder::get("/sub/slow").build())
+            .await
+            .unwrap_err();
+        assert_eq!(error.status_code(), StatusCode::GATEWAY_TIMEOUT);
+    }
+
+    fn request_with_host(path: &str, host: &'static str) -> Request {
+        let mut request = TestRequestBuilder::get(path).build();
+        request
+            .headers_mut()
+            .insert(http::header::HOST, http::HeaderValue::from_static(host));
+        request
+    }
+
+    #[cot::test]
+    async fn router_host() {
+        use crate::request::extractors::Path;
+
+        async fn api(_request: Request) -> Html {
+            Html::new("api")
+        }
+        async fn tenant(Path(subdomain): Path<String>) -> Html {
+            Html::new(format!("tenant {subdomain}"))
+        }
+        async fn default(_request: Request) -> Html {
+            Html::new("default")
+        }
+
+        let router = Router::with_urls(vec![
+            Route::with_host(
+                "api.example.com",
+                Router::with_urls(vec![Route::with_handler("/test", api)]),
+            ),
+            Route::with_host(
+                "*.example.com",
+                Router::with_urls(vec![Route::with_handler("/test", tenant)]),
+            ),
+            Route::with_handler("/test", default),
+        ]);
+
+        for (host, expected) in [
+            ("API.example.com:8000", "api"),
+            ("acme.example.com", "tenant acme"),
+            ("eu.acme.example.com", "tenant eu.acme"),
+            ("example.com", "default"),
+            ("localhost", "default"),
+        ] {
+            let response = router
+                .handle(request_with_host("/test", host))
+                .await
+                .unwrap();
+            let body = response.into_body().into_bytes().await.unwrap();
+            assert_eq!(body, expected, "host: {host}");
+        }
+    }
+
+    #[cot::test]
+    async fn router_host_not_found() {
+        let router = Router::with_urls(vec![Route::with_host(
+            "api.example.com",
+            Router::with_urls(vec![Route::with_handler("/test", MockHandler)]),
+        )]);
+
+        let error = router
+            .handle(request_with_host("/test", "www.example.com"))
+            .await
+            .unwrap_err();
+        assert_eq!(error.status_code(), StatusCode::NOT_FOUND);
+
+        let error = router
+            .handle(TestRequestBuilder::get("/test").build())
+            .await
+            .unwrap_err();
+        assert_eq!(error.status_code(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate route name `test`")]
+    fn router_duplicate_route_names() {
+        let _router = Router::with_urls(vec![
+            Route::with_handler_and_name("/test", MockHandler, "test"),
+            Route::with_handler_and_name("/test2", MockHandler, "test"),
+        ]);
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate route name `test`")]
+    fn router_duplicate_route_names_nested() {
+        let sub_router = Router::with_urls(vec![Route::with_handler_and_name(
+            "/test",
+            MockHandler,
+            "test",
+        )]);
+
+        let _router = Router::with_urls(vec![
+            Route::with_handler_and_name("/test", MockHandler, "test"),
+            Route::with_router("/sub", sub_router),
+        ]);
+    }
+
     #[test]
     fn router_reverse() {
         let route = Route::with_handler_and_name("/test", MockHandler, "test");
@@ -1247,6 +1884,67 @@ mod tests {
         assert_eq!(router.routes().len(), 1);
     }
 
+    #[test]
+    fn router_iter_nested() {
+        let mut posts = Router::with_urls([
+            Route::with_handler_and_name("/", MockHandler, "list"),
+            Route::with_handler_and_name(
+                "/{id}",
+                MethodRouter::new().get(MockHandler).delete(MockHandler),
+                "detail",
+            ),
+        ]);
+        posts.set_app_name(AppName("blog".to_string()));
+        let api = Router::with_urls([
+            Route::with_router("/posts", posts),
+            Route::with_handler("/status", MockHandler),
+        ]);
+        let router = Router::with_urls([
+            Route::with_handler_and_name("/", MockHandler, "home"),
+            Route::with_router("/api", api),
+        ]);
+
+        let routes: Vec<_> = router.iter().collect();
+
+        let paths: Vec<_> = routes.iter().map(RouteInfo::path).collect();
+        assert_eq!(
+            paths,
+            ["/", "/api/posts/", "/api/posts/{id}", "/api/status"]
+        );
+        let names: Vec<_> = routes
+            .iter()
+            .map(|route| (route.app_name(), route.name()))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                (None, Some("home")),
+                (Some("blog"), Some("list")),
+                (Some("blog"), Some("detail")),
+                (None, None),
+            ]
+        );
+        assert!(routes[0].methods().is_empty());
+        assert_eq!(routes[2].methods(), [Method::GET, Method::DELETE]);
+        assert!(routes[2].handler().ends_with("MockHandler"));
+    }
+
+    #[test]
+    fn router_iter_host() {
+        let api = Router::with_urls([Route::with_handler("/status", MockHandler)]);
+        let router = Router::with_urls([
+            Route::with_host("api.example.com", api),
+            Route::with_handler("", MockHandler),
+        ]);
+
+        let routes: Vec<_> = router.iter().collect();
+
+        assert_eq!(routes[0].host(), Some("api.example.com"));
+        assert_eq!(routes[0].path(), "/status");
+        assert_eq!(routes[1].host(), None);
+        assert_eq!(routes[1].path(), "/");
+    }
+
     #[test]
     fn router_is_empty() {
         let router = Router::with_urls(vec![]);
@@ -0,0 +1,232 @@
+//! Host matching for the host-based routing.
+//!
+//! A host pattern consists of labels separated by dots. Each label is one of:
+//!
+//! * a literal, such as `api` or `example`, matching the label exactly,
+//! * a parameter, such as `{tenant}`, matching any single label and capturing
+//!   it under the given name,
+//! * a wildcard `*`, allowed only as the first label, matching one or more
+//!   labels and capturing them under the name `subdomain`.
+//!
+//! Host names are matched case-insensitively, and the port is ignored.
+
+use std::fmt::Display;
+
+use http::uri::Authority;
+
+/// The name of the parameter capturing the labels matched by the `*`
+/// wildcard.
+pub(super) const WILDCARD_PARAM: &str = "subdomain";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct HostMatcher {
+    wildcard: bool,
+    /// The labels following the wildcard (or all the labels, if there's no
+    /// wildcard).
+    labels: Vec<HostLabel>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HostLabel {
+    Literal(String),
+    Param { name: String },
+}
+
+impl HostMatcher {
+    #[must_use]
+    pub(super) fn new(host_pattern: &str) -> Self {
+        assert!(!host_pattern.is_empty(), "Host pattern must not be empty");
+
+        let mut labels = host_pattern.split('.').peekable();
+        let wildcard = labels.next_if_eq(&"*").is_some();
+        let labels: Vec<_> = labels
+            .map(|label| {
+                assert!(
+                    !label.is_empty(),
+                    "Empty label in host pattern: `{host_pattern}`"
+                );
+                assert!(
+                    label != "*",
+                    "Wildcard is only allowed as the first label of the host pattern: \
+                    `{host_pattern}`"
+                );
+
+                if let Some(name) = label.strip_prefix('{').and_then(|l| l.strip_suffix('}')) {
+                    assert!(
+                        !name.is_empty() && name != WILDCARD_PARAM,
+                        "Invalid parameter name in host pattern: `{host_pattern}`"
+                    );
+                    HostLabel::Param {
+                        name: name.to_owned(),
+                    }
+                } else {
+                    assert!(
+                        !label.contains(['{', '}']),
+                        "Invalid label in host pattern: `{host_pattern}`"
+                    );
+                    HostLabel::Literal(label.to_ascii_lowercase())
+                }
+            })
+            .collect();
+        assert!(
+            !labels.is_empty(),
+            "Host pattern must contain at least one label after the wildcard"
+        );
+
+        Self { wildcard, labels }
+    }
+
+    /// Matches the host name against the pattern, returning the captured
+    /// parameters if it matches.
+    ///
+    /// The host name is expected to be lowercase and without the port, as
+    /// returned by [`normalize_host`].
+    pub(super) fn capture(&self, host: &str) -> Option<Vec<(String, String)>> {
+        let host_labels: Vec<_> = host.split('.').collect();
+        let prefix_len = host_labels.len().checked_sub(self.labels.len())?;
+        if (prefix_len > 0) != self.wildcard {
+            return None;
+        }
+
+        let mut params = Vec::new();
+        if self.wildcard {
+            params.push((
+                WILDCARD_PARAM.to_owned(),
+                host_labels[..prefix_len].join("."),
+            ));
+        }
+        for (label, host_label) in self.labels.iter().zip(&host_labels[prefix_len..]) {
+            match label {
+                HostLabel::Literal(literal) => {
+                    if literal != host_label {
+                        return None;
+                    }
+                }
+                HostLabel::Param { name } => {
+                    if host_label.is_empty() {
+                        return None;
+                    }
+                    params.push((name.clone(), (*host_label).to_owned()));
+                }
+            }
+        }
+
+        Some(params)
+    }
+}
+
+impl Display for HostMatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.wildcard {
+            write!(f, "*.")?;
+        }
+        for (index, label) in self.labels.iter().enumerate() {
+            if index > 0 {
+                write!(f, ".")?;
+            }
+            match label {
+                HostLabel::Literal(literal) => write!(f, "{literal}")?,
+                HostLabel::Param { name } => write!(f, "{{{name}}}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Extracts the host name from the value of the `Host` header (or the URI
+/// authority), stripping the port and converting it to lowercase.
+pub(super) fn normalize_host(authority: &str) -> Option<String> {
+    let authority: Authority = authority.parse().ok()?;
+    let host = authority.host().trim_end_matches('.');
+    if host.is_empty() {
+        None
+    } else {
+        Some(host.to_ascii_lowercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(params: &[(&str, &str)]) -> Option<Vec<(String, String)>> {
+        Some(
+            params
+                .iter()
+                .map(|(name, value)| ((*name).to_owned(), (*value).to_owned()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn host_matcher_literal() {
+        let matcher = HostMatcher::new("api.Example.com");
+
+        assert_eq!(matcher.capture("api.example.com"), params(&[]));
+        assert_eq!(matcher.capture("www.example.com"), None);
+        assert_eq!(matcher.capture("example.com"), None);
+        assert_eq!(matcher.capture("v1.api.example.com"), None);
+    }
+
+    #[test]
+    fn host_matcher_param() {
+        let matcher = HostMatcher::new("{tenant}.example.com");
+
+        assert_eq!(
+            matcher.capture("acme.example.com"),
+            params(&[("tenant", "acme")])
+        );
+        assert_eq!(matcher.capture("example.com"), None);
+        assert_eq!(matcher.capture("a.b.example.com"), None);
+    }
+
+    #[test]
+    fn host_matcher_wildcard() {
+        let matcher = HostMatcher::new("*.example.com");
+
+        assert_eq!(
+            matcher.capture("acme.example.com"),
+            params(&[("subdomain", "acme")])
+        );
+        assert_eq!(
+            matcher.capture("eu.acme.example.com"),
+            params(&[("subdomain", "eu.acme")])
+        );
+        assert_eq!(matcher.capture("example.com"), None);
+        assert_eq!(matcher.capture("acme.example.org"), None);
+    }
+
+    #[test]
+    fn host_matcher_display() {
+        assert_eq!(
+            HostMatcher::new("*.{tenant}.example.com").to_string(),
+            "*.{tenant}.example.com"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Wildcard is only allowed as the first label")]
+    fn host_matcher_wildcard_not_first() {
+        let _ = HostMatcher::new("api.*.com");
+    }
+
+    #[test]
+    #[should_panic(expected = "Empty label in host pattern")]
+    fn host_matcher_empty_label() {
+        let _ = HostMatcher::new("api..com");
+    }
+
+    #[test]
+    fn normalize_host_strips_port() {
+        assert_eq!(
+            normalize_host("API.example.com:8000").as_deref(),
+            Some("api.example.com")
+        );
+        assert_eq!(
+            normalize_host("example.com.").as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(normalize_host("[::1]:8000").as_deref(), Some("[::1]"));
+        assert_eq!(normalize_host("").as_deref(), None);
+    }
+}
@@ -242,6 +242,12 @@ impl MethodRouter {
         self.inner.fallback = InnerHandler::new(handler);
         self
     }
+
+    /// Returns the methods that have a handler registered, along with the type
+    /// names of the handlers.
+    pub(crate) fn handlers(&self) -> Vec<(Method, &'static str)> {
+        self.inner.handlers()
+    }
 }
 
 impl RequestHandler for MethodRouter {
@@ -283,6 +289,34 @@ impl<T> InnerMethodRouter<T> {
     }
 }
 
+impl<T: NamedHandler> InnerMethodRouter<T> {
+    /// Returns the methods that have a handler registered, along with the type
+    /// names of the handlers. The fallback handler is not included.
+    fn handlers(&self) -> Vec<(Method, &'static str)> {
+        let mut handlers = Vec::new();
+
+        macro_rules! add_handler {
+            ($name:ident => $method:ident) => {
+                if let Some(handler) = &self.$name {
+                    handlers.push((Method::$method, handler.type_name()));
+                }
+            };
+        }
+
+        add_handler!(get => GET);
+        add_handler!(head => HEAD);
+        add_handler!(delete => DELETE);
+        add_handler!(options => OPTIONS);
+        add_handler!(patch => PATCH);
+        add_handler!(post => POST);
+        add_handler!(put => PUT);
+        add_handler!(trace => TRACE);
+        add_handler!(connect => CONNECT);
+
+        handlers
+    }
+}
+
 impl<T: RequestHandler + Send + Sync> RequestHandler for InnerMethodRouter<T> {
     async fn handle(&self, request: Request) -> cot::Result<Response> {
         macro_rules! handle_method {
@@ -318,7 +352,10 @@ impl<T: RequestHandler + Send + Sync> RequestHandler for InnerMethodRouter<T> {
     }
 }
 
-struct InnerHandler(Box<dyn BoxRequestHandler + Send + Sync>);
+struct InnerHandler {
+    handler: Box<dyn BoxRequestHandler + Send + Sync>,
+    type_name: &'static str,
+}
 
 impl InnerHandler {
     fn new<HandlerParams, H>(handler: H) -> Self
@@ -326,7 +363,16 @@ impl InnerHandler {
         HandlerParams: 'static,
         H: RequestHandler<HandlerParams> + Send + Sync + 'static,
     {
-        Self(Box::new(into_box_request_handler(handler)))
+        Self {
+            handler: Box::new(into_box_request_handler(handler)),
+            type_name: std::any::type_name::<H>(),
+        }
+    }
+}
+
+impl NamedHandler for InnerHandler {
+    fn type_name(&self) -> &'static str {
+        self.type_name
     }
 }
 
@@ -338,10 +384,16 @@ impl Debug for InnerHandler {
 
 impl RequestHandler for InnerHandler {
     fn handle(&self, request: Request) -> impl Future<Output = cot::Result<Response>> + Send {
-        self.0.handle(request)
+        self.handler.handle(request)
     }
 }
 
+/// A handler stored in an [`InnerMethodRouter`] that knows the name of its
+/// type, so that the routes can be listed along with their handlers.
+trait NamedHandler {
+    fn type_name(&self) -> &'static str;
+}
+
 macro_rules! define_method_router {
     ($name:ident => $method:ident) => {
         #[doc = concat!(
@@ -17,7 +17,7 @@ use crate::RequestHandler;
 use crate::openapi::{
     AsApiOperation, AsApiRoute, BoxApiRequestHandler, into_box_api_request_handler,
 };
-use crate::router::method::InnerMethodRouter;
+use crate::router::method::{InnerMethodRouter, NamedHandler};
 
 /// A version of [`MethodRouter`](crate::router::method::MethodRouter) that
 /// supports OpenAPI.
@@ -312,6 +312,12 @@ impl ApiMethodRouter {
         self.inner.fallback = InnerHandler::new(handler);
         self
     }
+
+    /// Returns the methods that have a handler registered, along with the type
+    /// names of the handlers.
+    pub(crate) fn handlers(&self) -> Vec<(crate::Method, &'static str)> {
+        self.inner.handlers()
+    }
 }
 
 impl RequestHandler for ApiMethodRouter {
@@ -352,7 +358,10 @@ impl AsApiRoute for ApiMethodRouter {
     }
 }
 
-struct InnerApiHandler(Box<dyn BoxApiRequestHandler + Send + Sync>);
+struct InnerApiHandler {
+    handler: Box<dyn BoxApiRequestHandler + Send + Sync>,
+    type_name: &'static str,
+}
 
 impl InnerApiHandler {
     fn new<HandlerParams, ApiParams, H>(handler: H) -> Self
@@ -361,7 +370,16 @@ impl InnerApiHandler {
         ApiParams: 'static,
         H: RequestHandler<HandlerParams> + AsApiOperation<ApiParams> + Send + Sync + 'static,
     {
-        Self(Box::new(into_box_api_request_handler(handler)))
+        Self {
+            handler: Box::new(into_box_api_request_handler(handler)),
+            type_name: std::any::type_name::<H>(),
+        }
+    }
+}
+
+impl NamedHandler for InnerApiHandler {
+    fn type_name(&self) -> &'static str {
+        self.type_name
     }
 }
 
@@ -373,7 +391,7 @@ impl Debug for InnerApiHandler {
 
 impl RequestHandler for InnerApiHandler {
     fn handle(&self, request: Request) -> impl Future<Output = cot::Result<Response>> + Send {
-        self.0.handle(request)
+        self.handler.handle(request)
     }
 }
 
@@ -383,7 +401,8 @@ impl AsApiOperation for InnerApiHandler {
         route_context: &RouteContext<'_>,
         schema_generator: &mut SchemaGenerator,
     ) -> Option<Operation> {
-        self.0.as_api_operation(route_context, schema_generator)
+        self.handler
+            .as_api_operation(route_context, schema_generator)
     }
 }
 
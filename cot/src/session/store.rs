@@ -8,6 +8,8 @@
 //! Different implementations store data in different places, such as memory,
 //! files, databases, or external caching services like Redis.
 
+#[cfg(feature = "json")]
+pub mod cookie;
 #[cfg(all(feature = "db", feature = "json"))]
 pub mod db;
 #[cfg(feature = "json")]
@@ -0,0 +1,565 @@
+//! Cookie session store
+//!
+//! This module provides a session store that keeps the whole session record
+//! in the session cookie itself, signed with the project's secret key. No
+//! session state is kept on the server, which makes it suitable for stateless
+//! deployments.
+//!
+//! # Examples
+//!
+//! ```
+//! use cot::config::SecretKey;
+//! use cot::session::store::cookie::CookieStore;
+//!
+//! let store = CookieStore::new(SecretKey::from("my-secret-key"));
+//! ```
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use base64::Engine;
+use base64::prelude::BASE64_URL_SAFE_NO_PAD;
+use http::HeaderValue;
+use http::header::{COOKIE, SET_COOKIE};
+use thiserror::Error;
+use time::OffsetDateTime;
+use tower_sessions::session::{Id, Record};
+use tower_sessions::{SessionStore, session_store};
+
+use crate::config::SecretKey;
+use crate::session::store::ERROR_PREFIX;
+
+/// The maximum size of a session cookie, in bytes.
+///
+/// Browsers typically refuse to store cookies larger than 4 KB (including the
+/// cookie name and attributes).
+pub const MAX_COOKIE_SIZE: usize = 4096;
+
+/// The space reserved for the cookie attributes (such as `Path`, `Expires`,
+/// `HttpOnly`, `Secure`, and `SameSite`), which are only added after the
+/// session has been saved.
+const COOKIE_ATTRIBUTES_SIZE: usize = 256;
+
+const COOKIE_STORE_CONTEXT: &str = "cot.rs cookie session store v1";
+
+tokio::task_local! {
+    static COOKIE_SESSION: Arc<Mutex<CookieSession>>;
+}
+
+/// Errors that can occur when using the Cookie session store.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum CookieStoreError {
+    /// The store was used outside of
+    /// [`SessionMiddleware`](crate::middleware::SessionMiddleware), so there is
+    /// no cookie to read the session from or write the session to.
+    #[error("{ERROR_PREFIX} cookie store can only be used within SessionMiddleware")]
+    NoCookieContext,
+    /// The serialized session does not fit in a cookie.
+    #[error(
+        "{ERROR_PREFIX} session cookie is {size} bytes (including its name and attributes), \
+        which exceeds the maximum cookie size of {MAX_COOKIE_SIZE} bytes"
+    )]
+    TooLarge {
+        /// The size of the session cookie, including its name and the space
+        /// reserved for its attributes, in bytes.
+        size: usize,
+    },
+    /// An error occurred during JSON serialization.
+    #[error("{ERROR_PREFIX} JSON serialization error: {0}")]
+    Serialize(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl From<CookieStoreError> for session_store::Error {
+    fn from(error: CookieStoreError) -> session_store::Error {
+        match error {
+            CookieStoreError::Serialize(inner) => session_store::Error::Encode(inner.to_string()),
+            CookieStoreError::TooLarge { .. } => session_store::Error::Encode(error.to_string()),
+            other => session_store::Error::Backend(other.to_string()),
+        }
+    }
+}
+
+/// A session store that keeps the session data in a signed cookie.
+///
+/// The session record is serialized to JSON and signed using a key derived
+/// from the project's secret key, so that the client can read, but not modify
+/// the session data. Cookies that have been tampered with, were signed with an
+/// unknown key, or have expired are rejected when the session is loaded,
+/// resulting in a new, empty session.
+///
+/// Since the data is not encrypted, sensitive information should not be
+/// stored in the session when using this store.
+///
+/// This store only works together with
+/// [`SessionMiddleware`](crate::middleware::SessionMiddleware), which moves
+/// the session data between the store and the cookie.
+///
+/// # Size limit
+///
+/// Browsers refuse to store cookies larger than about 4 KB, including the
+/// cookie name and attributes. Since the session data is base64-encoded, this
+/// leaves room for roughly 2.8 KB of serialized session data. Saving a session
+/// whose cookie (with 256 bytes reserved for the attributes) does not fit in
+/// [`MAX_COOKIE_SIZE`] bytes fails with [`CookieStoreError::TooLarge`]
+/// instead of the browser silently dropping the cookie.
+///
+/// # Examples
+///
+/// ```
+/// use cot::config::SecretKey;
+/// use cot::middleware::SessionMiddleware;
+/// use cot::session::store::cookie::CookieStore;
+///
+/// let store = CookieStore::new(SecretKey::from("my-secret-key"));
+/// let middleware = SessionMiddleware::new(store);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CookieStore {
+    secret_key: SecretKey,
+    fallback_secret_keys: Vec<SecretKey>,
+}
+
+impl CookieStore {
+    /// Creates a new `CookieStore` signing the session cookies with the given
+    /// secret key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::config::SecretKey;
+    /// use cot::session::store::cookie::CookieStore;
+    ///
+    /// let store = CookieStore::new(SecretKey::from("my-secret-key"));
+    /// ```
+    #[must_use]
+    pub fn new(secret_key: SecretKey) -> Self {
+        Self {
+            secret_key,
+            fallback_secret_keys: Vec::new(),
+        }
+    }
+
+    /// Sets the fallback secret keys.
+    ///
+    /// Cookies signed with any of these keys are still accepted when loading a
+    /// session, but new cookies are always signed with the main secret key.
+    /// This allows rotating the secret key without invalidating all the
+    /// existing sessions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::config::SecretKey;
+    /// use cot::session::store::cookie::CookieStore;
+    ///
+    /// let store = CookieStore::new(SecretKey::from("new-secret-key"))
+    ///     .fallback_secret_keys(vec![SecretKey::from("old-secret-key")]);
+    /// ```
+    #[must_use]
+    pub fn fallback_secret_keys(mut self, fallback_secret_keys: Vec<SecretKey>) -> Self {
+        self.fallback_secret_keys = fallback_secret_keys;
+        self
+    }
+
+    fn encode(&self, record: &Record) -> Result<String, CookieStoreError> {
+        let json_data =
+            serde_json::to_vec(record).map_err(|err| CookieStoreError::Serialize(Box::new(err)))?;
+        let message = format!("{}.{}", record.id, BASE64_URL_SAFE_NO_PAD.encode(json_data));
+        let signature = sign(&self.secret_key, &message);
+        Ok(format!("{message}.{}", signature.to_hex()))
+    }
+
+    fn decode(&self, value: &str) -> Option<Record> {
+        let (message, signature) = value.rsplit_once('.')?;
+        let signature = blake3::Hash::from_hex(signature).ok()?;
+        // `blake3::Hash` comparisons are constant-time
+        let valid = std::iter::once(&self.secret_key)
+            .chain(&self.fallback_secret_keys)
+            .any(|key| sign(key, message) == signature);
+        if !valid {
+            return None;
+        }
+
+        let (_, json_data) = message.split_once('.')?;
+        let json_data = BASE64_URL_SAFE_NO_PAD.decode(json_data).ok()?;
+        serde_json::from_slice(&json_data).ok()
+    }
+}
+
+#[async_trait]
+impl SessionStore for CookieStore {
+    async fn create(&self, record: &mut Record) -> session_store::Result<()> {
+        // there is no shared state, so there is nothing to collide with
+        self.save(record).await
+    }
+
+    async fn save(&self, record: &Record) -> session_store::Result<()> {
+        let value = self.encode(record)?;
+        with_cookie_session(|session| {
+            let size = session.name.len() + "=".len() + value.len() + COOKIE_ATTRIBUTES_SIZE;
+            if size > MAX_COOKIE_SIZE {
+                return Err(CookieStoreError::TooLarge { size });
+            }
+            session.saved = Some((record.id, value));
+            Ok(())
+        })??;
+        Ok(())
+    }
+
+    async fn load(&self, session_id: &Id) -> session_store::Result<Option<Record>> {
+        let value = with_cookie_session(|session| session.cookie.clone())?;
+        let record = value
+            .and_then(|value| self.decode(&value))
+            .filter(|record| record.id == *session_id && is_active(record.expiry_date));
+        Ok(record)
+    }
+
+    async fn delete(&self, session_id: &Id) -> session_store::Result<()> {
+        with_cookie_session(|session| {
+            if session
+                .saved
+                .as_ref()
+                .is_some_and(|(saved_id, _)| saved_id == session_id)
+            {
+                session.saved = None;
+            }
+        })?;
+        Ok(())
+    }
+}
+
+fn sign(key: &SecretKey, message: &str) -> blake3::Hash {
+    let key = blake3::derive_key(COOKIE_STORE_CONTEXT, key.as_bytes());
+    blake3::keyed_hash(&key, message.as_bytes())
+}
+
+fn is_active(expiry_date: OffsetDateTime) -> bool {
+    expiry_date > OffsetDateTime::now_utc()
+}
+
+fn with_cookie_session<T>(f: impl FnOnce(&mut CookieSession) -> T) -> Result<T, CookieStoreError> {
+    COOKIE_SESSION
+        .try_with(|session| {
+            let mut session = session.lock().expect("cookie session lock poisoned");
+            f(&mut session)
+        })
+        .map_err(|_| CookieStoreError::NoCookieContext)
+}
+
+/// The per-request state shared between [`CookieStore`] and the session
+/// middleware.
+///
+/// The session cookie set by [`CookieStore`] has the form
+/// `<session ID>.<base64-encoded record>.<signature>`. The session middleware
+/// only understands plain session IDs, so on the way in, the cookie value is
+/// replaced with the session ID and the full value is kept here for
+/// [`CookieStore::load`]. On the way out, the session ID in the `Set-Cookie`
+/// header is replaced with the value produced by [`CookieStore::save`].
+///
+/// For other session stores, the cookie only ever contains the session ID, so
+/// this is a no-op.
+#[derive(Debug, Default)]
+pub(crate) struct CookieSession {
+    name: String,
+    cookie: Option<String>,
+    saved: Option<(Id, String)>,
+}
+
+impl CookieSession {
+    /// Extracts the session cookie from the request headers, replacing its
+    /// value with the bare session ID.
+    pub(crate) fn from_request_headers(name: &str, headers: &mut http::HeaderMap) -> Self {
+        let mut cookie = None;
+
+        let values: Vec<HeaderValue> = headers.get_all(COOKIE).iter().cloned().collect();
+        if values.is_empty() {
+            return Self {
+                name: name.to_owned(),
+                ..Self::default()
+            };
+        }
+        headers.remove(COOKIE);
+        for value in values {
+            let rewritten = value.to_str().ok().map(|value| {
+                value
+                    .split(';')
+                    .map(|pair| match pair.trim().split_once('=') {
+                        Some((cookie_name, cookie_value)) if cookie_name == name => {
+                            match cookie_value.split_once('.') {
+                                Some((id, _)) => {
+                                    cookie = Some(cookie_value.to_owned());
+                                    format!("{cookie_name}={id}")
+                                }
+                                None => pair.trim().to_owned(),
+                            }
+                        }
+                        _ => pair.trim().to_owned(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            });
+            let value = rewritten
+                .and_then(|rewritten| HeaderValue::from_str(&rewritten).ok())
+                .unwrap_or(value);
+            headers.append(COOKIE, value);
+        }
+
+        Self {
+            name: name.to_owned(),
+            cookie,
+            saved: None,
+        }
+    }
+
+    /// Runs the given future with this state available to [`CookieStore`].
+    pub(crate) async fn scope<F: Future>(self, future: F) -> (F::Output, Self) {
+        let session = Arc::new(Mutex::new(self));
+        let output = COOKIE_SESSION.scope(Arc::clone(&session), future).await;
+        let session = std::mem::take(&mut *session.lock().expect("cookie session lock poisoned"));
+        (output, session)
+    }
+
+    /// Replaces the session ID in the session's `Set-Cookie` header with the
+    /// value saved by [`CookieStore`], if any.
+    pub(crate) fn apply_to_response_headers(self, name: &str, headers: &mut http::HeaderMap) {
+        let Some((id, saved_value)) = self.saved else {
+            return;
+        };
+
+        let prefix = format!("{name}={id}");
+        let values: Vec<HeaderValue> = headers.get_all(SET_COOKIE).iter().cloned().collect();
+        headers.remove(SET_COOKIE);
+        for value in values {
+            let rewritten = value
+                .to_str()
+                .ok()
+                .and_then(|value| value.strip_prefix(&prefix))
+                .filter(|attributes| attributes.is_empty() || attributes.starts_with(';'))
+                .and_then(|attributes| {
+                    HeaderValue::from_str(&format!("{name}={saved_value}{attributes}")).ok()
+                });
+            headers.append(SET_COOKIE, rewritten.unwrap_or(value));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use time::Duration;
+
+    use super::*;
+
+    fn make_store() -> CookieStore {
+        CookieStore::new(SecretKey::from("secret"))
+    }
+
+    fn make_record() -> Record {
+        Record {
+            id: Id::default(),
+            data: HashMap::from([("foo".to_owned(), "bar".into())]),
+            expiry_date: OffsetDateTime::now_utc() + Duration::minutes(30),
+        }
+    }
+
+    async fn load_from_cookie(store: &CookieStore, id: &Id, value: String) -> Option<Record> {
+        let session = CookieSession {
+            cookie: Some(value),
+            ..CookieSession::default()
+        };
+        let (record, _) = session.scope(store.load(id)).await;
+        record.unwrap()
+    }
+
+    #[cot::test]
+    async fn test_save_and_load() {
+        let store = make_store();
+        let mut record = make_record();
+
+        let (result, session) = CookieSession::default()
+            .scope(store.create(&mut record))
+            .await;
+        result.unwrap();
+        let (saved_id, value) = session.saved.unwrap();
+        assert_eq!(saved_id, record.id);
+        assert!(value.starts_with(&format!("{}.", record.id)));
+
+        let loaded = load_from_cookie(&store, &record.id, value).await;
+        assert_eq!(loaded, Some(record));
+    }
+
+    #[cot::test]
+    async fn test_load_tampered() {
+        let store = make_store();
+        let mut record = make_record();
+        let value = store.encode(&record).unwrap();
+
+        record.data.insert("foo".to_owned(), "baz".into());
+        let tampered_data = BASE64_URL_SAFE_NO_PAD.encode(serde_json::to_vec(&record).unwrap());
+        let signature = value.rsplit_once('.').unwrap().1;
+        let tampered = format!("{}.{tampered_data}.{signature}", record.id);
+
+        assert_eq!(load_from_cookie(&store, &record.id, tampered).await, None);
+    }
+
+    #[cot::test]
+    async fn test_load_wrong_key() {
+        let record = make_record();
+        let value = CookieStore::new(SecretKey::from("other"))
+            .encode(&record)
+            .unwrap();
+
+        assert_eq!(
+            load_from_cookie(&make_store(), &record.id, value).await,
+            None
+        );
+    }
+
+    #[cot::test]
+    async fn test_load_fallback_key() {
+        let record = make_record();
+        let value = CookieStore::new(SecretKey::from("old"))
+            .encode(&record)
+            .unwrap();
+        let store = make_store().fallback_secret_keys(vec![SecretKey::from("old")]);
+
+        assert_eq!(
+            load_from_cookie(&store, &record.id, value).await,
+            Some(record)
+        );
+    }
+
+    #[cot::test]
+    async fn test_load_expired() {
+        let store = make_store();
+        let mut record = make_record();
+        record.expiry_date = OffsetDateTime::now_utc() - Duration::minutes(1);
+        let value = store.encode(&record).unwrap();
+
+        assert_eq!(load_from_cookie(&store, &record.id, value).await, None);
+    }
+
+    #[cot::test]
+    async fn test_load_different_id() {
+        let store = make_store();
+        let record = make_record();
+        let value = store.encode(&record).unwrap();
+
+        assert_eq!(load_from_cookie(&store, &Id::default(), value).await, None);
+    }
+
+    #[cot::test]
+    async fn test_save_too_large() {
+        let store = make_store();
+        let mut record = make_record();
+        record
+            .data
+            .insert("large".to_owned(), "x".repeat(MAX_COOKIE_SIZE).into());
+
+        let (result, session) = CookieSession::default().scope(store.save(&record)).await;
+        let error = result.unwrap_err();
+        assert!(matches!(error, session_store::Error::Encode(_)));
+        assert!(
+            error
+                .to_string()
+                .contains("exceeds the maximum cookie size")
+        );
+        assert!(session.saved.is_none());
+    }
+
+    #[cot::test]
+    async fn test_save_too_large_with_name_and_attributes() {
+        let store = make_store();
+        let mut record = make_record();
+        let base_size = store.encode(&record).unwrap().len();
+        // the value alone fits in the limit, but not with the attributes
+        let padding = (MAX_COOKIE_SIZE - COOKIE_ATTRIBUTES_SIZE / 2 - base_size) * 3 / 4;
+        record
+            .data
+            .insert("large".to_owned(), "x".repeat(padding).into());
+        assert!(store.encode(&record).unwrap().len() <= MAX_COOKIE_SIZE);
+
+        let session = CookieSession {
+            name: "id".to_owned(),
+            ..CookieSession::default()
+        };
+        let (result, session) = session.scope(store.save(&record)).await;
+        assert!(matches!(result, Err(session_store::Error::Encode(_))));
+        assert!(session.saved.is_none());
+    }
+
+    #[cot::test]
+    async fn test_delete() {
+        let store = make_store();
+        let record = make_record();
+
+        let (result, session) = CookieSession::default()
+            .scope(async {
+                store.save(&record).await?;
+                store.delete(&record.id).await
+            })
+            .await;
+        result.unwrap();
+        assert!(session.saved.is_none());
+    }
+
+    #[cot::test]
+    async fn test_outside_of_middleware() {
+        let store = make_store();
+        let record = make_record();
+
+        let error = store.save(&record).await.unwrap_err();
+        assert!(matches!(error, session_store::Error::Backend(_)));
+    }
+
+    #[test]
+    fn test_from_request_headers() {
+        let mut headers = http::HeaderMap::new();
+        headers.insert(
+            COOKIE,
+            HeaderValue::from_static("other=a.b; id=session-id.data.signature"),
+        );
+
+        let session = CookieSession::from_request_headers("id", &mut headers);
+
+        assert_eq!(session.cookie.as_deref(), Some("session-id.data.signature"));
+        assert_eq!(headers.get(COOKIE).unwrap(), "other=a.b; id=session-id");
+    }
+
+    #[test]
+    fn test_from_request_headers_plain_id() {
+        let mut headers = http::HeaderMap::new();
+        headers.insert(COOKIE, HeaderValue::from_static("id=session-id"));
+
+        let session = CookieSession::from_request_headers("id", &mut headers);
+
+        assert_eq!(session.cookie, None);
+        assert_eq!(headers.get(COOKIE).unwrap(), "id=session-id");
+    }
+
+    #[test]
+    fn test_apply_to_response_headers() {
+        let id = Id::default();
+        let mut headers = http::HeaderMap::new();
+        headers.append(SET_COOKIE, HeaderValue::from_static("other=value"));
+        headers.append(
+            SET_COOKIE,
+            HeaderValue::from_str(&format!("id={id}; HttpOnly; Path=/")).unwrap(),
+        );
+        let session = CookieSession {
+            saved: Some((id, format!("{id}.data.signature"))),
+            ..CookieSession::default()
+        };
+
+        session.apply_to_response_headers("id", &mut headers);
+
+        let values: Vec<_> = headers.get_all(SET_COOKIE).iter().collect();
+        assert_eq!(values[0], "other=value");
+        assert_eq!(
+            values[1].to_str().unwrap(),
+            format!("id={id}.data.signature; HttpOnly; Path=/")
+        );
+    }
+}
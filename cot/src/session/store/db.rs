@@ -154,7 +154,7 @@ impl SessionStore for DbStore {
             .map_err(|err| DbStoreError::Serialize(Box::new(err)))?;
 
         let query = query!(Session, $key == key)
-            .get(&self.connection)
+            .first(&self.connection)
             .await
             .map_err(DbStoreError::DatabaseError)?;
         if let Some(mut model) = query {
@@ -173,7 +173,7 @@ impl SessionStore for DbStore {
     async fn load(&self, session_id: &Id) -> session_store::Result<Option<Record>> {
         let key = session_id.to_string();
         let query = query!(Session, $key == key)
-            .get(&self.connection)
+            .first(&self.connection)
             .await
             .map_err(DbStoreError::DatabaseError)?;
         if let Some(session) = query {
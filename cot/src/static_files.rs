@@ -3,6 +3,7 @@
 //! This module provides middleware for serving static files from the `static`
 //! directory of the project.
 
+use std::any::Any;
 use std::collections::HashMap;
 use std::future::Future;
 use std::path::Path;
@@ -14,15 +15,16 @@ use std::time::Duration;
 use bytes::Bytes;
 use cot_core::error::impl_into_cot_error;
 use futures_core::ready;
-use http::{Request, header};
+use http::{Method, Request, StatusCode, header};
 use pin_project_lite::pin_project;
 use thiserror::Error;
 use tower::Service;
 
-use crate::Body;
 use crate::config::{StaticFilesConfig, StaticFilesPathRewriteMode};
+use crate::error::{NotFound, NotFoundKind};
 use crate::project::MiddlewareContext;
 use crate::response::{Response, ResponseExt};
+use crate::{Body, Error};
 
 /// Macro to define static files by specifying paths.
 ///
@@ -270,9 +272,51 @@ impl StaticFile {
 /// When a request is made to a path starting with `/static/`, the middleware
 /// checks if the file exists in the static files collection. If it does, the
 /// file is served. Otherwise, the request is passed to the inner service.
+///
+/// For single-page apps, the middleware can also serve an entry file (such
+/// as `index.html`) for the paths that are not handled by the project, so
+/// that the routing can be done on the client side. See
+/// [`spa_fallback`](Self::spa_fallback) for details.
 #[derive(Debug, Clone)]
 pub struct StaticFilesMiddleware {
     static_files: Arc<StaticFiles>,
+    spa_fallback: Arc<SpaFallback>,
+}
+
+/// The configuration of the single-page app fallback of the
+/// [`StaticFilesMiddleware`].
+#[derive(Debug, Clone, Default)]
+struct SpaFallback {
+    entry_file: Option<String>,
+    excluded_prefixes: Vec<String>,
+}
+
+impl SpaFallback {
+    /// Returns whether the request can be answered with the entry file if
+    /// the inner service responds with `404 Not Found`.
+    fn applies_to<B>(&self, request: &Request<B>, static_url_prefix: &str) -> bool {
+        if self.entry_file.is_none() {
+            return false;
+        }
+        if request.method() != Method::GET && request.method() != Method::HEAD {
+            return false;
+        }
+
+        let path = request.uri().path();
+        if path.starts_with(static_url_prefix)
+            || self
+                .excluded_prefixes
+                .iter()
+                .any(|prefix| path.starts_with(prefix.as_str()))
+        {
+            return false;
+        }
+
+        // paths that look like files (e.g. `/missing.js`) are missing assets
+        // rather than client-side routes
+        let last_segment = path.rsplit('/').next().unwrap_or_default();
+        !last_segment.contains('.')
+    }
 }
 
 impl StaticFilesMiddleware {
@@ -280,17 +324,101 @@ impl StaticFilesMiddleware {
     /// context.
     #[must_use]
     pub fn from_context(context: &MiddlewareContext) -> Self {
+        Self::new(Arc::new(StaticFiles::from(context)))
+    }
+
+    fn new(static_files: Arc<StaticFiles>) -> Self {
         Self {
-            static_files: Arc::new(StaticFiles::from(context)),
+            static_files,
+            spa_fallback: Arc::new(SpaFallback::default()),
         }
     }
+
+    /// Enables the single-page app fallback, serving the given static file
+    /// for the client-side routes.
+    ///
+    /// When no route matches the path of a `GET` or `HEAD` request, the entry
+    /// file is served instead, with `200 OK`. The `404 Not Found` responses
+    /// and errors returned by the views of the matched routes are passed
+    /// through unchanged. The fallback is not used for the paths that look like files (i.e., the
+    /// last segment of the path contains a dot, such as `/missing.js`), the
+    /// paths under the static files URL prefix, and the paths excluded with
+    /// [`exclude_from_spa_fallback`](Self::exclude_from_spa_fallback), so
+    /// that the missing assets and API endpoints still respond with `404 Not
+    /// Found`.
+    ///
+    /// The entry file is a path relative to the static files directory, just
+    /// like the paths passed to [`StaticFile::new`]. If there is no such
+    /// static file, the fallback is not used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::Project;
+    /// use cot::project::{MiddlewareContext, RootHandler, RootHandlerBuilder};
+    /// use cot::static_files::StaticFilesMiddleware;
+    ///
+    /// struct MyProject;
+    /// impl Project for MyProject {
+    ///     fn middlewares(
+    ///         &self,
+    ///         handler: RootHandlerBuilder,
+    ///         context: &MiddlewareContext,
+    ///     ) -> RootHandler {
+    ///         handler
+    ///             .middleware(
+    ///                 StaticFilesMiddleware::from_context(context)
+    ///                     .spa_fallback("app/index.html")
+    ///                     .exclude_from_spa_fallback("/api/"),
+    ///             )
+    ///             .build()
+    ///     }
+    /// }
+    /// ```
+    #[must_use]
+    pub fn spa_fallback<P: Into<String>>(mut self, entry_file: P) -> Self {
+        Arc::make_mut(&mut self.spa_fallback).entry_file = Some(entry_file.into());
+        self
+    }
+
+    /// Excludes the paths starting with the given prefix from the
+    /// [single-page app fallback](Self::spa_fallback).
+    ///
+    /// This is typically used for the API endpoints, so that requesting a
+    /// non-existent API endpoint responds with `404 Not Found` instead of
+    /// the entry file of the app.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::project::MiddlewareContext;
+    /// use cot::static_files::StaticFilesMiddleware;
+    ///
+    /// # fn example(context: &MiddlewareContext) {
+    /// let middleware = StaticFilesMiddleware::from_context(context)
+    ///     .spa_fallback("index.html")
+    ///     .exclude_from_spa_fallback("/api/")
+    ///     .exclude_from_spa_fallback("/admin/");
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn exclude_from_spa_fallback<P: Into<String>>(mut self, prefix: P) -> Self {
+        Arc::make_mut(&mut self.spa_fallback)
+            .excluded_prefixes
+            .push(prefix.into());
+        self
+    }
 }
 
 impl<S> tower::Layer<S> for StaticFilesMiddleware {
     type Service = StaticFilesService<S>;
 
     fn layer(&self, inner: S) -> Self::Service {
-        StaticFilesService::new(Arc::clone(&self.static_files), inner)
+        StaticFilesService::new(
+            Arc::clone(&self.static_files),
+            Arc::clone(&self.spa_fallback),
+            inner,
+        )
     }
 }
 
@@ -298,18 +426,34 @@ impl<S> tower::Layer<S> for StaticFilesMiddleware {
 #[derive(Clone, Debug)]
 pub struct StaticFilesService<S> {
     static_files: Arc<StaticFiles>,
+    spa_fallback: Arc<SpaFallback>,
     inner: S,
 }
 
 impl<S> StaticFilesService<S> {
     /// Create a new static files service.
     #[must_use]
-    fn new(static_files: Arc<StaticFiles>, inner: S) -> Self {
+    fn new(static_files: Arc<StaticFiles>, spa_fallback: Arc<SpaFallback>, inner: S) -> Self {
         Self {
             static_files,
+            spa_fallback,
             inner,
         }
     }
+
+    fn spa_fallback_response<B>(&self, request: &Request<B>) -> Option<Response> {
+        if !self
+            .spa_fallback
+            .applies_to(request, &self.static_files.url_prefix)
+        {
+            return None;
+        }
+
+        let entry_file = self.spa_fallback.entry_file.as_deref()?;
+        self.static_files
+            .get_file(entry_file)
+            .map(StaticFile::as_response)
+    }
 }
 
 impl<ReqBody, S> Service<Request<ReqBody>> for StaticFilesService<S>
@@ -346,9 +490,11 @@ where
             }
             ResponseFuture::StaticFileResponse { response }
         } else {
+            let fallback = self.spa_fallback_response(&req);
             req.extensions_mut().insert(Arc::clone(&self.static_files));
             ResponseFuture::Inner {
                 future: self.inner.call(req),
+                fallback,
             }
         }
     }
@@ -369,6 +515,9 @@ pin_project! {
             // The inner service's future.
             #[pin]
             future: F,
+            // The response to use if the router doesn't match the request
+            // path.
+            fallback: Option<Response>,
         },
     }
 }
@@ -376,6 +525,7 @@ pin_project! {
 impl<F, E> Future for ResponseFuture<F>
 where
     F: Future<Output = Result<Response, E>>,
+    E: 'static,
 {
     type Output = F::Output;
 
@@ -386,9 +536,23 @@ where
             ResponseFutureProj::StaticFileResponse { response } => {
                 Poll::Ready(Ok(std::mem::take(response)))
             }
-            ResponseFutureProj::Inner { future } => {
-                let res = ready!(future.poll(cx)?);
-                Poll::Ready(Ok(res))
+            ResponseFutureProj::Inner { future, fallback } => {
+                let res = ready!(future.poll(cx));
+                // only the paths not matched by the router fall back to the
+                // entry file; the 404s returned by the views are kept
+                let unmatched = match &res {
+                    Ok(_) => false,
+                    Err(error) => (error as &dyn Any)
+                        .downcast_ref::<Error>()
+                        .and_then(|error| error.inner().downcast_ref::<NotFound>())
+                        .is_some_and(|not_found| {
+                            matches!(not_found.kind, NotFoundKind::FromRouter)
+                        }),
+                };
+                if unmatched && let Some(fallback) = fallback.take() {
+                    return Poll::Ready(Ok(fallback));
+                }
+                Poll::Ready(res)
             }
         }
     }
@@ -448,9 +612,7 @@ mod tests {
     #[cot::test]
     async fn static_files_middleware() {
         let static_files = Arc::new(create_static_files());
-        let middleware = StaticFilesMiddleware {
-            static_files: Arc::clone(&static_files),
-        };
+        let middleware = StaticFilesMiddleware::new(Arc::clone(&static_files));
 
         let service = middleware.layer(tower::service_fn(|_req| async {
             Ok::<_, std::convert::Infallible>(Response::new(Body::empty()))
@@ -483,9 +645,7 @@ mod tests {
         static_files.add_file(StaticFile::new("test.txt", "This is a test file"));
         let static_files = Arc::new(static_files);
 
-        let middleware = StaticFilesMiddleware {
-            static_files: Arc::clone(&static_files),
-        };
+        let middleware = StaticFilesMiddleware::new(Arc::clone(&static_files));
 
         let service = middleware.layer(tower::service_fn(|_req| async {
             Ok::<_, std::convert::Infallible>(Response::new(Body::empty()))
@@ -508,9 +668,7 @@ mod tests {
     #[cot::test]
     async fn static_files_middleware_not_found() {
         let static_files = Arc::new(create_static_files());
-        let middleware = StaticFilesMiddleware {
-            static_files: Arc::clone(&static_files),
-        };
+        let middleware = StaticFilesMiddleware::new(Arc::clone(&static_files));
         let service = middleware.layer(tower::service_fn(|_req| async {
             Ok::<_, std::convert::Infallible>(Response::new(Body::fixed("test")))
         }));
@@ -529,6 +687,122 @@ mod tests {
         );
     }
 
+    fn create_spa_service()
+    -> impl Service<Request<Body>, Response = Response, Error = Error> + Clone {
+        let mut static_files = create_static_files();
+        static_files.add_file(StaticFile::new("index.html", "<div id=\"app\"></div>"));
+        let middleware = StaticFilesMiddleware::new(Arc::new(static_files))
+            .spa_fallback("index.html")
+            .exclude_from_spa_fallback("/api/");
+
+        middleware.layer(tower::service_fn(|req: Request<Body>| async move {
+            match req.uri().path() {
+                "/" => Ok(Response::new(Body::fixed("home"))),
+                "/error-page" => Ok(Response::builder()
+                    .status(StatusCode::NOT_FOUND)
+                    .body(Body::fixed("not found"))
+                    .unwrap()),
+                "/missing-item" => Err(Error::from(crate::error::NotFound::with_message(
+                    "item not found",
+                ))),
+                _ => Err(Error::from(crate::error::NotFound::router())),
+            }
+        }))
+    }
+
+    async fn spa_request(
+        service: impl Service<Request<Body>, Response = Response, Error = Error>,
+        method: http::Method,
+        uri: &str,
+    ) -> crate::Result<Response> {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap();
+        service.oneshot(request).await
+    }
+
+    #[cot::test]
+    async fn static_files_middleware_spa_fallback() {
+        let service = create_spa_service();
+
+        let response = spa_request(service.clone(), http::Method::GET, "/app/some/route")
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "text/html");
+        assert_eq!(
+            response.into_body().into_bytes().await.unwrap(),
+            Bytes::from("<div id=\"app\"></div>")
+        );
+
+        // the 404 responses returned by the matched routes are kept
+        let response = spa_request(service.clone(), http::Method::GET, "/error-page")
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            response.into_body().into_bytes().await.unwrap(),
+            Bytes::from("not found")
+        );
+        let error = spa_request(service.clone(), http::Method::GET, "/missing-item")
+            .await
+            .unwrap_err();
+        assert_eq!(error.status_code(), StatusCode::NOT_FOUND);
+
+        // the matched routes are not affected
+        let response = spa_request(service, http::Method::GET, "/").await.unwrap();
+        assert_eq!(
+            response.into_body().into_bytes().await.unwrap(),
+            Bytes::from("home")
+        );
+    }
+
+    #[cot::test]
+    async fn static_files_middleware_spa_fallback_not_applied() {
+        let service = create_spa_service();
+
+        for uri in [
+            "/missing.js",
+            "/app/missing.css",
+            "/api/items",
+            "/static/missing",
+        ] {
+            let error = spa_request(service.clone(), http::Method::GET, uri)
+                .await
+                .unwrap_err();
+            assert_eq!(error.status_code(), StatusCode::NOT_FOUND, "{uri}");
+        }
+
+        let error = spa_request(service, http::Method::POST, "/app/some/route")
+            .await
+            .unwrap_err();
+        assert_eq!(error.status_code(), StatusCode::NOT_FOUND);
+    }
+
+    #[cot::test]
+    async fn static_files_middleware_spa_fallback_missing_entry_file() {
+        let middleware =
+            StaticFilesMiddleware::new(Arc::new(create_static_files())).spa_fallback("index.html");
+        let service = middleware.layer(tower::service_fn(|_req| async {
+            Ok::<_, std::convert::Infallible>(
+                Response::builder()
+                    .status(StatusCode::NOT_FOUND)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+        }));
+
+        let request = Request::builder()
+            .uri("/app/some/route")
+            .body(Body::empty())
+            .unwrap();
+        let response = service.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
     #[cot::test]
     #[cfg_attr(
         miri,
@@ -568,6 +842,7 @@ mod tests {
         let bootstrapper = Bootstrapper::new(TestProject)
             .with_config(ProjectConfig::default())
             .with_apps()
+            .unwrap()
             .with_database()
             .await
             .unwrap()
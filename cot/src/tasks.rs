@@ -0,0 +1,656 @@
+//! Background tasks running on a schedule.
+//!
+//! Apps can define tasks that are run periodically while the server is
+//! running, such as cleaning up expired sessions or sending email digests.
+//! The tasks are returned by [`App::tasks`](crate::App::tasks), started when
+//! the server starts, and stopped when the server shuts down gracefully.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! use cot::App;
+//! use cot::project::ProjectContext;
+//! use cot::tasks::{RetryPolicy, Schedule, Task};
+//!
+//! async fn cleanup(context: Arc<ProjectContext>) -> cot::Result<()> {
+//!     // use the database, cache, etc. from the context
+//!     Ok(())
+//! }
+//!
+//! async fn daily_digest(context: Arc<ProjectContext>) -> cot::Result<()> {
+//!     Ok(())
+//! }
+//!
+//! struct MyApp;
+//! impl App for MyApp {
+//!     fn name(&self) -> &str {
+//!         "my_app"
+//!     }
+//!
+//!     fn tasks(&self) -> Vec<Task> {
+//!         vec![
+//!             Task::new(
+//!                 "cleanup",
+//!                 Schedule::interval(Duration::from_secs(15 * 60)),
+//!                 cleanup,
+//!             ),
+//!             Task::new(
+//!                 "daily_digest",
+//!                 Schedule::cron("0 6 * * *").unwrap(),
+//!                 daily_digest,
+//!             )
+//!             .retry_policy(RetryPolicy::new(3, Duration::from_secs(60))),
+//!         ]
+//!     }
+//! }
+//! ```
+
+mod cron;
+
+use std::fmt::Display;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub use cron::{CronParseError, CronSchedule};
+use derive_more::with_trait::Debug;
+use futures_core::future::BoxFuture;
+use futures_util::FutureExt;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+use crate::error::UncaughtPanic;
+use crate::project::ProjectContext;
+
+type TaskJob =
+    Box<dyn Fn(Arc<ProjectContext>) -> BoxFuture<'static, crate::Result<()>> + Send + Sync>;
+
+/// A background task run on a schedule.
+///
+/// The task runs the given async function every time the schedule fires. The
+/// function gets the [`ProjectContext`], so it can use the database, the
+/// cache, and other project-wide resources.
+///
+/// The runs of a single task never overlap: the next run is scheduled only
+/// after the previous one (including its retries) has finished. If the task
+/// returns an error (or panics), the error is logged and the task is retried
+/// according to its [`RetryPolicy`]; the task keeps being scheduled either
+/// way.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// use cot::project::ProjectContext;
+/// use cot::tasks::{Schedule, Task};
+///
+/// async fn cleanup(context: Arc<ProjectContext>) -> cot::Result<()> {
+///     Ok(())
+/// }
+///
+/// let task = Task::new(
+///     "cleanup",
+///     Schedule::interval(Duration::from_secs(60)),
+///     cleanup,
+/// );
+/// ```
+#[derive(Debug)]
+pub struct Task {
+    name: String,
+    schedule: Schedule,
+    retry_policy: RetryPolicy,
+    #[debug("..")]
+    job: TaskJob,
+}
+
+impl Task {
+    /// Creates a new task with the given name, schedule and function to run.
+    ///
+    /// The name is used to identify the task in the logs. By default, the
+    /// task is not retried on failure; use [`Self::retry_policy`] to change
+    /// this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// use cot::project::ProjectContext;
+    /// use cot::tasks::{Schedule, Task};
+    ///
+    /// async fn cleanup(context: Arc<ProjectContext>) -> cot::Result<()> {
+    ///     Ok(())
+    /// }
+    ///
+    /// let task = Task::new(
+    ///     "cleanup",
+    ///     Schedule::interval(Duration::from_secs(60)),
+    ///     cleanup,
+    /// );
+    /// ```
+    #[must_use]
+    pub fn new<F, Fut>(name: impl Into<String>, schedule: Schedule, job: F) -> Self
+    where
+        F: Fn(Arc<ProjectContext>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = crate::Result<()>> + Send + 'static,
+    {
+        Self {
+            name: name.into(),
+            schedule,
+            retry_policy: RetryPolicy::none(),
+            job: Box::new(move |context| Box::pin(job(context))),
+        }
+    }
+
+    /// Sets the policy for retrying the task when it fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// use cot::project::ProjectContext;
+    /// use cot::tasks::{RetryPolicy, Schedule, Task};
+    ///
+    /// async fn cleanup(context: Arc<ProjectContext>) -> cot::Result<()> {
+    ///     Ok(())
+    /// }
+    ///
+    /// let task = Task::new(
+    ///     "cleanup",
+    ///     Schedule::interval(Duration::from_secs(60)),
+    ///     cleanup,
+    /// )
+    /// .retry_policy(RetryPolicy::new(3, Duration::from_secs(1)));
+    /// ```
+    #[must_use]
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Returns the name of the task.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// use cot::project::ProjectContext;
+    /// use cot::tasks::{Schedule, Task};
+    ///
+    /// async fn cleanup(context: Arc<ProjectContext>) -> cot::Result<()> {
+    ///     Ok(())
+    /// }
+    ///
+    /// let task = Task::new(
+    ///     "cleanup",
+    ///     Schedule::interval(Duration::from_secs(60)),
+    ///     cleanup,
+    /// );
+    /// assert_eq!(task.name(), "cleanup");
+    /// ```
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the schedule of the task.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// use cot::project::ProjectContext;
+    /// use cot::tasks::{Schedule, Task};
+    ///
+    /// async fn cleanup(context: Arc<ProjectContext>) -> cot::Result<()> {
+    ///     Ok(())
+    /// }
+    ///
+    /// let task = Task::new(
+    ///     "cleanup",
+    ///     Schedule::interval(Duration::from_secs(60)),
+    ///     cleanup,
+    /// );
+    /// assert_eq!(task.schedule(), &Schedule::interval(Duration::from_secs(60)));
+    /// ```
+    #[must_use]
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+}
+
+/// When a [`Task`] should run.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use cot::tasks::Schedule;
+///
+/// let every_minute = Schedule::interval(Duration::from_secs(60));
+/// let every_night = Schedule::cron("0 3 * * *").unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule(ScheduleInner);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ScheduleInner {
+    Interval(Duration),
+    Cron(CronSchedule),
+}
+
+impl Schedule {
+    /// Runs the task repeatedly with the given interval.
+    ///
+    /// The interval is measured from the end of the previous run, and the
+    /// first run happens one interval after the server starts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the interval is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::tasks::Schedule;
+    ///
+    /// let schedule = Schedule::interval(Duration::from_secs(60));
+    /// ```
+    #[must_use]
+    pub fn interval(interval: Duration) -> Self {
+        assert!(!interval.is_zero(), "task interval must not be zero");
+        Self(ScheduleInner::Interval(interval))
+    }
+
+    /// Runs the task at the times matching the given cron expression,
+    /// evaluated in UTC. See [`CronSchedule`] for the supported syntax.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cron expression is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::tasks::Schedule;
+    ///
+    /// let schedule = Schedule::cron("*/5 * * * *")?;
+    /// # Ok::<(), cot::tasks::CronParseError>(())
+    /// ```
+    pub fn cron(expression: &str) -> Result<Self, CronParseError> {
+        Ok(Self(ScheduleInner::Cron(expression.parse()?)))
+    }
+
+    /// Returns how long to wait for the next run, or [`None`] if the task
+    /// should never run again.
+    fn next_delay(&self) -> Option<Duration> {
+        match &self.0 {
+            ScheduleInner::Interval(interval) => Some(*interval),
+            ScheduleInner::Cron(cron) => {
+                let now = chrono::Utc::now();
+                let next = cron.next_after(now)?;
+                Some((next - now).to_std().unwrap_or_default())
+            }
+        }
+    }
+}
+
+impl From<CronSchedule> for Schedule {
+    fn from(cron: CronSchedule) -> Self {
+        Self(ScheduleInner::Cron(cron))
+    }
+}
+
+impl Display for Schedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            ScheduleInner::Interval(interval) => write!(f, "every {interval:?}"),
+            ScheduleInner::Cron(cron) => write!(f, "cron `{cron}`"),
+        }
+    }
+}
+
+/// How a failed [`Task`] is retried.
+///
+/// The task is retried up to the given number of times, waiting between the
+/// attempts. The delay starts at the initial delay and doubles after each
+/// attempt, up to the maximum delay (1 hour by default). If all the retries
+/// fail, the task waits for its next scheduled run.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use cot::tasks::RetryPolicy;
+///
+/// // retry after 1, 2, and 4 seconds
+/// let policy = RetryPolicy::new(3, Duration::from_secs(1));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    initial_delay: Duration,
+    max_delay: Duration,
+}
+
+impl RetryPolicy {
+    const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(60 * 60);
+
+    /// A policy that never retries the task. This is the default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::tasks::RetryPolicy;
+    ///
+    /// assert_eq!(RetryPolicy::none(), RetryPolicy::default());
+    /// ```
+    #[must_use]
+    pub const fn none() -> Self {
+        Self {
+            max_retries: 0,
+            initial_delay: Duration::ZERO,
+            max_delay: Self::DEFAULT_MAX_DELAY,
+        }
+    }
+
+    /// A policy that retries the task up to `max_retries` times, with an
+    /// exponential backoff starting at `initial_delay`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::tasks::RetryPolicy;
+    ///
+    /// let policy = RetryPolicy::new(3, Duration::from_secs(1));
+    /// ```
+    #[must_use]
+    pub const fn new(max_retries: u32, initial_delay: Duration) -> Self {
+        Self {
+            max_retries,
+            initial_delay,
+            max_delay: Self::DEFAULT_MAX_DELAY,
+        }
+    }
+
+    /// Sets the maximum delay between the attempts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use cot::tasks::RetryPolicy;
+    ///
+    /// let policy =
+    ///     RetryPolicy::new(10, Duration::from_secs(1)).max_delay(Duration::from_secs(60));
+    /// ```
+    #[must_use]
+    pub const fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Returns the delay before the retry following the given failed attempt
+    /// (counting from 0), or [`None`] if the task should not be retried.
+    pub(crate) fn delay(&self, attempt: u32) -> Option<Duration> {
+        if attempt >= self.max_retries {
+            return None;
+        }
+
+        let multiplier = 2_u32.checked_pow(attempt).unwrap_or(u32::MAX);
+        Some(
+            self.initial_delay
+                .saturating_mul(multiplier)
+                .min(self.max_delay),
+        )
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+/// Runs the tasks of all the apps in the background.
+#[derive(Debug)]
+pub(crate) struct TaskRunner {
+    shutdown: watch::Sender<bool>,
+    handles: Vec<JoinHandle<()>>,
+}
+
+impl TaskRunner {
+    /// Spawns the tasks of all the apps in the project.
+    pub(crate) fn start(context: &Arc<ProjectContext>) -> Self {
+        let (shutdown, _) = watch::channel(false);
+        let handles = context
+            .apps()
+            .iter()
+            .flat_map(|app| app.tasks())
+            .map(|task| {
+                info!(task = task.name, schedule = %task.schedule, "Scheduling task");
+                tokio::spawn(run_task(task, Arc::clone(context), shutdown.subscribe()))
+            })
+            .collect();
+
+        Self { shutdown, handles }
+    }
+
+    /// Stops scheduling the tasks and waits for the runs in progress to
+    /// finish.
+    pub(crate) async fn shutdown(self) {
+        self.shutdown.send_replace(true);
+        for handle in self.handles {
+            if let Err(error) = handle.await {
+                error!(%error, "Task runner failed");
+            }
+        }
+    }
+}
+
+async fn run_task(task: Task, context: Arc<ProjectContext>, mut shutdown: watch::Receiver<bool>) {
+    loop {
+        let Some(delay) = task.schedule.next_delay() else {
+            warn!(
+                task = task.name,
+                "The task schedule has no more occurrences; stopping the task"
+            );
+            return;
+        };
+        if !sleep_unless_shutdown(delay, &mut shutdown).await {
+            return;
+        }
+
+        let mut attempt = 0;
+        loop {
+            debug!(task = task.name, attempt, "Running task");
+            let context = Arc::clone(&context);
+            // the async block makes sure that the panics in the function
+            // creating the future are caught as well
+            let result = AssertUnwindSafe(async { (task.job)(context).await })
+                .catch_unwind()
+                .await
+                .unwrap_or_else(|payload| Err(UncaughtPanic::new(payload).into()));
+
+            let Err(error) = result else {
+                break;
+            };
+            let Some(retry_delay) = task.retry_policy.delay(attempt) else {
+                error!(task = task.name, %error, "Task failed");
+                break;
+            };
+            warn!(
+                task = task.name,
+                %error,
+                ?retry_delay,
+                "Task failed; retrying"
+            );
+            if !sleep_unless_shutdown(retry_delay, &mut shutdown).await {
+                return;
+            }
+            attempt += 1;
+        }
+    }
+}
+
+/// Sleeps for the given duration, returning `false` if the shutdown was
+/// requested in the meantime.
+async fn sleep_unless_shutdown(duration: Duration, shutdown: &mut watch::Receiver<bool>) -> bool {
+    tokio::select! {
+        () = tokio::time::sleep(duration) => true,
+        _ = shutdown.wait_for(|&shutdown| shutdown) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::test::TestRequestBuilder;
+
+    fn test_context() -> Arc<ProjectContext> {
+        let request = TestRequestBuilder::get("/").build();
+        Arc::clone(
+            request
+                .extensions()
+                .get::<Arc<ProjectContext>>()
+                .expect("test request should contain the context"),
+        )
+    }
+
+    fn counting_task(schedule: Schedule, fail_times: usize) -> (Task, Arc<AtomicUsize>) {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let task = {
+            let runs = Arc::clone(&runs);
+            Task::new("test", schedule, move |_context| {
+                let run = runs.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if run < fail_times {
+                        Err(crate::Error::internal("task failed"))
+                    } else {
+                        Ok(())
+                    }
+                }
+            })
+        };
+        (task, runs)
+    }
+
+    #[cot::test]
+    async fn task_runs_periodically() {
+        let (task, runs) = counting_task(Schedule::interval(Duration::from_millis(10)), 0);
+        let (shutdown, shutdown_receiver) = watch::channel(false);
+        let handle = tokio::spawn(run_task(task, test_context(), shutdown_receiver));
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        shutdown.send_replace(true);
+        handle.await.unwrap();
+
+        assert!(runs.load(Ordering::SeqCst) >= 2);
+    }
+
+    #[cot::test]
+    async fn task_retried_on_failure() {
+        let (task, runs) = counting_task(Schedule::interval(Duration::from_millis(1)), 2);
+        let task = task.retry_policy(RetryPolicy::new(5, Duration::from_millis(1)));
+        let (shutdown, shutdown_receiver) = watch::channel(false);
+        let handle = tokio::spawn(run_task(task, test_context(), shutdown_receiver));
+
+        // two failures followed by a successful retry
+        while runs.load(Ordering::SeqCst) < 3 {
+            tokio::task::yield_now().await;
+        }
+        shutdown.send_replace(true);
+        handle.await.unwrap();
+    }
+
+    #[cot::test]
+    async fn task_panic_does_not_stop_scheduling() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let task = {
+            let runs = Arc::clone(&runs);
+            Task::new(
+                "panicking",
+                Schedule::interval(Duration::from_millis(1)),
+                move |_context| -> std::future::Ready<crate::Result<()>> {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                    panic!("task panicked");
+                },
+            )
+        };
+        let (shutdown, shutdown_receiver) = watch::channel(false);
+        let handle = tokio::spawn(run_task(task, test_context(), shutdown_receiver));
+
+        while runs.load(Ordering::SeqCst) < 2 {
+            tokio::task::yield_now().await;
+        }
+        shutdown.send_replace(true);
+        handle.await.unwrap();
+    }
+
+    #[cot::test]
+    async fn task_shutdown_interrupts_wait() {
+        let (task, runs) = counting_task(Schedule::interval(Duration::from_secs(3600)), 0);
+        let (shutdown, shutdown_receiver) = watch::channel(false);
+        let handle = tokio::spawn(run_task(task, test_context(), shutdown_receiver));
+
+        shutdown.send_replace(true);
+        tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .expect("task should stop on shutdown")
+            .unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn retry_policy_delay() {
+        let policy = RetryPolicy::new(4, Duration::from_secs(1)).max_delay(Duration::from_secs(5));
+
+        assert_eq!(policy.delay(0), Some(Duration::from_secs(1)));
+        assert_eq!(policy.delay(1), Some(Duration::from_secs(2)));
+        assert_eq!(policy.delay(2), Some(Duration::from_secs(4)));
+        assert_eq!(policy.delay(3), Some(Duration::from_secs(5)));
+        assert_eq!(policy.delay(4), None);
+        assert_eq!(RetryPolicy::none().delay(0), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "task interval must not be zero")]
+    fn schedule_zero_interval() {
+        let _ = Schedule::interval(Duration::ZERO);
+    }
+
+    #[test]
+    fn schedule_display() {
+        assert_eq!(
+            Schedule::interval(Duration::from_secs(5)).to_string(),
+            "every 5s"
+        );
+        assert_eq!(
+            Schedule::cron("0 * * * *").unwrap().to_string(),
+            "cron `0 * * * *`"
+        );
+    }
+}
@@ -0,0 +1,392 @@
+//! Cron expressions for scheduling tasks.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Timelike, Utc};
+use thiserror::Error;
+
+/// How many years ahead to look for the next occurrence before giving up.
+///
+/// Every valid expression matching any date has an occurrence within this
+/// period (the rarest being February 29th, which occurs every 4 years, or 8
+/// years around the years not divisible by 400).
+const MAX_YEARS_AHEAD: i32 = 9;
+
+/// A parsed cron expression.
+///
+/// The expression consists of five whitespace-separated fields: minute
+/// (0-59), hour (0-23), day of month (1-31), month (1-12 or `JAN`-`DEC`) and
+/// day of week (0-7 or `SUN`-`SAT`, where both 0 and 7 mean Sunday). Each
+/// field can be:
+///
+/// * `*`, matching any value,
+/// * a single value, such as `5`,
+/// * a range, such as `1-5`,
+/// * a step, such as `*/15`, `0-30/10`, or `5/20` (starting at 5),
+/// * a comma-separated list of any of the above, such as `0,30` or `1-5,10`.
+///
+/// As in the traditional cron, if both the day of month and the day of week
+/// are restricted (i.e., not `*`), a day matches if either of them matches.
+///
+/// The shorthands `@yearly` (or `@annually`), `@monthly`, `@weekly`, `@daily`
+/// (or `@midnight`) and `@hourly` are supported as well.
+///
+/// The expressions are evaluated in UTC.
+///
+/// # Examples
+///
+/// ```
+/// use cot::tasks::CronSchedule;
+///
+/// // every day at 3:30 AM UTC
+/// let schedule: CronSchedule = "30 3 * * *".parse().unwrap();
+/// // every 15 minutes during the working hours on weekdays
+/// let schedule: CronSchedule = "*/15 9-17 * * MON-FRI".parse().unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+impl CronSchedule {
+    /// Returns the first time matching the expression that is strictly after
+    /// the given time, or [`None`] if there is no such time (for instance,
+    /// for `0 0 30 2 *`, which would only match February 30th).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use cot::tasks::CronSchedule;
+    ///
+    /// let schedule: CronSchedule = "0 12 * * *".parse().unwrap();
+    /// let now = Utc.with_ymd_and_hms(2025, 1, 1, 13, 0, 0).unwrap();
+    ///
+    /// assert_eq!(
+    ///     schedule.next_after(now),
+    ///     Some(Utc.with_ymd_and_hms(2025, 1, 2, 12, 0, 0).unwrap())
+    /// );
+    /// ```
+    #[must_use]
+    pub fn next_after(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut time = time
+            .with_second(0)
+            .and_then(|time| time.with_nanosecond(0))
+            .expect("zero seconds and nanoseconds are always valid")
+            + Duration::minutes(1);
+        let max_year = time.year() + MAX_YEARS_AHEAD;
+
+        while time.year() <= max_year {
+            if !contains(self.months, time.month()) {
+                time = start_of_next_month(time);
+            } else if !self.day_matches(time) {
+                time = start_of_day(time) + Duration::days(1);
+            } else if !contains(self.hours, time.hour()) {
+                time = time.with_minute(0).expect("zero minutes are always valid")
+                    + Duration::hours(1);
+            } else if !contains(self.minutes, time.minute()) {
+                time += Duration::minutes(1);
+            } else {
+                return Some(time);
+            }
+        }
+
+        None
+    }
+
+    fn day_matches(&self, time: DateTime<Utc>) -> bool {
+        let day_of_month = contains(self.days_of_month, time.day());
+        let day_of_week = contains(self.days_of_week, time.weekday().num_days_from_sunday());
+
+        match (self.day_of_month_restricted, self.day_of_week_restricted) {
+            (true, true) => day_of_month || day_of_week,
+            _ => day_of_month && day_of_week,
+        }
+    }
+}
+
+fn contains(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+fn start_of_day(time: DateTime<Utc>) -> DateTime<Utc> {
+    time.with_time(NaiveTime::MIN)
+        .single()
+        .expect("midnight always exists in UTC")
+}
+
+fn start_of_next_month(time: DateTime<Utc>) -> DateTime<Utc> {
+    let time = start_of_day(time);
+    let (year, month) = if time.month() == 12 {
+        (time.year() + 1, 1)
+    } else {
+        (time.year(), time.month() + 1)
+    };
+    time.with_day(1)
+        .and_then(|time| time.with_month(month))
+        .and_then(|time| time.with_year(year))
+        .expect("the first day of a month is always valid")
+}
+
+impl FromStr for CronSchedule {
+    type Err = CronParseError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let expanded = match expression.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other => other,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minutes, hours, days_of_month, months, days_of_week] = fields[..] else {
+            return Err(CronParseError::new(
+                expression,
+                format!("expected 5 fields, found {}", fields.len()),
+            ));
+        };
+
+        let parse = |field: &str, kind| {
+            parse_field(field, kind).map_err(|message| CronParseError::new(expression, message))
+        };
+        let mut days_of_week_set = parse(days_of_week, FieldKind::DayOfWeek)?;
+        // both 0 and 7 mean Sunday
+        if contains(days_of_week_set, 7) {
+            days_of_week_set = (days_of_week_set | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            expression: expression.trim().to_owned(),
+            minutes: parse(minutes, FieldKind::Minute)?,
+            hours: parse(hours, FieldKind::Hour)?,
+            days_of_month: parse(days_of_month, FieldKind::DayOfMonth)?,
+            months: parse(months, FieldKind::Month)?,
+            days_of_week: days_of_week_set,
+            day_of_month_restricted: days_of_month != "*",
+            day_of_week_restricted: days_of_week != "*",
+        })
+    }
+}
+
+impl Display for CronSchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+enum FieldKind {
+    Minute,
+    Hour,
+    DayOfMonth,
+    Month,
+    DayOfWeek,
+}
+
+impl FieldKind {
+    fn range(self) -> (u32, u32) {
+        match self {
+            Self::Minute => (0, 59),
+            Self::Hour => (0, 23),
+            Self::DayOfMonth => (1, 31),
+            Self::Month => (1, 12),
+            Self::DayOfWeek => (0, 7),
+        }
+    }
+
+    fn names(self) -> &'static [&'static str] {
+        match self {
+            Self::Month => &[
+                "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+            ],
+            Self::DayOfWeek => &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"],
+            _ => &[],
+        }
+    }
+
+    fn parse_value(self, value: &str) -> Result<u32, String> {
+        let (min, max) = self.range();
+        let value = if let Some(index) = self
+            .names()
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(value))
+        {
+            // the names start at the minimum value (`JAN` is 1, `SUN` is 0)
+            min + u32::try_from(index).expect("there are at most 12 names")
+        } else {
+            value
+                .parse::<u32>()
+                .map_err(|_| format!("invalid value `{value}`"))?
+        };
+
+        if (min..=max).contains(&value) {
+            Ok(value)
+        } else {
+            Err(format!("value `{value}` out of range {min}-{max}"))
+        }
+    }
+}
+
+fn parse_field(field: &str, kind: FieldKind) -> Result<u64, String> {
+    let (min, max) = kind.range();
+    let mut set = 0;
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step = step
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|&step| step > 0)
+                    .ok_or_else(|| format!("invalid step `{step}`"))?;
+                (range, Some(step))
+            }
+            None => (part, None),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (kind.parse_value(start)?, kind.parse_value(end)?)
+        } else {
+            let value = kind.parse_value(range)?;
+            // `5/20` means "every 20, starting at 5"
+            (value, if step.is_some() { max } else { value })
+        };
+        if start > end {
+            return Err(format!("invalid range `{range}`"));
+        }
+
+        let mut value = start;
+        while value <= end {
+            set |= 1 << value;
+            value += step.unwrap_or(1);
+        }
+    }
+
+    Ok(set)
+}
+
+/// An error returned when parsing an invalid cron expression.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid cron expression `{expression}`: {message}")]
+pub struct CronParseError {
+    expression: String,
+    message: String,
+}
+
+impl CronParseError {
+    fn new(expression: &str, message: String) -> Self {
+        Self {
+            expression: expression.to_owned(),
+            message,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn time(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn next(expression: &str, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        expression
+            .parse::<CronSchedule>()
+            .unwrap()
+            .next_after(after)
+    }
+
+    #[test]
+    fn cron_every_minute() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 10, 15, 30).unwrap();
+
+        assert_eq!(next("* * * * *", now), Some(time(2025, 3, 1, 10, 16)));
+    }
+
+    #[test]
+    fn cron_steps_and_ranges() {
+        let now = time(2025, 3, 1, 10, 15);
+
+        assert_eq!(next("*/20 * * * *", now), Some(time(2025, 3, 1, 10, 20)));
+        assert_eq!(next("5/20 * * * *", now), Some(time(2025, 3, 1, 10, 25)));
+        assert_eq!(next("0,30 8-9 * * *", now), Some(time(2025, 3, 2, 8, 0)));
+    }
+
+    #[test]
+    fn cron_month_rollover() {
+        assert_eq!(
+            next("0 0 1 * *", time(2025, 12, 15, 0, 0)),
+            Some(time(2026, 1, 1, 0, 0))
+        );
+        assert_eq!(
+            next("@yearly", time(2025, 1, 1, 0, 0)),
+            Some(time(2026, 1, 1, 0, 0))
+        );
+    }
+
+    #[test]
+    fn cron_day_of_week() {
+        // 2025-03-01 is a Saturday
+        let now = time(2025, 3, 1, 12, 0);
+
+        assert_eq!(next("0 9 * * MON-FRI", now), Some(time(2025, 3, 3, 9, 0)));
+        assert_eq!(next("0 9 * * 7", now), Some(time(2025, 3, 2, 9, 0)));
+        // either the day of month or the day of week matches
+        assert_eq!(next("0 9 15 * 1", now), Some(time(2025, 3, 3, 9, 0)));
+    }
+
+    #[test]
+    fn cron_leap_day() {
+        assert_eq!(
+            next("0 0 29 FEB *", time(2025, 3, 1, 0, 0)),
+            Some(time(2028, 2, 29, 0, 0))
+        );
+        assert_eq!(next("0 0 30 2 *", time(2025, 3, 1, 0, 0)), None);
+    }
+
+    #[test]
+    fn cron_invalid() {
+        for expression in [
+            "",
+            "* * * *",
+            "* * * * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+        ] {
+            assert!(
+                expression.parse::<CronSchedule>().is_err(),
+                "`{expression}` should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn cron_display() {
+        let schedule: CronSchedule = " @daily ".parse().unwrap();
+
+        assert_eq!(schedule.to_string(), "@daily");
+    }
+}
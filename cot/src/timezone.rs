@@ -0,0 +1,504 @@
+//! Displaying datetimes in the users' timezones.
+//!
+//! Datetimes are typically stored in UTC, but should be displayed to the users
+//! in their own timezones. The [`TimezoneMiddleware`] resolves the timezone of
+//! each request from a cookie (`timezone` by default) containing an IANA
+//! timezone name, such as `Europe/Warsaw`, falling back to a default timezone.
+//! The resolved timezone is available through the [`UserTimezone`] extractor
+//! and [`RequestExt::timezone`](crate::request::RequestExt::timezone).
+//!
+//! If the users choose their timezone in their profile instead, you can create
+//! a [`UserTimezone`] from the stored timezone name with
+//! [`UserTimezone::from_name`].
+//!
+//! The conversions use the IANA timezone database, so the daylight saving time
+//! transitions are handled correctly.
+//!
+//! # Examples
+//!
+//! ```
+//! use chrono::{DateTime, Utc};
+//! use cot::html::Html;
+//! use cot::timezone::UserTimezone;
+//!
+//! async fn post(timezone: UserTimezone) -> Html {
+//!     let published_at: DateTime<Utc> = "2024-07-01T12:00:00Z".parse().unwrap();
+//!
+//!     Html::new(format!(
+//!         "Published at {}",
+//!         timezone.format(&published_at, "%Y-%m-%d %H:%M %Z")
+//!     ))
+//! }
+//! ```
+//!
+//! The [`UserTimezone`] can also be passed to templates, so that the datetimes
+//! can be formatted there, e.g., `{{ timezone.format(&post.published_at,
+//! "%Y-%m-%d %H:%M") }}`. To use a pattern appropriate for the user's locale,
+//! store it in the message catalogs (see the [`i18n`](crate::i18n) module) and
+//! pass the translated pattern to [`UserTimezone::format`].
+
+use std::fmt::Write;
+use std::task::{Context, Poll};
+
+use chrono::{DateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use futures_core::future::BoxFuture;
+use time::OffsetDateTime;
+use tower::Service;
+use tracing::debug;
+
+use crate::request::extractors::FromRequestHead;
+use crate::request::{Request, RequestExt, RequestHead};
+use crate::response::Response;
+use crate::utils::cookies::cookie_value;
+
+/// The default name of the cookie containing the timezone of the user.
+pub const DEFAULT_TIMEZONE_COOKIE_NAME: &str = "timezone";
+
+/// A point in time that can be converted to a UTC datetime.
+///
+/// This is implemented for [`chrono::DateTime`] in any timezone and for
+/// [`time::OffsetDateTime`], so that both can be displayed using
+/// [`UserTimezone`].
+pub trait AsUtc {
+    /// Returns the same point in time as a UTC datetime.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{DateTime, FixedOffset, Utc};
+    /// use cot::timezone::AsUtc;
+    ///
+    /// let datetime: DateTime<FixedOffset> = "2024-07-01T14:00:00+02:00".parse().unwrap();
+    /// assert_eq!(
+    ///     datetime.as_utc(),
+    ///     "2024-07-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap()
+    /// );
+    /// ```
+    fn as_utc(&self) -> DateTime<Utc>;
+}
+
+impl<T: TimeZone> AsUtc for DateTime<T> {
+    fn as_utc(&self) -> DateTime<Utc> {
+        self.with_timezone(&Utc)
+    }
+}
+
+impl AsUtc for OffsetDateTime {
+    fn as_utc(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp(self.unix_timestamp(), self.nanosecond())
+            .expect("OffsetDateTime should be in the range supported by chrono")
+    }
+}
+
+/// The timezone in which the datetimes should be displayed to the user.
+///
+/// This is an extractor that requires the [`TimezoneMiddleware`] to be
+/// enabled. It can also be created manually, e.g., from the timezone stored in
+/// the user's profile.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{DateTime, Utc};
+/// use cot::timezone::UserTimezone;
+///
+/// let timezone = UserTimezone::from_name("America/New_York").unwrap();
+/// let datetime: DateTime<Utc> = "2024-01-15T12:00:00Z".parse().unwrap();
+///
+/// assert_eq!(timezone.format(&datetime, "%H:%M %Z"), "07:00 EST");
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct UserTimezone {
+    tz: Tz,
+}
+
+impl UserTimezone {
+    /// Creates a new user timezone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::timezone::UserTimezone;
+    ///
+    /// let timezone = UserTimezone::new(chrono_tz::Europe::Warsaw);
+    /// assert_eq!(timezone.name(), "Europe/Warsaw");
+    /// ```
+    #[must_use]
+    pub const fn new(tz: Tz) -> Self {
+        Self { tz }
+    }
+
+    /// Creates a new user timezone from an IANA timezone name, such as
+    /// `Europe/Warsaw`. Returns [`None`] if the timezone is unknown.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::timezone::UserTimezone;
+    ///
+    /// assert!(UserTimezone::from_name("Europe/Warsaw").is_some());
+    /// assert!(UserTimezone::from_name("Mars/Olympus_Mons").is_none());
+    /// ```
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        name.parse().ok().map(Self::new)
+    }
+
+    /// Returns the timezone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::timezone::UserTimezone;
+    ///
+    /// let timezone = UserTimezone::new(chrono_tz::Europe::Warsaw);
+    /// assert_eq!(timezone.tz(), chrono_tz::Europe::Warsaw);
+    /// ```
+    #[must_use]
+    pub const fn tz(&self) -> Tz {
+        self.tz
+    }
+
+    /// Returns the IANA name of the timezone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::timezone::UserTimezone;
+    ///
+    /// let timezone = UserTimezone::new(chrono_tz::Europe::Warsaw);
+    /// assert_eq!(timezone.name(), "Europe/Warsaw");
+    /// ```
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        self.tz.name()
+    }
+
+    /// Converts the datetime to the user's timezone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{DateTime, Timelike, Utc};
+    /// use cot::timezone::UserTimezone;
+    ///
+    /// let timezone = UserTimezone::new(chrono_tz::Europe::Warsaw);
+    /// let datetime: DateTime<Utc> = "2024-07-01T12:00:00Z".parse().unwrap();
+    ///
+    /// assert_eq!(timezone.localize(&datetime).hour(), 14);
+    /// ```
+    #[must_use]
+    pub fn localize(&self, datetime: &impl AsUtc) -> DateTime<Tz> {
+        datetime.as_utc().with_timezone(&self.tz)
+    }
+
+    /// Converts the datetime to the user's timezone and formats it using a
+    /// [`strftime`-like pattern](chrono::format::strftime).
+    ///
+    /// If the pattern is invalid, the datetime is formatted as RFC 3339
+    /// instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{DateTime, Utc};
+    /// use cot::timezone::UserTimezone;
+    ///
+    /// let timezone = UserTimezone::new(chrono_tz::Europe::Warsaw);
+    /// let datetime: DateTime<Utc> = "2024-07-01T12:00:00Z".parse().unwrap();
+    ///
+    /// assert_eq!(
+    ///     timezone.format(&datetime, "%d.%m.%Y %H:%M"),
+    ///     "01.07.2024 14:00"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn format(&self, datetime: &impl AsUtc, pattern: &str) -> String {
+        let datetime = self.localize(datetime);
+        let mut result = String::new();
+        if write!(result, "{}", datetime.format(pattern)).is_err() {
+            debug!(pattern, "Invalid datetime format pattern");
+            return datetime.to_rfc3339();
+        }
+        result
+    }
+}
+
+impl Default for UserTimezone {
+    fn default() -> Self {
+        Self::new(Tz::UTC)
+    }
+}
+
+impl FromRequestHead for UserTimezone {
+    async fn from_request_head(head: &RequestHead) -> cot::Result<Self> {
+        let timezone = *head
+            .extensions
+            .get::<UserTimezone>()
+            .expect("TimezoneMiddleware not enabled for the route/project");
+
+        Ok(timezone)
+    }
+}
+
+/// Middleware that resolves the timezone of each request and makes it
+/// available through the [`UserTimezone`] extractor.
+///
+/// The timezone is read from a cookie containing an IANA timezone name (see
+/// [`DEFAULT_TIMEZONE_COOKIE_NAME`]). If the cookie is missing or contains an
+/// unknown timezone, the default timezone (UTC unless configured otherwise)
+/// is used.
+///
+/// # Examples
+///
+/// ```
+/// use cot::Project;
+/// use cot::project::{MiddlewareContext, RootHandler, RootHandlerBuilder};
+/// use cot::timezone::TimezoneMiddleware;
+///
+/// struct MyProject;
+/// impl Project for MyProject {
+///     fn middlewares(
+///         &self,
+///         handler: RootHandlerBuilder,
+///         context: &MiddlewareContext,
+///     ) -> RootHandler {
+///         handler
+///             .middleware(TimezoneMiddleware::new().default_timezone(chrono_tz::Europe::Warsaw))
+///             .build()
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TimezoneMiddleware {
+    default_timezone: Tz,
+    cookie_name: String,
+}
+
+impl TimezoneMiddleware {
+    /// Creates a new middleware with UTC as the default timezone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::timezone::TimezoneMiddleware;
+    ///
+    /// let middleware = TimezoneMiddleware::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            default_timezone: Tz::UTC,
+            cookie_name: DEFAULT_TIMEZONE_COOKIE_NAME.to_owned(),
+        }
+    }
+
+    /// Sets the timezone used when the request doesn't specify a valid one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::timezone::TimezoneMiddleware;
+    ///
+    /// let middleware = TimezoneMiddleware::new().default_timezone(chrono_tz::Europe::Warsaw);
+    /// ```
+    #[must_use]
+    pub fn default_timezone(mut self, default_timezone: Tz) -> Self {
+        self.default_timezone = default_timezone;
+        self
+    }
+
+    /// Sets the name of the cookie containing the timezone of the user. The
+    /// default is [`DEFAULT_TIMEZONE_COOKIE_NAME`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::timezone::TimezoneMiddleware;
+    ///
+    /// let middleware = TimezoneMiddleware::new().cookie_name("tz");
+    /// ```
+    #[must_use]
+    pub fn cookie_name(mut self, cookie_name: impl Into<String>) -> Self {
+        self.cookie_name = cookie_name.into();
+        self
+    }
+}
+
+impl Default for TimezoneMiddleware {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> tower::Layer<S> for TimezoneMiddleware {
+    type Service = TimezoneService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TimezoneService {
+            inner,
+            default_timezone: self.default_timezone,
+            cookie_name: self.cookie_name.clone(),
+        }
+    }
+}
+
+/// Service that resolves the timezone of the requests.
+///
+/// Used by [`TimezoneMiddleware`].
+#[derive(Debug, Clone)]
+pub struct TimezoneService<S> {
+    inner: S,
+    default_timezone: Tz,
+    cookie_name: String,
+}
+
+impl<S> Service<Request> for TimezoneService<S>
+where
+    S: Service<Request, Response = Response, Error = crate::Error> + Send,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request) -> Self::Future {
+        let timezone = cookie_value(req.headers(), &self.cookie_name)
+            .and_then(UserTimezone::from_name)
+            .unwrap_or(UserTimezone::new(self.default_timezone));
+        req.extensions_mut().insert(timezone);
+
+        Box::pin(self.inner.call(req))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{HeaderValue, header};
+    use tower::{Layer, ServiceExt};
+
+    use super::*;
+    use crate::Body;
+    use crate::test::TestRequestBuilder;
+
+    fn utc(datetime: &str) -> DateTime<Utc> {
+        datetime.parse().unwrap()
+    }
+
+    #[test]
+    fn localize_to_different_zones() {
+        let datetime = utc("2024-07-01T12:00:00Z");
+
+        let warsaw = UserTimezone::new(chrono_tz::Europe::Warsaw);
+        let new_york = UserTimezone::new(chrono_tz::America::New_York);
+
+        assert_eq!(
+            warsaw.format(&datetime, "%Y-%m-%d %H:%M %Z"),
+            "2024-07-01 14:00 CEST"
+        );
+        assert_eq!(
+            new_york.format(&datetime, "%Y-%m-%d %H:%M %Z"),
+            "2024-07-01 08:00 EDT"
+        );
+        assert_eq!(warsaw.localize(&datetime), new_york.localize(&datetime));
+    }
+
+    #[test]
+    fn localize_across_dst_transition() {
+        let new_york = UserTimezone::new(chrono_tz::America::New_York);
+
+        // DST starts on 2024-03-10 at 2:00 local time
+        assert_eq!(
+            new_york.format(&utc("2024-03-10T06:59:59Z"), "%H:%M:%S %Z"),
+            "01:59:59 EST"
+        );
+        assert_eq!(
+            new_york.format(&utc("2024-03-10T07:00:00Z"), "%H:%M:%S %Z"),
+            "03:00:00 EDT"
+        );
+        // DST ends on 2024-11-03 at 2:00 local time, so 1:30 happens twice
+        assert_eq!(
+            new_york.format(&utc("2024-11-03T05:30:00Z"), "%H:%M %Z"),
+            "01:30 EDT"
+        );
+        assert_eq!(
+            new_york.format(&utc("2024-11-03T06:30:00Z"), "%H:%M %Z"),
+            "01:30 EST"
+        );
+    }
+
+    #[test]
+    fn localize_offset_datetime() {
+        let warsaw = UserTimezone::new(chrono_tz::Europe::Warsaw);
+        let datetime = OffsetDateTime::from_unix_timestamp(1_704_110_400).unwrap();
+
+        assert_eq!(
+            warsaw.format(&datetime, "%Y-%m-%d %H:%M %Z"),
+            "2024-01-01 13:00 CET"
+        );
+    }
+
+    #[test]
+    fn format_invalid_pattern() {
+        let warsaw = UserTimezone::new(chrono_tz::Europe::Warsaw);
+
+        assert_eq!(
+            warsaw.format(&utc("2024-07-01T12:00:00Z"), "%Q"),
+            "2024-07-01T14:00:00+02:00"
+        );
+    }
+
+    async fn resolved_timezone(
+        middleware: TimezoneMiddleware,
+        cookie: Option<&'static str>,
+    ) -> String {
+        let service = middleware.layer(tower::service_fn(|request: Request| async move {
+            let timezone = request.timezone().unwrap().name();
+            Ok::<_, crate::Error>(Response::new(Body::fixed(timezone)))
+        }));
+
+        let mut request = TestRequestBuilder::get("/").build();
+        if let Some(cookie) = cookie {
+            request
+                .headers_mut()
+                .insert(header::COOKIE, HeaderValue::from_static(cookie));
+        }
+
+        let response = service.oneshot(request).await.unwrap();
+        let body = response.into_body().into_bytes().await.unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[cot::test]
+    async fn middleware_resolves_timezone() {
+        assert_eq!(
+            resolved_timezone(TimezoneMiddleware::new(), None).await,
+            "UTC"
+        );
+        assert_eq!(
+            resolved_timezone(TimezoneMiddleware::new(), Some("timezone=America/New_York")).await,
+            "America/New_York"
+        );
+        assert_eq!(
+            resolved_timezone(
+                TimezoneMiddleware::new().default_timezone(chrono_tz::Europe::Warsaw),
+                Some("timezone=Invalid/Zone")
+            )
+            .await,
+            "Europe/Warsaw"
+        );
+        assert_eq!(
+            resolved_timezone(
+                TimezoneMiddleware::new().cookie_name("tz"),
+                Some("timezone=Asia/Tokyo; tz=Europe/Warsaw")
+            )
+            .await,
+            "Europe/Warsaw"
+        );
+    }
+}
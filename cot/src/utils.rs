@@ -1,4 +1,5 @@
 pub(crate) mod accept_header_parser;
+pub(crate) mod accept_language_parser;
 pub(crate) mod chrono;
-#[cfg(feature = "db")]
+pub(crate) mod cookies;
 pub(crate) mod graph;
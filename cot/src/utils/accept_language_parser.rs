@@ -0,0 +1,205 @@
+/// Parser for the [`Accept-Language`] HTTP header.
+///
+/// [`Accept-Language`]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Headers/Accept-Language
+#[derive(Debug, Clone)]
+pub(crate) struct AcceptLanguageParser {
+    languages: Vec<Language>,
+}
+
+impl AcceptLanguageParser {
+    /// Parses the header value. Malformed entries are skipped.
+    #[must_use]
+    pub(crate) fn parse(accept_language_header: &str) -> Self {
+        let mut languages: Vec<Language> = accept_language_header
+            .split(',')
+            .filter_map(Self::parse_single)
+            .collect();
+        // stable sort, so that the languages with the same weight keep the order
+        // from the header
+        languages.sort_by(|a, b| {
+            b.weight
+                .partial_cmp(&a.weight)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        AcceptLanguageParser { languages }
+    }
+
+    fn parse_single(part: &str) -> Option<Language> {
+        let part = part.trim();
+        if part.is_empty() {
+            return None;
+        }
+
+        let mut parts = part.split(';');
+        let tag = parts.next()?.trim();
+        if !Self::is_valid_tag(tag) {
+            return None;
+        }
+        let mut weight = 1.0; // Default weight is 1.0
+        for param in parts {
+            if let Some(weight_value) = param.trim().strip_prefix("q=") {
+                weight = weight_value.trim().parse::<f32>().ok()?;
+            }
+        }
+        if weight.is_nan() {
+            return None;
+        }
+        let weight = weight.clamp(0.0, 1.0);
+
+        Some(Language {
+            tag: tag.to_owned(),
+            weight,
+        })
+    }
+
+    fn is_valid_tag(tag: &str) -> bool {
+        tag == "*"
+            || (!tag.is_empty()
+                && tag.split('-').all(|subtag| {
+                    !subtag.is_empty() && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+                }))
+    }
+
+    /// Returns the acceptable language tags (i.e., the ones with a non-zero
+    /// weight), ordered by preference.
+    pub(crate) fn languages(&self) -> impl Iterator<Item = &str> {
+        self.languages
+            .iter()
+            .filter(|language| language.weight > 0.0)
+            .map(|language| language.tag.as_str())
+    }
+
+    /// Returns the supported language that best matches the header.
+    ///
+    /// The language ranges are checked in the order of preference. A range
+    /// matches a supported language if it's equal to it, if it's a prefix of
+    /// it (`en` matches `en-US`), or if the supported language is a prefix of
+    /// the range (`en-US` matches `en`). The `*` range matches any supported
+    /// language that is not explicitly excluded with `q=0`.
+    pub(crate) fn preferred_language<'a>(&self, supported: &[&'a str]) -> Option<&'a str> {
+        let is_acceptable = |tag: &str| {
+            !self
+                .languages
+                .iter()
+                .any(|language| language.weight <= 0.0 && language.matches_prefix_of(tag))
+        };
+
+        for language in self
+            .languages
+            .iter()
+            .filter(|language| language.weight > 0.0)
+        {
+            if language.tag == "*" {
+                if let Some(tag) = supported.iter().copied().find(|&tag| is_acceptable(tag)) {
+                    return Some(tag);
+                }
+                continue;
+            }
+
+            let candidates = [
+                supported
+                    .iter()
+                    .copied()
+                    .find(|&tag| tag.eq_ignore_ascii_case(&language.tag)),
+                supported
+                    .iter()
+                    .copied()
+                    .find(|&tag| language.matches_prefix_of(tag)),
+                supported
+                    .iter()
+                    .copied()
+                    .find(|&tag| is_prefix_of(tag, &language.tag)),
+            ];
+            if let Some(tag) = candidates
+                .into_iter()
+                .flatten()
+                .find(|&tag| is_acceptable(tag))
+            {
+                return Some(tag);
+            }
+        }
+
+        None
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Language {
+    tag: String,
+    weight: f32,
+}
+
+impl Language {
+    fn matches_prefix_of(&self, tag: &str) -> bool {
+        self.tag != "*" && is_prefix_of(&self.tag, tag)
+    }
+}
+
+/// Checks whether the language range `prefix` is equal to `tag` or is its
+/// prefix ending at a subtag boundary, ignoring the case.
+fn is_prefix_of(prefix: &str, tag: &str) -> bool {
+    tag.len() >= prefix.len()
+        && tag.is_char_boundary(prefix.len())
+        && tag[..prefix.len()].eq_ignore_ascii_case(prefix)
+        && (tag.len() == prefix.len() || tag.as_bytes()[prefix.len()] == b'-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_accept_language_and_sort() {
+        let parser = AcceptLanguageParser::parse("de;q=0.7, fr-CH, fr;q=0.9, en;q=0.8, *;q=0.5");
+
+        assert_eq!(
+            parser.languages().collect::<Vec<_>>(),
+            ["fr-CH", "fr", "en", "de", "*"]
+        );
+    }
+
+    #[test]
+    fn parse_accept_language_invalid() {
+        let parser =
+            AcceptLanguageParser::parse(", ;q=0.9, en_US, pl;q=abc, de;q=0, fr;q=20, it-;q=0.5");
+
+        assert_eq!(parser.languages().collect::<Vec<_>>(), ["fr"]);
+    }
+
+    #[test]
+    fn preferred_language_exact() {
+        let parser = AcceptLanguageParser::parse("fr-CH, fr;q=0.9, en;q=0.8");
+
+        assert_eq!(parser.preferred_language(&["en", "fr"]), Some("fr"));
+        assert_eq!(parser.preferred_language(&["en", "fr-ch"]), Some("fr-ch"));
+        assert_eq!(parser.preferred_language(&["en"]), Some("en"));
+        assert_eq!(parser.preferred_language(&["de"]), None);
+        assert_eq!(parser.preferred_language(&[]), None);
+    }
+
+    #[test]
+    fn preferred_language_prefix() {
+        let parser = AcceptLanguageParser::parse("en, pl;q=0.5");
+
+        assert_eq!(parser.preferred_language(&["pl", "en-GB"]), Some("en-GB"));
+        assert_eq!(parser.preferred_language(&["pl", "eng"]), Some("pl"));
+    }
+
+    #[test]
+    fn preferred_language_wildcard() {
+        let parser = AcceptLanguageParser::parse("pl, *;q=0.1, en;q=0");
+
+        assert_eq!(parser.preferred_language(&["en", "de"]), Some("de"));
+        assert_eq!(parser.preferred_language(&["en-US", "pl"]), Some("pl"));
+        assert_eq!(parser.preferred_language(&["en", "en-US"]), None);
+    }
+
+    #[test]
+    fn is_prefix() {
+        assert!(is_prefix_of("en", "en"));
+        assert!(is_prefix_of("en", "EN-us"));
+        assert!(!is_prefix_of("en", "eng"));
+        assert!(!is_prefix_of("en-US", "en"));
+    }
+}
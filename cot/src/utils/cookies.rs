@@ -0,0 +1,44 @@
+//! Helpers for reading the cookies sent with a request.
+
+/// Returns the value of the cookie with the given name from the [`Cookie`]
+/// headers, or [`None`] if there is no such cookie.
+///
+/// [`Cookie`]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Headers/Cookie
+#[must_use]
+pub(crate) fn cookie_value<'a>(headers: &'a http::HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get_all(http::header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(cookie_name, _)| *cookie_name == name)
+        .map(|(_, value)| value.trim_matches('"'))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{HeaderMap, HeaderValue, header};
+
+    use super::*;
+
+    #[test]
+    fn cookie_value_found() {
+        let mut headers = HeaderMap::new();
+        headers.append(header::COOKIE, HeaderValue::from_static("a=1; b=\"2\""));
+        headers.append(header::COOKIE, HeaderValue::from_static("c=3"));
+
+        assert_eq!(cookie_value(&headers, "a"), Some("1"));
+        assert_eq!(cookie_value(&headers, "b"), Some("2"));
+        assert_eq!(cookie_value(&headers, "c"), Some("3"));
+    }
+
+    #[test]
+    fn cookie_value_missing() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(cookie_value(&headers, "a"), None);
+
+        headers.append(header::COOKIE, HeaderValue::from_static("ab=1; invalid"));
+        assert_eq!(cookie_value(&headers, "a"), None);
+    }
+}
@@ -1,8 +1,20 @@
 use thiserror::Error;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Error)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Error)]
 #[error("cycle detected in the graph")]
-pub struct CycleDetected;
+pub struct CycleDetected {
+    cycle: Vec<usize>,
+}
+
+impl CycleDetected {
+    /// Returns the vertices forming the cycle, in the order of the edges
+    /// between them (i.e., there is an edge from each vertex to the next one,
+    /// and from the last one to the first one).
+    #[must_use]
+    pub(crate) fn cycle(&self) -> &[usize] {
+        &self.cycle
+    }
+}
 
 #[doc(hidden)] // not part of public API, used in the Cot CLI
 pub fn apply_permutation<T>(items: &mut [T], order: &mut [usize]) {
@@ -48,9 +60,10 @@ impl Graph {
     pub(crate) fn toposort(&mut self) -> Result<Vec<usize>, CycleDetected> {
         let mut visited = vec![VisitedStatus::NotVisited; self.vertex_num()];
         let mut sorted_indices_stack = Vec::with_capacity(self.vertex_num());
+        let mut path = Vec::new();
 
         for index in (0..self.vertex_num()).rev() {
-            self.toposort_visit(index, &mut visited, &mut sorted_indices_stack)?;
+            self.toposort_visit(index, &mut visited, &mut path, &mut sorted_indices_stack)?;
         }
 
         assert_eq!(sorted_indices_stack.len(), self.vertex_num());
@@ -64,22 +77,33 @@ impl Graph {
         &self,
         index: usize,
         visited: &mut Vec<VisitedStatus>,
+        path: &mut Vec<usize>,
         sorted_indices_stack: &mut Vec<usize>,
     ) -> Result<(), CycleDetected> {
         match visited[index] {
             VisitedStatus::Visited => return Ok(()),
             VisitedStatus::Visiting => {
-                return Err(CycleDetected);
+                // the vertex is on the current path, so the part of the path
+                // starting at it forms a cycle
+                let start = path
+                    .iter()
+                    .position(|&vertex| vertex == index)
+                    .expect("vertex being visited must be on the current path");
+                return Err(CycleDetected {
+                    cycle: path[start..].to_vec(),
+                });
             }
             VisitedStatus::NotVisited => {}
         }
 
         visited[index] = VisitedStatus::Visiting;
+        path.push(index);
 
         for &neighbor in &self.vertex_edges[index] {
-            self.toposort_visit(neighbor, visited, sorted_indices_stack)?;
+            self.toposort_visit(neighbor, visited, path, sorted_indices_stack)?;
         }
 
+        path.pop();
         visited[index] = VisitedStatus::Visited;
         sorted_indices_stack.push(index);
 
@@ -131,6 +155,22 @@ mod tests {
         graph.add_edge(2, 3);
         graph.add_edge(3, 0);
 
-        assert!(matches!(graph.toposort(), Err(CycleDetected)));
+        let error = graph.toposort().unwrap_err();
+
+        assert_eq!(error.cycle(), &[3, 0, 1, 2]);
+    }
+
+    #[test]
+    fn graph_toposort_with_cycle_not_at_start() {
+        let mut graph = Graph::new(5);
+        graph.add_edge(4, 0);
+        graph.add_edge(0, 1);
+        graph.add_edge(1, 2);
+        graph.add_edge(2, 1);
+        graph.add_edge(2, 3);
+
+        let error = graph.toposort().unwrap_err();
+
+        assert_eq!(error.cycle(), &[1, 2]);
     }
 }
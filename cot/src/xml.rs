@@ -0,0 +1,505 @@
+//! XML requests and responses.
+//!
+//! This module provides the [`Xml`] type, which can be used both as a
+//! responder serializing data to XML, and as a request extractor
+//! deserializing XML request bodies. Request bodies can also be read with
+//! [`RequestExt::xml`](crate::request::RequestExt::xml).
+//!
+//! The data is (de)serialized with [`serde`] using [`quick_xml`], so the
+//! usual serde attributes can be used to control the XML structure:
+//!
+//! * the name of the root element is the name of the serialized struct (which
+//!   can be changed with `#[serde(rename = "...")]` or with [`Xml::root`]),
+//! * struct fields are serialized as child elements, unless they are renamed
+//!   to a name starting with `@`, in which case they become attributes of the
+//!   element (e.g. `#[serde(rename = "@id")]`),
+//! * a field renamed to `$text` becomes the text content of the element.
+//!
+//! Namespaces can be declared on the root element with [`Xml::namespace`]
+//! and [`Xml::prefixed_namespace`], and the elements and attributes can use
+//! the prefixes in their names (e.g. `#[serde(rename = "atom:link")]`).
+//!
+//! # Examples
+//!
+//! ```
+//! use cot::response::IntoResponse;
+//! use cot::xml::Xml;
+//! use serde::Serialize;
+//!
+//! #[derive(Serialize)]
+//! #[serde(rename = "book")]
+//! struct Book {
+//!     #[serde(rename = "@isbn")]
+//!     isbn: &'static str,
+//!     title: &'static str,
+//! }
+//!
+//! async fn book() -> impl IntoResponse {
+//!     Xml::new(Book {
+//!         isbn: "978-0-00-000000-0",
+//!         title: "The Rust Programming Language",
+//!     })
+//!     .namespace("http://example.com/books")
+//! }
+//! ```
+
+use std::fmt::Write;
+
+use cot_core::error::impl_into_cot_error;
+use cot_core::request::InvalidContentType;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+use crate::Body;
+use crate::request::RequestHead;
+use crate::request::extractors::FromRequest;
+use crate::response::{IntoResponse, Response};
+
+/// The content type of the XML responses.
+pub const XML_CONTENT_TYPE: &str = "application/xml";
+
+const XML_DECLARATION: &str = r#"<?xml version="1.0" encoding="UTF-8"?>"#;
+
+/// An error that can occur while serializing data to XML.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum XmlError {
+    /// The data could not be serialized as XML.
+    #[error("could not serialize the data as XML: {0}")]
+    Serialize(#[from] quick_xml::se::SeError),
+}
+impl_into_cot_error!(XmlError, INTERNAL_SERVER_ERROR);
+
+#[derive(Debug, Error)]
+#[error("XML deserialization error: {0}")]
+struct XmlDeserializeError(#[from] quick_xml::de::DeError);
+impl_into_cot_error!(XmlDeserializeError, BAD_REQUEST);
+
+/// A type that represents XML content.
+///
+/// When returned from a request handler, the data is serialized to XML and
+/// sent with the `application/xml` content type. When used as a request
+/// extractor, the request body is deserialized from XML; the request must
+/// have an XML content type (`application/xml`, `text/xml`, or a type with
+/// the `+xml` suffix).
+///
+/// See the [module documentation](self) for how the XML structure is
+/// determined.
+///
+/// # Examples
+///
+/// ```
+/// use cot::xml::Xml;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Order {
+///     #[serde(rename = "@id")]
+///     id: u32,
+///     item: Vec<String>,
+/// }
+///
+/// async fn echo_order(order: Xml<Order>) -> Xml<Order> {
+///     let order = order.into_inner();
+///     Xml::new(order).root("confirmedOrder")
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Xml<T> {
+    data: T,
+    root: Option<String>,
+    namespaces: Vec<(Option<String>, String)>,
+}
+
+impl<T> Xml<T> {
+    /// Creates a new XML value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::xml::Xml;
+    ///
+    /// let xml = Xml::new(vec![1, 2, 3]).root("numbers");
+    /// ```
+    #[must_use]
+    pub fn new(data: T) -> Self {
+        Self {
+            data,
+            root: None,
+            namespaces: Vec::new(),
+        }
+    }
+
+    /// Sets the name of the root element.
+    ///
+    /// By default, the name of the serialized type is used. Setting the root
+    /// element name is required to serialize values without a type name, such
+    /// as sequences, maps, or primitive values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::xml::Xml;
+    ///
+    /// let xml = Xml::new("Hello").root("greeting");
+    /// assert_eq!(
+    ///     xml.to_xml_string().unwrap(),
+    ///     r#"<?xml version="1.0" encoding="UTF-8"?><greeting>Hello</greeting>"#
+    /// );
+    /// ```
+    #[must_use]
+    pub fn root<S: Into<String>>(mut self, name: S) -> Self {
+        self.root = Some(name.into());
+        self
+    }
+
+    /// Declares the default namespace on the root element (the `xmlns`
+    /// attribute).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::xml::Xml;
+    ///
+    /// let xml = Xml::new("Hello")
+    ///     .root("greeting")
+    ///     .namespace("http://example.com/ns");
+    /// assert_eq!(
+    ///     xml.to_xml_string().unwrap(),
+    ///     r#"<?xml version="1.0" encoding="UTF-8"?><greeting xmlns="http://example.com/ns">Hello</greeting>"#
+    /// );
+    /// ```
+    #[must_use]
+    pub fn namespace<S: Into<String>>(mut self, uri: S) -> Self {
+        self.namespaces.push((None, uri.into()));
+        self
+    }
+
+    /// Declares a namespace with the given prefix on the root element (the
+    /// `xmlns:prefix` attribute).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::xml::Xml;
+    ///
+    /// let xml = Xml::new("Hello")
+    ///     .root("ex:greeting")
+    ///     .prefixed_namespace("ex", "http://example.com/ns");
+    /// assert_eq!(
+    ///     xml.to_xml_string().unwrap(),
+    ///     r#"<?xml version="1.0" encoding="UTF-8"?><ex:greeting xmlns:ex="http://example.com/ns">Hello</ex:greeting>"#
+    /// );
+    /// ```
+    #[must_use]
+    pub fn prefixed_namespace<P: Into<String>, S: Into<String>>(
+        mut self,
+        prefix: P,
+        uri: S,
+    ) -> Self {
+        self.namespaces.push((Some(prefix.into()), uri.into()));
+        self
+    }
+
+    /// Returns the wrapped data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::xml::Xml;
+    ///
+    /// let xml = Xml::new("content");
+    /// assert_eq!(xml.into_inner(), "content");
+    /// ```
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+}
+
+impl<T: Serialize> Xml<T> {
+    /// Serializes the data to an XML document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data can't be serialized as XML, for instance
+    /// if it's a sequence or a primitive value and no root element name has
+    /// been set with [`Xml::root`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::xml::Xml;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Point {
+    ///     #[serde(rename = "@x")]
+    ///     x: i32,
+    ///     #[serde(rename = "@y")]
+    ///     y: i32,
+    /// }
+    ///
+    /// let xml = Xml::new(Point { x: 1, y: 2 });
+    /// assert_eq!(
+    ///     xml.to_xml_string().unwrap(),
+    ///     r#"<?xml version="1.0" encoding="UTF-8"?><Point x="1" y="2"/>"#
+    /// );
+    /// ```
+    pub fn to_xml_string(&self) -> Result<String, XmlError> {
+        let mut body = String::new();
+        let serializer = quick_xml::se::Serializer::with_root(&mut body, self.root.as_deref())?;
+        self.data.serialize(serializer)?;
+
+        Ok(format!(
+            "{XML_DECLARATION}{}",
+            add_namespaces(&body, &self.namespaces)
+        ))
+    }
+}
+
+impl<T: Serialize> IntoResponse for Xml<T> {
+    /// Creates a new XML response.
+    ///
+    /// This creates a new [`Response`] object with a content type of
+    /// `application/xml` and the serialized data as the body.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cot::response::IntoResponse;
+    /// use cot::xml::Xml;
+    ///
+    /// let response = Xml::new("Hello").root("greeting").into_response();
+    /// ```
+    fn into_response(self) -> crate::Result<Response> {
+        self.to_xml_string()?
+            .with_content_type(XML_CONTENT_TYPE)
+            .into_response()
+    }
+}
+
+impl<T: DeserializeOwned> FromRequest for Xml<T> {
+    async fn from_request(head: &RequestHead, body: Body) -> crate::Result<Self> {
+        from_request_body(&head.headers, body).await.map(Self::new)
+    }
+}
+
+pub(crate) async fn from_request_body<T: DeserializeOwned>(
+    headers: &http::HeaderMap,
+    body: Body,
+) -> crate::Result<T> {
+    let content_type = headers
+        .get(http::header::CONTENT_TYPE)
+        .map_or("".into(), |value| String::from_utf8_lossy(value.as_bytes()));
+    if !is_xml_content_type(&content_type) {
+        return Err(InvalidContentType {
+            expected: XML_CONTENT_TYPE,
+            actual: content_type.into_owned(),
+        }
+        .into());
+    }
+
+    let body = body.into_string().await?;
+    let data = quick_xml::de::from_str(&body).map_err(XmlDeserializeError)?;
+    Ok(data)
+}
+
+fn is_xml_content_type(content_type: &str) -> bool {
+    let essence = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    essence == XML_CONTENT_TYPE || essence == "text/xml" || essence.ends_with("+xml")
+}
+
+/// Adds the namespace declarations as attributes of the root element of the
+/// serialized document.
+fn add_namespaces(body: &str, namespaces: &[(Option<String>, String)]) -> String {
+    // the serializer escapes `>` in attribute values, so the first `>` always
+    // closes the start tag of the root element
+    let Some(tag_end) = body.find('>') else {
+        return body.to_owned();
+    };
+    if namespaces.is_empty() {
+        return body.to_owned();
+    }
+    let insert_at = if body[..tag_end].ends_with('/') {
+        tag_end - 1
+    } else {
+        tag_end
+    };
+
+    let mut declarations = String::new();
+    for (prefix, uri) in namespaces {
+        let uri = quick_xml::escape::escape(uri.as_str());
+        match prefix {
+            Some(prefix) => write!(declarations, r#" xmlns:{prefix}="{uri}""#),
+            None => write!(declarations, r#" xmlns="{uri}""#),
+        }
+        .expect("writing to a String should never fail");
+    }
+
+    let mut result = String::with_capacity(body.len() + declarations.len());
+    result.push_str(&body[..insert_at]);
+    result.push_str(&declarations);
+    result.push_str(&body[insert_at..]);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    fn xml_request(content_type: &str, body: impl Into<String>) -> (RequestHead, Body) {
+        http::Request::builder()
+            .method(http::Method::POST)
+            .header(http::header::CONTENT_TYPE, content_type)
+            .body(Body::fixed(body.into()))
+            .unwrap()
+            .into_parts()
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(rename = "feed")]
+    struct Feed {
+        #[serde(rename = "@version")]
+        version: String,
+        title: String,
+        #[serde(rename = "entry", default)]
+        entries: Vec<Entry>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Entry {
+        #[serde(rename = "@id")]
+        id: u32,
+        #[serde(rename = "$text")]
+        text: String,
+    }
+
+    fn feed() -> Feed {
+        Feed {
+            version: "1.0".to_owned(),
+            title: "News & updates".to_owned(),
+            entries: vec![
+                Entry {
+                    id: 1,
+                    text: "first".to_owned(),
+                },
+                Entry {
+                    id: 2,
+                    text: "<second>".to_owned(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn serialize_struct() {
+        let xml = Xml::new(feed()).to_xml_string().unwrap();
+
+        assert_eq!(
+            xml,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+             <feed version=\"1.0\">\
+             <title>News &amp; updates</title>\
+             <entry id=\"1\">first</entry>\
+             <entry id=\"2\">&lt;second&gt;</entry>\
+             </feed>"
+        );
+    }
+
+    #[test]
+    fn serialize_root_and_namespaces() {
+        let xml = Xml::new(feed())
+            .root("atom:feed")
+            .namespace("http://example.com/default")
+            .prefixed_namespace("atom", "http://www.w3.org/2005/Atom?a=1&b=2")
+            .to_xml_string()
+            .unwrap();
+
+        assert!(xml.contains(
+            "<atom:feed version=\"1.0\" xmlns=\"http://example.com/default\" \
+             xmlns:atom=\"http://www.w3.org/2005/Atom?a=1&amp;b=2\">"
+        ));
+        assert!(xml.ends_with("</atom:feed>"));
+    }
+
+    #[test]
+    fn serialize_namespaces_empty_element() {
+        #[derive(Serialize)]
+        struct Empty {}
+
+        let xml = Xml::new(Empty {})
+            .namespace("http://example.com/ns")
+            .to_xml_string()
+            .unwrap();
+
+        assert_eq!(
+            xml,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?><Empty xmlns=\"http://example.com/ns\"/>"
+        );
+    }
+
+    #[test]
+    fn serialize_sequence_without_root() {
+        assert!(Xml::new(vec![1, 2, 3]).to_xml_string().is_err());
+    }
+
+    #[cot::test]
+    async fn xml_response() {
+        let response = Xml::new(feed()).into_response().unwrap();
+
+        assert_eq!(
+            response.headers().get(http::header::CONTENT_TYPE).unwrap(),
+            XML_CONTENT_TYPE
+        );
+        let body = response.into_body().into_string().await.unwrap();
+        assert!(body.starts_with(XML_DECLARATION));
+    }
+
+    #[cot::test]
+    async fn xml_from_request() {
+        let (head, body) = xml_request(
+            "text/xml; charset=utf-8",
+            Xml::new(feed()).to_xml_string().unwrap(),
+        );
+
+        let xml = Xml::<Feed>::from_request(&head, body).await.unwrap();
+
+        assert_eq!(xml.into_inner(), feed());
+    }
+
+    #[cot::test]
+    async fn xml_from_request_invalid_content_type() {
+        let (head, body) = xml_request("application/json", "<feed/>");
+
+        let error = Xml::<Feed>::from_request(&head, body).await.unwrap_err();
+
+        assert_eq!(error.status_code(), http::StatusCode::BAD_REQUEST);
+    }
+
+    #[cot::test]
+    async fn xml_from_request_invalid_body() {
+        let (head, body) = xml_request("application/atom+xml", "<feed><title>unclosed</feed>");
+
+        let error = Xml::<Feed>::from_request(&head, body).await.unwrap_err();
+
+        assert_eq!(error.status_code(), http::StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn xml_content_types() {
+        assert!(is_xml_content_type("application/xml"));
+        assert!(is_xml_content_type("Text/XML; charset=utf-8"));
+        assert!(is_xml_content_type("application/soap+xml"));
+        assert!(!is_xml_content_type("application/json"));
+        assert!(!is_xml_content_type(""));
+    }
+}
@@ -1,17 +1,21 @@
 use std::error::Error;
+use std::fmt::Display;
 
 use async_trait::async_trait;
-use cot::admin::AdminApp;
+use cot::admin::{AdminApp, AdminModel, Pagination};
 use cot::auth::db::{DatabaseUser, DatabaseUserApp};
 use cot::cli::CliMetadata;
 use cot::config::{
     AuthBackendConfig, DatabaseConfig, MiddlewareConfig, ProjectConfig, SessionMiddlewareConfig,
 };
+use cot::db::migrations::{Field, Operation};
+use cot::db::{Auto, DatabaseField, Identifier, Model, model};
+use cot::form::Form;
 use cot::middleware::{AuthMiddleware, SessionMiddleware};
 use cot::project::{MiddlewareContext, RegisterAppsContext, RootHandler};
 use cot::static_files::StaticFilesMiddleware;
-use cot::test::{TestServer, TestServerBuilder};
-use cot::{App, AppBuilder, Project, ProjectContext};
+use cot::test::{TestDatabase, TestRequestBuilder, TestServer, TestServerBuilder};
+use cot::{App, AppBuilder, Project, ProjectContext, StatusCode};
 use fantoccini::{Client, ClientBuilder, Locator};
 
 const DEFAULT_USERNAME: &str = "admin";
@@ -136,6 +140,180 @@ async fn admin_e2e_change_password() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[derive(Debug, Form, AdminModel)]
+#[admin(search_fields = ["title", "content"], list_filter = ["published"])]
+#[model]
+struct BlogPost {
+    #[model(primary_key)]
+    id: Auto<i32>,
+    title: String,
+    content: String,
+    published: bool,
+}
+
+impl Display for BlogPost {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.title)
+    }
+}
+
+const CREATE_BLOG_POST: Operation = Operation::create_model()
+    .table_name(Identifier::new("cot__blog_post"))
+    .fields(&[
+        Field::new(Identifier::new("id"), <Auto<i32> as DatabaseField>::TYPE)
+            .primary_key()
+            .auto(),
+        Field::new(Identifier::new("title"), <String as DatabaseField>::TYPE),
+        Field::new(Identifier::new("content"), <String as DatabaseField>::TYPE),
+        Field::new(Identifier::new("published"), <bool as DatabaseField>::TYPE),
+    ])
+    .build();
+
+#[cot_macros::dbtest]
+async fn admin_model_search_and_filter(test_db: &mut TestDatabase) {
+    CREATE_BLOG_POST.forwards(test_db).await.unwrap();
+    for (title, content, published) in [
+        ("Hello world", "First post", true),
+        ("Second post", "Hello again", false),
+        ("Third post", "Nothing here", true),
+    ] {
+        let mut post = BlogPost {
+            id: Auto::auto(),
+            title: title.to_owned(),
+            content: content.to_owned(),
+            published,
+        };
+        post.insert(&**test_db).await.unwrap();
+    }
+
+    let count = async |url: &str| {
+        let request = TestRequestBuilder::get(url)
+            .database(test_db.database())
+            .build();
+        BlogPost::get_total_object_counts(&request).await
+    };
+
+    assert_eq!(count("/admin/blog_post/").await.unwrap(), 3);
+    assert_eq!(count("/admin/blog_post/?q=hello").await.unwrap(), 2);
+    assert_eq!(count("/admin/blog_post/?q=POST").await.unwrap(), 3);
+    assert_eq!(count("/admin/blog_post/?published=true").await.unwrap(), 2);
+    assert_eq!(
+        count("/admin/blog_post/?q=hello&published=true")
+            .await
+            .unwrap(),
+        1
+    );
+
+    let error = count("/admin/blog_post/?published=maybe")
+        .await
+        .unwrap_err();
+    assert_eq!(error.status_code(), StatusCode::BAD_REQUEST);
+}
+
+#[derive(Debug, Form, AdminModel)]
+#[admin(list_display = ["title", "views", "status_label"], ordering = ["-views"])]
+#[model]
+struct Article {
+    #[model(primary_key)]
+    id: Auto<i32>,
+    title: String,
+    views: i32,
+    published: bool,
+}
+
+impl Article {
+    fn status_label(&self) -> &'static str {
+        if self.published { "Published" } else { "Draft" }
+    }
+}
+
+impl Display for Article {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.title)
+    }
+}
+
+const CREATE_ARTICLE: Operation = Operation::create_model()
+    .table_name(Identifier::new("cot__article"))
+    .fields(&[
+        Field::new(Identifier::new("id"), <Auto<i32> as DatabaseField>::TYPE)
+            .primary_key()
+            .auto(),
+        Field::new(Identifier::new("title"), <String as DatabaseField>::TYPE),
+        Field::new(Identifier::new("views"), <i32 as DatabaseField>::TYPE),
+        Field::new(Identifier::new("published"), <bool as DatabaseField>::TYPE),
+    ])
+    .build();
+
+#[cot_macros::dbtest]
+async fn admin_model_list_display_and_ordering(test_db: &mut TestDatabase) {
+    CREATE_ARTICLE.forwards(test_db).await.unwrap();
+    for (title, views, published) in [
+        ("Banana", 10, true),
+        ("Apple", 30, false),
+        ("Cherry", 20, true),
+        ("Date", 20, false),
+    ] {
+        let mut article = Article {
+            id: Auto::auto(),
+            title: title.to_owned(),
+            views,
+            published,
+        };
+        article.insert(&**test_db).await.unwrap();
+    }
+
+    assert_eq!(Article::list_display(), &["title", "views", "status_label"]);
+    assert_eq!(Article::sortable_fields(), &["title", "views"]);
+
+    let titles = async |url: &str, pagination: Pagination| {
+        let request = TestRequestBuilder::get(url)
+            .database(test_db.database())
+            .build();
+        Article::get_objects(&request, pagination)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|article| article.title)
+            .collect::<Vec<_>>()
+    };
+
+    // default ordering, with the primary key as the tiebreaker
+    assert_eq!(
+        titles("/admin/article/", Pagination::new(10, 1)).await,
+        ["Apple", "Cherry", "Date", "Banana"]
+    );
+    assert_eq!(
+        titles("/admin/article/?sort=title", Pagination::new(10, 1)).await,
+        ["Apple", "Banana", "Cherry", "Date"]
+    );
+    assert_eq!(
+        titles("/admin/article/?sort=-title", Pagination::new(2, 2)).await,
+        ["Banana", "Apple"]
+    );
+    assert_eq!(
+        titles("/admin/article/?sort=views", Pagination::new(10, 1)).await,
+        ["Banana", "Cherry", "Date", "Apple"]
+    );
+    // unknown columns fall back to the default ordering
+    assert_eq!(
+        titles("/admin/article/?sort=status_label", Pagination::new(10, 1)).await,
+        ["Apple", "Cherry", "Date", "Banana"]
+    );
+
+    let request = TestRequestBuilder::get("/admin/article/?sort=title")
+        .database(test_db.database())
+        .build();
+    let articles = Article::get_objects(&request, Pagination::new(10, 1))
+        .await
+        .unwrap();
+    assert_eq!(articles[0].list_display_values(), ["Apple", "30", "Draft"]);
+    assert_eq!(
+        articles[1].list_display_values(),
+        ["Banana", "10", "Published"]
+    );
+}
+
 async fn login(server: &TestServer<AdminProject>, driver: &Client) -> Result<(), Box<dyn Error>> {
     login_with(server, driver, DEFAULT_USERNAME, DEFAULT_PASSWORD).await
 }
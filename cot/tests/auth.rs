@@ -1,10 +1,21 @@
 use std::borrow::Cow;
 
-use cot::auth::Auth;
-use cot::auth::db::{DatabaseUser, DatabaseUserCredentials};
-use cot::common_types::Password;
-use cot::request::RequestExt;
+use std::time::Duration;
+
+use cot::auth::db::{
+    ApiToken, ApiTokenError, ApiTokenMiddleware, ApiTokenUser, DatabaseUser,
+    DatabaseUserCredentials, LockoutPolicy,
+};
+use cot::auth::{Auth, AuthError, User};
+use cot::common_types::{Email, Password};
+use cot::config::SecretKey;
+use cot::db::Model;
+use cot::http::{HeaderValue, header};
+use cot::request::{Request, RequestExt};
+use cot::response::Response;
 use cot::test::{TestDatabase, TestRequestBuilder};
+use cot::{Body, StatusCode};
+use tower::{Layer, ServiceExt};
 
 #[cot_macros::dbtest]
 async fn database_user(test_db: &mut TestDatabase) {
@@ -66,3 +77,310 @@ async fn database_user(test_db: &mut TestDatabase) {
     auth.logout().await.unwrap();
     assert!(!auth.user().is_authenticated());
 }
+
+#[cot_macros::dbtest]
+async fn database_user_lockout(test_db: &mut TestDatabase) {
+    test_db.with_auth().run_migrations().await;
+    DatabaseUser::create_user(
+        &**test_db,
+        "testuser".to_string(),
+        &Password::new("password123"),
+    )
+    .await
+    .unwrap();
+    let policy = LockoutPolicy::new(3, Duration::from_secs(60), Duration::from_secs(60));
+    let valid = DatabaseUserCredentials::new("testuser".to_string(), Password::new("password123"));
+    let invalid = DatabaseUserCredentials::new("testuser".to_string(), Password::new("wrong"));
+
+    // A successful login resets the failure count
+    for _ in 0..2 {
+        let user = DatabaseUser::authenticate_with_lockout_policy(&**test_db, &invalid, &policy)
+            .await
+            .unwrap();
+        assert!(user.is_none());
+    }
+    let user = DatabaseUser::authenticate_with_lockout_policy(&**test_db, &valid, &policy)
+        .await
+        .unwrap();
+    assert!(user.is_some());
+
+    for _ in 0..3 {
+        let user = DatabaseUser::authenticate_with_lockout_policy(&**test_db, &invalid, &policy)
+            .await
+            .unwrap();
+        assert!(user.is_none());
+    }
+
+    // The account is locked, even for the correct password
+    let result = DatabaseUser::authenticate_with_lockout_policy(&**test_db, &valid, &policy).await;
+    assert!(matches!(result, Err(AuthError::AccountLocked { .. })));
+
+    // Other accounts (including non-existing ones) are tracked separately
+    let other = DatabaseUserCredentials::new("other".to_string(), Password::new("wrong"));
+    let user = DatabaseUser::authenticate_with_lockout_policy(&**test_db, &other, &policy)
+        .await
+        .unwrap();
+    assert!(user.is_none());
+
+    // The lockout expires
+    let short_policy = LockoutPolicy::new(1, Duration::from_secs(60), Duration::ZERO);
+    DatabaseUser::authenticate_with_lockout_policy(&**test_db, &other, &short_policy)
+        .await
+        .unwrap();
+    let user = DatabaseUser::authenticate_with_lockout_policy(&**test_db, &other, &short_policy)
+        .await
+        .unwrap();
+    assert!(user.is_none());
+}
+
+#[cot_macros::dbtest]
+async fn database_user_lockout_concurrent(test_db: &mut TestDatabase) {
+    test_db.with_auth().run_migrations().await;
+    let policy = LockoutPolicy::new(4, Duration::from_secs(60), Duration::from_secs(60));
+
+    // Concurrent failed attempts are all counted
+    let mut tasks = tokio::task::JoinSet::new();
+    for _ in 0..4 {
+        let db = (**test_db).clone();
+        tasks.spawn(async move {
+            let invalid =
+                DatabaseUserCredentials::new("testuser".to_string(), Password::new("wrong"));
+            DatabaseUser::authenticate_with_lockout_policy(&db, &invalid, &policy).await
+        });
+    }
+    for result in tasks.join_all().await {
+        assert!(result.unwrap().is_none());
+    }
+
+    let invalid = DatabaseUserCredentials::new("testuser".to_string(), Password::new("wrong"));
+    let result =
+        DatabaseUser::authenticate_with_lockout_policy(&**test_db, &invalid, &policy).await;
+    assert!(matches!(result, Err(AuthError::AccountLocked { .. })));
+}
+
+#[cot_macros::dbtest]
+async fn database_user_email_verification(test_db: &mut TestDatabase) {
+    test_db.with_auth().run_migrations().await;
+    let secret_key = SecretKey::new(b"supersecretkey");
+    let mut user = DatabaseUser::create_user(
+        &**test_db,
+        "testuser".to_string(),
+        &Password::new("password123"),
+    )
+    .await
+    .unwrap();
+    assert!(
+        user.email_verification_token(&secret_key, Duration::from_secs(60))
+            .is_none()
+    );
+
+    user.set_email(Some(Email::new("testuser@example.com").unwrap()));
+    user.save(&**test_db).await.unwrap();
+    let token = user
+        .email_verification_token(&secret_key, Duration::from_secs(60))
+        .unwrap();
+
+    // Invalid tokens are rejected
+    let other_key = SecretKey::new(b"otherkey");
+    let verified = DatabaseUser::verify_email(&**test_db, &other_key, &token)
+        .await
+        .unwrap();
+    assert!(verified.is_none());
+    let verified = DatabaseUser::verify_email(&**test_db, &secret_key, "invalid")
+        .await
+        .unwrap();
+    assert!(verified.is_none());
+
+    // A valid token marks the email as verified
+    let verified = DatabaseUser::verify_email(&**test_db, &secret_key, &token)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(verified.id(), user.id());
+    assert!(verified.is_email_verified());
+    let user = DatabaseUser::get_by_id(&**test_db, user.id())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(user.is_email_verified());
+
+    // Changing the email address resets the verification and invalidates the
+    // tokens
+    let mut user = user;
+    user.set_email(Some(Email::new("other@example.com").unwrap()));
+    user.save(&**test_db).await.unwrap();
+    assert!(!user.is_email_verified());
+    let verified = DatabaseUser::verify_email(&**test_db, &secret_key, &token)
+        .await
+        .unwrap();
+    assert!(verified.is_none());
+
+    // Expired tokens are rejected
+    let token = user
+        .email_verification_token(&secret_key, Duration::ZERO)
+        .unwrap();
+    let verified = DatabaseUser::verify_email(&**test_db, &secret_key, &token)
+        .await
+        .unwrap();
+    assert!(verified.is_none());
+}
+
+#[cot_macros::dbtest]
+async fn database_user_password_reset(test_db: &mut TestDatabase) {
+    test_db.with_auth().run_migrations().await;
+    let secret_key = SecretKey::new(b"supersecretkey");
+    let user = DatabaseUser::create_user(
+        &**test_db,
+        "testuser".to_string(),
+        &Password::new("password123"),
+    )
+    .await
+    .unwrap();
+    let token = user.password_reset_token(&secret_key, Duration::from_secs(60));
+
+    // Invalid and expired tokens are rejected
+    let other_key = SecretKey::new(b"otherkey");
+    let verified = DatabaseUser::verify_password_reset_token(&**test_db, &other_key, &token)
+        .await
+        .unwrap();
+    assert!(verified.is_none());
+    let expired = user.password_reset_token(&secret_key, Duration::ZERO);
+    let reset = DatabaseUser::reset_password(
+        &**test_db,
+        &secret_key,
+        &expired,
+        &Password::new("new_password"),
+    )
+    .await
+    .unwrap();
+    assert!(reset.is_none());
+
+    // A valid token allows setting a new password
+    let verified = DatabaseUser::verify_password_reset_token(&**test_db, &secret_key, &token)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(verified.id(), user.id());
+    let reset = DatabaseUser::reset_password(
+        &**test_db,
+        &secret_key,
+        &token,
+        &Password::new("new_password"),
+    )
+    .await
+    .unwrap();
+    assert!(reset.is_some());
+
+    let old_credentials =
+        DatabaseUserCredentials::new("testuser".to_string(), Password::new("password123"));
+    let new_credentials =
+        DatabaseUserCredentials::new("testuser".to_string(), Password::new("new_password"));
+    let authenticated = DatabaseUser::authenticate(&**test_db, &old_credentials)
+        .await
+        .unwrap();
+    assert!(authenticated.is_none());
+    let authenticated = DatabaseUser::authenticate(&**test_db, &new_credentials)
+        .await
+        .unwrap();
+    assert!(authenticated.is_some());
+
+    // The token can only be used once
+    let reset = DatabaseUser::reset_password(
+        &**test_db,
+        &secret_key,
+        &token,
+        &Password::new("another_password"),
+    )
+    .await
+    .unwrap();
+    assert!(reset.is_none());
+}
+
+#[cot_macros::dbtest]
+async fn api_token(test_db: &mut TestDatabase) {
+    test_db.with_auth().run_migrations().await;
+    let user = DatabaseUser::create_user(
+        &**test_db,
+        "testuser".to_string(),
+        &Password::new("password123"),
+    )
+    .await
+    .unwrap();
+    let (token, plaintext) = ApiToken::create(&**test_db, &user, &["read"], None)
+        .await
+        .unwrap();
+    let (_, expired_plaintext) = ApiToken::create(
+        &**test_db,
+        &user,
+        &["read"],
+        Some(chrono::Utc::now() - chrono::Duration::hours(1)),
+    )
+    .await
+    .unwrap();
+
+    let service =
+        ApiTokenMiddleware::new().layer(tower::service_fn(|request: Request| async move {
+            let user = request.extensions().get::<ApiTokenUser>().unwrap();
+            assert_eq!(user.username(), Some(Cow::from("testuser")));
+            user.require_scope("read")?;
+            user.require_scope("write")?;
+            Ok::<_, cot::Error>(Response::new(Body::empty()))
+        }));
+    let request = |token: Option<&str>| {
+        let mut request = TestRequestBuilder::get("/")
+            .database(test_db.database())
+            .build();
+        if let Some(token) = token {
+            request.headers_mut().insert(
+                header::AUTHORIZATION,
+                HeaderValue::from_str(&format!("Bearer {token}")).unwrap(),
+            );
+        }
+        request
+    };
+
+    // Valid token, but missing the "write" scope
+    let error = service
+        .clone()
+        .oneshot(request(Some(&plaintext)))
+        .await
+        .unwrap_err();
+    assert_eq!(error.status_code(), StatusCode::FORBIDDEN);
+
+    // Invalid tokens
+    let error = service.clone().oneshot(request(None)).await.unwrap_err();
+    assert_eq!(error.status_code(), StatusCode::UNAUTHORIZED);
+    assert!(matches!(
+        error.inner().downcast_ref::<ApiTokenError>(),
+        Some(ApiTokenError::MissingToken)
+    ));
+    let error = service
+        .clone()
+        .oneshot(request(Some("invalid")))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error.inner().downcast_ref::<ApiTokenError>(),
+        Some(ApiTokenError::InvalidToken)
+    ));
+    let error = service
+        .clone()
+        .oneshot(request(Some(&expired_plaintext)))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error.inner().downcast_ref::<ApiTokenError>(),
+        Some(ApiTokenError::Expired)
+    ));
+
+    // Revoked token
+    token.revoke(&**test_db).await.unwrap();
+    let error = service
+        .oneshot(request(Some(&plaintext)))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error.inner().downcast_ref::<ApiTokenError>(),
+        Some(ApiTokenError::InvalidToken)
+    ));
+}
@@ -0,0 +1,92 @@
+use bytes::Bytes;
+use cot::client::Client;
+use cot::config::ProjectConfig;
+use cot::html::Html;
+use cot::project::RegisterAppsContext;
+use cot::request::Request;
+use cot::response::{IntoResponse, Response};
+use cot::router::{Route, Router};
+use cot::test::TestServerBuilder;
+use cot::{App, AppBuilder, Body, Project, StatusCode};
+
+async fn hello() -> Html {
+    Html::new("Hello, world!")
+}
+
+async fn echo(request: Request) -> cot::Result<Response> {
+    let body = request.into_body().into_bytes().await?;
+    Body::fixed(body).into_response()
+}
+
+struct ClientTestApp;
+impl App for ClientTestApp {
+    fn name(&self) -> &'static str {
+        "client_test"
+    }
+
+    fn router(&self) -> Router {
+        Router::with_urls([
+            Route::with_handler("/hello", hello),
+            Route::with_handler("/echo", echo),
+        ])
+    }
+}
+
+struct ClientTestProject;
+impl Project for ClientTestProject {
+    fn config(&self, _config_name: &str) -> cot::Result<ProjectConfig> {
+        Ok(ProjectConfig::default())
+    }
+
+    fn register_apps(&self, apps: &mut AppBuilder, _context: &RegisterAppsContext) {
+        apps.register_with_views(ClientTestApp, "");
+    }
+}
+
+#[cot::e2e_test]
+#[cfg_attr(
+    miri,
+    ignore = "unsupported operation: can't call foreign function `sqlite3_open_v2`"
+)]
+async fn client_get() {
+    let server = TestServerBuilder::new(ClientTestProject).start().await;
+    let client = Client::new().unwrap();
+
+    let response = client
+        .get(&format!("{}/hello", server.url()))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.into_body().into_bytes().await.unwrap(),
+        Bytes::from("Hello, world!")
+    );
+
+    server.close().await;
+}
+
+#[cot::e2e_test]
+#[cfg_attr(
+    miri,
+    ignore = "unsupported operation: can't call foreign function `sqlite3_open_v2`"
+)]
+async fn client_send_streaming_body() {
+    let server = TestServerBuilder::new(ClientTestProject).start().await;
+    let client = Client::new().unwrap();
+    let request = http::Request::post(format!("{}/echo", server.url()))
+        .body(Body::from_stream(futures_util::stream::iter([
+            "Hello, ", "world!",
+        ])))
+        .unwrap();
+
+    let response = client.send(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.into_body().into_string_lossy().await.unwrap(),
+        "Hello, world!"
+    );
+
+    server.close().await;
+}
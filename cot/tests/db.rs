@@ -4,13 +4,17 @@
 use bytes::Bytes;
 use cot::auth::PasswordHash;
 use cot::common_types::{Email, Password, Url};
-use cot::db::migrations::{Field, Operation};
-use cot::db::query::ExprEq;
+use cot::db::fixtures::{FixtureError, Fixtures};
+use cot::db::migrations::{DefaultValue, Field, Operation};
+use cot::db::query::{Expr, ExprEq};
 use cot::db::{
-    Auto, Database, DatabaseError, DatabaseField, ForeignKey, ForeignKeyOnDeletePolicy,
-    ForeignKeyOnUpdatePolicy, Identifier, LimitedString, Model, model, query,
+    Auto, Choice, Database, DatabaseError, DatabaseField, ForeignKey, ForeignKeyOnDeletePolicy,
+    ForeignKeyOnUpdatePolicy, FromRow, Identifier, Json, LimitedString, Model, Row, RowsNum, Slug,
+    model, query,
 };
-use cot::test::TestDatabase;
+use cot::form::fields::SelectChoice;
+use cot::form::{Form, PatchForm};
+use cot::test::{TestDatabase, TestRequestBuilder};
 use fake::rand::rngs::StdRng;
 use fake::rand::{RngExt, SeedableRng};
 use fake::{Dummy, Fake, Faker};
@@ -75,6 +79,11 @@ struct TestModel {
     name: String,
 }
 
+#[cot_macros::dbtest]
+async fn ping(test_db: &mut TestDatabase) {
+    test_db.ping().await.unwrap();
+}
+
 #[cot_macros::dbtest]
 async fn model_crud(test_db: &mut TestDatabase) {
     migrate_test_model(&*test_db).await;
@@ -167,25 +176,1005 @@ async fn model_update(test_db: &mut TestDatabase) {
 async fn model_macro_filtering(test_db: &mut TestDatabase) {
     migrate_test_model(&*test_db).await;
 
-    assert_eq!(TestModel::objects().all(&**test_db).await.unwrap(), vec![]);
+    assert_eq!(TestModel::objects().all(&**test_db).await.unwrap(), vec![]);
+
+    let mut model = TestModel {
+        id: Auto::auto(),
+        name: "test".to_owned(),
+    };
+    model.save(&**test_db).await.unwrap();
+    let objects = query!(TestModel, $name == "test")
+        .all(&**test_db)
+        .await
+        .unwrap();
+    assert_eq!(objects.len(), 1);
+    assert_eq!(objects[0].name, "test");
+
+    let objects = query!(TestModel, $name == "t")
+        .all(&**test_db)
+        .await
+        .unwrap();
+    assert!(objects.is_empty());
+}
+
+#[cot_macros::dbtest]
+async fn model_delete_rows_affected(test_db: &mut TestDatabase) {
+    migrate_test_model(&*test_db).await;
+
+    for name in ["a", "a", "b"] {
+        let mut model = TestModel {
+            id: Auto::auto(),
+            name: name.to_owned(),
+        };
+        model.save(&**test_db).await.unwrap();
+    }
+
+    let result = query!(TestModel, $name == "c")
+        .delete(&**test_db)
+        .await
+        .unwrap();
+    assert_eq!(result.rows_affected(), RowsNum(0));
+
+    let result = query!(TestModel, $name == "a")
+        .delete(&**test_db)
+        .await
+        .unwrap();
+    assert_eq!(result.rows_affected(), RowsNum(2));
+
+    let objects = TestModel::objects().all(&**test_db).await.unwrap();
+    assert_eq!(objects.len(), 1);
+    assert_eq!(objects[0].name, "b");
+}
+
+#[cot_macros::dbtest]
+async fn model_delete_returning(test_db: &mut TestDatabase) {
+    migrate_test_model(&*test_db).await;
+
+    for name in ["a", "b"] {
+        let mut model = TestModel {
+            id: Auto::auto(),
+            name: name.to_owned(),
+        };
+        model.save(&**test_db).await.unwrap();
+    }
+
+    let deleted = query!(TestModel, $name == "c")
+        .delete_returning(&**test_db)
+        .await
+        .unwrap();
+    assert!(deleted.is_empty());
+
+    let deleted = query!(TestModel, $name == "a")
+        .delete_returning(&**test_db)
+        .await
+        .unwrap();
+    assert_eq!(deleted.len(), 1);
+    assert_eq!(deleted[0].name, "a");
+
+    let objects = TestModel::objects().all(&**test_db).await.unwrap();
+    assert_eq!(objects.len(), 1);
+    assert_eq!(objects[0].name, "b");
+}
+
+#[cot_macros::dbtest]
+async fn model_bulk_update(test_db: &mut TestDatabase) {
+    migrate_test_model(&*test_db).await;
+
+    for name in ["a", "a", "b"] {
+        let mut model = TestModel {
+            id: Auto::auto(),
+            name: name.to_owned(),
+        };
+        model.save(&**test_db).await.unwrap();
+    }
+
+    let result = query!(TestModel, $name == "c")
+        .update(&**test_db, &[<TestModel as Model>::Fields::name.set("d")])
+        .await
+        .unwrap();
+    assert_eq!(result.rows_affected(), RowsNum(0));
+
+    let result = query!(TestModel, $name == "a")
+        .update(&**test_db, &[<TestModel as Model>::Fields::name.set("c")])
+        .await
+        .unwrap();
+    assert_eq!(result.rows_affected(), RowsNum(2));
+
+    let objects = query!(TestModel, $name == "c")
+        .all(&**test_db)
+        .await
+        .unwrap();
+    assert_eq!(objects.len(), 2);
+    let objects = query!(TestModel, $name == "b")
+        .all(&**test_db)
+        .await
+        .unwrap();
+    assert_eq!(objects.len(), 1);
+}
+
+#[derive(Debug, PartialEq)]
+#[model]
+struct ProfileModel {
+    #[model(primary_key)]
+    id: Auto<i32>,
+    name: String,
+    bio: Option<String>,
+}
+
+#[derive(Form)]
+#[form(patch = ProfileModel)]
+struct ProfilePatchForm {
+    name: Option<String>,
+    bio: Option<Option<String>>,
+}
+
+const CREATE_PROFILE_MODEL: Operation = Operation::create_model()
+    .table_name(Identifier::new("cot__profile_model"))
+    .fields(&[
+        Field::new(Identifier::new("id"), <Auto<i32> as DatabaseField>::TYPE)
+            .primary_key()
+            .auto(),
+        Field::new(Identifier::new("name"), <String as DatabaseField>::TYPE),
+        Field::new(Identifier::new("bio"), <String as DatabaseField>::TYPE).set_null(true),
+    ])
+    .build();
+
+async fn apply_profile_patch(db: &Database, id: i32, form_data: &[(&str, &str)]) -> ProfileModel {
+    let mut request = TestRequestBuilder::post("/").form_data(form_data).build();
+    let form = ProfilePatchForm::from_request(&mut request)
+        .await
+        .unwrap()
+        .unwrap();
+
+    query!(ProfileModel, $id == id)
+        .update(db, &form.into_assignments())
+        .await
+        .unwrap();
+
+    query!(ProfileModel, $id == id).get(db).await.unwrap()
+}
+
+#[cot_macros::dbtest]
+async fn model_patch_form_update(test_db: &mut TestDatabase) {
+    CREATE_PROFILE_MODEL.forwards(&*test_db).await.unwrap();
+
+    let mut profile = ProfileModel {
+        id: Auto::fixed(1),
+        name: "Alice".to_owned(),
+        bio: Some("Hello".to_owned()),
+    };
+    profile.save(&**test_db).await.unwrap();
+
+    // omitting a field leaves its value unchanged
+    let profile = apply_profile_patch(&**test_db, 1, &[("name", "Bob")]).await;
+    assert_eq!(profile.name, "Bob");
+    assert_eq!(profile.bio, Some("Hello".to_owned()));
+
+    // submitting an empty value clears the field
+    let profile = apply_profile_patch(&**test_db, 1, &[("bio", "")]).await;
+    assert_eq!(profile.name, "Bob");
+    assert_eq!(profile.bio, None);
+
+    // submitting nothing doesn't change anything
+    let profile = apply_profile_patch(&**test_db, 1, &[]).await;
+    assert_eq!(profile.name, "Bob");
+    assert_eq!(profile.bio, None);
+}
+
+#[cot_macros::dbtest]
+async fn model_all_executes_single_query(test_db: &mut TestDatabase) {
+    migrate_test_model(&*test_db).await;
+
+    for name in ["a", "b", "c"] {
+        let mut model = TestModel {
+            id: Auto::auto(),
+            name: name.to_owned(),
+        };
+        model.save(&**test_db).await.unwrap();
+    }
+    test_db.clear_queries();
+
+    let objects = TestModel::objects().all(&**test_db).await.unwrap();
+    assert_eq!(objects.len(), 3);
+
+    let queries = test_db.queries();
+    assert_eq!(queries.len(), 1);
+    assert!(queries[0].sql().starts_with("SELECT"));
+}
+
+#[cot_macros::dbtest]
+async fn model_first_and_get(test_db: &mut TestDatabase) {
+    migrate_test_model(&*test_db).await;
+
+    for name in ["a", "b", "b"] {
+        let mut model = TestModel {
+            id: Auto::auto(),
+            name: name.to_owned(),
+        };
+        model.save(&**test_db).await.unwrap();
+    }
+
+    let model = query!(TestModel, $name == "a")
+        .get(&**test_db)
+        .await
+        .unwrap();
+    assert_eq!(model.name, "a");
+    let model = query!(TestModel, $name == "a")
+        .first(&**test_db)
+        .await
+        .unwrap();
+    assert_eq!(model.unwrap().name, "a");
+
+    let result = query!(TestModel, $name == "c").get(&**test_db).await;
+    assert!(matches!(result, Err(DatabaseError::NotFound)));
+    let model = query!(TestModel, $name == "c")
+        .first(&**test_db)
+        .await
+        .unwrap();
+    assert!(model.is_none());
+
+    let result = query!(TestModel, $name == "b").get(&**test_db).await;
+    assert!(matches!(result, Err(DatabaseError::MultipleObjects)));
+    let model = query!(TestModel, $name == "b")
+        .first(&**test_db)
+        .await
+        .unwrap();
+    assert_eq!(model.unwrap().name, "b");
+}
+
+#[cot_macros::dbtest]
+async fn model_order_by(test_db: &mut TestDatabase) {
+    migrate_test_model(&*test_db).await;
+
+    for name in ["b", "c", "a", "b"] {
+        let mut model = TestModel {
+            id: Auto::auto(),
+            name: name.to_owned(),
+        };
+        model.save(&**test_db).await.unwrap();
+    }
+
+    let names = |models: Vec<TestModel>| -> Vec<_> {
+        models
+            .into_iter()
+            .map(|model| (model.id.unwrap(), model.name))
+            .collect()
+    };
+
+    let models = TestModel::objects()
+        .order_by(<TestModel as Model>::Fields::name.asc())
+        .order_by(<TestModel as Model>::Fields::id.desc())
+        .all(&**test_db)
+        .await
+        .unwrap();
+    assert_eq!(
+        names(models),
+        [
+            (3, "a".to_owned()),
+            (4, "b".to_owned()),
+            (1, "b".to_owned()),
+            (2, "c".to_owned())
+        ]
+    );
+
+    let models = TestModel::objects()
+        .order_by(<TestModel as Model>::Fields::name.desc())
+        .limit(2)
+        .all(&**test_db)
+        .await
+        .unwrap();
+    assert_eq!(models[0].name, "c");
+    assert_eq!(models[1].name, "b");
+
+    let model = TestModel::objects()
+        .order_by(<TestModel as Model>::Fields::name.asc())
+        .first(&**test_db)
+        .await
+        .unwrap();
+    assert_eq!(model.unwrap().name, "a");
+}
+
+#[cot_macros::dbtest]
+async fn model_paginate(test_db: &mut TestDatabase) {
+    migrate_test_model(&*test_db).await;
+
+    for name in ["a", "b", "c", "d", "e"] {
+        let mut model = TestModel {
+            id: Auto::auto(),
+            name: name.to_owned(),
+        };
+        model.save(&**test_db).await.unwrap();
+    }
+
+    let mut query = TestModel::objects();
+    query.order_by(<TestModel as Model>::Fields::name.asc());
+
+    let page = query.paginate(test_db, 2, 2).await.unwrap();
+    let names: Vec<_> = page
+        .items()
+        .iter()
+        .map(|model| model.name.as_str())
+        .collect();
+    assert_eq!(names, ["c", "d"]);
+    assert_eq!(page.page(), 2);
+    assert_eq!(page.total_count(), 5);
+    assert_eq!(page.total_pages(), 3);
+    assert!(
+        page.pagination(|page| format!("?page={page}"))
+            .as_str()
+            .contains("<a href=\"?page=2\" aria-current=\"page\">2</a>")
+    );
+
+    let last_page = query.paginate(test_db, 3, 2).await.unwrap();
+    assert_eq!(last_page.items().len(), 1);
+
+    let past_end = query.paginate(test_db, 4, 2).await.unwrap();
+    assert!(past_end.items().is_empty());
+    assert_eq!(past_end.total_pages(), 3);
+}
+
+#[cot_macros::dbtest]
+async fn model_get_or_create(test_db: &mut TestDatabase) {
+    migrate_test_model(&*test_db).await;
+
+    let create = || TestModel {
+        id: Auto::auto(),
+        name: "test".to_owned(),
+    };
+    let (model, created) = query!(TestModel, $name == "test")
+        .get_or_create(&**test_db, create)
+        .await
+        .unwrap();
+    assert!(created);
+    assert_eq!(model.name, "test");
+
+    let (existing, created) = query!(TestModel, $name == "test")
+        .get_or_create(&**test_db, create)
+        .await
+        .unwrap();
+    assert!(!created);
+    assert_eq!(existing, model);
+    assert_eq!(TestModel::objects().count(test_db).await.unwrap(), 1);
+}
+
+#[cot_macros::dbtest]
+async fn model_update_or_create(test_db: &mut TestDatabase) {
+    migrate_test_model(&*test_db).await;
+
+    let (model, created) = query!(TestModel, $name == "test")
+        .update_or_create(
+            &**test_db,
+            |_| unreachable!(),
+            || TestModel {
+                id: Auto::auto(),
+                name: "test".to_owned(),
+            },
+        )
+        .await
+        .unwrap();
+    assert!(created);
+
+    let (updated, created) = query!(TestModel, $name == "test")
+        .update_or_create(
+            &**test_db,
+            |model| model.name = "updated".to_owned(),
+            || unreachable!(),
+        )
+        .await
+        .unwrap();
+    assert!(!created);
+    assert_eq!(updated.id, model.id);
+
+    let objects = TestModel::objects().all(&**test_db).await.unwrap();
+    assert_eq!(objects, vec![updated]);
+    assert_eq!(objects[0].name, "updated");
+}
+
+#[derive(Debug, PartialEq)]
+#[model]
+struct UniqueNameModel {
+    #[model(primary_key)]
+    id: Auto<i32>,
+    #[model(unique)]
+    name: String,
+}
+
+const CREATE_UNIQUE_NAME_MODEL: Operation = Operation::create_model()
+    .table_name(Identifier::new("cot__unique_name_model"))
+    .fields(&[
+        Field::new(Identifier::new("id"), <Auto<i32> as DatabaseField>::TYPE)
+            .primary_key()
+            .auto(),
+        Field::new(Identifier::new("name"), <String as DatabaseField>::TYPE).unique(),
+    ])
+    .build();
+
+#[cot_macros::dbtest]
+async fn model_get_or_create_concurrent(test_db: &mut TestDatabase) {
+    CREATE_UNIQUE_NAME_MODEL.forwards(test_db).await.unwrap();
+
+    let mut tasks = tokio::task::JoinSet::new();
+    for _ in 0..8 {
+        let db = (**test_db).clone();
+        tasks.spawn(async move {
+            query!(UniqueNameModel, $name == "test")
+                .get_or_create(&db, || UniqueNameModel {
+                    id: Auto::auto(),
+                    name: "test".to_owned(),
+                })
+                .await
+        });
+    }
+    let results: Vec<_> = tasks
+        .join_all()
+        .await
+        .into_iter()
+        .map(Result::unwrap)
+        .collect();
+
+    assert_eq!(results.iter().filter(|(_, created)| *created).count(), 1);
+    let (model, _) = &results[0];
+    assert!(results.iter().all(|(other, _)| other == model));
+    assert_eq!(
+        UniqueNameModel::objects().count(&**test_db).await.unwrap(),
+        1
+    );
+}
+
+#[cot_macros::dbtest]
+async fn transaction_commit_and_rollback(test_db: &mut TestDatabase) {
+    migrate_test_model(&*test_db).await;
+
+    let transaction = test_db.begin().await.unwrap();
+    let mut committed = TestModel {
+        id: Auto::auto(),
+        name: "committed".to_owned(),
+    };
+    committed.insert(&*transaction).await.unwrap();
+    transaction.commit().await.unwrap();
+
+    let transaction = test_db.begin().await.unwrap();
+    let mut rolled_back = TestModel {
+        id: Auto::auto(),
+        name: "rolled back".to_owned(),
+    };
+    rolled_back.insert(&*transaction).await.unwrap();
+    assert_eq!(TestModel::objects().count(&*transaction).await.unwrap(), 2);
+    transaction.rollback().await.unwrap();
+
+    let transaction = test_db.begin().await.unwrap();
+    let mut dropped = TestModel {
+        id: Auto::auto(),
+        name: "dropped".to_owned(),
+    };
+    dropped.insert(&*transaction).await.unwrap();
+    drop(transaction);
+
+    assert_eq!(
+        TestModel::objects().all(&**test_db).await.unwrap(),
+        vec![committed]
+    );
+}
+
+#[cot_macros::dbtest]
+async fn transaction_nested(test_db: &mut TestDatabase) {
+    let transaction = test_db.begin().await.unwrap();
+
+    let result = transaction.begin().await;
+
+    assert!(matches!(result, Err(DatabaseError::NestedTransaction)));
+}
+
+#[cot_macros::dbtest]
+async fn model_filter_in_like_and_range(test_db: &mut TestDatabase) {
+    migrate_test_model(&*test_db).await;
+
+    for name in ["foo", "barfoo", "bar", "baz"] {
+        let mut model = TestModel {
+            id: Auto::auto(),
+            name: name.to_owned(),
+        };
+        model.save(&**test_db).await.unwrap();
+    }
+    let sorted_names = |objects: Vec<TestModel>| {
+        let mut names: Vec<_> = objects.into_iter().map(|object| object.name).collect();
+        names.sort();
+        names
+    };
+
+    let names = ["bar", "baz", "qux"];
+    let objects = query!(TestModel, $name in names)
+        .all(&**test_db)
+        .await
+        .unwrap();
+    assert_eq!(sorted_names(objects), ["bar", "baz"]);
+
+    let empty: Vec<String> = Vec::new();
+    let objects = query!(TestModel, $name in empty)
+        .all(&**test_db)
+        .await
+        .unwrap();
+    assert!(objects.is_empty());
+
+    let objects = query!(TestModel, $name like "%foo")
+        .all(&**test_db)
+        .await
+        .unwrap();
+    assert_eq!(sorted_names(objects), ["barfoo", "foo"]);
+
+    let objects = query!(TestModel, $name >= "baz")
+        .all(&**test_db)
+        .await
+        .unwrap();
+    assert_eq!(sorted_names(objects), ["baz", "foo"]);
+}
+
+#[cot_macros::dbtest]
+async fn model_raw_query(test_db: &mut TestDatabase) {
+    migrate_test_model(&*test_db).await;
+
+    for name in ["b", "a", "b"] {
+        let mut model = TestModel {
+            id: Auto::auto(),
+            name: name.to_owned(),
+        };
+        model.save(&**test_db).await.unwrap();
+    }
+
+    let objects: Vec<TestModel> = test_db
+        .raw_query("SELECT * FROM cot__test_model ORDER BY name, id", &[])
+        .await
+        .unwrap();
+    let names: Vec<_> = objects.iter().map(|object| object.name.as_str()).collect();
+    assert_eq!(names, ["a", "b", "b"]);
+
+    let counts: Vec<NameCount> = test_db
+        .raw_query(
+            "SELECT name, COUNT(*) OVER (PARTITION BY name) AS name_count \
+            FROM cot__test_model ORDER BY id",
+            &[],
+        )
+        .await
+        .unwrap();
+    let counts: Vec<_> = counts
+        .iter()
+        .map(|count| (count.name.as_str(), count.count))
+        .collect();
+    assert_eq!(counts, [("b", 2), ("a", 1), ("b", 2)]);
+}
+
+#[cot_macros::dbtest]
+async fn model_raw_query_column_mismatch(test_db: &mut TestDatabase) {
+    migrate_test_model(&*test_db).await;
+
+    let mut model = TestModel {
+        id: Auto::auto(),
+        name: "a".to_owned(),
+    };
+    model.save(&**test_db).await.unwrap();
+
+    let result = test_db
+        .raw_query::<TestModel>("SELECT name, id FROM cot__test_model", &[])
+        .await;
+    assert!(matches!(
+        result,
+        Err(DatabaseError::UnexpectedColumns { .. })
+    ));
+}
+
+#[cot_macros::dbtest]
+async fn model_raw_query_derive_from_row(test_db: &mut TestDatabase) {
+    migrate_test_model(&*test_db).await;
+
+    for name in ["b", "a", "b"] {
+        let mut model = TestModel {
+            id: Auto::auto(),
+            name: name.to_owned(),
+        };
+        model.save(&**test_db).await.unwrap();
+    }
+
+    let summaries: Vec<NameSummary> = test_db
+        .raw_query(
+            "SELECT COUNT(*) AS name_count, CASE WHEN name = 'a' THEN name END AS nickname, name \
+            FROM cot__test_model GROUP BY name ORDER BY name",
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        summaries,
+        [
+            NameSummary {
+                name: "a".to_owned(),
+                count: 1,
+                nickname: Some("a".to_owned()),
+            },
+            NameSummary {
+                name: "b".to_owned(),
+                count: 2,
+                nickname: None,
+            },
+        ]
+    );
+}
+
+#[cot_macros::dbtest]
+async fn model_raw_query_derive_from_row_missing_column(test_db: &mut TestDatabase) {
+    migrate_test_model(&*test_db).await;
+
+    let mut model = TestModel {
+        id: Auto::auto(),
+        name: "a".to_owned(),
+    };
+    model.save(&**test_db).await.unwrap();
+
+    let result = test_db
+        .raw_query::<NameSummary>("SELECT name, name AS nickname FROM cot__test_model", &[])
+        .await;
+    match result {
+        Err(DatabaseError::MissingColumn { column, found }) => {
+            assert_eq!(column, "name_count");
+            assert_eq!(found, ["name", "nickname"]);
+        }
+        _ => panic!("expected a missing column error, got {result:?}"),
+    }
+}
+
+#[derive(Debug, PartialEq, FromRow)]
+struct NameSummary {
+    name: String,
+    #[row(rename = "name_count")]
+    count: i64,
+    nickname: Option<String>,
+}
+
+struct NameCount {
+    name: String,
+    count: i64,
+}
+
+impl FromRow for NameCount {
+    fn from_row(row: Row) -> cot::db::Result<Self> {
+        Ok(Self {
+            name: row.get(0)?,
+            count: row.get(1)?,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+#[model]
+struct DefaultsModel {
+    #[model(primary_key)]
+    id: Auto<i32>,
+    #[model(default = 10)]
+    views: i64,
+    #[model(default = "unnamed")]
+    nickname: Option<String>,
+    #[model(default = 2 * 21)]
+    answer: Option<i32>,
+}
+
+const CREATE_DEFAULTS_MODEL: Operation = Operation::create_model()
+    .table_name(Identifier::new("cot__defaults_model"))
+    .fields(&[
+        Field::new(Identifier::new("id"), <Auto<i32> as DatabaseField>::TYPE)
+            .primary_key()
+            .auto(),
+        Field::new(Identifier::new("views"), <i64 as DatabaseField>::TYPE)
+            .default_value(DefaultValue::Int(10)),
+        Field::new(
+            Identifier::new("nickname"),
+            <Option<String> as DatabaseField>::TYPE,
+        )
+        .set_null(<Option<String> as DatabaseField>::NULLABLE)
+        .default_value(DefaultValue::String("unnamed")),
+        Field::new(
+            Identifier::new("answer"),
+            <Option<i32> as DatabaseField>::TYPE,
+        )
+        .set_null(<Option<i32> as DatabaseField>::NULLABLE),
+    ])
+    .build();
+
+#[cot_macros::dbtest]
+async fn model_field_defaults(test_db: &mut TestDatabase) {
+    CREATE_DEFAULTS_MODEL.forwards(test_db).await.unwrap();
+
+    let mut model = DefaultsModel {
+        id: Auto::auto(),
+        views: 5,
+        nickname: None,
+        answer: Some(1),
+    };
+    model.save(&**test_db).await.unwrap();
+    assert_eq!(model.nickname.as_deref(), Some("unnamed"));
+    assert_eq!(model.answer, Some(1));
+
+    let mut models = [DefaultsModel {
+        id: Auto::auto(),
+        views: 5,
+        nickname: Some("set".to_owned()),
+        answer: None,
+    }];
+    DefaultsModel::bulk_insert(&**test_db, &mut models)
+        .await
+        .unwrap();
+    assert_eq!(models[0].nickname.as_deref(), Some("set"));
+    assert_eq!(models[0].answer, Some(42));
+
+    // the SQL `DEFAULT` is used when the column is omitted
+    test_db
+        .raw("INSERT INTO cot__defaults_model (answer) VALUES (7)")
+        .await
+        .unwrap();
+    let model = query!(DefaultsModel, $answer == Some(7))
+        .get(&**test_db)
+        .await
+        .unwrap();
+    assert_eq!(model.views, 10);
+    assert_eq!(model.nickname.as_deref(), Some("unnamed"));
+}
+
+#[derive(Debug, PartialEq)]
+#[model]
+struct MembershipModel {
+    #[model(primary_key)]
+    group_id: i32,
+    #[model(primary_key)]
+    user_id: i32,
+    role: String,
+}
+
+const CREATE_MEMBERSHIP_MODEL: Operation = Operation::create_model()
+    .table_name(Identifier::new("cot__membership_model"))
+    .fields(&[
+        Field::new(Identifier::new("group_id"), <i32 as DatabaseField>::TYPE).primary_key(),
+        Field::new(Identifier::new("user_id"), <i32 as DatabaseField>::TYPE).primary_key(),
+        Field::new(Identifier::new("role"), <String as DatabaseField>::TYPE),
+    ])
+    .build();
+
+#[cot_macros::dbtest]
+async fn model_composite_primary_key(test_db: &mut TestDatabase) {
+    CREATE_MEMBERSHIP_MODEL.forwards(test_db).await.unwrap();
+
+    let mut member = MembershipModel {
+        group_id: 1,
+        user_id: 1,
+        role: "member".to_owned(),
+    };
+    member.save(&**test_db).await.unwrap();
+    let mut owner = MembershipModel {
+        group_id: 1,
+        user_id: 2,
+        role: "owner".to_owned(),
+    };
+    owner.save(&**test_db).await.unwrap();
+    assert_eq!(member.primary_key(), (1, 1));
+
+    // saving again updates the row with the same full key
+    member.role = "admin".to_owned();
+    member.save(&**test_db).await.unwrap();
+    owner.role = "maintainer".to_owned();
+    owner.update(&**test_db).await.unwrap();
+    assert_eq!(MembershipModel::objects().count(test_db).await.unwrap(), 2);
+
+    let found = MembershipModel::get_by_primary_key(&**test_db, (1, 1))
+        .await
+        .unwrap();
+    assert_eq!(found, Some(member));
+    let found = MembershipModel::get_by_primary_key(&**test_db, (1, 2))
+        .await
+        .unwrap();
+    assert_eq!(found, Some(owner));
+    let missing = MembershipModel::get_by_primary_key(&**test_db, (2, 1))
+        .await
+        .unwrap();
+    assert_eq!(missing, None);
+
+    query!(MembershipModel, $group_id == 1 && $user_id == 2)
+        .delete(&**test_db)
+        .await
+        .unwrap();
+    let deleted = MembershipModel::get_by_primary_key(&**test_db, (1, 2))
+        .await
+        .unwrap();
+    assert_eq!(deleted, None);
+    assert_eq!(MembershipModel::objects().count(test_db).await.unwrap(), 1);
+}
+
+#[derive(Debug, Clone, PartialEq, SelectChoice)]
+enum ArticleStatus {
+    #[select_choice(id = "draft")]
+    Draft,
+    #[select_choice(id = "published")]
+    Published,
+}
+
+#[derive(Debug, PartialEq)]
+#[model]
+struct ChoicesModel {
+    #[model(primary_key)]
+    id: Auto<i32>,
+    #[model(choices)]
+    status: Choice<ArticleStatus>,
+}
+
+const CREATE_CHOICES_MODEL: Operation = Operation::create_model()
+    .table_name(Identifier::new("cot__choices_model"))
+    .fields(&[
+        Field::new(Identifier::new("id"), <i32 as DatabaseField>::TYPE)
+            .primary_key()
+            .auto(),
+        Field::new(
+            Identifier::new("status"),
+            <Choice<ArticleStatus> as DatabaseField>::TYPE,
+        )
+        .choices(&["draft", "published"]),
+    ])
+    .build();
+
+#[cot_macros::dbtest(backends = ["sqlite", "postgres"])]
+async fn model_choices_check_constraint(test_db: &mut TestDatabase) {
+    CREATE_CHOICES_MODEL.forwards(test_db).await.unwrap();
+
+    let mut model = ChoicesModel {
+        id: Auto::auto(),
+        status: Choice(ArticleStatus::Published),
+    };
+    model.save(&**test_db).await.unwrap();
+    let objects = ChoicesModel::objects().all(&**test_db).await.unwrap();
+    assert_eq!(objects, vec![model]);
+
+    let result = test_db
+        .raw("INSERT INTO cot__choices_model (status) VALUES ('deleted')")
+        .await;
+    assert!(result.is_err());
+}
+
+#[cot_macros::dbtest(backends = ["postgres"])]
+async fn alter_field_choices(test_db: &mut TestDatabase) {
+    const ALTER_CHOICES: Operation = Operation::alter_field_choices()
+        .table_name(Identifier::new("cot__choices_model"))
+        .field_name(Identifier::new("status"))
+        .old_choices(&["draft", "published"])
+        .new_choices(&["draft", "published", "archived"])
+        .build();
+    const INSERT_ARCHIVED: &str = "INSERT INTO cot__choices_model (status) VALUES ('archived')";
+
+    CREATE_CHOICES_MODEL.forwards(test_db).await.unwrap();
+    assert!(test_db.raw(INSERT_ARCHIVED).await.is_err());
+
+    ALTER_CHOICES.forwards(test_db).await.unwrap();
+    test_db.raw(INSERT_ARCHIVED).await.unwrap();
+    assert!(
+        test_db
+            .raw("INSERT INTO cot__choices_model (status) VALUES ('deleted')")
+            .await
+            .is_err()
+    );
+
+    test_db
+        .raw("DELETE FROM cot__choices_model WHERE status = 'archived'")
+        .await
+        .unwrap();
+    ALTER_CHOICES.backwards(test_db).await.unwrap();
+    assert!(test_db.raw(INSERT_ARCHIVED).await.is_err());
+}
+
+#[derive(Debug, PartialEq)]
+#[model(timestamps)]
+struct TimestampsModel {
+    #[model(primary_key)]
+    id: Auto<i32>,
+    title: String,
+    created_at: chrono::DateTime<chrono::Utc>,
+    updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl TimestampsModel {
+    fn new(title: &str) -> Self {
+        Self {
+            id: Auto::auto(),
+            title: title.to_owned(),
+            created_at: chrono::DateTime::UNIX_EPOCH,
+            updated_at: chrono::DateTime::UNIX_EPOCH,
+        }
+    }
+}
+
+const CREATE_TIMESTAMPS_MODEL: Operation = Operation::create_model()
+    .table_name(Identifier::new("cot__timestamps_model"))
+    .fields(&[
+        Field::new(Identifier::new("id"), <Auto<i32> as DatabaseField>::TYPE)
+            .primary_key()
+            .auto(),
+        Field::new(Identifier::new("title"), <String as DatabaseField>::TYPE),
+        Field::new(
+            Identifier::new("created_at"),
+            <chrono::DateTime<chrono::Utc> as DatabaseField>::TYPE,
+        ),
+        Field::new(
+            Identifier::new("updated_at"),
+            <chrono::DateTime<chrono::Utc> as DatabaseField>::TYPE,
+        ),
+    ])
+    .build();
+
+#[cot_macros::dbtest]
+async fn model_timestamps(test_db: &mut TestDatabase) {
+    CREATE_TIMESTAMPS_MODEL.forwards(test_db).await.unwrap();
+
+    let mut model = TimestampsModel::new("first");
+    model.save(&**test_db).await.unwrap();
+    let created_at = model.created_at;
+    assert_ne!(created_at, chrono::DateTime::UNIX_EPOCH);
+    assert_eq!(model.updated_at, created_at);
+
+    model.title = "changed".to_owned();
+    model.updated_at = chrono::DateTime::UNIX_EPOCH;
+    model.save(&**test_db).await.unwrap();
+    assert_eq!(model.created_at, created_at);
+    assert!(model.updated_at >= created_at);
+
+    // updating an existing row keeps its creation time, even if the instance
+    // has a different one
+    let mut stale = TimestampsModel {
+        id: model.id,
+        ..TimestampsModel::new("stale")
+    };
+    stale.save(&**test_db).await.unwrap();
+    test_db
+        .bulk_insert_or_update(&mut [TimestampsModel {
+            id: model.id,
+            ..TimestampsModel::new("bulk stale")
+        }])
+        .await
+        .unwrap();
+    let saved = TimestampsModel::get_by_primary_key(&**test_db, model.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(saved.title, "bulk stale");
+    assert_eq!(saved.created_at, created_at);
+
+    let mut models = [
+        TimestampsModel::new("second"),
+        TimestampsModel::new("third"),
+    ];
+    TimestampsModel::bulk_insert(&**test_db, &mut models)
+        .await
+        .unwrap();
+    for model in &models {
+        assert_ne!(model.created_at, chrono::DateTime::UNIX_EPOCH);
+        assert_eq!(model.updated_at, model.created_at);
+    }
 
-    let mut model = TestModel {
-        id: Auto::auto(),
-        name: "test".to_owned(),
-    };
-    model.save(&**test_db).await.unwrap();
-    let objects = query!(TestModel, $name == "test")
-        .all(&**test_db)
+    // explicit assignments take precedence over the automatic ones
+    let mut query = TimestampsModel::objects();
+    query.filter(<TimestampsModel as Model>::Fields::title.eq("second"));
+    query
+        .update(
+            &**test_db,
+            &[<TimestampsModel as Model>::Fields::updated_at.set(chrono::DateTime::UNIX_EPOCH)],
+        )
         .await
         .unwrap();
-    assert_eq!(objects.len(), 1);
-    assert_eq!(objects[0].name, "test");
+    let model = query.get(&**test_db).await.unwrap();
+    assert_eq!(model.updated_at, chrono::DateTime::UNIX_EPOCH);
 
-    let objects = query!(TestModel, $name == "t")
-        .all(&**test_db)
+    query
+        .update(
+            &**test_db,
+            &[<TimestampsModel as Model>::Fields::title.set("second")],
+        )
         .await
         .unwrap();
-    assert!(objects.is_empty());
+    let updated = query.get(&**test_db).await.unwrap();
+    assert_eq!(updated.created_at, model.created_at);
+    assert_ne!(updated.updated_at, chrono::DateTime::UNIX_EPOCH);
 }
 
 async fn migrate_test_model(db: &Database) {
@@ -453,6 +1442,112 @@ async fn password_hash_option(db: &TestDatabase) {
     assert!(models[1].password.is_none());
 }
 
+#[cot_macros::dbtest]
+async fn load_fixtures(db: &mut TestDatabase) {
+    #[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+    #[model]
+    struct FixtureAuthor {
+        #[model(primary_key)]
+        #[serde(default)]
+        id: Auto<i32>,
+        name: String,
+    }
+
+    #[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+    #[model]
+    struct FixtureBook {
+        #[model(primary_key)]
+        #[serde(default)]
+        id: Auto<i32>,
+        author: ForeignKey<FixtureAuthor>,
+        title: String,
+    }
+
+    const CREATE_AUTHOR: Operation = Operation::create_model()
+        .table_name(Identifier::new("cot__fixture_author"))
+        .fields(&[
+            Field::new(Identifier::new("id"), <Auto<i32> as DatabaseField>::TYPE)
+                .primary_key()
+                .auto(),
+            Field::new(Identifier::new("name"), <String as DatabaseField>::TYPE),
+        ])
+        .build();
+    const CREATE_BOOK: Operation = Operation::create_model()
+        .table_name(Identifier::new("cot__fixture_book"))
+        .fields(&[
+            Field::new(Identifier::new("id"), <Auto<i32> as DatabaseField>::TYPE)
+                .primary_key()
+                .auto(),
+            Field::new(
+                Identifier::new("author"),
+                <ForeignKey<FixtureAuthor> as DatabaseField>::TYPE,
+            )
+            .foreign_key(
+                <FixtureAuthor as Model>::TABLE_NAME,
+                <FixtureAuthor as Model>::PRIMARY_KEY_NAME,
+                ForeignKeyOnDeletePolicy::Restrict,
+                ForeignKeyOnUpdatePolicy::Restrict,
+            ),
+            Field::new(Identifier::new("title"), <String as DatabaseField>::TYPE),
+        ])
+        .build();
+
+    CREATE_AUTHOR.forwards(db).await.unwrap();
+    CREATE_BOOK.forwards(db).await.unwrap();
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("fixtures.json");
+    std::fs::write(
+        &path,
+        r#"[
+            {"model": "cot__fixture_author", "key": "tolkien", "fields": {"name": "J. R. R. Tolkien"}},
+            {"model": "cot__fixture_author", "key": "pratchett", "fields": {"name": "Terry Pratchett"}},
+            {
+                "model": "cot__fixture_book",
+                "fields": {"title": "The Hobbit", "author": {"$ref": "tolkien"}}
+            },
+            {
+                "model": "cot__fixture_book",
+                "fields": {"title": "Mort", "author": {"$ref": "pratchett"}}
+            }
+        ]"#,
+    )
+    .unwrap();
+
+    let mut fixtures = Fixtures::new();
+    fixtures
+        .register::<FixtureAuthor>()
+        .register::<FixtureBook>();
+
+    assert_eq!(fixtures.load_file(db, &path).await.unwrap(), 4);
+    // loading the same fixture again doesn't duplicate the rows
+    assert_eq!(fixtures.load_file(db, &path).await.unwrap(), 4);
+
+    assert_eq!(FixtureAuthor::objects().count(db).await.unwrap(), 2);
+    assert_eq!(FixtureBook::objects().count(db).await.unwrap(), 2);
+
+    let mut book = query!(FixtureBook, $title == "Mort")
+        .get(&**db)
+        .await
+        .unwrap();
+    let author = book.author.get(&**db).await.unwrap();
+    assert_eq!(author.name, "Terry Pratchett");
+
+    let error = fixtures
+        .load(
+            db,
+            r#"[
+                {"model": "cot__fixture_author", "fields": {"name": "Neil Gaiman"}},
+                {"model": "cot__fixture_book", "fields": {"title": "X", "author": {"$ref": "nobody"}}}
+            ]"#,
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(error, FixtureError::UnknownReference(key) if key == "nobody"));
+    // the entries loaded before the error are rolled back
+    assert_eq!(FixtureAuthor::objects().count(db).await.unwrap(), 2);
+}
+
 #[cot_macros::dbtest]
 async fn foreign_keys(db: &mut TestDatabase) {
     #[derive(Debug, Clone, PartialEq)]
@@ -691,6 +1786,116 @@ async fn foreign_keys_cascade(db: &mut TestDatabase) {
     assert!(Child::objects().all(&**db).await.unwrap().is_empty());
 }
 
+#[cot_macros::dbtest]
+async fn foreign_keys_select_related(db: &mut TestDatabase) {
+    #[derive(Debug, Clone, PartialEq)]
+    #[model]
+    struct User {
+        #[model(primary_key)]
+        id: Auto<i32>,
+        name: String,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    #[model]
+    struct Post {
+        #[model(primary_key)]
+        id: Auto<i32>,
+        author: ForeignKey<User>,
+        editor: Option<ForeignKey<User>>,
+        title: String,
+    }
+
+    const CREATE_USER: Operation = Operation::create_model()
+        .table_name(Identifier::new("cot__user"))
+        .fields(&[
+            Field::new(Identifier::new("id"), <Auto<i32> as DatabaseField>::TYPE)
+                .primary_key()
+                .auto(),
+            Field::new(Identifier::new("name"), <String as DatabaseField>::TYPE),
+        ])
+        .build();
+    const CREATE_POST: Operation = Operation::create_model()
+        .table_name(Identifier::new("cot__post"))
+        .fields(&[
+            Field::new(Identifier::new("id"), <Auto<i32> as DatabaseField>::TYPE)
+                .primary_key()
+                .auto(),
+            Field::new(
+                Identifier::new("author"),
+                <ForeignKey<User> as DatabaseField>::TYPE,
+            )
+            .foreign_key(
+                <User as Model>::TABLE_NAME,
+                <User as Model>::PRIMARY_KEY_NAME,
+                ForeignKeyOnDeletePolicy::Restrict,
+                ForeignKeyOnUpdatePolicy::Restrict,
+            ),
+            Field::new(
+                Identifier::new("editor"),
+                <Option<ForeignKey<User>> as DatabaseField>::TYPE,
+            )
+            .set_null(<Option<ForeignKey<User>> as DatabaseField>::NULLABLE)
+            .foreign_key(
+                <User as Model>::TABLE_NAME,
+                <User as Model>::PRIMARY_KEY_NAME,
+                ForeignKeyOnDeletePolicy::SetNone,
+                ForeignKeyOnUpdatePolicy::SetNone,
+            ),
+            Field::new(Identifier::new("title"), <String as DatabaseField>::TYPE),
+        ])
+        .build();
+
+    run_migrations!(db, CREATE_USER, CREATE_POST);
+
+    let mut user = User {
+        id: Auto::auto(),
+        name: "user".to_owned(),
+    };
+    user.save(&**db).await.unwrap();
+
+    for i in 0..10 {
+        let mut post = Post {
+            id: Auto::auto(),
+            author: ForeignKey::from(&user),
+            editor: (i % 2 == 0).then(|| ForeignKey::from(&user)),
+            title: format!("post {i}"),
+        };
+        post.save(&**db).await.unwrap();
+    }
+    db.clear_queries();
+
+    let posts = Post::objects()
+        .select_related(<Post as Model>::Fields::author)
+        .select_related(<Post as Model>::Fields::editor)
+        .all(&**db)
+        .await
+        .unwrap();
+
+    assert_eq!(db.queries().len(), 1);
+    assert_eq!(posts.len(), 10);
+    for post in &posts {
+        assert_eq!(post.author.model(), Some(&user));
+        if let Some(editor) = &post.editor {
+            assert_eq!(editor.model(), Some(&user));
+        }
+    }
+    assert_eq!(posts.iter().filter(|post| post.editor.is_some()).count(), 5);
+
+    // filters, limits and `get` only apply to the queried model
+    db.clear_queries();
+    let post = query!(Post, $id > 1 && $title == "post 4")
+        .select_related(<Post as Model>::Fields::author)
+        .get(&**db)
+        .await
+        .unwrap();
+
+    assert_eq!(db.queries().len(), 1);
+    assert_eq!(post.title, "post 4");
+    assert_eq!(post.author.model(), Some(&user));
+    assert!(post.editor.unwrap().model().is_none());
+}
+
 // Check different types for the primary key
 #[derive(Debug, PartialEq)]
 #[model]
@@ -861,6 +2066,305 @@ async fn weekday_set_field_functionality(db: &mut TestDatabase) {
     assert_eq!(updated_model.optional_schedule, Some(weekend_only));
 }
 
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct JsonSettings {
+    theme: String,
+    tags: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+#[model]
+struct JsonModel {
+    #[model(primary_key)]
+    id: Auto<i32>,
+    settings: Json<JsonSettings>,
+    extra: Option<Json<serde_json::Value>>,
+}
+
+const CREATE_JSON_MODEL: Operation = Operation::create_model()
+    .table_name(Identifier::new("cot__json_model"))
+    .fields(&[
+        Field::new(Identifier::new("id"), <Auto<i32> as DatabaseField>::TYPE)
+            .primary_key()
+            .auto(),
+        Field::new(
+            Identifier::new("settings"),
+            <Json<JsonSettings> as DatabaseField>::TYPE,
+        ),
+        Field::new(
+            Identifier::new("extra"),
+            <Option<Json<serde_json::Value>> as DatabaseField>::TYPE,
+        )
+        .set_null(<Option<Json<serde_json::Value>> as DatabaseField>::NULLABLE),
+    ])
+    .build();
+
+#[cot_macros::dbtest]
+async fn json_field(db: &mut TestDatabase) {
+    run_migrations!(db, CREATE_JSON_MODEL);
+
+    let mut dark = JsonModel {
+        id: Auto::auto(),
+        settings: Json(JsonSettings {
+            theme: "dark".to_owned(),
+            tags: vec!["a".to_owned(), "b".to_owned()],
+        }),
+        extra: Some(Json(serde_json::json!({"nested": {"value": 1}}))),
+    };
+    dark.save(&**db).await.unwrap();
+    let mut light = JsonModel {
+        id: Auto::auto(),
+        settings: Json(JsonSettings {
+            theme: "light".to_owned(),
+            tags: Vec::new(),
+        }),
+        extra: None,
+    };
+    light.save(&**db).await.unwrap();
+
+    let models = JsonModel::objects().all(&**db).await.unwrap();
+    assert_eq!(models, vec![dark, light]);
+}
+
+#[cot_macros::dbtest(backends = ["postgres"])]
+async fn json_field_filter(db: &mut TestDatabase) {
+    run_migrations!(db, CREATE_JSON_MODEL);
+
+    for theme in ["dark", "light"] {
+        let mut model = JsonModel {
+            id: Auto::auto(),
+            settings: Json(JsonSettings {
+                theme: theme.to_owned(),
+                tags: Vec::new(),
+            }),
+            extra: None,
+        };
+        model.save(&**db).await.unwrap();
+    }
+
+    let light_models = JsonModel::objects()
+        .filter(Expr::eq(
+            Expr::json_get(Expr::field("settings"), "theme"),
+            Expr::value("light"),
+        ))
+        .all(&**db)
+        .await
+        .unwrap();
+    assert_eq!(light_models.len(), 1);
+    assert_eq!(light_models[0].settings.theme, "light");
+}
+
+#[cfg(feature = "decimal")]
+#[derive(Debug, PartialEq)]
+#[model]
+struct DecimalModel {
+    #[model(primary_key)]
+    id: Auto<i32>,
+    #[model(decimal(precision = 20, scale = 10))]
+    price: cot::db::Decimal,
+    total: Option<cot::db::Decimal>,
+}
+
+#[cfg(feature = "decimal")]
+const CREATE_DECIMAL_MODEL: Operation = Operation::create_model()
+    .table_name(Identifier::new("cot__decimal_model"))
+    .fields(&[
+        Field::new(Identifier::new("id"), <Auto<i32> as DatabaseField>::TYPE)
+            .primary_key()
+            .auto(),
+        Field::new(
+            Identifier::new("price"),
+            <cot::db::Decimal as DatabaseField>::TYPE,
+        )
+        .decimal_precision(20, 10),
+        Field::new(
+            Identifier::new("total"),
+            <Option<cot::db::Decimal> as DatabaseField>::TYPE,
+        )
+        .set_null(<Option<cot::db::Decimal> as DatabaseField>::NULLABLE),
+    ])
+    .build();
+
+#[cfg(feature = "decimal")]
+#[cot_macros::dbtest]
+async fn decimal_field(db: &mut TestDatabase) {
+    use std::str::FromStr;
+
+    use cot::db::Decimal;
+
+    run_migrations!(db, CREATE_DECIMAL_MODEL);
+
+    let values = [
+        ("1234567890.0123456789", Some("0.1")),
+        ("-0.0000000001", Some("79228162514264.337593543950335")),
+        ("9999999999.9999999999", None),
+    ];
+    let mut expected = Vec::new();
+    for (price, total) in values {
+        let mut model = DecimalModel {
+            id: Auto::auto(),
+            price: Decimal::from_str(price).unwrap(),
+            total: total.map(|total| Decimal::from_str(total).unwrap()),
+        };
+        model.save(&**db).await.unwrap();
+        expected.push(model);
+    }
+
+    let models = DecimalModel::objects().all(&**db).await.unwrap();
+    assert_eq!(models, expected);
+    for (model, (price, _)) in models.iter().zip(values) {
+        assert_eq!(model.price.normalize().to_string(), price);
+    }
+
+    let max_price = Decimal::from_str("9999999999.9999999999").unwrap();
+    let model = query!(DecimalModel, $price == max_price)
+        .get(&**db)
+        .await
+        .unwrap();
+    assert_eq!(model.total, None);
+}
+
+#[cfg(feature = "decimal")]
+#[cot_macros::dbtest]
+async fn decimal_field_equality_ignores_trailing_zeros(db: &mut TestDatabase) {
+    use std::str::FromStr;
+
+    use cot::db::Decimal;
+
+    run_migrations!(db, CREATE_DECIMAL_MODEL);
+
+    let mut model = DecimalModel {
+        id: Auto::auto(),
+        price: Decimal::from_str("1.50").unwrap(),
+        total: None,
+    };
+    model.save(&**db).await.unwrap();
+
+    let price = Decimal::from_str("1.5").unwrap();
+    let found = query!(DecimalModel, $price == price)
+        .get(&**db)
+        .await
+        .unwrap();
+    assert_eq!(found.id, model.id);
+    assert_eq!(found.price, price);
+}
+
+// SQLite stores decimals as text, so the range filters compare the strings
+#[cfg(feature = "decimal")]
+#[cot_macros::dbtest(backends = ["postgres", "mysql"])]
+async fn decimal_field_range_filter(db: &mut TestDatabase) {
+    use std::str::FromStr;
+
+    use cot::db::Decimal;
+
+    run_migrations!(db, CREATE_DECIMAL_MODEL);
+
+    for price in ["9", "10", "1.50"] {
+        let mut model = DecimalModel {
+            id: Auto::auto(),
+            price: Decimal::from_str(price).unwrap(),
+            total: None,
+        };
+        model.save(&**db).await.unwrap();
+    }
+
+    let min_price = Decimal::from_str("2").unwrap();
+    let mut prices: Vec<_> = query!(DecimalModel, $price > min_price)
+        .all(&**db)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|model| model.price)
+        .collect();
+    prices.sort();
+    assert_eq!(prices, [Decimal::from(9), Decimal::from(10)]);
+}
+
+#[cot_macros::dbtest]
+async fn slug_field_generation(db: &mut TestDatabase) {
+    #[derive(Debug, PartialEq)]
+    #[model]
+    struct SlugModel {
+        #[model(primary_key)]
+        id: Auto<i32>,
+        title: String,
+        #[model(slugify_from = "title")]
+        slug: Slug,
+    }
+
+    const CREATE_SLUG_MODEL: Operation = Operation::create_model()
+        .table_name(Identifier::new("cot__slug_model"))
+        .fields(&[
+            Field::new(Identifier::new("id"), <Auto<i32> as DatabaseField>::TYPE)
+                .primary_key()
+                .auto(),
+            Field::new(Identifier::new("title"), <String as DatabaseField>::TYPE),
+            Field::new(Identifier::new("slug"), <Slug as DatabaseField>::TYPE),
+        ])
+        .build();
+
+    run_migrations!(db, CREATE_SLUG_MODEL);
+
+    let new_model = |title: &str| SlugModel {
+        id: Auto::auto(),
+        title: title.to_owned(),
+        slug: Slug::default(),
+    };
+
+    let mut first = new_model("Hello, World!");
+    first.save(&**db).await.unwrap();
+    assert_eq!(first.slug, "hello-world");
+
+    let mut second = new_model("Hello world");
+    second.save(&**db).await.unwrap();
+    assert_eq!(second.slug, "hello-world-2");
+
+    let mut explicit = new_model("Hello world");
+    explicit.slug = Slug::new("custom");
+    explicit.save(&**db).await.unwrap();
+    assert_eq!(explicit.slug, "custom");
+
+    // saving again doesn't change the slug, and an instance doesn't collide
+    // with its own slug when it is regenerated
+    first.save(&**db).await.unwrap();
+    assert_eq!(first.slug, "hello-world");
+    first.slug = Slug::default();
+    first.save(&**db).await.unwrap();
+    assert_eq!(first.slug, "hello-world");
+
+    let mut bulk = vec![
+        new_model("Zażółć gęślą jaźń"),
+        new_model("Zazolc gesla jazn"),
+    ];
+    SlugModel::bulk_insert(&**db, &mut bulk).await.unwrap();
+    assert_eq!(bulk[0].slug, "zazolc-gesla-jazn");
+    assert_eq!(bulk[1].slug, "zazolc-gesla-jazn-2");
+
+    let mut untitled = new_model("?!");
+    untitled.save(&**db).await.unwrap();
+    assert_eq!(untitled.slug, "slug-model");
+
+    let mut slugs: Vec<_> = SlugModel::objects()
+        .all(&**db)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|model| model.slug.into_string())
+        .collect();
+    slugs.sort();
+    assert_eq!(
+        slugs,
+        [
+            "custom",
+            "hello-world",
+            "hello-world-2",
+            "slug-model",
+            "zazolc-gesla-jazn",
+            "zazolc-gesla-jazn-2",
+        ]
+    );
+}
+
 #[cot_macros::dbtest]
 async fn bulk_insert_basic(test_db: &mut TestDatabase) {
     migrate_test_model(&*test_db).await;
@@ -1070,3 +2574,29 @@ async fn bulk_insert_with_fixed_pk(test_db: &mut TestDatabase) {
         .unwrap();
     assert_eq!(model300.name, "test300");
 }
+
+#[ignore = "Tests that use PostgreSQL are ignored by default"]
+#[cot::test]
+async fn postgres_test_databases_are_isolated() {
+    let (first, second) = tokio::join!(
+        TestDatabase::new_postgres("isolated"),
+        TestDatabase::new_postgres("isolated"),
+    );
+    let first = first.unwrap();
+    let second = second.unwrap();
+
+    migrate_test_model(&first).await;
+    migrate_test_model(&second).await;
+    let mut model = TestModel {
+        id: Auto::auto(),
+        name: "first".to_owned(),
+    };
+    model.save(&*first).await.unwrap();
+
+    assert_eq!(TestModel::objects().count(&*first).await.unwrap(), 1);
+    assert_eq!(TestModel::objects().count(&*second).await.unwrap(), 0);
+
+    first.cleanup().await.unwrap();
+    assert_eq!(TestModel::objects().count(&*second).await.unwrap(), 0);
+    second.cleanup().await.unwrap();
+}
@@ -39,3 +39,97 @@ async fn request_email() {
 
     assert!(email_service.send(message).await.is_ok());
 }
+
+#[cfg(feature = "email")]
+#[cot::test]
+async fn request_email_test_mailer() {
+    use cot::common_types::Email;
+    use cot::email::EmailMessage;
+    use cot::test::TestMailer;
+
+    let mailer = TestMailer::new();
+    let mut request = TestRequestBuilder::get("/").email(mailer.email()).build();
+    let email_service: cot::email::Email = request.extract_from_head().await.unwrap();
+
+    let message = EmailMessage::builder()
+        .from(Email::new("sender@example.com").unwrap())
+        .to(vec![Email::new("recipient@example.com").unwrap()])
+        .subject("Test Email")
+        .body("Hello, this is a test email.")
+        .html("<p>Hello, this is a test email.</p>")
+        .build()
+        .unwrap();
+    email_service.send(message).await.unwrap();
+
+    let sent = mailer.sent();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].from().email(), "sender@example.com");
+    assert_eq!(sent[0].to()[0].email(), "recipient@example.com");
+    assert_eq!(sent[0].subject(), "Test Email");
+    assert_eq!(sent[0].body(), "Hello, this is a test email.");
+    assert_eq!(sent[0].html(), Some("<p>Hello, this is a test email.</p>"));
+
+    mailer.clear();
+    assert!(mailer.sent().is_empty());
+}
+
+#[cfg(feature = "json")]
+#[cot::test]
+async fn handler_typed_arguments() {
+    use cot::json::Json;
+    use cot::request::extractors::{Path, UrlQuery};
+    use cot::router::{Route, Router};
+
+    #[derive(serde::Deserialize)]
+    struct Query {
+        greeting: String,
+    }
+
+    async fn handler(
+        Path(id): Path<i32>,
+        UrlQuery(query): UrlQuery<Query>,
+        Json(body): Json<serde_json::Value>,
+    ) -> String {
+        format!("{} {id} {}", query.greeting, body["name"])
+    }
+
+    let router = Router::with_urls([Route::with_handler("/{id}/", handler)]);
+    let request = TestRequestBuilder::post("/42/?greeting=hello")
+        .router(router.clone())
+        .json(&serde_json::json!({"name": "world"}))
+        .build();
+
+    let response = router.handle(request).await.unwrap();
+    assert_eq!(
+        response.into_body().into_bytes().await.unwrap(),
+        "hello 42 \"world\""
+    );
+}
+
+#[cot::test]
+async fn handler_extraction_failure_short_circuits() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use cot::StatusCode;
+    use cot::request::extractors::Path;
+    use cot::router::{Route, Router};
+
+    let called = Arc::new(AtomicBool::new(false));
+    let called_clone = Arc::clone(&called);
+    let handler = move |Path(_id): Path<i32>| {
+        let called = Arc::clone(&called_clone);
+        async move {
+            called.store(true, Ordering::SeqCst);
+        }
+    };
+
+    let router = Router::with_urls([Route::with_handler("/{id}/", handler)]);
+    let request = TestRequestBuilder::get("/not-a-number/")
+        .router(router.clone())
+        .build();
+
+    let error = router.handle(request).await.unwrap_err();
+    assert_eq!(error.status_code(), StatusCode::BAD_REQUEST);
+    assert!(!called.load(Ordering::SeqCst));
+}
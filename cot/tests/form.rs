@@ -1,9 +1,10 @@
 use cot::db::{Auto, ForeignKey};
 use cot::form::fields::{SelectChoice, SelectField};
 use cot::form::{
-    AsFormField, Form, FormContext, FormErrorTarget, FormField, FormFieldValidationError,
-    FormResult,
+    AsFormField, Form, FormContext, FormErrorTarget, FormField, FormFieldOptions,
+    FormFieldValidationError, FormResult, FormWidget,
 };
+use cot::html::{Html, HtmlTag};
 use cot::test::TestRequestBuilder;
 use cot_macros::model;
 
@@ -43,6 +44,40 @@ async fn form_from_request() {
     assert_eq!(form.age, 30);
 }
 
+#[cot::test]
+async fn context_with_initial() {
+    let context = MyForm::with_initial(&[("name", "Alice"), ("unknown", "value")]).await;
+
+    assert!(!context.has_errors());
+    let form_rendered = context.to_string();
+    assert!(form_rendered.contains("value=\"Alice\""));
+}
+
+#[cot::test]
+async fn context_display_validation_errors() {
+    let mut request = TestRequestBuilder::post("/")
+        .form_data(&[("name", "<b>Alice</b>"), ("age", "old")])
+        .build();
+
+    let form = MyForm::from_request(&mut request).await.unwrap();
+    let FormResult::ValidationError(context) = form else {
+        panic!("Expected a validation error");
+    };
+    let form_rendered = context.to_string();
+
+    // the submitted values are preserved
+    assert!(form_rendered.contains("value=\"&#60;b&#62;Alice&#60;/b&#62;\""));
+    assert!(form_rendered.contains("value=\"old\""));
+    // the errors are shown inline, right after the field they refer to
+    let age_field = form_rendered.find("name=\"age\"").unwrap();
+    let age_errors = form_rendered.find("<ul class=\"field-errors\">").unwrap();
+    assert!(age_errors > age_field);
+    assert_eq!(
+        form_rendered.matches("<ul class=\"field-errors\">").count(),
+        1
+    );
+}
+
 #[cot::test]
 async fn form_errors_required() {
     let mut request = TestRequestBuilder::post("/")
@@ -67,6 +102,50 @@ async fn form_errors_required() {
     }
 }
 
+#[derive(Debug, Form)]
+struct SignupForm {
+    newsletter: bool,
+    #[form(opts(must_be_true = true))]
+    accept_terms: bool,
+}
+
+#[cot::test]
+async fn bool_field_missing_key() {
+    // unchecked checkboxes are not sent by the browsers at all
+    let mut request = TestRequestBuilder::post("/")
+        .form_data(&[("accept_terms", "on")])
+        .build();
+
+    let form = SignupForm::from_request(&mut request)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!form.newsletter);
+    assert!(form.accept_terms);
+}
+
+#[cot::test]
+async fn bool_field_must_be_true_missing_key() {
+    let mut request = TestRequestBuilder::post("/")
+        .form_data(&[("newsletter", "1")])
+        .build();
+
+    let form = SignupForm::from_request(&mut request).await;
+    match form {
+        Ok(FormResult::ValidationError(context)) => {
+            assert_eq!(
+                context.errors_for(FormErrorTarget::Field("newsletter")),
+                &[]
+            );
+            assert_eq!(
+                context.errors_for(FormErrorTarget::Field("accept_terms")),
+                &[FormFieldValidationError::BooleanRequiredToBeTrue]
+            );
+        }
+        _ => panic!("Expected a validation error"),
+    }
+}
+
 #[cot::test]
 async fn values_persist_on_form_errors() {
     let mut request = TestRequestBuilder::post("/")
@@ -275,3 +354,193 @@ async fn select_field_context_display() {
     assert!(form_rendered.contains("value=\"medium\""));
     assert!(form_rendered.contains("value=\"high\""));
 }
+
+#[derive(Debug, Default)]
+struct DatePickerWidget;
+
+impl FormWidget for DatePickerWidget {
+    fn render(&self, options: &FormFieldOptions, value: Option<&str>) -> Html {
+        let mut tag = HtmlTag::input("text");
+        tag.attr("name", &options.id);
+        tag.attr("id", &options.id);
+        tag.add_class("date-picker");
+        tag.data("format", "yyyy-mm-dd");
+        if let Some(value) = value {
+            tag.attr("value", value);
+        }
+        tag.render()
+    }
+}
+
+#[derive(Debug, Form)]
+struct WidgetForm {
+    name: String,
+    #[form(widget = DatePickerWidget, opts(max_length = 10))]
+    birthday: String,
+}
+
+#[cot::test]
+async fn custom_widget_rendering() {
+    let context = WidgetForm::with_initial(&[("name", "Alice"), ("birthday", "2000-01-01")]).await;
+    let form_rendered = context.to_string();
+
+    // the default rendering is used for fields without a widget
+    assert!(form_rendered.contains(r#"name="name""#));
+    assert!(!form_rendered.contains(r#"id="name" class="date-picker""#));
+    // the widget is used for the field with a custom widget
+    assert!(form_rendered.contains(
+        r#"<input type="text" name="birthday" id="birthday" class="date-picker" data-format="yyyy-mm-dd" value="2000-01-01"/>"#
+    ));
+}
+
+#[cot::test]
+async fn custom_widget_form_from_request() {
+    let mut request = TestRequestBuilder::post("/")
+        .form_data(&[("name", "Alice"), ("birthday", "2000-01-01")])
+        .build();
+
+    let form = WidgetForm::from_request(&mut request)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(form.name, "Alice");
+    assert_eq!(form.birthday, "2000-01-01");
+
+    let context = form.to_context().await;
+    assert!(context.to_string().contains("class=\"date-picker\""));
+}
+
+#[derive(Debug, Form)]
+struct OrderedForm {
+    #[form(group = "address")]
+    city: String,
+    #[form(group = "contact")]
+    email: String,
+    notes: String,
+    #[form(group = "address", order = 1)]
+    street: String,
+    #[form(order = 2)]
+    last_name: String,
+    #[form(order = 1)]
+    first_name: String,
+}
+
+#[cot::test]
+async fn form_field_order() {
+    let context = <OrderedForm as Form>::Context::new();
+
+    let ids: Vec<_> = context.fields().map(|field| field.dyn_id()).collect();
+    assert_eq!(
+        ids,
+        [
+            "first_name",
+            "last_name",
+            "notes",
+            "street",
+            "city",
+            "email"
+        ]
+    );
+
+    let form_rendered = context.to_string();
+    let positions: Vec<_> = ids
+        .iter()
+        .map(|id| form_rendered.find(&format!("name=\"{id}\"")).unwrap())
+        .collect();
+    assert!(positions.is_sorted());
+}
+
+#[cot::test]
+async fn form_field_groups() {
+    let context = <OrderedForm as Form>::Context::new();
+    let form_rendered = context.to_string();
+
+    assert_eq!(form_rendered.matches("<fieldset>").count(), 2);
+    assert_eq!(form_rendered.matches("</fieldset>").count(), 2);
+
+    let address = form_rendered
+        .find("<fieldset><legend>Address</legend>")
+        .unwrap();
+    let contact = form_rendered
+        .find("<fieldset><legend>Contact</legend>")
+        .unwrap();
+    let notes = form_rendered.find("name=\"notes\"").unwrap();
+    let street = form_rendered.find("name=\"street\"").unwrap();
+    let email = form_rendered.find("name=\"email\"").unwrap();
+    assert!(notes < address);
+    assert!(address < street);
+    assert!(street < contact);
+    assert!(contact < email);
+}
+
+#[derive(Debug, Form)]
+#[form(patch)]
+struct ProfilePatchForm {
+    name: Option<String>,
+    bio: Option<Option<String>>,
+    age: Option<u8>,
+}
+
+#[cot::test]
+async fn patch_form_absent_fields() {
+    let mut request = TestRequestBuilder::post("/")
+        .form_data(&[("name", "Alice")])
+        .build();
+
+    let form = ProfilePatchForm::from_request(&mut request)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(form.name, Some("Alice".to_owned()));
+    assert_eq!(form.bio, None);
+    assert_eq!(form.age, None);
+}
+
+#[cot::test]
+async fn patch_form_cleared_field() {
+    let mut request = TestRequestBuilder::post("/")
+        .form_data(&[("bio", "")])
+        .build();
+
+    let form = ProfilePatchForm::from_request(&mut request)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(form.name, None);
+    assert_eq!(form.bio, Some(None));
+}
+
+#[cot::test]
+async fn patch_form_submitted_fields_are_validated() {
+    let mut request = TestRequestBuilder::post("/")
+        .form_data(&[("name", ""), ("age", "not a number")])
+        .build();
+
+    let form = ProfilePatchForm::from_request(&mut request).await.unwrap();
+
+    let FormResult::ValidationError(context) = form else {
+        panic!("expected a validation error");
+    };
+    assert_eq!(
+        context.errors_for(FormErrorTarget::Field("name")),
+        &[FormFieldValidationError::Required]
+    );
+    assert!(!context.errors_for(FormErrorTarget::Field("age")).is_empty());
+    assert!(context.errors_for(FormErrorTarget::Field("bio")).is_empty());
+}
+
+#[cot::test]
+async fn patch_form_to_context() {
+    let form = ProfilePatchForm {
+        name: Some("Alice".to_owned()),
+        bio: None,
+        age: Some(30),
+    };
+
+    let context = form.to_context().await;
+
+    let values: Vec<_> = context.fields().map(|field| field.dyn_value()).collect();
+    assert_eq!(values, [Some("Alice"), None, Some("30")]);
+}
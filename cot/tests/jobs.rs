@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
+
+use cot::App;
+use cot::jobs::{Job, JobQueueApp, JobRecord, JobStatus, enqueue};
+use cot::project::ProjectContext;
+use cot::tasks::RetryPolicy;
+use cot::test::{TestDatabase, TestRequestBuilder};
+use serde::{Deserialize, Serialize};
+
+/// Counts the runs of each job, keyed by a value unique to each test, as the
+/// tests for different databases run in parallel.
+static RUNS: LazyLock<Mutex<HashMap<usize, usize>>> = LazyLock::new(Mutex::default);
+static NEXT_KEY: AtomicUsize = AtomicUsize::new(0);
+
+fn new_key() -> usize {
+    NEXT_KEY.fetch_add(1, Ordering::SeqCst)
+}
+
+fn record_run(key: usize) {
+    *RUNS.lock().unwrap().entry(key).or_default() += 1;
+}
+
+fn runs(key: usize) -> usize {
+    RUNS.lock().unwrap().get(&key).copied().unwrap_or_default()
+}
+
+#[derive(Serialize, Deserialize)]
+struct SucceedingJob {
+    key: usize,
+}
+
+impl Job for SucceedingJob {
+    const NAME: &'static str = "succeeding";
+
+    async fn run(self, _context: Arc<ProjectContext>) -> cot::Result<()> {
+        record_run(self.key);
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct FailingJob {
+    key: usize,
+}
+
+impl Job for FailingJob {
+    const NAME: &'static str = "failing";
+
+    fn retry_policy() -> RetryPolicy {
+        RetryPolicy::new(2, Duration::ZERO)
+    }
+
+    async fn run(self, _context: Arc<ProjectContext>) -> cot::Result<()> {
+        record_run(self.key);
+        Err(cot::Error::internal("job failed"))
+    }
+}
+
+async fn setup(test_db: &mut TestDatabase) -> (JobQueueApp, Arc<ProjectContext>) {
+    let app = JobQueueApp::new()
+        .register::<SucceedingJob>()
+        .register::<FailingJob>();
+    test_db.add_migrations(app.migrations());
+    test_db.run_migrations().await;
+
+    let request = TestRequestBuilder::get("/")
+        .database(test_db.database())
+        .build();
+    let context = Arc::clone(
+        request
+            .extensions()
+            .get::<Arc<ProjectContext>>()
+            .expect("test request should contain the context"),
+    );
+
+    (app, context)
+}
+
+#[cot_macros::dbtest]
+async fn enqueued_job_executed_once(test_db: &mut TestDatabase) {
+    let (app, context) = setup(test_db).await;
+    let worker = app.worker();
+    let key = new_key();
+
+    let job = enqueue(&**test_db, &SucceedingJob { key }).await.unwrap();
+    assert_eq!(job.name(), "succeeding");
+    assert_eq!(job.status(), JobStatus::Pending);
+
+    assert_eq!(worker.run_pending(&context).await.unwrap(), 1);
+    assert_eq!(worker.run_pending(&context).await.unwrap(), 0);
+    assert_eq!(runs(key), 1);
+    assert!(
+        JobRecord::dead_letters(&**test_db)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[cot_macros::dbtest]
+async fn failing_job_retried_then_dead_lettered(test_db: &mut TestDatabase) {
+    let (app, context) = setup(test_db).await;
+    let worker = app.worker();
+    let key = new_key();
+
+    enqueue(&**test_db, &FailingJob { key }).await.unwrap();
+
+    // the initial attempt and 2 retries
+    assert_eq!(worker.run_pending(&context).await.unwrap(), 3);
+    assert_eq!(worker.run_pending(&context).await.unwrap(), 0);
+    assert_eq!(runs(key), 3);
+
+    let dead = JobRecord::dead_letters(&**test_db).await.unwrap();
+    assert_eq!(dead.len(), 1);
+    assert_eq!(dead[0].name(), "failing");
+    assert_eq!(dead[0].status(), JobStatus::Dead);
+    assert_eq!(dead[0].attempts(), 3);
+    assert!(dead[0].last_error().unwrap().contains("job failed"));
+
+    let mut job = dead.into_iter().next().unwrap();
+    job.requeue(&**test_db).await.unwrap();
+    assert!(
+        JobRecord::dead_letters(&**test_db)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(worker.run_pending(&context).await.unwrap(), 3);
+    assert_eq!(runs(key), 6);
+}
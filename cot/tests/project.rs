@@ -1,10 +1,14 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use async_trait::async_trait;
 use bytes::Bytes;
 use cot::config::ProjectConfig;
 use cot::html::Html;
-use cot::project::RegisterAppsContext;
-use cot::request::Request;
+use cot::project::{ProjectContext, RegisterAppsContext};
+use cot::request::{Request, RequestExt};
 use cot::router::{Route, Router};
-use cot::test::Client;
+use cot::test::{Client, TestServerBuilder};
 use cot::{App, AppBuilder, Project, StatusCode, reverse};
 
 #[cot::test]
@@ -120,3 +124,125 @@ async fn cot_router_reverse_local() {
         Bytes::from("/index2")
     );
 }
+
+#[cot::e2e_test]
+#[cfg_attr(
+    miri,
+    ignore = "unsupported operation: can't call foreign function `sqlite3_open_v2`"
+)]
+async fn cot_app_lifecycle_hooks() {
+    static EVENTS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    static STARTED: AtomicBool = AtomicBool::new(false);
+
+    fn record(event: String) {
+        EVENTS.lock().unwrap().push(event);
+    }
+
+    async fn started() -> Html {
+        Html::new(STARTED.load(Ordering::SeqCst).to_string())
+    }
+
+    struct LifecycleApp(&'static str);
+    #[async_trait]
+    impl App for LifecycleApp {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        async fn on_startup(&self, _context: &ProjectContext) -> cot::Result<()> {
+            record(format!("startup {}", self.0));
+            STARTED.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn on_shutdown(&self, _context: &ProjectContext) -> cot::Result<()> {
+            record(format!("shutdown {}", self.0));
+            Ok(())
+        }
+
+        fn router(&self) -> Router {
+            if self.0 == "app1" {
+                Router::with_urls([Route::with_handler("/started", started)])
+            } else {
+                Router::empty()
+            }
+        }
+    }
+
+    struct TestProject;
+    impl Project for TestProject {
+        fn config(&self, _config_name: &str) -> cot::Result<ProjectConfig> {
+            Ok(ProjectConfig::default())
+        }
+
+        fn register_apps(&self, apps: &mut AppBuilder, _context: &RegisterAppsContext) {
+            apps.register_with_views(LifecycleApp("app1"), "");
+            apps.register(LifecycleApp("app2"));
+        }
+    }
+
+    let server = TestServerBuilder::new(TestProject).start().await;
+
+    let response = reqwest::get(format!("{}/started", server.url()))
+        .await
+        .unwrap();
+    assert_eq!(response.text().await.unwrap(), "true");
+
+    server.close().await;
+    assert_eq!(
+        *EVENTS.lock().unwrap(),
+        [
+            "startup app1",
+            "startup app2",
+            "shutdown app2",
+            "shutdown app1"
+        ]
+    );
+}
+
+#[cot::test]
+#[cfg_attr(
+    miri,
+    ignore = "unsupported operation: can't call foreign function `sqlite3_open_v2`"
+)]
+async fn cot_project_shared_state() {
+    struct Counter(AtomicUsize);
+
+    async fn increment(request: Request) -> Html {
+        let count = request.state::<Counter>().0.fetch_add(1, Ordering::SeqCst) + 1;
+        Html::new(count.to_string())
+    }
+
+    struct CounterApp;
+    impl App for CounterApp {
+        fn name(&self) -> &'static str {
+            "counter"
+        }
+
+        fn router(&self) -> Router {
+            Router::with_urls([Route::with_handler("/increment", increment)])
+        }
+    }
+
+    struct TestProject;
+    impl Project for TestProject {
+        fn config(&self, _config_name: &str) -> cot::Result<ProjectConfig> {
+            Ok(ProjectConfig::default())
+        }
+
+        fn register_apps(&self, apps: &mut AppBuilder, _context: &RegisterAppsContext) {
+            apps.register_with_views(CounterApp, "");
+            apps.register_state(Counter(AtomicUsize::new(0)));
+        }
+    }
+
+    let mut client = Client::new(TestProject).await;
+
+    for expected in ["1", "2"] {
+        let response = client.get("/increment").await.unwrap();
+        assert_eq!(
+            response.into_body().into_bytes().await.unwrap(),
+            Bytes::from(expected)
+        );
+    }
+}
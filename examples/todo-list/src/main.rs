@@ -5,11 +5,13 @@ use cot::cli::CliMetadata;
 use cot::config::{DatabaseConfig, ProjectConfig};
 use cot::db::migrations::SyncDynMigration;
 use cot::db::{Auto, Database, Model, model, query};
-use cot::form::Form;
+use cot::form::{Form, FormContext, FormResult};
 use cot::html::Html;
+use cot::middleware::MethodOverrideMiddleware;
 use cot::project::{MiddlewareContext, RegisterAppsContext, RootHandler};
 use cot::request::extractors::{Path, RequestForm};
-use cot::response::Response;
+use cot::response::{IntoResponse, Response};
+use cot::router::method::delete;
 use cot::router::{Route, Router, Urls};
 use cot::static_files::StaticFilesMiddleware;
 use cot::{App, AppBuilder, Project, Template, reverse_redirect};
@@ -27,19 +29,29 @@ struct TodoItem {
 struct IndexTemplate<'a> {
     urls: &'a Urls,
     todo_items: Vec<TodoItem>,
+    todo_form: <TodoForm as Form>::Context,
 }
 
-async fn index(urls: Urls, db: Database) -> cot::Result<Html> {
-    let todo_items = TodoItem::objects().all(&db).await?;
+async fn render_index(
+    urls: &Urls,
+    db: &Database,
+    todo_form: <TodoForm as Form>::Context,
+) -> cot::Result<Html> {
+    let todo_items = TodoItem::objects().all(db).await?;
     let index_template = IndexTemplate {
-        urls: &urls,
+        urls,
         todo_items,
+        todo_form,
     };
     let rendered = index_template.render()?;
 
     Ok(Html::new(rendered))
 }
 
+async fn index(urls: Urls, db: Database) -> cot::Result<Html> {
+    render_index(&urls, &db, <TodoForm as Form>::Context::new()).await
+}
+
 #[derive(Debug, Form)]
 struct TodoForm {
     #[form(opts(max_length = 100))]
@@ -51,7 +63,13 @@ async fn add_todo(
     db: Database,
     RequestForm(todo_form): RequestForm<TodoForm>,
 ) -> cot::Result<Response> {
-    let todo_form = todo_form.unwrap();
+    let todo_form = match todo_form {
+        FormResult::Ok(todo_form) => todo_form,
+        FormResult::ValidationError(context) => {
+            // re-render the page with the submitted value and the errors
+            return render_index(&urls, &db, context).await?.into_response();
+        }
+    };
 
     TodoItem {
         id: Auto::auto(),
@@ -84,7 +102,7 @@ impl App for TodoApp {
         Router::with_urls([
             Route::with_handler_and_name("/", index, "index"),
             Route::with_handler_and_name("/todos/add", add_todo, "add-todo"),
-            Route::with_handler_and_name("/todos/{todo_id}/remove", remove_todo, "remove-todo"),
+            Route::with_handler_and_name("/todos/{todo_id}", delete(remove_todo), "remove-todo"),
         ])
     }
 }
@@ -115,6 +133,7 @@ impl Project for TodoProject {
     ) -> RootHandler {
         handler
             .middleware(StaticFilesMiddleware::from_context(context))
+            .middleware(MethodOverrideMiddleware::new())
             .build()
     }
 }